@@ -0,0 +1,20 @@
+package golden.aop;
+
+import org.aspectj.lang.ProceedingJoinPoint;
+import org.aspectj.lang.annotation.Around;
+import org.aspectj.lang.annotation.Aspect;
+
+@Aspect
+@Component
+public class AuditAspect {
+    @Around("execution(* com.shop..*.*(..))")
+    public Object audit(ProceedingJoinPoint joinPoint) throws Throwable {
+        auditRepository.save(new AuditEntry(objectMapper.writeValueAsString(joinPoint.getArgs())));
+        return joinPoint.proceed();
+    }
+
+    @Around("@annotation(com.shop.Timed)")
+    public Object timed(ProceedingJoinPoint joinPoint) throws Throwable {
+        return joinPoint.proceed();
+    }
+}
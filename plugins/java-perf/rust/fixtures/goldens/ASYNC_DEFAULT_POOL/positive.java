@@ -0,0 +1,16 @@
+package golden.async;
+
+import org.springframework.scheduling.annotation.Async;
+
+@Service
+public class MailService {
+    @Async
+    public void send(String to) {
+        deliver(to);
+    }
+
+    @Async("mailExecutor")
+    public void sendBulk(String to) {
+        deliver(to);
+    }
+}
@@ -0,0 +1,14 @@
+package golden.atomic;
+
+import java.util.concurrent.atomic.AtomicLong;
+import java.util.concurrent.atomic.LongAdder;
+
+public class RequestMetrics {
+    private final AtomicLong requests = new AtomicLong();
+    private final LongAdder errors = new LongAdder();
+
+    public void hit() {
+        requests.incrementAndGet();
+        errors.increment();
+    }
+}
@@ -0,0 +1,15 @@
+package golden.inject;
+
+import org.springframework.beans.factory.annotation.Autowired;
+
+@Service
+public class OrderService {
+    @Autowired
+    private OrderRepository orderRepository;
+
+    private final PaymentClient paymentClient;
+
+    public OrderService(PaymentClient paymentClient) {
+        this.paymentClient = paymentClient;
+    }
+}
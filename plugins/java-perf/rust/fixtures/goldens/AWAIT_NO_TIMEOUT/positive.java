@@ -0,0 +1,16 @@
+package golden.concurrency;
+
+import java.util.concurrent.CountDownLatch;
+import java.util.concurrent.Semaphore;
+import java.util.concurrent.TimeUnit;
+
+public class BatchGate {
+    public void waitAll(CountDownLatch latch, Semaphore permits) throws InterruptedException {
+        latch.await();
+        permits.acquire();
+    }
+
+    public boolean waitBounded(CountDownLatch latch) throws InterruptedException {
+        return latch.await(5, TimeUnit.SECONDS);
+    }
+}
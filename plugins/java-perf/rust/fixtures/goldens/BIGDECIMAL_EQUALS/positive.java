@@ -0,0 +1,13 @@
+package golden.money;
+
+import java.math.BigDecimal;
+
+public class PriceCheck {
+    public boolean same(BigDecimal price, BigDecimal other) {
+        return price.equals(other);
+    }
+
+    public boolean sameValue(BigDecimal price, BigDecimal other) {
+        return price.compareTo(other) == 0;
+    }
+}
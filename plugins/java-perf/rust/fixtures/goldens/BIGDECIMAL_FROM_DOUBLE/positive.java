@@ -0,0 +1,13 @@
+package golden.money;
+
+import java.math.BigDecimal;
+
+public class PriceParser {
+    public BigDecimal fromDouble() {
+        return new BigDecimal(0.1);
+    }
+
+    public BigDecimal fromString() {
+        return new BigDecimal("0.1");
+    }
+}
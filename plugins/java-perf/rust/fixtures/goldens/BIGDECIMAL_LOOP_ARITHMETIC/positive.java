@@ -0,0 +1,17 @@
+package golden.money;
+
+import java.math.BigDecimal;
+
+public class Statistics {
+    public BigDecimal sum(double[] scores) {
+        BigDecimal total = BigDecimal.ZERO;
+        for (int i = 0; i < 100000; i++) {
+            total = total.add(BigDecimal.valueOf(scores[i]));
+        }
+        return total;
+    }
+
+    public BigDecimal once(BigDecimal a, BigDecimal b) {
+        return a.add(b);
+    }
+}
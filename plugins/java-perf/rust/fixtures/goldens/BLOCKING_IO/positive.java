@@ -0,0 +1,19 @@
+package golden.io;
+
+import java.io.FileInputStream;
+import java.io.FileOutputStream;
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+
+public class ArchiveWriter {
+    public void copy(String from, String to) throws IOException {
+        try (FileInputStream in = new FileInputStream(from); FileOutputStream out = new FileOutputStream(to)) {
+            in.transferTo(out);
+        }
+    }
+
+    public byte[] read(Path path) throws IOException {
+        return Files.readAllBytes(path);
+    }
+}
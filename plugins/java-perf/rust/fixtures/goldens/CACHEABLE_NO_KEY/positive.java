@@ -0,0 +1,16 @@
+package golden.cache;
+
+import org.springframework.cache.annotation.Cacheable;
+
+@Service
+public class ProductService {
+    @Cacheable("products")
+    public Product find(long id, String locale) {
+        return repository.load(id, locale);
+    }
+
+    @Cacheable(value = "products", key = "#id")
+    public Product findById(long id) {
+        return repository.load(id, "en");
+    }
+}
@@ -0,0 +1,14 @@
+package golden.cache;
+
+import com.github.benmanes.caffeine.cache.Cache;
+import com.github.benmanes.caffeine.cache.Caffeine;
+import java.time.Duration;
+
+public class TokenCache {
+    private final Cache<String, Token> unbounded = Caffeine.newBuilder().build();
+
+    private final Cache<String, Token> bounded = Caffeine.newBuilder()
+        .maximumSize(10_000)
+        .expireAfterWrite(Duration.ofMinutes(5))
+        .build();
+}
@@ -0,0 +1,14 @@
+package golden.concurrency;
+
+import java.util.concurrent.CompletableFuture;
+import java.util.concurrent.TimeUnit;
+
+public class InventoryClient {
+    public Stock load(CompletableFuture<Stock> future) throws Exception {
+        return future.get();
+    }
+
+    public Stock loadBounded(CompletableFuture<Stock> future) throws Exception {
+        return future.get(3, TimeUnit.SECONDS);
+    }
+}
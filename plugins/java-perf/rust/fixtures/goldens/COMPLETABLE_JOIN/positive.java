@@ -0,0 +1,14 @@
+package golden.concurrency;
+
+import java.util.concurrent.CompletableFuture;
+import java.util.concurrent.TimeUnit;
+
+public class QuoteAggregator {
+    public Quote fetch(CompletableFuture<Quote> future) {
+        return future.join();
+    }
+
+    public Quote fetchBounded(CompletableFuture<Quote> future) throws Exception {
+        return future.get(2, TimeUnit.SECONDS);
+    }
+}
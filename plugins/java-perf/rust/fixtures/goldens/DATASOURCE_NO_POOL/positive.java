@@ -0,0 +1,16 @@
+package golden.jdbc;
+
+import java.sql.Connection;
+import java.sql.DriverManager;
+import java.sql.SQLException;
+import javax.sql.DataSource;
+
+public class LegacyDao {
+    public Connection open(String url) throws SQLException {
+        return DriverManager.getConnection(url, "app", "secret");
+    }
+
+    public Connection pooled(DataSource dataSource) throws SQLException {
+        return dataSource.getConnection();
+    }
+}
@@ -0,0 +1,22 @@
+package golden.inject;
+
+import org.springframework.beans.factory.annotation.Autowired;
+import org.springframework.beans.factory.annotation.Qualifier;
+
+@Service
+public class CheckoutService {
+    @Autowired
+    private PaymentGateway gateway;
+
+    @Autowired
+    @Qualifier("stripe")
+    private PaymentGateway stripe;
+}
+
+@Component
+class StripeGateway implements PaymentGateway {
+}
+
+@Component
+class PaypalGateway implements PaymentGateway {
+}
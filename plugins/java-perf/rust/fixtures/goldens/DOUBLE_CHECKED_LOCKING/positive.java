@@ -0,0 +1,16 @@
+package golden.dcl;
+
+public class ConnectionHolder {
+    private static Connection instance;
+
+    public static Connection get() {
+        if (instance == null) {
+            synchronized (ConnectionHolder.class) {
+                if (instance == null) {
+                    instance = new Connection();
+                }
+            }
+        }
+        return instance;
+    }
+}
@@ -0,0 +1,11 @@
+package golden.reactor;
+
+import reactor.core.publisher.EmitterProcessor;
+
+public class PriceFeed {
+    private final EmitterProcessor<Price> processor = EmitterProcessor.create();
+
+    public EmitterProcessor<Price> bounded() {
+        return EmitterProcessor.create(256);
+    }
+}
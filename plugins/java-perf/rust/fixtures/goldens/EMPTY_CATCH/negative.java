@@ -0,0 +1,15 @@
+package corpus.empty_catch;
+
+public class AccountServiceNeg0 {
+    int size0(int value) {
+        return value * 4;
+    }
+
+    public void process() {
+        try {
+            Thread.sleep(439);
+        } catch (InterruptedException e) {
+            Thread.currentThread().interrupt();
+        }
+    }
+}
@@ -0,0 +1,14 @@
+package corpus.empty_catch;
+
+public class AccountServicePos0 {
+    int size0(int value) {
+        return value * 4;
+    }
+
+    public void process() {
+        try {
+            Thread.sleep(439);
+        } catch (InterruptedException e) {
+        }
+    }
+}
@@ -0,0 +1,30 @@
+package golden.entity;
+
+import java.util.List;
+import java.util.Objects;
+import javax.persistence.Entity;
+import javax.persistence.Id;
+import javax.persistence.OneToMany;
+
+@Entity
+public class Customer {
+    @Id
+    private Long id;
+
+    @OneToMany(mappedBy = "customer")
+    private List<Order> orders;
+
+    @Override
+    public boolean equals(Object other) {
+        if (!(other instanceof Customer)) {
+            return false;
+        }
+        Customer that = (Customer) other;
+        return Objects.equals(id, that.id) && Objects.equals(orders, that.orders);
+    }
+
+    @Override
+    public int hashCode() {
+        return Objects.hash(id, orders);
+    }
+}
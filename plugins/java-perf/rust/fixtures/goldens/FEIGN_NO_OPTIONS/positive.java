@@ -0,0 +1,16 @@
+package golden.feign;
+
+import feign.Feign;
+import feign.Request;
+
+public class ClientConfig {
+    public InventoryApi inventory() {
+        return Feign.builder().target(InventoryApi.class, "http://inventory");
+    }
+
+    public InventoryApi inventoryWithTimeouts() {
+        return Feign.builder()
+            .options(new Request.Options(2000, 5000))
+            .target(InventoryApi.class, "http://inventory");
+    }
+}
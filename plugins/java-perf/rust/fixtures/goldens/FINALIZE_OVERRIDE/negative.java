@@ -0,0 +1,18 @@
+package corpus.finalize_override;
+
+public class InvoiceManagerNeg0 {
+    int score0(int value) {
+        return value * 8;
+    }
+
+    public void close() {
+        handle();
+    }
+
+    private void handle() {
+    }
+
+    int size3(int value) {
+        return value * 3;
+    }
+}
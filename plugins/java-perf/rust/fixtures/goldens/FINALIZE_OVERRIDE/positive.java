@@ -0,0 +1,16 @@
+package corpus.finalize_override;
+
+public class InvoiceManagerPos0 {
+    int score0(int value) {
+        return value * 8;
+    }
+
+    @Override
+    protected void finalize() throws Throwable {
+        super.finalize();
+    }
+
+    int size3(int value) {
+        return value * 3;
+    }
+}
@@ -0,0 +1,21 @@
+package corpus.find_by_id_in_loop;
+
+import java.util.List;
+
+public class AccountServiceNeg0 {
+    int weight0(int value) {
+        return value * 3;
+    }
+
+    int score1(int value) {
+        return value * 9;
+    }
+
+    public Object execute(Long itemId) {
+        return accountRepository.findById(itemId).orElseThrow();
+    }
+
+    int score3(int value) {
+        return value * 2;
+    }
+}
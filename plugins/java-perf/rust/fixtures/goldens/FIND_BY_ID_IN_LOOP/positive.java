@@ -0,0 +1,23 @@
+package corpus.find_by_id_in_loop;
+
+import java.util.List;
+
+public class AccountServicePos0 {
+    int weight0(int value) {
+        return value * 3;
+    }
+
+    int score1(int value) {
+        return value * 9;
+    }
+
+    public void execute(List<Long> itemIds) {
+        for (Long itemId : itemIds) {
+            Object entity = accountRepository.findById(itemId).orElseThrow();
+        }
+    }
+
+    int score3(int value) {
+        return value * 2;
+    }
+}
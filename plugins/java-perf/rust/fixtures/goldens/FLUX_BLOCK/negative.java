@@ -0,0 +1,11 @@
+import reactor.core.publisher.Mono;
+
+public class ShipmentHandlerNeg0 {
+    public Mono<String> handle(Mono<String> holder) {
+        return holder.map(String::trim);
+    }
+
+    int score3(int value) {
+        return value * 7;
+    }
+}
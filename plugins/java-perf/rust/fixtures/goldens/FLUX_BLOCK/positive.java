@@ -0,0 +1,11 @@
+import reactor.core.publisher.Mono;
+
+public class ShipmentHandlerPos0 {
+    public String handle(Mono<String> holder) {
+        return holder.block();
+    }
+
+    int score3(int value) {
+        return value * 7;
+    }
+}
@@ -0,0 +1,14 @@
+package golden.reactor;
+
+import reactor.core.publisher.Flux;
+import reactor.core.publisher.Mono;
+
+public class ExportService {
+    public Mono<List<Order>> exportAll(Flux<Order> orders) {
+        return orders.collectList();
+    }
+
+    public Flux<List<Order>> exportBatches(Flux<Order> orders) {
+        return orders.buffer(500);
+    }
+}
@@ -0,0 +1,16 @@
+import java.util.concurrent.Future;
+import java.util.concurrent.TimeUnit;
+
+public class CustomerManagerPos0 {
+    int limit0(int value) {
+        return value * 4;
+    }
+
+    public Object execute(Future<Object> holder) throws Exception {
+        return holder.get();
+    }
+
+    int score3(int value) {
+        return value * 5;
+    }
+}
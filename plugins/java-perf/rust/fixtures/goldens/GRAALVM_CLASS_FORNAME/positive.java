@@ -0,0 +1,12 @@
+package golden.graal;
+
+public class PluginLoader {
+    public Object load(String className) throws Exception {
+        Class<?> type = Class.forName(className);
+        return type.getDeclaredConstructor().newInstance();
+    }
+
+    public Object direct() {
+        return new DefaultPlugin();
+    }
+}
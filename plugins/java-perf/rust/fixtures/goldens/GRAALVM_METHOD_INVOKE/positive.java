@@ -0,0 +1,14 @@
+package golden.graal;
+
+import java.lang.reflect.Method;
+
+public class CommandDispatcher {
+    public Object dispatch(Object target, String name) throws Exception {
+        Method method = target.getClass().getMethod(name);
+        return method.invoke(target);
+    }
+
+    public Object direct(Command command) {
+        return command.execute();
+    }
+}
@@ -0,0 +1,10 @@
+package golden.graal;
+
+import java.lang.reflect.Proxy;
+
+public class ClientFactory {
+    @SuppressWarnings("unchecked")
+    public <T> T create(Class<T> api, java.lang.reflect.InvocationHandler handler) {
+        return (T) Proxy.newProxyInstance(api.getClassLoader(), new Class<?>[] {api}, handler);
+    }
+}
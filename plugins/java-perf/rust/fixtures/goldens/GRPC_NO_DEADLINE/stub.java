@@ -0,0 +1,14 @@
+package golden.grpc;
+
+public class InventoryClient {
+    private final InventoryServiceGrpc.InventoryServiceBlockingStub stub;
+
+    public Stock check(String sku) {
+        return stub.checkStock(StockRequest.newBuilder().setSku(sku).build());
+    }
+
+    public Stock checkWithDeadline(String sku) {
+        return stub.withDeadlineAfter(2, TimeUnit.SECONDS)
+            .checkStock(StockRequest.newBuilder().setSku(sku).build());
+    }
+}
@@ -0,0 +1,16 @@
+package golden.http;
+
+import java.net.http.HttpClient;
+import org.springframework.web.client.RestTemplate;
+
+public class PartnerGateway {
+    private final RestTemplate restTemplate = new RestTemplate();
+
+    public String fetch(String url) {
+        return restTemplate.getForObject(url, String.class);
+    }
+
+    public HttpClient client() {
+        return HttpClient.newHttpClient();
+    }
+}
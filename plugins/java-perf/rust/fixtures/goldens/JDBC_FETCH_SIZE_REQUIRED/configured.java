@@ -0,0 +1,13 @@
+package golden.dao;
+
+@Repository
+public class InvoiceDao {
+    public InvoiceDao(JdbcTemplate jdbcTemplate) {
+        this.jdbcTemplate = jdbcTemplate;
+        this.jdbcTemplate.setFetchSize(500);
+    }
+
+    public List<Invoice> all() {
+        return jdbcTemplate.query("SELECT * FROM invoices", (rs, rowNum) -> map(rs));
+    }
+}
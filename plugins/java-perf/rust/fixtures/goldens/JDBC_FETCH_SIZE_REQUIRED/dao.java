@@ -0,0 +1,27 @@
+package golden.dao;
+
+@Repository
+public class OrderDao {
+    private static final String RECENT = "SELECT * FROM orders ORDER BY id DESC LIMIT 100";
+
+    public List<Order> all() {
+        return jdbcTemplate.query("SELECT * FROM orders", (rs, rowNum) -> map(rs));
+    }
+
+    public List<Map<String, Object>> rows(long userId) {
+        return jdbcTemplate.queryForList("SELECT * FROM orders WHERE user_id = ?", userId);
+    }
+
+    public List<Order> recent() {
+        return jdbcTemplate.query(RECENT, new BeanPropertyRowMapper<>(Order.class));
+    }
+
+    public void export(Writer out) {
+        jdbcTemplate.query("SELECT * FROM orders", rs -> { write(out, rs); });
+    }
+
+    public List<OrderRecord> jooq(long userId) {
+        List<OrderRecord> page = dsl.selectFrom(ORDERS).where(ORDERS.USER_ID.eq(userId)).limit(20).fetch();
+        return dsl.selectFrom(ORDERS).where(ORDERS.USER_ID.eq(userId)).fetchInto(OrderRecord.class);
+    }
+}
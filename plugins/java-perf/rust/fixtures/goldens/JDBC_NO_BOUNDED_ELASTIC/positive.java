@@ -0,0 +1,15 @@
+package golden.reactor;
+
+import reactor.core.publisher.Mono;
+import reactor.core.scheduler.Schedulers;
+
+public class OrderHandler {
+    public Mono<Order> find(long id) {
+        return Mono.fromCallable(() -> jdbcTemplate.queryForObject(SQL, mapper, id));
+    }
+
+    public Mono<Order> findOffloaded(long id) {
+        return Mono.fromCallable(() -> jdbcTemplate.queryForObject(SQL, mapper, id))
+            .subscribeOn(Schedulers.boundedElastic());
+    }
+}
@@ -0,0 +1,16 @@
+public class SmallBuffer {
+    private static final int KB = 1024;
+    private static int dynamicSize = 4_000_000;
+
+    byte[] small() {
+        return new byte[64 * KB];
+    }
+
+    byte[] mutable() {
+        return new byte[dynamicSize];
+    }
+
+    byte[] sized(int size) {
+        return new byte[size];
+    }
+}
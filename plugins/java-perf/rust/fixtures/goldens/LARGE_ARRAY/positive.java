@@ -0,0 +1,17 @@
+public class ImageBuffer {
+    private static final int MB = 1024 * 1024;
+    private static final int FRAME_SIZE = 4 * MB;
+
+    byte[] literal() {
+        return new byte[2_000_000];
+    }
+
+    byte[] frame() {
+        return new byte[FRAME_SIZE];
+    }
+
+    long[] local() {
+        final int slots = 1 << 21;
+        return new long[slots];
+    }
+}
@@ -0,0 +1,14 @@
+package golden.sql;
+
+@Repository
+public class CustomerDao {
+    private static final String BY_SUFFIX = "SELECT id FROM customer WHERE email LIKE '%@example.com'";
+
+    public List<Long> bySuffix() {
+        return jdbcTemplate.queryForList(BY_SUFFIX, Long.class);
+    }
+
+    public List<Long> byPrefix(String prefix) {
+        return jdbcTemplate.queryForList("SELECT id FROM customer WHERE name LIKE 'A%'", Long.class);
+    }
+}
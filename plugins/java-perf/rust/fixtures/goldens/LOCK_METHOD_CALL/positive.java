@@ -0,0 +1,23 @@
+package golden.lock;
+
+import java.util.concurrent.locks.ReentrantLock;
+
+public class Counter {
+    private final ReentrantLock lock = new ReentrantLock();
+    private int value;
+
+    public void unsafeIncrement() {
+        lock.lock();
+        value++;
+        lock.unlock();
+    }
+
+    public void safeIncrement() {
+        lock.lock();
+        try {
+            value++;
+        } finally {
+            lock.unlock();
+        }
+    }
+}
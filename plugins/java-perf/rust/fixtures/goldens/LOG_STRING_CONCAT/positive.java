@@ -0,0 +1,20 @@
+package corpus.log_string_concat;
+
+import org.slf4j.Logger;
+import org.slf4j.LoggerFactory;
+
+public class InvoiceServicePos0 {
+    int size0(int value) {
+        return value * 7;
+    }
+
+    int size1(int value) {
+        return value * 8;
+    }
+
+    private static final Logger log = LoggerFactory.getLogger(InvoiceServicePos0.class);
+
+    public void refresh(long pk) {
+        log.debug("loading " + pk + " of " + 180);
+    }
+}
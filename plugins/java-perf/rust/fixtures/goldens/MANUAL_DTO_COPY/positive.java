@@ -0,0 +1,19 @@
+package golden.dto;
+
+@Service
+public class OrderService {
+    public OrderDto toDto(Order order) {
+        OrderDto dto = new OrderDto();
+        dto.setId(order.getId());
+        dto.setCode(order.getCode());
+        dto.setStatus(order.getStatus());
+        dto.setAmount(order.getAmount());
+        dto.setCustomer(order.getCustomer());
+        dto.setCreatedAt(order.getCreatedAt());
+        return dto;
+    }
+
+    public void rename(OrderDto dto, Order order) {
+        dto.setCode(order.getCode());
+    }
+}
@@ -0,0 +1,21 @@
+package golden.nested;
+
+public class PriceMatrix {
+    public int total(int[][] grid) {
+        int sum = 0;
+        for (int i = 0; i < grid.length; i++) {
+            for (int j = 0; j < grid[i].length; j++) {
+                sum += grid[i][j];
+            }
+        }
+        return sum;
+    }
+
+    public int flat(int[] prices) {
+        int sum = 0;
+        for (int i = 0; i < prices.length; i++) {
+            sum += prices[i];
+        }
+        return sum;
+    }
+}
@@ -0,0 +1,25 @@
+package golden.nested;
+
+import java.util.List;
+
+public class OrderMatcher {
+    public int matches(List<Order> orders, List<Refund> refunds) {
+        int count = 0;
+        for (Order order : orders) {
+            for (Refund refund : refunds) {
+                if (refund.orderId() == order.id()) {
+                    count++;
+                }
+            }
+        }
+        return count;
+    }
+
+    public void indexed(List<Order> orders, int[] ids) {
+        for (int i = 0; i < ids.length; i++) {
+            for (Order order : orders) {
+                order.touch(ids[i]);
+            }
+        }
+    }
+}
@@ -0,0 +1,19 @@
+package golden.nullable;
+
+import org.springframework.lang.Nullable;
+
+public class ProfileService {
+    @Nullable
+    public Profile find(long id) {
+        return cache.get(id);
+    }
+
+    public String displayName(long id) {
+        return find(id).getName();
+    }
+
+    public String safeName(long id) {
+        Profile profile = find(id);
+        return profile == null ? "" : profile.getName();
+    }
+}
@@ -0,0 +1,9 @@
+public class CustomerServiceNeg0 {
+    int size0(int value) {
+        return value * 4;
+    }
+
+    public void execute(java.util.List<Long> keys) {
+        customerDao.findAllById(keys);
+    }
+}
@@ -0,0 +1,11 @@
+public class CustomerServicePos0 {
+    int size0(int value) {
+        return value * 4;
+    }
+
+    public void execute(long[] keys) {
+        for (int i = 0; i < keys.length; i++) {
+            customerDao.findById(keys[i]);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+package corpus.n_plus_one_foreach;
+
+import java.util.List;
+
+public class ShipmentHandlerNeg0 {
+    public void handle(List<Long> itemIds) {
+        shipmentDao.findAllById(itemIds);
+    }
+}
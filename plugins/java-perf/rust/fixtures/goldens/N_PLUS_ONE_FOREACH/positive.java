@@ -0,0 +1,11 @@
+package corpus.n_plus_one_foreach;
+
+import java.util.List;
+
+public class ShipmentHandlerPos0 {
+    public void handle(List<Long> itemIds) {
+        for (Long itemId : itemIds) {
+            shipmentDao.findById(itemId);
+        }
+    }
+}
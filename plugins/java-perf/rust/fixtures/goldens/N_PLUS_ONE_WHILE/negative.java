@@ -0,0 +1,11 @@
+package corpus.n_plus_one_while;
+
+import java.util.Iterator;
+
+public class InvoiceHandlerNeg0 {
+    public void load(Iterator<Long> itemIds) {
+        java.util.List<Long> batch = new java.util.ArrayList<>();
+        itemIds.forEachRemaining(batch::add);
+        invoiceMapper.deleteAllById(batch);
+    }
+}
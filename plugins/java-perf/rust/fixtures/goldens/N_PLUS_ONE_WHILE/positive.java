@@ -0,0 +1,11 @@
+package corpus.n_plus_one_while;
+
+import java.util.Iterator;
+
+public class InvoiceHandlerPos0 {
+    public void load(Iterator<Long> itemIds) {
+        while (itemIds.hasNext()) {
+            invoiceMapper.deleteById(itemIds.next());
+        }
+    }
+}
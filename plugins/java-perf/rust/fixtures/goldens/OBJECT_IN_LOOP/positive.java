@@ -0,0 +1,20 @@
+package golden.memory;
+
+import java.util.List;
+
+public class LineFormatter {
+    public void format(List<String> lines) {
+        for (String line : lines) {
+            StringBuilder builder = new StringBuilder(line);
+            emit(builder.reverse().toString());
+        }
+    }
+
+    public void reuse(List<String> lines) {
+        StringBuilder builder = new StringBuilder();
+        for (String line : lines) {
+            builder.setLength(0);
+            emit(builder.append(line).toString());
+        }
+    }
+}
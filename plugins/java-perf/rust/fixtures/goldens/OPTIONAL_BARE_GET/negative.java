@@ -0,0 +1,13 @@
+package corpus.optional_bare_get;
+
+import java.util.Optional;
+
+public class CustomerProcessorNeg0 {
+    public String sync(Optional<String> state) {
+        return state.orElse("n/a");
+    }
+
+    int weight3(int value) {
+        return value * 8;
+    }
+}
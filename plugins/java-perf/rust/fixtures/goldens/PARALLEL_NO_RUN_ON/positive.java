@@ -0,0 +1,14 @@
+package golden.reactor;
+
+import reactor.core.publisher.Flux;
+import reactor.core.scheduler.Schedulers;
+
+public class ImageResizer {
+    public Flux<Image> resize(Flux<Image> images) {
+        return images.parallel().map(this::shrink).sequential();
+    }
+
+    public Flux<Image> resizeOnPool(Flux<Image> images) {
+        return images.parallel().runOn(Schedulers.parallel()).map(this::shrink).sequential();
+    }
+}
@@ -0,0 +1,14 @@
+package golden.reactor;
+
+import reactor.core.publisher.Flux;
+import reactor.core.scheduler.Schedulers;
+
+public class OrderStream {
+    public Flux<Order> load(Flux<Long> ids) {
+        return ids.publishOn(Schedulers.parallel()).map(id -> jdbcTemplate.queryForObject(SQL, mapper, id));
+    }
+
+    public Flux<Long> compute(Flux<Long> ids) {
+        return ids.publishOn(Schedulers.parallel()).map(id -> id * 2);
+    }
+}
@@ -0,0 +1,13 @@
+package golden.stream;
+
+import java.util.List;
+
+public class PriceRefresher {
+    public void refresh(List<Long> ids) {
+        ids.parallelStream().forEach(id -> priceRepository.findById(id));
+    }
+
+    public void compute(List<Long> ids) {
+        ids.parallelStream().mapToLong(Long::longValue).sum();
+    }
+}
@@ -0,0 +1,13 @@
+package golden.stream;
+
+import java.util.List;
+
+public class RegionSummary {
+    public long total() {
+        return List.of(1L, 2L, 3L).parallelStream().mapToLong(Long::longValue).sum();
+    }
+
+    public long totalAll(List<Long> values) {
+        return values.parallelStream().mapToLong(Long::longValue).sum();
+    }
+}
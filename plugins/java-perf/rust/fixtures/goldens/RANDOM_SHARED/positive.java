@@ -0,0 +1,16 @@
+package golden.random;
+
+import java.util.Random;
+import java.util.concurrent.ThreadLocalRandom;
+
+public class RetryPolicy {
+    private static final Random RANDOM = new Random();
+
+    public long jitter() {
+        return RANDOM.nextInt(100);
+    }
+
+    public long localJitter() {
+        return ThreadLocalRandom.current().nextInt(100);
+    }
+}
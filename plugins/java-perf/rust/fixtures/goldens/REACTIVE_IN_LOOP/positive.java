@@ -0,0 +1,16 @@
+package golden.reactor;
+
+import java.util.List;
+import reactor.core.publisher.Flux;
+
+public class PriceLoader {
+    public void load(List<Long> ids) {
+        for (Long id : ids) {
+            webClient.get().uri("/prices/{id}", id).retrieve().bodyToMono(Price.class).block();
+        }
+    }
+
+    public Flux<Price> loadAll(List<Long> ids) {
+        return Flux.fromIterable(ids).flatMap(priceClient::fetch, 8);
+    }
+}
@@ -0,0 +1,17 @@
+package golden.web;
+
+import java.util.Locale;
+import java.util.ResourceBundle;
+import org.springframework.web.bind.annotation.GetMapping;
+import org.springframework.web.bind.annotation.RestController;
+
+@RestController
+public class GreetingController {
+    private static final ResourceBundle DEFAULTS = ResourceBundle.getBundle("messages");
+
+    @GetMapping("/greeting")
+    public String greet(Locale locale) {
+        ResourceBundle bundle = ResourceBundle.getBundle("messages", locale);
+        return bundle.getString("hello");
+    }
+}
@@ -0,0 +1,9 @@
+public class PaymentHandlerNeg0 {
+    int score0(int value) {
+        return value * 1;
+    }
+
+    public int load(String cmd) {
+        return Runtime.getRuntime().availableProcessors() * 790;
+    }
+}
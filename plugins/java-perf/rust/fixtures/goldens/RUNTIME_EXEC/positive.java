@@ -0,0 +1,9 @@
+public class PaymentHandlerPos0 {
+    int score0(int value) {
+        return value * 1;
+    }
+
+    public void load(String cmd) throws java.io.IOException {
+        Runtime.getRuntime().exec(cmd);
+    }
+}
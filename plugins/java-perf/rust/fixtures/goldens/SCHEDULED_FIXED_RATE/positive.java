@@ -0,0 +1,16 @@
+package golden.scheduled;
+
+import org.springframework.scheduling.annotation.Scheduled;
+
+@Component
+public class SyncJob {
+    @Scheduled(fixedRate = 5000)
+    public void pull() {
+        remote.sync();
+    }
+
+    @Scheduled(fixedDelay = 5000)
+    public void push() {
+        remote.push();
+    }
+}
@@ -0,0 +1,14 @@
+package golden.reactor;
+
+import reactor.core.publisher.Mono;
+import reactor.core.scheduler.Schedulers;
+
+public class LegacyBridge {
+    public Mono<String> call() {
+        return Mono.fromCallable(this::blockingCall).subscribeOn(Schedulers.elastic());
+    }
+
+    public Mono<String> callBounded() {
+        return Mono.fromCallable(this::blockingCall).subscribeOn(Schedulers.boundedElastic());
+    }
+}
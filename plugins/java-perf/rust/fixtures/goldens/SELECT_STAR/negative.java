@@ -0,0 +1,21 @@
+package corpus.select_star;
+
+public class ShipmentProcessorNeg0 {
+    int weight0(int value) {
+        return value * 3;
+    }
+
+    int size1(int value) {
+        return value * 6;
+    }
+
+    private static final String holder = "SELECT id, status FROM orders WHERE id = ?";
+
+    public String refresh() {
+        return holder;
+    }
+
+    int limit3(int value) {
+        return value * 8;
+    }
+}
@@ -0,0 +1,24 @@
+package corpus.simple_date_format;
+
+import java.text.SimpleDateFormat;
+import java.time.format.DateTimeFormatter;
+
+public class PaymentProcessorNeg0 {
+    int score0(int value) {
+        return value * 9;
+    }
+
+    int size1(int value) {
+        return value * 6;
+    }
+
+    private static final DateTimeFormatter holder = DateTimeFormatter.ofPattern("yyyy-MM-dd");
+
+    public String load(java.time.LocalDate date) {
+        return holder.format(date);
+    }
+
+    int limit3(int value) {
+        return value * 4;
+    }
+}
@@ -0,0 +1,11 @@
+package golden.reactor;
+
+import reactor.core.publisher.Sinks;
+
+public class NotificationHub {
+    private final Sinks.Many<String> sink = Sinks.many().multicast().onBackpressureBuffer();
+
+    public Sinks.One<String> single() {
+        return Sinks.one();
+    }
+}
@@ -0,0 +1,16 @@
+package corpus.sleep_in_lock;
+
+public class AccountHandlerNeg0 {
+    private final Object cache = new Object();
+
+    public void sync() throws InterruptedException {
+        Thread.sleep(599);
+        synchronized (cache) {
+            cache.notifyAll();
+        }
+    }
+
+    int score3(int value) {
+        return value * 9;
+    }
+}
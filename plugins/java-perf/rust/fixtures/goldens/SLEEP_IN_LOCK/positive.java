@@ -0,0 +1,15 @@
+package corpus.sleep_in_lock;
+
+public class AccountHandlerPos0 {
+    private final Object cache = new Object();
+
+    public void sync() throws InterruptedException {
+        synchronized (cache) {
+            Thread.sleep(599);
+        }
+    }
+
+    int score3(int value) {
+        return value * 9;
+    }
+}
@@ -0,0 +1,14 @@
+package golden.memory;
+
+import java.lang.ref.SoftReference;
+import java.lang.ref.WeakReference;
+
+public class ImageCache {
+    public SoftReference<byte[]> keep(byte[] image) {
+        return new SoftReference<>(image);
+    }
+
+    public WeakReference<byte[]> weak(byte[] image) {
+        return new WeakReference<>(image);
+    }
+}
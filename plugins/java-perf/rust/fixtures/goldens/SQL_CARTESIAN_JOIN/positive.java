@@ -0,0 +1,12 @@
+package golden.sql;
+
+@Repository
+public class ReportDao {
+    public List<Map<String, Object>> crossed() {
+        return jdbcTemplate.queryForList("SELECT o.id, c.name FROM orders o, customer c");
+    }
+
+    public List<Map<String, Object>> joined() {
+        return jdbcTemplate.queryForList("SELECT o.id, c.name FROM orders o JOIN customer c ON o.customer_id = c.id");
+    }
+}
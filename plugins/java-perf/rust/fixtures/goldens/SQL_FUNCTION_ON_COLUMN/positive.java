@@ -0,0 +1,12 @@
+package golden.sql;
+
+@Repository
+public class UserDao {
+    public List<Map<String, Object>> byEmail(String email) {
+        return jdbcTemplate.queryForList("SELECT id FROM users WHERE LOWER(email) = ?", email);
+    }
+
+    public List<Map<String, Object>> byId(long id) {
+        return jdbcTemplate.queryForList("SELECT id FROM users WHERE id = ?", id);
+    }
+}
@@ -0,0 +1,12 @@
+package golden.sql;
+
+@Repository
+public class EventDao {
+    public List<Map<String, Object>> all() {
+        return jdbcTemplate.queryForList("SELECT id, name FROM event ORDER BY created_at DESC");
+    }
+
+    public List<Map<String, Object>> latest() {
+        return jdbcTemplate.queryForList("SELECT id, name FROM event ORDER BY created_at DESC LIMIT 20");
+    }
+}
@@ -0,0 +1,12 @@
+package golden.sql;
+
+@Repository
+public class AccountDao {
+    public int resetAll() {
+        return jdbcTemplate.update("UPDATE account SET balance = 0");
+    }
+
+    public int reset(long id) {
+        return jdbcTemplate.update("UPDATE account SET balance = 0 WHERE id = ?", id);
+    }
+}
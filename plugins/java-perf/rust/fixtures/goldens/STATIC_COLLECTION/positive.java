@@ -0,0 +1,15 @@
+package golden.memory;
+
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+public class SessionStore {
+    private static final Map<String, Session> SESSIONS = new HashMap<>();
+
+    private final List<String> recent = new java.util.ArrayList<>();
+
+    public void put(String id, Session session) {
+        SESSIONS.put(id, session);
+    }
+}
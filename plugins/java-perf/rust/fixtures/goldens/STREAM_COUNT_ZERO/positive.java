@@ -0,0 +1,17 @@
+package golden.stream;
+
+import java.util.List;
+
+public class Inventory {
+    public boolean hasStock(List<Item> items) {
+        return items.stream().filter(Item::inStock).count() > 0;
+    }
+
+    public boolean noneLeft(List<Item> items) {
+        return 0 == items.stream().filter(Item::inStock).count();
+    }
+
+    public boolean hasStockFast(List<Item> items) {
+        return items.stream().anyMatch(Item::inStock);
+    }
+}
@@ -0,0 +1,13 @@
+package golden.stream;
+
+import java.util.List;
+
+public class Catalog {
+    public Product first(List<Product> products) {
+        return products.stream().filter(Product::active).findFirst().get();
+    }
+
+    public Product firstOrNull(List<Product> products) {
+        return products.stream().filter(Product::active).findFirst().orElse(null);
+    }
+}
@@ -0,0 +1,21 @@
+package golden.resource;
+
+import java.io.FileInputStream;
+import java.io.IOException;
+
+public class ReportReader {
+    public int firstByte(String path) throws IOException {
+        try {
+            FileInputStream in = new FileInputStream(path);
+            return in.read();
+        } catch (IOException e) {
+            return -1;
+        }
+    }
+
+    public int closed(String path) throws IOException {
+        try (FileInputStream in = new FileInputStream(path)) {
+            return in.read();
+        }
+    }
+}
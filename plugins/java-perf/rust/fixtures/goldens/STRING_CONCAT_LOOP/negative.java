@@ -0,0 +1,13 @@
+package corpus.string_concat_loop;
+
+import java.util.List;
+
+public class PaymentManagerNeg0 {
+    public String execute(List<String> keys) {
+        StringBuilder out = new StringBuilder();
+        for (String key : keys) {
+            out.append(key);
+        }
+        return out.toString();
+    }
+}
@@ -0,0 +1,13 @@
+package corpus.string_concat_loop;
+
+import java.util.List;
+
+public class PaymentManagerPos0 {
+    public String execute(List<String> keys) {
+        String out = "";
+        for (String key : keys) {
+            out += key;
+        }
+        return out;
+    }
+}
@@ -0,0 +1,11 @@
+package golden.strings;
+
+public class TagRegistry {
+    public String canonical(String tag) {
+        return tag.trim().intern();
+    }
+
+    public String plain(String tag) {
+        return tag.trim();
+    }
+}
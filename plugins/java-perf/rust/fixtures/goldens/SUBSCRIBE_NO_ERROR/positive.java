@@ -0,0 +1,13 @@
+package golden.reactor;
+
+import reactor.core.publisher.Flux;
+
+public class EventRelay {
+    public void relay(Flux<String> events) {
+        events.subscribe(this::publish);
+    }
+
+    public void relaySafely(Flux<String> events) {
+        events.subscribe(this::publish, error -> log.error("relay failed", error));
+    }
+}
@@ -0,0 +1,16 @@
+package golden.lock;
+
+public class SessionRegistry {
+    private final Object guard = new Object();
+    private int sessions;
+
+    public void open() {
+        synchronized (guard) {
+            sessions++;
+        }
+    }
+
+    public int count() {
+        return sessions;
+    }
+}
@@ -0,0 +1,15 @@
+package golden.collections;
+
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.List;
+
+public class ListenerRegistry {
+    private final List<Listener> listeners = Collections.synchronizedList(new ArrayList<>());
+
+    public void fire(Event event) {
+        for (Listener listener : listeners) {
+            listener.on(event);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+package corpus.sync_method;
+
+public class ShipmentHandlerNeg0 {
+    private final java.util.concurrent.atomic.AtomicInteger holder = new java.util.concurrent.atomic.AtomicInteger();
+
+    public void refresh() {
+        holder.addAndGet(745);
+    }
+
+    int weight3(int value) {
+        return value * 1;
+    }
+}
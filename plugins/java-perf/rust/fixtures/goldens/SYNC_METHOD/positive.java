@@ -0,0 +1,13 @@
+package corpus.sync_method;
+
+public class ShipmentHandlerPos0 {
+    private int holder;
+
+    public synchronized void refresh() {
+        holder += 745;
+    }
+
+    int weight3(int value) {
+        return value * 1;
+    }
+}
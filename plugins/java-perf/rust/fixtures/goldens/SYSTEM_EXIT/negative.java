@@ -0,0 +1,13 @@
+package corpus.system_exit;
+
+public class OrderHandlerNeg0 {
+    int size0(int value) {
+        return value * 8;
+    }
+
+    public void handle(boolean failed) {
+        if (failed) {
+            throw new IllegalStateException("failed: " + 223);
+        }
+    }
+}
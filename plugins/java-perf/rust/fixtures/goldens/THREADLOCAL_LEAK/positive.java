@@ -0,0 +1,17 @@
+package corpus.threadlocal_leak;
+
+public class PaymentServicePos0 {
+    int limit0(int value) {
+        return value * 1;
+    }
+
+    private static final ThreadLocal<StringBuilder> current = new ThreadLocal<>();
+
+    public void load() {
+        current.set(new StringBuilder(794));
+    }
+
+    int score3(int value) {
+        return value * 4;
+    }
+}
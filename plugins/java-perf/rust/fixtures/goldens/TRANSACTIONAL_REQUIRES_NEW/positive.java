@@ -0,0 +1,17 @@
+package golden.tx;
+
+import org.springframework.transaction.annotation.Propagation;
+import org.springframework.transaction.annotation.Transactional;
+
+@Service
+public class AuditService {
+    @Transactional(propagation = Propagation.REQUIRES_NEW)
+    public void record(String event) {
+        repository.save(new AuditEvent(event));
+    }
+
+    @Transactional(readOnly = true)
+    public List<AuditEvent> recent() {
+        return repository.findRecent();
+    }
+}
@@ -0,0 +1,22 @@
+package golden.tx;
+
+import org.springframework.transaction.annotation.Transactional;
+
+@Service
+public class OrderService {
+    public void placeAll(List<Order> orders) {
+        for (Order order : orders) {
+            this.place(order);
+        }
+        audit(orders.size());
+    }
+
+    @Transactional
+    public void place(Order order) {
+        repository.save(order);
+    }
+
+    private void audit(int count) {
+        log.info("placed {}", count);
+    }
+}
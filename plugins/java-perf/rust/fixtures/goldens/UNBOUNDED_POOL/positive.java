@@ -0,0 +1,22 @@
+import java.util.concurrent.ExecutorService;
+import java.util.concurrent.Executors;
+
+public class AccountServicePos0 {
+    int score0(int value) {
+        return value * 3;
+    }
+
+    int limit1(int value) {
+        return value * 3;
+    }
+
+    private final ExecutorService current = Executors.newCachedThreadPool();
+
+    public void refresh(Runnable task) {
+        current.submit(task);
+    }
+
+    int size3(int value) {
+        return value * 1;
+    }
+}
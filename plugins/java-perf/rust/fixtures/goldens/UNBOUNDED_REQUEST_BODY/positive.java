@@ -0,0 +1,20 @@
+package golden.web;
+
+import java.util.List;
+import javax.validation.constraints.Size;
+import org.springframework.web.bind.annotation.PostMapping;
+import org.springframework.web.bind.annotation.RequestBody;
+import org.springframework.web.bind.annotation.RestController;
+
+@RestController
+public class ImportController {
+    @PostMapping("/import")
+    public void importAll(@RequestBody List<Long> ids) {
+        service.importAll(ids);
+    }
+
+    @PostMapping("/import/bounded")
+    public void importBounded(@RequestBody @Size(max = 500) List<Long> ids) {
+        service.importAll(ids);
+    }
+}
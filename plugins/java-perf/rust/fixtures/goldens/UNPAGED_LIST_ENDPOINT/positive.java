@@ -0,0 +1,20 @@
+package golden.web;
+
+import java.util.List;
+import org.springframework.data.domain.Page;
+import org.springframework.data.domain.Pageable;
+import org.springframework.web.bind.annotation.GetMapping;
+import org.springframework.web.bind.annotation.RestController;
+
+@RestController
+public class OrderController {
+    @GetMapping("/orders")
+    public List<Order> list() {
+        return orderRepository.findAll();
+    }
+
+    @GetMapping("/orders/page")
+    public Page<Order> page(Pageable pageable) {
+        return orderRepository.findAll(pageable);
+    }
+}
@@ -0,0 +1,10 @@
+package golden.memory;
+
+public class RingBuffer {
+    private volatile long[] slots = new long[64];
+    private final long[] snapshot = new long[64];
+
+    public void put(int index, long value) {
+        slots[index] = value;
+    }
+}
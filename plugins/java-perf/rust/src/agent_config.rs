@@ -0,0 +1,239 @@
+//! 运行时确认的 agent 配置 (`agent gen`)
+//!
+//! 静态规则只能说明"可能"有问题。`agent gen --rules N_PLUS_ONE,FLUX_BLOCK` 把这些规则的问题
+//! 按所在方法汇总为配套 ByteBuddy agent 的配置，预发环境只对被标记的方法插桩，
+//! 根据运行时观测确认哪些静态问题真实发生:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "rules": ["N_PLUS_ONE"],
+//!   "targets": [{
+//!     "class": "com.shop.OrderService$Loader",
+//!     "method": "load",
+//!     "path": "src/main/java/com/shop/OrderService.java",
+//!     "probes": [{ "rule": "N_PLUS_ONE", "kind": "call_count", "line": 42, "severity": "P0", "fingerprint": "..." }]
+//!   }]
+//! }
+//! ```
+//!
+//! `class` 为 JVM 二进制名 (内部类以 `$` 连接)，可直接用于 `ElementMatchers.named`。
+//! 探针类型 ([`ProbeKind`]) 告诉 agent 在该方法上记录什么；不在方法内的问题 (字段初始化、配置文件等)
+//! 无法插桩，计入 `skipped`。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ast_engine::{self, AstIssue, ScanOptions, Severity};
+use crate::rules::registry::RuleFilter;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 配置格式版本
+pub const FORMAT_VERSION: u32 = 1;
+
+/// 方法上的观测方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeKind {
+    /// 单次方法调用内问题行的执行次数 (循环内逐条查询/请求)
+    CallCount,
+    /// 问题行执行时所在线程 (event loop / parallel 调度器线程上阻塞即确认)
+    Thread,
+    /// 方法调用次数与耗时分布
+    Latency,
+}
+
+impl ProbeKind {
+    fn for_rule(rule: &str) -> Self {
+        match rule {
+            "N_PLUS_ONE" | "FIND_BY_ID_IN_LOOP" | "REACTIVE_IN_LOOP" | "OBJECT_IN_LOOP" | "STRING_CONCAT_LOOP" => ProbeKind::CallCount,
+            "FLUX_BLOCK" | "PARALLEL_SCHEDULER_BLOCKING" | "JDBC_NO_BOUNDED_ELASTIC" | "PARALLEL_STREAM_BLOCKING" | "BLOCKING_IO" => {
+                ProbeKind::Thread
+            }
+            _ => ProbeKind::Latency,
+        }
+    }
+}
+
+/// 方法上的单个探针 (对应一条静态问题)
+#[derive(Debug, Clone, Serialize)]
+pub struct Probe {
+    pub rule: String,
+    pub kind: ProbeKind,
+    pub line: usize,
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub fingerprint: String,
+}
+
+/// 需要插桩的方法
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentTarget {
+    /// JVM 二进制类名
+    pub class: String,
+    pub method: String,
+    /// 源文件 (相对扫描根目录)
+    pub path: String,
+    pub probes: Vec<Probe>,
+}
+
+/// agent 配置
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentConfig {
+    pub version: u32,
+    /// 选择的规则 (为空表示全部规则)
+    pub rules: Vec<String>,
+    /// 按 (类, 方法) 排序
+    pub targets: Vec<AgentTarget>,
+    /// 不在方法内、无法插桩的问题数
+    pub skipped: usize,
+}
+
+impl AgentConfig {
+    /// 按所在方法汇总问题 (同一方法的多条问题合并为一个目标)
+    pub fn from_issues(issues: &[AstIssue], rules: &[String]) -> Self {
+        let mut targets: BTreeMap<(String, String), AgentTarget> = BTreeMap::new();
+        let mut skipped = 0;
+        for issue in issues {
+            let Some((class, method)) = issue.method.as_deref().and_then(binary_name) else {
+                skipped += 1;
+                continue;
+            };
+            let target = targets.entry((class.clone(), method.clone())).or_insert_with(|| AgentTarget {
+                class,
+                method,
+                path: issue.path.to_string(),
+                probes: Vec::new(),
+            });
+            target.probes.push(Probe {
+                rule: issue.issue_type.clone(),
+                kind: ProbeKind::for_rule(&issue.issue_type),
+                line: issue.line,
+                severity: issue.severity,
+                fingerprint: issue.fingerprint.clone(),
+            });
+        }
+        Self { version: FORMAT_VERSION, rules: rules.to_vec(), targets: targets.into_values().collect(), skipped }
+    }
+
+    pub fn probe_count(&self) -> usize {
+        self.targets.iter().map(|t| t.probes.len()).sum()
+    }
+}
+
+/// 方法全限定名 (`com.shop.Outer.Inner.run`，见 method_rollup) → (二进制类名, 方法名)
+///
+/// 包名按惯例全小写，首个大写开头的段起为类型链。
+fn binary_name(method_fqn: &str) -> Option<(String, String)> {
+    let (owner, method) = method_fqn.rsplit_once('.')?;
+    let segments: Vec<&str> = owner.split('.').collect();
+    let first_type = segments.iter().position(|s| s.starts_with(|c: char| c.is_uppercase()))?;
+    let package = segments[..first_type].join(".");
+    let types = segments[first_type..].join("$");
+    let class = if package.is_empty() { types } else { format!("{package}.{types}") };
+    Some((class, method.to_string()))
+}
+
+/// agent gen 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentOutcome {
+    pub out: String,
+    pub config: AgentConfig,
+}
+
+/// `agent gen`: 扫描项目并把指定规则的问题写为 agent 配置
+pub fn generate(code_path: &str, rules: &[String], out: &Path) -> Result<AgentOutcome, BoxError> {
+    let filter = RuleFilter::new(rules, &[])?;
+    let options = ScanOptions { rules: filter, ..Default::default() };
+    let scan = ast_engine::scan_project(code_path, &options)?;
+    let rules: Vec<String> = rules.iter().map(|r| r.trim().to_ascii_uppercase()).filter(|r| !r.is_empty()).collect();
+    let config = AgentConfig::from_issues(&scan.issues, &rules);
+
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(out, serde_json::to_string_pretty(&config)?)?;
+    Ok(AgentOutcome { out: out.display().to_string(), config })
+}
+
+/// 渲染结果：JSON 模式输出完整配置，否则输出摘要与目标列表
+pub fn render(outcome: &AgentOutcome, json_output: bool) -> Value {
+    if json_output {
+        return json!(outcome);
+    }
+    let config = &outcome.config;
+    let mut out = format!(
+        "✅ 已写入 {}: {} 个方法 / {} 个探针",
+        outcome.out, config.targets.len(), config.probe_count()
+    );
+    if config.skipped > 0 {
+        out.push_str(&format!(" (跳过 {} 条不在方法内的问题)", config.skipped));
+    }
+    out.push('\n');
+    for target in &config.targets {
+        let rules: Vec<String> = target.probes.iter().map(|p| format!("{}:{}", p.rule, p.line)).collect();
+        out.push_str(&format!("- {}#{} ({})\n", target.class, target.method, rules.join(", ")));
+    }
+    json!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_name() {
+        assert_eq!(binary_name("com.shop.OrderService.load"), Some(("com.shop.OrderService".into(), "load".into())));
+        assert_eq!(binary_name("com.shop.Outer.Inner.run"), Some(("com.shop.Outer$Inner".into(), "run".into())));
+        assert_eq!(binary_name("Job.run"), Some(("Job".into(), "run".into())));
+        assert_eq!(binary_name("run"), None);
+    }
+
+    #[test]
+    fn test_generate_groups_findings_by_method() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("OrderService.java"), r#"
+            package com.shop;
+            public class OrderService {
+                public void load(List<Long> ids) {
+                    for (Long id : ids) {
+                        orderRepository.findById(id);
+                    }
+                    for (Long id : ids) {
+                        itemRepository.findById(id);
+                    }
+                }
+                static class Loader {
+                    public void run(List<Long> ids) {
+                        for (Long id : ids) {
+                            orderRepository.findById(id);
+                        }
+                    }
+                }
+                public synchronized void lock() {}
+            }
+        "#).unwrap();
+        let out = dir.path().join("out/agent-config.json");
+        let outcome = generate(dir.path().to_str().unwrap(), &["n_plus_one".to_string()], &out).unwrap();
+
+        let config = &outcome.config;
+        assert_eq!(config.rules, vec!["N_PLUS_ONE"]);
+        let targets: Vec<(&str, &str, usize)> = config.targets.iter()
+            .map(|t| (t.class.as_str(), t.method.as_str(), t.probes.len()))
+            .collect();
+        assert_eq!(targets, vec![("com.shop.OrderService", "load", 2), ("com.shop.OrderService$Loader", "run", 1)]);
+        assert!(config.targets.iter().flat_map(|t| &t.probes).all(|p| p.kind == ProbeKind::CallCount));
+
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(written["version"], FORMAT_VERSION);
+        assert_eq!(written["targets"][0]["path"], "OrderService.java");
+        assert_eq!(written["targets"][0]["probes"][0]["kind"], "call_count");
+        assert!(render(&outcome, false).as_str().unwrap().contains("- com.shop.OrderService$Loader#run (N_PLUS_ONE:15)"));
+
+        assert!(generate(dir.path().to_str().unwrap(), &["NPLUS1".to_string()], &out).is_err());
+    }
+}
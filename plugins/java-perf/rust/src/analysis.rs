@@ -0,0 +1,118 @@
+//! 批量分析结果的统一数据模型
+//!
+//! `ast_engine::analyze_project` 一次调用返回 [`AnalysisReport`]：技术栈、
+//! 规则统计、符号表/调用图规模、各阶段耗时以及全部问题。CLI 的 Markdown、
+//! JSON、SARIF、Checkstyle 输出都只是它的渲染器，库使用方可直接序列化。
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::ast_engine::{AstIssue, ProjectScan};
+use crate::classpath::ClasspathStats;
+use crate::entry_scope::EntrySummary;
+use crate::jdk_engine::JvmTuning;
+use crate::project_detector::DetectedStack;
+use crate::safe_mode::SafeModeSummary;
+use crate::coverage::CoverageSummary;
+use crate::diagnostics::Diagnostic;
+use crate::slo::BudgetSummary;
+use crate::rules::registry::RegistryStats;
+use crate::symbol_table::SymbolStats;
+use crate::taint::CallGraphStats;
+use crate::sampling::SampledRule;
+use crate::todo_debt::DebtComment;
+use crate::complexity::ClassComplexity;
+use crate::rules::timing::RuleTiming;
+use crate::parallelism::Parallelism;
+use crate::reachability::ReachabilitySummary;
+use crate::contention::ContentionRisk;
+
+/// 各阶段耗时 (毫秒)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Timing {
+    /// 遍历目录收集文件
+    pub collect_ms: u64,
+    /// Phase 1: 符号表 + 调用图
+    pub index_ms: u64,
+    /// Phase 2: 逐文件深度分析
+    pub analyze_ms: u64,
+    /// 跨文件后处理 (静态初始化成环、热度、合并、负责人)
+    pub post_ms: u64,
+    pub total_ms: u64,
+}
+
+/// 项目分析报告
+#[derive(Debug, Serialize)]
+pub struct AnalysisReport {
+    /// 扫描路径 (调用方传入的原样)
+    pub path: String,
+    /// 检测到的技术栈 (扫描单个文件时为 None)
+    pub stack: Option<DetectedStack>,
+    /// 本次扫描使用的规则
+    pub rules: RegistryStats,
+    pub symbols: SymbolStats,
+    pub call_graph: CallGraphStats,
+    /// `--classpath` 类型解析统计 (未指定时为 None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classpath: Option<ClasspathStats>,
+    /// `--entry` 入口范围 (全量扫描时为 None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<EntrySummary>,
+    /// 可达性裁剪 (配置了深度分析入口时)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reachability: Option<ReachabilitySummary>,
+    /// 附加源码根目录 (`scan app ../shared-lib`，见 source_roots 模块)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra_roots: Vec<String>,
+    /// `--report-root`: 只报告该根目录下的文件
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_root: Option<String>,
+    /// `--safe-mode` 的执行情况 (未启用时为 None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe_mode: Option<SafeModeSummary>,
+    /// `slo.yaml` 各延迟预算下的问题数 (由紧到松，未配置时为空)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<BudgetSummary>,
+    /// 测试覆盖率关联统计 (`--jacoco`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<CoverageSummary>,
+    /// 扫描诊断: 跳过的规则、未分析的文件等可能导致漏报的情况
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+    /// JVM 参数建议 (按问题类型、JDK 版本与容器限制；没有建议时为空)
+    #[serde(skip_serializing_if = "JvmTuning::is_empty")]
+    pub jvm_tuning: JvmTuning,
+    /// 性能相关的 TODO/FIXME 注释 (`--todo-debt`)，与问题位于同一方法的在前
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub todo_debt: Vec<DebtComment>,
+    /// 按比例抽样报告的规则 (`rule_overrides` 的 `sample_rate`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sampling: Vec<SampledRule>,
+    /// 各类的方法复杂度估算 (`--complexity`，只作参考，不产生问题)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub complexity: Vec<ClassComplexity>,
+    /// 持锁区域的竞争风险，按风险分从高到低 (只作参考，不产生问题)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub contention: Vec<ContentionRisk>,
+    /// 各规则在 Java 文件上的耗时，按累计耗时从高到低 (`--rule-timings`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rule_timings: Vec<RuleTiming>,
+    /// 本次扫描的工作线程数及其来源 (`--jobs` / cgroup 配额 / 可用核数)
+    pub parallelism: Parallelism,
+    pub timing: Timing,
+    /// 问题与容量建议等扫描结果
+    pub scan: ProjectScan,
+}
+
+impl AnalysisReport {
+    /// 按文件 (相对路径) 分组的问题，组内保持扫描结果的顺序
+    #[allow(dead_code)]
+    pub fn by_file(&self) -> BTreeMap<&str, Vec<&AstIssue>> {
+        let mut files: BTreeMap<&str, Vec<&AstIssue>> = BTreeMap::new();
+        for issue in &self.scan.issues {
+            files.entry(issue.path.as_str()).or_default().push(issue);
+        }
+        files
+    }
+}
@@ -22,40 +22,225 @@
 //! 8. 移除所有 Regex 规则，全部使用 Tree-sitter (v9.1)
 //! 9. Rayon reduce 并行合并符号表 (v9.4)
 //! 10. CallGraph 调用链追踪 (v9.4)
+//! 11. Phase 2 改为有界生产者/消费者流水线 (pipeline.rs)，峰值内存与仓库规模无关
+//! 12. Phase 1 符号表改为 DashMap 分片并发写入 (symbol_shards.rs)，不再两两合并
 
+use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
-use std::path::Path;
-use std::sync::Mutex;
+use tree_sitter::Tree;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 use rayon::prelude::*;
 
 use crate::scanner::{CodeAnalyzer, Issue as ScannerIssue, Severity as ScannerSeverity};
-use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+use crate::scanner::tree_sitter_java::{JavaTreeSitterAnalyzer, SuppressedIssue};
 use crate::scanner::config::LineBasedConfigAnalyzer;
 use crate::scanner::dockerfile::DockerfileAnalyzer;
-use crate::taint::{CallGraph, MethodSig, LayerType};
-use crate::symbol_table::{LayerType as SymbolLayerType, ImportIndex};
-use std::collections::HashMap;
+use crate::scanner::issue_builder::{fingerprint, line_snippet};
+use crate::scanner::parse_health::{ParseHealth, ParseStatus};
+use crate::scanner::source_shape::{ShapeStats, SourceShape};
+use crate::scanner::{held_lock, patch};
+use crate::taint::{CallGraph, MethodSig, LayerType, ProxyKind};
+use crate::shared_path::FilePath;
+use crate::symbol_table::{LayerType as SymbolLayerType, ImportIndex, SymbolTable};
+use crate::symbol_shards::SymbolShards;
+use crate::encoding::{read_source, strip_bom, SourceText};
+use crate::parallelism::Parallelism;
+use crate::reachability::{self, ClassReachability};
+use crate::density::{self, FileLoc, LocIndex};
+use crate::contention::{self, LockSite};
+use crate::pipeline::{self, PipelineOptions};
+use crate::project_detector::{detect_stack_in, BuildScope, DetectedStack};
+use crate::rules::registry::{RuleFilter, RuleRegistry, StackAdjustment, CONFIG_FILE_RULES, DOCKERFILE_RULES};
+use crate::rules::suppression::SuppressionSource;
+use crate::rules::custom::CustomRules;
+use crate::rules::timing::TimingProfile;
+use crate::jdk_engine::{self, JvmTuning};
+use crate::pool_advisor::{self, PoolAdvice};
+use crate::sampling;
+use crate::sql_mapping::{self, MappedSql};
+use crate::todo_debt::{self, DebtComment, DebtRef};
+use crate::complexity::{self, MethodEstimate};
+use crate::static_init;
+use crate::dependency_audit;
+use crate::safe_mode::{self, SafeMode};
+use crate::slo::{self, BudgetIndex, BudgetRef};
+use crate::coverage::{self, Coverage};
+use crate::checkpoint::{Checkpoint, Resumable};
+use crate::diagnostics::{self, Diagnostic, DiagnosticKind};
+use crate::hotness::{self, HotnessIndex, MethodSpan};
+use crate::method_rollup;
+use crate::category_report;
+use crate::dedup::{self, DedupPolicy};
+use crate::symbol_cache;
+use crate::dead_code::{self, InactiveRange};
+use crate::project_config::{self, RuleExclusions, SeverityMap};
+use crate::codeowners::{self, CodeOwners};
+use crate::analysis::{AnalysisReport, Timing};
+use crate::baseline::{self, BaselineStore, IssueState, Triage};
+use crate::classpath::{self, ClassIndex};
+use crate::entry_scope::{EntryPoint, EntryScope};
+use crate::blame_age::{self, IssueAge};
+use crate::source_roots::SourceRoots;
+use clap::ValueEnum;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // 规则定义
 // ============================================================================
 
 /// 问题严重级别
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Severity {
     P0, // 严重
     P1, // 警告
 }
 
 /// AST 检测问题
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AstIssue {
     pub severity: Severity,
+    #[serde(rename = "id")]
     pub issue_type: String,
     pub file: String,
+    /// 相对扫描根目录的路径 (用于 verify 重新定位文件)；同一文件的问题共享一份
+    #[serde(default)]
+    pub path: FilePath,
     pub line: usize,
+    #[serde(default)]
+    pub column: usize,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// 问题所在行源码 (去除首尾空白)，用于 verify 识别"已移动"的问题
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub snippet: String,
+    /// 位置无关的指纹 (规则 ID + 所在类 + 行源码，见 scanner::issue_builder)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub fingerprint: String,
+    /// 热度 0-100：所在方法离 Controller 入口越近越高，不可达为 0
+    #[serde(default)]
+    pub hotness: u8,
+    /// 所在方法的全限定名 (见 method_rollup 模块)，不在方法内时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// 同一位置被合并的其它规则 ID (见 dedup 模块)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secondary: Vec<String>,
+    /// 位于常量条件的不可达分支内 (见 dead_code 模块)，P0 已降为 P1
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inactive: bool,
+    /// 被抑制的问题 (仅 `--include-suppressed` 时输出，不计入 P0/P1)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suppressed: bool,
+    /// 抑制来源 (指令形式与所在行)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<SuppressionSource>,
+    /// CODEOWNERS 中的负责人 (见 codeowners 模块)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// 确定性修复的 unified diff (可 `git apply`，见 scanner::patch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_patch: Option<String>,
+    /// 基线中记录的处理状态/负责人/备注 (`--baseline` / `--state`，见 baseline 模块)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triage: Option<Triage>,
+    /// 所在行最后修改距今的时间 (`--blame-age`，见 blame_age 模块)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age: Option<IssueAge>,
+    /// 问题所在方法危及的最紧延迟预算 (`slo.yaml`，见 slo 模块)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetRef>,
+    /// 所在方法的测试覆盖 (`--jacoco`，见 coverage 模块)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<Coverage>,
+    /// 同一方法中最近的性能相关 TODO 注释 (`--todo-debt`，见 todo_debt 模块)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debt: Option<DebtRef>,
+    /// N+1 调用的 DAO 方法对应的 SQL (mapper XML / `@Query`，见 sql_mapping 模块)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql: Option<MappedSql>,
+}
+
+/// 问题排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum IssueSort {
+    /// 按文件路径与行号
+    #[default]
+    Location,
+    /// 热路径优先 (同热度按位置)
+    Hotness,
+    /// 最近修改优先 (需 `--blame-age`，无修改时间的问题排在最后)
+    Recent,
+    /// 延迟预算最紧优先 (需 `slo.yaml`，不在任何预算内的问题排在最后)
+    Budget,
+}
+
+/// 报告的汇总维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    /// 按 CODEOWNERS 负责人
+    Owner,
+}
+
+/// 扫描输出选项
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub sort: IssueSort,
+    /// 重叠问题合并策略
+    pub dedup: DedupPolicy,
+    /// 复用/更新 `.javaperf/symbols.bin` 项目索引缓存
+    pub cache: bool,
+    /// 保留被抑制的问题 (标记 suppressed 与抑制来源，单独输出)
+    pub include_suppressed: bool,
+    /// 只运行指定规则/类别 (`--rules` / `--categories`)
+    pub rules: RuleFilter,
+    /// 报告额外按该维度汇总 (`--group-by`)
+    pub group_by: Option<GroupBy>,
+    /// 隐藏基线内的已知问题 (`--baseline`，见 baseline 模块)
+    pub baseline: bool,
+    /// 存在语法错误的 Java 文件时扫描失败 (`--strict-parse`)
+    pub strict_parse: bool,
+    /// 库调用方在运行时注册的规则与处理器 (见 rules::custom)
+    pub custom_rules: CustomRules,
+    /// 编译产物 classpath (`--classpath`)，用于确认第三方字段类型 (见 classpath 模块)
+    pub classpath: Vec<PathBuf>,
+    /// 只分析从该方法沿调用图可达的代码 (`--entry`，见 entry_scope 模块)
+    pub entry: Option<EntryPoint>,
+    /// 只报告处于这些处理状态的问题 (`--state`，基线之外的问题为 new)
+    pub states: Vec<IssueState>,
+    /// 超过该年数未修改的代码中的 P0 降为 P1 (`--blame-age`，见 blame_age 模块)
+    pub blame_age: Option<u32>,
+    /// 附加源码根目录：参与索引与跨根调用解析 (见 source_roots 模块)
+    pub extra_roots: Vec<PathBuf>,
+    /// 只分析并报告该根目录下的文件 (`--report-root`)
+    pub report_root: Option<PathBuf>,
+    /// 扫描不可信代码库：不启动外部进程、限制单文件资源、不读取根目录之外的文件 (见 safe_mode 模块)
+    pub safe_mode: bool,
+    /// 延迟预算文件 (`--slo`，未指定时读取扫描根目录下的 slo.yaml，见 slo 模块)
+    pub slo: Option<PathBuf>,
+    /// 检查点文件 (`--checkpoint`)：按批持久化 Phase 2 结果，中断后再次扫描时跳过已完成的文件 (见 checkpoint 模块)
+    pub checkpoint: Option<PathBuf>,
+    /// JaCoCo XML 报告 (`--jacoco`)：标注问题所在方法是否被测试覆盖 (见 coverage 模块)
+    pub jacoco: Option<PathBuf>,
+    /// 只保留未覆盖代码中的问题 (`--only-uncovered`，需 jacoco)
+    pub only_uncovered: bool,
+    /// HELD_LOCK_BLOCKING 沿调用图追踪的层数 (`--lock-depth`，未指定时见 held_lock::DEFAULT_DEPTH)
+    pub lock_depth: Option<usize>,
+    /// 收集性能相关的 TODO/FIXME 注释并与同一方法的问题关联 (`--todo-debt`，见 todo_debt 模块)
+    pub todo_debt: bool,
+    /// 估算各方法的时间复杂度并按类汇总 (`--complexity`，见 complexity 模块)
+    pub complexity: bool,
+    /// 统计并输出各规则耗时 (`--rule-timings`，见 rules::timing)
+    pub rule_timings: bool,
+    /// 工作线程数 (`--jobs`，未指定时按可用核数与 cgroup 配额，见 parallelism 模块)
+    pub jobs: Option<usize>,
+    /// 深度分析的入口类 (`--entrypoints`，为空时取 .javaperf.toml 的 `[reachability]`，见 reachability 模块)
+    pub entrypoints: Vec<String>,
+    /// 忽略入口配置，对全部文件执行深度分析 (`--exhaustive`)
+    pub exhaustive: bool,
 }
 
 // v9.1: Regex 规则已全部迁移到 tree_sitter_java.rs
@@ -67,12 +252,228 @@ fn convert_issue(issue: ScannerIssue) -> AstIssue {
         ScannerSeverity::P0 => Severity::P0,
         ScannerSeverity::P1 => Severity::P1,
     };
+    let suggested_patch = issue.patch.map(|hunks| patch::unified_diff(&issue.file, &hunks));
     AstIssue {
         severity: sev,
         issue_type: issue.id,
+        path: FilePath::new(&issue.file),
         file: issue.file,
         line: issue.line,
+        column: issue.column,
         description: issue.description,
+        context: issue.context,
+        snippet: issue.snippet,
+        fingerprint: issue.fingerprint,
+        hotness: 0,
+        method: None,
+        secondary: Vec::new(),
+        inactive: false,
+        suppressed: false,
+        suppression: None,
+        owners: Vec::new(),
+        suggested_patch,
+        triage: None,
+        age: None,
+        budget: None,
+        coverage: None,
+        debt: None,
+        sql: None,
+    }
+}
+
+/// 转换并补全位置信息 (相对路径 + 行源码 + 指纹)
+///
+/// Java 规则经 IssueBuilder 构造时已带行源码与指纹；配置文件/Dockerfile 等
+/// 行级分析器的问题在这里补全，所在类取文件名 (与 Java 规则一致)。
+/// 同一文件的问题共享调用方传入的相对路径。
+pub(crate) fn locate_issue(mut issue: ScannerIssue, rel_path: &FilePath, content: &str) -> AstIssue {
+    let hunks = issue.patch.take();
+    let mut ast_issue = convert_issue(issue);
+    ast_issue.path = rel_path.clone();
+    ast_issue.suggested_patch = hunks.map(|hunks| patch::unified_diff(rel_path.as_str(), &hunks));
+    if ast_issue.snippet.is_empty() {
+        ast_issue.snippet = line_snippet(content, ast_issue.line);
+    }
+    if ast_issue.fingerprint.is_empty() {
+        let scope = rel_path.file_stem().unwrap_or_default();
+        ast_issue.fingerprint = fingerprint(&ast_issue.issue_type, scope, &ast_issue.snippet);
+    }
+    ast_issue
+}
+
+/// 计算相对扫描根目录的路径 (统一使用 '/' 分隔)
+pub(crate) fn relative_path(root: &Path, file_path: &Path) -> String {
+    let rel = file_path.strip_prefix(root).ok()
+        .filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| file_path.file_name().map(Path::new))
+        .unwrap_or(file_path);
+    rel.to_string_lossy().replace('\\', "/")
+}
+
+// ============================================================================
+// 文件分析器分派
+// ============================================================================
+
+/// 按文件类型分派的分析器集合 (Phase 2、verify 与局部分析共用，创建时编译全部 Query，应复用)
+pub struct FileAnalyzers {
+    java: JavaTreeSitterAnalyzer,
+    config: Option<LineBasedConfigAnalyzer>,
+    docker: Option<DockerfileAnalyzer>,
+    /// 配置文件/Dockerfile 规则不经 RuleRegistry 编译，按筛选结果过滤
+    filter: RuleFilter,
+    /// 按路径排除的规则 (.javaperf.toml rule_overrides) 及其匹配的根目录
+    exclusions: RuleExclusions,
+    root: PathBuf,
+    /// 只在入口可达的文件上运行深度分析 (未配置入口时为 None)
+    reachability: Option<ClassReachability>,
+}
+
+impl FileAnalyzers {
+    pub fn new(registry: &RuleRegistry) -> anyhow::Result<Self> {
+        let wants = |ids: &[&str]| ids.iter().any(|id| registry.selects(id));
+        Ok(Self {
+            java: JavaTreeSitterAnalyzer::with_registry(registry)?,
+            config: LineBasedConfigAnalyzer::new().ok().filter(|_| wants(CONFIG_FILE_RULES)),
+            docker: DockerfileAnalyzer::new().ok().filter(|_| wants(DOCKERFILE_RULES)),
+            filter: registry.filter().clone(),
+            exclusions: RuleExclusions::default(),
+            root: PathBuf::new(),
+            reachability: None,
+        })
+    }
+
+    /// 设置 Java 文件的解析超时 (`--safe-mode`)
+    pub(crate) fn with_parse_timeout(mut self, timeout: Duration) -> Self {
+        self.java.set_parse_timeout(timeout);
+        self
+    }
+
+    /// 累计各规则在 Java 文件上的耗时 (`--rule-timings`)
+    pub(crate) fn with_rule_timings(mut self) -> Self {
+        self.java.set_record_timings(true);
+        self
+    }
+
+    /// Java 规则按耗时记录中的代价从低到高执行 (见 rules::timing)；None 为注册表顺序
+    pub(crate) fn with_rule_order(mut self, profile: Option<&TimingProfile>) -> Self {
+        self.java.order_rules(profile);
+        self
+    }
+
+    /// 启用按路径排除 (模式匹配相对 `root` 的路径)
+    pub(crate) fn with_exclusions(mut self, root: &Path, exclusions: RuleExclusions) -> Self {
+        self.root = root.to_path_buf();
+        self.exclusions = exclusions;
+        self
+    }
+
+    /// 深度分析只在入口可达的文件上进行 (见 reachability 模块)
+    pub(crate) fn with_reachability(mut self, reachability: Option<ClassReachability>) -> Self {
+        self.reachability = reachability;
+        self
+    }
+
+    /// 是否在该文件上执行跨文件深度分析
+    pub(crate) fn deep_analysis(&self, file_path: &Path) -> bool {
+        self.reachability.as_ref().is_none_or(|reach| reach.includes_file(file_path))
+    }
+
+    /// 文件上不运行的规则
+    fn excluded_for(&self, file_path: &Path) -> Vec<&str> {
+        let mut excluded = if self.exclusions.is_empty() {
+            Vec::new()
+        } else {
+            self.exclusions.excluded_for(&relative_path(&self.root, file_path))
+        };
+        if !self.deep_analysis(file_path) {
+            excluded.extend_from_slice(reachability::DEEP_RULES);
+        }
+        excluded
+    }
+
+    pub(crate) fn java(&self) -> &JavaTreeSitterAnalyzer {
+        &self.java
+    }
+
+    /// 是否为会被分析的文件 (Java / 配置 / Dockerfile)
+    pub(crate) fn is_target(file_path: &Path) -> bool {
+        let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let file_name = file_path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        ext == "java"
+            || ["yml", "yaml", "properties"].contains(&ext)
+            || file_name == "Dockerfile" || file_name.starts_with("Dockerfile.")
+    }
+
+    /// 分析单个文件 (内容已转码为 UTF-8)
+    pub(crate) fn analyze_file(
+        &self,
+        file_path: &Path,
+        content: &str,
+        symbol_table: Option<&SymbolTable>,
+        call_graph: Option<&CallGraph>,
+    ) -> Vec<ScannerIssue> {
+        let file_name_str = file_path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let excluded = self.excluded_for(file_path);
+
+        if ext == "java" {
+            // v9.4: 传入 SymbolTable 和 CallGraph 用于语义分析和 N+1 验证
+            return self.java.analyze_excluding(content, file_path, symbol_table, call_graph, &excluded)
+                .map(|(issues, _)| issues)
+                .unwrap_or_default();
+        }
+
+        let mut issues = if ["yml", "yaml", "properties"].contains(&ext) {
+            // 3. Config Analysis
+            self.config.as_ref().map(|analyzer| {
+                // v9.5: 优先使用结构化 YAML 解析
+                if ["yml", "yaml"].contains(&ext) {
+                    let structured_issues = analyzer.analyze_yaml_structured(content, &file_name_str);
+                    if !structured_issues.is_empty() {
+                        return structured_issues;
+                    }
+                }
+                // 备用：行匹配 (properties 文件继续使用行匹配)
+                analyzer.analyze(content, file_path).unwrap_or_default()
+            })
+        } else if file_name_str == "Dockerfile" || file_name_str.starts_with("Dockerfile.") {
+            // 4. Dockerfile Analysis (v5.1 NEW)
+            self.docker.as_ref().map(|analyzer| analyzer.analyze(content, file_path).unwrap_or_default())
+        } else {
+            None
+        }.unwrap_or_default();
+
+        issues.retain(|issue| self.filter.selects(&issue.id) && !excluded.contains(&issue.id.as_str()));
+        issues
+    }
+
+    /// 同 analyze_file，额外返回被抑制的问题及抑制来源 (目前只有 Java 支持抑制指令)
+    /// 与 Java 文件的解析健康度
+    ///
+    /// Java 文件使用调用方解析好的 `tree` (由 `content` 解析，见 [`JavaTreeSitterAnalyzer::parse`])，
+    /// 同一棵树随后供项目级收集复用。
+    pub(crate) fn analyze_file_with_suppressed(
+        &self,
+        file_path: &Path,
+        content: &str,
+        tree: Option<&Tree>,
+        symbol_table: Option<&SymbolTable>,
+        call_graph: Option<&CallGraph>,
+    ) -> (Vec<ScannerIssue>, Vec<SuppressedIssue>, Option<ParseHealth>) {
+        if file_path.extension().and_then(|e| e.to_str()) == Some("java") {
+            let excluded = self.excluded_for(file_path);
+            return match self.java.analyze_parsed(tree, content, file_path, symbol_table, call_graph, &excluded) {
+                Ok((issues, suppressed, health)) => (issues, suppressed, Some(health)),
+                Err(e) => {
+                    diagnostics::report(Diagnostic::new(DiagnosticKind::ParseFailure, format!("分析失败，未报告问题: {e}"))
+                        .file(file_path.display().to_string()));
+                    (Vec::new(), Vec::new(), None)
+                }
+            };
+        }
+        (self.analyze_file(file_path, content, symbol_table, call_graph), Vec::new(), None)
     }
 }
 
@@ -80,7 +481,315 @@ fn convert_issue(issue: ScannerIssue) -> AstIssue {
 // 核心扫描函数
 // ============================================================================
 
-/// 全项目雷达扫描 (v9.1 优化架构)
+/// Per-file import index storage
+/// Maps file path (as String) to ImportIndex for that file
+pub(crate) type ImportIndexMap = HashMap<String, ImportIndex>;
+
+/// Phase 1: Indexing (构建全局符号表 + 调用图 + ImportIndex)
+///
+/// 各文件的符号并发写入 [`SymbolShards`]，全部完成后转为只读 SymbolTable；
+/// CallGraph 与 ImportIndex 仍由 Rayon reduce 合并。
+/// v9.7: 收集 per-file ImportIndex 用于 FQN 解析
+pub(crate) fn build_index(java_files: &[&Path], java_analyzer: &JavaTreeSitterAnalyzer) -> (SymbolTable, CallGraph, ImportIndexMap) {
+    if java_files.is_empty() {
+        return (SymbolTable::new(), CallGraph::new(), HashMap::new());
+    }
+
+    let shards = SymbolShards::new();
+    let (graph, imports) = java_files.par_iter()
+        .enumerate()
+        .map(|(ordinal, path)| {
+            let (table, graph, imports) = match read_source(path) {
+                Ok(source) => index_file(path, &source.content, java_analyzer),
+                Err(_) => (SymbolTable::new(), CallGraph::new(), HashMap::new()),
+            };
+            shards.absorb(ordinal, table);
+            (graph, imports)
+        })
+        .reduce(
+            || (CallGraph::new(), HashMap::new()),
+            |(mut acc_graph, mut acc_imports), (graph, imports)| {
+                acc_graph.merge(graph);
+                acc_imports.extend(imports);
+                (acc_graph, acc_imports)
+            }
+        );
+    (shards.into_table(), graph, imports)
+}
+
+/// v9.4 的 Phase 1: SymbolTable 与 CallGraph 一起由 Rayon reduce 两两合并
+///
+/// 保留用于 `dev bench-index` 与分片构建对比 (结果应一致)。
+pub(crate) fn build_index_merged(java_files: &[&Path], java_analyzer: &JavaTreeSitterAnalyzer) -> (SymbolTable, CallGraph, ImportIndexMap) {
+    if java_files.is_empty() {
+        return (SymbolTable::new(), CallGraph::new(), HashMap::new());
+    }
+
+    // 使用 reduce 并行两两合并
+    java_files.par_iter()
+        .map(|path| match read_source(path) {
+            Ok(source) => index_file(path, &source.content, java_analyzer),
+            Err(_) => (SymbolTable::new(), CallGraph::new(), HashMap::new()),
+        })
+        .reduce(
+            || (crate::symbol_table::SymbolTable::new(), CallGraph::new(), HashMap::new()),
+            |(mut acc_table, mut acc_graph, mut acc_imports), (table, graph, imports)| {
+                acc_table.merge(table);
+                acc_graph.merge(graph);
+                // v9.7: Merge ImportIndex maps (per-file, no cross-contamination)
+                acc_imports.extend(imports);
+                (acc_table, acc_graph, acc_imports)
+            }
+        )
+}
+
+/// 索引单个 Java 源文件 (符号 + 调用点 + ImportIndex)
+///
+/// 内容由调用方提供：全量扫描读磁盘，precommit 读 git 暂存区。
+pub(crate) fn index_file(path: &Path, content: &str, java_analyzer: &JavaTreeSitterAnalyzer) -> (SymbolTable, CallGraph, ImportIndexMap) {
+    let mut local_table = SymbolTable::new();
+    let mut local_graph = CallGraph::new();
+    let mut local_import_indices: ImportIndexMap = HashMap::new();
+    // 本文件的全部条目共享同一份路径 (非 UTF-8 路径有损转换，见 shared_path)
+    let file = FilePath::from_path_lossy(path);
+    // 只解析一次，以下各提取步骤共用同一棵树
+    let content = strip_bom(content);
+    let Some(tree) = java_analyzer.parse(content) else {
+        return (local_table, local_graph, local_import_indices);
+    };
+
+    // 0. 字符串常量 (SQL 常量引用解析；枚举等非 class 文件也需要)
+    for constant in java_analyzer.extract_constants_from_tree(&tree, content, &file) {
+        local_table.register_constant(constant);
+    }
+
+    // 布尔常量 (功能开关，用于识别不可达分支)
+    if content.contains("boolean") {
+        for flag in dead_code::collect_constants(tree.root_node(), content) {
+            local_table.register_bool_constant(&flag.class, &flag.name, flag.value);
+        }
+    }
+
+    // 方法签名 (返回类型用于解析链式调用的接收者，如 `service.getRepo().findById()`)
+    // 同时记下带代理注解的方法，类信息就绪后登记到 CallGraph
+    let mut declared_methods = Vec::new();
+    for method in java_analyzer.extract_methods_from_tree(&tree, content) {
+        let class = method.class.clone();
+        let proxy = method.annotations.iter().find_map(|ann| ProxyKind::from_annotation(ann));
+        declared_methods.push((class.clone(), method.name.clone(), proxy));
+        local_table.register_method(&class, method);
+    }
+
+    // 方法体内的加锁与阻塞调用 (HELD_LOCK_BLOCKING 沿调用图追踪)
+    let method_effects = if held_lock::may_have_effects(content) {
+        held_lock::method_effects(tree.root_node(), content)
+    } else {
+        Vec::new()
+    };
+
+    // 1. 提取符号和类信息 (v9.6: now includes ImportIndex)
+    if let Ok((Some(type_info), bindings, import_index)) = java_analyzer.extract_symbols_from_tree(&tree, content, &file) {
+        let class_name = type_info.name.clone();
+        let class_fqn = type_info.fqn.clone(); // v9.8: Use FQN for CallGraph
+        
+        // v9.7: Store ImportIndex for this file
+        local_import_indices.insert(file.to_string(), import_index.clone());
+        
+        // 根据 SymbolTable 的 LayerType 转换为 taint 的 LayerType
+        let layer = match type_info.layer {
+            SymbolLayerType::Controller => LayerType::Controller,
+            SymbolLayerType::Service => LayerType::Service,
+            SymbolLayerType::Repository => LayerType::Repository,
+            _ => LayerType::Unknown,
+        };
+        
+        // v9.8: 注册到 CallGraph 使用 FQN
+        local_graph.register_class(&class_fqn, &file, layer);
+        // Also register with simple name for backward compatibility
+        local_graph.register_class(&class_name, &file, layer);
+
+        // 代理拦截的方法：方法注解，或顶层类上的 @Transactional 等作用于类中声明的全部方法。
+        // 文件内的调用边以顶层类 FQN 为调用方，这里同样以顶层类 FQN 登记，另以声明类简单名登记
+        let class_proxy = type_info.annotations.iter().find_map(|ann| ProxyKind::from_annotation(ann));
+        for (class, method, proxy) in declared_methods {
+            let Some(kind) = proxy.or(class_proxy.filter(|_| class == class_name)) else { continue };
+            local_graph.mark_proxied(MethodSig::new_fqn(&class_fqn, &method), kind);
+            local_graph.mark_proxied(MethodSig::new(&class, &method), kind);
+        }
+        for (class, method, effects) in method_effects {
+            local_graph.mark_effects(MethodSig::new_fqn(&class_fqn, &method), effects.clone());
+            local_graph.mark_effects(MethodSig::new(&class, &method), effects);
+        }
+        
+        // 注册到 SymbolTable (v9.7: use FQN-based registration)
+        local_table.register_class_fqn(type_info);
+        for binding in bindings {
+            local_table.register_field(&class_name, binding);
+        }
+        
+        // 2. 提取调用点并构建 CallGraph
+        // v9.8: Use FQN resolution for call sites
+        if let Ok(call_sites) = java_analyzer.extract_call_sites_from_tree(&tree, content, path) {
+            for (caller_method, receiver, callee_method, line) in call_sites {
+                // v9.8: 构建调用关系，使用 FQN 解析
+                // Caller uses the class FQN directly
+                let caller = MethodSig::new_fqn(&class_fqn, &caller_method);
+                
+                // Callee: Try to resolve receiver to FQN using ImportIndex and local SymbolTable
+                // Note: receiver 可能是字段名，需要通过 SymbolTable 解析实际类型；"this" 为本类方法
+                let callee = if receiver == "this" {
+                    MethodSig::new_fqn(&class_fqn, &callee_method)
+                } else {
+                    MethodSig::resolve(&receiver, &callee_method, &import_index, &local_table)
+                };
+                
+                local_graph.add_call(caller, callee, &file, line);
+            }
+        }
+    }
+
+    (local_table, local_graph, local_import_indices)
+}
+
+/// 项目扫描结果 (渲染前的结构化数据)
+#[derive(Debug, Serialize)]
+pub struct ProjectScan {
+    pub file_count: usize,
+    /// 按 (path, line, column, id) 排序
+    pub issues: Vec<AstIssue>,
+    /// 非 UTF-8 源文件统计: 编码名 -> (文件数, 含无法映射字节的文件数)
+    pub encoding_stats: HashMap<&'static str, (usize, usize)>,
+    /// 技术栈导致的规则调整
+    pub adjustments: Vec<StackAdjustment>,
+    /// 线程池容量建议
+    pub pool_advice: Vec<PoolAdvice>,
+    /// 容量估算使用的核数
+    pub cpus: usize,
+    /// 输出用的严重级别名称 (`.javaperf.toml` 的 `[severity_map]`)
+    pub severity_map: SeverityMap,
+    /// 被抑制的问题 (仅 include_suppressed 时收集)，按位置排序
+    pub suppressed: Vec<AstIssue>,
+    /// 因在基线内而隐藏的问题数 (仅 baseline 时统计)
+    pub baselined: usize,
+    /// 含语法错误的 Java 文件 (PARSE_DEGRADED)，按路径排序
+    pub parse_degraded: Vec<DegradedFile>,
+    /// 默认包 / package-info / 仅注解类型 / BOM 文件数
+    pub source_shapes: ShapeStats,
+    /// 各文件的代码行数 (问题密度的分母，见 density 模块)
+    pub loc: LocIndex,
+}
+
+/// 解析降级的文件 (错误区域内的问题未报告，见 scanner::parse_health)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DegradedFile {
+    pub path: String,
+    pub status: ParseStatus,
+    /// ERROR / MISSING 节点数
+    pub error_nodes: usize,
+    /// 错误区域覆盖源码的比例
+    pub error_ratio: f64,
+    pub first_error_line: usize,
+}
+
+impl DegradedFile {
+    fn from_health(path: &str, health: &ParseHealth) -> Option<Self> {
+        let status = health.status();
+        (status != ParseStatus::Clean).then(|| Self {
+            path: path.to_string(),
+            status,
+            error_nodes: health.error_nodes,
+            error_ratio: (health.error_ratio() * 1000.0).round() / 1000.0,
+            first_error_line: health.first_error_line().unwrap_or(1),
+        })
+    }
+}
+
+impl ProjectScan {
+    /// 重新排序问题列表 (扫描结果默认按位置排序)
+    pub fn sort_issues(&mut self, sort: IssueSort) {
+        // 稳定排序：同热度/同修改时间保持位置顺序
+        match sort {
+            IssueSort::Location => {}
+            IssueSort::Hotness => self.issues.sort_by_key(|i| std::cmp::Reverse(i.hotness)),
+            IssueSort::Recent => self.issues.sort_by_key(|i| i.age.as_ref().map_or(u64::MAX, |age| age.days)),
+            IssueSort::Budget => self.issues.sort_by_key(|i| i.budget.as_ref().map_or(u64::MAX, |b| b.latency_ms)),
+        }
+    }
+
+    pub fn p0_count(&self) -> usize {
+        self.issues.iter().filter(|i| matches!(i.severity, Severity::P0)).count()
+    }
+
+    pub fn p1_count(&self) -> usize {
+        self.issues.iter().filter(|i| matches!(i.severity, Severity::P1)).count()
+    }
+}
+
+/// 单个文件在 Phase 2 的分析结果 (工作线程产出，写入者聚合；`--checkpoint` 时按批持久化)
+#[derive(Default, Serialize, Deserialize)]
+struct FileOutcome {
+    rel_path: String,
+    /// 分析时的内容哈希 (恢复检查点时判断文件是否变化)
+    content_hash: u64,
+    /// 来自检查点 (无需再次写入)
+    #[serde(skip)]
+    resumed: bool,
+    /// 转码来源编码及是否含无法映射的字节
+    #[serde(deserialize_with = "deserialize_encoding")]
+    encoding: Option<(&'static str, bool)>,
+    issues: Vec<AstIssue>,
+    suppressed: Vec<AstIssue>,
+    pool_usage: Option<pool_advisor::FileUsage>,
+    static_inits: Vec<static_init::StaticInit>,
+    method_spans: Option<Vec<MethodSpan>>,
+    /// Controller 方法的 HTTP 映射 (配置了延迟预算时收集)
+    routes: Vec<slo::Route>,
+    inactive_ranges: Vec<InactiveRange>,
+    parse: Option<DegradedFile>,
+    /// Java 文件的 (文件名, 顶层结构, 是否带 BOM)；解析失败时结构为 None
+    shape: Option<(String, Option<SourceShape>, bool)>,
+    /// 分析中报告的诊断 (跳过的文件、规则执行限制等)
+    diagnostics: Vec<Diagnostic>,
+    /// 性能相关的 TODO 注释 (`--todo-debt`)
+    debt_comments: Vec<DebtComment>,
+    /// 各方法的复杂度估算 (`--complexity`)
+    complexity: Vec<MethodEstimate>,
+    /// 代码行数与所属包
+    #[serde(default)]
+    loc: Option<FileLoc>,
+    /// 持锁区域 (锁竞争风险排名)
+    #[serde(default)]
+    lock_sites: Vec<LockSite>,
+}
+
+impl Resumable for FileOutcome {
+    fn key(&self) -> &str {
+        &self.rel_path
+    }
+
+    fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+}
+
+/// 编码名称还原为 encoding_rs 的静态名称
+fn deserialize_encoding<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<(&'static str, bool)>, D::Error> {
+    let value: Option<(String, bool)> = Option::deserialize(deserializer)?;
+    Ok(value.and_then(|(name, had_errors)| {
+        encoding_rs::Encoding::for_label(name.as_bytes()).map(|encoding| (encoding.name(), had_errors))
+    }))
+}
+
+/// Phase 2 待处理的文件: 新读取的源码，或检查点中已完成的结果
+enum PendingFile {
+    /// 源码与内容哈希 (未启用检查点时为 0)
+    Source(SourceText, u64),
+    Done(Box<FileOutcome>),
+    /// 读取失败 (错误信息)
+    Unreadable(String),
+}
+
+/// 全项目扫描，返回结构化结果 (v9.1 优化架构)
 ///
 /// ## 性能优化 (v9.1):
 /// - **thread_local Parser 复用**: 每个线程只初始化一次 Parser
@@ -93,245 +802,916 @@ fn convert_issue(issue: ScannerIssue) -> AstIssue {
 ///
 /// 虽然每个文件被解析两次，但通过 thread_local Parser 复用，
 /// 避免了每次调用都创建 Parser 的开销（主要开销是 native 层初始化）。
+#[allow(dead_code)]
+pub fn scan_project(code_path: &str, options: &ScanOptions) -> Result<ProjectScan, Box<dyn std::error::Error>> {
+    analyze_project(code_path, options).map(|report| report.scan)
+}
+
+/// 全项目分析，返回带技术栈、规则/符号表/调用图统计与阶段耗时的完整报告
 ///
-/// compact: true 时只返回 P0，每个 issue 只有 id/file/line
-/// max_p1: compact=false 时最多返回的 P1 数量
-pub fn radar_scan(code_path: &str, compact: bool, max_p1: usize) -> Result<Value, Box<dyn std::error::Error>> {
+/// 所有输出格式 (Markdown / JSON / SARIF / Checkstyle) 都从这份报告渲染。
+pub fn analyze_project(code_path: &str, options: &ScanOptions) -> Result<AnalysisReport, Box<dyn std::error::Error>> {
     let path = Path::new(code_path);
     let is_dir = path.is_dir();
+    let config = project_config::load(path)?;
+    let roots = SourceRoots::new(path, &options.extra_roots)?;
+    if roots.is_multi() {
+        tracing::debug!("附加源码根: {}", roots.extra_labels().join(", "));
+    }
+    let report_dir = options.report_root.as_deref().map(|dir| roots.resolve(dir)).transpose()?;
+    let safe = if options.safe_mode {
+        if options.blame_age.is_some() {
+            return Err("--safe-mode 下不执行 git，不能使用 --blame-age".into());
+        }
+        Some(SafeMode::new(std::iter::once(path).chain(roots.extra_dirs()))?)
+    } else {
+        None
+    };
+    let build_scope = if safe.is_some() { BuildScope::confined(path) } else { BuildScope::default() };
+    let slo_config = slo::load(path, options.slo.as_deref())?;
+    let coverage_report = match &options.jacoco {
+        Some(file) => Some(coverage::load(file)?),
+        None if options.only_uncovered => return Err("--only-uncovered 需要 --jacoco 指定覆盖率报告".into()),
+        None => None,
+    };
+    // 收集本线程上报告的诊断 (规则编译、检查点、基线等)；单文件分析的诊断随 FileOutcome 汇总
+    let diagnostics_scope = diagnostics::Scope::enter();
+    if coverage_report.as_ref().is_some_and(coverage::CoverageReport::is_empty) {
+        diagnostics::warn("JaCoCo 报告中没有行覆盖数据，问题不会标注覆盖情况");
+    }
+    // 各阶段耗时 span (--log-level debug 时输出，见 logging 模块)
+    let _scan_span = tracing::debug_span!("scan", path = code_path).entered();
+    let started = Instant::now();
+    let mut lap = started;
+    // Phase 1 的 rayon 线程池与 Phase 2 的工作线程数 (--jobs，默认按可用核数与 cgroup 配额)
+    let parallelism = Parallelism::detect(options.jobs).install();
+    tracing::debug!(?parallelism, "parallelism");
     
-    // 收集所有待扫描文件
-    let entries: Vec<_> = WalkDir::new(path)
-        .follow_links(true)
-        .into_iter()
+    // 收集所有待扫描文件 (主根 + 附加根)
+    let collect_span = tracing::debug_span!("collect_files").entered();
+    let entries: Vec<PathBuf> = std::iter::once(path)
+        .chain(roots.extra_dirs())
+        .flat_map(|root| {
+            WalkDir::new(root)
+                .follow_links(true)
+                .into_iter()
+                .filter_entry(|e| e.file_name() != symbol_cache::CACHE_DIR && safe.as_ref().is_none_or(|s| s.admits_entry(e)))
+        })
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
         .collect();
+    // 索引与报告中的路径均为 UTF-8 (见 shared_path)：非 UTF-8 路径 (如 GBK 编码的文件名) 照常分析，
+    // 报告中无法表示的字节显示为 U+FFFD，并给出警告
+    for entry in entries.iter().filter(|entry| entry.to_str().is_none()) {
+        diagnostics::report(Diagnostic::new(DiagnosticKind::Warning, "路径不是 UTF-8，报告中无法表示的字节显示为 U+FFFD")
+            .file(roots.relative_path(entry)));
+    }
 
     let file_count = entries.len();
+    tracing::debug!(files = file_count, "collected files");
+    drop(collect_span);
+    let collect_ms = elapsed_ms(&mut lap);
+
+    // 按技术栈调整规则 (如 WebFlux 下 BLOCKING_IO 升级为 P0) 与 .javaperf.toml 启用/关闭，再按 --rules/--categories 筛选
+    let stack = is_dir.then(|| detect_stack_in(path, &build_scope));
+    let mut registry = stack.as_ref().map_or_else(RuleRegistry::default, RuleRegistry::with_stack);
+    registry.register_custom(&options.custom_rules)?;
+    registry.apply_overrides(&config.rule_overrides)?;
+    let sampling = config.sampling()?;
+    registry.apply_filter(options.rules.clone());
+    if let Some(depth) = options.lock_depth {
+        registry.set_lock_depth(depth);
+    }
+    if let Some(names) = config.bigdecimal.exact_names.clone() {
+        registry.set_exact_names(names);
+    }
+    if safe.is_some() {
+        registry.limit_all(safe_mode::RULE_LIMITS);
+    }
+    let static_init_selected = static_init::RULES.iter().any(|id| registry.selects(id));
+    let dependency_selected = is_dir && dependency_audit::RULES.iter().any(|id| registry.selects(id));
 
-    // 初始化分析器 (Arc 共享，只编译一次 queries)
-    let java_analyzer = std::sync::Arc::new(JavaTreeSitterAnalyzer::new()?);
-    let config_analyzer = LineBasedConfigAnalyzer::new().ok();
-    let docker_analyzer = DockerfileAnalyzer::new().ok();
+    // 初始化分析器 (只编译一次 queries)
+    let exclusions = config.exclusions()?;
+    let mut analyzers = FileAnalyzers::new(&registry)?.with_exclusions(path, exclusions.clone());
+    if safe.is_some() {
+        analyzers = analyzers.with_parse_timeout(safe_mode::PARSE_TIMEOUT);
+    }
+    // 规则按该项目以往 --rule-timings 的实测耗时排序 (没有记录时按 Query 估计)
+    let mut timing_profile = if is_dir { TimingProfile::load(path) } else { TimingProfile::default() };
+    analyzers = analyzers.with_rule_order(Some(&timing_profile));
+    if options.rule_timings {
+        analyzers = analyzers.with_rule_timings();
+    }
 
     // === Phase 1: Indexing (构建全局符号表 + 调用图 + ImportIndex) ===
-    // v9.4: 使用 Rayon reduce 并行合并 SymbolTable 和 CallGraph
-    // v9.7: 收集 per-file ImportIndex 用于 FQN 解析
-    
-    /// Per-file import index storage
-    /// Maps file path (as String) to ImportIndex for that file
-    type ImportIndexMap = HashMap<String, ImportIndex>;
-    
-    let (symbol_table, call_graph, _import_indices) = if is_dir {
+    let index_span = tracing::debug_span!("index").entered();
+    let (mut symbol_table, call_graph, import_indices) = if is_dir {
         // 筛选 Java 文件
-        let java_files: Vec<_> = entries.iter()
-            .filter(|e| e.path().extension().and_then(|e| e.to_str()) == Some("java"))
+        let java_files: Vec<&Path> = entries.iter()
+            .map(PathBuf::as_path)
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("java"))
             .collect();
-            
-        if !java_files.is_empty() {
-            // 使用 reduce 并行两两合并
-            java_files.par_iter()
-                .map(|entry| {
-                    let mut local_table = crate::symbol_table::SymbolTable::new();
-                    let mut local_graph = CallGraph::new();
-                    let mut local_import_indices: ImportIndexMap = HashMap::new();
-                    
-                    if let Ok(content) = std::fs::read_to_string(entry.path()) {
-                        // 1. 提取符号和类信息 (v9.6: now includes ImportIndex)
-                        if let Ok((Some(type_info), bindings, import_index)) = java_analyzer.extract_symbols(&content, entry.path()) {
-                            let class_name = type_info.name.clone();
-                            let class_fqn = type_info.fqn.clone(); // v9.8: Use FQN for CallGraph
-                            let file_path_str = entry.path().to_string_lossy().to_string();
-                            
-                            // v9.7: Store ImportIndex for this file
-                            local_import_indices.insert(file_path_str, import_index.clone());
-                            
-                            // 根据 SymbolTable 的 LayerType 转换为 taint 的 LayerType
-                            let layer = match type_info.layer {
-                                SymbolLayerType::Controller => LayerType::Controller,
-                                SymbolLayerType::Service => LayerType::Service,
-                                SymbolLayerType::Repository => LayerType::Repository,
-                                _ => LayerType::Unknown,
-                            };
-                            
-                            // v9.8: 注册到 CallGraph 使用 FQN
-                            local_graph.register_class(&class_fqn, entry.path().to_path_buf(), layer);
-                            // Also register with simple name for backward compatibility
-                            local_graph.register_class(&class_name, entry.path().to_path_buf(), layer);
-                            
-                            // 注册到 SymbolTable (v9.7: use FQN-based registration)
-                            local_table.register_class_fqn(type_info);
-                            for binding in bindings {
-                                local_table.register_field(&class_name, binding);
-                            }
-                            
-                            // 2. 提取调用点并构建 CallGraph
-                            // v9.8: Use FQN resolution for call sites
-                            if let Ok(call_sites) = java_analyzer.extract_call_sites(&content, entry.path()) {
-                                for (caller_method, receiver, callee_method, line) in call_sites {
-                                    // v9.8: 构建调用关系，使用 FQN 解析
-                                    // Caller uses the class FQN directly
-                                    let caller = MethodSig::new_fqn(&class_fqn, &caller_method);
-                                    
-                                    // Callee: Try to resolve receiver to FQN using ImportIndex and local SymbolTable
-                                    // Note: receiver 可能是字段名，需要通过 SymbolTable 解析实际类型
-                                    let callee = MethodSig::resolve(&receiver, &callee_method, &import_index, &local_table);
-                                    
-                                    local_graph.add_call(caller, callee, entry.path().to_path_buf(), line);
-                                }
-                            }
-                        }
-                    }
-                    (local_table, local_graph, local_import_indices)
-                })
-                .reduce(
-                    || (crate::symbol_table::SymbolTable::new(), CallGraph::new(), HashMap::new()),
-                    |(mut acc_table, mut acc_graph, mut acc_imports), (table, graph, imports)| {
-                        acc_table.merge(table);
-                        acc_graph.merge(graph);
-                        // v9.7: Merge ImportIndex maps (per-file, no cross-contamination)
-                        acc_imports.extend(imports);
-                        (acc_table, acc_graph, acc_imports)
-                    }
-                )
+        if options.cache {
+            let index = symbol_cache::build(path, &java_files, analyzers.java(), false, &HashSet::new());
+            tracing::debug!(stats = ?index.stats, "symbol cache");
+            (index.table, index.graph, index.imports)
         } else {
-            (crate::symbol_table::SymbolTable::new(), CallGraph::new(), HashMap::new())
+            build_index(&java_files, analyzers.java())
         }
     } else {
-        (crate::symbol_table::SymbolTable::new(), CallGraph::new(), HashMap::new())
+        (SymbolTable::new(), CallGraph::new(), HashMap::new())
+    };
+    // 第三方字段类型 (--classpath)：确认后相关规则按高置信度报告
+    let classpath = if is_dir && !options.classpath.is_empty() {
+        let index = ClassIndex::load(&options.classpath)?;
+        let stats = classpath::resolve_external(&mut symbol_table, &import_indices, &index);
+        tracing::debug!(?stats, "classpath");
+        Some(stats)
+    } else {
+        None
+    };
+    drop(index_span);
+    let index_ms = elapsed_ms(&mut lap);
+
+    // 入口范围：只分析可达方法所在的 Java 文件
+    let entry_scope = match &options.entry {
+        Some(entry) if is_dir => Some(EntryScope::build(&call_graph, &symbol_table, entry)?),
+        Some(_) => return Err("--entry 需要扫描项目目录".into()),
+        None => None,
+    };
+    // 其余根目录只参与索引 (--report-root)
+    let targets: Cow<[PathBuf]> = if entry_scope.is_none() && report_dir.is_none() {
+        Cow::Borrowed(&entries)
+    } else {
+        entries.iter()
+            .filter(|p| entry_scope.as_ref().is_none_or(|scope| scope.includes_file(p)))
+            .filter(|p| report_dir.is_none_or(|dir| p.starts_with(dir)))
+            .cloned()
+            .collect()
+    };
+    // 可达性裁剪：跨文件深度分析只在入口可达的类上进行 (--exhaustive 时对全部文件)
+    let entrypoints = if options.entrypoints.is_empty() { &config.reachability.entrypoints } else { &options.entrypoints };
+    let reachability = if is_dir && !options.exhaustive && !entrypoints.is_empty() {
+        Some(ClassReachability::build(&call_graph, &symbol_table, entrypoints)?)
+    } else {
+        None
     };
+    let reachability_summary = reachability.as_ref().map(ClassReachability::summary);
+    tracing::debug!(?reachability_summary, "reachability");
+    let analyzers = analyzers.with_reachability(reachability);
     
-    let symbol_table_ref = &symbol_table;
-    let call_graph_ref = &call_graph; // v9.4: 用于 N+1 验证
+    // v9.4: 传入 SymbolTable 和 CallGraph 用于语义分析和 N+1 验证
+    let symbol_ctx = if is_dir { Some(&symbol_table) } else { None };
+    let cg_ctx = if is_dir { Some(&call_graph) } else { None };
 
     // === Phase 2: Deep Analysis (深度扫描) ===
-    // 使用 Mutex 保护共享状态 (rayon 并行安全)
-    let issues: Mutex<Vec<AstIssue>> = Mutex::new(Vec::new());
+    let analyze_span = tracing::debug_span!("analyze").entered();
+    // 热度权重：没有 Controller 入口时无需收集方法范围
+    let hot_index = if is_dir {
+        HotnessIndex::build(&call_graph, &symbol_table, hotness::MAX_DEPTH)
+    } else {
+        HotnessIndex::default()
+    };
 
-    // 并行处理文件
-    entries.par_iter().for_each(|entry| {
-        let file_path = entry.path();
-        let file_name_str = file_path.file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_default();
-        let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    // 有界流水线：IO 线程读取并转码 (GBK 等遗留编码) → 工作线程分析 → 当前线程聚合
+    let mut issues: Vec<AstIssue> = Vec::new();
+    let mut encoding_stats: HashMap<&'static str, (usize, usize)> = HashMap::new();
+    let mut pool_usages: Vec<pool_advisor::FileUsage> = Vec::new();
+    let mut static_inits: Vec<static_init::StaticInit> = Vec::new();
+    let mut method_spans: HashMap<String, Vec<MethodSpan>> = HashMap::new();
+    let mut routes: Vec<slo::Route> = Vec::new();
+    let mut inactive_ranges: HashMap<String, Vec<InactiveRange>> = HashMap::new();
+    let mut suppressed: Vec<AstIssue> = Vec::new();
+    let mut parse_degraded: Vec<DegradedFile> = Vec::new();
+    let mut source_shapes = ShapeStats::default();
+    let mut file_diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut debt_comments: Vec<DebtComment> = Vec::new();
+    let mut complexity_estimates: Vec<MethodEstimate> = Vec::new();
+    let mut loc_index = LocIndex::default();
+    let mut lock_sites: Vec<LockSite> = Vec::new();
 
-        // 本线程的 issues
-        let mut local_issues: Vec<AstIssue> = Vec::new();
+    // 检查点：参数摘要覆盖影响单文件结果的选项 (规则、抑制、classpath 等)，排序/基线等后处理选项不影响
+    let (mut checkpoint, resumed) = match &options.checkpoint {
+        Some(file) => {
+            let rules: Vec<(&str, ScannerSeverity)> = registry.enabled_rules().map(|r| (r.id, r.severity)).collect();
+            let fingerprint = symbol_cache::content_hash(format!(
+                "{rules:?}|{:?}|{:?}|{}|{}|{:?}|{}|{}|{}|{}|{:?}", options.rules, config.rule_overrides, options.include_suppressed,
+                options.safe_mode, options.classpath, slo_config.is_some(), registry.lock_depth(), options.todo_debt,
+                options.complexity, reachability_summary.as_ref().map(|r| &r.entrypoints)
+            ).as_bytes());
+            let worklist = targets.iter().map(|p| roots.relative_path(p)).collect();
+            let (checkpoint, resumed) = Checkpoint::<FileOutcome>::open(file, fingerprint, worklist)?;
+            (Some(checkpoint), Some(resumed))
+        }
+        None => (None, None),
+    };
 
-        if ext == "java" {
-            if let Ok(content) = std::fs::read_to_string(file_path) {
-                // v9.4: 传入 SymbolTable 和 CallGraph 用于语义分析和 N+1 验证
-                let symbol_ctx = if is_dir { Some(symbol_table_ref) } else { None };
-                let cg_ctx = if is_dir { Some(call_graph_ref) } else { None };
+    let read = |file_path: &Path| {
+        if !FileAnalyzers::is_target(file_path) || safe.as_ref().is_some_and(|s| !s.admits_size(file_path)) {
+            return None;
+        }
+        let source = match read_source(file_path) {
+            Ok(source) => source,
+            Err(e) => return Some(PendingFile::Unreadable(e.to_string())),
+        };
+        let Some(resumed) = &resumed else { return Some(PendingFile::Source(source, 0)) };
+        let hash = symbol_cache::content_hash(source.content.as_bytes());
+        Some(match resumed.take(&roots.relative_path(file_path), hash) {
+            Some(outcome) => PendingFile::Done(Box::new(outcome)),
+            None => PendingFile::Source(source, hash),
+        })
+    };
+    let analyze = |file_path: &Path, pending: PendingFile| {
+        let (source, content_hash) = match pending {
+            PendingFile::Source(source, hash) => (source, hash),
+            PendingFile::Done(outcome) => return FileOutcome { resumed: true, ..*outcome },
+            PendingFile::Unreadable(error) => {
+                let rel_path = roots.relative_path(file_path);
+                let diagnostic = Diagnostic::new(DiagnosticKind::ParseFailure, format!("无法读取，未分析: {error}")).file(&rel_path);
+                diagnostics::report(diagnostic.clone());
+                return FileOutcome { rel_path, diagnostics: vec![diagnostic], ..Default::default() };
+            }
+        };
+        let rel_path = roots.relative_path(file_path);
+        let is_java = file_path.extension().and_then(|e| e.to_str()) == Some("java");
+        let deep = analyzers.deep_analysis(file_path);
+        let mut outcome = FileOutcome {
+            content_hash,
+            encoding: source.is_transcoded().then_some((source.encoding, source.had_errors)),
+            ..Default::default()
+        };
 
-                if let Ok(ast_results) = java_analyzer.analyze_with_context(&content, file_path, symbol_ctx, cg_ctx) {
-                    local_issues.extend(ast_results.into_iter().map(convert_issue));
-                }
+        // Java 文件只解析一次：规则分析与下面的各项收集共用同一棵树
+        let code = source.content.as_str();
+        let tree = if is_java { analyzers.java().parse(code) } else { None };
+        let ((active, mut local_suppressed, health), mut reported) = diagnostics::capture(|| {
+            analyzers.analyze_file_with_suppressed(file_path, code, tree.as_ref(), symbol_ctx, cg_ctx)
+        });
+        if is_java && health.as_ref().is_some_and(|h| h.status() == ParseStatus::Failed) {
+            reported.push(Diagnostic::new(DiagnosticKind::ParseFailure, "语法错误过多，整个文件未分析"));
+        }
+        for diagnostic in &mut reported {
+            diagnostic.file = Some(rel_path.clone());
+        }
+        outcome.diagnostics = reported;
+        if !options.include_suppressed {
+            local_suppressed.clear();
+        }
+        if is_java {
+            let file_name = file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let shape = health.as_ref().filter(|h| h.status() != ParseStatus::Failed).map(|h| h.shape);
+            outcome.shape = Some((file_name, shape, source.bom));
+        }
+        outcome.parse = health.and_then(|h| DegradedFile::from_health(&rel_path, &h));
+        let issue_path = FilePath::new(&rel_path);
+        outcome.issues = active
+            .into_iter()
+            .map(|issue| locate_issue(issue, &issue_path, &source.content))
+            .collect();
+        outcome.suppressed = local_suppressed.into_iter()
+            .map(|(issue, origin)| {
+                let mut issue = locate_issue(issue, &issue_path, &source.content);
+                issue.suppressed = true;
+                issue.suppression = Some(origin);
+                issue
+            })
+            .collect();
+
+        // 线程池声明与任务提交 (容量建议)
+        if is_java && pool_advisor::is_candidate(code) {
+            let class_name = file_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let is_dao_var = |receiver: &str| symbol_ctx.is_some_and(|t| t.is_dao_var(&class_name, receiver));
+            outcome.pool_usage = tree.as_ref().map(|tree| {
+                pool_advisor::collect(tree.root_node(), code, &rel_path, &is_dao_var)
+            });
+        }
+
+        // 静态初始化依赖 (跨文件成环检测)
+        if static_init_selected && is_java && deep && static_init::is_candidate(code) {
+            outcome.static_inits = tree.as_ref().map(|tree| {
+                static_init::collect(tree.root_node(), code, &rel_path)
+            }).unwrap_or_default();
+        }
+
+        // 方法行范围 (问题 → 所在方法 → 入口距离；TODO 注释的归属)
+        let debt_candidate = options.todo_debt && is_java && todo_debt::is_candidate(code);
+        if (!outcome.issues.is_empty() || debt_candidate) && is_java {
+            outcome.method_spans = tree.as_ref().map(|tree| {
+                hotness::collect_spans(tree.root_node(), code)
+            });
+        }
+
+        // 性能相关的 TODO 注释
+        if debt_candidate {
+            let spans = outcome.method_spans.as_deref().unwrap_or_default();
+            outcome.debt_comments = tree.as_ref().map(|tree| {
+                todo_debt::collect(tree.root_node(), code, &rel_path, spans)
+            }).unwrap_or_default();
+        }
+
+        // 方法复杂度估算
+        if options.complexity && is_java {
+            outcome.complexity = tree.as_ref().map(|tree| {
+                complexity::collect(tree.root_node(), code, &rel_path)
+            }).unwrap_or_default();
+        }
+
+        // 持锁区域 (锁竞争风险排名)
+        if is_java && contention::is_candidate(code) {
+            outcome.lock_sites = tree.as_ref().map(|tree| {
+                contention::collect(tree.root_node(), code, &rel_path)
+            }).unwrap_or_default();
+        }
+
+        // HTTP 映射 (延迟预算的 endpoint 入口)
+        if slo_config.is_some() && is_java && slo::is_candidate(code) {
+            outcome.routes = tree.as_ref().map(|tree| {
+                slo::collect_routes(tree.root_node(), code)
+            }).unwrap_or_default();
+        }
+
+        // 常量条件的不可达分支 (问题降级)
+        if !outcome.issues.is_empty() && is_java && dead_code::is_candidate(code) {
+            outcome.inactive_ranges = tree.as_ref().map(|tree| {
+                let local = dead_code::collect_constants(tree.root_node(), code);
+                let lookup = |class: &str, reference: &str| {
+                    dead_code::lookup_in(&local, class, reference)
+                        .or_else(|| symbol_ctx.filter(|_| deep).and_then(|t| t.resolve_bool_constant(class, reference)))
+                };
+                dead_code::inactive_ranges(tree.root_node(), code, &lookup)
+            }).unwrap_or_default();
+        }
+
+        outcome.loc = Some(FileLoc {
+            loc: density::count_loc(file_path, &source.content),
+            package: density::package_of(&rel_path, &source.content),
+        });
+        outcome.rel_path = rel_path;
+        outcome
+    };
+
+    pipeline::run(&targets, PipelineOptions::with_workers(parallelism.jobs), read, analyze, |outcome| {
+        if let Some(writer) = checkpoint.as_mut().filter(|_| !outcome.resumed) {
+            if let Err(e) = writer.record(&outcome) {
+                diagnostics::warn(format!("写入检查点失败，后续不再记录: {e}"));
+                checkpoint = None;
             }
-        } else if ["yml", "yaml", "properties"].contains(&ext) {
-            if let Ok(content) = std::fs::read_to_string(file_path) {
-                // 3. Config Analysis
-                if let Some(analyzer) = &config_analyzer {
-                    // v9.5: 优先使用结构化 YAML 解析
-                    if ["yml", "yaml"].contains(&ext) {
-                        let structured_issues = analyzer.analyze_yaml_structured(&content, &file_name_str);
-                        if !structured_issues.is_empty() {
-                            local_issues.extend(structured_issues.into_iter().map(convert_issue));
-                        } else {
-                            // 备用：行匹配
-                            if let Ok(config_results) = analyzer.analyze(&content, file_path) {
-                                local_issues.extend(config_results.into_iter().map(convert_issue));
-                            }
-                        }
-                    } else {
-                        // properties 文件继续使用行匹配
-                        if let Ok(config_results) = analyzer.analyze(&content, file_path) {
-                            local_issues.extend(config_results.into_iter().map(convert_issue));
-                        }
-                    }
-                }
+        }
+        if let Some((encoding, had_errors)) = outcome.encoding {
+            let entry = encoding_stats.entry(encoding).or_insert((0, 0));
+            entry.0 += 1;
+            if had_errors {
+                entry.1 += 1;
             }
-        } else if file_name_str == "Dockerfile" || file_name_str.starts_with("Dockerfile.") {
-            if let Ok(content) = std::fs::read_to_string(file_path) {
-                // 4. Dockerfile Analysis (v5.1 NEW)
-                if let Some(analyzer) = &docker_analyzer {
-                    if let Ok(docker_results) = analyzer.analyze(&content, file_path) {
-                        local_issues.extend(docker_results.into_iter().map(convert_issue));
-                    }
-                }
+        }
+        pool_usages.extend(outcome.pool_usage);
+        static_inits.extend(outcome.static_inits);
+        parse_degraded.extend(outcome.parse);
+        if let Some((file_name, shape, bom)) = &outcome.shape {
+            source_shapes.record(file_name, *shape, *bom);
+        }
+        if let Some(spans) = outcome.method_spans {
+            method_spans.insert(outcome.rel_path.clone(), spans);
+        }
+        routes.extend(outcome.routes);
+        file_diagnostics.extend(outcome.diagnostics);
+        debt_comments.extend(outcome.debt_comments);
+        complexity_estimates.extend(outcome.complexity);
+        lock_sites.extend(outcome.lock_sites);
+        if let Some(loc) = outcome.loc {
+            loc_index.record(outcome.rel_path.clone(), loc);
+        }
+        if !outcome.inactive_ranges.is_empty() {
+            inactive_ranges.insert(outcome.rel_path, outcome.inactive_ranges);
+        }
+        issues.extend(outcome.issues);
+        suppressed.extend(outcome.suppressed);
+    });
+
+    // 未满一批的结果也落盘：后处理失败 (如 --strict-parse) 时再次扫描无需重新分析
+    if let Some(Err(e)) = checkpoint.as_mut().map(Checkpoint::flush) {
+        diagnostics::warn(format!("写入检查点失败: {e}"));
+    }
+    drop(analyze_span);
+    let analyze_ms = elapsed_ms(&mut lap);
+    let rule_timings = if options.rule_timings {
+        let mut timings = analyzers.java().rule_timings();
+        timings.retain(|timing| timing.cost.files > 0);
+        if is_dir {
+            timing_profile.record(&timings);
+            if let Err(e) = timing_profile.save(path) {
+                diagnostics::warn(format!("写入规则耗时记录失败: {e}"));
             }
         }
+        timings.sort_by_key(|timing| std::cmp::Reverse(timing.cost.total_us));
+        timings
+    } else {
+        Vec::new()
+    };
+
+    // 跨文件后处理：静态初始化成环、构建依赖、热度、不可达分支、合并
+    let post_span = tracing::debug_span!("post_process").entered();
+    let dependency_issues = if dependency_selected { dependency_audit::analyze(path, &build_scope, &import_indices) } else { Vec::new() };
+    issues.extend(static_init::analyze(static_inits)
+        .into_iter()
+        .chain(dependency_issues)
+        .filter(|issue| registry.selects(&issue.issue_type))
+        .filter(|issue| !exclusions.excluded_for(issue.path.as_str()).contains(&issue.issue_type.as_str())));
+    // 框架版本相关说明 (如 Spring Boot 3 已移除的配置项)
+    for issue in &mut issues {
+        if let Some(note) = registry.version_note(&issue.issue_type) {
+            issue.description = format!("{}；{note}", issue.description);
+        }
+    }
+    if let Some(scope) = &entry_scope {
+        scope.retain(&mut issues, &method_spans, path);
+        scope.retain(&mut suppressed, &method_spans, path);
+    }
+    hotness::annotate(&mut issues, &method_spans, &hot_index);
+    let budget_index = slo_config.as_ref()
+        .map(|config| BudgetIndex::build(config, &routes, &call_graph, &symbol_table, hotness::MAX_DEPTH));
+    if let Some(index) = &budget_index {
+        slo::annotate(&mut issues, &method_spans, index);
+    }
+    if let Some(report) = &coverage_report {
+        coverage::annotate(&mut issues, &method_spans, report);
+    }
+    method_rollup::annotate(&mut issues, &method_spans);
+    let mapped_sql = sql_mapping::annotate(&mut issues, &symbol_table, &method_spans, path, &entries);
+    tracing::debug!(mapped_sql, "mapped N+1 calls to SQL");
+    dead_code::mark(&mut issues, &inactive_ranges);
+    // 并行扫描顺序不确定，排序保证输出稳定
+    issues.sort_by(|a, b| {
+        (&a.path, a.line, a.column, &a.issue_type).cmp(&(&b.path, b.line, b.column, &b.issue_type))
+    });
+    let mut issues = dedup::dedup(issues, options.dedup);
+    // 按比例灰度启用的规则只报告指纹落在抽样范围内的问题
+    let sampled = sampling.apply(&mut issues);
+    suppressed.sort_by(|a, b| {
+        (&a.path, a.line, a.column, &a.issue_type).cmp(&(&b.path, b.line, b.column, &b.issue_type))
+    });
+    if let Some(owners) = CodeOwners::load(path) {
+        owners.annotate(&mut issues);
+        owners.annotate(&mut suppressed);
+    }
+    if let Some(years) = options.blame_age.filter(|_| is_dir) {
+        let downgraded = blame_age::annotate(&mut issues, path, years);
+        tracing::debug!(downgraded, "blame age applied");
+    }
+    let mut baselined = 0;
+    if (options.baseline || !options.states.is_empty()) && is_dir {
+        // 安全模式下基线服务器地址来自不可信的配置，且需要执行 curl，只读本地基线
+        let store = BaselineStore::resolve(path, config.baseline.as_ref().filter(|_| safe.is_none()));
+        match store.load()? {
+            Some(baseline) if options.baseline => baselined = baseline.apply(&mut issues),
+            Some(baseline) => baseline.annotate(&mut issues),
+            None => diagnostics::warn(format!("尚未建立基线 ({})，请先执行 baseline update", store.describe())),
+        }
+    }
+    if !options.states.is_empty() {
+        issues.retain(|issue| options.states.contains(&baseline::state_of(issue)));
+    }
+    // 覆盖率统计在 --only-uncovered 过滤前计算
+    let coverage = options.jacoco.as_deref()
+        .filter(|_| coverage_report.is_some())
+        .map(|file| coverage::summarize(file, &issues));
+    if options.only_uncovered {
+        issues.retain(|issue| issue.coverage == Some(Coverage::Uncovered));
+    }
+    todo_debt::annotate(&mut issues, &mut debt_comments);
+    let complexity = complexity::summarize(complexity_estimates, &call_graph);
+    let contention = contention::rank(lock_sites, &call_graph, &symbol_table, &hot_index);
+    todo_debt::sort(&mut debt_comments);
+    parse_degraded.sort_by(|a, b| a.path.cmp(&b.path));
+    if options.strict_parse && !parse_degraded.is_empty() {
+        let files: Vec<String> = parse_degraded.iter()
+            .map(|f| format!("{}:{}", f.path, f.first_error_line))
+            .collect();
+        return Err(format!("--strict-parse: {} 个文件存在语法错误: {}", files.len(), files.join(", ")).into());
+    }
+    let budgets = budget_index.map(|index| slo::summarize(&index, &issues)).unwrap_or_default();
+    tracing::debug!(issues = issues.len(), "post-processed issues");
+    drop(post_span);
+    let post_ms = elapsed_ms(&mut lap);
+
+    let cpus = parallelism.cpus();
+    let pool_config = pool_advisor::read_config(path, &entries);
+    let pool_advice = pool_advisor::advise(pool_usages, &pool_config, cpus);
+    let mut findings: BTreeMap<String, usize> = BTreeMap::new();
+    for issue in &issues {
+        *findings.entry(issue.issue_type.clone()).or_default() += 1;
+    }
+    let container = jdk_engine::read_container_limits(path, &entries);
+    let jdk = stack.as_ref().and_then(DetectedStack::jdk_major);
+    let jvm_tuning = JvmTuning { advice: jdk_engine::recommend_flags(&findings, jdk, &container, cpus), jdk, container };
+
+    let mut scan = ProjectScan {
+        file_count,
+        issues,
+        encoding_stats,
+        adjustments: registry.adjustments().to_vec(),
+        pool_advice,
+        cpus,
+        severity_map: config.severity_map,
+        suppressed,
+        baselined,
+        parse_degraded,
+        source_shapes,
+        loc: loc_index,
+    };
+    scan.sort_issues(options.sort);
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.finish();
+    }
+    let mut diagnostics = diagnostics_scope.finish();
+    diagnostics.extend(file_diagnostics);
+    diagnostics.extend(crate::scanner::grammar::mismatch());
+    diagnostics::normalize(&mut diagnostics);
+    Ok(AnalysisReport {
+        path: code_path.to_string(),
+        stack,
+        rules: registry.stats(),
+        symbols: symbol_table.stats(),
+        call_graph: call_graph.stats(),
+        classpath,
+        entry: entry_scope.zip(options.entry.as_ref()).map(|(scope, entry)| scope.summary(entry)),
+        reachability: reachability_summary,
+        extra_roots: roots.extra_labels(),
+        report_root: report_dir.map(|dir| dir.display().to_string()),
+        safe_mode: safe.map(|s| s.summary(|file| roots.relative_path(file))),
+        budgets,
+        coverage,
+        diagnostics,
+        jvm_tuning,
+        todo_debt: debt_comments,
+        sampling: sampled,
+        complexity,
+        contention,
+        rule_timings,
+        parallelism,
+        timing: Timing {
+            collect_ms,
+            index_ms,
+            analyze_ms,
+            post_ms,
+            total_ms: started.elapsed().as_millis() as u64,
+        },
+        scan,
+    })
+}
+
+/// 距上次计时的毫秒数，并重置起点
+fn elapsed_ms(since: &mut Instant) -> u64 {
+    let ms = since.elapsed().as_millis() as u64;
+    *since = Instant::now();
+    ms
+}
+
+/// 全项目雷达扫描 - Markdown 报告
+///
+/// compact: true 时只返回 P0，每个 issue 只有 id/file/line
+/// max_p1: compact=false 时最多返回的 P1 数量
+/// options: 排序 (hotness 时优先展示请求路径上的问题) 与重叠问题合并策略
+#[allow(dead_code)]
+pub fn radar_scan(code_path: &str, compact: bool, max_p1: usize, options: &ScanOptions) -> Result<Value, Box<dyn std::error::Error>> {
+    analyze_project(code_path, options).map(|report| render_report(&report, compact, max_p1, options))
+}
+
+/// 全项目雷达扫描 - 结构化 JSON (包含全部问题，供 verify 等工具消费)
+#[allow(dead_code)]
+pub fn radar_scan_json(code_path: &str, options: &ScanOptions) -> Result<Value, Box<dyn std::error::Error>> {
+    analyze_project(code_path, options).map(|report| render_json(&report, options))
+}
+
+/// 渲染 Markdown 报告 (见 [`radar_scan`])
+pub fn render_report(report: &AnalysisReport, compact: bool, max_p1: usize, options: &ScanOptions) -> Value {
+    let scan = &report.scan;
+    let mut markdown = render_markdown(scan, compact, max_p1);
+    if options.group_by == Some(GroupBy::Owner) {
+        let (p0_label, p1_label) = (scan.severity_map.label(Severity::P0), scan.severity_map.label(Severity::P1));
+        markdown.push_str(&codeowners::render_section(&codeowners::group_by_owner(&scan.issues), p0_label, p1_label));
+    }
+    if !compact {
+        let (p0_label, p1_label) = (scan.severity_map.label(Severity::P0), scan.severity_map.label(Severity::P1));
+        let worst = method_rollup::worst_methods(&scan.issues, method_rollup::TOP_METHODS);
+        markdown.push_str(&method_rollup::render_section(&worst, p0_label, p1_label));
+        markdown.push_str(&density::render_section(&density::by_package(&scan.loc, &scan.issues), p0_label, p1_label));
+        markdown.push_str(&slo::render_section(&report.budgets, p0_label, p1_label));
+        markdown.push_str(&contention::render_section(&report.contention));
+    }
+    if let Some(summary) = &report.coverage {
+        markdown.push_str(&coverage::render_section(summary, &scan.issues, scan.severity_map.label(Severity::P0)));
+    }
+    if scan.baselined > 0 {
+        markdown.push_str(&format!("\n*（{} 个基线内的已知问题已隐藏）*\n", scan.baselined));
+    }
+    if let Some(entry) = &report.entry {
+        markdown.push_str(&format!(
+            "\n*（入口范围: `{}`，可达 {} 个方法 / {} 个文件，范围外的问题未报告）*\n", entry.entry, entry.methods, entry.files
+        ));
+    }
+    if let Some(reach) = &report.reachability {
+        markdown.push_str(&format!(
+            "\n*（可达性裁剪: 入口 {} 可达 {}/{} 个类，深度分析只在其中 {} 个文件上进行，`--exhaustive` 分析全部文件）*\n",
+            reach.entrypoints.join(", "), reach.classes, reach.total_classes, reach.files
+        ));
+    }
+    if let Some(cp) = &report.classpath {
+        markdown.push_str(&format!(
+            "\n*（classpath: {} 个类，确认 {} 个字段的第三方类型）*\n", cp.classes, cp.resolved_fields
+        ));
+    }
+    if !report.extra_roots.is_empty() {
+        let scope = report.report_root.as_ref()
+            .map_or_else(String::new, |root| format!("，只报告 `{root}` 下的问题"));
+        markdown.push_str(&format!("\n*（附加源码根: {}{scope}）*\n", report.extra_roots.join(", ")));
+    }
+    if let Some(summary) = &report.safe_mode {
+        markdown.push_str(&safe_mode::render_line(summary));
+    }
+    markdown.push_str(&sampling::render_line(&report.sampling));
+    markdown.push_str(&diagnostics::render_section(&report.diagnostics));
+    if !compact {
+        markdown.push_str(&todo_debt::render_section(&report.todo_debt));
+        markdown.push_str(&jdk_engine::render_appendix(&report.jvm_tuning));
+    }
+    markdown.push_str(&format!("\n*（规则包 `{}`，与其它版本的报告比对前可用 `rules changelog --from <规则包>` 查看规则变化）*\n", report.rules.pack));
+    json!(markdown)
+}
 
-        // 合并到全局 issues
-        if !local_issues.is_empty() {
-            // 使用 unwrap_or_else 处理 poisoned mutex（如果持锁线程 panic）
-            let mut global = issues.lock().unwrap_or_else(|e| e.into_inner());
-            global.extend(local_issues);
+/// 渲染结构化 JSON (见 [`radar_scan_json`])
+pub fn render_json(analysis: &AnalysisReport, options: &ScanOptions) -> Value {
+    let scan = &analysis.scan;
+    let encodings: HashMap<&str, usize> = scan.encoding_stats.iter()
+        .map(|(enc, (count, _))| (*enc, *count))
+        .collect();
+
+    let mut issues = json!(scan.issues);
+    if !scan.severity_map.is_empty() {
+        // 内部仍以 P0/P1 判定 (verify 依赖)，映射名称单独输出
+        for (value, issue) in issues.as_array_mut().into_iter().flatten().zip(&scan.issues) {
+            value["severity_label"] = json!(scan.severity_map.label(issue.severity));
         }
+    }
+
+    let mut report = json!({
+        "path": analysis.path,
+        "files": scan.file_count,
+        "p0": scan.p0_count(),
+        "p1": scan.p1_count(),
+        "loc": scan.loc.total(),
+        "issues_per_kloc": density::per_kloc(scan.issues.len(), scan.loc.total()),
+        "stack": analysis.stack,
+        "stats": {
+            "rules": analysis.rules,
+            "symbols": analysis.symbols,
+            "call_graph": analysis.call_graph,
+            "timing": analysis.timing,
+            "parallelism": analysis.parallelism,
+            "parse_degraded": scan.parse_degraded,
+            "source_shapes": scan.source_shapes,
+            "classpath": analysis.classpath,
+        },
+        "encodings": encodings,
+        "stack_adjustments": scan.adjustments,
+        "thread_pools": scan.pool_advice,
+        "worst_methods": method_rollup::worst_methods(&scan.issues, method_rollup::TOP_METHODS),
+        "categories": category_report::summarize(&scan.issues, scan.loc.total()),
+        "packages": density::by_package(&scan.loc, &scan.issues),
+        "issues": issues,
     });
+    if options.include_suppressed {
+        // 单独列出，避免影响 issues 的计数与 verify
+        report["suppressed"] = json!(scan.suppressed);
+    }
+    if options.group_by == Some(GroupBy::Owner) {
+        report["by_owner"] = json!(codeowners::group_by_owner(&scan.issues));
+    }
+    if options.baseline {
+        report["baselined"] = json!(scan.baselined);
+    }
+    if let Some(entry) = &analysis.entry {
+        report["entry"] = json!(entry);
+    }
+    if let Some(reachability) = &analysis.reachability {
+        report["reachability"] = json!(reachability);
+    }
+    if !analysis.extra_roots.is_empty() {
+        report["extra_roots"] = json!(analysis.extra_roots);
+    }
+    if let Some(root) = &analysis.report_root {
+        report["report_root"] = json!(root);
+    }
+    if let Some(summary) = &analysis.safe_mode {
+        report["safe_mode"] = json!(summary);
+    }
+    if !analysis.budgets.is_empty() {
+        report["budgets"] = json!(analysis.budgets);
+    }
+    if let Some(summary) = &analysis.coverage {
+        report["coverage"] = json!(summary);
+    }
+    if !analysis.diagnostics.is_empty() {
+        report["diagnostics"] = json!(analysis.diagnostics);
+    }
+    if !analysis.jvm_tuning.is_empty() {
+        report["jvm_tuning"] = json!(analysis.jvm_tuning);
+    }
+    if !analysis.todo_debt.is_empty() {
+        report["todo_debt"] = json!(analysis.todo_debt);
+    }
+    if !analysis.sampling.is_empty() {
+        report["sampling"] = json!(analysis.sampling);
+    }
+    if !analysis.complexity.is_empty() {
+        report["complexity"] = json!(analysis.complexity);
+    }
+    if !analysis.contention.is_empty() {
+        report["contention"] = json!(analysis.contention);
+    }
+    if !analysis.rule_timings.is_empty() {
+        report["rule_timings"] = json!(analysis.rule_timings);
+    }
+    report
+}
 
-    // 安全地解包：如果 mutex 被 poisoned，仍然获取内部数据
-    let issues = issues.into_inner().unwrap_or_else(|e| e.into_inner());
-    let p0_count = issues.iter().filter(|i| matches!(i.severity, Severity::P0)).count();
-    let p1_count = issues.iter().filter(|i| matches!(i.severity, Severity::P1)).count();
+/// 渲染 Markdown 报告
+fn render_markdown(scan: &ProjectScan, compact: bool, max_p1: usize) -> String {
+    let issues = &scan.issues;
+    let file_count = scan.file_count;
+    let p0_count = scan.p0_count();
+    let p1_count = scan.p1_count();
+    let encoding_line = format_encoding_stats(&scan.encoding_stats);
+    let parse_line = format_parse_degraded(&scan.parse_degraded);
+    let p0_label = scan.severity_map.label(Severity::P0);
+    let p1_label = scan.severity_map.label(Severity::P1);
 
     // === 根据 compact 模式生成不同报告 ===
     if compact {
         // 紧凑模式：只返回 P0，精简格式
         let mut report = format!(
-            "## 🛰️ 雷达扫描 (v9.1 AST 引擎)\n\n**P0**: {p0_count} | **P1**: {p1_count} | **文件**: {file_count}\n\n"
+            "## 🛰️ 雷达扫描 (v9.1 AST 引擎)\n\n**{p0_label}**: {p0_count} | **{p1_label}**: {p1_count} | **文件**: {file_count}\n\n"
         );
+        report.push_str(&encoding_line);
+        report.push_str(&parse_line);
 
         if p0_count > 0 {
             for issue in issues.iter().filter(|i| matches!(i.severity, Severity::P0)) {
                 report.push_str(&format!(
-                    "- `{}` {}:{}\n",
-                    issue.issue_type, issue.file, issue.line
+                    "- `{}` {}:{}{}\n",
+                    issue.issue_type, issue.file, issue.line, format_triage(issue)
                 ));
             }
         } else {
-            report.push_str("✅ 无 P0 问题\n");
+            report.push_str(&format!("✅ 无 {p0_label} 问题\n"));
         }
 
         if p1_count > 0 {
-            report.push_str(&format!("\n*（{p1_count} 个 P1 警告已省略，使用 compact=false 查看）*\n"));
+            report.push_str(&format!("\n*（{p1_count} 个 {p1_label} 警告已省略，使用 compact=false 查看）*\n"));
         }
+        report.push_str(&format_suppressed(&scan.suppressed));
 
-        Ok(json!(report))
+        report
     } else {
         // 完整模式
         let mut report = format!(
             "## 🛰️ 雷达扫描结果 (v9.1 AST 引擎)\n\n\
             **扫描**: {} 个文件\n\
-            **发现**: {} 个嫌疑点 (P0: {}, P1: {})\n\n",
-            file_count, issues.len(), p0_count, p1_count
+            **发现**: {} 个嫌疑点 ({p0_label}: {}, {p1_label}: {})\n\
+            **密度**: {:.2} 个/KLoC ({} 行代码)\n\n",
+            file_count, issues.len(), p0_count, p1_count, density::per_kloc(issues.len(), scan.loc.total()), scan.loc.total()
         );
+        report.push_str(&encoding_line);
+        report.push_str(&parse_line);
+        report.push_str(&scan.source_shapes.render());
+        let categories = category_report::summarize(issues, scan.loc.total());
+        report.push_str(&category_report::render_index(&categories, p0_label, p1_label));
 
-        if p0_count > 0 {
-            report.push_str("### 🔴 P0 严重嫌疑\n\n");
-            for issue in issues.iter().filter(|i| matches!(i.severity, Severity::P0)) {
-                report.push_str(&format!(
-                    "- **{}** - `{}:{}` - {}\n",
-                    issue.issue_type, issue.file, issue.line, issue.description
-                ));
+        if !scan.adjustments.is_empty() {
+            report.push_str("### ⚙️ 技术栈规则调整\n\n");
+            for adj in &scan.adjustments {
+                report.push_str(&format!("- `{}` {} - {}\n", adj.rule_id, adj.change, adj.reason));
             }
             report.push('\n');
         }
 
-        if p1_count > 0 {
-            report.push_str(&format!("### 🟡 P1 警告 (显示前 {max_p1})\n\n"));
-            for issue in issues.iter().filter(|i| matches!(i.severity, Severity::P1)).take(max_p1) {
+        report.push_str(&pool_advisor::render_section(&scan.pool_advice, scan.cpus));
+
+        // 按类别分章节：P0 全部显示，P1 按排序顺序只显示前 max_p1 个
+        let mut p1_seen = 0;
+        let shown: Vec<bool> = issues.iter()
+            .map(|issue| match issue.severity {
+                Severity::P0 => true,
+                Severity::P1 => {
+                    p1_seen += 1;
+                    p1_seen <= max_p1
+                }
+            })
+            .collect();
+        for category in &categories {
+            report.push_str(&category_report::render_header(category, p0_label, p1_label));
+            let listed = issues.iter().zip(&shown)
+                .filter(|(issue, shown)| **shown && category_report::category_name(issue) == category.category);
+            for (issue, _) in listed {
+                let marker = match issue.severity {
+                    Severity::P0 => "🔴",
+                    Severity::P1 => "🟡",
+                };
                 report.push_str(&format!(
-                    "- **{}** - `{}:{}` - {}\n",
-                    issue.issue_type, issue.file, issue.line, issue.description
+                    "- {marker} **{}**{} - `{}:{}` - {}{}{}{}{}{}{}{}\n{}",
+                    issue.issue_type, format_secondary(issue), issue.file, issue.line, issue.description,
+                    format_hotness(issue), slo::format_budget(issue), coverage::format_coverage(issue), format_inactive(issue),
+                    blame_age::format_age(issue), todo_debt::format_debt(issue), format_triage(issue),
+                    sql_mapping::format_sql(issue)
                 ));
             }
+            report.push('\n');
+        }
+        if p1_count > max_p1 {
+            report.push_str(&format!("*（{p1_label} 警告只列出前 {max_p1} 个，共 {p1_count} 个）*\n"));
         }
+        report.push_str(&format_suppressed(&scan.suppressed));
+
+        report
+    }
+}
+
+/// 热路径标记 (不可达时为空)
+fn format_hotness(issue: &AstIssue) -> String {
+    if issue.hotness == 0 {
+        String::new()
+    } else {
+        format!(" 🔥{}", issue.hotness)
+    }
+}
 
-        Ok(json!(report))
+/// 基线处理状态标记
+fn format_triage(issue: &AstIssue) -> String {
+    issue.triage.as_ref().map(baseline::format_triage).unwrap_or_default()
+}
+
+/// 不可达分支标记
+fn format_inactive(issue: &AstIssue) -> &'static str {
+    if issue.inactive { " 💤不可达分支" } else { "" }
+}
+
+/// 被抑制问题的按规则统计 (未收集时为空)
+fn format_suppressed(suppressed: &[AstIssue]) -> String {
+    if suppressed.is_empty() {
+        return String::new();
+    }
+    let mut by_rule: BTreeMap<&str, usize> = BTreeMap::new();
+    for issue in suppressed {
+        *by_rule.entry(issue.issue_type.as_str()).or_default() += 1;
+    }
+    let parts: Vec<String> = by_rule.iter().map(|(id, count)| format!("{id} × {count}")).collect();
+    format!("\n*（{} 个问题被抑制: {}，使用 --json 查看抑制来源）*\n", suppressed.len(), parts.join(", "))
+}
+
+/// 合并进来的其它规则 (无合并时为空)
+fn format_secondary(issue: &AstIssue) -> String {
+    if issue.secondary.is_empty() {
+        String::new()
+    } else {
+        format!(" (+{})", issue.secondary.join(", "))
+    }
+}
+
+/// 格式化非 UTF-8 编码统计 (无转码文件时返回空串)
+fn format_encoding_stats(stats: &HashMap<&'static str, (usize, usize)>) -> String {
+    if stats.is_empty() {
+        return String::new();
+    }
+    let mut entries: Vec<_> = stats.iter().collect();
+    entries.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(a.0.cmp(b.0)));
+    let parts: Vec<String> = entries.iter()
+        .map(|(enc, (count, lossy))| if *lossy > 0 {
+            format!("{enc} × {count} ({lossy} 个含无法映射字节)")
+        } else {
+            format!("{enc} × {count}")
+        })
+        .collect();
+    format!("**编码**: {} (已转码为 UTF-8 后分析)\n\n", parts.join(", "))
+}
+
+/// 解析降级文件列表 (无降级文件时返回空串)
+fn format_parse_degraded(files: &[DegradedFile]) -> String {
+    const SHOWN: usize = 5;
+    if files.is_empty() {
+        return String::new();
+    }
+    let mut parts: Vec<String> = files.iter().take(SHOWN)
+        .map(|f| match f.status {
+            ParseStatus::Failed => format!("`{}` (解析失败，未分析)", f.path),
+            _ => format!("`{}:{}` ({} 处语法错误)", f.path, f.first_error_line, f.error_nodes),
+        })
+        .collect();
+    if files.len() > SHOWN {
+        parts.push(format!("等 {} 个", files.len()));
     }
+    format!("**解析降级**: {} (语法错误区域内不报告问题)\n\n", parts.join(", "))
 }
 
 /// 单文件扫描 (v9.1: 仅使用 Tree-sitter AST 分析)
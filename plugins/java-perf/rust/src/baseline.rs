@@ -0,0 +1,724 @@
+//! 问题基线 (`.javaperf/baseline.json`)
+//!
+//! 基线记录已知问题的指纹 (位置无关，见 scanner::issue_builder)，`scan --baseline`
+//! 只报告基线之外的新问题。`baseline update` 以当前扫描结果重写基线。
+//!
+//! 每个条目带处理状态 ([`IssueState`])、负责人与备注，由 `baseline set` 维护:
+//!
+//! ```text
+//! java-perf baseline set 6fb4f993d413b3ac --state acknowledged --assignee bob --notes "下个迭代改批量查询"
+//! ```
+//!
+//! `baseline update` 保留仍存在的问题的状态；已处理过 (非 new 或有负责人/备注) 但不再出现的
+//! 问题标记为 fixed 并保留，fixed 的问题再次出现时重新变为 new (回归)。
+//! `scan --state acknowledged,new` 按状态筛选问题，报告中每个问题附带其状态与负责人。
+//!
+//! 在 `.javaperf.toml` 中配置团队服务器后，多个仓库/服务 (如拆分后的 monorepo)
+//! 共享同一份基线:
+//!
+//! ```toml
+//! [baseline]
+//! server = "https://javaperf.example.com"
+//! project = "order-service"
+//! ```
+//!
+//! 协议为简单 REST: `GET/PUT {server}/v1/baselines/{project}`，请求体与本地文件格式相同；
+//! token 取自环境变量 `JAVAPERF_BASELINE_TOKEN` (Bearer，不写入配置文件)。被扫描仓库的
+//! `.javaperf.toml` 不可信，token 只发给以下服务器，否则不带 token 请求并给出警告:
+//! - server 来自 `JAVAPERF_BASELINE_SERVER` 或 `-D baseline.server=...`，或列在
+//!   `JAVAPERF_BASELINE_TRUSTED_SERVERS` (逗号分隔的服务器地址) 中
+//! - 且使用 https (本机回环地址允许 http)
+//!
+//! HTTP 请求交给系统 curl 执行 (与 --git 调用系统 git 一致，TLS/代理沿用系统配置)。
+//! 每次成功拉取/推送都会同步本地文件；服务器不可达时回退到本地文件。
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ast_engine::{self, AstIssue, ScanOptions};
+use crate::diagnostics;
+use crate::project_config::{self, BaselineServer};
+use crate::symbol_cache::CACHE_DIR;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 本地基线文件名 (位于 .javaperf/ 下)
+pub const BASELINE_FILE: &str = "baseline.json";
+
+/// 服务器 token 的环境变量
+pub const TOKEN_ENV: &str = "JAVAPERF_BASELINE_TOKEN";
+
+/// 允许发送 token 的服务器地址 (逗号分隔)，用于 `.javaperf.toml` 中配置的 server
+pub const TRUSTED_SERVERS_ENV: &str = "JAVAPERF_BASELINE_TRUSTED_SERVERS";
+
+/// 基线格式版本 (v2 起条目带处理状态；读取 v1 时状态为 new)
+pub const FORMAT_VERSION: u32 = 2;
+
+/// 请求超时 (秒)
+const HTTP_TIMEOUT_SECS: &str = "30";
+
+/// 基线
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    /// 指纹 → 记录时的问题位置 (仅供查阅，匹配只看指纹)
+    pub entries: BTreeMap<String, BaselineEntry>,
+}
+
+/// 基线条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub id: String,
+    pub path: String,
+    pub line: usize,
+    #[serde(flatten)]
+    pub triage: Triage,
+}
+
+/// 问题处理状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum IssueState {
+    /// 未处理 (基线之外的问题同样视为 new)
+    #[default]
+    New,
+    /// 已确认，待修复
+    Acknowledged,
+    /// 不修复 (已评估可接受)
+    WontFix,
+    /// 已修复 (baseline update 时不再出现)
+    Fixed,
+}
+
+impl IssueState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Acknowledged => "acknowledged",
+            Self::WontFix => "wont-fix",
+            Self::Fixed => "fixed",
+        }
+    }
+}
+
+/// 问题的处理信息 (基线条目中保存，扫描时附加到问题上)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Triage {
+    #[serde(default)]
+    pub state: IssueState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl Triage {
+    /// 是否被处理过 (非 new 或有负责人/备注)
+    fn is_triaged(&self) -> bool {
+        self != &Self::default()
+    }
+}
+
+/// 问题的处理状态 (未附加处理信息的问题为 new)
+pub fn state_of(issue: &AstIssue) -> IssueState {
+    issue.triage.as_ref().map_or(IssueState::New, |t| t.state)
+}
+
+impl Baseline {
+    /// 以扫描结果建立基线
+    pub fn from_issues(issues: &[AstIssue]) -> Self {
+        let entries = issues.iter()
+            .map(|issue| (key(issue), BaselineEntry {
+                id: issue.issue_type.clone(),
+                path: issue.path.to_string(),
+                line: issue.line,
+                triage: Triage::default(),
+            }))
+            .collect();
+        Self { version: FORMAT_VERSION, entries }
+    }
+
+    /// 以新的扫描结果更新基线，保留处理信息 (规则见模块文档)
+    pub fn refresh(&self, issues: &[AstIssue]) -> Self {
+        let mut next = Self::from_issues(issues);
+        for (key, entry) in &mut next.entries {
+            if let Some(previous) = self.entries.get(key) {
+                entry.triage = previous.triage.clone();
+                if entry.triage.state == IssueState::Fixed {
+                    entry.triage.state = IssueState::New;
+                }
+            }
+        }
+        for (key, previous) in &self.entries {
+            if !next.entries.contains_key(key) && previous.triage.is_triaged() {
+                let mut entry = previous.clone();
+                entry.triage.state = IssueState::Fixed;
+                next.entries.insert(key.clone(), entry);
+            }
+        }
+        next
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, issue: &AstIssue) -> bool {
+        self.entries.contains_key(&key(issue))
+    }
+
+    /// 移除基线内的问题，返回移除数量；fixed 的问题再次出现 (回归) 时保留并附加处理信息
+    pub fn apply(&self, issues: &mut Vec<AstIssue>) -> usize {
+        let before = issues.len();
+        self.annotate(issues);
+        issues.retain(|issue| issue.triage.as_ref().is_none_or(|t| t.state == IssueState::Fixed));
+        before - issues.len()
+    }
+
+    /// 为基线内的问题附加处理信息
+    pub fn annotate(&self, issues: &mut [AstIssue]) {
+        for issue in issues {
+            issue.triage = self.entries.get(&key(issue)).map(|entry| entry.triage.clone());
+        }
+    }
+
+    /// 修改条目的处理信息 (负责人/备注传空字符串时清除)
+    pub fn set(
+        &mut self,
+        fingerprint: &str,
+        state: Option<IssueState>,
+        assignee: Option<String>,
+        notes: Option<String>,
+    ) -> Result<&BaselineEntry, String> {
+        let entry = self.entries.get_mut(fingerprint).ok_or_else(|| format!("指纹 {fingerprint} 不在基线中"))?;
+        if let Some(state) = state {
+            entry.triage.state = state;
+        }
+        if let Some(assignee) = assignee {
+            entry.triage.assignee = Some(assignee).filter(|a| !a.is_empty());
+        }
+        if let Some(notes) = notes {
+            entry.triage.notes = Some(notes).filter(|n| !n.is_empty());
+        }
+        Ok(entry)
+    }
+
+    fn parse(content: &str, origin: &str) -> Result<Self, BoxError> {
+        let mut baseline: Self = serde_json::from_str(content).map_err(|e| format!("{origin} 格式错误: {e}"))?;
+        if !(1..=FORMAT_VERSION).contains(&baseline.version) {
+            return Err(format!("{origin} 版本 {} 不受支持 (当前 {FORMAT_VERSION})", baseline.version).into());
+        }
+        baseline.version = FORMAT_VERSION;
+        Ok(baseline)
+    }
+}
+
+/// 匹配键: 指纹；个别没有指纹的问题退回 (规则, 路径, 行号)
+fn key(issue: &AstIssue) -> String {
+    if issue.fingerprint.is_empty() {
+        format!("{}:{}:{}", issue.issue_type, issue.path, issue.line)
+    } else {
+        issue.fingerprint.clone()
+    }
+}
+
+/// 基线存储位置
+#[derive(Debug, Clone)]
+pub enum BaselineStore {
+    /// 只使用本地文件
+    Local(PathBuf),
+    /// 团队服务器，本地文件作为副本与回退
+    Remote {
+        url: String,
+        token: Option<String>,
+        local: PathBuf,
+    },
+}
+
+impl BaselineStore {
+    /// 按项目配置选择存储 (未配置 [baseline] 时为本地文件)
+    pub fn resolve(root: &Path, server: Option<&BaselineServer>) -> Self {
+        Self::resolve_with_env(root, server, |name| std::env::var(name).ok())
+    }
+
+    fn resolve_with_env(root: &Path, server: Option<&BaselineServer>, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let local = root.join(CACHE_DIR).join(BASELINE_FILE);
+        match server {
+            Some(server) => Self::Remote {
+                url: format!("{}/v1/baselines/{}", server.server.trim_end_matches('/'), encode_path_segment(&server.project)),
+                token: lookup(TOKEN_ENV).filter(|t| !t.is_empty()).and_then(|token| {
+                    match token_refusal(server, lookup(TRUSTED_SERVERS_ENV).as_deref()) {
+                        Some(reason) => {
+                            diagnostics::warn(format!("{reason}，不发送 {TOKEN_ENV}"));
+                            None
+                        }
+                        None => Some(token),
+                    }
+                }),
+                local,
+            },
+            None => Self::Local(local),
+        }
+    }
+
+    fn local(&self) -> &Path {
+        match self {
+            Self::Local(local) | Self::Remote { local, .. } => local,
+        }
+    }
+
+    /// 读取基线 (尚未建立时返回 None)；服务器不可达时回退到本地副本
+    pub fn load(&self) -> Result<Option<Baseline>, BoxError> {
+        if let Self::Remote { url, token, local } = self {
+            match http(url, "GET", token.as_deref(), None) {
+                Ok((200, body)) => {
+                    let body = String::from_utf8_lossy(&body);
+                    let baseline = Baseline::parse(&body, url)?;
+                    write_local(local, &baseline)?;
+                    return Ok(Some(baseline));
+                }
+                Ok((404, _)) => return Ok(None),
+                Ok((status, _)) => diagnostics::warn(format!("基线服务器返回 HTTP {status}，使用本地基线 {}", local.display())),
+                Err(e) => diagnostics::warn(format!("基线服务器不可达 ({e})，使用本地基线 {}", local.display())),
+            }
+        }
+        read_local(self.local())
+    }
+
+    /// 写入基线 (本地文件；配置服务器时随后推送)
+    pub fn save(&self, baseline: &Baseline) -> Result<(), BoxError> {
+        write_local(self.local(), baseline)?;
+        self.push()
+    }
+
+    /// 把本地基线推送到服务器 (本地存储时无操作)
+    pub fn push(&self) -> Result<(), BoxError> {
+        let Self::Remote { url, token, local } = self else { return Ok(()) };
+        if !local.is_file() {
+            return Err(format!("本地基线 {} 不存在，请先执行 baseline update", local.display()).into());
+        }
+        match http(url, "PUT", token.as_deref(), Some(local))? {
+            (200..=299, _) => Ok(()),
+            (status, body) => Err(format!("推送基线失败: HTTP {status} {}", String::from_utf8_lossy(&body).trim()).into()),
+        }
+    }
+
+    /// 用服务器上的基线覆盖本地副本 (本地存储时只读取)
+    pub fn pull(&self) -> Result<Option<Baseline>, BoxError> {
+        let Self::Remote { url, token, local } = self else { return read_local(self.local()) };
+        match http(url, "GET", token.as_deref(), None)? {
+            (200, body) => {
+                let baseline = Baseline::parse(&String::from_utf8_lossy(&body), url)?;
+                write_local(local, &baseline)?;
+                Ok(Some(baseline))
+            }
+            (404, _) => Ok(None),
+            (status, body) => Err(format!("拉取基线失败: HTTP {status} {}", String::from_utf8_lossy(&body).trim()).into()),
+        }
+    }
+
+    /// 基线所在位置 (用于输出)
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Local(local) => local.display().to_string(),
+            Self::Remote { url, .. } => url.clone(),
+        }
+    }
+}
+
+/// `baseline` 子命令的执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineOutcome {
+    pub action: &'static str,
+    /// 本地文件或服务器地址
+    pub location: String,
+    /// 基线条目数 (服务器上尚无基线时为 None)
+    pub entries: Option<usize>,
+    /// `baseline set` 修改后的条目
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed: Option<(String, BaselineEntry)>,
+}
+
+fn store_for(code_path: &str) -> Result<BaselineStore, BoxError> {
+    let root = Path::new(code_path);
+    let config = project_config::load(root)?;
+    Ok(BaselineStore::resolve(root, config.baseline.as_ref()))
+}
+
+/// `baseline update`: 以当前全量扫描结果重写基线，保留处理信息 (配置服务器时同时推送)
+pub fn update(code_path: &str) -> Result<BaselineOutcome, BoxError> {
+    let store = store_for(code_path)?;
+    let scan = ast_engine::scan_project(code_path, &ScanOptions::default())?;
+    let baseline = match store.load()? {
+        Some(previous) => previous.refresh(&scan.issues),
+        None => Baseline::from_issues(&scan.issues),
+    };
+    store.save(&baseline)?;
+    Ok(BaselineOutcome { action: "update", location: store.describe(), entries: Some(baseline.entries.len()), changed: None })
+}
+
+/// `baseline set`: 修改单个问题的状态/负责人/备注 (配置服务器时同时推送)
+pub fn set(
+    code_path: &str,
+    fingerprint: &str,
+    state: Option<IssueState>,
+    assignee: Option<String>,
+    notes: Option<String>,
+) -> Result<BaselineOutcome, BoxError> {
+    if state.is_none() && assignee.is_none() && notes.is_none() {
+        return Err("请至少指定 --state、--assignee、--notes 之一".into());
+    }
+    let store = store_for(code_path)?;
+    let mut baseline = store.load()?
+        .ok_or_else(|| format!("尚未建立基线 ({})，请先执行 baseline update", store.describe()))?;
+    let entry = baseline.set(fingerprint, state, assignee, notes)?.clone();
+    store.save(&baseline)?;
+    Ok(BaselineOutcome {
+        action: "set",
+        location: store.describe(),
+        entries: Some(baseline.entries.len()),
+        changed: Some((fingerprint.to_string(), entry)),
+    })
+}
+
+/// `baseline pull`: 用服务器上的基线覆盖本地副本
+pub fn pull(code_path: &str) -> Result<BaselineOutcome, BoxError> {
+    let store = store_for(code_path)?;
+    let baseline = store.pull()?;
+    Ok(BaselineOutcome { action: "pull", location: store.describe(), entries: baseline.map(|b| b.entries.len()), changed: None })
+}
+
+/// `baseline push`: 把本地基线推送到服务器
+pub fn push(code_path: &str) -> Result<BaselineOutcome, BoxError> {
+    let store = store_for(code_path)?;
+    if matches!(store, BaselineStore::Local(_)) {
+        return Err("未在 .javaperf.toml 中配置 [baseline] server，无需推送".into());
+    }
+    store.push()?;
+    let entries = read_local(store.local())?.map(|b| b.entries.len());
+    Ok(BaselineOutcome { action: "push", location: store.describe(), entries, changed: None })
+}
+
+pub fn render(outcome: &BaselineOutcome, json_output: bool) -> Value {
+    if json_output {
+        return json!(outcome);
+    }
+    if let Some((fingerprint, entry)) = &outcome.changed {
+        return json!(format!(
+            "✅ baseline set {fingerprint}: {} `{}` {}:{} ({})",
+            format_triage(&entry.triage).trim_start(), entry.id, entry.path, entry.line, outcome.location
+        ));
+    }
+    match outcome.entries {
+        Some(entries) => json!(format!("✅ baseline {}: {} 条 ({})", outcome.action, entries, outcome.location)),
+        None => json!(format!("❓ {} 上尚无基线，请先执行 baseline update", outcome.location)),
+    }
+}
+
+/// 报告中的处理状态标注: ` 📌 acknowledged @bob (备注)`
+pub fn format_triage(triage: &Triage) -> String {
+    let mut text = format!(" 📌 {}", triage.state.as_str());
+    if let Some(assignee) = &triage.assignee {
+        text.push_str(&format!(" @{assignee}"));
+    }
+    if let Some(notes) = &triage.notes {
+        text.push_str(&format!(" ({notes})"));
+    }
+    text
+}
+
+fn read_local(path: &Path) -> Result<Option<Baseline>, BoxError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    Baseline::parse(&content, &path.display().to_string()).map(Some)
+}
+
+/// 不能向该服务器发送 token 的原因 (None 为允许)
+fn token_refusal(server: &BaselineServer, trusted_servers: Option<&str>) -> Option<String> {
+    let address = server.server.trim().trim_end_matches('/');
+    let trusted = server.from_env || trusted_servers.is_some_and(|list| {
+        list.split(',').any(|entry| entry.trim().trim_end_matches('/').eq_ignore_ascii_case(address))
+    });
+    if !trusted {
+        return Some(format!("基线服务器 {address} 来自项目配置文件，且不在 {TRUSTED_SERVERS_ENV} 中"));
+    }
+    let lower = address.to_ascii_lowercase();
+    let secure = lower.starts_with("https://") || lower.strip_prefix("http://").is_some_and(|rest| {
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+        let host = match authority.strip_prefix('[') {
+            Some(ipv6) => ipv6.split(']').next().unwrap_or(""),
+            None => authority.split(':').next().unwrap_or(""),
+        };
+        matches!(host, "localhost" | "127.0.0.1" | "::1")
+    });
+    if !secure {
+        return Some(format!("基线服务器 {address} 未使用 https"));
+    }
+    None
+}
+
+/// 项目名按 URL 路径段做百分号编码 (仅保留 RFC 3986 非保留字符)，`/`、`?`、空格等不会改变请求路径
+fn encode_path_segment(segment: &str) -> String {
+    segment.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+        _ => format!("%{b:02X}"),
+    }).collect()
+}
+
+fn write_local(path: &Path, baseline: &Baseline) -> Result<(), BoxError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(baseline)?)?;
+    Ok(())
+}
+
+/// 通过 curl 发送请求，返回 (状态码, 响应体)
+///
+/// token 经 stdin 以请求头传入 (`-H @-`)，不出现在进程参数中；请求体直接读取本地基线文件。
+/// 地址经 `--url` 传入，以 `-` 开头的 server 不会被 curl 当作选项。
+fn http(url: &str, method: &str, token: Option<&str>, body: Option<&Path>) -> Result<(u16, Vec<u8>), BoxError> {
+    let mut command = Command::new("curl");
+    command.args(["-sS", "-X", method, "--max-time", HTTP_TIMEOUT_SECS, "-w", "\n%{http_code}", "-H", "@-"]);
+    if let Some(body) = body {
+        command.arg("--data-binary").arg(format!("@{}", body.display()));
+    }
+    let mut child = command.arg("--url").arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("无法执行 curl: {e}"))?;
+
+    let mut headers = String::from("Accept: application/json\nContent-Type: application/json\n");
+    if let Some(token) = token {
+        headers.push_str(&format!("Authorization: Bearer {token}\n"));
+    }
+    child.stdin.take().ok_or("curl stdin 不可用")?.write_all(headers.as_bytes())?;
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!("curl {method} {url} 失败: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    let stdout = output.stdout;
+    let split = stdout.iter().rposition(|b| *b == b'\n').ok_or("curl 输出缺少状态码")?;
+    let status = std::str::from_utf8(&stdout[split + 1..])?.trim().parse()?;
+    Ok((status, stdout[..split].to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+
+    fn issue(id: &str, fingerprint: &str, line: usize) -> AstIssue {
+        serde_json::from_value(serde_json::json!({
+            "severity": "P1", "id": id, "file": "A.java", "path": "src/A.java",
+            "line": line, "description": "", "fingerprint": fingerprint
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_apply_filters_known_fingerprints() {
+        let baseline = Baseline::from_issues(&[issue("EMPTY_CATCH", "abc", 10), issue("SYNC_METHOD", "", 20)]);
+        let mut issues = vec![
+            // 位置变化不影响匹配
+            issue("EMPTY_CATCH", "abc", 42),
+            issue("EMPTY_CATCH", "def", 50),
+            issue("SYNC_METHOD", "", 20),
+        ];
+        assert_eq!(baseline.apply(&mut issues), 2);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].fingerprint, "def");
+    }
+
+    #[test]
+    fn test_refresh_keeps_triage_and_tracks_fixed() {
+        let mut baseline = Baseline::from_issues(&[
+            issue("EMPTY_CATCH", "ack", 10), issue("SYNC_METHOD", "plain", 20), issue("N_PLUS_ONE", "gone", 30),
+        ]);
+        baseline.set("ack", Some(IssueState::Acknowledged), Some("bob".into()), None).unwrap();
+        baseline.set("gone", None, None, Some("批量查询".into())).unwrap();
+        assert!(baseline.set("missing", Some(IssueState::WontFix), None, None).is_err());
+
+        // gone 与 plain 不再出现：处理过的标记为 fixed，未处理的直接移除
+        let next = baseline.refresh(&[issue("EMPTY_CATCH", "ack", 12), issue("OBJECT_IN_LOOP", "fresh", 5)]);
+        let states: Vec<(&str, IssueState)> = next.entries.iter().map(|(k, e)| (k.as_str(), e.triage.state)).collect();
+        assert_eq!(states, vec![("ack", IssueState::Acknowledged), ("fresh", IssueState::New), ("gone", IssueState::Fixed)]);
+        assert_eq!(next.entries["ack"].triage.assignee.as_deref(), Some("bob"));
+        assert_eq!(next.entries["ack"].line, 12);
+
+        // fixed 的问题再次出现：扫描时作为回归报告，update 后重新变为 new
+        let mut issues = vec![issue("N_PLUS_ONE", "gone", 31), issue("EMPTY_CATCH", "ack", 12)];
+        assert_eq!(next.apply(&mut issues), 1);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(state_of(&issues[0]), IssueState::Fixed);
+        assert_eq!(format_triage(issues[0].triage.as_ref().unwrap()), " 📌 fixed (批量查询)");
+        assert_eq!(next.refresh(&issues).entries["gone"].triage.state, IssueState::New);
+    }
+
+    #[test]
+    fn test_v1_baseline_reads_as_new() {
+        let v1 = r#"{"version":1,"entries":{"abc":{"id":"EMPTY_CATCH","path":"src/A.java","line":10}}}"#;
+        let baseline = Baseline::parse(v1, "baseline.json").unwrap();
+        assert_eq!(baseline.version, FORMAT_VERSION);
+        assert_eq!(baseline.entries["abc"].triage, Triage::default());
+
+        let mut baseline = baseline;
+        baseline.set("abc", Some(IssueState::WontFix), Some("alice".into()), None).unwrap();
+        let json = serde_json::to_value(&baseline).unwrap();
+        assert_eq!(json["entries"]["abc"]["state"], "wont-fix");
+        assert_eq!(json["entries"]["abc"]["assignee"], "alice");
+        assert!(json["entries"]["abc"].get("notes").is_none());
+        // 空字符串清除负责人
+        baseline.set("abc", None, Some(String::new()), None).unwrap();
+        assert_eq!(baseline.entries["abc"].triage.assignee, None);
+        assert!(Baseline::parse(r#"{"version":3,"entries":{}}"#, "baseline.json").is_err());
+    }
+
+    #[test]
+    fn test_local_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BaselineStore::resolve(dir.path(), None);
+        assert_eq!(store.load().unwrap(), None);
+
+        let baseline = Baseline::from_issues(&[issue("EMPTY_CATCH", "abc", 10)]);
+        store.save(&baseline).unwrap();
+        assert!(dir.path().join(CACHE_DIR).join(BASELINE_FILE).is_file());
+        assert_eq!(store.load().unwrap(), Some(baseline));
+    }
+
+    #[test]
+    fn test_unreachable_server_falls_back_to_local() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = Baseline::from_issues(&[issue("EMPTY_CATCH", "abc", 10)]);
+        BaselineStore::resolve(dir.path(), None).save(&baseline).unwrap();
+
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+        // 端口 9 (discard) 上没有 HTTP 服务
+        let config = BaselineServer { server: "http://127.0.0.1:9/".into(), project: "order-service".into(), from_env: false };
+        let store = BaselineStore::resolve(dir.path(), Some(&config));
+        assert_eq!(store.describe(), "http://127.0.0.1:9/v1/baselines/order-service");
+        assert_eq!(store.load().unwrap(), Some(baseline));
+        assert!(store.push().is_err());
+    }
+
+    #[test]
+    fn test_option_like_server_and_project_stay_in_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = BaselineServer { server: "http://127.0.0.1:9".into(), project: "--output ../x?y".into(), from_env: false };
+        let store = BaselineStore::resolve(dir.path(), Some(&config));
+        assert_eq!(store.describe(), "http://127.0.0.1:9/v1/baselines/--output%20..%2Fx%3Fy");
+
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+        // server 以 - 开头时只能作为地址 (非法 URL)，不会被 curl 解析为 --output 选项
+        let pwned = dir.path().join("pwned");
+        let config = BaselineServer { server: format!("--output={}", pwned.display()), project: "p".into(), from_env: false };
+        let store = BaselineStore::resolve(dir.path(), Some(&config));
+        let error = store.save(&Baseline::from_issues(&[issue("EMPTY_CATCH", "abc", 10)])).unwrap_err().to_string();
+        assert!(!error.contains("option"), "{error}");
+        assert!(!pwned.exists());
+    }
+
+    #[test]
+    fn test_token_only_sent_to_trusted_https_servers() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = |url: &str, from_env: bool| BaselineServer { server: url.into(), project: "p".into(), from_env };
+        let token_for = |server: &BaselineServer, trusted: Option<&'static str>| {
+            let lookup = move |name: &str| match name {
+                TOKEN_ENV => Some("s3cret".to_string()),
+                TRUSTED_SERVERS_ENV => trusted.map(str::to_string),
+                _ => None,
+            };
+            match BaselineStore::resolve_with_env(dir.path(), Some(server), lookup) {
+                BaselineStore::Remote { token, .. } => token,
+                BaselineStore::Local(_) => unreachable!(),
+            }
+        };
+
+        // 仓库配置文件中的 server 默认不可信
+        assert_eq!(token_for(&server("https://evil.example.com", false), None), None);
+        let listed = Some(" https://javaperf.example.com/ ,https://other.example.com");
+        assert_eq!(token_for(&server("https://JavaPerf.example.com", false), listed).as_deref(), Some("s3cret"));
+        assert_eq!(token_for(&server("https://javaperf.example.com.evil.io", false), listed), None);
+        // 来自环境变量 / -D 的 server 可信，但仍需 https (回环地址除外)
+        assert_eq!(token_for(&server("https://ci.example.com", true), None).as_deref(), Some("s3cret"));
+        assert_eq!(token_for(&server("http://ci.example.com", true), None), None);
+        assert_eq!(token_for(&server("http://localhost.evil.io:8080", true), None), None);
+        assert_eq!(token_for(&server("http://127.0.0.1:8080/", true), None).as_deref(), Some("s3cret"));
+        assert_eq!(token_for(&server("http://[::1]:8080", true), None).as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_remote_store_sends_token_and_syncs_local() {
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+        let remote = Baseline::from_issues(&[issue("N_PLUS_ONE", "remote", 3)]);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let body = serde_json::to_string(&remote).unwrap();
+        let handle = std::thread::spawn(move || {
+            // 依次处理 GET 与 PUT，返回收到的 (请求行, Authorization, 请求体)
+            let mut requests = Vec::new();
+            for response_body in [body.as_str(), ""] {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let (mut auth, mut length) = (String::new(), 0usize);
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Authorization: ") {
+                        auth = value.to_string();
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length: ") {
+                        length = value.parse().unwrap();
+                    }
+                }
+                let mut payload = vec![0; length];
+                reader.read_exact(&mut payload).unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response_body}",
+                    response_body.len()
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+                requests.push((request_line.trim_end().to_string(), auth, String::from_utf8(payload).unwrap()));
+            }
+            requests
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = BaselineStore::Remote {
+            url: format!("http://127.0.0.1:{port}/v1/baselines/order-service"),
+            token: Some("s3cret".to_string()),
+            local: dir.path().join(CACHE_DIR).join(BASELINE_FILE),
+        };
+        // 拉取后同步本地副本
+        assert_eq!(store.load().unwrap(), Some(remote.clone()));
+        assert_eq!(read_local(store.local()).unwrap(), Some(remote.clone()));
+        store.push().unwrap();
+
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[0].0, "GET /v1/baselines/order-service HTTP/1.1");
+        assert_eq!(requests[0].1, "Bearer s3cret");
+        assert_eq!(requests[1].0, "PUT /v1/baselines/order-service HTTP/1.1");
+        let pushed: Baseline = serde_json::from_str(&requests[1].2).unwrap();
+        assert_eq!(pushed, remote);
+    }
+}
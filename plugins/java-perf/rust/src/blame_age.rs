@@ -0,0 +1,149 @@
+//! 按代码修改时间调整严重级别 (`scan --blame-age <YEARS>`)
+//!
+//! 对问题所在行执行 `git blame --line-porcelain`，为问题附加 `age` (最后修改距今天数与提交)。
+//! 超过 YEARS 年未修改的代码中的 P0 降为 P1 (`age.stale`)：长期未变更的代码已经过生产验证，
+//! 整改收益通常不如正在变更的代码；配合 `--sort recent` 最近修改的问题排在最前。
+//!
+//! 未提交的修改视为刚刚修改；扫描目录不在 git 仓库内或文件未跟踪时不附加 age。
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::diagnostics;
+use crate::git;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const DAYS_PER_YEAR: u64 = 365;
+
+/// 问题所在行的修改时间
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueAge {
+    /// 最后修改距今天数
+    pub days: u64,
+    /// 最后修改的提交 (短哈希，未提交的修改为空)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub commit: String,
+    /// 超过阈值未修改，P0 已降为 P1
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+}
+
+/// 行号 → (提交, author-time)
+type BlameLines = HashMap<usize, (String, i64)>;
+
+/// 为问题附加 age，并把超过 `stale_years` 年未修改的 P0 降为 P1，返回降级数
+pub fn annotate(issues: &mut [AstIssue], root: &Path, stale_years: u32) -> usize {
+    if git(root, &["rev-parse", "--is-inside-work-tree"]).is_none() {
+        diagnostics::warn(format!("{} 不在 git 仓库内，--blame-age 未生效", root.display()));
+        return 0;
+    }
+    let mut lines_by_path: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for issue in issues.iter() {
+        lines_by_path.entry(issue.path.as_str()).or_default().push(issue.line);
+    }
+    let blamed: HashMap<String, BlameLines> = lines_by_path
+        .into_par_iter()
+        .filter_map(|(path, lines)| blame(root, path, &lines).map(|blame| (path.to_string(), blame)))
+        .collect();
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+    let stale_days = u64::from(stale_years) * DAYS_PER_YEAR;
+    let mut downgraded = 0;
+    for issue in issues.iter_mut() {
+        let Some((commit, time)) = blamed.get(issue.path.as_str()).and_then(|lines| lines.get(&issue.line)) else {
+            continue;
+        };
+        let days = ((now - time).max(0) / SECONDS_PER_DAY) as u64;
+        let stale = days >= stale_days;
+        if stale && issue.severity == Severity::P0 {
+            issue.severity = Severity::P1;
+            downgraded += 1;
+        }
+        issue.age = Some(IssueAge { days, commit: commit.clone(), stale });
+    }
+    downgraded
+}
+
+/// blame 指定行 (文件未跟踪等失败时返回 None)
+fn blame(root: &Path, path: &str, lines: &[usize]) -> Option<BlameLines> {
+    let mut lines = lines.to_vec();
+    lines.sort_unstable();
+    lines.dedup();
+    let ranges: Vec<String> = lines.iter().map(|line| format!("-L{line},{line}")).collect();
+    let mut args = vec!["blame", "--line-porcelain"];
+    args.extend(ranges.iter().map(String::as_str));
+    args.extend(["--", path]);
+    let output = git(root, &args)?;
+    Some(parse_line_porcelain(&String::from_utf8_lossy(&output)))
+}
+
+fn git(root: &Path, args: &[&str]) -> Option<Vec<u8>> {
+    git::run(root, args).map_err(|e| tracing::debug!("{e}")).ok()
+}
+
+/// 解析 `git blame --line-porcelain`: 每行以 `<sha> <原行号> <行号> [行数]` 开头，随后是完整的提交信息
+fn parse_line_porcelain(output: &str) -> BlameLines {
+    let mut out = BlameLines::new();
+    let mut current: Option<(usize, String)> = None;
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            current = None;
+            continue;
+        }
+        let mut fields = line.split(' ');
+        let first = fields.next().unwrap_or("");
+        if first.len() == 40 && first.bytes().all(|b| b.is_ascii_hexdigit()) {
+            if let Some(final_line) = fields.nth(1).and_then(|n| n.parse().ok()) {
+                // 未提交的修改为全 0 哈希
+                let commit = if first.bytes().all(|b| b == b'0') { String::new() } else { first[..7].to_string() };
+                current = Some((final_line, commit));
+            }
+        } else if let (Some(time), Some((final_line, commit))) = (line.strip_prefix("author-time "), &current) {
+            if let Ok(time) = time.trim().parse() {
+                out.insert(*final_line, (commit.clone(), time));
+            }
+        }
+    }
+    out
+}
+
+/// Markdown 标记 (超过阈值未修改的问题)
+pub fn format_age(issue: &AstIssue) -> String {
+    match &issue.age {
+        Some(age) if age.stale => format!(" 🕰️{} 年未修改", age.days / DAYS_PER_YEAR),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_porcelain() {
+        let output = "\
+3f2a9c1e0b7d4a6f8e2c1b0a9d8e7f6a5b4c3d2e 1 4 1
+author Alice
+author-mail <alice@example.com>
+author-time 1500000000
+author-tz +0800
+summary init
+filename src/A.java
+\t        orderRepository.findById(id);
+0000000000000000000000000000000000000000 9 9 1
+author Not Committed Yet
+author-time 1700000000
+filename src/A.java
+\t        Thread.sleep(100);
+";
+        let parsed = parse_line_porcelain(output);
+        assert_eq!(parsed[&4], ("3f2a9c1".to_string(), 1_500_000_000));
+        assert_eq!(parsed[&9], (String::new(), 1_700_000_000));
+        assert_eq!(parsed.len(), 2);
+    }
+}
@@ -0,0 +1,213 @@
+//! 构建工具接入片段
+//!
+//! `print-hook maven|gradle` 输出可直接粘贴的构建配置 (exec-maven-plugin / Gradle Exec task)，
+//! 在 verify/check 阶段调用 java-perf 扫描并把 JSON 报告写入构建目录 (可作为 `verify --against` 输入)。
+//!
+//! 多模块项目只在聚合根执行一次、扫描整个仓库：SymbolTable/CallGraph 需要跨模块解析，
+//! 逐模块扫描会丢失跨模块的 N+1、Bean 歧义等问题。
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::project_detector::detect_modules;
+
+/// 目标构建工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookTool {
+    Maven,
+    Gradle,
+}
+
+/// 生成的接入片段
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildHook {
+    pub tool: HookTool,
+    /// 子模块目录 (相对项目根)；为空表示单模块
+    pub modules: Vec<String>,
+    /// 粘贴位置说明
+    pub target_file: String,
+    pub snippet: String,
+}
+
+/// 根据项目布局生成接入片段
+pub fn generate(tool: HookTool, root: &Path, binary: &str) -> BuildHook {
+    let modules = detect_modules(root);
+    let (target_file, snippet) = match tool {
+        HookTool::Maven => ("pom.xml <build><plugins>".to_string(), maven_snippet(&modules, binary)),
+        HookTool::Gradle => {
+            let kotlin = root.join("build.gradle.kts").exists() || root.join("settings.gradle.kts").exists();
+            let file = if kotlin { "build.gradle.kts" } else { "build.gradle" };
+            (format!("{file} (根项目)"), gradle_snippet(&modules, binary, kotlin))
+        }
+    };
+    BuildHook { tool, modules, target_file, snippet }
+}
+
+fn module_comment(modules: &[String], open: &str, close: &str) -> String {
+    if modules.is_empty() {
+        return String::new();
+    }
+    let line = format!("{open} 聚合扫描 {} 个模块: {} {close}", modules.len(), modules.join(", "));
+    format!("{}\n", line.trim_end())
+}
+
+fn maven_snippet(modules: &[String], binary: &str) -> String {
+    let multi = !modules.is_empty();
+    let (root, inherited) = if multi {
+        // 只在聚合根执行；子模块继承 plugin 配置但不重复扫描
+        ("${maven.multiModuleProjectDirectory}", "\n    <inherited>false</inherited>")
+    } else {
+        ("${project.basedir}", "")
+    };
+
+    format!(
+        "<!-- java-perf: verify 阶段雷达扫描，报告写入 target/java-perf-report.json -->\n\
+{modules}<plugin>
+    <groupId>org.codehaus.mojo</groupId>
+    <artifactId>exec-maven-plugin</artifactId>
+    <version>3.1.0</version>{inherited}
+    <executions>
+        <execution>
+            <id>java-perf-scan</id>
+            <phase>verify</phase>
+            <goals>
+                <goal>exec</goal>
+            </goals>
+            <configuration>
+                <executable>{binary}</executable>
+                <workingDirectory>{root}</workingDirectory>
+                <outputFile>${{project.build.directory}}/java-perf-report.json</outputFile>
+                <arguments>
+                    <argument>--json</argument>
+                    <argument>scan</argument>
+                    <argument>--path</argument>
+                    <argument>{root}</argument>
+                </arguments>
+            </configuration>
+        </execution>
+    </executions>
+</plugin>
+",
+        modules = module_comment(modules, "<!--", "-->"),
+    )
+}
+
+fn gradle_snippet(modules: &[String], binary: &str, kotlin: bool) -> String {
+    let multi = !modules.is_empty();
+    let mut out = String::from("// java-perf: check 阶段雷达扫描，报告写入 build/reports/java-perf/report.json\n");
+    out.push_str(&module_comment(modules, "//", ""));
+
+    if kotlin {
+        out.push_str(&format!(
+            r#"val javaPerfScan = tasks.register<Exec>("javaPerfScan") {{
+    group = "verification"
+    description = "Run java-perf radar scan"
+    workingDir = rootDir
+    commandLine("{binary}", "--json", "scan", "--path", rootDir.absolutePath)
+    val report = layout.buildDirectory.file("reports/java-perf/report.json")
+    outputs.file(report)
+    doFirst {{
+        val file = report.get().asFile
+        file.parentFile.mkdirs()
+        standardOutput = file.outputStream()
+    }}
+}}
+"#
+        ));
+        if multi {
+            out.push_str("subprojects {\n    tasks.matching { it.name == \"check\" }.configureEach { dependsOn(javaPerfScan) }\n}\n");
+        } else {
+            out.push_str("tasks.named(\"check\") { dependsOn(javaPerfScan) }\n");
+        }
+    } else {
+        out.push_str(&format!(
+            r#"def javaPerfScan = tasks.register('javaPerfScan', Exec) {{
+    group = 'verification'
+    description = 'Run java-perf radar scan'
+    workingDir = rootDir
+    commandLine '{binary}', '--json', 'scan', '--path', rootDir.absolutePath
+    def report = layout.buildDirectory.file('reports/java-perf/report.json')
+    outputs.file(report)
+    doFirst {{
+        def file = report.get().asFile
+        file.parentFile.mkdirs()
+        standardOutput = new FileOutputStream(file)
+    }}
+}}
+"#
+        ));
+        if multi {
+            out.push_str("subprojects {\n    tasks.matching { it.name == 'check' }.configureEach { dependsOn javaPerfScan }\n}\n");
+        } else {
+            out.push_str("tasks.named('check') { dependsOn javaPerfScan }\n");
+        }
+    }
+    out
+}
+
+/// 渲染输出：人类可读模式直接输出片段，便于重定向/复制
+pub fn render(hook: &BuildHook, json_output: bool) -> Value {
+    if json_output {
+        return json!(hook);
+    }
+    let header = match hook.tool {
+        HookTool::Maven => format!("<!-- 粘贴到 {} -->", hook.target_file),
+        HookTool::Gradle => format!("// 粘贴到 {}", hook.target_file),
+    };
+    json!(format!("{header}\n{}", hook.snippet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maven_single_module() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pom.xml"), "<project></project>").unwrap();
+        let hook = generate(HookTool::Maven, dir.path(), "java-perf");
+        assert!(hook.modules.is_empty());
+        assert!(hook.snippet.contains("<workingDirectory>${project.basedir}</workingDirectory>"));
+        assert!(hook.snippet.contains("<outputFile>${project.build.directory}/java-perf-report.json</outputFile>"));
+        assert!(!hook.snippet.contains("<inherited>"));
+    }
+
+    #[test]
+    fn test_maven_multi_module_aggregates_at_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pom.xml"),
+            "<project><modules><module>api</module><module>service</module></modules></project>").unwrap();
+        let hook = generate(HookTool::Maven, dir.path(), "/opt/bin/java-perf");
+        assert_eq!(hook.modules, vec!["api", "service"]);
+        assert!(hook.snippet.contains("<!-- 聚合扫描 2 个模块: api, service -->"));
+        assert!(hook.snippet.contains("<inherited>false</inherited>"));
+        assert!(hook.snippet.contains("<argument>${maven.multiModuleProjectDirectory}</argument>"));
+        assert!(hook.snippet.contains("<executable>/opt/bin/java-perf</executable>"));
+    }
+
+    #[test]
+    fn test_gradle_kotlin_dsl_multi_module() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("settings.gradle.kts"), "include(\":app\", \":lib\")\n").unwrap();
+        let hook = generate(HookTool::Gradle, dir.path(), "java-perf");
+        assert_eq!(hook.target_file, "build.gradle.kts (根项目)");
+        assert!(hook.snippet.contains("tasks.register<Exec>(\"javaPerfScan\")"));
+        assert!(hook.snippet.contains("commandLine(\"java-perf\", \"--json\", \"scan\", \"--path\", rootDir.absolutePath)"));
+        assert!(hook.snippet.contains("subprojects {"));
+
+        let rendered = render(&hook, false);
+        assert!(rendered.as_str().unwrap().starts_with("// 粘贴到 build.gradle.kts (根项目)\n// java-perf:"));
+    }
+
+    #[test]
+    fn test_gradle_groovy_single_module() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("build.gradle"), "plugins { id 'java' }\n").unwrap();
+        let hook = generate(HookTool::Gradle, dir.path(), "java-perf");
+        assert!(hook.snippet.contains("tasks.register('javaPerfScan', Exec)"));
+        assert!(hook.snippet.contains("tasks.named('check') { dependsOn javaPerfScan }"));
+    }
+}
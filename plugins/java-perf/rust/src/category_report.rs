@@ -0,0 +1,238 @@
+//! 按规则类别组织报告 (Markdown 各类别章节 / JSON `categories`)
+//!
+//! 问题按规则类别 (`RuleCategory`，见 rules::registry) 分组，每个类别给出汇总统计与
+//! "优先处理" 清单: 同一规则的问题合并为一步，按以下顺序排列，最多 [`CHECKLIST_STEPS`] 步:
+//!
+//! 1. 含 P0 的规则在前 (P0 数降序)
+//! 2. 最高热度降序 (离请求入口越近越优先)
+//! 3. 问题总数降序
+//!
+//! 每步附规则的修复建议 (`registry::fix_suggestion`，未登记时取问题描述) 与第一个位置。
+//! 每个类别附问题密度 (`issues_per_kloc`，分母为项目代码行数，见 density 模块)。
+//! 类别章节带锚点 `category-<name>`，HTML 模板可通过 JSON 中的 `anchor` 深度链接。
+//! 无法归类的问题 (库调用方注册的自定义规则) 归入 [`OTHER`]。
+
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::density;
+use crate::rules::registry::{category_of, fix_suggestion, RuleCategory};
+
+/// 每个类别"优先处理"清单的最大步数
+pub const CHECKLIST_STEPS: usize = 5;
+
+/// 无法归类的问题所在分组名
+pub const OTHER: &str = "other";
+
+/// 单个类别的汇总
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CategorySummary {
+    /// 类别名 (`--categories` 取值，无法归类时为 [`OTHER`])
+    pub category: &'static str,
+    pub title: &'static str,
+    /// Markdown 锚点 (`category-<name>`)
+    pub anchor: String,
+    pub p0: usize,
+    pub p1: usize,
+    /// 命中的规则数
+    pub rules: usize,
+    /// 涉及的文件数
+    pub files: usize,
+    /// 类别内问题的最高热度
+    pub hotness: u8,
+    /// 每千行代码的问题数 (分母为项目 LOC)
+    pub issues_per_kloc: f64,
+    /// 优先处理清单
+    pub checklist: Vec<ChecklistStep>,
+}
+
+/// 优先处理清单中的一步 (同一规则的全部问题)
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ChecklistStep {
+    pub rule: String,
+    pub p0: usize,
+    pub p1: usize,
+    pub hotness: u8,
+    pub fix: String,
+    /// 第一个问题的位置 (`path:line`)
+    pub first: String,
+}
+
+/// 问题所属类别名 (与 [`CategorySummary::category`] 一致)
+pub fn category_name(issue: &AstIssue) -> &'static str {
+    category_key(issue).0
+}
+
+/// 类别 (名称, 标题)，问题无法归类时为 [`OTHER`]
+fn category_key(issue: &AstIssue) -> (&'static str, &'static str) {
+    match category_of(&issue.issue_type) {
+        Some(category) => (category.name(), category.title()),
+        None => (OTHER, "📦 其他"),
+    }
+}
+
+fn category_rank(name: &str) -> usize {
+    RuleCategory::value_variants().iter().position(|c| c.name() == name).unwrap_or(usize::MAX)
+}
+
+/// 按类别汇总 (`loc` 为项目代码行数)，P0 数降序，其次问题总数、最高热度降序，仍相同时按类别定义顺序
+pub fn summarize(issues: &[AstIssue], loc: usize) -> Vec<CategorySummary> {
+    let mut groups: BTreeMap<&'static str, (&'static str, Vec<&AstIssue>)> = BTreeMap::new();
+    for issue in issues {
+        let (name, title) = category_key(issue);
+        groups.entry(name).or_insert_with(|| (title, Vec::new())).1.push(issue);
+    }
+    let mut summaries: Vec<CategorySummary> = groups.into_iter()
+        .map(|(name, (title, issues))| summarize_category(name, title, &issues, loc))
+        .collect();
+    summaries.sort_by_key(|s| (std::cmp::Reverse((s.p0, s.p0 + s.p1, s.hotness)), category_rank(s.category)));
+    summaries
+}
+
+fn summarize_category(name: &'static str, title: &'static str, issues: &[&AstIssue], loc: usize) -> CategorySummary {
+    let mut steps: BTreeMap<&str, ChecklistStep> = BTreeMap::new();
+    for issue in issues {
+        let step = steps.entry(issue.issue_type.as_str()).or_insert_with(|| ChecklistStep {
+            rule: issue.issue_type.clone(),
+            p0: 0,
+            p1: 0,
+            hotness: 0,
+            fix: fix_suggestion(&issue.issue_type).map_or_else(|| issue.description.clone(), str::to_string),
+            first: format!("{}:{}", issue.path, issue.line),
+        });
+        match issue.severity {
+            Severity::P0 => step.p0 += 1,
+            Severity::P1 => step.p1 += 1,
+        }
+        step.hotness = step.hotness.max(issue.hotness);
+    }
+    let rules = steps.len();
+    let mut checklist: Vec<ChecklistStep> = steps.into_values().collect();
+    // 稳定排序：同优先级保持规则 ID 顺序
+    checklist.sort_by_key(|s| std::cmp::Reverse((s.p0, s.hotness, s.p0 + s.p1)));
+    checklist.truncate(CHECKLIST_STEPS);
+
+    let mut files: Vec<&str> = issues.iter().map(|i| i.path.as_str()).collect();
+    files.sort_unstable();
+    files.dedup();
+    CategorySummary {
+        category: name,
+        title,
+        anchor: format!("category-{name}"),
+        p0: issues.iter().filter(|i| i.severity == Severity::P0).count(),
+        p1: issues.iter().filter(|i| i.severity == Severity::P1).count(),
+        rules,
+        files: files.len(),
+        hotness: issues.iter().map(|i| i.hotness).max().unwrap_or(0),
+        issues_per_kloc: density::per_kloc(issues.len(), loc),
+        checklist,
+    }
+}
+
+/// Markdown 报告开头的类别目录 (链接到各类别章节)
+pub fn render_index(summaries: &[CategorySummary], p0_label: &str, p1_label: &str) -> String {
+    if summaries.is_empty() {
+        return String::new();
+    }
+    let mut index = format!("| 类别 | {p0_label} | {p1_label} | 规则 | 文件 |\n|------|----|----|------|------|\n");
+    for s in summaries {
+        index.push_str(&format!(
+            "| [{}](#{}) | {} | {} | {} | {} |\n", s.title, s.anchor, s.p0, s.p1, s.rules, s.files
+        ));
+    }
+    index.push('\n');
+    index
+}
+
+/// 类别章节的标题、汇总统计与优先处理清单 (问题列表由调用方追加)
+pub fn render_header(summary: &CategorySummary, p0_label: &str, p1_label: &str) -> String {
+    let mut section = format!(
+        "<a id=\"{}\"></a>\n\n### {} ({p0_label}: {}, {p1_label}: {})\n\n**规则**: {} | **文件**: {}",
+        summary.anchor, summary.title, summary.p0, summary.p1, summary.rules, summary.files
+    );
+    if summary.issues_per_kloc > 0.0 {
+        section.push_str(&format!(" | **密度**: {:.2}/KLoC", summary.issues_per_kloc));
+    }
+    if summary.hotness > 0 {
+        section.push_str(&format!(" | **最高热度**: 🔥{}", summary.hotness));
+    }
+    section.push_str("\n\n**优先处理**:\n\n");
+    for (step, item) in summary.checklist.iter().enumerate() {
+        let mut counts = Vec::new();
+        if item.p0 > 0 {
+            counts.push(format!("{p0_label} × {}", item.p0));
+        }
+        if item.p1 > 0 {
+            counts.push(format!("{p1_label} × {}", item.p1));
+        }
+        section.push_str(&format!(
+            "{}. **{}** ({}) - {}，从 `{}` 开始\n", step + 1, item.rule, counts.join(", "), item.fix, item.first
+        ));
+    }
+    section.push('\n');
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, severity: Severity, path: &str, line: usize, hotness: u8) -> AstIssue {
+        serde_json::from_value(serde_json::json!({
+            "severity": severity, "id": id, "file": "", "path": path, "line": line,
+            "description": format!("{id} 描述"), "hotness": hotness
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_summarize_orders_categories_and_checklist() {
+        let issues = vec![
+            issue("SYNC_METHOD", Severity::P1, "A.java", 3, 0),
+            issue("EMPTY_CATCH", Severity::P1, "A.java", 5, 0),
+            issue("NESTED_LOOP", Severity::P1, "B.java", 7, 80),
+            issue("N_PLUS_ONE", Severity::P0, "B.java", 9, 0),
+            issue("N_PLUS_ONE", Severity::P0, "C.java", 2, 0),
+            issue("STRING_CONCAT_LOOP", Severity::P1, "C.java", 4, 0),
+            issue("STRING_CONCAT_LOOP", Severity::P1, "C.java", 6, 0),
+            issue("MY_CUSTOM_RULE", Severity::P0, "D.java", 1, 0),
+        ];
+        let summaries = summarize(&issues, 2000);
+        let order: Vec<&str> = summaries.iter().map(|s| s.category).collect();
+        // P0 多的在前；同为 0 个 P0 且各 1 个问题时按类别定义顺序
+        assert_eq!(order, ["performance", OTHER, "concurrency", "reliability"]);
+
+        let perf = &summaries[0];
+        assert_eq!((perf.p0, perf.p1, perf.rules, perf.files, perf.hotness), (2, 3, 3, 2, 80));
+        assert_eq!(perf.anchor, "category-performance");
+        assert_eq!(perf.issues_per_kloc, 2.5);
+        let steps: Vec<&str> = perf.checklist.iter().map(|s| s.rule.as_str()).collect();
+        // P0 优先，其次热度，再次问题数
+        assert_eq!(steps, ["N_PLUS_ONE", "NESTED_LOOP", "STRING_CONCAT_LOOP"]);
+        assert_eq!(perf.checklist[0].first, "B.java:9");
+        assert!(perf.checklist[0].fix.contains("批量查询"));
+        // 未登记修复建议的规则回退到问题描述
+        assert_eq!(summaries[1].checklist[0].fix, "MY_CUSTOM_RULE 描述");
+    }
+
+    #[test]
+    fn test_render_index_and_header() {
+        let issues = vec![
+            issue("N_PLUS_ONE", Severity::P0, "B.java", 9, 50),
+            issue("SELECT_STAR", Severity::P1, "B.java", 12, 0),
+        ];
+        let summaries = summarize(&issues, 0);
+        let index = render_index(&summaries, "P0", "P1");
+        assert!(index.contains("| [⚡ 执行效率](#category-performance) | 1 | 1 | 2 | 1 |"), "{index}");
+
+        let header = render_header(&summaries[0], "Blocker", "P1");
+        assert!(header.starts_with("<a id=\"category-performance\"></a>"), "{header}");
+        assert!(header.contains("### ⚡ 执行效率 (Blocker: 1, P1: 1)"), "{header}");
+        assert!(header.contains("**最高热度**: 🔥50"), "{header}");
+        assert!(header.contains("1. **N_PLUS_ONE** (Blocker × 1) - 改为循环外批量查询"), "{header}");
+        assert!(header.contains("2. **SELECT_STAR** (P1 × 1) - 只查询需要的列，从 `B.java:12` 开始"), "{header}");
+        assert!(render_index(&[], "P0", "P1").is_empty());
+    }
+}
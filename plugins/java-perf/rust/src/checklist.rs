@@ -185,6 +185,13 @@ pub fn get_checklist_data() -> Vec<CheckSection> {
                     fix: Some("finally 归还".to_string()),
                     why: Some("每次请求泄露1个连接，池很快被占满".to_string()),
                 },
+                CheckItem {
+                    desc: "RPC 客户端无超时（gRPC stub 未 withDeadlineAfter、Feign 未配置 Request.Options）".to_string(),
+                    verify: Some("java-perf scan: GRPC_NO_DEADLINE / FEIGN_NO_OPTIONS".to_string()),
+                    threshold: None,
+                    fix: Some("每次调用 stub.withDeadlineAfter(...)；Feign.builder().options(...)".to_string()),
+                    why: Some("下游挂起时调用线程被无限占用，线程池随之耗尽".to_string()),
+                },
             ],
         },
         CheckSection {
@@ -0,0 +1,228 @@
+//! 扫描检查点 (`scan --checkpoint FILE`)：中断后从最后完成的一批文件继续
+//!
+//! 受限 CI 中扫描超大仓库可能因超时或抢占被中断。检查点为 JSON Lines 文件:
+//!
+//! ```text
+//! {"format":1,"tool_version":"9.5.0","fingerprint":…,"worklist":["src/A.java", …]}   ← 头部: 本次待分析文件
+//! {"files":[{…FileOutcome…}, …]}                                                    ← 每完成一批追加一行
+//! ```
+//!
+//! 再次以相同参数扫描时，内容哈希未变的文件直接复用检查点中的 Phase 2 结果，其余文件照常分析；
+//! 扫描成功结束后删除检查点。中断时写了一半的行在恢复时丢弃。
+//!
+//! - Phase 1 索引不进检查点 (配合 `--cache` 复用)，跨文件后处理在全部结果上重新执行
+//! - 工具版本、启用的规则、`.javaperf.toml` 规则覆盖等影响单文件结果的参数变化时检查点作废，从头扫描
+//! - 只用于同一代码版本的中断恢复：其他文件的修改可能影响跨文件语义 (如 DAO 类型)，但不会使已完成文件失效
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// 检查点格式或单文件结果结构变化时递增
+const FORMAT_VERSION: u32 = 2;
+
+/// 每批文件数 (每批追加一行并落盘)
+pub const BATCH_SIZE: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: u32,
+    tool_version: String,
+    /// 影响单文件结果的扫描参数摘要
+    fingerprint: u64,
+    /// 本次扫描的全部待分析文件 (相对路径)
+    worklist: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Batch<T> {
+    files: Vec<T>,
+}
+
+/// 检查点中的单文件结果
+pub trait Resumable: Serialize + DeserializeOwned {
+    /// 相对路径
+    fn key(&self) -> &str;
+    /// 分析时的文件内容哈希
+    fn content_hash(&self) -> u64;
+}
+
+/// 上次中断前完成的结果 (读取线程并发取用)
+pub struct Resumed<T> {
+    outcomes: Mutex<HashMap<String, T>>,
+}
+
+impl<T: Resumable> Resumed<T> {
+    /// 取出文件的已完成结果 (内容哈希不一致时为 None，需重新分析)
+    pub fn take(&self, key: &str, content_hash: u64) -> Option<T> {
+        let mut outcomes = self.outcomes.lock().unwrap_or_else(|e| e.into_inner());
+        outcomes.remove(key).filter(|outcome| outcome.content_hash() == content_hash)
+    }
+}
+
+/// 追加写入新完成的结果
+pub struct Checkpoint<T> {
+    path: PathBuf,
+    file: File,
+    /// 未落盘的结果 (已序列化)
+    pending: Vec<String>,
+    _outcome: PhantomData<T>,
+}
+
+impl<T: Resumable> Checkpoint<T> {
+    /// 打开检查点：头部匹配时保留已完成的批次并继续追加，否则 (不存在/参数变化/损坏) 重新开始
+    pub fn open(path: &Path, fingerprint: u64, worklist: Vec<String>) -> Result<(Self, Resumed<T>), String> {
+        let header = Header { format: FORMAT_VERSION, tool_version: env!("CARGO_PKG_VERSION").to_string(), fingerprint, worklist };
+        let (outcomes, valid_len) = load(path, &header).unwrap_or_default();
+        let describe = |e: std::io::Error| format!("检查点 {}: {e}", path.display());
+        let file = if valid_len > 0 {
+            let remaining = header.worklist.iter().filter(|key| !outcomes.contains_key(*key)).count();
+            tracing::info!(path = %path.display(), done = outcomes.len(), remaining, "从检查点恢复扫描");
+            // 截掉中断时写了一半的行
+            let file = OpenOptions::new().write(true).open(path).map_err(describe)?;
+            file.set_len(valid_len).map_err(describe)?;
+            OpenOptions::new().append(true).open(path).map_err(describe)?
+        } else {
+            if path.exists() {
+                tracing::warn!(path = %path.display(), "检查点与本次扫描参数不一致或已损坏，重新开始");
+            }
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir).map_err(describe)?;
+            }
+            let mut file = File::create(path).map_err(describe)?;
+            let line = serde_json::to_string(&header).map_err(|e| e.to_string())?;
+            writeln!(file, "{line}").and_then(|_| file.sync_data()).map_err(describe)?;
+            file
+        };
+        let checkpoint = Self { path: path.to_path_buf(), file, pending: Vec::new(), _outcome: PhantomData };
+        Ok((checkpoint, Resumed { outcomes: Mutex::new(outcomes) }))
+    }
+
+    /// 记录新完成的文件，满一批时落盘
+    pub fn record(&mut self, outcome: &T) -> std::io::Result<()> {
+        self.pending.push(serde_json::to_string(outcome)?);
+        if self.pending.len() >= BATCH_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// 写入未满一批的结果
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        writeln!(self.file, "{{\"files\":[{}]}}", self.pending.join(","))?;
+        self.pending.clear();
+        self.file.sync_data()
+    }
+
+    /// 扫描完成，删除检查点
+    pub fn finish(self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!(path = %self.path.display(), "删除检查点失败: {e}");
+        }
+    }
+}
+
+/// 读取头部匹配的检查点: (已完成结果, 最后一个完整行的结束位置)
+fn load<T: Resumable>(path: &Path, expected: &Header) -> Option<(HashMap<String, T>, u64)> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let mut line = String::new();
+    let mut valid_len = reader.read_line(&mut line).ok()? as u64;
+    let header: Header = serde_json::from_str(&line).ok()?;
+    if header.format != expected.format || header.tool_version != expected.tool_version
+        || header.fingerprint != expected.fingerprint || header.worklist != expected.worklist {
+        return None;
+    }
+    let mut outcomes = HashMap::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).ok()?;
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        let Ok(batch) = serde_json::from_str::<Batch<T>>(&line) else { break };
+        outcomes.extend(batch.files.into_iter().map(|outcome| (outcome.key().to_string(), outcome)));
+        valid_len += read as u64;
+    }
+    Some((outcomes, valid_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Outcome {
+        path: String,
+        hash: u64,
+    }
+
+    impl Resumable for Outcome {
+        fn key(&self) -> &str {
+            &self.path
+        }
+
+        fn content_hash(&self) -> u64 {
+            self.hash
+        }
+    }
+
+    fn outcome(i: usize) -> Outcome {
+        Outcome { path: format!("F{i}.java"), hash: i as u64 }
+    }
+
+    #[test]
+    fn test_resume_from_completed_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.ckpt");
+        let worklist: Vec<String> = (0..BATCH_SIZE + 10).map(|i| outcome(i).path).collect();
+
+        // 第一次: 完成一整批 + 未落盘的 5 个后中断
+        let (mut checkpoint, resumed) = Checkpoint::<Outcome>::open(&path, 7, worklist.clone()).unwrap();
+        assert!(resumed.take("F0.java", 0).is_none());
+        for i in 0..BATCH_SIZE + 5 {
+            checkpoint.record(&outcome(i)).unwrap();
+        }
+        drop(checkpoint);
+        // 中断时写了一半的行
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"files\":[{\"pa").unwrap();
+
+        let (mut checkpoint, resumed) = Checkpoint::<Outcome>::open(&path, 7, worklist.clone()).unwrap();
+        assert_eq!(resumed.take("F3.java", 3), Some(outcome(3)));
+        // 内容变化的文件重新分析
+        assert_eq!(resumed.take("F4.java", 99), None);
+        assert_eq!(resumed.take(&outcome(BATCH_SIZE + 1).path, (BATCH_SIZE + 1) as u64), None);
+        checkpoint.record(&outcome(BATCH_SIZE)).unwrap();
+        checkpoint.flush().unwrap();
+        drop(checkpoint);
+
+        let (checkpoint, resumed) = Checkpoint::<Outcome>::open(&path, 7, worklist.clone()).unwrap();
+        assert!(resumed.take(&outcome(BATCH_SIZE).path, BATCH_SIZE as u64).is_some());
+        assert!(resumed.take("F5.java", 5).is_some());
+        checkpoint.finish();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_mismatched_parameters_start_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/scan.ckpt");
+        let (mut checkpoint, _) = Checkpoint::<Outcome>::open(&path, 1, vec!["F0.java".into()]).unwrap();
+        checkpoint.record(&outcome(0)).unwrap();
+        checkpoint.flush().unwrap();
+        drop(checkpoint);
+
+        let (_, resumed) = Checkpoint::<Outcome>::open(&path, 2, vec!["F0.java".into()]).unwrap();
+        assert!(resumed.take("F0.java", 0).is_none());
+        // 重新开始后只剩头部
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+    }
+}
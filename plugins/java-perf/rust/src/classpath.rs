@@ -0,0 +1,360 @@
+//! 构建期类型解析 (`scan --classpath`)
+//!
+//! 字段类型定义在第三方 jar 中时 (如共享数据模块里的 `OrderRepository extends JpaRepository`)，
+//! 源码索引里没有它，N+1、阻塞调用等规则只能按变量名/方法名猜测，置信度为低/中。
+//! 提供编译产物的 classpath 后，这里直接读取 class 文件 (与 jar-scan 共用解析，不依赖
+//! JDK 的 javap)，把项目字段引用到的外部类型注册进符号表:
+//!
+//! - 字段类型按所在文件的 import 解析为 FQN (显式 → 同包 → 通配)，都未命中时取 classpath
+//!   中唯一的同名类；同名类有多个时不猜测
+//! - 符号表按简单名查找字段类型，与项目类或其它外部类同名的类型仍视为未确认
+//! - 父类/接口链 (跨 jar 传递) 记为 supertypes，含 Spring Data / Panache 等 Repository 基类时
+//!   标记为数据访问层
+//! - 类型确认后规则按 "FQN 已解析" 报告高置信度
+//!
+//! classpath 写法与 `java -cp` 一致: 平台路径分隔符 (`:` / `;`)，条目为 jar、class 目录或 `lib/*`。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::ast_engine::ImportIndexMap;
+use crate::jar_scan::{walk_classes, ClassFile};
+use crate::symbol_table::{ImportIndex, SymbolTable, TypeInfo};
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// classpath 中的一个类
+#[derive(Debug, Clone)]
+struct ExternalClass {
+    /// 父类与接口 (FQN，`java.lang.Object` 除外)
+    supertypes: Vec<String>,
+    /// 所在构件 (jar 路径或 class 目录)
+    artifact: String,
+}
+
+/// classpath 类索引
+#[derive(Debug, Default)]
+pub struct ClassIndex {
+    classes: HashMap<String, ExternalClass>,
+    /// 简单名 -> FQN 列表
+    by_simple: HashMap<String, Vec<String>>,
+    /// 读取的 classpath 条目数
+    entries: usize,
+    /// 无法解析的 class/jar
+    pub errors: Vec<String>,
+}
+
+/// `--classpath` 解析统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ClasspathStats {
+    /// classpath 条目数 (展开 `dir/*` 之后)
+    pub entries: usize,
+    /// 读取的类
+    pub classes: usize,
+    /// 解析到 classpath 类型的项目字段
+    pub resolved_fields: usize,
+    /// 注册进符号表的外部类型
+    pub external_types: usize,
+    pub errors: usize,
+}
+
+/// 拆分 classpath 字符串，展开 `dir/*` (目录下全部 jar，按文件名排序)
+pub fn parse_classpath(spec: &str) -> Vec<PathBuf> {
+    let mut entries = Vec::new();
+    for entry in std::env::split_paths(spec).filter(|p| !p.as_os_str().is_empty()) {
+        if entry.file_name().is_some_and(|n| n == "*") {
+            let dir = entry.parent().unwrap_or(Path::new("."));
+            let mut jars: Vec<PathBuf> = std::fs::read_dir(dir).into_iter().flatten()
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("jar")))
+                .collect();
+            jars.sort();
+            entries.extend(jars);
+        } else {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+/// 内部名 (`com/acme/Outer$Inner`) 转 FQN (`com.acme.Outer.Inner`)
+fn internal_to_fqn(name: &str) -> String {
+    name.replace(['/', '$'], ".")
+}
+
+fn simple_name(fqn: &str) -> &str {
+    fqn.rsplit('.').next().unwrap_or(fqn)
+}
+
+impl ClassIndex {
+    /// 读取 classpath 条目；条目不存在时报错 (避免拼错路径后静默退化为启发式)
+    pub fn load(entries: &[PathBuf]) -> Result<Self, BoxError> {
+        let mut index = Self::default();
+        for entry in entries {
+            if !entry.exists() {
+                return Err(format!("classpath 条目不存在: {}", entry.display()).into());
+            }
+            let root = entry.display().to_string();
+            let walk = walk_classes(entry, &mut |class, artifact| {
+                let artifact = if artifact == "." || Path::new(&root).is_file() {
+                    root.clone()
+                } else {
+                    format!("{root}/{artifact}")
+                };
+                index.insert(class, artifact);
+            });
+            index.errors.extend(walk.errors);
+            index.entries += 1;
+        }
+        Ok(index)
+    }
+
+    fn insert(&mut self, class: ClassFile, artifact: String) {
+        let fqn = internal_to_fqn(&class.name);
+        // 匿名类/局部类 (`Outer$1`) 不会出现在字段类型中
+        if fqn.is_empty() || simple_name(&fqn).starts_with(|c: char| c.is_ascii_digit()) {
+            return;
+        }
+        if self.classes.contains_key(&fqn) {
+            // 与 JVM 一致：classpath 中靠前的条目优先
+            return;
+        }
+        let supertypes = class.super_class.iter()
+            .chain(&class.interfaces)
+            .map(|name| internal_to_fqn(name))
+            .filter(|name| name != "java.lang.Object")
+            .collect();
+        self.by_simple.entry(simple_name(&fqn).to_string()).or_default().push(fqn.clone());
+        self.classes.insert(fqn, ExternalClass { supertypes, artifact });
+    }
+
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// 按源文件的 import 解析类型名为 classpath 中的 FQN
+    pub fn resolve(&self, type_name: &str, imports: Option<&ImportIndex>) -> Option<&str> {
+        let known = |fqn: String| self.classes.get_key_value(&fqn).map(|(k, _)| k.as_str());
+        if type_name.contains('.') {
+            return known(type_name.to_string());
+        }
+        if let Some(imports) = imports {
+            let candidates = imports.explicit.get(type_name).cloned().into_iter()
+                .chain(imports.package.iter().map(|pkg| format!("{pkg}.{type_name}")))
+                .chain(imports.wildcards.iter().map(|pkg| format!("{pkg}.{type_name}")));
+            for candidate in candidates {
+                if let Some(fqn) = known(candidate) {
+                    return Some(fqn);
+                }
+            }
+        }
+        match self.by_simple.get(type_name).map(Vec::as_slice) {
+            Some([only]) => Some(only.as_str()),
+            _ => None,
+        }
+    }
+
+    /// 全部祖先类型 (父类/接口的传递闭包，FQN)；不在 classpath 中的祖先保留名称但不再展开
+    pub fn ancestors(&self, fqn: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([fqn]);
+        let mut result = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            let Some(class) = self.classes.get(current) else { continue };
+            for supertype in &class.supertypes {
+                if seen.insert(supertype.as_str()) {
+                    result.push(supertype.clone());
+                    queue.push_back(supertype);
+                }
+            }
+        }
+        result
+    }
+
+    /// 外部类型的符号表条目
+    fn type_info(&self, fqn: &str) -> Option<TypeInfo> {
+        let class = self.classes.get(fqn)?;
+        let package = fqn.rsplit_once('.').map(|(pkg, _)| pkg);
+        let mut info = TypeInfo::new_with_package(simple_name(fqn), package, class.artifact.as_str(), 0);
+        info.fqn = fqn.to_string();
+        for ancestor in self.ancestors(fqn) {
+            info.add_supertype(simple_name(&ancestor));
+        }
+        Some(info)
+    }
+}
+
+/// 把项目字段引用到的 classpath 类型注册进符号表
+///
+/// 已能在源码索引中解析的字段不受影响；字段绑定保持简单名 (DI 候选等按简单名匹配)。
+pub fn resolve_external(table: &mut SymbolTable, imports: &ImportIndexMap, index: &ClassIndex) -> ClasspathStats {
+    let mut stats = ClasspathStats {
+        entries: index.entries,
+        classes: index.len(),
+        errors: index.errors.len(),
+        ..Default::default()
+    };
+    let mut fields: Vec<(String, String, String)> = table.fields.iter()
+        .map(|((owner, name), binding)| (owner.clone(), name.clone(), binding.type_name.clone()))
+        .collect();
+    fields.sort();
+
+    let mut registered: HashSet<String> = HashSet::new();
+    for (owner, name, type_name) in fields {
+        let raw = type_name.split('<').next().unwrap_or(&type_name).trim();
+        if raw.is_empty() || raw.ends_with(']') || table.lookup_var_type(&owner, &name).is_some() {
+            continue;
+        }
+        let file_imports = table.lookup_by_simple_name(&owner).first()
+            .and_then(|info| imports.get(info.file.as_str()));
+        let Some(fqn) = index.resolve(raw, file_imports) else { continue };
+        if !table.classes.contains_key(fqn) {
+            let Some(info) = index.type_info(fqn) else { continue };
+            table.register_class_fqn(info);
+            registered.insert(fqn.to_string());
+        }
+        if table.lookup_var_type(&owner, &name).is_some() {
+            stats.resolved_fields += 1;
+        }
+    }
+    stats.external_types = registered.len();
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jar_scan::tests::{jar, ClassBuilder};
+    use crate::symbol_table::{LayerType, VarBinding};
+
+    fn write_jar(dir: &Path, name: &str, entries: &[(&str, Vec<u8>)]) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, jar(entries)).unwrap();
+        path
+    }
+
+    fn sample_jars(dir: &Path) -> Vec<PathBuf> {
+        let data = write_jar(dir, "acme-data.jar", &[
+            ("com/acme/data/OrderStore.class", ClassBuilder::default()
+                .extends("com/acme/data/BaseStore").build("com/acme/data/OrderStore")),
+            ("com/acme/data/BaseStore.class", ClassBuilder::default()
+                .implements("org/springframework/data/jpa/repository/JpaRepository").build("com/acme/data/BaseStore")),
+            ("com/acme/data/Outer$1.class", ClassBuilder::default().build("com/acme/data/Outer$1")),
+        ]);
+        let client = write_jar(dir, "acme-client.jar", &[
+            ("com/acme/client/PriceClient.class", ClassBuilder::default().build("com/acme/client/PriceClient")),
+            ("com/acme/other/PriceClient.class", ClassBuilder::default().build("com/acme/other/PriceClient")),
+        ]);
+        vec![data, client]
+    }
+
+    #[test]
+    fn test_parse_classpath_expands_wildcard() {
+        let dir = tempfile::tempdir().unwrap();
+        sample_jars(dir.path());
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        let spec = std::env::join_paths([dir.path().join("*"), dir.path().join("classes")]).unwrap();
+        let entries = parse_classpath(spec.to_str().unwrap());
+        let names: Vec<_> = entries.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, vec!["acme-client.jar", "acme-data.jar", "classes"]);
+    }
+
+    #[test]
+    fn test_index_resolves_imports_and_ancestors() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = ClassIndex::load(&sample_jars(dir.path())).unwrap();
+        assert_eq!(index.len(), 4);
+
+        assert_eq!(index.resolve("OrderStore", None), Some("com.acme.data.OrderStore"));
+        // 同名类：无 import 时不猜测，按 import 确定
+        assert_eq!(index.resolve("PriceClient", None), None);
+        let imports = ImportIndex {
+            explicit: HashMap::from([("PriceClient".to_string(), "com.acme.other.PriceClient".to_string())]),
+            ..Default::default()
+        };
+        assert_eq!(index.resolve("PriceClient", Some(&imports)), Some("com.acme.other.PriceClient"));
+        let wildcard = ImportIndex { wildcards: vec!["com.acme.client".to_string()], ..Default::default() };
+        assert_eq!(index.resolve("PriceClient", Some(&wildcard)), Some("com.acme.client.PriceClient"));
+
+        assert_eq!(index.ancestors("com.acme.data.OrderStore"), vec![
+            "com.acme.data.BaseStore".to_string(),
+            "org.springframework.data.jpa.repository.JpaRepository".to_string(),
+        ]);
+
+        assert!(ClassIndex::load(&[dir.path().join("missing.jar")]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_external_registers_field_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = ClassIndex::load(&sample_jars(dir.path())).unwrap();
+
+        let mut table = SymbolTable::new();
+        let service = TypeInfo::new_with_package("CheckoutService", Some("com.shop"), "CheckoutService.java", 1);
+        table.register_class_fqn(service);
+        table.register_field("CheckoutService", VarBinding::new("store", "OrderStore", true));
+        table.register_field("CheckoutService", VarBinding::new("prices", "PriceClient", true));
+        table.register_field("CheckoutService", VarBinding::new("names", "List<String>", true));
+        assert!(table.lookup_var_type("CheckoutService", "store").is_none());
+
+        let stats = resolve_external(&mut table, &ImportIndexMap::new(), &index);
+        assert_eq!((stats.resolved_fields, stats.external_types), (1, 1));
+
+        let store = table.lookup_var_type("CheckoutService", "store").unwrap();
+        assert_eq!(store.fqn, "com.acme.data.OrderStore");
+        assert_eq!(store.layer, LayerType::Repository);
+        assert!(store.is_dao());
+        assert!(store.file.ends_with("acme-data.jar"), "{:?}", store.file);
+        // 同名类无 import 可依：保持未解析
+        assert!(table.lookup_var_type("CheckoutService", "prices").is_none());
+    }
+
+    #[test]
+    fn test_classpath_raises_n_plus_one_confidence() {
+        use crate::ast_engine::{analyze_project, ScanOptions};
+
+        let project = tempfile::tempdir().unwrap();
+        let libs = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join("pom.xml"), "<project></project>").unwrap();
+        std::fs::write(project.path().join("CheckoutService.java"), r#"
+package com.shop;
+
+import com.acme.data.OrderStore;
+
+public class CheckoutService {
+    private OrderStore store;
+
+    public void settle(java.util.List<Long> ids) {
+        for (Long id : ids) {
+            store.findById(id);
+        }
+    }
+}
+"#).unwrap();
+        let root = project.path().to_str().unwrap();
+        let n_plus_one = |options: &ScanOptions| {
+            let report = analyze_project(root, options).unwrap();
+            let issue = report.scan.issues.iter().find(|i| i.issue_type == "N_PLUS_ONE").map(|i| i.context.clone());
+            (issue, report.classpath)
+        };
+
+        let (heuristic, stats) = n_plus_one(&ScanOptions::default());
+        let heuristic = heuristic.flatten().unwrap_or_default();
+        assert!(!heuristic.contains("高置信度"), "{heuristic}");
+        assert!(stats.is_none());
+
+        let options = ScanOptions { classpath: sample_jars(libs.path()), ..Default::default() };
+        let (resolved, stats) = n_plus_one(&options);
+        let resolved = resolved.flatten().unwrap_or_default();
+        assert!(resolved.contains("高置信度"), "{resolved}");
+        let stats = stats.unwrap();
+        assert_eq!((stats.entries, stats.resolved_fields, stats.external_types), (2, 1, 1));
+    }
+}
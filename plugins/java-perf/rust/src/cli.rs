@@ -3,12 +3,23 @@
 //! 提供命令行接口，默认输出人类可读格式
 //! 使用 --json 参数可输出 JSON 格式
 
-use crate::{ast_engine, checklist, forensic, jdk_engine};
+use crate::{agent_config, ast_engine, baseline, build_hook, checklist, classpath, corpus, daemon, explain, forensic, goldens, index_bench, inspect, jar_scan, jdk_engine, precommit, report, report_template, rule_bench, services, symbol_cache, usage_stats, verify, workspace};
+use crate::ast_engine::{GroupBy, IssueSort, ScanOptions};
+use crate::baseline::IssueState;
+use crate::dedup::DedupPolicy;
+use crate::entry_scope::EntryPoint;
+use crate::report::ExportFormat;
+use crate::rules::{pack, version};
+use crate::rules::registry::{RuleCategory, RuleFilter};
+use crate::workspace::{FetchOptions, RemoteSource};
 use anyhow::Result;
 use serde_json::{json, Value};
+use clap::builder::FalseyValueParser;
 use clap::Subcommand;
 
 /// CLI Commands
+// Scan 的参数远多于其他子命令；命令只解析一次并立即消费，不值得装箱
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug, Clone)]
 pub enum Command {
     /// 🛰️ 雷达扫描 - 全项目 AST 分析
@@ -18,12 +29,200 @@ pub enum Command {
         path: String,
 
         /// 显示完整结果（默认只显示 P0）
-        #[arg(long)]
+        #[arg(long, env = "JAVAPERF_FULL", value_parser = FalseyValueParser::new())]
         full: bool,
 
         /// 最多返回的 P1 数量 (--full 模式)
-        #[arg(long, default_value = "5")]
+        #[arg(long, env = "JAVAPERF_MAX_P1", default_value = "5")]
         max_p1: usize,
+
+        /// 问题排序: location (文件/行号) | hotness (离 Controller 入口越近越靠前) | recent (最近修改优先，需 --blame-age) | budget (延迟预算最紧优先，需 slo.yaml)
+        #[arg(long, env = "JAVAPERF_SORT", value_enum, default_value = "location")]
+        sort: IssueSort,
+
+        /// 重叠问题合并: group (同一行同类规则合并，默认) | line (同一行全部合并) | off
+        #[arg(long, env = "JAVAPERF_DEDUP", value_enum, default_value = "group")]
+        dedup: DedupPolicy,
+
+        /// 复用 .javaperf/symbols.bin 索引缓存 (只重新索引内容变化的文件)
+        #[arg(long, env = "JAVAPERF_CACHE", value_parser = FalseyValueParser::new())]
+        cache: bool,
+
+        /// 导出为标准格式: sarif | checkstyle | rdjson (严重级别名称取自 .javaperf.toml 的 [severity_map])
+        #[arg(long, env = "JAVAPERF_FORMAT", value_enum)]
+        format: Option<ExportFormat>,
+
+        /// 输出被抑制的问题 (JSON 中单独的 suppressed 列表，SARIF 中带 suppressions 的 result)，用于审计
+        #[arg(long, env = "JAVAPERF_INCLUDE_SUPPRESSED", value_parser = FalseyValueParser::new())]
+        include_suppressed: bool,
+
+        /// 只运行指定规则 (逗号分隔)，如 N_PLUS_ONE,EMPTY_CATCH
+        #[arg(long, env = "JAVAPERF_RULES", value_delimiter = ',')]
+        rules: Vec<String>,
+
+        /// 只运行指定类别的规则 (逗号分隔，与 --rules 取并集)
+        #[arg(long, env = "JAVAPERF_CATEGORIES", value_enum, value_delimiter = ',')]
+        categories: Vec<RuleCategory>,
+
+        /// 报告额外按维度汇总: owner (CODEOWNERS 负责人，JSON 中为 by_owner)
+        #[arg(long, env = "JAVAPERF_GROUP_BY", value_enum)]
+        group_by: Option<GroupBy>,
+
+        /// 扫描远程 git 仓库 (浅克隆到临时工作区，忽略 --path)
+        #[arg(long, conflicts_with = "archive")]
+        git: Option<String>,
+
+        /// --git 的分支/tag/commit (默认远端 HEAD)
+        #[arg(long = "ref", requires = "git")]
+        git_ref: Option<String>,
+
+        /// 扫描源码压缩包 (.zip/.tar/.tar.gz/.tgz，解压到临时工作区，忽略 --path)
+        #[arg(long)]
+        archive: Option<String>,
+
+        /// --git/--archive 工作区内容大小上限 (MB)
+        #[arg(long, env = "JAVAPERF_MAX_FETCH_MB", default_value_t = workspace::DEFAULT_MAX_MB)]
+        max_fetch_mb: u64,
+
+        /// 扫描结束后保留 --git/--archive 的临时工作区
+        #[arg(long)]
+        keep_workspace: bool,
+
+        /// 只报告基线之外的新问题 (基线见 `baseline update`；配置 [baseline] server 时从团队服务器拉取)
+        #[arg(long, env = "JAVAPERF_BASELINE", value_parser = FalseyValueParser::new())]
+        baseline: bool,
+
+        /// 只报告处于这些处理状态的问题 (逗号分隔: new,acknowledged,wont-fix,fixed；状态见 `baseline set`，基线之外的问题为 new)
+        #[arg(long = "state", env = "JAVAPERF_STATE", value_enum, value_delimiter = ',')]
+        states: Vec<IssueState>,
+
+        /// Monorepo: 识别可独立部署的服务 (spring-boot 等构建插件或 main 方法)，逐服务输出报告与技术栈
+        #[arg(long, conflicts_with = "format")]
+        per_service: bool,
+
+        /// 存在语法错误 (tree-sitter ERROR/MISSING 节点) 的 Java 文件时扫描失败，而不是降级分析
+        #[arg(long, env = "JAVAPERF_STRICT_PARSE", value_parser = FalseyValueParser::new())]
+        strict_parse: bool,
+
+        /// 编译产物 classpath (同 java -cp: jar、class 目录、lib/*)，读取 class 文件确认第三方字段类型，提高 N+1/阻塞调用的置信度
+        #[arg(long, env = "JAVAPERF_CLASSPATH")]
+        classpath: Option<String>,
+
+        /// 加载外部规则包目录 (.scm Query，可重复；相对路径相对扫描目录)，默认在沙箱中运行
+        #[arg(long = "rule-pack", value_name = "DIR")]
+        rule_packs: Vec<String>,
+
+        /// 信任规则包：允许读取扫描目录外的规则包，并取消 Query 复杂度与执行时间限制
+        #[arg(long)]
+        trusted: bool,
+
+        /// 解释单个位置的规则判定 (RULE_ID:file:line)：Query 匹配、符号解析、置信度与抑制，输出决策树而非报告
+        #[arg(long, value_name = "RULE_ID:FILE:LINE", conflicts_with_all = ["format", "per_service", "git", "archive", "template"])]
+        explain: Option<String>,
+
+        /// 只分析从该入口方法沿调用图可达的代码与相关配置 (类#方法，如 com.example.OrderController#create)
+        #[arg(long, value_name = "CLASS#METHOD", conflicts_with = "per_service")]
+        entry: Option<String>,
+
+        /// 对问题行执行 git blame 附加修改时间 (age)，超过 YEARS 年未修改的代码中的 P0 降为 P1
+        #[arg(long, env = "JAVAPERF_BLAME_AGE", value_name = "YEARS")]
+        blame_age: Option<u32>,
+
+        /// 用 Jinja2 模板 (minijinja) 渲染报告，上下文同 --json 输出并附 markdown；.html 模板自动转义
+        #[arg(long, env = "JAVAPERF_TEMPLATE", value_name = "FILE", conflicts_with_all = ["format", "per_service"])]
+        template: Option<String>,
+
+        /// 多根扫描 (代替 --path)：第一个为主根，其余根目录 (如单独 checkout 的共享库) 一起索引以跨根解析调用
+        #[arg(value_name = "ROOTS", conflicts_with_all = ["path", "per_service", "git", "archive", "explain"])]
+        roots: Vec<String>,
+
+        /// 只报告该根目录下的问题 (须为扫描根目录之一)，其余根目录只参与索引
+        #[arg(long, env = "JAVAPERF_REPORT_ROOT", value_name = "DIR", conflicts_with = "per_service")]
+        report_root: Option<String>,
+
+        /// 安全模式 (扫描下载的不可信仓库)：不执行 git/curl 等外部命令，限制单文件大小与解析/匹配耗时，
+        /// 跳过指向扫描目录之外的符号链接与构建文件路径
+        #[arg(long, env = "JAVAPERF_SAFE_MODE", value_parser = FalseyValueParser::new(), conflicts_with_all = ["git", "blame_age", "trusted"])]
+        safe_mode: bool,
+
+        /// 延迟预算文件 (默认读取扫描根目录下的 slo.yaml)：问题标注所在接口的最紧预算，报告按预算汇总
+        #[arg(long, env = "JAVAPERF_SLO", value_name = "FILE", conflicts_with = "per_service")]
+        slo: Option<String>,
+
+        /// 检查点文件：每完成一批文件即持久化分析结果，扫描中断 (超时/抢占) 后以相同参数重跑时跳过已完成的文件，成功后删除
+        #[arg(long, env = "JAVAPERF_CHECKPOINT", value_name = "FILE", conflicts_with = "per_service")]
+        checkpoint: Option<String>,
+
+        /// JaCoCo XML 报告 (如 target/site/jacoco/jacoco.xml)：标注问题所在方法是否被测试覆盖，单列未覆盖代码中的 P0
+        #[arg(long, env = "JAVAPERF_JACOCO", value_name = "FILE")]
+        jacoco: Option<String>,
+
+        /// 只报告未被测试覆盖的代码中的问题 (需 --jacoco)
+        #[arg(long, env = "JAVAPERF_ONLY_UNCOVERED", value_parser = FalseyValueParser::new(), requires = "jacoco")]
+        only_uncovered: bool,
+
+        /// HELD_LOCK_BLOCKING 从持锁区域内的调用沿调用图追踪的层数 (默认 3)
+        #[arg(long, env = "JAVAPERF_LOCK_DEPTH", value_name = "N")]
+        lock_depth: Option<usize>,
+
+        /// 收集提到性能的 TODO/FIXME/HACK 注释 (slow、N+1、临时等)，与同一方法中的问题一起报告
+        #[arg(long, env = "JAVAPERF_TODO_DEBT", value_parser = FalseyValueParser::new())]
+        todo_debt: bool,
+
+        /// 估算各方法的时间复杂度 (循环嵌套、递归、集合操作)，标出比同类其它方法高出一截的方法 (JSON complexity)
+        #[arg(long, env = "JAVAPERF_COMPLEXITY", value_parser = FalseyValueParser::new())]
+        complexity: bool,
+
+        /// 统计各规则在 Java 文件上的耗时 (JSON rule_timings，按累计耗时从高到低) 并累加到 .javaperf/rule-timings.json，之后的扫描按实测耗时安排规则执行顺序；不开启时分析过程不计时
+        #[arg(long, env = "JAVAPERF_RULE_TIMINGS", value_parser = FalseyValueParser::new())]
+        rule_timings: bool,
+
+        /// 工作线程数 (索引与分析)；默认取可用核数，容器设置了 cgroup CPU 配额时不超过配额 (JSON stats.parallelism)
+        #[arg(long, env = "JAVAPERF_JOBS", value_name = "N")]
+        jobs: Option<usize>,
+
+        /// 深度分析的入口类 (简单名/全限定名，可用 * 通配，逗号分隔)：沿调用图、静态初始化等跨文件分析只在可达类上进行；
+        /// 未指定时取 .javaperf.toml 的 [reachability] entrypoints
+        #[arg(long, env = "JAVAPERF_ENTRYPOINTS", value_delimiter = ',', value_name = "CLASS")]
+        entrypoints: Vec<String>,
+
+        /// 忽略入口配置，对全部文件执行跨文件深度分析
+        #[arg(long, env = "JAVAPERF_EXHAUSTIVE", value_parser = FalseyValueParser::new(), conflicts_with = "entrypoints")]
+        exhaustive: bool,
+    },
+
+    /// 🔁 修复验证 - 只重新分析上次报告中的问题文件
+    Verify {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// 上次 `--json scan` 输出的报告文件
+        #[arg(long)]
+        against: String,
+    },
+
+    /// 🪝 提交前检查 - 只分析 git 暂存区中的文件 (存在 P0 时以非零状态退出)
+    Precommit {
+        /// 仓库内任意路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// 写入 .git/hooks/pre-commit 而不是执行检查
+        #[arg(long)]
+        install: bool,
+
+        /// 覆盖已有的非 java-perf pre-commit hook (--install)
+        #[arg(long)]
+        force: bool,
+
+        /// hook 中调用的 java-perf 可执行文件 (--install)
+        #[arg(long, default_value = "java-perf")]
+        bin: String,
+
+        /// 重建 .javaperf/ 下的符号表缓存
+        #[arg(long)]
+        refresh_symbols: bool,
     },
 
     /// 🔍 单文件分析
@@ -82,23 +281,382 @@ pub enum Command {
         path: String,
     },
 
+    /// 🔧 输出构建工具接入片段 (exec-maven-plugin / Gradle Exec task)
+    PrintHook {
+        /// 构建工具
+        #[arg(value_enum)]
+        tool: build_hook::HookTool,
+
+        /// 项目路径 (用于识别多模块布局)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// 片段中调用的 java-perf 可执行文件
+        #[arg(long, default_value = "java-perf")]
+        bin: String,
+    },
+
+    /// 🧭 符号索引调试
+    Symbols {
+        #[command(subcommand)]
+        action: SymbolsCommand,
+    },
+
+    /// 🔎 查看类的元数据：字段解析、方法签名、调用关系与问题
+    Inspect {
+        #[command(subcommand)]
+        target: InspectCommand,
+    },
+
+    /// 📌 问题基线 - 记录已知问题，scan --baseline 只报告新问题
+    Baseline {
+        #[command(subcommand)]
+        action: BaselineCommand,
+    },
+
+    /// 🧪 运行时确认 - 为配套 agent 生成只对问题方法插桩的配置
+    Agent {
+        #[command(subcommand)]
+        action: AgentCommand,
+    },
+
+    /// 📦 依赖字节码扫描 - 无源码时从 jar/war/class 中识别已知的库级性能隐患
+    JarScan {
+        /// jar/war/ear、class 文件或包含它们的目录 (如 target/lib)
+        path: String,
+    },
+
+    /// 🛠️ 规则作者工具
+    Dev {
+        #[command(subcommand)]
+        action: DevCommand,
+    },
+
+    /// 📏 规则维护
+    Rules {
+        #[command(subcommand)]
+        action: RulesCommand,
+    },
+
+    /// 🛰️ 常驻进程 - 项目索引常驻内存，经 unix socket 以 JSON-RPC 2.0 应答
+    /// analyze_file / query_symbol / list_rules / rescan / status / shutdown
+    Daemon {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// socket 路径 (默认 <项目>/.javaperf/daemon.sock)
+        #[arg(long)]
+        socket: Option<String>,
+    },
+
+    /// 📈 本地使用统计 (`.javaperf.toml` 中 `[stats] enabled = true` 后由 scan 记录，只写本地文件)
+    Stats {
+        #[command(subcommand)]
+        action: StatsCommand,
+    },
+
     /// ℹ️ 引擎状态
     Status,
 }
 
+/// `stats` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum StatsCommand {
+    /// 汇总最近几次扫描的耗时变化与各规则的命中趋势
+    Show {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// 汇总的扫描次数
+        #[arg(long, default_value_t = 20)]
+        last: usize,
+    },
+}
+
+/// `dev` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum DevCommand {
+    /// 生成各规则的正例/反例 Java 语料与 corpus.json 清单 (同一种子输出相同)
+    GenCorpus {
+        /// 输出目录
+        #[arg(short, long, default_value = "corpus")]
+        out: String,
+
+        /// 随机种子
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+
+        /// 每个规则的正例/反例各生成几个变体
+        #[arg(long, default_value_t = 3)]
+        variants: usize,
+
+        /// 只生成指定规则 (逗号分隔)
+        #[arg(long, value_delimiter = ',')]
+        rules: Vec<String>,
+
+        /// 生成后用内置规则校验，正例未命中或反例命中时以非零码退出
+        #[arg(long)]
+        check: bool,
+    },
+    /// 生成合成项目，对比符号表两两合并与分片并发构建的耗时
+    BenchIndex {
+        /// 合成 Java 文件数
+        #[arg(long, default_value_t = 50_000)]
+        files: usize,
+
+        /// 语料目录 (默认系统临时目录，结束后删除)
+        #[arg(long)]
+        dir: Option<String>,
+    },
+    /// 对比注册表顺序、估计/实测代价顺序、开启规则计时与按类别排除后跳过文件的分析耗时 (多轮交替执行取中位数)
+    BenchRules {
+        /// 合成 Java 文件数
+        #[arg(long, default_value_t = 2_000)]
+        files: usize,
+
+        /// 语料目录 (默认系统临时目录，结束后删除)
+        #[arg(long)]
+        dir: Option<String>,
+    },
+}
+
+/// `rules` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum RulesCommand {
+    /// 比对各规则 golden fixture 的 Query 匹配范围与报告位置 (升级 tree-sitter 语法后运行)，不符时以非零码退出
+    VerifyGoldens {
+        /// golden 目录 (<RULE_ID>/<case>.java + <case>.golden)
+        #[arg(short, long, default_value = goldens::DEFAULT_DIR)]
+        dir: String,
+
+        /// 只比对指定规则 (逗号分隔)
+        #[arg(long, value_delimiter = ',')]
+        rules: Vec<String>,
+
+        /// 把实际结果写为期望文件 (确认变化符合预期后、或新增 fixture 时使用)
+        #[arg(long)]
+        bless: bool,
+    },
+    /// 列出从旧规则包版本到当前版本之间新增、移除与变更的规则 (区分报告差异来自代码还是规则)
+    Changelog {
+        /// 旧报告中的规则包版本 (JSON stats.rules.pack，可只写前缀) 或工具版本 (如 9.4.0)
+        #[arg(long)]
+        from: String,
+    },
+}
+
+/// `agent` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum AgentCommand {
+    /// 扫描项目，把指定规则的问题按所在方法写为 ByteBuddy agent 配置
+    Gen {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// 只导出指定规则的问题 (逗号分隔，如 N_PLUS_ONE,FLUX_BLOCK；默认全部规则)
+        #[arg(long, value_delimiter = ',')]
+        rules: Vec<String>,
+
+        /// 配置输出文件
+        #[arg(short, long, default_value = "agent-config.json")]
+        out: String,
+    },
+}
+
+/// `baseline` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum BaselineCommand {
+    /// 以当前全量扫描结果重写基线 (配置服务器时同时推送)
+    Update {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// 用团队服务器上的基线覆盖本地 .javaperf/baseline.json
+    Pull {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// 把本地基线推送到团队服务器
+    Push {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// 修改基线中单个问题的处理状态、负责人与备注 (配置服务器时同时推送)
+    Set {
+        /// 问题指纹 (JSON 报告的 fingerprint)
+        fingerprint: String,
+
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// 处理状态
+        #[arg(long, value_enum)]
+        state: Option<IssueState>,
+
+        /// 负责人 (空字符串清除)
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// 备注 (空字符串清除)
+        #[arg(long)]
+        notes: Option<String>,
+    },
+}
+
+/// `symbols` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum SymbolsCommand {
+    /// 输出类在索引中的解析结果 (字段绑定、import、调用边)；不指定 --class 时输出概况
+    Dump {
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// 类的简单名或 FQN
+        #[arg(long)]
+        class: Option<String>,
+
+        /// 忽略已有缓存全部重建
+        #[arg(long)]
+        refresh: bool,
+    },
+}
+
+/// `inspect` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum InspectCommand {
+    /// 按简单名或 FQN 查看类
+    Class {
+        /// 类的简单名或 FQN
+        name: String,
+
+        /// 项目路径
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+}
+
 /// 处理 CLI 命令
 ///
 /// json_output: 是否输出 JSON 格式（默认 false，输出人类可读格式）
 pub fn handle_command(cmd: Command, json_output: bool) -> Result<()> {
+    // 输出结果后使用的退出码 (precommit 阻止提交)
+    let mut exit_code = 0;
     let result = match cmd {
-        Command::Scan { path, full, max_p1 } => {
-            // full=false means compact=true (default)
-            ast_engine::radar_scan(&path, !full, max_p1)
+        Command::Scan { path, explain: Some(spec), .. } => {
+            explain::explain(&path, &spec).map(|explanation| explain::render(&explanation, json_output))
+        }
+
+        Command::Scan {
+            path, full, max_p1, sort, dedup, cache, format, include_suppressed, rules, categories, group_by,
+            git, git_ref, archive, max_fetch_mb, keep_workspace, baseline, states, per_service,
+            strict_parse, classpath, rule_packs, trusted, explain: None, entry, blame_age, template,
+            roots, report_root, safe_mode, slo, checkpoint, jacoco, only_uncovered, lock_depth, todo_debt, complexity,
+            rule_timings, jobs, entrypoints, exhaustive,
+        } => {
+            // 参数校验、拉取与规则包加载的错误同样走下方统一的错误输出 (JSON 模式为 success=false)
+            let scan = || -> Result<Value, Box<dyn std::error::Error>> {
+                let rules = RuleFilter::new(&rules, &categories)?;
+                let mut options = ScanOptions {
+                    sort, dedup, cache, include_suppressed, rules, group_by, baseline, states, strict_parse, blame_age,
+                    classpath: classpath.as_deref().map(classpath::parse_classpath).unwrap_or_default(),
+                    entry: entry.as_deref().map(EntryPoint::parse).transpose()?,
+                    extra_roots: roots.iter().skip(1).map(std::path::PathBuf::from).collect(),
+                    report_root: report_root.map(std::path::PathBuf::from),
+                    safe_mode,
+                    slo: slo.map(std::path::PathBuf::from),
+                    checkpoint: checkpoint.map(std::path::PathBuf::from),
+                    jacoco: jacoco.map(std::path::PathBuf::from),
+                    only_uncovered,
+                    lock_depth,
+                    todo_debt,
+                    complexity,
+                    rule_timings,
+                    jobs,
+                    entrypoints,
+                    exhaustive,
+                    ..Default::default()
+                };
+                let path = roots.into_iter().next().unwrap_or(path);
+                let source = match (git, archive) {
+                    (Some(url), _) => Some(RemoteSource::Git { url, reference: git_ref }),
+                    (None, Some(archive)) => Some(RemoteSource::Archive(archive.into())),
+                    (None, None) => None,
+                };
+                let fetch_options = FetchOptions { max_bytes: max_fetch_mb.saturating_mul(1024 * 1024), keep: keep_workspace };
+                // 工作区需存活到扫描结束
+                let fetched = source.map(|s| workspace::fetch(&s, fetch_options)).transpose()?;
+                let path = fetched.as_ref().map_or(path, |w| w.root().to_string_lossy().into_owned());
+                if !rule_packs.is_empty() {
+                    let root = std::path::Path::new(&path);
+                    let sandbox = pack::Sandbox::new(root, trusted)?;
+                    let dirs: Vec<std::path::PathBuf> = rule_packs.iter().map(|dir| root.join(dir)).collect();
+                    options.custom_rules = pack::load(&dirs, &sandbox)?;
+                }
+                if per_service {
+                    services::analyze_services(&path, &options).map(|(reports, shared)| {
+                        if json_output {
+                            services::render_json(&reports, &shared, &options)
+                        } else {
+                            services::render_report(&reports, &shared, !full, max_p1, &options)
+                        }
+                    })
+                } else {
+                    // 所有输出格式共用同一份分析结果
+                    ast_engine::analyze_project(&path, &options).and_then(|analysis| {
+                        // 临时工作区扫描完即删除，不记录
+                        if fetched.is_none() {
+                            if let Err(e) = usage_stats::record(std::path::Path::new(&path), &analysis) {
+                                tracing::warn!("写入使用统计失败: {e}");
+                            }
+                        }
+                        Ok(if let Some(template) = &template {
+                            json!(report_template::render(std::path::Path::new(template), &analysis, !full, max_p1, &options)?)
+                        } else if let Some(format) = format {
+                            report::export(&analysis.scan, format)
+                        } else if json_output {
+                            // 结构化输出，可作为 verify --against 的输入
+                            ast_engine::render_json(&analysis, &options)
+                        } else {
+                            // full=false means compact=true (default)
+                            ast_engine::render_report(&analysis, !full, max_p1, &options)
+                        })
+                    })
+                }
+            };
+            scan()
+        }
+
+        Command::Verify { path, against } => {
+            verify::verify_against(&path, &against)
+                .map(|result| verify::render(&result, json_output))
+        }
+
+        Command::Precommit { path, install, force, bin, refresh_symbols } => {
+            if install {
+                precommit::install(&path, &bin, force)
+                    .map(|hook| json!(format!("✅ 已写入 {}", hook.display())))
+            } else {
+                precommit::run(&path, refresh_symbols).map(|result| {
+                    if result.blocks_commit() {
+                        exit_code = 1;
+                    }
+                    precommit::render(&result, json_output)
+                })
+            }
         }
 
         Command::Analyze { file } => {
-            let content = std::fs::read_to_string(&file)?;
-            ast_engine::scan_source_code(&content, &file)
+            crate::encoding::read_source(std::path::Path::new(&file))
+                .map_err(|e| e.into())
+                .and_then(|source| ast_engine::scan_source_code(&source.content, &file))
         }
 
         Command::Checklist { symptoms, full } => {
@@ -130,6 +688,88 @@ pub fn handle_command(cmd: Command, json_output: bool) -> Result<()> {
             get_project_summary(&path, json_output)
         }
 
+        Command::PrintHook { tool, path, bin } => {
+            let hook = build_hook::generate(tool, std::path::Path::new(&path), &bin);
+            Ok(build_hook::render(&hook, json_output))
+        }
+
+        Command::Symbols { action: SymbolsCommand::Dump { path, class, refresh } } => {
+            symbol_cache::dump(&path, class.as_deref(), refresh)
+                .map(|dump| symbol_cache::render_dump(&dump, json_output))
+        }
+
+        Command::Inspect { target: InspectCommand::Class { name, path } } => {
+            inspect::inspect_class(&path, &name)
+                .map(|result| inspect::render(&result, json_output))
+        }
+
+        Command::Baseline { action } => {
+            let outcome = match action {
+                BaselineCommand::Update { path } => baseline::update(&path),
+                BaselineCommand::Pull { path } => baseline::pull(&path),
+                BaselineCommand::Push { path } => baseline::push(&path),
+                BaselineCommand::Set { fingerprint, path, state, assignee, notes } => {
+                    baseline::set(&path, &fingerprint, state, assignee, notes)
+                }
+            };
+            outcome.map(|outcome| baseline::render(&outcome, json_output))
+        }
+
+        Command::Agent { action: AgentCommand::Gen { path, rules, out } } => {
+            agent_config::generate(&path, &rules, std::path::Path::new(&out))
+                .map(|outcome| agent_config::render(&outcome, json_output))
+        }
+
+        Command::JarScan { path } => {
+            jar_scan::jar_scan(&path).map(|result| jar_scan::render(&result, json_output))
+        }
+
+        Command::Dev { action: DevCommand::GenCorpus { out, seed, variants, rules, check } } => {
+            let options = corpus::CorpusOptions { seed, variants, rules };
+            corpus::gen_corpus(&out, &options, check).map(|outcome| {
+                if outcome.failed() {
+                    exit_code = 1;
+                }
+                corpus::render(&outcome, json_output)
+            })
+        }
+
+        Command::Dev { action: DevCommand::BenchIndex { files, dir } } => {
+            let options = index_bench::BenchOptions { files, dir: dir.map(Into::into) };
+            index_bench::run(&options).map(|result| index_bench::render(&result, json_output))
+        }
+
+        Command::Dev { action: DevCommand::BenchRules { files, dir } } => {
+            let options = rule_bench::BenchOptions { files, dir: dir.map(Into::into) };
+            rule_bench::run(&options).map(|result| rule_bench::render(&result, json_output))
+        }
+
+        Command::Rules { action: RulesCommand::VerifyGoldens { dir, rules, bless } } => {
+            goldens::verify(std::path::Path::new(&dir), &rules, bless).map(|outcome| {
+                if outcome.failed() {
+                    exit_code = 1;
+                }
+                goldens::render(&outcome, json_output)
+            })
+        }
+
+        Command::Rules { action: RulesCommand::Changelog { from } } => {
+            version::changelog(&from)
+                .map(|changelog| version::render(&changelog, json_output))
+                .map_err(Into::into)
+        }
+
+        Command::Daemon { path, socket } => {
+            let socket = socket.map_or_else(|| daemon::default_socket(std::path::Path::new(&path)), std::path::PathBuf::from);
+            daemon::Daemon::open(&path)
+                .and_then(|state| daemon::serve(std::sync::Arc::new(state), &socket))
+                .map(|requests| json!(format!("🛰️ daemon 已停止 (共处理 {requests} 个请求)")))
+        }
+
+        Command::Stats { action: StatsCommand::Show { path, last } } => {
+            usage_stats::show(&path, last).map(|summary| usage_stats::render(&summary, json_output))
+        }
+
         Command::Status => {
             let version = env!("CARGO_PKG_VERSION");
             let status = json!({
@@ -188,12 +828,15 @@ pub fn handle_command(cmd: Command, json_output: bool) -> Result<()> {
                 });
                 println!("{}", serde_json::to_string_pretty(&output)?);
             } else {
-                println!("✅ Engine Status: ACTIVE (v8.0.0 Deep Semantic)");
+                eprintln!("❌ {e}");
             }
             std::process::exit(1);
         }
     }
 
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
     Ok(())
 }
 
@@ -0,0 +1,224 @@
+//! CODEOWNERS 负责人映射
+//!
+//! 读取扫描根目录下的 GitHub CODEOWNERS (`.github/`、根目录、`docs/` 依次查找)，
+//! 按路径为每个问题附加负责人，`scan --group-by owner` 时按负责人汇总，
+//! 便于大型组织直接从 JSON 输出分派整改。
+//!
+//! 匹配语义与 GitHub 一致:
+//! - 后出现的规则优先 (最后一条匹配的规则生效)
+//! - 以 `/` 开头或中间含 `/` 的模式相对仓库根锚定，否则匹配任意层级
+//! - `*` 不跨目录，`**` 跨目录；目录模式匹配其下全部文件，但 `docs/*` 只匹配直接子文件
+//! - 没有负责人的规则表示"无人负责"，会覆盖之前的匹配
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::diagnostics;
+
+/// CODEOWNERS 的查找位置 (与 GitHub 的优先顺序一致)
+pub const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// 无匹配负责人时的汇总键
+pub const UNOWNED: &str = "(unowned)";
+
+/// 解析后的 CODEOWNERS
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<(GlobSet, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// 解析 CODEOWNERS 内容 (无效模式的行被跳过，与 GitHub 行为一致)
+    pub fn parse(content: &str) -> Self {
+        let mut rules = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut tokens = line.split_whitespace();
+            let Some(pattern) = tokens.next() else { continue };
+            let owners: Vec<String> = tokens
+                .take_while(|t| !t.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+            match compile_pattern(pattern) {
+                Some(set) => rules.push((set, owners)),
+                None => diagnostics::warn(format!("CODEOWNERS 第 {} 行模式无效: {pattern}", idx + 1)),
+            }
+        }
+        Self { rules }
+    }
+
+    /// 读取 root 下的 CODEOWNERS (不存在时返回 None)
+    pub fn load(root: &Path) -> Option<Self> {
+        CODEOWNERS_PATHS.iter()
+            .map(|rel| root.join(rel))
+            .find(|p| p.is_file())
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|content| Self::parse(&content))
+    }
+
+    /// `rel_path` (相对仓库根，`/` 分隔) 的负责人
+    pub fn owners_of(&self, rel_path: &str) -> &[String] {
+        self.rules.iter()
+            .rev()
+            .find(|(set, _)| set.is_match(rel_path))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// 为问题附加负责人
+    pub fn annotate(&self, issues: &mut [AstIssue]) {
+        for issue in issues {
+            issue.owners = self.owners_of(issue.path.as_str()).to_vec();
+        }
+    }
+}
+
+/// CODEOWNERS 模式 → glob 集合
+fn compile_pattern(pattern: &str) -> Option<GlobSet> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    let base = match (trimmed.is_empty(), anchored) {
+        // "/" 匹配整个仓库
+        (true, _) => "**".to_string(),
+        (false, true) => trimmed.to_string(),
+        (false, false) => format!("**/{trimmed}"),
+    };
+
+    let mut globs = Vec::new();
+    if !dir_only {
+        globs.push(base.clone());
+    }
+    // 目录模式匹配其下全部文件；以 * 结尾的模式 (docs/*) 只匹配直接子文件
+    if dir_only || !base.ends_with('*') {
+        globs.push(format!("{base}/**"));
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(GlobBuilder::new(&glob).literal_separator(true).build().ok()?);
+    }
+    builder.build().ok()
+}
+
+/// 单个负责人的问题汇总
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct OwnerSummary {
+    pub owner: String,
+    pub p0: usize,
+    pub p1: usize,
+    /// 规则 ID → 问题数
+    pub rules: BTreeMap<String, usize>,
+    /// 涉及的文件 (相对路径)
+    pub files: Vec<String>,
+}
+
+/// 按负责人汇总 (多负责人的问题计入每个负责人；P0 多的排在前面)
+pub fn group_by_owner(issues: &[AstIssue]) -> Vec<OwnerSummary> {
+    let mut groups: BTreeMap<&str, OwnerSummary> = BTreeMap::new();
+    for issue in issues {
+        let owners: Vec<&str> = if issue.owners.is_empty() {
+            vec![UNOWNED]
+        } else {
+            issue.owners.iter().map(String::as_str).collect()
+        };
+        for owner in owners {
+            let summary = groups.entry(owner).or_insert_with(|| OwnerSummary {
+                owner: owner.to_string(),
+                p0: 0,
+                p1: 0,
+                rules: BTreeMap::new(),
+                files: Vec::new(),
+            });
+            match issue.severity {
+                Severity::P0 => summary.p0 += 1,
+                Severity::P1 => summary.p1 += 1,
+            }
+            *summary.rules.entry(issue.issue_type.clone()).or_default() += 1;
+            if !summary.files.iter().any(|file| issue.path == *file) {
+                summary.files.push(issue.path.to_string());
+            }
+        }
+    }
+    let mut summaries: Vec<OwnerSummary> = groups.into_values().collect();
+    summaries.sort_by_key(|s| std::cmp::Reverse((s.p0, s.p1)));
+    summaries
+}
+
+/// Markdown 报告中的负责人汇总段落
+pub fn render_section(summaries: &[OwnerSummary], p0_label: &str, p1_label: &str) -> String {
+    if summaries.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from("\n### 👥 按负责人汇总\n\n");
+    for summary in summaries {
+        let rules: Vec<String> = summary.rules.iter().map(|(id, n)| format!("{id} × {n}")).collect();
+        section.push_str(&format!(
+            "- **{}** - {p0_label}: {}, {p1_label}: {}, {} 个文件 ({})\n",
+            summary.owner, summary.p0, summary.p1, summary.files.len(), rules.join(", ")
+        ));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = r#"
+# 默认负责人
+*                       @org/platform
+*.yml                   @org/sre
+/order/                 @org/order @alice   # 订单域
+docs/*                  @org/docs
+**/generated/**
+/order/legacy/Old.java  @bob
+"#;
+
+    #[test]
+    fn test_owners_of_follows_github_semantics() {
+        let owners = CodeOwners::parse(CODEOWNERS);
+        assert_eq!(owners.owners_of("src/Main.java"), ["@org/platform"]);
+        assert_eq!(owners.owners_of("config/application.yml"), ["@org/sre"]);
+        assert_eq!(owners.owners_of("order/src/OrderService.java"), ["@org/order", "@alice"]);
+        // 锚定模式不匹配其它层级的同名目录
+        assert_eq!(owners.owners_of("app/order/X.java"), ["@org/platform"]);
+        // docs/* 只匹配直接子文件
+        assert_eq!(owners.owners_of("docs/guide.md"), ["@org/docs"]);
+        assert_eq!(owners.owners_of("docs/api/ref.md"), ["@org/platform"]);
+        // 无负责人的规则覆盖之前的匹配
+        assert!(owners.owners_of("order/generated/Dto.java").is_empty());
+        assert_eq!(owners.owners_of("order/legacy/Old.java"), ["@bob"]);
+    }
+
+    #[test]
+    fn test_group_by_owner() {
+        let owners = CodeOwners::parse(CODEOWNERS);
+        let issue = |id: &str, severity: Severity, path: &str| {
+            let mut issue: AstIssue = serde_json::from_value(serde_json::json!({
+                "severity": severity, "id": id, "file": "", "path": path, "line": 1, "description": ""
+            })).unwrap();
+            issue.owners = owners.owners_of(path).to_vec();
+            issue
+        };
+        let issues = vec![
+            issue("N_PLUS_ONE", Severity::P0, "order/OrderService.java"),
+            issue("EMPTY_CATCH", Severity::P1, "order/OrderService.java"),
+            issue("EMPTY_CATCH", Severity::P1, "src/Main.java"),
+            issue("EMPTY_CATCH", Severity::P1, "order/generated/Dto.java"),
+        ];
+        let summaries = group_by_owner(&issues);
+        let keys: Vec<(&str, usize, usize)> = summaries.iter().map(|s| (s.owner.as_str(), s.p0, s.p1)).collect();
+        assert_eq!(keys, vec![("@alice", 1, 1), ("@org/order", 1, 1), ("(unowned)", 0, 1), ("@org/platform", 0, 1)]);
+        assert_eq!(summaries[0].files, vec!["order/OrderService.java"]);
+        assert_eq!(summaries[0].rules.get("EMPTY_CATCH"), Some(&1));
+    }
+}
@@ -0,0 +1,473 @@
+//! 方法复杂度估算 (`scan --complexity`)
+//!
+//! 为每个方法粗估时间复杂度，按类汇总，找出比同类其它方法高出一截的方法 (复杂度突增)。
+//! 只作为参考信息输出 (JSON `complexity`，`--template` 生成的 HTML 报告同样可用)，不产生问题。
+//!
+//! 估算只看方法体本身，n 统一表示"输入规模":
+//!
+//! - 循环 (for / 增强 for / while / do) 与迭代型调用的 lambda (`forEach`、`map`、`filter` 等) 每嵌套一层乘 n；
+//!   上界是不超过 [`SMALL_BOUND`] 的字面量的计数循环视为常数
+//! - `stream()`、对 List/Collection 类型变量的 `contains`/`indexOf`/`remove` 等线性查找记 n，排序记 n log n，
+//!   与所在的循环层数相乘
+//! - 递归取自调用图中的自环 (同一方法调用自身)：一处自调用乘 n，两处及以上视为分支递归 O(2^n)
+//!
+//! 集合类型只从同一文件中的字段、参数与局部变量声明推断，声明类型未知的接收者不计入。
+//! 二分、分治等递归会被高估，结果只用于发现异常值，不代表精确的复杂度。
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::scanner::node_walk::NodeWalk;
+use crate::taint::{CallGraph, MethodSig};
+
+/// 视为常数次的计数循环上界
+pub const SMALL_BOUND: u64 = 16;
+
+/// 比同类其它方法 (下中位数) 高出的权重达到该值时视为突增 (两个 n 因子，如 O(1) → O(n^2))
+const SPIKE_MARGIN: u32 = 4;
+
+/// 判断突增时同类中至少需要的其它方法数
+const MIN_SIBLINGS: usize = 2;
+
+const LOOP_KINDS: &[&str] = &["for_statement", "enhanced_for_statement", "while_statement", "do_statement"];
+
+/// lambda 参数按元素执行的调用
+const ITERATING_CALLS: &[&str] = &[
+    "forEach", "forEachOrdered", "removeIf", "replaceAll", "map", "filter", "flatMap", "mapToInt", "mapToLong",
+    "mapToObj", "peek", "anyMatch", "allMatch", "noneMatch", "takeWhile", "dropWhile",
+];
+
+/// 本身遍历一次集合的调用 (与接收者类型无关)
+const TRAVERSAL_CALLS: &[&str] = &["stream", "parallelStream", "forEach", "removeIf", "replaceAll"];
+
+/// 在线性结构上逐个比较的查找
+const LINEAR_LOOKUPS: &[&str] = &["contains", "indexOf", "lastIndexOf", "remove", "containsAll", "removeAll", "retainAll"];
+
+/// 线性查找的接收者类型
+const LINEAR_TYPES: &[&str] = &["List", "ArrayList", "LinkedList", "Collection", "Queue", "Deque", "ArrayDeque", "Vector"];
+
+const SORT_CALLS: &[&str] = &["sort", "sorted", "parallelSort"];
+
+/// 复杂度的阶: O(2^n) > O(n^k log n) > O(n^k)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BigO {
+    pub exponential: bool,
+    /// n 的次数
+    pub poly: u8,
+    /// 是否带 log n 因子
+    pub log: bool,
+}
+
+impl BigO {
+    /// 用于比较同类方法的权重 (每个 n 因子 2，log n 因子 1)
+    fn weight(self) -> u32 {
+        if self.exponential {
+            u32::from(u8::MAX)
+        } else {
+            u32::from(self.poly) * 2 + u32::from(self.log)
+        }
+    }
+}
+
+impl fmt::Display for BigO {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let term = match (self.exponential, self.poly, self.log) {
+            (true, _, _) => "2^n".to_string(),
+            (false, 0, false) => "1".to_string(),
+            (false, 0, true) => "log n".to_string(),
+            (false, 1, log) => format!("n{}", if log { " log n" } else { "" }),
+            (false, k, log) => format!("n^{k}{}", if log { " log n" } else { "" }),
+        };
+        write!(f, "O({term})")
+    }
+}
+
+/// 单个方法的估算 (Phase 2 逐文件收集，尚未计入递归)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodEstimate {
+    /// 相对路径
+    pub path: String,
+    /// 所在类型的全限定名
+    pub class: String,
+    pub method: String,
+    pub line: usize,
+    pub order: BigO,
+    /// 决定复杂度的位置，如 "第 12 行: 2 层嵌套循环"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+}
+
+/// 报告中的单个方法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MethodComplexity {
+    pub method: String,
+    pub line: usize,
+    /// 如 `O(n^2)`
+    pub estimate: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub drivers: Vec<String>,
+    /// 比同类其它方法的中位数高出至少两个 n 因子
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub spike: bool,
+    #[serde(skip)]
+    order: BigO,
+}
+
+/// 按类汇总 (只列出非常数复杂度的方法)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClassComplexity {
+    pub class: String,
+    pub path: String,
+    /// 类中最高的估算
+    pub worst: String,
+    /// 类中的方法总数 (含常数复杂度)
+    pub total_methods: usize,
+    pub methods: Vec<MethodComplexity>,
+}
+
+impl ClassComplexity {
+    pub fn spikes(&self) -> usize {
+        self.methods.iter().filter(|m| m.spike).count()
+    }
+}
+
+/// 收集文件中各方法的估算
+pub fn collect(root: Node, code: &str, rel_path: &str) -> Vec<MethodEstimate> {
+    let types = declared_types(root, code);
+    let walk = NodeWalk::new(code);
+    let mut estimates = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "method_declaration" | "constructor_declaration") {
+            let start_line = node.start_position().row + 1;
+            let fqn = walk.enclosing_method_fqn(node);
+            if let (Some((class, method)), Some(body)) = (fqn.as_deref().and_then(|f| f.rsplit_once('.')), node.child_by_field_name("body")) {
+                let mut walker = Walker { code, types: &types, worst: None };
+                walker.visit(body, 0);
+                let (order, driver) = walker.worst.unwrap_or_default();
+                estimates.push(MethodEstimate {
+                    path: rel_path.to_string(),
+                    class: class.to_string(),
+                    method: method.to_string(),
+                    line: start_line,
+                    order,
+                    driver: (order != BigO::default()).then_some(driver),
+                });
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    estimates.sort_by_key(|e| e.line);
+    estimates
+}
+
+/// 计入递归后按类汇总，有突增的类在前，其次按最高估算降序
+pub fn summarize(estimates: Vec<MethodEstimate>, graph: &CallGraph) -> Vec<ClassComplexity> {
+    let mut classes: BTreeMap<(String, String), Vec<MethodComplexity>> = BTreeMap::new();
+    for estimate in estimates {
+        let sig = MethodSig::new_fqn(&estimate.class, &estimate.method);
+        let self_calls = graph.outgoing.get(&sig)
+            .map_or(0, |sites| sites.iter().filter(|site| site.callee == sig).count());
+        let mut order = estimate.order;
+        let mut drivers: Vec<String> = estimate.driver.into_iter().collect();
+        match self_calls {
+            0 => {}
+            1 => {
+                order.poly = order.poly.saturating_add(1);
+                drivers.push("递归调用自身 (每层乘 n)".to_string());
+            }
+            calls => {
+                order.exponential = true;
+                drivers.push(format!("分支递归 ({calls} 处自调用)"));
+            }
+        }
+        let method = MethodComplexity {
+            method: estimate.method,
+            line: estimate.line,
+            estimate: order.to_string(),
+            drivers,
+            spike: false,
+            order,
+        };
+        classes.entry((estimate.class, estimate.path)).or_default().push(method);
+    }
+
+    let mut summaries: Vec<ClassComplexity> = classes.into_iter()
+        .filter_map(|((class, path), mut methods)| {
+            mark_spikes(&mut methods);
+            let total_methods = methods.len();
+            let worst = methods.iter().map(|m| m.order).max().unwrap_or_default();
+            methods.retain(|m| m.order != BigO::default());
+            (!methods.is_empty()).then(|| ClassComplexity { class, path, worst: worst.to_string(), total_methods, methods })
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        let worst = |c: &ClassComplexity| c.methods.iter().map(|m| m.order).max().unwrap_or_default();
+        b.spikes().cmp(&a.spikes())
+            .then_with(|| worst(b).cmp(&worst(a)))
+            .then_with(|| a.class.cmp(&b.class))
+    });
+    summaries
+}
+
+/// 与同类其它方法权重的下中位数比较
+fn mark_spikes(methods: &mut [MethodComplexity]) {
+    if methods.len() <= MIN_SIBLINGS {
+        return;
+    }
+    let weights: Vec<u32> = methods.iter().map(|m| m.order.weight()).collect();
+    for (index, method) in methods.iter_mut().enumerate() {
+        let mut others: Vec<u32> = weights.iter().enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, w)| *w)
+            .collect();
+        others.sort_unstable();
+        let median = others[(others.len() - 1) / 2];
+        method.spike = weights[index] >= median + SPIKE_MARGIN;
+    }
+}
+
+/// 文件中字段、参数与局部变量的声明类型 (去掉泛型参数的简单名)
+fn declared_types(root: Node, code: &str) -> HashMap<String, String> {
+    let mut types = HashMap::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "field_declaration" | "local_variable_declaration" | "formal_parameter") {
+            if let Some(ty) = node.child_by_field_name("type").map(|t| raw_type(text(t, code))) {
+                let mut cursor = node.walk();
+                let names = node.children(&mut cursor)
+                    .filter_map(|child| match child.kind() {
+                        "variable_declarator" => child.child_by_field_name("name"),
+                        "identifier" => Some(child),
+                        _ => None,
+                    });
+                for name in names {
+                    types.insert(text(name, code).to_string(), ty.to_string());
+                }
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    types
+}
+
+fn raw_type(ty: &str) -> &str {
+    let raw = ty.split('<').next().unwrap_or(ty).trim();
+    raw.rsplit('.').next().unwrap_or(raw)
+}
+
+fn text<'a>(node: Node, code: &'a str) -> &'a str {
+    node.utf8_text(code.as_bytes()).unwrap_or("")
+}
+
+fn line_of(node: Node) -> usize {
+    node.start_position().row + 1
+}
+
+/// 计数循环的上界是小字面量: `for (int i = 0; i < 3; i++)`
+fn constant_bound(node: Node, code: &str) -> bool {
+    if node.kind() != "for_statement" {
+        return false;
+    }
+    let Some(condition) = node.child_by_field_name("condition").filter(|c| c.kind() == "binary_expression") else {
+        return false;
+    };
+    let operator = condition.child_by_field_name("operator").map_or("", |op| text(op, code));
+    let right = condition.child_by_field_name("right");
+    matches!(operator, "<" | "<=")
+        && right.is_some_and(|r| r.kind() == "decimal_integer_literal"
+            && text(r, code).trim_end_matches(['l', 'L']).parse::<u64>().is_ok_and(|bound| bound <= SMALL_BOUND))
+}
+
+struct Walker<'a> {
+    code: &'a str,
+    types: &'a HashMap<String, String>,
+    /// 当前最高的阶及其位置
+    worst: Option<(BigO, String)>,
+}
+
+impl Walker<'_> {
+    fn record(&mut self, order: BigO, line: usize, reason: impl FnOnce() -> String) {
+        if self.worst.as_ref().is_none_or(|(worst, _)| order > *worst) {
+            self.worst = Some((order, format!("第 {line} 行: {}", reason())));
+        }
+    }
+
+    fn visit(&mut self, node: Node, depth: u8) {
+        match node.kind() {
+            // 匿名类/局部类的方法单独估算
+            "class_body" => {}
+            kind if LOOP_KINDS.contains(&kind) => {
+                let inner = if constant_bound(node, self.code) { depth } else { depth + 1 };
+                if inner > depth {
+                    self.record(BigO { poly: inner, ..Default::default() }, line_of(node), || {
+                        if inner == 1 { "循环".to_string() } else { format!("{inner} 层嵌套循环") }
+                    });
+                }
+                let body = node.child_by_field_name("body").map(|b| b.id());
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    self.visit(child, if Some(child.id()) == body { inner } else { depth });
+                }
+            }
+            "method_invocation" => {
+                self.invocation(node, depth);
+                let name = node.child_by_field_name("name").map_or("", |n| text(n, self.code));
+                let iterating = ITERATING_CALLS.contains(&name);
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    if child.kind() == "argument_list" && iterating {
+                        let mut args = child.walk();
+                        for arg in child.children(&mut args) {
+                            let per_element = matches!(arg.kind(), "lambda_expression" | "method_reference");
+                            self.visit(arg, if per_element { depth + 1 } else { depth });
+                        }
+                    } else {
+                        self.visit(child, depth);
+                    }
+                }
+            }
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    self.visit(child, depth);
+                }
+            }
+        }
+    }
+
+    /// 调用本身的开销 (遍历、线性查找、排序)
+    fn invocation(&mut self, node: Node, depth: u8) {
+        let code = self.code;
+        let Some(name) = node.child_by_field_name("name").map(|n| text(n, code)) else { return };
+        let receiver = node.child_by_field_name("object").map_or("", |o| text(o, code));
+        let line = line_of(node);
+        if SORT_CALLS.contains(&name) {
+            self.record(BigO { poly: depth + 1, log: true, ..Default::default() }, line, || format!("排序 {name}()"));
+        } else if TRAVERSAL_CALLS.contains(&name) {
+            self.record(BigO { poly: depth + 1, ..Default::default() }, line, || {
+                if depth == 0 { format!("{name}() 遍历") } else { format!("循环内 {name}() 遍历") }
+            });
+        } else if LINEAR_LOOKUPS.contains(&name) {
+            let field = receiver.strip_prefix("this.").unwrap_or(receiver);
+            if let Some(ty) = self.types.get(field).filter(|ty| LINEAR_TYPES.contains(&ty.as_str())) {
+                self.record(BigO { poly: depth + 1, ..Default::default() }, line, || {
+                    format!("{}{ty}.{name}() 线性查找", if depth == 0 { "" } else { "循环内 " })
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+    const CODE: &str = r#"package com.shop;
+
+public class OrderService {
+    private List<Order> orders;
+    private Set<Long> seen;
+
+    public Order first() {
+        return orders.get(0);
+    }
+
+    public int size() {
+        for (int i = 0; i < 3; i++) { warm(i); }
+        return orders.size();
+    }
+
+    public String name() {
+        return "orders";
+    }
+
+    public void dedup(List<Order> incoming) {
+        for (Order order : incoming) {
+            if (!orders.contains(order) && !seen.contains(order.getId())) {
+                orders.add(order);
+            }
+        }
+    }
+
+    public void report() {
+        orders.forEach(o -> incoming.stream().filter(i -> i.equals(o)).count());
+        Collections.sort(orders);
+    }
+
+    long fib(int n) {
+        return n < 2 ? n : fib(n - 1) + fib(n - 2);
+    }
+
+    int depth(Node node) {
+        return node == null ? 0 : depth(node.next) + 1;
+    }
+}
+"#;
+
+    fn estimates() -> Vec<MethodEstimate> {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(CODE, |tree| collect(tree.root_node(), CODE, "OrderService.java")).unwrap()
+    }
+
+    #[test]
+    fn test_big_o_display_and_order() {
+        let o = |poly, log| BigO { poly, log, exponential: false };
+        assert_eq!(o(0, false).to_string(), "O(1)");
+        assert_eq!(o(1, true).to_string(), "O(n log n)");
+        assert_eq!(o(3, false).to_string(), "O(n^3)");
+        assert_eq!(BigO { exponential: true, ..Default::default() }.to_string(), "O(2^n)");
+        assert!(o(2, false) > o(1, true) && o(1, true) > o(1, false));
+        assert!(BigO { exponential: true, ..Default::default() } > o(5, true));
+    }
+
+    #[test]
+    fn test_collect_method_estimates() {
+        let estimates = estimates();
+        let by_name = |name: &str| estimates.iter().find(|e| e.method == name).unwrap();
+        assert_eq!(by_name("first").order, BigO::default());
+        // 小常量上界的循环不计入
+        assert_eq!(by_name("size").order, BigO::default());
+        // Set.contains 不计，List.contains 在循环内
+        let dedup = by_name("dedup");
+        assert_eq!(dedup.order.to_string(), "O(n^2)");
+        assert_eq!(dedup.driver.as_deref(), Some("第 22 行: 循环内 List.contains() 线性查找"));
+        assert_eq!(dedup.class, "com.shop.OrderService");
+        // forEach 的 lambda 中再遍历
+        assert_eq!(by_name("report").order.to_string(), "O(n^2)");
+        assert_eq!(by_name("fib").order, BigO::default());
+    }
+
+    #[test]
+    fn test_summarize_recursion_and_spikes() {
+        let mut graph = CallGraph::new();
+        let fib = MethodSig::new_fqn("com.shop.OrderService", "fib");
+        graph.add_call(fib.clone(), fib.clone(), "OrderService.java", 34);
+        graph.add_call(fib.clone(), fib, "OrderService.java", 34);
+        let depth = MethodSig::new_fqn("com.shop.OrderService", "depth");
+        graph.add_call(depth.clone(), depth, "OrderService.java", 38);
+
+        let summary = summarize(estimates(), &graph);
+        assert_eq!(summary.len(), 1);
+        let class = &summary[0];
+        assert_eq!((class.class.as_str(), class.worst.as_str(), class.total_methods), ("com.shop.OrderService", "O(2^n)", 7));
+        let methods: Vec<(&str, &str, bool)> = class.methods.iter()
+            .map(|m| (m.method.as_str(), m.estimate.as_str(), m.spike))
+            .collect();
+        assert_eq!(methods, vec![
+            ("dedup", "O(n^2)", true),
+            ("report", "O(n^2)", true),
+            ("fib", "O(2^n)", true),
+            ("depth", "O(n)", false),
+        ]);
+        assert_eq!(class.methods[2].drivers, vec!["分支递归 (2 处自调用)"]);
+        assert_eq!(class.spikes(), 3);
+    }
+}
@@ -0,0 +1,154 @@
+//! 环境变量与 `-D` 覆盖层
+//!
+//! CI 中不便模板化 `.javaperf.toml` 或改写调用命令时，可以用 `JAVAPERF_*` 环境变量或
+//! `-D key=value` 调整选项:
+//!
+//! ```text
+//! JAVAPERF_RULES=N_PLUS_ONE,EMPTY_CATCH JAVAPERF_FULL=1 java-perf scan
+//! java-perf -D format=sarif -D severity_map.P0=Blocker scan
+//! ```
+//!
+//! 优先级: 命令行参数 > `-D` > `JAVAPERF_*` 环境变量 > `.javaperf.toml` > 默认值。
+//!
+//! 键名为长参数名或配置项路径 (`max_p1` / `max-p1`、`severity_map.P0`)，对应的环境变量为
+//! `JAVAPERF_` + 键名大写、`.`/`-` 换成 `_` (见 [`env_name`])。命令行参数的环境变量由 clap 读取
+//! (各参数的 `env`)，配置文件项 ([`CONFIG_ENV`]) 由 [`crate::project_config::load`] 合并；
+//! `-D` 在解析命令行之前写入同名环境变量，因此两类选项都适用。
+//! `rule_overrides` 与基线 token 不支持 `-D`。
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+/// 环境变量前缀
+pub const PREFIX: &str = "JAVAPERF_";
+
+/// `.javaperf.toml` 中可由环境变量覆盖的配置项
+pub const SEVERITY_P0_ENV: &str = "JAVAPERF_SEVERITY_MAP_P0";
+pub const SEVERITY_P1_ENV: &str = "JAVAPERF_SEVERITY_MAP_P1";
+pub const BASELINE_SERVER_ENV: &str = "JAVAPERF_BASELINE_SERVER";
+pub const BASELINE_PROJECT_ENV: &str = "JAVAPERF_BASELINE_PROJECT";
+pub const STATS_ENABLED_ENV: &str = "JAVAPERF_STATS_ENABLED";
+/// (`-D` 键名, 环境变量)
+pub const CONFIG_ENV: &[(&str, &str)] = &[
+    ("severity_map.P0", SEVERITY_P0_ENV),
+    ("severity_map.P1", SEVERITY_P1_ENV),
+    ("baseline.server", BASELINE_SERVER_ENV),
+    ("baseline.project", BASELINE_PROJECT_ENV),
+    ("stats.enabled", STATS_ENABLED_ENV),
+];
+
+/// 键名 → 环境变量名 (`severity_map.P0` → `JAVAPERF_SEVERITY_MAP_P0`)
+pub fn env_name(key: &str) -> String {
+    let normalized: String = key.trim()
+        .chars()
+        .map(|c| if c == '.' || c == '-' { '_' } else { c.to_ascii_uppercase() })
+        .collect();
+    format!("{PREFIX}{normalized}")
+}
+
+/// 从原始命令行提取 `-D key=value` / `-Dkey=value` (遇到 `--` 停止)
+pub fn defines(args: &[OsString]) -> Result<Vec<(String, String)>, String> {
+    let mut out = Vec::new();
+    let mut iter = args.iter().skip(1).map(|a| a.to_string_lossy());
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            break;
+        }
+        let Some(rest) = arg.strip_prefix("-D") else { continue };
+        let define = if rest.is_empty() {
+            iter.next().ok_or("-D 缺少 KEY=VALUE")?.into_owned()
+        } else {
+            rest.to_string()
+        };
+        let (key, value) = define.split_once('=')
+            .filter(|(key, _)| !key.trim().is_empty())
+            .ok_or_else(|| format!("-D {define}: 格式应为 KEY=VALUE"))?;
+        out.push((key.trim().to_string(), value.to_string()));
+    }
+    Ok(out)
+}
+
+/// clap 命令树中声明了环境变量的参数 (含子命令) 与配置文件项: 环境变量 → 显示用键名
+pub fn known_env(command: &clap::Command) -> BTreeMap<String, String> {
+    fn collect(command: &clap::Command, out: &mut BTreeMap<String, String>) {
+        for arg in command.get_arguments() {
+            if let (Some(env), Some(long)) = (arg.get_env(), arg.get_long()) {
+                out.insert(env.to_string_lossy().into_owned(), long.replace('-', "_"));
+            }
+        }
+        for sub in command.get_subcommands() {
+            collect(sub, out);
+        }
+    }
+    let mut out: BTreeMap<String, String> = CONFIG_ENV.iter().map(|(key, env)| (env.to_string(), key.to_string())).collect();
+    collect(command, &mut out);
+    out
+}
+
+/// 校验 `-D` 键名并写入对应的环境变量 (覆盖已有值)，须在解析命令行、启动工作线程之前调用
+pub fn apply(defines: &[(String, String)], known: &BTreeMap<String, String>) -> Result<(), String> {
+    let resolved = resolve(defines, known)?;
+    for (env, value) in resolved {
+        std::env::set_var(env, value);
+    }
+    Ok(())
+}
+
+fn resolve(defines: &[(String, String)], known: &BTreeMap<String, String>) -> Result<Vec<(String, String)>, String> {
+    defines.iter()
+        .map(|(key, value)| {
+            let env = env_name(key);
+            if !known.contains_key(&env) {
+                let mut keys: Vec<&str> = known.values().map(String::as_str).collect();
+                keys.sort_unstable();
+                return Err(format!("-D {key}: 未知选项 (可用: {})", keys.join(", ")));
+            }
+            Ok((env, value.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<OsString> {
+        std::iter::once("java-perf").chain(args.iter().copied()).map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_env_name() {
+        assert_eq!(env_name("max_p1"), "JAVAPERF_MAX_P1");
+        assert_eq!(env_name("max-p1"), "JAVAPERF_MAX_P1");
+        assert_eq!(env_name("severity_map.P0"), "JAVAPERF_SEVERITY_MAP_P0");
+    }
+
+    #[test]
+    fn test_defines_from_argv() {
+        let parsed = defines(&argv(&["-D", "rules=N_PLUS_ONE,EMPTY_CATCH", "scan", "-Dseverity_map.P0=Blocker", "--", "-Dignored=1"])).unwrap();
+        assert_eq!(parsed, vec![
+            ("rules".to_string(), "N_PLUS_ONE,EMPTY_CATCH".to_string()),
+            ("severity_map.P0".to_string(), "Blocker".to_string()),
+        ]);
+        assert!(defines(&argv(&["scan", "-D"])).is_err());
+        assert!(defines(&argv(&["-D", "max_p1"])).unwrap_err().contains("KEY=VALUE"));
+        assert!(defines(&argv(&["-D", "=1"])).is_err());
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_keys() {
+        let command = clap::Command::new("java-perf")
+            .subcommand(clap::Command::new("scan").arg(clap::Arg::new("max_p1").long("max-p1").env("JAVAPERF_MAX_P1")));
+        let known = known_env(&command);
+        assert_eq!(known["JAVAPERF_MAX_P1"], "max_p1");
+        assert_eq!(known[SEVERITY_P0_ENV], "severity_map.P0");
+
+        let defines = vec![("max-p1".to_string(), "20".to_string()), ("severity_map.P0".to_string(), "Blocker".to_string())];
+        assert_eq!(resolve(&defines, &known).unwrap(), vec![
+            ("JAVAPERF_MAX_P1".to_string(), "20".to_string()),
+            (SEVERITY_P0_ENV.to_string(), "Blocker".to_string()),
+        ]);
+        let err = resolve(&[("max_p2".to_string(), "1".to_string())], &known).unwrap_err();
+        assert!(err.contains("未知选项") && err.contains("max_p1, severity_map.P0"), "{err}");
+    }
+}
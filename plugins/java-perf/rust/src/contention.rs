@@ -0,0 +1,264 @@
+//! 锁竞争风险排名 (静态结构估算)
+//!
+//! SYNC_METHOD / SYNC_BLOCK / LOCK_METHOD_CALL 逐处报告加锁写法，但无法区分"每个请求都要抢的锁"与
+//! "启动时跑一次的锁"。这里为每个持锁区域 (synchronized 方法、synchronized 块、`lock()` 后紧跟的 try 块，
+//! 区域的界定同 HELD_LOCK_BLOCKING) 综合三项静态信息估算竞争风险，输出排名表 (JSON `contention`，
+//! Markdown "锁竞争风险" 章节)，不产生问题:
+//!
+//! | 因子 | 取值 |
+//! |------|------|
+//! | 调用方 | 1 + 所在方法在 CallGraph 中的不同调用方法数 (上限 [`MAX_CALLERS`]，接收者展开到实现类同 hotness) |
+//! | 入口可达 | 1 + hotness / 25 (Controller 方法本身为 5，不可达为 1) |
+//! | 临界区长度 | 持锁区域的行数 (上限 [`MAX_LINES`]) |
+//!
+//! 风险分为三者之积。调用方多说明并发进入的路径多，离请求入口近说明并发度随流量上升，
+//! 临界区越长持锁时间越长；只用于排序，不代表实际的锁等待时间。
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::hotness::{self, HotnessIndex};
+use crate::scanner::held_lock::{self, LOCK_METHODS};
+use crate::symbol_table::SymbolTable;
+use crate::taint::CallGraph;
+
+/// Markdown 中列出的持锁区域数
+pub const TOP_SITES: usize = 10;
+
+/// 计入风险分的调用方上限
+pub const MAX_CALLERS: usize = 20;
+
+/// 计入风险分的临界区行数上限
+pub const MAX_LINES: usize = 50;
+
+/// 加锁方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockKind {
+    SyncMethod,
+    SyncBlock,
+    ExplicitLock,
+}
+
+impl LockKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::SyncMethod => "synchronized 方法",
+            Self::SyncBlock => "synchronized 块",
+            Self::ExplicitLock => "lock()",
+        }
+    }
+}
+
+/// 单个持锁区域 (Phase 2 逐文件收集)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockSite {
+    /// 相对路径
+    pub path: String,
+    pub line: usize,
+    /// 锁标识 (`类名.this` / `类名.class` / `类名.字段`，同 HELD_LOCK_BLOCKING)
+    pub lock: String,
+    pub kind: LockKind,
+    /// 所在类型的简单名
+    pub class: String,
+    pub method: String,
+    /// 临界区行数
+    pub lines: usize,
+}
+
+/// 排名中的单个持锁区域
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentionRisk {
+    #[serde(flatten)]
+    pub site: LockSite,
+    /// 所在方法的不同调用方法数
+    pub callers: usize,
+    /// 所在方法的热度 (见 hotness 模块)
+    pub hotness: u8,
+    pub score: u32,
+}
+
+/// 源码中可能有持锁区域 (跳过无关文件的额外解析)
+pub fn is_candidate(code: &str) -> bool {
+    code.contains("synchronized") || code.contains(".lock")
+}
+
+/// 收集文件中所有持锁区域
+pub fn collect(root: Node, code: &str, rel_path: &str) -> Vec<LockSite> {
+    let spans = hotness::collect_spans(root, code);
+    let mut sites = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+        let kind = match node.kind() {
+            "method_declaration" => LockKind::SyncMethod,
+            "synchronized_statement" => LockKind::SyncBlock,
+            "method_invocation" if node.child_by_field_name("name")
+                .is_some_and(|name| LOCK_METHODS.contains(&name.utf8_text(code.as_bytes()).unwrap_or(""))) => LockKind::ExplicitLock,
+            _ => continue,
+        };
+        let line = node.start_position().row + 1;
+        let Some(span) = hotness::innermost(&spans, line) else { continue };
+        let Some((lock, region)) = held_lock::held_region(node, &span.class, code) else { continue };
+        sites.push(LockSite {
+            path: rel_path.to_string(),
+            line,
+            lock,
+            kind,
+            class: span.class.clone(),
+            method: span.method.clone(),
+            lines: region.end_position().row - region.start_position().row + 1,
+        });
+    }
+    sites.sort_by_key(|s| s.line);
+    sites
+}
+
+/// 按风险分降序排名 (相同时按位置)
+pub fn rank(sites: Vec<LockSite>, graph: &CallGraph, table: &SymbolTable, hot: &HotnessIndex) -> Vec<ContentionRisk> {
+    if sites.is_empty() {
+        return Vec::new();
+    }
+    let callers = callers_by_method(graph, table);
+    let mut ranked: Vec<ContentionRisk> = sites.into_iter()
+        .map(|site| {
+            let callers = callers.get(&(site.class.clone(), site.method.clone())).map_or(0, HashSet::len);
+            let hotness = hotness::score(hot.distance(&site.class, &site.method));
+            let score = (1 + callers.min(MAX_CALLERS) as u32) * (1 + u32::from(hotness) / 25) * site.lines.min(MAX_LINES) as u32;
+            ContentionRisk { site, callers, hotness, score }
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| (&a.site.path, a.site.line).cmp(&(&b.site.path, b.site.line))));
+    ranked
+}
+
+/// (类简单名, 方法名) -> 调用它的不同方法 (不含自身递归)
+fn callers_by_method(graph: &CallGraph, table: &SymbolTable) -> HashMap<(String, String), HashSet<(String, String)>> {
+    let mut callers: HashMap<(String, String), HashSet<(String, String)>> = HashMap::new();
+    for (caller, sites) in &graph.outgoing {
+        let from = (caller.simple_class_name().to_string(), caller.name.clone());
+        for site in sites {
+            for target in hotness::callee_targets(graph, table, site) {
+                let to = (target.simple_class_name().to_string(), target.name);
+                if to != from {
+                    callers.entry(to).or_default().insert(from.clone());
+                }
+            }
+        }
+    }
+    callers
+}
+
+/// Markdown 报告中的锁竞争风险章节
+pub fn render_section(ranked: &[ContentionRisk]) -> String {
+    if ranked.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "\n### 🔒 锁竞争风险\n\n| 风险分 | 位置 | 方法 | 锁 | 方式 | 调用方 | 热度 | 临界区行数 |\n|--------|------|------|----|------|--------|------|------------|\n"
+    );
+    for risk in ranked.iter().take(TOP_SITES) {
+        let site = &risk.site;
+        section.push_str(&format!(
+            "| {} | `{}:{}` | `{}.{}` | `{}` | {} | {} | {} | {} |\n",
+            risk.score, site.path, site.line, site.class, site.method, site.lock, site.kind.label(), risk.callers, risk.hotness, site.lines
+        ));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+    use crate::symbol_table::{TypeInfo, VarBinding};
+    use crate::taint::{LayerType, MethodSig};
+
+    const CODE: &str = r#"package com.shop;
+
+public class StockService {
+    private final ReentrantLock lock = new ReentrantLock();
+
+    public synchronized void reserve(long sku) {
+        stock.merge(sku, -1, Integer::sum);
+    }
+
+    public void refill(long sku) {
+        lock.lock();
+        try {
+            stock.put(sku, 100);
+            audit(sku);
+        } finally {
+            lock.unlock();
+        }
+    }
+
+    public void reload() {
+        synchronized (this.stock) {
+            stock.clear();
+        }
+    }
+}
+"#;
+
+    fn sites() -> Vec<LockSite> {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(CODE, |tree| collect(tree.root_node(), CODE, "StockService.java")).unwrap()
+    }
+
+    #[test]
+    fn test_collect_lock_sites() {
+        let sites = sites();
+        let summary: Vec<(usize, &str, LockKind, &str, usize)> = sites.iter()
+            .map(|s| (s.line, s.lock.as_str(), s.kind, s.method.as_str(), s.lines))
+            .collect();
+        assert_eq!(summary, vec![
+            (6, "StockService.this", LockKind::SyncMethod, "reserve", 3),
+            (11, "StockService.lock", LockKind::ExplicitLock, "refill", 4),
+            (21, "StockService.stock", LockKind::SyncBlock, "reload", 3),
+        ]);
+    }
+
+    #[test]
+    fn test_rank_by_callers_reachability_and_length() {
+        let mut table = SymbolTable::new();
+        for (name, annotation) in [("OrderController", "RestController"), ("StockService", "Service"), ("StockJob", "Component")] {
+            let mut info = TypeInfo::new_with_package(name, Some("com.shop"), format!("{name}.java"), 1);
+            info.add_annotation(annotation);
+            table.register_class_fqn(info);
+        }
+        table.register_field("OrderController", VarBinding::new("stockService", "StockService", true));
+        table.register_field("StockJob", VarBinding::new("stockService", "StockService", true));
+
+        let mut graph = CallGraph::new();
+        graph.register_class("com.shop.OrderController", "", LayerType::Controller);
+        graph.register_class("com.shop.StockService", "", LayerType::Service);
+        graph.register_class("com.shop.StockJob", "", LayerType::Unknown);
+        let call = |graph: &mut CallGraph, caller: (&str, &str), method: &str| {
+            graph.add_call(
+                MethodSig::new_fqn(caller.0, caller.1),
+                MethodSig { class_fqn: "UNRESOLVED:stockService".into(), name: method.into() },
+                "",
+                1,
+            );
+        };
+        call(&mut graph, ("com.shop.OrderController", "create"), "reserve");
+        call(&mut graph, ("com.shop.OrderController", "update"), "reserve");
+        call(&mut graph, ("com.shop.StockJob", "nightly"), "refill");
+        let hot = HotnessIndex::build(&graph, &table, hotness::MAX_DEPTH);
+
+        let ranked = rank(sites(), &graph, &table, &hot);
+        let summary: Vec<(&str, usize, u8, u32)> = ranked.iter()
+            .map(|r| (r.site.method.as_str(), r.callers, r.hotness, r.score))
+            .collect();
+        // reserve: (1+2) * (1+50/25) * 3；refill: (1+1) * 1 * 4；reload: 1 * 1 * 3
+        assert_eq!(summary, vec![("reserve", 2, 50, 27), ("refill", 1, 0, 8), ("reload", 0, 0, 3)]);
+
+        let section = render_section(&ranked);
+        assert!(section.contains("| 27 | `StockService.java:6` | `StockService.reserve` | `StockService.this` | synchronized 方法 | 2 | 50 | 3 |"), "{section}");
+        assert!(render_section(&[]).is_empty());
+    }
+}
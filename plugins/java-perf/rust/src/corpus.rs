@@ -0,0 +1,714 @@
+//! 规则语料生成 (`dev gen-corpus`)
+//!
+//! 按规则生成正例 (应报告) 与反例 (不应报告) 的 Java 文件。每个规则给出一对
+//! 模板，生成时由种子决定类名、变量名、方法名、包名与穿插的无关方法，同一种子
+//! 总是生成相同的文件；单个文件的随机源由 (种子, 规则, 变体序号) 派生，
+//! 只生成部分规则 (`--rules`) 不影响其余文件。
+//!
+//! 用途:
+//! - 规则测试: 单元测试对多个种子检查正例命中、反例不命中
+//! - 用户验证配置: 把语料放进项目后运行 `scan`，对照 `corpus.json` 确认
+//!   `.javaperf.toml`、抑制注释等按预期生效
+//!
+//! 模板占位符: `${Class}` `${method}` `${repo}` `${id}` `${ids}` `${n}` `${field}`
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::rules::registry::reported_id;
+use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+use crate::scanner::CodeAnalyzer;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 语料清单文件名
+pub const MANIFEST: &str = "corpus.json";
+
+/// 单个规则的正例/反例模板 (类成员)
+#[derive(Debug, Clone, Copy)]
+pub struct RuleCase {
+    pub rule: &'static str,
+    pub imports: &'static [&'static str],
+    pub positive: &'static str,
+    pub negative: &'static str,
+}
+
+const CASES: &[RuleCase] = &[
+    RuleCase {
+        rule: "N_PLUS_ONE",
+        imports: &[],
+        positive: r#"
+    public void ${method}(long[] ${ids}) {
+        for (int i = 0; i < ${ids}.length; i++) {
+            ${repo}.findById(${ids}[i]);
+        }
+    }
+"#,
+        negative: r#"
+    public void ${method}(java.util.List<Long> ${ids}) {
+        ${repo}.findAllById(${ids});
+    }
+"#,
+    },
+    RuleCase {
+        rule: "N_PLUS_ONE_FOREACH",
+        imports: &["java.util.List"],
+        positive: r#"
+    public void ${method}(List<Long> ${ids}) {
+        for (Long ${id} : ${ids}) {
+            ${repo}.findById(${id});
+        }
+    }
+"#,
+        negative: r#"
+    public void ${method}(List<Long> ${ids}) {
+        ${repo}.findAllById(${ids});
+    }
+"#,
+    },
+    RuleCase {
+        rule: "N_PLUS_ONE_WHILE",
+        imports: &["java.util.Iterator"],
+        positive: r#"
+    public void ${method}(Iterator<Long> ${ids}) {
+        while (${ids}.hasNext()) {
+            ${repo}.deleteById(${ids}.next());
+        }
+    }
+"#,
+        negative: r#"
+    public void ${method}(Iterator<Long> ${ids}) {
+        java.util.List<Long> batch = new java.util.ArrayList<>();
+        ${ids}.forEachRemaining(batch::add);
+        ${repo}.deleteAllById(batch);
+    }
+"#,
+    },
+    RuleCase {
+        rule: "FIND_BY_ID_IN_LOOP",
+        imports: &["java.util.List"],
+        positive: r#"
+    public void ${method}(List<Long> ${ids}) {
+        for (Long ${id} : ${ids}) {
+            Object entity = ${repo}.findById(${id}).orElseThrow();
+        }
+    }
+"#,
+        negative: r#"
+    public Object ${method}(Long ${id}) {
+        return ${repo}.findById(${id}).orElseThrow();
+    }
+"#,
+    },
+    RuleCase {
+        rule: "EMPTY_CATCH",
+        imports: &[],
+        positive: r#"
+    public void ${method}() {
+        try {
+            Thread.sleep(${n});
+        } catch (InterruptedException e) {
+        }
+    }
+"#,
+        negative: r#"
+    public void ${method}() {
+        try {
+            Thread.sleep(${n});
+        } catch (InterruptedException e) {
+            Thread.currentThread().interrupt();
+        }
+    }
+"#,
+    },
+    RuleCase {
+        rule: "SYSTEM_EXIT",
+        imports: &[],
+        positive: r#"
+    public void ${method}(boolean failed) {
+        if (failed) {
+            System.exit(${n});
+        }
+    }
+"#,
+        negative: r#"
+    public void ${method}(boolean failed) {
+        if (failed) {
+            throw new IllegalStateException("failed: " + ${n});
+        }
+    }
+"#,
+    },
+    RuleCase {
+        rule: "SYNC_METHOD",
+        imports: &[],
+        positive: r#"
+    private int ${field};
+
+    public synchronized void ${method}() {
+        ${field} += ${n};
+    }
+"#,
+        negative: r#"
+    private final java.util.concurrent.atomic.AtomicInteger ${field} = new java.util.concurrent.atomic.AtomicInteger();
+
+    public void ${method}() {
+        ${field}.addAndGet(${n});
+    }
+"#,
+    },
+    RuleCase {
+        rule: "SLEEP_IN_LOCK",
+        imports: &[],
+        positive: r#"
+    private final Object ${field} = new Object();
+
+    public void ${method}() throws InterruptedException {
+        synchronized (${field}) {
+            Thread.sleep(${n});
+        }
+    }
+"#,
+        negative: r#"
+    private final Object ${field} = new Object();
+
+    public void ${method}() throws InterruptedException {
+        Thread.sleep(${n});
+        synchronized (${field}) {
+            ${field}.notifyAll();
+        }
+    }
+"#,
+    },
+    RuleCase {
+        rule: "SIMPLE_DATE_FORMAT",
+        imports: &["java.text.SimpleDateFormat", "java.time.format.DateTimeFormatter"],
+        positive: r#"
+    private static final SimpleDateFormat ${field} = new SimpleDateFormat("yyyy-MM-dd");
+
+    public String ${method}(java.util.Date date) {
+        return ${field}.format(date);
+    }
+"#,
+        negative: r#"
+    private static final DateTimeFormatter ${field} = DateTimeFormatter.ofPattern("yyyy-MM-dd");
+
+    public String ${method}(java.time.LocalDate date) {
+        return ${field}.format(date);
+    }
+"#,
+    },
+    RuleCase {
+        rule: "FUTURE_GET_NO_TIMEOUT",
+        imports: &["java.util.concurrent.Future", "java.util.concurrent.TimeUnit"],
+        positive: r#"
+    public Object ${method}(Future<Object> ${field}) throws Exception {
+        return ${field}.get();
+    }
+"#,
+        negative: r#"
+    public Object ${method}(Future<Object> ${field}) throws Exception {
+        return ${field}.get(${n}, TimeUnit.MILLISECONDS);
+    }
+"#,
+    },
+    RuleCase {
+        rule: "UNBOUNDED_POOL",
+        imports: &["java.util.concurrent.ExecutorService", "java.util.concurrent.Executors"],
+        positive: r#"
+    private final ExecutorService ${field} = Executors.newCachedThreadPool();
+
+    public void ${method}(Runnable task) {
+        ${field}.submit(task);
+    }
+"#,
+        negative: r#"
+    private final ExecutorService ${field} = Executors.newFixedThreadPool(${n});
+
+    public void ${method}(Runnable task) {
+        ${field}.submit(task);
+    }
+"#,
+    },
+    RuleCase {
+        rule: "STRING_CONCAT_LOOP",
+        imports: &["java.util.List"],
+        positive: r#"
+    public String ${method}(List<String> ${ids}) {
+        String out = "";
+        for (String ${id} : ${ids}) {
+            out += ${id};
+        }
+        return out;
+    }
+"#,
+        negative: r#"
+    public String ${method}(List<String> ${ids}) {
+        StringBuilder out = new StringBuilder();
+        for (String ${id} : ${ids}) {
+            out.append(${id});
+        }
+        return out.toString();
+    }
+"#,
+    },
+    RuleCase {
+        rule: "LOG_STRING_CONCAT",
+        imports: &["org.slf4j.Logger", "org.slf4j.LoggerFactory"],
+        positive: r#"
+    private static final Logger log = LoggerFactory.getLogger(${Class}.class);
+
+    public void ${method}(long ${id}) {
+        log.debug("loading " + ${id} + " of " + ${n});
+    }
+"#,
+        negative: r#"
+    private static final Logger log = LoggerFactory.getLogger(${Class}.class);
+
+    public void ${method}(long ${id}) {
+        log.debug("loading {} of {}", ${id}, ${n});
+    }
+"#,
+    },
+    RuleCase {
+        rule: "SELECT_STAR",
+        imports: &[],
+        positive: r#"
+    private static final String ${field} = "SELECT * FROM orders WHERE id = ?";
+
+    public String ${method}() {
+        return ${field};
+    }
+"#,
+        negative: r#"
+    private static final String ${field} = "SELECT id, status FROM orders WHERE id = ?";
+
+    public String ${method}() {
+        return ${field};
+    }
+"#,
+    },
+    RuleCase {
+        rule: "THREADLOCAL_LEAK",
+        imports: &[],
+        positive: r#"
+    private static final ThreadLocal<StringBuilder> ${field} = new ThreadLocal<>();
+
+    public void ${method}() {
+        ${field}.set(new StringBuilder(${n}));
+    }
+"#,
+        negative: r#"
+    private static final ThreadLocal<StringBuilder> ${field} = new ThreadLocal<>();
+
+    public void ${method}() {
+        ${field}.set(new StringBuilder(${n}));
+        try {
+            ${field}.get().append('x');
+        } finally {
+            ${field}.remove();
+        }
+    }
+"#,
+    },
+    RuleCase {
+        rule: "FINALIZE_OVERRIDE",
+        imports: &[],
+        positive: r#"
+    @Override
+    protected void finalize() throws Throwable {
+        super.finalize();
+    }
+"#,
+        negative: r#"
+    public void close() {
+        ${method}();
+    }
+
+    private void ${method}() {
+    }
+"#,
+    },
+    RuleCase {
+        rule: "RUNTIME_EXEC",
+        imports: &[],
+        positive: r#"
+    public void ${method}(String cmd) throws java.io.IOException {
+        Runtime.getRuntime().exec(cmd);
+    }
+"#,
+        negative: r#"
+    public int ${method}(String cmd) {
+        return Runtime.getRuntime().availableProcessors() * ${n};
+    }
+"#,
+    },
+    RuleCase {
+        rule: "OPTIONAL_BARE_GET",
+        imports: &["java.util.Optional"],
+        positive: r#"
+    public String ${method}(Optional<String> ${field}) {
+        return ${field}.get();
+    }
+"#,
+        negative: r#"
+    public String ${method}(Optional<String> ${field}) {
+        return ${field}.orElse("n/a");
+    }
+"#,
+    },
+    RuleCase {
+        rule: "FLUX_BLOCK",
+        imports: &["reactor.core.publisher.Mono"],
+        positive: r#"
+    public String ${method}(Mono<String> ${field}) {
+        return ${field}.block();
+    }
+"#,
+        negative: r#"
+    public Mono<String> ${method}(Mono<String> ${field}) {
+        return ${field}.map(String::trim);
+    }
+"#,
+    },
+];
+
+/// 种子化伪随机数 (SplitMix64)：不依赖外部 crate，跨版本输出稳定
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+/// FNV-1a，用于从规则 ID 派生子种子
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// 期望结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expect {
+    /// 应报告该规则
+    Positive,
+    /// 不应报告该规则
+    Negative,
+}
+
+impl fmt::Display for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Expect::Positive => "positive",
+            Expect::Negative => "negative",
+        })
+    }
+}
+
+/// 生成的单个 Java 文件
+#[derive(Debug, Clone, Serialize)]
+pub struct CorpusFile {
+    pub rule: &'static str,
+    /// 报告中出现的问题 ID
+    pub reports: &'static str,
+    pub expect: Expect,
+    /// 相对语料目录的路径
+    pub path: String,
+    #[serde(skip)]
+    pub code: String,
+}
+
+/// 生成选项
+#[derive(Debug, Clone)]
+pub struct CorpusOptions {
+    pub seed: u64,
+    /// 每个规则的正例/反例各生成几个变体
+    pub variants: usize,
+    /// 只生成这些规则 (空 = 全部有模板的规则)
+    pub rules: Vec<String>,
+}
+
+impl Default for CorpusOptions {
+    fn default() -> Self {
+        Self { seed: 42, variants: 3, rules: Vec::new() }
+    }
+}
+
+/// 有模板的规则
+pub fn covered_rules() -> impl Iterator<Item = &'static str> {
+    CASES.iter().map(|case| case.rule)
+}
+
+/// 按选项生成语料；`rules` 中含没有模板的规则时报错
+pub fn generate(options: &CorpusOptions) -> Result<Vec<CorpusFile>, String> {
+    let unknown: Vec<&str> = options.rules.iter()
+        .map(String::as_str)
+        .filter(|id| !CASES.iter().any(|case| case.rule == *id))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!(
+            "以下规则没有语料模板: {} (可用: {})",
+            unknown.join(", "),
+            covered_rules().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    let mut files = Vec::new();
+    for case in CASES.iter().filter(|case| options.rules.is_empty() || options.rules.iter().any(|r| r == case.rule)) {
+        for variant in 0..options.variants {
+            for (expect, template) in [(Expect::Positive, case.positive), (Expect::Negative, case.negative)] {
+                let mut rng = SplitMix64(options.seed ^ fnv1a(case.rule) ^ (variant as u64).wrapping_mul(0x9E37_79B9));
+                files.push(render_case(case, expect, template, variant, &mut rng));
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn render_case(case: &RuleCase, expect: Expect, template: &str, variant: usize, rng: &mut SplitMix64) -> CorpusFile {
+    let domain = rng.pick(&["Order", "Invoice", "Customer", "Payment", "Shipment", "Account"]);
+    let role = rng.pick(&["Service", "Handler", "Processor", "Manager"]);
+    let tag = match expect {
+        Expect::Positive => "Pos",
+        Expect::Negative => "Neg",
+    };
+    let class = format!("{domain}{role}{tag}{variant}");
+    let repo = format!("{}{}", domain.to_lowercase(), rng.pick(&["Repository", "Dao", "Mapper"]));
+    let (id, ids) = *[("id", "ids"), ("key", "keys"), ("pk", "pks"), ("itemId", "itemIds")]
+        .get(rng.below(4))
+        .unwrap_or(&("id", "ids"));
+    let method = rng.pick(&["process", "handle", "execute", "sync", "refresh", "load"]);
+    let field = rng.pick(&["holder", "state", "cache", "current", "pending"]);
+    let n = (rng.below(900) + 100).to_string();
+
+    let body = template
+        .replace("${Class}", &class)
+        .replace("${repo}", &repo)
+        .replace("${ids}", ids)
+        .replace("${id}", id)
+        .replace("${method}", method)
+        .replace("${field}", field)
+        .replace("${n}", &n);
+
+    // 规则成员前后穿插无关方法，检验行号与方法定位
+    let noise = |rng: &mut SplitMix64, index: usize| -> String {
+        let name = rng.pick(&["size", "limit", "weight", "score"]);
+        format!("\n    int {name}{index}(int value) {{\n        return value * {};\n    }}\n", rng.below(9) + 1)
+    };
+    let mut members = String::new();
+    for i in 0..rng.below(3) {
+        members.push_str(&noise(rng, i));
+    }
+    members.push_str(&body);
+    for i in 3..3 + rng.below(2) {
+        members.push_str(&noise(rng, i));
+    }
+
+    let mut code = String::new();
+    let package = rng.below(4) > 0;
+    if package {
+        code.push_str(&format!("package corpus.{};\n\n", case.rule.to_lowercase()));
+    }
+    for import in case.imports {
+        code.push_str(&format!("import {import};\n"));
+    }
+    if !case.imports.is_empty() {
+        code.push('\n');
+    }
+    code.push_str(&format!("public class {class} {{{members}}}\n"));
+
+    CorpusFile {
+        rule: case.rule,
+        reports: reported_id(case.rule),
+        expect,
+        path: format!("{}/{class}.java", case.rule.to_lowercase()),
+        code,
+    }
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    seed: u64,
+    variants: usize,
+    files: &'a [CorpusFile],
+}
+
+/// 写入语料文件与清单 (`corpus.json`)
+pub fn write(out: &Path, files: &[CorpusFile], options: &CorpusOptions) -> Result<(), BoxError> {
+    for file in files {
+        let path = out.join(&file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &file.code)?;
+    }
+    let manifest = Manifest { seed: options.seed, variants: options.variants, files };
+    std::fs::write(out.join(MANIFEST), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+/// 与期望不符的语料文件
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Mismatch {
+    pub rule: &'static str,
+    pub expect: Expect,
+    pub path: String,
+    /// 实际报告的规则 ID
+    pub reported: Vec<String>,
+}
+
+/// 用内置规则分析语料，返回与期望不符的文件
+pub fn check(files: &[CorpusFile]) -> Result<Vec<Mismatch>, BoxError> {
+    let analyzer = JavaTreeSitterAnalyzer::new()?;
+    let mut mismatches = Vec::new();
+    for file in files {
+        let issues = analyzer.analyze(&file.code, Path::new(&file.path))?;
+        let hit = issues.iter().any(|issue| issue.id == file.reports);
+        if hit != (file.expect == Expect::Positive) {
+            let mut reported: Vec<String> = issues.into_iter().map(|issue| issue.id).collect();
+            reported.dedup();
+            mismatches.push(Mismatch { rule: file.rule, expect: file.expect, path: file.path.clone(), reported });
+        }
+    }
+    Ok(mismatches)
+}
+
+/// `dev gen-corpus` 的结果
+#[derive(Debug, Serialize)]
+pub struct CorpusOutcome {
+    pub out: String,
+    pub seed: u64,
+    pub files: usize,
+    pub rules: Vec<&'static str>,
+    /// `--check` 时与期望不符的文件
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mismatches: Option<Vec<Mismatch>>,
+}
+
+impl CorpusOutcome {
+    /// `--check` 发现不符 (CLI 以非零码退出)
+    pub fn failed(&self) -> bool {
+        self.mismatches.as_ref().is_some_and(|m| !m.is_empty())
+    }
+}
+
+/// 生成语料写入 `out`，`check` 时同时用内置规则校验
+pub fn gen_corpus(out: &str, options: &CorpusOptions, check_files: bool) -> Result<CorpusOutcome, BoxError> {
+    let files = generate(options)?;
+    write(Path::new(out), &files, options)?;
+    let mut rules: Vec<&'static str> = files.iter().map(|f| f.rule).collect();
+    rules.dedup();
+    let mismatches = if check_files { Some(check(&files)?) } else { None };
+    Ok(CorpusOutcome { out: out.to_string(), seed: options.seed, files: files.len(), rules, mismatches })
+}
+
+pub fn render(outcome: &CorpusOutcome, json_output: bool) -> Value {
+    if json_output {
+        return json!(outcome);
+    }
+    let mut out = format!(
+        "## 🧪 规则语料\n\n**目录**: {} | **种子**: {} | **规则**: {} | **文件**: {}\n\n清单: {}/{MANIFEST}\n",
+        outcome.out, outcome.seed, outcome.rules.len(), outcome.files, outcome.out
+    );
+    match &outcome.mismatches {
+        Some(mismatches) if mismatches.is_empty() => out.push_str("\n✅ 正例全部命中，反例全部未命中\n"),
+        Some(mismatches) => {
+            out.push_str(&format!("\n### ❌ {} 个文件与期望不符\n\n", mismatches.len()));
+            for m in mismatches {
+                out.push_str(&format!("- `{}` ({} {}) - 实际报告: [{}]\n", m.path, m.rule, m.expect, m.reported.join(", ")));
+            }
+        }
+        None => {}
+    }
+    json!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::registry::RuleRegistry;
+
+    #[test]
+    fn test_every_case_matches_expectation_across_seeds() {
+        for seed in [0, 7, 42, 2024] {
+            let files = generate(&CorpusOptions { seed, variants: 2, rules: Vec::new() }).unwrap();
+            assert_eq!(files.len(), CASES.len() * 2 * 2);
+            let mismatches = check(&files).unwrap();
+            assert!(mismatches.is_empty(), "seed {seed}: {mismatches:#?}");
+        }
+    }
+
+    #[test]
+    fn test_generation_is_reproducible_and_independent_of_rule_filter() {
+        let all = generate(&CorpusOptions::default()).unwrap();
+        let again = generate(&CorpusOptions::default()).unwrap();
+        assert_eq!(all.iter().map(|f| &f.code).collect::<Vec<_>>(), again.iter().map(|f| &f.code).collect::<Vec<_>>());
+
+        let only = generate(&CorpusOptions { rules: vec!["EMPTY_CATCH".into()], ..Default::default() }).unwrap();
+        assert_eq!(only.len(), 6);
+        let same: Vec<&CorpusFile> = all.iter().filter(|f| f.rule == "EMPTY_CATCH").collect();
+        for (a, b) in only.iter().zip(same) {
+            assert_eq!((&a.path, &a.code), (&b.path, &b.code));
+        }
+
+        let other = generate(&CorpusOptions { seed: 43, ..Default::default() }).unwrap();
+        assert!(all.iter().zip(&other).any(|(a, b)| a.code != b.code));
+    }
+
+    #[test]
+    fn test_unknown_rule_and_registry_ids() {
+        let err = generate(&CorpusOptions { rules: vec!["NO_SUCH_RULE".into()], ..Default::default() }).unwrap_err();
+        assert!(err.contains("NO_SUCH_RULE"), "{err}");
+
+        let registry = RuleRegistry::default();
+        for rule in covered_rules() {
+            assert!(registry.get(rule).is_some(), "{rule} 不是内置 Query 规则");
+        }
+    }
+
+    #[test]
+    fn test_gen_corpus_check_outcome() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().to_str().unwrap();
+        let options = CorpusOptions { rules: vec!["N_PLUS_ONE_FOREACH".into()], variants: 2, ..Default::default() };
+        let outcome = gen_corpus(out, &options, true).unwrap();
+        assert_eq!((outcome.files, outcome.rules.clone()), (4, vec!["N_PLUS_ONE_FOREACH"]));
+        assert!(!outcome.failed());
+        assert!(render(&outcome, false).as_str().unwrap().contains("✅"));
+        assert!(gen_corpus(out, &CorpusOptions { rules: vec!["NOPE".into()], ..Default::default() }, false).is_err());
+    }
+
+    #[test]
+    fn test_write_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = CorpusOptions { rules: vec!["SYSTEM_EXIT".into()], variants: 1, ..Default::default() };
+        let files = generate(&options).unwrap();
+        write(dir.path(), &files, &options).unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join(MANIFEST)).unwrap()).unwrap();
+        assert_eq!(manifest["seed"], 42);
+        assert_eq!(manifest["files"][0]["expect"], "positive");
+        assert_eq!(manifest["files"][1]["expect"], "negative");
+        for file in &files {
+            assert_eq!(std::fs::read_to_string(dir.path().join(&file.path)).unwrap(), file.code);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! 测试覆盖率关联 (`scan --jacoco jacoco.xml`)：优先处理没有测试保护的问题
+//!
+//! 读取 JaCoCo XML 报告中 `<sourcefile>` 的行覆盖 (`<line nr ci>`)，为每个问题标注所在方法是否被测试执行过:
+//! 方法行范围 (见 hotness::collect_spans) 内任一行有被执行的指令即为已覆盖；不在方法内的问题 (字段初始化等)
+//! 按问题所在行判断。报告中的文件按 `包路径/文件名` 与问题路径的后缀匹配，多模块聚合报告 (`<group>`) 同样适用；
+//! 报告中没有该文件或相关行均未插桩 (接口、配置文件) 时不标注。
+//!
+//! 未覆盖代码中的 P0 改动时没有回归测试兜底，报告中单列为最高风险；`--only-uncovered` 只保留未覆盖的问题。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::hotness::{self, MethodSpan};
+
+/// 最高风险章节最多列出的问题数
+const TOP_RISKS: usize = 20;
+
+/// 问题所在代码的测试覆盖
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Coverage {
+    Covered,
+    Uncovered,
+}
+
+/// JaCoCo 行覆盖: `包路径/文件名` → 行号 → 是否有被执行的指令
+#[derive(Debug, Default)]
+pub struct CoverageReport {
+    files: HashMap<String, HashMap<usize, bool>>,
+}
+
+/// 读取 JaCoCo XML 报告
+pub fn load(path: &Path) -> Result<CoverageReport, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    parse(&content).map_err(|e| format!("{} 不是有效的 JaCoCo XML 报告: {e}", path.display()))
+}
+
+fn parse(xml: &str) -> Result<CoverageReport, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut report = CoverageReport::default();
+    let mut package = String::new();
+    let mut source: Option<String> = None;
+    let mut seen_report = false;
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let attr = |name: &[u8]| {
+                    e.attributes().flatten()
+                        .find(|a| a.key.as_ref() == name)
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|v| v.into_owned())
+                };
+                match e.name().as_ref() {
+                    b"report" => seen_report = true,
+                    b"package" => package = attr(b"name").unwrap_or_default(),
+                    b"sourcefile" if matches!(event, Event::Start(_)) => {
+                        let name = attr(b"name").unwrap_or_default();
+                        source = Some(if package.is_empty() { name } else { format!("{package}/{name}") });
+                    }
+                    b"line" => {
+                        let (Some(file), Some(nr)) = (&source, attr(b"nr").and_then(|v| v.parse().ok())) else { continue };
+                        let covered = attr(b"ci").and_then(|v| v.parse::<u64>().ok()).unwrap_or(0) > 0;
+                        report.files.entry(file.clone()).or_default().insert(nr, covered);
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"sourcefile" => source = None,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !seen_report {
+        return Err("缺少 <report> 根元素".to_string());
+    }
+    Ok(report)
+}
+
+impl CoverageReport {
+    /// 问题路径对应的行覆盖 (报告路径是问题路径的后缀，取最长的匹配)
+    fn lines_for(&self, path: &str) -> Option<&HashMap<usize, bool>> {
+        let path = path.replace('\\', "/");
+        self.files.iter()
+            .filter(|(file, _)| path == **file || path.ends_with(&format!("/{file}")))
+            .max_by_key(|(file, _)| file.len())
+            .map(|(_, lines)| lines)
+    }
+
+    /// 行范围 [start, end] 的覆盖；范围内没有插桩行时为 None
+    fn coverage_of(&self, path: &str, start: usize, end: usize) -> Option<Coverage> {
+        let lines = self.lines_for(path)?;
+        let mut instrumented = (start..=end).filter_map(|line| lines.get(&line)).peekable();
+        instrumented.peek()?;
+        Some(if instrumented.any(|covered| *covered) { Coverage::Covered } else { Coverage::Uncovered })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// 按所在方法 (或所在行) 标注覆盖
+pub fn annotate(issues: &mut [AstIssue], spans: &HashMap<String, Vec<MethodSpan>>, report: &CoverageReport) {
+    for issue in issues.iter_mut() {
+        let (start, end) = spans.get(issue.path.as_str())
+            .and_then(|s| hotness::innermost(s, issue.line))
+            .map_or((issue.line, issue.line), |span| (span.start_line, span.end_line));
+        issue.coverage = report.coverage_of(issue.path.as_str(), start, end);
+    }
+}
+
+/// 覆盖率关联统计
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CoverageSummary {
+    /// JaCoCo 报告路径
+    pub report: String,
+    pub covered: usize,
+    pub uncovered: usize,
+    /// 报告中没有对应文件或行未插桩
+    pub unknown: usize,
+    /// 未覆盖代码中的 P0 (最高风险)
+    pub uncovered_p0: usize,
+}
+
+pub fn summarize(report: &Path, issues: &[AstIssue]) -> CoverageSummary {
+    let count = |coverage: Option<Coverage>| issues.iter().filter(|i| i.coverage == coverage).count();
+    CoverageSummary {
+        report: report.display().to_string(),
+        covered: count(Some(Coverage::Covered)),
+        uncovered: count(Some(Coverage::Uncovered)),
+        unknown: count(None),
+        uncovered_p0: issues.iter().filter(|i| is_highest_risk(i)).count(),
+    }
+}
+
+fn is_highest_risk(issue: &AstIssue) -> bool {
+    issue.severity == Severity::P0 && issue.coverage == Some(Coverage::Uncovered)
+}
+
+/// 问题行的覆盖标记 (只标出未覆盖)
+pub fn format_coverage(issue: &AstIssue) -> &'static str {
+    if issue.coverage == Some(Coverage::Uncovered) { " 🧪未覆盖" } else { "" }
+}
+
+/// Markdown 报告中的覆盖率章节：未覆盖代码中的 P0
+pub fn render_section(summary: &CoverageSummary, issues: &[AstIssue], p0_label: &str) -> String {
+    let mut section = format!(
+        "\n### 🧪 测试覆盖 (JaCoCo)\n\n已覆盖 {} · 未覆盖 {} · 未知 {}\n",
+        summary.covered, summary.uncovered, summary.unknown,
+    );
+    if summary.uncovered_p0 == 0 {
+        return section;
+    }
+    section.push_str(&format!("\n**最高风险 - 未覆盖代码中的 {p0_label} ({})**\n\n", summary.uncovered_p0));
+    for issue in issues.iter().filter(|i| is_highest_risk(i)).take(TOP_RISKS) {
+        let method = issue.method.as_deref().map(|m| format!(" `{m}`")).unwrap_or_default();
+        section.push_str(&format!("- `{}:{}` {}{method}\n", issue.path, issue.line, issue.issue_type));
+    }
+    if summary.uncovered_p0 > TOP_RISKS {
+        section.push_str(&format!("- ... 另有 {} 个\n", summary.uncovered_p0 - TOP_RISKS));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JACOCO: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<!DOCTYPE report PUBLIC "-//JACOCO//DTD Report 1.1//EN" "report.dtd">
+<report name="orders">
+  <sessioninfo id="ci" start="1" dump="2"/>
+  <group name="order-service">
+    <package name="com/shop">
+      <class name="com/shop/OrderService" sourcefilename="OrderService.java">
+        <method name="load" desc="()V" line="4"><counter type="LINE" missed="0" covered="2"/></method>
+      </class>
+      <sourcefile name="OrderService.java">
+        <line nr="4" mi="0" ci="3" mb="0" cb="0"/>
+        <line nr="5" mi="2" ci="0" mb="0" cb="0"/>
+        <line nr="9" mi="4" ci="0" mb="0" cb="0"/>
+        <line nr="10" mi="2" ci="0" mb="0" cb="0"/>
+        <counter type="LINE" missed="3" covered="1"/>
+      </sourcefile>
+    </package>
+  </group>
+</report>"#;
+
+    fn issue(path: &str, line: usize, severity: Severity) -> AstIssue {
+        serde_json::from_value(serde_json::json!({
+            "severity": severity, "id": "N_PLUS_ONE", "file": "", "path": path, "line": line, "description": ""
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_annotate_by_enclosing_method() {
+        let report = parse(JACOCO).unwrap();
+        let path = "order-service/src/main/java/com/shop/OrderService.java";
+        let spans = HashMap::from([(path.to_string(), vec![
+            MethodSpan { class: "OrderService".into(), method: "load".into(), fqn: "com.shop.OrderService.load".into(), start_line: 3, end_line: 6 },
+            MethodSpan { class: "OrderService".into(), method: "export".into(), fqn: "com.shop.OrderService.export".into(), start_line: 8, end_line: 11 },
+        ])]);
+        let mut issues = vec![
+            // load() 的第 4 行被执行过：同一方法内未执行的第 5 行也算已覆盖
+            issue(path, 5, Severity::P0),
+            issue(path, 10, Severity::P0),
+            issue(path, 10, Severity::P1),
+            // 方法外、未插桩的行与报告中没有的文件
+            issue(path, 1, Severity::P0),
+            issue("src/main/java/com/shop/Other.java", 3, Severity::P0),
+            issue("src/main/java/org/shop/OrderService.java", 5, Severity::P0),
+        ];
+        annotate(&mut issues, &spans, &report);
+        let coverage: Vec<Option<Coverage>> = issues.iter().map(|i| i.coverage).collect();
+        assert_eq!(coverage, [Some(Coverage::Covered), Some(Coverage::Uncovered), Some(Coverage::Uncovered), None, None, None]);
+
+        let summary = summarize(Path::new("jacoco.xml"), &issues);
+        assert_eq!((summary.covered, summary.uncovered, summary.unknown, summary.uncovered_p0), (1, 2, 3, 1));
+        let section = render_section(&summary, &issues, "P0");
+        assert!(section.contains("已覆盖 1 · 未覆盖 2 · 未知 3"), "{section}");
+        assert!(section.contains("未覆盖代码中的 P0 (1)"), "{section}");
+        assert!(section.contains(&format!("- `{path}:10` N_PLUS_ONE")), "{section}");
+        assert_eq!(format_coverage(&issues[1]), " 🧪未覆盖");
+    }
+
+    #[test]
+    fn test_load_rejects_non_jacoco() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("coverage.xml");
+        std::fs::write(&file, "<coverage line-rate=\"0.5\"></coverage>").unwrap();
+        assert!(load(&file).unwrap_err().contains("JaCoCo"));
+        assert!(load(&dir.path().join("missing.xml")).is_err());
+    }
+}
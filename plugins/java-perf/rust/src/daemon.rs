@@ -0,0 +1,512 @@
+//! 常驻进程 (`daemon`)：项目索引常驻内存，经 unix socket 以 JSON-RPC 2.0 应答查询
+//!
+//! 每次调用 CLI 都要重新编译规则 Query、构建符号表与调用图，大项目上冷启动需要数秒；而 skill 在一次排查中
+//! 通常会连续追问 (某个文件、某个类、修改后重扫某个目录)。daemon 启动时构建一次，之后的请求都复用:
+//!
+//! - 协议: 每行一个 JSON-RPC 2.0 请求、每行一个响应 (没有 `id` 的通知不响应)，一个连接可发送多个请求
+//! - 方法: `analyze_file` / `analyze_range` / `query_symbol` / `list_rules` / `rescan` / `status` / `shutdown`
+//! - `analyze_range` 只重新分析包含修改范围 (`start`/`end` 字节偏移) 的方法 (见 region 模块)，供编辑器保存时调用
+//! - `rescan` 按内容哈希增量更新索引 (同 `.javaperf/symbols.bin`)，再分析指定子目录
+//! - socket 默认为 `<项目>/.javaperf/daemon.sock`；已有 daemon 在监听时拒绝启动，异常退出残留的 socket 文件直接替换
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+
+use crate::ast_engine::{locate_issue, relative_path, AstIssue, FileAnalyzers, Severity};
+use crate::dedup::{dedup, DedupPolicy};
+use crate::encoding::read_source;
+use crate::inspect;
+use crate::shared_path::FilePath;
+use crate::project_config;
+use crate::project_detector::detect_stack;
+use crate::region;
+use crate::rules::registry::{category_of, fix_suggestion, RuleRegistry};
+use crate::symbol_cache::{self, ProjectIndex};
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 默认 socket 文件名 (位于项目 .javaperf/ 下)
+const SOCKET_FILE: &str = "daemon.sock";
+
+// JSON-RPC 2.0 错误码
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// 服务端错误 (文件读取失败等)
+const SERVER_ERROR: i64 = -32000;
+
+/// 项目的默认 socket 路径
+pub fn default_socket(root: &Path) -> PathBuf {
+    root.join(symbol_cache::CACHE_DIR).join(SOCKET_FILE)
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self { code: INVALID_PARAMS, message: message.into() }
+    }
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+/// 常驻的项目状态: 规则与分析器只编译一次，索引由 `rescan` 增量更新
+///
+/// 请求取当前索引的 Arc 快照后即释放锁；`rescan` 在锁外重建索引，只在替换时短暂持有写锁，
+/// 分析期间不阻塞其他请求。
+pub struct Daemon {
+    root: PathBuf,
+    registry: RuleRegistry,
+    analyzers: FileAnalyzers,
+    index: RwLock<Arc<ProjectIndex>>,
+    started: Instant,
+    requests: AtomicU64,
+    stopping: AtomicBool,
+}
+
+impl Daemon {
+    /// 编译规则 (技术栈调整 + .javaperf.toml) 并构建项目索引
+    pub fn open(code_path: &str) -> Result<Self, BoxError> {
+        let root = Path::new(code_path).canonicalize().map_err(|e| format!("{code_path}: {e}"))?;
+        if !root.is_dir() {
+            return Err(format!("{code_path} 不是目录").into());
+        }
+        let config = project_config::load(&root)?;
+        let mut registry = RuleRegistry::with_stack(&detect_stack(&root));
+        registry.apply_overrides(&config.rule_overrides)?;
+        let analyzers = FileAnalyzers::new(&registry)?.with_exclusions(&root, config.exclusions()?);
+        let index = load_index(&root, &analyzers);
+        tracing::info!(root = %root.display(), classes = index.table.classes.len(), "daemon 索引已就绪");
+        Ok(Self {
+            root,
+            registry,
+            analyzers,
+            index: RwLock::new(Arc::new(index)),
+            started: Instant::now(),
+            requests: AtomicU64::new(0),
+            stopping: AtomicBool::new(false),
+        })
+    }
+
+    /// 处理一行请求；通知 (没有 id) 返回 None
+    pub fn handle(&self, line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, e.to_string())),
+        };
+        let id = request.get("id").cloned();
+        let method = request["method"].as_str().filter(|_| request["jsonrpc"] == "2.0");
+        let Some(method) = method else {
+            return Some(error_response(id.unwrap_or(Value::Null), INVALID_REQUEST, "请求需要 \"jsonrpc\": \"2.0\" 与 method"));
+        };
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let _span = tracing::debug_span!("rpc", method).entered();
+        let result = self.call(method, request.get("params").unwrap_or(&Value::Null));
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_response(id, e.code, e.message),
+        })
+    }
+
+    fn call(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "analyze_file" => self.analyze_file(params),
+            "analyze_range" => self.analyze_range(params),
+            "query_symbol" => {
+                let name = str_param(params, "name")?;
+                Ok(inspect::inspect_indexed(&self.root, name, &self.index(), &self.analyzers))
+            }
+            "list_rules" => Ok(self.list_rules()),
+            "rescan" => self.rescan(params),
+            "status" => Ok(self.status()),
+            "shutdown" => {
+                self.stopping.store(true, Ordering::SeqCst);
+                Ok(json!(true))
+            }
+            _ => Err(RpcError { code: METHOD_NOT_FOUND, message: format!("未知方法: {method}") }),
+        }
+    }
+
+    /// 带项目上下文分析单个文件；`content` 参数可传入未保存的内容
+    fn analyze_file(&self, params: &Value) -> Result<Value, RpcError> {
+        let (file, content) = self.source(params)?;
+        let issues = self.analyze(&file, &content, &self.index());
+        Ok(json!({ "file": relative_path(&self.root, &file), "issues": issues }))
+    }
+
+    /// 只分析包含 `start..end` 字节范围的方法，问题位置仍相对整个文件
+    fn analyze_range(&self, params: &Value) -> Result<Value, RpcError> {
+        let (file, content) = self.source(params)?;
+        let start = usize_param(params, "start")?;
+        let end = usize_param(params, "end")?;
+        let rel_path = relative_path(&self.root, &file);
+        let index = self.index();
+        let analysis = region::analyze_range(
+            &self.analyzers, &file, &rel_path, &content, start..end, Some(&index.table), Some(&index.graph),
+        ).map_err(RpcError::invalid_params)?;
+        Ok(json!({ "file": rel_path, "region": analysis.region, "issues": analysis.issues }))
+    }
+
+    /// 请求中的文件及其内容 (`content` 参数优先于磁盘内容)
+    fn source(&self, params: &Value) -> Result<(PathBuf, String), RpcError> {
+        let file = self.resolve(str_param(params, "file")?)?;
+        if !file.is_file() || !FileAnalyzers::is_target(&file) {
+            return Err(RpcError::invalid_params(format!("{} 不是 Java/配置/Dockerfile 文件", file.display())));
+        }
+        let content = match params.get("content").and_then(Value::as_str) {
+            Some(content) => content.to_string(),
+            None => read_source(&file)
+                .map_err(|e| RpcError { code: SERVER_ERROR, message: format!("{}: {e}", file.display()) })?
+                .content,
+        };
+        Ok((file, content))
+    }
+
+    /// 增量更新索引后分析子目录 (默认整个项目)
+    fn rescan(&self, params: &Value) -> Result<Value, RpcError> {
+        let path = params.get("path").and_then(Value::as_str).unwrap_or(".");
+        let subtree = self.resolve(path)?;
+        let index = Arc::new(load_index(&self.root, &self.analyzers));
+        *self.index.write().expect("index lock") = Arc::clone(&index);
+        let stats = index.stats.clone();
+
+        let files: Vec<PathBuf> = WalkDir::new(&subtree)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git" && e.file_name() != symbol_cache::CACHE_DIR)
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && FileAnalyzers::is_target(e.path()))
+            .map(|e| e.into_path())
+            .collect();
+        let mut issues: Vec<AstIssue> = files.par_iter()
+            .filter_map(|file| read_source(file).ok().map(|source| self.analyze(file, &source.content, &index)))
+            .flatten()
+            .collect();
+        issues.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+
+        let count = |severity: Severity| issues.iter().filter(|i| i.severity == severity).count();
+        Ok(json!({
+            "path": path,
+            "files": files.len(),
+            "p0": count(Severity::P0),
+            "p1": count(Severity::P1),
+            "issues": issues,
+            "index": stats,
+        }))
+    }
+
+    /// 当前索引的快照 (读锁只在克隆 Arc 时持有)
+    fn index(&self) -> Arc<ProjectIndex> {
+        Arc::clone(&self.index.read().expect("index lock"))
+    }
+
+    fn analyze(&self, file: &Path, content: &str, index: &ProjectIndex) -> Vec<AstIssue> {
+        let rel_path = FilePath::new(&relative_path(&self.root, file));
+        let issues: Vec<AstIssue> = self.analyzers
+            .analyze_file(file, content, Some(&index.table), Some(&index.graph))
+            .into_iter()
+            .map(|issue| locate_issue(issue, &rel_path, content))
+            .collect();
+        let mut issues = dedup(issues, DedupPolicy::default());
+        issues.sort_by_key(|issue| issue.line);
+        issues
+    }
+
+    /// 规则元数据 (含技术栈调整后的严重级别与启用状态)
+    fn list_rules(&self) -> Value {
+        let rules: Vec<Value> = self.registry.rules().iter()
+            .map(|rule| json!({
+                "id": rule.id,
+                "severity": rule.severity_override.unwrap_or(rule.severity),
+                "enabled": rule.enabled,
+                "category": category_of(rule.id).map(|c| c.name()),
+                "description": rule.description,
+                "fix": fix_suggestion(rule.id),
+                "version_note": self.registry.version_note(rule.id),
+            }))
+            .collect();
+        json!(rules)
+    }
+
+    fn status(&self) -> Value {
+        let index = self.index();
+        json!({
+            "root": self.root.display().to_string(),
+            "version": env!("CARGO_PKG_VERSION"),
+            "uptime_secs": self.started.elapsed().as_secs(),
+            "requests": self.requests.load(Ordering::Relaxed),
+            "java_files": index.stats.reused + index.stats.reindexed,
+            "classes": index.table.classes.len(),
+            "call_sites": index.graph.outgoing.values().map(Vec::len).sum::<usize>(),
+        })
+    }
+
+    /// 请求中的路径 (相对项目根或绝对路径)，必须位于项目内
+    fn resolve(&self, path: &str) -> Result<PathBuf, RpcError> {
+        let resolved = self.root.join(path).canonicalize()
+            .map_err(|e| RpcError::invalid_params(format!("{path}: {e}")))?;
+        if !resolved.starts_with(&self.root) {
+            return Err(RpcError::invalid_params(format!("{path} 不在项目 {} 内", self.root.display())));
+        }
+        Ok(resolved)
+    }
+
+    fn stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+}
+
+fn str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    params.get(name).and_then(Value::as_str)
+        .ok_or_else(|| RpcError::invalid_params(format!("缺少字符串参数 {name}")))
+}
+
+fn usize_param(params: &Value, name: &str) -> Result<usize, RpcError> {
+    params.get(name).and_then(Value::as_u64).map(|n| n as usize)
+        .ok_or_else(|| RpcError::invalid_params(format!("缺少非负整数参数 {name}")))
+}
+
+/// 构建 (或按哈希增量更新) 项目索引
+fn load_index(root: &Path, analyzers: &FileAnalyzers) -> ProjectIndex {
+    let files = symbol_cache::java_files(root);
+    let paths: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+    symbol_cache::build(root, &paths, analyzers.java(), false, &HashSet::new())
+}
+
+/// 在 socket 上应答请求直到收到 `shutdown`，返回处理的请求数
+#[cfg(unix)]
+pub fn serve(daemon: Arc<Daemon>, socket: &Path) -> Result<u64, BoxError> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let listener = bind(socket)?;
+    tracing::info!(socket = %socket.display(), "daemon 已启动");
+    for stream in listener.incoming() {
+        if daemon.stopping() {
+            break;
+        }
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!(error = %e, "接受连接失败");
+                continue;
+            }
+        };
+        let daemon = Arc::clone(&daemon);
+        let socket = socket.to_path_buf();
+        std::thread::spawn(move || {
+            let Ok(mut writer) = stream.try_clone() else { return };
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if let Some(response) = daemon.handle(&line) {
+                    if writeln!(writer, "{response}").is_err() {
+                        break;
+                    }
+                }
+                if daemon.stopping() {
+                    // 唤醒阻塞在 accept 上的主循环
+                    let _ = UnixStream::connect(&socket);
+                    break;
+                }
+            }
+        });
+    }
+    let _ = std::fs::remove_file(socket);
+    Ok(daemon.requests.load(Ordering::Relaxed))
+}
+
+#[cfg(not(unix))]
+pub fn serve(_daemon: Arc<Daemon>, _socket: &Path) -> Result<u64, BoxError> {
+    Err("daemon 需要 unix socket，当前平台不支持".into())
+}
+
+/// 监听 socket；已有 daemon 在监听时报错，残留的 socket 文件删除后重新绑定
+#[cfg(unix)]
+fn bind(socket: &Path) -> Result<std::os::unix::net::UnixListener, BoxError> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(format!("{} 上已有 daemon 在运行", socket.display()).into());
+        }
+        std::fs::remove_file(socket)?;
+    }
+    if let Some(dir) = socket.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let listener = UnixListener::bind(socket)?;
+    // 请求可读取项目任意源码，只允许当前用户连接
+    std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVICE: &str = r#"package com.shop;
+@Service
+public class OrderService {
+    @Autowired
+    private OrderRepository orderRepository;
+
+    public void load(List<Long> ids) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+    }
+}
+"#;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("OrderRepository.java"), "package com.shop;\n@Repository\npublic class OrderRepository {}\n").unwrap();
+        std::fs::write(src.join("OrderService.java"), SERVICE).unwrap();
+        dir
+    }
+
+    fn call(daemon: &Daemon, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        daemon.handle(&request.to_string()).unwrap()
+    }
+
+    fn ids(issues: &Value) -> Vec<&str> {
+        issues.as_array().unwrap().iter().map(|i| i["id"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_handle_methods() {
+        let dir = project();
+        let daemon = Daemon::open(dir.path().to_str().unwrap()).unwrap();
+
+        let rules = call(&daemon, "list_rules", Value::Null);
+        let n_plus_one = rules["result"].as_array().unwrap().iter().find(|r| r["id"] == "N_PLUS_ONE").unwrap();
+        assert_eq!(n_plus_one["enabled"], true);
+        assert!(n_plus_one["fix"].is_string());
+
+        // 字段类型经常驻索引解析到 Repository
+        let analyzed = call(&daemon, "analyze_file", json!({ "file": "src/OrderService.java" }));
+        assert_eq!(analyzed["result"]["file"], "src/OrderService.java");
+        assert!(ids(&analyzed["result"]["issues"]).contains(&"N_PLUS_ONE"), "{analyzed}");
+        let unsaved = SERVICE.replace("orderRepository.findById(id);", "");
+        let analyzed = call(&daemon, "analyze_file", json!({ "file": "src/OrderService.java", "content": unsaved }));
+        assert!(!ids(&analyzed["result"]["issues"]).contains(&"N_PLUS_ONE"), "{analyzed}");
+
+        let symbol = call(&daemon, "query_symbol", json!({ "name": "OrderService" }));
+        assert_eq!(symbol["result"]["classes"][0]["fqn"], "com.shop.OrderService");
+
+        // 修改后重扫子目录
+        std::fs::write(dir.path().join("src/OrderService.java"), &unsaved).unwrap();
+        let rescan = call(&daemon, "rescan", json!({ "path": "src" }));
+        assert_eq!(rescan["result"]["files"], 2);
+        assert_eq!(rescan["result"]["index"]["reindexed"], 1);
+        assert!(!ids(&rescan["result"]["issues"]).contains(&"N_PLUS_ONE"), "{rescan}");
+
+        let status = call(&daemon, "status", Value::Null);
+        assert_eq!(status["result"]["classes"], 2);
+        assert_eq!(status["result"]["requests"], 6);
+    }
+
+    #[test]
+    fn test_analyze_range() {
+        let dir = project();
+        let daemon = Daemon::open(dir.path().to_str().unwrap()).unwrap();
+        let edited = SERVICE.replace(
+            "\n}\n",
+            "\n\n    public String join(List<String> parts) {\n        String out = \"\";\n        for (String p : parts) {\n            out += p;\n        }\n        return out;\n    }\n}\n",
+        );
+        let at = |needle: &str| edited.find(needle).unwrap();
+        let range = |needle: &str| json!({
+            "file": "src/OrderService.java", "content": edited, "start": at(needle), "end": at(needle) + needle.len()
+        });
+
+        let load = call(&daemon, "analyze_range", range("findById"));
+        assert_eq!(load["result"]["region"]["method"], "com.shop.OrderService.load", "{load}");
+        assert_eq!(ids(&load["result"]["issues"]), ["N_PLUS_ONE"]);
+        assert_eq!(load["result"]["issues"][0]["line"], 9);
+
+        let join = call(&daemon, "analyze_range", range("out += p;"));
+        assert_eq!(join["result"]["region"]["start_line"], 13);
+        let join_ids = ids(&join["result"]["issues"]);
+        assert!(!join_ids.contains(&"N_PLUS_ONE") && join_ids.contains(&"STRING_CONCAT_LOOP"), "{join}");
+
+        let code = |response: Value| response["error"]["code"].as_i64().unwrap();
+        assert_eq!(code(call(&daemon, "analyze_range", json!({ "file": "src/OrderService.java", "start": 0 }))), INVALID_PARAMS);
+        assert_eq!(code(call(&daemon, "analyze_range", json!({ "file": "src/OrderService.java", "start": 0, "end": 100_000 }))), INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        let dir = project();
+        let daemon = Daemon::open(dir.path().to_str().unwrap()).unwrap();
+        let code = |response: Value| response["error"]["code"].as_i64().unwrap();
+
+        assert_eq!(code(daemon.handle("{not json").unwrap()), PARSE_ERROR);
+        assert_eq!(code(daemon.handle(r#"{"id": 1, "method": "status"}"#).unwrap()), INVALID_REQUEST);
+        assert_eq!(code(call(&daemon, "scan", Value::Null)), METHOD_NOT_FOUND);
+        assert_eq!(code(call(&daemon, "query_symbol", json!({}))), INVALID_PARAMS);
+        assert_eq!(code(call(&daemon, "analyze_file", json!({ "file": "../outside.java" }))), INVALID_PARAMS);
+        assert_eq!(code(call(&daemon, "analyze_file", json!({ "file": "src" }))), INVALID_PARAMS);
+        // 通知不响应
+        assert!(daemon.handle(r#"{"jsonrpc": "2.0", "method": "status"}"#).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_serve_until_shutdown() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        let dir = project();
+        let socket = default_socket(dir.path());
+        let daemon = Arc::new(Daemon::open(dir.path().to_str().unwrap()).unwrap());
+        let server = {
+            let socket = socket.clone();
+            std::thread::spawn(move || serve(daemon, &socket).unwrap())
+        };
+        let stream = loop {
+            match UnixStream::connect(&socket) {
+                Ok(stream) => break stream,
+                Err(_) => std::thread::sleep(std::time::Duration::from_millis(20)),
+            }
+        };
+        // 已有 daemon 在监听时拒绝启动
+        assert!(bind(&socket).unwrap_err().to_string().contains("已有 daemon"));
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&socket).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut exchange = |request: &str| {
+            writeln!(writer, "{request}").unwrap();
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            serde_json::from_str::<Value>(&line).unwrap()
+        };
+        assert_eq!(exchange(r#"{"jsonrpc":"2.0","id":"a","method":"status"}"#)["id"], "a");
+        assert_eq!(exchange(r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#)["result"], true);
+        assert_eq!(server.join().unwrap(), 2);
+        assert!(!socket.exists());
+    }
+}
@@ -0,0 +1,288 @@
+//! 不可达分支识别 (`if (false)` / 常量开关)
+//!
+//! 条件为编译期常量的分支不会执行，其中的问题不值得投入修复：
+//!
+//! ```java
+//! static final boolean LEGACY_EXPORT = false;   // 已下线的功能开关
+//!
+//! if (LEGACY_EXPORT) {
+//!     for (Order o : orders) orderRepository.save(o);   // 标记为 inactive，P0 降为 P1
+//! }
+//! ```
+//!
+//! 常量来源：字面量 `true`/`false`，以及 `static final boolean X = true|false`
+//! (接口字段隐式 static final)。跨文件引用 `Flags.LEGACY_EXPORT` 通过 SymbolTable 解析。
+//! 条件支持 `!`、`&&`、`||` 与括号；无法求值的条件视为活跃代码。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::scanner::node_walk::{NodeWalk, TYPE_DECLARATIONS};
+
+/// 布尔常量声明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoolConstant {
+    /// 声明所在类型的简单名
+    pub class: String,
+    pub name: String,
+    pub value: bool,
+}
+
+/// 不可达的行范围
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InactiveRange {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// 快速预筛：文件中是否存在 if 语句
+pub fn is_candidate(code: &str) -> bool {
+    code.contains("if (") || code.contains("if(")
+}
+
+fn text<'a>(node: Node, code: &'a str) -> &'a str {
+    node.utf8_text(code.as_bytes()).unwrap_or("")
+}
+
+fn bool_literal(node: Node) -> Option<bool> {
+    match node.kind() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// 收集 `static final boolean X = true|false` 常量
+pub fn collect_constants(root: Node, code: &str) -> Vec<BoolConstant> {
+    let mut constants = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let kind = node.kind();
+        if kind == "field_declaration" || kind == "constant_declaration" {
+            constants.extend(field_constants(node, code));
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    constants
+}
+
+fn field_constants(field: Node, code: &str) -> Vec<BoolConstant> {
+    let Some(class) = NodeWalk::new(code).enclosing_type_name(field) else {
+        return Vec::new();
+    };
+    if field.child_by_field_name("type").map(|t| text(t, code)) != Some("boolean") {
+        return Vec::new();
+    }
+    let in_interface = NodeWalk::new(code).ancestor_of_kind(field, TYPE_DECLARATIONS).is_some_and(|t| t.kind() == "interface_declaration");
+    let mut cursor = field.walk();
+    let modifiers = field.children(&mut cursor)
+        .find(|c| c.kind() == "modifiers")
+        .map(|m| text(m, code))
+        .unwrap_or("");
+    let is_constant = in_interface || (modifiers.contains("static") && modifiers.contains("final"));
+    if !is_constant {
+        return Vec::new();
+    }
+
+    let mut cursor = field.walk();
+    field.children_by_field_name("declarator", &mut cursor)
+        .filter_map(|declarator| {
+            let name = text(declarator.child_by_field_name("name")?, code);
+            let value = bool_literal(declarator.child_by_field_name("value")?)?;
+            Some(BoolConstant { class: class.to_string(), name: name.to_string(), value })
+        })
+        .collect()
+}
+
+/// 求值常量条件；无法确定时返回 None
+///
+/// lookup(当前类, 引用) 解析 `FLAG` / `Flags.FLAG` 形式的常量。
+fn eval(node: Node, code: &str, class: &str, lookup: &dyn Fn(&str, &str) -> Option<bool>) -> Option<bool> {
+    match node.kind() {
+        "true" | "false" => bool_literal(node),
+        "parenthesized_expression" => eval(node.named_child(0)?, code, class, lookup),
+        "identifier" | "field_access" => lookup(class, text(node, code)),
+        "unary_expression" => {
+            let operator = node.child_by_field_name("operator").map(|o| text(o, code));
+            if operator == Some("!") {
+                eval(node.child_by_field_name("operand")?, code, class, lookup).map(|v| !v)
+            } else {
+                None
+            }
+        }
+        "binary_expression" => {
+            let operator = node.child_by_field_name("operator").map(|o| text(o, code))?;
+            let left = eval(node.child_by_field_name("left")?, code, class, lookup);
+            let right = eval(node.child_by_field_name("right")?, code, class, lookup);
+            match operator {
+                "&&" => match (left, right) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                },
+                "||" => match (left, right) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn range(node: Node) -> InactiveRange {
+    InactiveRange {
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+    }
+}
+
+/// 查找条件恒假的 then 分支与条件恒真的 else 分支
+pub fn inactive_ranges(root: Node, code: &str, lookup: &dyn Fn(&str, &str) -> Option<bool>) -> Vec<InactiveRange> {
+    let mut ranges = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "if_statement" {
+            let class = NodeWalk::new(code).enclosing_type_name(node).unwrap_or("");
+            let value = node.child_by_field_name("condition").and_then(|c| eval(c, code, class, lookup));
+            let dead = match value {
+                Some(false) => node.child_by_field_name("consequence"),
+                Some(true) => node.child_by_field_name("alternative"),
+                None => None,
+            };
+            if let Some(dead) = dead {
+                // 整个分支不可达，内部无需再检查
+                ranges.push(range(dead));
+                let live = if value == Some(false) { "alternative" } else { "consequence" };
+                stack.extend(node.child_by_field_name(live));
+                continue;
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    ranges.sort_by_key(|r| r.start_line);
+    ranges
+}
+
+/// 按引用形式查找常量 (`FLAG` 为当前类，`Flags.FLAG` / `com.x.Flags.FLAG` 取最后两段)
+pub fn lookup_in<'a>(constants: impl IntoIterator<Item = &'a BoolConstant>, current_class: &str, reference: &str) -> Option<bool> {
+    let mut parts = reference.rsplitn(3, '.');
+    let name = parts.next()?.trim();
+    let class = parts.next().map(str::trim).unwrap_or(current_class);
+    constants.into_iter().find(|c| c.class == class && c.name == name).map(|c| c.value)
+}
+
+/// 标记落在不可达分支中的问题：inactive = true，P0 降为 P1
+pub fn mark(issues: &mut [AstIssue], ranges: &HashMap<String, Vec<InactiveRange>>) {
+    for issue in issues.iter_mut() {
+        let Some(file_ranges) = ranges.get(issue.path.as_str()) else {
+            continue;
+        };
+        if file_ranges.iter().any(|r| r.start_line <= issue.line && issue.line <= r.end_line) {
+            issue.inactive = true;
+            issue.severity = Severity::P1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+    fn analyze(code: &str, external: &[BoolConstant]) -> (Vec<BoolConstant>, Vec<(usize, usize)>) {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(code, |tree| {
+            let local = collect_constants(tree.root_node(), code);
+            let lookup = |class: &str, reference: &str| {
+                lookup_in(local.iter().chain(external), class, reference)
+            };
+            let ranges = inactive_ranges(tree.root_node(), code, &lookup)
+                .into_iter()
+                .map(|r| (r.start_line, r.end_line))
+                .collect();
+            (local, ranges)
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_collect_static_final_booleans() {
+        let code = "class Flags {\n    static final boolean OFF = false, ON = true;\n    static boolean MUTABLE = false;\n    final boolean instance = false;\n}\ninterface Toggles {\n    boolean BETA = false;\n}\n";
+        let (constants, _) = analyze(code, &[]);
+        let mut names: Vec<_> = constants.iter().map(|c| (c.class.as_str(), c.name.as_str(), c.value)).collect();
+        names.sort();
+        assert_eq!(names, vec![("Flags", "OFF", false), ("Flags", "ON", true), ("Toggles", "BETA", false)]);
+    }
+
+    #[test]
+    fn test_constant_conditions() {
+        let code = r#"class OrderService {
+    private static final boolean LEGACY = false;
+    void run(boolean dynamic) {
+        if (false) {
+            a();
+        }
+        if (LEGACY && dynamic) {
+            b();
+        } else {
+            c();
+        }
+        if (!Flags.BETA) {
+            d();
+        } else {
+            e();
+        }
+        if (dynamic || LEGACY) {
+            f();
+        }
+    }
+}
+"#;
+        let external = [BoolConstant { class: "Flags".into(), name: "BETA".into(), value: false }];
+        let (_, ranges) = analyze(code, &external);
+        assert_eq!(ranges, vec![(4, 6), (7, 9), (14, 16)]);
+    }
+
+    #[test]
+    fn test_mark_downgrades_issues_in_range() {
+        let issue = |line: usize| AstIssue {
+            severity: Severity::P0,
+            issue_type: "N_PLUS_ONE".to_string(),
+            file: "A.java".to_string(),
+            path: "src/A.java".into(),
+            line,
+            column: 0,
+            description: String::new(),
+            context: None,
+            snippet: String::new(),
+            fingerprint: String::new(),
+            hotness: 0,
+            method: None,
+            secondary: Vec::new(),
+            inactive: false,
+            suppressed: false,
+            suppression: None,
+            owners: Vec::new(),
+            suggested_patch: None,
+            triage: None,
+            age: None,
+            budget: None,
+            coverage: None,
+            debt: None,
+            sql: None,
+        };
+        let mut issues = vec![issue(5), issue(12)];
+        let ranges = HashMap::from([("src/A.java".to_string(), vec![InactiveRange { start_line: 4, end_line: 6 }])]);
+        mark(&mut issues, &ranges);
+        assert!(issues[0].inactive && issues[0].severity == Severity::P1);
+        assert!(!issues[1].inactive && issues[1].severity == Severity::P0);
+    }
+}
@@ -0,0 +1,199 @@
+//! 重叠问题合并 (Dedup)
+//!
+//! 同一处代码常被多条规则同时命中，例如循环内的
+//! `orderRepository.save(new Order(prefix + id))` 会同时报告
+//! N_PLUS_ONE / OBJECT_IN_LOOP / STRING_CONCAT_LOOP。合并后只保留一条主问题，
+//! 其余规则 ID 记入 `secondary`，避免同一行刷屏。
+//!
+//! 主问题选择: 严重级别高者优先，同级别按重叠组内的顺序 (越靠前越根本)。
+//!
+//! | 策略 | 行为 |
+//! |------|------|
+//! | `group` (默认) | 同一行上属于同一重叠组的问题合并 |
+//! | `line` | 同一行上的所有问题合并 |
+//! | `off` | 不合并 |
+
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+
+use crate::ast_engine::{AstIssue, Severity};
+
+/// 合并策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DedupPolicy {
+    /// 不合并
+    Off,
+    /// 同一行、同一重叠组的问题合并
+    #[default]
+    Group,
+    /// 同一行的所有问题合并
+    Line,
+}
+
+/// 描述同一根因的规则组 (组内顺序即主问题优先级)
+pub const OVERLAP_GROUPS: &[&[&str]] = &[
+    // 循环体内的开销：N+1 是根因，对象创建/字符串拼接是附带症状
+    &[
+        "FIND_BY_ID_IN_LOOP", "N_PLUS_ONE", "N_PLUS_ONE_WHILE", "N_PLUS_ONE_FOREACH",
+        "NESTED_LOOP", "NESTED_LOOP_MIXED", "OBJECT_IN_LOOP", "STRING_CONCAT_LOOP",
+    ],
+    // 无超时等待：同一个 get()/join() 调用
+    &["COMPLETABLE_GET_NO_TIMEOUT", "FUTURE_GET_NO_TIMEOUT", "COMPLETABLE_JOIN", "AWAIT_NO_TIMEOUT"],
+    // 锁内阻塞
+    &["SLEEP_IN_LOCK", "LOCK_METHOD_CALL", "SYNC_BLOCK", "BLOCKING_IO"],
+    // Optional.get()
+    &["STREAM_FIND_GET", "OPTIONAL_BARE_GET"],
+];
+
+/// 规则所在的重叠组及组内位置
+fn group_of(rule_id: &str) -> Option<(usize, usize)> {
+    OVERLAP_GROUPS.iter().enumerate().find_map(|(group, ids)| {
+        ids.iter().position(|id| *id == rule_id).map(|rank| (group, rank))
+    })
+}
+
+fn severity_rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::P0 => 0,
+        Severity::P1 => 1,
+    }
+}
+
+/// 按策略合并重叠问题 (保持主问题的原始顺序)
+pub fn dedup(issues: Vec<AstIssue>, policy: DedupPolicy) -> Vec<AstIssue> {
+    if policy == DedupPolicy::Off {
+        return issues;
+    }
+
+    // (path, line, 组) -> 该组的问题下标；不属于任何组的问题在 group 策略下不合并
+    let mut buckets: HashMap<(&str, usize, Option<usize>), Vec<usize>> = HashMap::new();
+    for (idx, issue) in issues.iter().enumerate() {
+        let group = match policy {
+            DedupPolicy::Line => None,
+            _ => match group_of(&issue.issue_type) {
+                Some((group, _)) => Some(group),
+                None => continue,
+            },
+        };
+        buckets.entry((issue.path.as_str(), issue.line, group)).or_default().push(idx);
+    }
+
+    // 下标 -> 被合并进的主问题下标
+    let mut merged_into: HashMap<usize, usize> = HashMap::new();
+    for members in buckets.values().filter(|m| m.len() > 1) {
+        let primary = *members.iter()
+            .min_by_key(|&&idx| {
+                let issue = &issues[idx];
+                let rank = group_of(&issue.issue_type).map_or(usize::MAX, |(_, rank)| rank);
+                (severity_rank(issue.severity), rank, idx)
+            })
+            .expect("bucket is non-empty");
+        for &idx in members.iter().filter(|&&idx| idx != primary) {
+            merged_into.insert(idx, primary);
+        }
+    }
+    if merged_into.is_empty() {
+        return issues;
+    }
+
+    let mut secondary: HashMap<usize, Vec<String>> = HashMap::new();
+    for (&idx, &primary) in &merged_into {
+        let ids = secondary.entry(primary).or_default();
+        ids.push(issues[idx].issue_type.clone());
+        ids.extend(issues[idx].secondary.iter().cloned());
+    }
+
+    issues.into_iter()
+        .enumerate()
+        .filter(|(idx, _)| !merged_into.contains_key(idx))
+        .map(|(idx, mut issue)| {
+            if let Some(mut ids) = secondary.remove(&idx) {
+                ids.retain(|id| *id != issue.issue_type);
+                issue.secondary.extend(ids);
+                issue.secondary.sort();
+                issue.secondary.dedup();
+            }
+            issue
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, severity: Severity, line: usize) -> AstIssue {
+        AstIssue {
+            severity,
+            issue_type: id.to_string(),
+            file: "OrderService.java".to_string(),
+            path: "src/OrderService.java".into(),
+            line,
+            column: 0,
+            description: String::new(),
+            context: None,
+            snippet: String::new(),
+            fingerprint: String::new(),
+            hotness: 0,
+            method: None,
+            secondary: Vec::new(),
+            inactive: false,
+            suppressed: false,
+            suppression: None,
+            owners: Vec::new(),
+            suggested_patch: None,
+            triage: None,
+            age: None,
+            budget: None,
+            coverage: None,
+            debt: None,
+            sql: None,
+        }
+    }
+
+    fn summary(issues: &[AstIssue]) -> Vec<(String, usize, Vec<String>)> {
+        issues.iter().map(|i| (i.issue_type.clone(), i.line, i.secondary.clone())).collect()
+    }
+
+    fn sample() -> Vec<AstIssue> {
+        vec![
+            issue("STRING_CONCAT_LOOP", Severity::P1, 10),
+            issue("OBJECT_IN_LOOP", Severity::P1, 10),
+            issue("N_PLUS_ONE", Severity::P0, 10),
+            issue("LOG_STRING_CONCAT", Severity::P1, 10),
+            issue("OBJECT_IN_LOOP", Severity::P1, 12),
+        ]
+    }
+
+    #[test]
+    fn test_group_policy_merges_same_root_cause() {
+        let result = dedup(sample(), DedupPolicy::Group);
+        assert_eq!(summary(&result), vec![
+            ("N_PLUS_ONE".to_string(), 10, vec!["OBJECT_IN_LOOP".to_string(), "STRING_CONCAT_LOOP".to_string()]),
+            ("LOG_STRING_CONCAT".to_string(), 10, vec![]),
+            ("OBJECT_IN_LOOP".to_string(), 12, vec![]),
+        ]);
+    }
+
+    #[test]
+    fn test_line_and_off_policies() {
+        let line = dedup(sample(), DedupPolicy::Line);
+        assert_eq!(line.len(), 2);
+        assert_eq!(line[0].issue_type, "N_PLUS_ONE");
+        assert_eq!(line[0].secondary, vec!["LOG_STRING_CONCAT", "OBJECT_IN_LOOP", "STRING_CONCAT_LOOP"]);
+
+        assert_eq!(dedup(sample(), DedupPolicy::Off).len(), 5);
+    }
+
+    #[test]
+    fn test_same_severity_uses_group_order() {
+        let result = dedup(vec![
+            issue("FUTURE_GET_NO_TIMEOUT", Severity::P0, 7),
+            issue("COMPLETABLE_GET_NO_TIMEOUT", Severity::P0, 7),
+        ], DedupPolicy::Group);
+        assert_eq!(summary(&result), vec![
+            ("COMPLETABLE_GET_NO_TIMEOUT".to_string(), 7, vec!["FUTURE_GET_NO_TIMEOUT".to_string()]),
+        ]);
+    }
+}
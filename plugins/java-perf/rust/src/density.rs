@@ -0,0 +1,219 @@
+//! 问题密度 (每千行代码的问题数)
+//!
+//! 问题总数天然偏向大模块: 十万行的订单服务有 40 个问题，未必比两千行、有 10 个问题的网关更糟。
+//! 扫描时统计每个文件的代码行数 (LOC，不含空行与注释)，在汇总中给出 `issues_per_kloc`:
+//!
+//! - 整个项目 (JSON `loc` / `issues_per_kloc`)
+//! - 按包 (JSON `packages`，Markdown "问题密度" 章节)：Java 文件取 `package` 声明，其它文件取所在目录
+//! - 按规则类别 (JSON `categories[].issues_per_kloc`，分母为项目 LOC)
+//! - 按服务 (`scan --per-service` 总览表)
+//!
+//! 包排名按密度降序；LOC 少于 [`MIN_RANKED_LOC`] 的包只列出、不参与 Markdown 排名，
+//! 避免几十行的小包因一个问题排到最前面。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::ast_engine::{AstIssue, Severity};
+
+/// Markdown 中列出的包数
+pub const TOP_PACKAGES: usize = 10;
+
+/// 参与 Markdown 排名的最小 LOC
+pub const MIN_RANKED_LOC: usize = 200;
+
+static PACKAGE_DECL: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*package\s+([\w.]+)\s*;").unwrap());
+
+/// 单个文件的代码行数与所属包
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileLoc {
+    pub loc: usize,
+    pub package: String,
+}
+
+/// 各文件 (相对路径) 的代码行数
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LocIndex {
+    files: BTreeMap<String, FileLoc>,
+}
+
+impl LocIndex {
+    pub fn record(&mut self, rel_path: String, file: FileLoc) {
+        self.files.insert(rel_path, file);
+    }
+
+    /// 项目总 LOC
+    pub fn total(&self) -> usize {
+        self.files.values().map(|f| f.loc).sum()
+    }
+}
+
+/// 单个包的问题密度
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PackageDensity {
+    pub package: String,
+    pub files: usize,
+    pub loc: usize,
+    pub p0: usize,
+    pub p1: usize,
+    pub issues_per_kloc: f64,
+}
+
+/// 文件的代码行数: Java 不计空行、`//` 与 `/* */` 注释行；配置文件/Dockerfile 不计空行与 `#`/`!` 注释行
+pub fn count_loc(path: &Path, content: &str) -> usize {
+    if path.extension().and_then(|e| e.to_str()) != Some("java") {
+        return content.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('!'))
+            .count();
+    }
+    let mut in_block = false;
+    let mut loc = 0;
+    for line in content.lines() {
+        let mut rest = line.trim();
+        let mut code = false;
+        while !rest.is_empty() {
+            if in_block {
+                match rest.find("*/") {
+                    Some(end) => {
+                        in_block = false;
+                        rest = rest[end + 2..].trim_start();
+                    }
+                    None => break,
+                }
+            } else if rest.starts_with("//") {
+                break;
+            } else if let Some(body) = rest.strip_prefix("/*") {
+                in_block = true;
+                rest = body;
+            } else {
+                // 注释前有代码 (字符串中的 "/*" 也按代码行计)
+                code = true;
+                break;
+            }
+        }
+        if code {
+            loc += 1;
+        }
+    }
+    loc
+}
+
+/// 文件所属的包: Java 的 `package` 声明，否则为所在目录 (根目录为 `.`)
+pub fn package_of(rel_path: &str, content: &str) -> String {
+    if rel_path.ends_with(".java") {
+        if let Some(caps) = PACKAGE_DECL.captures(content) {
+            return caps[1].to_string();
+        }
+    }
+    match rel_path.rsplit_once('/') {
+        Some((dir, _)) => dir.to_string(),
+        None => ".".to_string(),
+    }
+}
+
+/// 每千行问题数 (保留两位小数；LOC 为 0 时为 0)
+pub fn per_kloc(issues: usize, loc: usize) -> f64 {
+    if loc == 0 {
+        return 0.0;
+    }
+    (issues as f64 * 1000.0 / loc as f64 * 100.0).round() / 100.0
+}
+
+/// 按包汇总，密度降序 (相同时 LOC 多的在前)
+pub fn by_package(index: &LocIndex, issues: &[AstIssue]) -> Vec<PackageDensity> {
+    let mut packages: BTreeMap<&str, PackageDensity> = BTreeMap::new();
+    for file in index.files.values() {
+        let entry = packages.entry(file.package.as_str()).or_insert_with(|| PackageDensity {
+            package: file.package.clone(),
+            files: 0,
+            loc: 0,
+            p0: 0,
+            p1: 0,
+            issues_per_kloc: 0.0,
+        });
+        entry.files += 1;
+        entry.loc += file.loc;
+    }
+    for issue in issues {
+        let Some(file) = index.files.get(issue.path.as_str()) else { continue };
+        if let Some(entry) = packages.get_mut(file.package.as_str()) {
+            match issue.severity {
+                Severity::P0 => entry.p0 += 1,
+                Severity::P1 => entry.p1 += 1,
+            }
+        }
+    }
+    let mut summaries: Vec<PackageDensity> = packages.into_values()
+        .map(|mut p| {
+            p.issues_per_kloc = per_kloc(p.p0 + p.p1, p.loc);
+            p
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.issues_per_kloc.total_cmp(&a.issues_per_kloc).then(b.loc.cmp(&a.loc)));
+    summaries
+}
+
+/// Markdown 报告中的包密度章节 (只列有问题且 LOC 不少于 [`MIN_RANKED_LOC`] 的包)
+pub fn render_section(packages: &[PackageDensity], p0_label: &str, p1_label: &str) -> String {
+    let ranked: Vec<&PackageDensity> = packages.iter()
+        .filter(|p| p.p0 + p.p1 > 0 && p.loc >= MIN_RANKED_LOC)
+        .take(TOP_PACKAGES)
+        .collect();
+    if ranked.is_empty() {
+        return String::new();
+    }
+    let mut section = format!(
+        "\n### 📏 问题密度 (按包)\n\n| 包 | 问题/KLoC | {p0_label} | {p1_label} | LOC | 文件 |\n|----|-----------|----|----|-----|------|\n"
+    );
+    for p in ranked {
+        section.push_str(&format!(
+            "| `{}` | {:.2} | {} | {} | {} | {} |\n", p.package, p.issues_per_kloc, p.p0, p.p1, p.loc, p.files
+        ));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_loc_skips_comments_and_blank_lines() {
+        let java = "package com.shop;\n\n/**\n * 订单\n */\npublic class A { // 类\n    /* 行内 */ int x;\n    // 注释\n    String s = \"/*\";\n}\n";
+        assert_eq!(count_loc(Path::new("A.java"), java), 5);
+        assert_eq!(count_loc(Path::new("application.properties"), "# 端口\nserver.port=8080\n\n! 旧写法\n"), 1);
+        assert_eq!(package_of("src/main/java/com/shop/A.java", java), "com.shop");
+        assert_eq!(package_of("config/application.yml", ""), "config");
+        assert_eq!(package_of("Dockerfile", ""), ".");
+    }
+
+    #[test]
+    fn test_by_package_normalizes_by_loc() {
+        let mut index = LocIndex::default();
+        index.record("big/Orders.java".into(), FileLoc { loc: 4000, package: "com.big".into() });
+        index.record("small/Gate.java".into(), FileLoc { loc: 500, package: "com.small".into() });
+        let issue = |path: &str, severity: Severity| -> AstIssue {
+            serde_json::from_value(serde_json::json!({
+                "severity": severity, "id": "N_PLUS_ONE", "file": "", "path": path, "line": 1, "description": ""
+            })).unwrap()
+        };
+        let mut issues: Vec<AstIssue> = (0..4).map(|_| issue("big/Orders.java", Severity::P1)).collect();
+        issues.push(issue("small/Gate.java", Severity::P0));
+        issues.push(issue("small/Gate.java", Severity::P1));
+
+        let packages = by_package(&index, &issues);
+        assert_eq!(packages[0].package, "com.small");
+        assert_eq!((packages[0].p0, packages[0].p1, packages[0].issues_per_kloc), (1, 1, 4.0));
+        assert_eq!(packages[1].issues_per_kloc, 1.0);
+        assert_eq!(index.total(), 4500);
+        assert_eq!(per_kloc(6, 4500), 1.33);
+
+        let section = render_section(&packages, "P0", "P1");
+        assert!(section.contains("| `com.small` | 4.00 | 1 | 1 | 500 | 1 |"), "{section}");
+    }
+}
@@ -0,0 +1,377 @@
+//! 依赖重复与膨胀分析 (DEP_VERSION_CONFLICT / DEP_OVERLAPPING_LIBS)
+//!
+//! 读取各模块的构建文件 (Maven 有效依赖见 `project_detector::MavenResolver`，Gradle 含版本目录引用)，
+//! 在整个项目范围内检查:
+//!
+//! - **版本冲突**: 同一 `groupId:artifactId` 在不同模块的依赖或 `dependencyManagement` 中声明了
+//!   不同版本。运行时只有依赖调解选中的一个版本生效，另一模块按旧版 API 编译时会出现
+//!   `NoSuchMethodError`，fat jar / 镜像中也可能同时打入两份。
+//! - **功能重叠**: 同时引入多个同类库 (JSON、HTTP 客户端、连接池、日志实现)，每套都要在启动时
+//!   加载类与初始化，增加启动时间与镜像体积。Spring Boot starter 隐含引入的库 (如 starter-web → Jackson)
+//!   一并计入，context 中注明来源，便于确认是否已经 `<exclusion>`。
+//!
+//! - **javax / jakarta 混用**: 见 [`crate::jakarta_audit`]，依据各模块的依赖声明与 Java import。
+//!
+//! 只统计主代码依赖 (test scope 与 testImplementation 等除外)；功能重叠另外排除 provided /
+//! compileOnly / annotationProcessor 等不进入运行时的依赖。问题定位到构建文件中首次声明该库的行。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::ast_engine::{AstIssue, ImportIndexMap, Severity};
+use crate::jakarta_audit;
+use crate::shared_path::FilePath;
+use crate::project_detector::{
+    self, load_version_catalogs, parse_catalog_references, parse_gradle_build, parse_maven_model,
+    BuildScope, DependencyScope, GradleConfiguration,
+};
+use crate::rules::registry::DEPENDENCY_RULES;
+use crate::scanner::issue_builder::{fingerprint, line_snippet};
+
+pub const RULES: &[&str] = DEPENDENCY_RULES;
+
+/// 功能重叠的库族: (族名, [(groupId:artifactId, 库名)])
+const LIBRARY_FAMILIES: &[(&str, &[(&str, &str)])] = &[
+    ("JSON", &[
+        ("com.fasterxml.jackson.core:jackson-databind", "Jackson"),
+        ("com.google.code.gson:gson", "Gson"),
+        ("com.alibaba:fastjson", "Fastjson"),
+        ("com.alibaba.fastjson2:fastjson2", "Fastjson2"),
+        ("org.json:json", "org.json"),
+        ("org.eclipse:yasson", "JSON-B (Yasson)"),
+    ]),
+    ("HTTP 客户端", &[
+        ("org.apache.httpcomponents:httpclient", "Apache HttpClient 4"),
+        ("org.apache.httpcomponents.client5:httpclient5", "Apache HttpClient 5"),
+        ("com.squareup.okhttp3:okhttp", "OkHttp"),
+        ("org.asynchttpclient:async-http-client", "AsyncHttpClient"),
+        ("org.eclipse.jetty:jetty-client", "Jetty HttpClient"),
+    ]),
+    ("数据库连接池", &[
+        ("com.zaxxer:HikariCP", "HikariCP"),
+        ("com.alibaba:druid", "Druid"),
+        ("com.alibaba:druid-spring-boot-starter", "Druid"),
+        ("com.mchange:c3p0", "c3p0"),
+        ("org.apache.commons:commons-dbcp2", "DBCP2"),
+        ("org.apache.tomcat:tomcat-jdbc", "Tomcat JDBC Pool"),
+    ]),
+    ("日志实现", &[
+        ("ch.qos.logback:logback-classic", "Logback"),
+        ("org.apache.logging.log4j:log4j-core", "Log4j2"),
+        ("org.slf4j:slf4j-simple", "slf4j-simple"),
+        ("log4j:log4j", "Log4j 1.x"),
+    ]),
+];
+
+/// Spring Boot starter 隐含引入的库: (starter artifactId, 隐含的 groupId:artifactId)
+const STARTER_IMPLIES: &[(&str, &str)] = &[
+    ("spring-boot-starter-web", "com.fasterxml.jackson.core:jackson-databind"),
+    ("spring-boot-starter-webflux", "com.fasterxml.jackson.core:jackson-databind"),
+    ("spring-boot-starter-json", "com.fasterxml.jackson.core:jackson-databind"),
+    ("spring-boot-starter-jdbc", "com.zaxxer:HikariCP"),
+    ("spring-boot-starter-data-jpa", "com.zaxxer:HikariCP"),
+];
+
+/// 构建文件中的一条依赖声明
+#[derive(Debug, Clone)]
+pub(crate) struct Declared {
+    /// groupId:artifactId
+    pub(crate) coordinate: String,
+    pub(crate) version: Option<String>,
+    /// 构建文件 (相对扫描根目录)
+    pub(crate) file: String,
+    /// 进入运行时 classpath
+    pub(crate) runtime: bool,
+}
+
+impl Declared {
+    fn artifact(&self) -> &str {
+        self.coordinate.rsplit(':').next().unwrap_or(&self.coordinate)
+    }
+
+    /// 所在模块 (构建文件所在目录，根模块为空)
+    pub(crate) fn module(&self) -> &str {
+        self.file.rsplit_once('/').map_or("", |(dir, _)| dir)
+    }
+}
+
+/// 分析项目构建文件 (只读取 scope 内的文件) 与各文件的 import，返回版本冲突、功能重叠与命名空间混用问题
+pub fn analyze(root: &Path, scope: &BuildScope, imports: &ImportIndexMap) -> Vec<AstIssue> {
+    let declared = if root.join("pom.xml").is_file() {
+        maven_declarations(root, scope)
+    } else {
+        gradle_declarations(root, scope)
+    };
+    let mut issues = version_conflicts(root, &declared);
+    issues.extend(overlapping_libraries(root, &declared));
+    issues.extend(jakarta_audit::analyze(root, &declared, imports));
+    issues
+}
+
+/// 根 pom 与各子模块 pom 的有效依赖，及各模块自身 dependencyManagement 中的显式版本
+fn maven_declarations(root: &Path, scope: &BuildScope) -> Vec<Declared> {
+    let resolver = scope.maven_resolver();
+    let modules = std::iter::once(String::new()).chain(project_detector::detect_modules_in(root, scope));
+    let mut declared = Vec::new();
+    for module in modules {
+        let pom_path = root.join(&module).join("pom.xml");
+        if !scope.admits(&pom_path) {
+            continue;
+        }
+        let file = if module.is_empty() { "pom.xml".to_string() } else { format!("{module}/pom.xml") };
+        let (Ok(effective), Some(pom)) = (
+            resolver.resolve(&pom_path),
+            std::fs::read_to_string(&pom_path).ok().and_then(|c| parse_maven_model(&c).ok()),
+        ) else {
+            continue;
+        };
+        for dep in effective.dependencies.iter().filter(|d| d.scope.is_main_scope()) {
+            // 继承自 parent 的依赖只在 parent 的 pom 中计一次
+            let own = pom.dependencies.iter().any(|d| d.artifact_id == dep.artifact_id);
+            if !own && !module.is_empty() {
+                continue;
+            }
+            declared.push(Declared {
+                coordinate: format!("{}:{}", dep.group_id, dep.artifact_id),
+                version: dep.version.clone(),
+                file: file.clone(),
+                runtime: !matches!(dep.scope, DependencyScope::Provided | DependencyScope::System),
+            });
+        }
+        for managed in pom.managed.iter().filter(|m| m.scope != DependencyScope::Import) {
+            let coordinate = effective.managed_versions.keys()
+                .find(|key| key.ends_with(&format!(":{}", managed.artifact_id)))
+                .cloned();
+            let Some(coordinate) = coordinate else { continue };
+            declared.push(Declared {
+                version: effective.managed_versions.get(&coordinate).cloned(),
+                coordinate,
+                file: file.clone(),
+                runtime: false,
+            });
+        }
+    }
+    declared
+}
+
+/// 各 Gradle 构建脚本中的依赖 (含版本目录引用)
+fn gradle_declarations(root: &Path, scope: &BuildScope) -> Vec<Declared> {
+    let catalogs = load_version_catalogs(root);
+    let mut declared = Vec::new();
+    for script in project_detector::gradle_build_scripts(root, scope) {
+        let Ok(content) = std::fs::read_to_string(&script) else { continue };
+        let mut deps = parse_gradle_build(&content).unwrap_or_default();
+        deps.extend(parse_catalog_references(&content, &catalogs));
+        let file = crate::ast_engine::relative_path(root, &script);
+        for dep in deps.into_iter().filter(|d| d.configuration.is_main_configuration()) {
+            declared.push(Declared {
+                coordinate: format!("{}:{}", dep.group, dep.name),
+                version: dep.version,
+                file: file.clone(),
+                runtime: !matches!(dep.configuration, GradleConfiguration::CompileOnly | GradleConfiguration::AnnotationProcessor),
+            });
+        }
+    }
+    declared
+}
+
+/// 同一坐标声明了多个版本: 每个坐标报告一次，定位到少数派版本的第一处声明
+fn version_conflicts(root: &Path, declared: &[Declared]) -> Vec<AstIssue> {
+    let mut by_coordinate: BTreeMap<&str, BTreeMap<&str, Vec<&Declared>>> = BTreeMap::new();
+    for dep in declared {
+        // 未插值的 ${...} 无法比较
+        let Some(version) = dep.version.as_deref().filter(|v| !v.contains("${")) else { continue };
+        by_coordinate.entry(&dep.coordinate).or_default().entry(version).or_default().push(dep);
+    }
+
+    let mut issues = Vec::new();
+    for (coordinate, versions) in by_coordinate.into_iter().filter(|(_, versions)| versions.len() > 1) {
+        let context: Vec<String> = versions.iter()
+            .map(|(version, deps)| {
+                let mut files: Vec<&str> = deps.iter().map(|d| d.file.as_str()).collect();
+                files.dedup();
+                format!("{version} ({})", files.join(", "))
+            })
+            .collect();
+        // 声明次数最多的版本视为主流版本，报告位置取其余版本中的第一处
+        let majority = versions.iter().max_by_key(|(_, deps)| deps.len()).map(|(version, _)| *version);
+        let Some(target) = versions.iter().find(|(version, _)| Some(**version) != majority).map(|(_, deps)| deps[0]) else {
+            continue;
+        };
+        issues.extend(issue(
+            root,
+            "DEP_VERSION_CONFLICT",
+            target,
+            format!("{coordinate} 在多个模块中声明了不同版本，运行时只有依赖调解选中的一个版本生效，按其他版本编译的代码可能抛出 NoSuchMethodError"),
+            format!("{coordinate}: {}", context.join(" / ")),
+        ));
+    }
+    issues
+}
+
+/// 同一库族引入了多个库: 每个库族报告一次，定位到第二个库的第一处声明
+fn overlapping_libraries(root: &Path, declared: &[Declared]) -> Vec<AstIssue> {
+    let runtime: Vec<&Declared> = declared.iter().filter(|d| d.runtime).collect();
+    let mut issues = Vec::new();
+    for (family, members) in LIBRARY_FAMILIES {
+        // 库名 -> (首次声明, 来源说明)
+        let mut found: Vec<(&str, &Declared, String)> = Vec::new();
+        for dep in &runtime {
+            let direct = members.iter().find(|(coordinate, _)| *coordinate == dep.coordinate);
+            let implied = STARTER_IMPLIES.iter()
+                .filter(|(starter, _)| dep.artifact() == *starter)
+                .find_map(|(_, coordinate)| members.iter().find(|(member, _)| member == coordinate));
+            let (name, source) = match (direct, implied) {
+                (Some((_, name)), _) => (*name, dep.coordinate.clone()),
+                (None, Some((_, name))) => (*name, format!("经 {} 引入", dep.artifact())),
+                (None, None) => continue,
+            };
+            if !found.iter().any(|(existing, _, _)| *existing == name) {
+                found.push((name, dep, source));
+            }
+        }
+        if found.len() < 2 {
+            continue;
+        }
+        let libraries: Vec<String> = found.iter().map(|(name, dep, source)| format!("{name} ({source}, {})", dep.file)).collect();
+        let names: Vec<&str> = found.iter().map(|(name, _, _)| *name).collect();
+        issues.extend(issue(
+            root,
+            "DEP_OVERLAPPING_LIBS",
+            found[1].1,
+            format!("同时引入多个{family}库 ({})，每套库都在启动时加载与初始化，增加启动时间与部署包体积", names.join(" / ")),
+            libraries.join("; "),
+        ));
+    }
+    issues
+}
+
+/// 在构建文件中定位依赖声明行并构造问题
+pub(crate) fn issue(root: &Path, id: &str, dep: &Declared, description: String, context: String) -> Option<AstIssue> {
+    let content = std::fs::read_to_string(root.join(&dep.file)).ok()?;
+    let line = declaration_line(&content, dep.artifact()).unwrap_or(1);
+    let snippet = line_snippet(&content, line);
+    let scope = Path::new(&dep.file).file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    Some(AstIssue {
+        severity: Severity::P1,
+        issue_type: id.to_string(),
+        file: dep.file.rsplit('/').next().unwrap_or(&dep.file).to_string(),
+        path: FilePath::new(&dep.file),
+        line,
+        column: 0,
+        description,
+        context: Some(context),
+        fingerprint: fingerprint(id, &format!("{scope}:{}", dep.coordinate), &snippet),
+        snippet,
+        hotness: 0,
+        method: None,
+        secondary: Vec::new(),
+        inactive: false,
+        suppressed: false,
+        suppression: None,
+        owners: Vec::new(),
+        suggested_patch: None,
+        triage: None,
+        age: None,
+        budget: None,
+        coverage: None,
+        debt: None,
+        sql: None,
+    })
+}
+
+/// 首个包含完整 artifactId 的行 (前后不是标识符字符，`httpclient` 不匹配 `httpclient5`)
+fn declaration_line(content: &str, artifact: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.';
+    content.lines().position(|line| {
+        line.match_indices(artifact).any(|(start, _)| {
+            let before = line[..start].chars().next_back();
+            let after = line[start + artifact.len()..].chars().next();
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        })
+    }).map(|idx| idx + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn dep(group: &str, artifact: &str, version: Option<&str>) -> String {
+        let version = version.map(|v| format!("<version>{v}</version>")).unwrap_or_default();
+        format!("<dependency><groupId>{group}</groupId><artifactId>{artifact}</artifactId>{version}</dependency>")
+    }
+
+    #[test]
+    fn test_maven_multi_module_conflicts_and_overlaps() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "pom.xml", &format!(
+            "<project><groupId>com.acme</groupId><artifactId>parent</artifactId><version>1</version>\n\
+             <modules><module>api</module><module>legacy</module></modules>\n\
+             <dependencyManagement><dependencies>\n{}\n</dependencies></dependencyManagement>\n\
+             <dependencies>\n{}\n{}\n</dependencies></project>\n",
+            dep("com.google.guava", "guava", Some("32.1.0-jre")),
+            dep("org.springframework.boot", "spring-boot-starter-web", Some("3.2.0")),
+            dep("junit", "junit", Some("4.13")),
+        ));
+        let parent = "<parent><groupId>com.acme</groupId><artifactId>parent</artifactId><version>1</version></parent>";
+        write(root, "api/pom.xml", &format!(
+            "<project>{parent}<artifactId>api</artifactId>\n<dependencies>\n{}\n{}\n</dependencies></project>\n",
+            dep("com.google.guava", "guava", None),
+            dep("com.squareup.okhttp3", "okhttp", Some("4.12.0")),
+        ));
+        write(root, "legacy/pom.xml", &format!(
+            "<project>{parent}<artifactId>legacy</artifactId>\n<dependencies>\n{}\n{}\n{}\n</dependencies></project>\n",
+            dep("com.google.guava", "guava", Some("20.0")),
+            dep("com.alibaba", "fastjson", Some("1.2.83")),
+            dep("org.apache.httpcomponents", "httpclient", Some("4.5.14")),
+        ));
+
+        let issues = analyze(root, &BuildScope::default(), &ImportIndexMap::new());
+        let conflicts: Vec<&AstIssue> = issues.iter().filter(|i| i.issue_type == "DEP_VERSION_CONFLICT").collect();
+        assert_eq!(conflicts.len(), 1, "{issues:#?}");
+        // 父 pom 管理版本 + api 继承 = 主流版本，legacy 的 20.0 为少数派
+        assert_eq!((conflicts[0].path.as_str(), conflicts[0].line), ("legacy/pom.xml", 3));
+        assert_eq!(
+            conflicts[0].context.as_deref(),
+            Some("com.google.guava:guava: 20.0 (legacy/pom.xml) / 32.1.0-jre (pom.xml, api/pom.xml)")
+        );
+
+        let overlaps: Vec<&AstIssue> = issues.iter().filter(|i| i.issue_type == "DEP_OVERLAPPING_LIBS").collect();
+        assert_eq!(overlaps.len(), 2, "{overlaps:#?}");
+        assert!(overlaps[0].description.contains("JSON库 (Jackson / Fastjson)"), "{}", overlaps[0].description);
+        assert!(overlaps[0].context.as_deref().unwrap().contains("Jackson (经 spring-boot-starter-web 引入, pom.xml)"));
+        assert_eq!((overlaps[0].path.as_str(), overlaps[0].line), ("legacy/pom.xml", 4));
+        assert!(overlaps[1].description.contains("HTTP 客户端库 (OkHttp / Apache HttpClient 4)"), "{}", overlaps[1].description);
+        assert!(overlaps.iter().all(|i| i.severity == Severity::P1 && !i.fingerprint.is_empty()));
+    }
+
+    #[test]
+    fn test_gradle_scopes_and_single_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "settings.gradle", "include 'app', 'tools'\n");
+        write(root, "app/build.gradle", "dependencies {\n    implementation 'com.zaxxer:HikariCP:5.1.0'\n    implementation 'com.google.code.gson:gson:2.10.1'\n    testImplementation 'com.alibaba:druid:1.2.20'\n}\n");
+        write(root, "tools/build.gradle", "dependencies {\n    compileOnly 'com.mchange:c3p0:0.9.5.5'\n    implementation 'com.google.code.gson:gson:2.8.9'\n}\n");
+
+        let issues = analyze(root, &BuildScope::default(), &ImportIndexMap::new());
+        // 测试依赖与 compileOnly 不计入连接池重叠；gson 版本不一致
+        let ids: Vec<&str> = issues.iter().map(|i| i.issue_type.as_str()).collect();
+        assert_eq!(ids, ["DEP_VERSION_CONFLICT"], "{issues:#?}");
+        assert_eq!((issues[0].path.as_str(), issues[0].line), ("app/build.gradle", 3));
+    }
+
+    #[test]
+    fn test_declaration_line_matches_whole_artifact() {
+        let content = "<artifactId>httpclient5</artifactId>\n<artifactId>httpclient</artifactId>\n";
+        assert_eq!(declaration_line(content, "httpclient"), Some(2));
+        assert_eq!(declaration_line("implementation 'com.squareup.okhttp3:okhttp:4.12.0'", "okhttp"), Some(1));
+        assert_eq!(declaration_line("nothing here", "okhttp"), None);
+    }
+}
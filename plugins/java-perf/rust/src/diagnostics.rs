@@ -0,0 +1,206 @@
+//! 扫描诊断：跳过的规则、规则执行限制、解析失败、语法版本不一致等
+//!
+//! 这些情况此前只以日志警告输出到 stderr，CI 中通常无人查看，规则被静默跳过也不会反映在报告里。
+//! 现在统一经 [`report`] 记录 (同时照常输出日志)，由扫描汇总到 `AnalysisReport.diagnostics`，
+//! Markdown 报告单列"扫描诊断"章节，JSON 输出 `diagnostics`。
+//!
+//! 收集范围按线程划分: [`Scope`] 收集当前线程上报告的诊断 (如规则编译)，Phase 2 工作线程用 [`capture`]
+//! 收集单个文件分析中的诊断并随文件结果汇总。不在收集范围内报告的诊断只输出日志。
+
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+
+/// Markdown 章节中每类最多列出的条数
+const MAX_LISTED: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// 规则 Query 无法编译，整条规则未执行
+    SkippedRule,
+    /// 规则匹配超时或超过匹配数上限，结果可能不完整
+    RuleLimit,
+    /// 文件无法读取或解析，未分析
+    ParseFailure,
+    /// 链接的语法版本与规则查询集不一致
+    GrammarMismatch,
+    /// 其他影响结果的警告 (覆盖率报告为空、检查点写入失败等)
+    Warning,
+}
+
+impl DiagnosticKind {
+    fn title(self) -> &'static str {
+        match self {
+            DiagnosticKind::SkippedRule => "跳过的规则",
+            DiagnosticKind::RuleLimit => "规则执行受限",
+            DiagnosticKind::ParseFailure => "未分析的文件",
+            DiagnosticKind::GrammarMismatch => "语法版本",
+            DiagnosticKind::Warning => "警告",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// 相对路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(kind: DiagnosticKind, message: impl Into<String>) -> Self {
+        Self { kind, rule: None, file: None, message: message.into() }
+    }
+
+    pub fn rule(mut self, rule: &str) -> Self {
+        self.rule = Some(rule.to_string());
+        self
+    }
+
+    pub fn file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+}
+
+thread_local! {
+    /// 当前线程的收集栈 (嵌套时记录到最内层)
+    static COLLECTORS: RefCell<Vec<Vec<Diagnostic>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// 报告诊断: 输出警告日志，并记录到当前线程的收集范围 (如有)
+pub fn report(diagnostic: Diagnostic) {
+    tracing::warn!(
+        kind = ?diagnostic.kind,
+        rule = diagnostic.rule.as_deref().unwrap_or(""),
+        file = diagnostic.file.as_deref().unwrap_or(""),
+        "{}", diagnostic.message
+    );
+    COLLECTORS.with(|stack| {
+        if let Some(top) = stack.borrow_mut().last_mut() {
+            top.push(diagnostic);
+        }
+    });
+}
+
+/// 报告 [`DiagnosticKind::Warning`]
+pub fn warn(message: impl Into<String>) {
+    report(Diagnostic::new(DiagnosticKind::Warning, message));
+}
+
+/// 执行 `f` 并收集其间当前线程报告的诊断
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Diagnostic>) {
+    let scope = Scope::enter();
+    let result = f();
+    (result, scope.finish())
+}
+
+/// 收集范围: 存活期间收集当前线程报告的诊断，`finish` 取出 (drop 时丢弃)
+pub struct Scope {
+    depth: usize,
+}
+
+impl Scope {
+    pub fn enter() -> Self {
+        let depth = COLLECTORS.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.push(Vec::new());
+            stack.len()
+        });
+        Self { depth }
+    }
+
+    pub fn finish(self) -> Vec<Diagnostic> {
+        let collected = COLLECTORS.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.truncate(self.depth);
+            stack.pop().unwrap_or_default()
+        });
+        std::mem::forget(self);
+        collected
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        COLLECTORS.with(|stack| stack.borrow_mut().truncate(self.depth - 1));
+    }
+}
+
+/// 按类别、规则、文件排序并去重 (同一规则在多个分析器实例上可能重复报告)
+pub fn normalize(diagnostics: &mut Vec<Diagnostic>) {
+    diagnostics.sort_by(|a, b| (a.kind, &a.rule, &a.file, &a.message).cmp(&(b.kind, &b.rule, &b.file, &b.message)));
+    diagnostics.dedup();
+}
+
+/// Markdown 报告中的诊断章节 (没有诊断时为空)
+pub fn render_section(diagnostics: &[Diagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return String::new();
+    }
+    let mut section = format!("\n### 🩺 扫描诊断 ({})\n\n以下情况可能导致问题漏报，请检查后再判断扫描结果:\n", diagnostics.len());
+    let mut kinds: Vec<DiagnosticKind> = diagnostics.iter().map(|d| d.kind).collect();
+    kinds.dedup();
+    for kind in kinds {
+        let entries: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.kind == kind).collect();
+        section.push_str(&format!("\n**{} ({})**\n\n", kind.title(), entries.len()));
+        for d in entries.iter().take(MAX_LISTED) {
+            let subject: Vec<String> = d.rule.iter().chain(&d.file).map(|s| format!("`{s}`")).collect();
+            if subject.is_empty() {
+                section.push_str(&format!("- {}\n", d.message));
+            } else {
+                section.push_str(&format!("- {} {}\n", subject.join(" "), d.message));
+            }
+        }
+        if entries.len() > MAX_LISTED {
+            section.push_str(&format!("- ... 另有 {} 条\n", entries.len() - MAX_LISTED));
+        }
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_nested_scopes() {
+        // 不在收集范围内只输出日志
+        warn("outside");
+        let scope = Scope::enter();
+        report(Diagnostic::new(DiagnosticKind::SkippedRule, "Query 无法编译").rule("N_PLUS_ONE"));
+        let ((), inner) = capture(|| {
+            report(Diagnostic::new(DiagnosticKind::ParseFailure, "无法解析").file("src/A.java"));
+        });
+        // 提前 drop 的内层范围不影响外层
+        drop(Scope::enter());
+        warn("覆盖率报告为空");
+        let outer = scope.finish();
+        assert_eq!(inner, vec![Diagnostic::new(DiagnosticKind::ParseFailure, "无法解析").file("src/A.java")]);
+        assert_eq!(outer.iter().map(|d| d.kind).collect::<Vec<_>>(), [DiagnosticKind::SkippedRule, DiagnosticKind::Warning]);
+        assert!(capture(|| ()).1.is_empty());
+    }
+
+    #[test]
+    fn test_render_section_groups_by_kind() {
+        let mut diagnostics: Vec<Diagnostic> = (0..12)
+            .map(|i| Diagnostic::new(DiagnosticKind::ParseFailure, "无法读取").file(format!("src/F{i:02}.java")))
+            .collect();
+        diagnostics.push(Diagnostic::new(DiagnosticKind::SkippedRule, "Query 无法编译").rule("N_PLUS_ONE"));
+        diagnostics.push(Diagnostic::new(DiagnosticKind::SkippedRule, "Query 无法编译").rule("N_PLUS_ONE"));
+        normalize(&mut diagnostics);
+        assert_eq!(diagnostics.len(), 13);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::SkippedRule);
+
+        let section = render_section(&diagnostics);
+        assert!(section.contains("### 🩺 扫描诊断 (13)"), "{section}");
+        assert!(section.contains("**跳过的规则 (1)**\n\n- `N_PLUS_ONE` Query 无法编译\n"), "{section}");
+        assert!(section.contains("- `src/F09.java` 无法读取\n- ... 另有 2 条\n"), "{section}");
+        assert!(render_section(&[]).is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! 源文件编码处理
+//!
+//! 遗留项目中常见 GBK/GB18030/Shift_JIS 等非 UTF-8 源文件，
+//! `std::fs::read_to_string` 会直接失败，导致文件被静默跳过。
+//!
+//! 处理策略:
+//! 1. BOM 优先 (UTF-8/UTF-16LE/UTF-16BE)，并剥离 BOM 避免列偏移
+//! 2. 合法 UTF-8 直接使用 (零拷贝快路径)
+//! 3. 否则使用 chardetng 探测编码，经 encoding_rs 转码为 UTF-8
+//!
+//! 转码后的文本交给 Tree-sitter 解析，Issue 中的行/列均基于转码后的 UTF-8 文本计算。
+
+use std::path::Path;
+
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+
+/// 已解码的源文件
+#[derive(Debug, Clone)]
+pub struct SourceText {
+    /// UTF-8 文本 (已剥离 BOM)
+    pub content: String,
+    /// 原始编码名称 (如 "UTF-8", "GBK")
+    pub encoding: &'static str,
+    /// 转码过程中是否出现无法映射的字节 (已替换为 U+FFFD)
+    pub had_errors: bool,
+    /// 原文件带 BOM
+    pub bom: bool,
+}
+
+impl SourceText {
+    /// 是否经过转码 (非 UTF-8 原文件)
+    pub fn is_transcoded(&self) -> bool {
+        self.encoding != encoding_rs::UTF_8.name()
+    }
+}
+
+/// 将原始字节解码为 UTF-8 文本
+pub fn decode_bytes(bytes: &[u8]) -> SourceText {
+    // 1. BOM 嗅探
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (content, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return SourceText {
+            content: content.into_owned(),
+            encoding: encoding.name(),
+            had_errors,
+            bom: true,
+        };
+    }
+
+    // 2. UTF-8 快路径
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return SourceText {
+            content: text.to_string(),
+            encoding: encoding_rs::UTF_8.name(),
+            had_errors: false,
+            bom: false,
+        };
+    }
+
+    // 3. chardetng 探测 (不允许猜测为 UTF-8，上一步已排除)
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, false);
+    let (content, had_errors) = encoding.decode_without_bom_handling(bytes);
+
+    SourceText {
+        content: content.into_owned(),
+        encoding: encoding.name(),
+        had_errors,
+        bom: false,
+    }
+}
+
+/// 剥离 UTF-8 BOM (直接传入源码字符串的调用方未经 [`decode_bytes`])
+///
+/// 保证同一文件无论经由 [`read_source`] 还是直接传入字符串，首行的字节偏移与列号一致。
+pub fn strip_bom(code: &str) -> &str {
+    code.strip_prefix('\u{FEFF}').unwrap_or(code)
+}
+
+/// 读取源文件并转码为 UTF-8
+pub fn read_source(path: &Path) -> std::io::Result<SourceText> {
+    let bytes = std::fs::read(path)?;
+    Ok(decode_bytes(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_plain_utf8() {
+        let src = decode_bytes("public class A {} // 中文".as_bytes());
+        assert_eq!(src.encoding, "UTF-8");
+        assert!(!src.is_transcoded());
+        assert!(src.content.ends_with("中文"));
+    }
+
+    #[test]
+    fn test_decode_strips_utf8_bom() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(b"package a;");
+        let src = decode_bytes(&bytes);
+        assert_eq!(src.encoding, "UTF-8");
+        assert_eq!(src.content, "package a;");
+        assert!(src.bom);
+        assert_eq!(strip_bom("\u{FEFF}package a;"), "package a;");
+    }
+
+    #[test]
+    fn test_decode_utf16le_bom() {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "class A {}".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        let src = decode_bytes(&bytes);
+        assert_eq!(src.encoding, "UTF-16LE");
+        assert_eq!(src.content, "class A {}");
+    }
+
+    #[test]
+    fn test_decode_gbk_source() {
+        let text = "public class 订单服务 {\n    // 查询用户订单列表，注意分页处理\n    String sql = \"SELECT * FROM 订单表\";\n}\n";
+        let (gbk, _, _) = encoding_rs::GBK.encode(text);
+        assert!(std::str::from_utf8(&gbk).is_err());
+
+        let src = decode_bytes(&gbk);
+        assert!(src.is_transcoded());
+        assert!(src.encoding == "GBK" || src.encoding == "gb18030", "got {}", src.encoding);
+        assert_eq!(src.content, text);
+    }
+
+    #[test]
+    fn test_read_source_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Legacy.java");
+        let (gbk, _, _) = encoding_rs::GBK.encode("// 遗留代码注释，包含较多中文字符以便探测\nclass Legacy {}\n");
+        std::fs::write(&path, &gbk).unwrap();
+
+        let src = read_source(&path).unwrap();
+        assert!(src.is_transcoded());
+        assert!(src.content.contains("class Legacy"));
+        assert!(src.content.contains("遗留代码"));
+    }
+}
@@ -0,0 +1,300 @@
+//! 入口方法范围分析 (`scan --entry com.example.OrderController#create`)
+//!
+//! 排查单个慢接口时，全量报告里大部分问题与它无关。这里从指定方法出发沿 CallGraph
+//! 做 BFS (接收者展开到实现类的方式同 hotness)，只分析可达方法所在的 Java 文件，
+//! 并只保留位于可达方法内 (含其中的 lambda / 匿名类) 或可达类字段上的问题。
+//!
+//! 配置文件问题按可达代码实际用到的资源保留，Dockerfile 问题与入口无关，不报告：
+//!
+//! | 配置规则 | 保留条件 |
+//! |---------|---------|
+//! | TOMCAT_THREADS_LOW / DEBUG_LOG_IN_PROD | 总是 (每个请求都经过) |
+//! | DB_* / JPA_* | 可达 Repository / DAO 调用 |
+//! | REDIS_TIMEOUT_MISSING | 可达 Redis 客户端调用 |
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::ast_engine::{relative_path, AstIssue};
+use crate::hotness::{self, MethodSpan};
+use crate::shared_path::FilePath;
+use crate::rules::registry::CONFIG_FILE_RULES;
+use crate::symbol_table::SymbolTable;
+use crate::taint::{CallGraph, LayerType, MethodSig};
+
+/// BFS 最大调用深度
+pub const MAX_DEPTH: usize = 12;
+
+/// Redis 客户端类型/字段名片段 (小写匹配)
+const REDIS_CLIENTS: &[&str] = &["redis", "jedis", "lettuce", "redisson"];
+
+/// 入口方法 (`类#方法`，类可以是简单名或全限定名)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub class: String,
+    pub method: String,
+}
+
+impl EntryPoint {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec.split_once('#') {
+            Some((class, method)) if !class.trim().is_empty() && !method.trim().is_empty() => Ok(Self {
+                class: class.trim().to_string(),
+                method: method.trim().to_string(),
+            }),
+            _ => Err(format!("--entry 格式应为 类#方法 (如 com.example.OrderController#create)，实际为 {spec:?}")),
+        }
+    }
+
+    fn simple_class(&self) -> &str {
+        self.class.rsplit('.').next().unwrap_or(&self.class)
+    }
+}
+
+impl fmt::Display for EntryPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.class, self.method)
+    }
+}
+
+/// 入口可达范围
+#[derive(Debug, Default)]
+pub struct EntryScope {
+    /// (类简单名, 方法名) -> 距入口的调用深度
+    methods: HashMap<(String, String), usize>,
+    /// 可达方法所在的源文件
+    files: HashSet<FilePath>,
+    reaches_repository: bool,
+    reaches_redis: bool,
+}
+
+/// 报告中的入口范围摘要
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct EntrySummary {
+    pub entry: String,
+    /// 可达方法数 (含入口)
+    pub methods: usize,
+    /// 分析的 Java 文件数
+    pub files: usize,
+}
+
+impl EntryScope {
+    /// 从入口方法出发做 BFS；入口类或方法不在索引中时返回错误
+    pub fn build(graph: &CallGraph, table: &SymbolTable, entry: &EntryPoint) -> Result<Self, String> {
+        let start = Self::resolve(graph, table, entry)?;
+        let mut scope = Self::default();
+        let mut queue: VecDeque<(MethodSig, usize)> = VecDeque::new();
+        scope.visit(graph, &start, 0);
+        queue.push_back((start, 0));
+
+        while let Some((sig, depth)) = queue.pop_front() {
+            if depth >= MAX_DEPTH {
+                continue;
+            }
+            let Some(calls) = graph.outgoing.get(&sig) else {
+                continue;
+            };
+            for site in calls {
+                scope.files.insert(site.file.clone());
+                let caller_class = site.caller.simple_class_name();
+                let receiver = site.callee.simple_class_name();
+                if !receiver.is_empty() && receiver != "this" {
+                    let type_name = table.fields.get(&(caller_class.to_string(), receiver.to_string()))
+                        .map_or(receiver, |binding| binding.type_name.as_str())
+                        .to_lowercase();
+                    let receiver = receiver.to_lowercase();
+                    scope.reaches_redis |= REDIS_CLIENTS.iter().any(|c| type_name.contains(c) || receiver.contains(c));
+                    scope.reaches_repository |= table.is_dao_var(caller_class, site.callee.simple_class_name());
+                }
+                for target in hotness::callee_targets(graph, table, site) {
+                    if scope.visit(graph, &target, depth + 1) {
+                        queue.push_back((target, depth + 1));
+                    }
+                }
+            }
+        }
+        Ok(scope)
+    }
+
+    /// 入口方法的签名 (CallGraph 中的类名优先取 FQN)
+    fn resolve(graph: &CallGraph, table: &SymbolTable, entry: &EntryPoint) -> Result<MethodSig, String> {
+        let simple = entry.simple_class();
+        let class_fqn = if entry.class.contains('.') {
+            graph.class_index.contains_key(&entry.class).then(|| entry.class.clone())
+        } else {
+            match table.lookup_by_simple_name(simple).as_slice() {
+                [only] => Some(only.fqn.clone()),
+                [] => graph.class_index.contains_key(simple).then(|| simple.to_string()),
+                many => {
+                    let candidates: Vec<&str> = many.iter().map(|t| t.fqn.as_str()).collect();
+                    return Err(format!("入口类 {simple} 不唯一，请使用全限定名: {}", candidates.join(", ")));
+                }
+            }
+        };
+        let class_fqn = class_fqn.ok_or_else(|| format!("入口类 {} 不在项目索引中", entry.class))?;
+
+        let sig = MethodSig::new_fqn(&class_fqn, &entry.method);
+        if graph.outgoing.contains_key(&sig) || !table.lookup_methods(simple, &entry.method).is_empty() {
+            Ok(sig)
+        } else {
+            Err(format!("入口方法 {entry} 不存在"))
+        }
+    }
+
+    /// 记录可达方法，首次到达时返回 true
+    fn visit(&mut self, graph: &CallGraph, sig: &MethodSig, depth: usize) -> bool {
+        let key = (sig.simple_class_name().to_string(), sig.name.clone());
+        if self.methods.contains_key(&key) {
+            return false;
+        }
+        self.methods.insert(key, depth);
+        let file = graph.class_index.get(&sig.class_fqn).or_else(|| graph.class_index.get(sig.simple_class_name()));
+        self.files.extend(file.cloned());
+        let layer = graph.class_layers.get(&sig.class_fqn).or_else(|| graph.class_layers.get(sig.simple_class_name()));
+        self.reaches_repository |= layer == Some(&LayerType::Repository);
+        true
+    }
+
+    /// 需要分析的文件: 可达的 Java 文件与全部非 Java 文件 (配置文件问题在 [`retain`](Self::retain) 中筛选)
+    pub fn includes_file(&self, path: &Path) -> bool {
+        path.extension().and_then(|e| e.to_str()) != Some("java") || path.to_str().is_some_and(|file| self.files.contains(file))
+    }
+
+    /// 方法距入口的调用深度 (不可达返回 None)
+    pub fn depth(&self, class: &str, method: &str) -> Option<usize> {
+        self.methods.get(&(class.to_string(), method.to_string())).copied()
+    }
+
+    /// 配置规则是否与可达代码相关
+    fn config_relevant(&self, rule_id: &str) -> bool {
+        match rule_id {
+            "TOMCAT_THREADS_LOW" | "DEBUG_LOG_IN_PROD" => true,
+            "REDIS_TIMEOUT_MISSING" => self.reaches_redis,
+            id if id.starts_with("DB_") || id.starts_with("JPA_") => self.reaches_repository,
+            _ => false,
+        }
+    }
+
+    /// 只保留入口范围内的问题 (`spans` 为相对路径 → 方法行范围)
+    pub fn retain(&self, issues: &mut Vec<AstIssue>, spans: &HashMap<String, Vec<MethodSpan>>, root: &Path) {
+        let files: HashSet<String> = self.files.iter().map(|f| relative_path(root, f.as_std_path())).collect();
+        issues.retain(|issue| {
+            if CONFIG_FILE_RULES.contains(&issue.issue_type.as_str()) {
+                return self.config_relevant(&issue.issue_type);
+            }
+            if !files.contains(issue.path.as_str()) {
+                return false;
+            }
+            let enclosing: Vec<&MethodSpan> = spans.get(issue.path.as_str()).into_iter().flatten()
+                .filter(|s| s.start_line <= issue.line && issue.line <= s.end_line)
+                .collect();
+            // 不在方法内 (字段、初始化块) 的问题属于可达类；方法内的问题要求外层某个方法可达
+            enclosing.is_empty() || enclosing.iter().any(|s| self.depth(&s.class, &s.method).is_some())
+        });
+    }
+
+    pub fn summary(&self, entry: &EntryPoint) -> EntrySummary {
+        EntrySummary {
+            entry: entry.to_string(),
+            methods: self.methods.len(),
+            files: self.files.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol_table::{TypeInfo, VarBinding};
+
+    fn register(graph: &mut CallGraph, table: &mut SymbolTable, name: &str, annotation: &str, layer: LayerType) {
+        let mut info = TypeInfo::new_with_package(name, Some("com.shop"), format!("/p/{name}.java"), 1);
+        info.add_annotation(annotation);
+        table.register_class_fqn(info);
+        graph.register_class(&format!("com.shop.{name}"), format!("/p/{name}.java"), layer);
+        graph.register_class(name, format!("/p/{name}.java"), layer);
+    }
+
+    fn call(graph: &mut CallGraph, caller: &str, method: &str, callee: &str, callee_method: &str) {
+        let file = format!("/p/{caller}.java");
+        graph.add_call(MethodSig::new_fqn(&format!("com.shop.{caller}"), method), MethodSig::new(callee, callee_method), file, 1);
+    }
+
+    fn project() -> (CallGraph, SymbolTable) {
+        let (mut graph, mut table) = (CallGraph::new(), SymbolTable::new());
+        register(&mut graph, &mut table, "OrderController", "RestController", LayerType::Controller);
+        register(&mut graph, &mut table, "OrderService", "Service", LayerType::Service);
+        register(&mut graph, &mut table, "ReportService", "Service", LayerType::Service);
+        table.register_field("OrderController", VarBinding::new("orderService", "OrderService", true));
+        table.register_field("OrderService", VarBinding::new("orderRepository", "OrderRepository", true));
+        table.register_field("ReportService", VarBinding::new("redisTemplate", "StringRedisTemplate", true));
+        call(&mut graph, "OrderController", "create", "UNRESOLVED:orderService", "place");
+        call(&mut graph, "OrderService", "place", "UNRESOLVED:orderRepository", "save");
+        call(&mut graph, "OrderService", "place", "UNRESOLVED:this", "validate");
+        call(&mut graph, "ReportService", "daily", "UNRESOLVED:redisTemplate", "opsForValue");
+        (graph, table)
+    }
+
+    fn issue(id: &str, path: &str, line: usize) -> AstIssue {
+        serde_json::from_value(serde_json::json!({
+            "severity": "P0", "id": id, "file": "", "path": path, "line": line, "description": ""
+        })).unwrap()
+    }
+
+    fn span(class: &str, method: &str, start_line: usize, end_line: usize) -> MethodSpan {
+        MethodSpan { class: class.into(), method: method.into(), fqn: format!("com.shop.{class}.{method}"), start_line, end_line }
+    }
+
+    #[test]
+    fn test_entry_point_parse() {
+        let entry = EntryPoint::parse("com.shop.OrderController#create").unwrap();
+        assert_eq!((entry.class.as_str(), entry.method.as_str()), ("com.shop.OrderController", "create"));
+        assert_eq!(entry.to_string(), "com.shop.OrderController#create");
+        assert!(EntryPoint::parse("OrderController.create").is_err());
+        assert!(EntryPoint::parse("OrderController#").is_err());
+    }
+
+    #[test]
+    fn test_scope_follows_call_graph() {
+        let (graph, table) = project();
+        let scope = EntryScope::build(&graph, &table, &EntryPoint::parse("OrderController#create").unwrap()).unwrap();
+        assert_eq!(scope.depth("OrderController", "create"), Some(0));
+        assert_eq!(scope.depth("OrderService", "place"), Some(1));
+        assert_eq!(scope.depth("OrderService", "validate"), Some(2));
+        assert_eq!(scope.depth("ReportService", "daily"), None);
+        assert!(scope.includes_file(Path::new("/p/OrderService.java")));
+        assert!(!scope.includes_file(Path::new("/p/ReportService.java")));
+        assert!(scope.includes_file(Path::new("/p/application.yml")));
+        assert!(scope.reaches_repository && !scope.reaches_redis);
+        assert_eq!(scope.summary(&EntryPoint::parse("OrderController#create").unwrap()).methods, 3);
+
+        let missing = EntryScope::build(&graph, &table, &EntryPoint::parse("com.shop.OrderController#delete").unwrap());
+        assert_eq!(missing.unwrap_err(), "入口方法 com.shop.OrderController#delete 不存在");
+        assert!(EntryScope::build(&graph, &table, &EntryPoint::parse("Nope#run").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_retain_issues_in_scope() {
+        let (graph, table) = project();
+        let scope = EntryScope::build(&graph, &table, &EntryPoint::parse("OrderController#create").unwrap()).unwrap();
+        let spans = HashMap::from([
+            ("OrderService.java".to_string(), vec![span("OrderService", "place", 5, 15), span("OrderService", "export", 20, 30)]),
+            ("ReportService.java".to_string(), vec![span("ReportService", "daily", 5, 15)]),
+        ]);
+        let mut issues = vec![
+            issue("N_PLUS_ONE", "OrderService.java", 8),
+            issue("EMPTY_CATCH", "OrderService.java", 25),
+            issue("STATIC_COLLECTION", "OrderService.java", 2),
+            issue("N_PLUS_ONE", "ReportService.java", 8),
+            issue("DB_POOL_SMALL", "src/main/resources/application.yml", 3),
+            issue("REDIS_TIMEOUT_MISSING", "src/main/resources/application.yml", 9),
+            issue("DOCKER_LATEST_TAG", "Dockerfile", 1),
+        ];
+        scope.retain(&mut issues, &spans, Path::new("/p"));
+        let kept: Vec<(&str, usize)> = issues.iter().map(|i| (i.issue_type.as_str(), i.line)).collect();
+        assert_eq!(kept, vec![("N_PLUS_ONE", 8), ("STATIC_COLLECTION", 2), ("DB_POOL_SMALL", 3)]);
+    }
+}
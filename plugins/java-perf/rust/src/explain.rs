@@ -0,0 +1,376 @@
+//! 规则判定解释 (`scan --explain RULE_ID:file:line`)
+//!
+//! 回答 "这条规则为什么报了/为什么没报"：对单个位置按扫描流程重放一遍，
+//! 逐步记录每个判定:
+//!
+//! 1. 规则是否存在、是否被技术栈禁用或调整
+//! 2. `.javaperf.toml` rule_overrides 是否排除了该文件
+//! 3. 该行是否落在语法错误区域 (区域恢复会丢弃)
+//! 4. Query 在该行的匹配与捕获，捕获中接收者的符号解析结果
+//! 5. 处理器的判定 (报告 ID、严重级别、置信度、context)
+//! 6. 抑制注释/注解
+//!
+//! 输出为决策树，第一个失败的步骤即 "没报" 的原因。
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ast_engine::{build_index, relative_path, FileAnalyzers};
+use crate::encoding::read_source;
+use crate::project_config;
+use crate::project_detector::detect_stack;
+use crate::rules::registry::{category_of, reported_id, RuleRegistry};
+use crate::rules::suppression::SuppressionContext;
+use crate::scanner::parse_health::{ParseHealth, ParseStatus};
+use crate::scanner::rule_handlers::RuleContext;
+use crate::scanner::tree_sitter_java::RuleTrace;
+use crate::symbol_cache;
+use crate::symbol_table::SymbolTable;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 解释目标 `RULE_ID:file:line`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplainTarget {
+    pub rule: String,
+    pub file: String,
+    pub line: usize,
+}
+
+impl ExplainTarget {
+    /// 解析 `RULE_ID:file:line` (文件路径可含 `:`，如 Windows 盘符)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("--explain 格式应为 RULE_ID:file:line，实际为 {spec:?}");
+        let (rule, rest) = spec.split_once(':').ok_or_else(invalid)?;
+        let (file, line) = rest.rsplit_once(':').ok_or_else(invalid)?;
+        let line = line.trim().parse::<usize>().ok().filter(|l| *l > 0).ok_or_else(invalid)?;
+        if rule.trim().is_empty() || file.trim().is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self { rule: rule.trim().to_string(), file: file.trim().to_string(), line })
+    }
+}
+
+/// 决策树中的一步
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Step {
+    pub name: &'static str,
+    /// 是否通过 (None = 仅说明)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passed: Option<bool>,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Step>,
+}
+
+impl Step {
+    fn new(name: &'static str, passed: Option<bool>, detail: impl Into<String>) -> Self {
+        Self { name, passed, detail: detail.into(), children: Vec::new() }
+    }
+}
+
+/// 解释结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub rule: String,
+    pub file: String,
+    pub line: usize,
+    pub reported: bool,
+    pub steps: Vec<Step>,
+}
+
+impl Explanation {
+    fn finish(mut self) -> Self {
+        self.reported = self.steps.iter().all(|s| s.passed != Some(false));
+        self
+    }
+}
+
+/// 按扫描流程重放规则在指定位置的判定
+pub fn explain(code_path: &str, spec: &str) -> Result<Explanation, BoxError> {
+    let target = ExplainTarget::parse(spec)?;
+    let root = Path::new(code_path);
+    let file = resolve_file(root, &target.file)?;
+    let project_root = if root.is_dir() { root } else { file.parent().unwrap_or(Path::new(".")) };
+    let rel_path = relative_path(project_root, &file);
+
+    let mut explanation = Explanation {
+        rule: target.rule.clone(),
+        file: rel_path.clone(),
+        line: target.line,
+        reported: false,
+        steps: Vec::new(),
+    };
+
+    // 1. 规则
+    let config = project_config::load(project_root)?;
+    let mut registry = if root.is_dir() { RuleRegistry::with_stack(&detect_stack(root)) } else { RuleRegistry::default() };
+    registry.apply_overrides(&config.rule_overrides)?;
+    let variants: Vec<_> = registry.rules().iter()
+        .filter(|r| r.id == target.rule || reported_id(r.id) == target.rule)
+        .collect();
+    if variants.is_empty() {
+        let detail = if category_of(&target.rule).is_some() {
+            format!("{} 不经 Tree-sitter Query 检测 (配置文件/Dockerfile/跨文件规则)，--explain 只支持 Java Query 规则", target.rule)
+        } else {
+            format!("未知规则 {}", target.rule)
+        };
+        explanation.steps.push(Step::new("规则", Some(false), detail));
+        return Ok(explanation.finish());
+    }
+    let mut rule_step = Step::new("规则", Some(variants.iter().any(|r| r.enabled)), String::new());
+    let ids: Vec<&str> = variants.iter().map(|r| r.id).collect();
+    rule_step.detail = match (rule_step.passed, variants.iter().find(|r| r.enabled)) {
+        (Some(true), Some(def)) => format!("{} 已启用 ({:?})", ids.join(" / "), def.severity),
+        _ => format!("{} 被当前技术栈或 .javaperf.toml 禁用", ids.join(" / ")),
+    };
+    for adjustment in registry.adjustments().iter().filter(|a| ids.contains(&a.rule_id)) {
+        rule_step.children.push(Step::new(
+            "技术栈调整", None, format!("{} {} - {}", adjustment.rule_id, adjustment.change, adjustment.reason),
+        ));
+    }
+    for gate in variants.iter().flat_map(|r| r.versions) {
+        rule_step.children.push(Step::new("版本门控", None, gate.describe()));
+    }
+    let enabled = rule_step.passed == Some(true);
+    explanation.steps.push(rule_step);
+    if !enabled {
+        return Ok(explanation.finish());
+    }
+
+    // 2. 配置排除
+    let excluded: Vec<String> = config.exclusions()?.excluded_for(&rel_path).into_iter()
+        .filter(|id| ids.contains(id) || *id == target.rule)
+        .map(str::to_string)
+        .collect();
+    explanation.steps.push(if excluded.is_empty() {
+        Step::new("配置", Some(true), "rule_overrides 未排除该文件")
+    } else {
+        Step::new("配置", Some(false), format!(".javaperf.toml rule_overrides 对该文件排除了 {}", excluded.join(", ")))
+    });
+    if !excluded.is_empty() {
+        return Ok(explanation.finish());
+    }
+
+    // 3-5. 解析、匹配、处理器 (带项目索引，与 scan 一致)
+    let analyzers = FileAnalyzers::new(&registry)?;
+    let files = if root.is_dir() { symbol_cache::java_files(root) } else { vec![file.clone()] };
+    let paths: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+    let (table, graph, _) = build_index(&paths, analyzers.java());
+    let content = read_source(&file)?.content;
+    let current_class = file.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let rule_ctx = RuleContext {
+        code: &content,
+        file_path: &file,
+        current_class: &current_class,
+        symbol_table: Some(&table),
+        call_graph: Some(&graph),
+    };
+    let (health, traces) = analyzers.java().parse_with(&content, |tree| {
+        let health = ParseHealth::of(tree.root_node(), &content);
+        let traces = analyzers.java().trace_line(tree, &rule_ctx, &target.rule, target.line);
+        (health, traces)
+    })?;
+
+    explanation.steps.push(match health.status() {
+        ParseStatus::Clean => Step::new("解析", Some(true), "文件无语法错误"),
+        ParseStatus::Failed => Step::new("解析", Some(false), format!(
+            "错误覆盖 {:.0}% 的源码，整个文件视为解析失败，不报告问题", health.error_ratio() * 100.0
+        )),
+        ParseStatus::Degraded if health.covers_line(target.line) => Step::new("解析", Some(false),
+            "该行位于语法错误区域内，区域恢复丢弃此处的问题"),
+        ParseStatus::Degraded => Step::new("解析", Some(true), format!(
+            "文件含 {} 处语法错误 (首处第 {} 行)，该行不受影响", health.error_nodes, health.first_error_line().unwrap_or(0)
+        )),
+    });
+    if !health.keeps_line(target.line) {
+        return Ok(explanation.finish());
+    }
+
+    let class = file.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let (match_step, reported_line) = match_step(&traces, &table, &class, &target);
+    explanation.steps.push(match_step);
+    let Some(reported_line) = reported_line else {
+        return Ok(explanation.finish());
+    };
+
+    // 6. 抑制
+    let suppression = SuppressionContext::parse(&content);
+    explanation.steps.push(match suppression.suppression_for(&target.rule, reported_line) {
+        Some(source) => Step::new("抑制", Some(false), format!(
+            "被第 {} 行的 {} 抑制", source.line, serde_json::to_value(source.kind).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+        )),
+        None => Step::new("抑制", Some(true), "无抑制注释/注解"),
+    });
+    Ok(explanation.finish())
+}
+
+fn resolve_file(root: &Path, file: &str) -> Result<PathBuf, BoxError> {
+    let candidates = [PathBuf::from(file), root.join(file)];
+    candidates.into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| format!("找不到文件 {file} (相对 {} 或当前目录)", root.display()).into())
+}
+
+/// Query 匹配与处理器判定；返回步骤与报告所在行 (未报告为 None)
+fn match_step(traces: &[RuleTrace], table: &SymbolTable, class: &str, target: &ExplainTarget) -> (Step, Option<usize>) {
+    let matched: usize = traces.iter().map(|t| t.matches.len()).sum();
+    if matched == 0 {
+        let mut step = Step::new("Query 匹配", Some(false), format!("第 {} 行没有匹配", target.line));
+        for trace in traces.iter().filter(|t| !t.other_lines.is_empty()) {
+            let lines: Vec<String> = trace.other_lines.iter().take(10).map(usize::to_string).collect();
+            step.children.push(Step::new("其它匹配", None, format!("{} 在第 {} 行匹配", trace.rule, lines.join(", "))));
+        }
+        return (step, None);
+    }
+
+    let mut step = Step::new("Query 匹配", Some(true), format!("第 {} 行匹配 {matched} 次", target.line));
+    let mut reported_line = None;
+    for trace in traces {
+        for m in &trace.matches {
+            let captures: Vec<String> = m.captures.iter().map(|(name, text, line)| {
+                let text = text.lines().next().unwrap_or("");
+                format!("@{name} = `{text}` (L{line})")
+            }).collect();
+            let mut node = Step::new("匹配", None, format!("{}: {}", trace.rule, captures.join(", ")));
+            for receiver in &m.receivers {
+                let resolved = table.lookup_var_type(class, receiver).or_else(|| table.lookup_expr_type(class, receiver));
+                node.children.push(Step::new("符号", None, match resolved {
+                    Some(info) => format!(
+                        "{receiver} → {}{}", info.fqn, if info.is_dao() { " (DAO)" } else { "" }
+                    ),
+                    None => format!("{receiver} → 未解析 (按名称启发式判断)"),
+                }));
+            }
+            node.children.push(match &m.issue {
+                Some(issue) if issue.id == target.rule => {
+                    reported_line = reported_line.or(Some(issue.line));
+                    let forced = trace.severity_override.map(|_| " (技术栈强制)").unwrap_or("");
+                    let confidence = issue.confidence.map(|c| format!("，置信度 {c:?}")).unwrap_or_default();
+                    let context = issue.context.as_deref().map(|c| format!(" - {c}")).unwrap_or_default();
+                    Step::new("处理器", Some(true), format!("报告 {} {:?}{forced}{confidence}{context}", issue.id, issue.severity))
+                }
+                Some(issue) => Step::new("处理器", Some(false), format!("以 {} 报告，不是 {}", issue.id, target.rule)),
+                None => Step::new("处理器", Some(false), "Query 匹配但处理器判定不报告 (语义条件不满足)"),
+            });
+            step.children.push(node);
+        }
+    }
+    if reported_line.is_none() {
+        step.passed = Some(false);
+        step.detail.push_str("，处理器均未报告");
+    }
+    (step, reported_line)
+}
+
+/// 渲染决策树
+pub fn render(explanation: &Explanation, json_output: bool) -> Value {
+    if json_output {
+        return json!(explanation);
+    }
+    let mut out = format!(
+        "## 🔍 规则判定: {} @ {}:{}\n\n",
+        explanation.rule, explanation.file, explanation.line
+    );
+    let count = explanation.steps.len();
+    for (i, step) in explanation.steps.iter().enumerate() {
+        render_step(&mut out, step, "", i + 1 == count);
+    }
+    let verdict = if explanation.reported {
+        "✅ 报告".to_string()
+    } else {
+        let reason = explanation.steps.iter().find(|s| s.passed == Some(false)).map(|s| s.name).unwrap_or("未完成");
+        format!("❌ 不报告 (原因: {reason})")
+    };
+    out.push_str(&format!("\n**结论**: {verdict}\n"));
+    json!(out)
+}
+
+fn render_step(out: &mut String, step: &Step, prefix: &str, last: bool) {
+    let icon = match step.passed {
+        Some(true) => "✅ ",
+        Some(false) => "❌ ",
+        None => "",
+    };
+    out.push_str(&format!("{prefix}{} {icon}{}: {}\n", if last { "└─" } else { "├─" }, step.name, step.detail));
+    let child_prefix = format!("{prefix}{}", if last { "   " } else { "│  " });
+    let count = step.children.len();
+    for (i, child) in step.children.iter().enumerate() {
+        render_step(out, child, &child_prefix, i + 1 == count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("OrderRepository.java"),
+            "package com.shop;\n@Repository\npublic class OrderRepository {}\n").unwrap();
+        std::fs::write(dir.path().join("OrderService.java"), r#"package com.shop;
+@Service
+public class OrderService {
+    @Autowired
+    private OrderRepository orderRepository;
+
+    public void load(List<Long> ids) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+        for (Long id : ids) {
+            // java-perf-ignore-next-line: N_PLUS_ONE
+            orderRepository.save(id);
+        }
+    }
+}
+"#).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            ExplainTarget::parse("N_PLUS_ONE:src/A.java:12").unwrap(),
+            ExplainTarget { rule: "N_PLUS_ONE".into(), file: "src/A.java".into(), line: 12 }
+        );
+        assert_eq!(ExplainTarget::parse("X:C:\\src\\A.java:3").unwrap().file, "C:\\src\\A.java");
+        assert!(ExplainTarget::parse("N_PLUS_ONE:A.java").is_err());
+        assert!(ExplainTarget::parse("N_PLUS_ONE:A.java:0").is_err());
+    }
+
+    #[test]
+    fn test_explain_reported_with_symbol_resolution() {
+        let dir = project();
+        let root = dir.path().to_str().unwrap();
+        let explanation = explain(root, "N_PLUS_ONE:OrderService.java:9").unwrap();
+        assert!(explanation.reported, "{explanation:#?}");
+        let text = render(&explanation, false);
+        let text = text.as_str().unwrap();
+        assert!(text.contains("匹配: N_PLUS_ONE_FOREACH: @call = `orderRepository.findById(id)`"), "{text}");
+        assert!(text.contains("orderRepository → com.shop.OrderRepository (DAO)"), "{text}");
+        assert!(text.contains("✅ 报告"), "{text}");
+    }
+
+    #[test]
+    fn test_explain_not_reported_reasons() {
+        let dir = project();
+        let root = dir.path().to_str().unwrap();
+
+        let suppressed = explain(root, "N_PLUS_ONE:OrderService.java:13").unwrap();
+        assert!(!suppressed.reported);
+        assert_eq!(suppressed.steps.last().map(|s| (s.name, s.passed)), Some(("抑制", Some(false))));
+
+        let no_match = explain(root, "N_PLUS_ONE:OrderService.java:7").unwrap();
+        let step = no_match.steps.iter().find(|s| s.name == "Query 匹配").unwrap();
+        assert_eq!(step.passed, Some(false));
+        assert!(step.children[0].detail.contains("第 9, 13 行"), "{step:?}");
+        assert!(render(&no_match, false).as_str().unwrap().contains("原因: Query 匹配"));
+
+        let config_rule = explain(root, "DB_POOL_SMALL:OrderService.java:1").unwrap();
+        assert!(config_rule.steps[0].detail.contains("不经 Tree-sitter Query"));
+        assert!(explain(root, "N_PLUS_ONE:Missing.java:1").is_err());
+    }
+}
@@ -116,7 +116,7 @@ pub fn analyze_log(log_path: &str) -> Result<Value, Box<dyn std::error::Error>>
     
     // 排序
     let mut fingerprints: Vec<_> = exception_map.values().collect();
-    fingerprints.sort_by(|a, b| b.count.cmp(&a.count));
+    fingerprints.sort_by_key(|b| std::cmp::Reverse(b.count));
     
     // 生成报告
     let file_name = path.file_name()
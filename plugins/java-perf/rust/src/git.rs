@@ -0,0 +1,43 @@
+//! 调用系统 git (`--changed`/pre-commit、`--blame-age`、`scan --git` 共用)
+//!
+//! 统一设置 `GIT_TERMINAL_PROMPT=0`: 需要凭据时直接失败，不在 CI 中挂起等待输入。
+//! 失败时错误信息包含完整命令与 git 的 stderr。
+
+use std::path::Path;
+use std::process::Command;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 在 `dir` 下执行 `git <args>` 的命令 (需要自行管理子进程时使用)
+pub fn command(dir: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(dir).env("GIT_TERMINAL_PROMPT", "0");
+    command
+}
+
+/// 在 `dir` 下执行 `git <args>`，成功时返回 stdout
+pub fn run(dir: &Path, args: &[&str]) -> Result<Vec<u8>, BoxError> {
+    let output = command(dir, args)
+        .output()
+        .map_err(|e| format!("无法执行 git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("git {} 失败: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_reports_stderr() {
+        let dir = tempfile::tempdir().unwrap();
+        run(dir.path(), &["init", "-q"]).unwrap();
+        let top = run(dir.path(), &["rev-parse", "--is-inside-work-tree"]).unwrap();
+        assert_eq!(String::from_utf8_lossy(&top).trim(), "true");
+
+        let err = run(dir.path(), &["rev-parse", "--verify", "-q", "no-such-ref"]).unwrap_err().to_string();
+        assert!(err.starts_with("git rev-parse --verify -q no-such-ref 失败"), "{err}");
+    }
+}
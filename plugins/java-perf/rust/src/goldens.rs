@@ -0,0 +1,272 @@
+//! 规则 golden 测试 (`rules verify-goldens`)
+//!
+//! 升级 tree-sitter / tree-sitter-java 时，节点类型、字段名或 Query 谓词的语义变化会悄然改变
+//! 规则的匹配结果，而单元测试通常只断言"有/无问题"。golden 测试为每条规则保存 fixture 与
+//! 期望的匹配范围，逐个位置对比:
+//!
+//! ```text
+//! fixtures/goldens/<RULE_ID>/<case>.java     规则 fixture (正例、反例均可)
+//! fixtures/goldens/<RULE_ID>/<case>.golden   期望结果 (JSON)
+//! ```
+//!
+//! 期望结果包含两层，分别定位是 Query 还是处理器的行为变化:
+//! - `matches`: Query 原始匹配，每个匹配取起点最前的捕获范围 (`行:列-行:列`)
+//! - `reported`: 处理器判定后报告的问题位置 (`行:列`)
+//!
+//! 行列均从 1 开始，列为字符列。规则在 golden 中全部启用 (含依赖门控与可选规则)，不使用符号表。
+//! `cargo test` (tests/integration_tests.rs) 与 `rules verify-goldens` 使用同一套比对，
+//! 且 `cargo test` 要求每条内置 Query 规则都有 fixture (新增规则时一并添加)；
+//! 确认变化符合预期后用 `--bless` 重写期望文件，新增 fixture 时也用它生成首个期望文件。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::rules::registry::RuleRegistry;
+use crate::scanner::byte_to_char_column;
+use crate::scanner::grammar;
+use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 默认 golden 目录 (相对 crate 根目录)
+pub const DEFAULT_DIR: &str = "fixtures/goldens";
+
+/// 期望文件扩展名
+const GOLDEN_EXT: &str = "golden";
+
+/// 单个 fixture 的匹配结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Golden {
+    /// Query 原始匹配范围
+    pub matches: Vec<String>,
+    /// 处理器报告的问题位置
+    pub reported: Vec<String>,
+}
+
+/// 与期望不符 (或缺少期望文件) 的 fixture
+#[derive(Debug, Clone, Serialize)]
+pub struct GoldenMismatch {
+    pub rule: String,
+    /// 相对 golden 目录的路径
+    pub case: String,
+    /// None = 缺少期望文件
+    pub expected: Option<Golden>,
+    pub actual: Golden,
+}
+
+/// `rules verify-goldens` 的结果
+#[derive(Debug, Serialize)]
+pub struct GoldenOutcome {
+    pub dir: String,
+    /// 链接的 tree-sitter-java 版本与选用的查询集
+    pub grammar: String,
+    pub cases: usize,
+    /// 有 fixture 的规则
+    pub rules: Vec<String>,
+    /// 没有 fixture 的内置规则
+    pub uncovered: Vec<&'static str>,
+    pub mismatches: Vec<GoldenMismatch>,
+    /// `--bless` 重写的期望文件
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blessed: Vec<String>,
+}
+
+impl GoldenOutcome {
+    /// 存在未 bless 的不符 (CLI 以非零码退出)
+    pub fn failed(&self) -> bool {
+        !self.mismatches.is_empty() && self.blessed.is_empty()
+    }
+}
+
+/// 比对 `dir` 下的 golden (`rules` 为空时全部规则)；`bless` 时把实际结果写为期望
+pub fn verify(dir: &Path, rules: &[String], bless: bool) -> Result<GoldenOutcome, BoxError> {
+    if !dir.is_dir() {
+        return Err(format!("golden 目录不存在: {}", dir.display()).into());
+    }
+    let registry = RuleRegistry::all_enabled();
+    let analyzer = JavaTreeSitterAnalyzer::with_registry(&registry)?;
+
+    let mut rule_dirs: Vec<(String, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| (entry.file_name().to_string_lossy().into_owned(), entry.path()))
+        .collect();
+    rule_dirs.sort();
+    if let Some(unknown) = rule_dirs.iter().find(|(rule, _)| registry.get(rule).is_none()) {
+        return Err(format!("golden 目录 {} 不是内置 Query 规则 ID", unknown.1.display()).into());
+    }
+    if let Some(missing) = rules.iter().find(|rule| !rule_dirs.iter().any(|(id, _)| id == *rule)) {
+        return Err(format!("规则 {missing} 没有 golden fixture").into());
+    }
+
+    let mut outcome = GoldenOutcome {
+        dir: dir.display().to_string(),
+        grammar: grammar_label(),
+        cases: 0,
+        rules: Vec::new(),
+        uncovered: registry.rules().iter()
+            .map(|rule| rule.id)
+            .filter(|id| !rule_dirs.iter().any(|(rule, _)| rule == id))
+            .collect(),
+        mismatches: Vec::new(),
+        blessed: Vec::new(),
+    };
+    for (rule, rule_dir) in rule_dirs.into_iter().filter(|(rule, _)| rules.is_empty() || rules.contains(rule)) {
+        let mut cases: Vec<PathBuf> = std::fs::read_dir(&rule_dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "java"))
+            .collect();
+        cases.sort();
+        for case in cases {
+            let code = std::fs::read_to_string(&case)?;
+            let actual = run_case(&analyzer, &code, &case, &rule)?;
+            let golden_path = case.with_extension(GOLDEN_EXT);
+            let expected: Option<Golden> = match std::fs::read_to_string(&golden_path) {
+                Ok(content) => Some(serde_json::from_str(&content)
+                    .map_err(|e| format!("{} 格式错误: {e}", golden_path.display()))?),
+                Err(_) => None,
+            };
+            outcome.cases += 1;
+            if expected.as_ref() == Some(&actual) {
+                continue;
+            }
+            let case_name = format!("{rule}/{}", case.file_name().unwrap_or_default().to_string_lossy());
+            if bless {
+                std::fs::write(&golden_path, serde_json::to_string_pretty(&actual)? + "\n")?;
+                outcome.blessed.push(case_name.clone());
+            }
+            outcome.mismatches.push(GoldenMismatch { rule: rule.clone(), case: case_name, expected, actual });
+        }
+        outcome.rules.push(rule);
+    }
+    Ok(outcome)
+}
+
+/// 如 `tree-sitter-java 0.21` 或 `tree-sitter-java 0.23 (查询集 0.21)`
+fn grammar_label() -> String {
+    let query_set = grammar::active().grammar;
+    if query_set == grammar::LINKED {
+        format!("tree-sitter-java {query_set}")
+    } else {
+        format!("tree-sitter-java {} (查询集 {query_set})", grammar::LINKED)
+    }
+}
+
+/// 运行单条规则，收集匹配范围与报告位置
+fn run_case(analyzer: &JavaTreeSitterAnalyzer, code: &str, path: &Path, rule: &str) -> Result<Golden, BoxError> {
+    let (ranges, issues) = analyzer.rule_matches(code, path, rule)?
+        .ok_or_else(|| format!("规则 {rule} 的 Query 未能编译"))?;
+    let point = |byte: usize, row: usize, column: usize| {
+        format!("{}:{}", row + 1, byte_to_char_column(code, byte, column) + 1)
+    };
+    let mut matches: Vec<String> = ranges.iter()
+        .map(|r| format!(
+            "{}-{}",
+            point(r.start_byte, r.start_point.row, r.start_point.column),
+            point(r.end_byte, r.end_point.row, r.end_point.column)
+        ))
+        .collect();
+    matches.dedup();
+    let reported = issues.iter().map(|issue| format!("{}:{}", issue.line, issue.column + 1)).collect();
+    Ok(Golden { matches, reported })
+}
+
+pub fn render(outcome: &GoldenOutcome, json_output: bool) -> Value {
+    if json_output {
+        return json!(outcome);
+    }
+    let covered = outcome.rules.len();
+    let mut out = format!(
+        "## 🧷 规则 golden 测试\n\n**目录**: {} | **语法**: {} | **规则**: {covered} (未覆盖 {}) | **fixture**: {}\n",
+        outcome.dir, outcome.grammar, outcome.uncovered.len(), outcome.cases
+    );
+    if !outcome.uncovered.is_empty() {
+        let uncovered: Vec<String> = outcome.uncovered.iter().map(|id| format!("`{id}`")).collect();
+        out.push_str(&format!("\n**未覆盖的规则**: {}\n", uncovered.join(", ")));
+    }
+    if outcome.mismatches.is_empty() {
+        out.push_str("\n✅ 全部与期望一致\n");
+        return json!(out);
+    }
+    let title = if outcome.blessed.is_empty() { "❌ 与期望不符" } else { "📝 已重写期望文件" };
+    out.push_str(&format!("\n### {title} ({})\n\n", outcome.mismatches.len()));
+    for m in &outcome.mismatches {
+        match &m.expected {
+            None => out.push_str(&format!("- `{}` - 缺少期望文件\n", m.case)),
+            Some(expected) => {
+                out.push_str(&format!("- `{}`\n", m.case));
+                if expected.matches != m.actual.matches {
+                    out.push_str(&format!(
+                        "  - Query 匹配: 期望 [{}] 实际 [{}]\n", expected.matches.join(", "), m.actual.matches.join(", ")
+                    ));
+                }
+                if expected.reported != m.actual.reported {
+                    out.push_str(&format!(
+                        "  - 报告位置: 期望 [{}] 实际 [{}]\n", expected.reported.join(", "), m.actual.reported.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    if outcome.blessed.is_empty() {
+        out.push_str("\n确认变化符合预期后用 `--bless` 重写期望文件\n");
+    }
+    json!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY_CATCH: &str = "public class Job {\n    void run() {\n        try { work(); } catch (Exception e) {}\n    }\n}\n";
+
+    #[test]
+    fn test_bless_then_detect_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        let rule_dir = dir.path().join("SYNC_METHOD");
+        std::fs::create_dir_all(&rule_dir).unwrap();
+        std::fs::write(rule_dir.join("positive.java"), "public class Counter {\n    public synchronized void inc() {}\n}\n").unwrap();
+
+        // 缺少期望文件: 失败，--bless 生成
+        let outcome = verify(dir.path(), &[], false).unwrap();
+        assert!(outcome.failed() && outcome.mismatches[0].expected.is_none());
+        let outcome = verify(dir.path(), &[], true).unwrap();
+        assert!(!outcome.failed());
+        assert_eq!(outcome.blessed, ["SYNC_METHOD/positive.java"]);
+        let golden: Golden = serde_json::from_str(&std::fs::read_to_string(rule_dir.join("positive.golden")).unwrap()).unwrap();
+        assert_eq!(golden.reported, ["2:5"]);
+        assert!(golden.matches[0].starts_with("2:5-"), "{golden:?}");
+
+        let outcome = verify(dir.path(), &[], false).unwrap();
+        assert_eq!((outcome.cases, outcome.mismatches.len()), (1, 0));
+        assert!(outcome.uncovered.contains(&"N_PLUS_ONE") && !outcome.uncovered.contains(&"SYNC_METHOD"));
+        assert!(render(&outcome, false).as_str().unwrap().contains("`N_PLUS_ONE`"));
+
+        // 期望被篡改 (模拟语法升级导致匹配变化)
+        std::fs::write(rule_dir.join("positive.golden"), r#"{"matches": ["2:5-2:40"], "reported": []}"#).unwrap();
+        let outcome = verify(dir.path(), &[], false).unwrap();
+        assert!(outcome.failed());
+        let text = render(&outcome, false);
+        let text = text.as_str().unwrap();
+        assert!(text.contains("Query 匹配: 期望 [2:5-2:40]") && text.contains("报告位置: 期望 [] 实际 [2:5]"), "{text}");
+    }
+
+    #[test]
+    fn test_rule_filter_and_unknown_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        for rule in ["EMPTY_CATCH", "SYNC_METHOD"] {
+            std::fs::create_dir_all(dir.path().join(rule)).unwrap();
+            std::fs::write(dir.path().join(rule).join("case.java"), EMPTY_CATCH).unwrap();
+        }
+        let outcome = verify(dir.path(), &["EMPTY_CATCH".into()], true).unwrap();
+        assert_eq!((outcome.rules.clone(), outcome.blessed.clone()), (vec!["EMPTY_CATCH".to_string()], vec!["EMPTY_CATCH/case.java".to_string()]));
+        assert!(!dir.path().join("SYNC_METHOD/case.golden").exists());
+        assert!(verify(dir.path(), &["N_PLUS_ONE".into()], false).unwrap_err().to_string().contains("没有 golden fixture"));
+
+        std::fs::create_dir_all(dir.path().join("NOT_A_RULE")).unwrap();
+        assert!(verify(dir.path(), &[], false).unwrap_err().to_string().contains("不是内置 Query 规则"));
+        assert!(verify(&dir.path().join("missing"), &[], false).is_err());
+    }
+}
@@ -0,0 +1,306 @@
+//! 热路径权重 (Controller 可达性)
+//!
+//! 从 `@Controller`/`@RestController` 的方法出发沿 CallGraph 做 BFS，
+//! 问题所在方法离请求入口越近越"热"：
+//!
+//! | 调用距离 | hotness |
+//! |---------|---------|
+//! | 0 (Controller 方法本身) | 100 |
+//! | 1 | 50 |
+//! | 2 | 33 |
+//! | d | 100 / (d + 1) |
+//! | 不可达 (定时任务、批处理、工具类) | 0 |
+//!
+//! CallGraph 的被调方多为 `UNRESOLVED:字段名`，这里借助 SymbolTable 的字段绑定
+//! 把接收者解析为类型，再展开到接口的实现类 (Bean 候选)。
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::ast_engine::AstIssue;
+use crate::scanner::node_walk::NodeWalk;
+use crate::symbol_table::SymbolTable;
+use crate::taint::{CallGraph, CallSite, LayerType, MethodSig};
+
+/// BFS 最大调用深度 (更深的调用视为不可达)
+pub const MAX_DEPTH: usize = 8;
+
+/// 方法声明的行范围
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodSpan {
+    /// 所在类型的简单名
+    pub class: String,
+    pub method: String,
+    /// 方法全限定名: 包名 + 外层类型链 + 方法名 (`com.shop.Outer.Inner.run`)
+    pub fqn: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// 收集文件中所有方法/构造器的行范围
+pub fn collect_spans(root: Node, code: &str) -> Vec<MethodSpan> {
+    let walk = NodeWalk::new(code);
+    let package = walk.package_name(root);
+    let mut spans = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "method_declaration" | "constructor_declaration") {
+            let method = node.child_by_field_name("name").map_or("", |n| walk.text(n));
+            if let (Some(class), Some(fqn)) = (walk.enclosing_type_name(node), walk.method_fqn(node, package)) {
+                spans.push(MethodSpan {
+                    class: class.to_string(),
+                    method: method.to_string(),
+                    fqn,
+                    start_line: node.start_position().row + 1,
+                    end_line: node.end_position().row + 1,
+                });
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    spans
+}
+
+/// 包含该行的最内层方法
+pub fn innermost(spans: &[MethodSpan], line: usize) -> Option<&MethodSpan> {
+    spans.iter()
+        .filter(|s| s.start_line <= line && line <= s.end_line)
+        .min_by_key(|s| s.end_line - s.start_line)
+}
+
+/// 各方法到最近 Controller 入口的调用距离
+#[derive(Debug, Default)]
+pub struct HotnessIndex {
+    /// (类简单名, 方法名) -> 距离
+    distances: HashMap<(String, String), usize>,
+    /// Controller 类 (简单名)
+    controllers: Vec<String>,
+}
+
+impl HotnessIndex {
+    /// 从所有 Controller 方法出发做多源 BFS
+    pub fn build(graph: &CallGraph, table: &SymbolTable, max_depth: usize) -> Self {
+        let mut controllers: Vec<String> = graph.class_layers.iter()
+            .filter(|(_, layer)| **layer == LayerType::Controller)
+            .map(|(name, _)| name.rsplit('.').next().unwrap_or(name).to_string())
+            .collect();
+        controllers.sort();
+        controllers.dedup();
+
+        let mut distances: HashMap<(String, String), usize> = HashMap::new();
+        let mut queue: VecDeque<(MethodSig, usize)> = VecDeque::new();
+        for sig in graph.outgoing.keys() {
+            if layer_of(graph, sig) == Some(LayerType::Controller) {
+                distances.insert(key(sig), 0);
+                queue.push_back((sig.clone(), 0));
+            }
+        }
+
+        while let Some((sig, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+            let Some(calls) = graph.outgoing.get(&sig) else {
+                continue;
+            };
+            for site in calls {
+                for target in callee_targets(graph, table, site) {
+                    let k = key(&target);
+                    if distances.contains_key(&k) {
+                        continue;
+                    }
+                    distances.insert(k, depth + 1);
+                    queue.push_back((target, depth + 1));
+                }
+            }
+        }
+
+        Self { distances, controllers }
+    }
+
+    /// 方法到 Controller 入口的距离 (不可达返回 None)
+    pub fn distance(&self, class: &str, method: &str) -> Option<usize> {
+        if self.controllers.iter().any(|c| c == class) {
+            return Some(0);
+        }
+        self.distances.get(&(class.to_string(), method.to_string())).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.controllers.is_empty()
+    }
+}
+
+fn key(sig: &MethodSig) -> (String, String) {
+    (sig.simple_class_name().to_string(), sig.name.clone())
+}
+
+fn layer_of(graph: &CallGraph, sig: &MethodSig) -> Option<LayerType> {
+    graph.class_layers.get(&sig.class_fqn)
+        .or_else(|| graph.class_layers.get(sig.simple_class_name()))
+        .copied()
+}
+
+/// 被调方可能落到的类方法 (字段类型 → 实现类)
+pub(crate) fn callee_targets(graph: &CallGraph, table: &SymbolTable, site: &CallSite) -> Vec<MethodSig> {
+    let callee = &site.callee;
+    if !callee.is_unresolved() && graph.class_layers.contains_key(&callee.class_fqn) {
+        return vec![callee.clone()];
+    }
+
+    let receiver = callee.simple_class_name();
+    let caller_class = site.caller.simple_class_name();
+    if receiver.is_empty() || receiver == "this" {
+        return vec![MethodSig::new_fqn(&site.caller.class_fqn, &callee.name)];
+    }
+
+    // 字段名 → 声明类型；否则按静态调用 (`OrderHelper.fill()`) 处理
+    let type_name = table.fields.get(&(caller_class.to_string(), receiver.to_string()))
+        .map(|binding| binding.type_name.split('<').next().unwrap_or(&binding.type_name).trim().to_string())
+        .unwrap_or_else(|| receiver.to_string());
+
+    let mut classes: Vec<&str> = table.bean_candidates(&type_name).into_iter()
+        .map(|info| info.fqn.as_str())
+        .collect();
+    if classes.is_empty() {
+        classes = table.lookup_by_simple_name(&type_name).into_iter()
+            .map(|info| info.fqn.as_str())
+            .collect();
+    }
+    classes.into_iter().map(|fqn| MethodSig::new_fqn(fqn, &callee.name)).collect()
+}
+
+/// 调用距离 → 热度 (0-100)
+pub fn score(distance: Option<usize>) -> u8 {
+    match distance {
+        Some(d) => (100 / (d + 1)) as u8,
+        None => 0,
+    }
+}
+
+/// 为问题填充 hotness：取问题行所在的最内层方法
+pub fn annotate(issues: &mut [AstIssue], spans: &HashMap<String, Vec<MethodSpan>>, index: &HotnessIndex) {
+    if index.is_empty() {
+        return;
+    }
+    for issue in issues.iter_mut() {
+        let Some(file_spans) = spans.get(issue.path.as_str()) else {
+            continue;
+        };
+        if let Some(span) = innermost(file_spans, issue.line) {
+            issue.hotness = score(index.distance(&span.class, &span.method));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+    use crate::symbol_table::{TypeInfo, VarBinding};
+
+    fn spans_of(code: &str) -> Vec<MethodSpan> {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let mut spans = analyzer.parse_with(code, |tree| collect_spans(tree.root_node(), code)).unwrap();
+        spans.sort_by_key(|s| s.start_line);
+        spans
+    }
+
+    fn bean(table: &mut SymbolTable, name: &str, annotation: &str, supertypes: &[&str]) {
+        let mut info = TypeInfo::new_with_package(name, Some("com.shop"), format!("{name}.java"), 1);
+        info.add_annotation(annotation);
+        info.supertypes = supertypes.iter().map(|s| s.to_string()).collect();
+        table.register_class_fqn(info);
+    }
+
+    /// OrderController.list → orderService.load (接口) → OrderServiceImpl.load → orderRepository.findAll
+    /// ReportJob.run → reportService.build (不可达)
+    fn sample() -> HotnessIndex {
+        let mut table = SymbolTable::new();
+        bean(&mut table, "OrderController", "RestController", &[]);
+        bean(&mut table, "OrderServiceImpl", "Service", &["OrderService"]);
+        bean(&mut table, "OrderRepository", "Repository", &[]);
+        bean(&mut table, "ReportJob", "Component", &[]);
+        table.register_field("OrderController", VarBinding::new("orderService", "OrderService", true));
+        table.register_field("OrderServiceImpl", VarBinding::new("orderRepository", "OrderRepository", true));
+
+        let mut graph = CallGraph::new();
+        for (class, layer) in [
+            ("com.shop.OrderController", LayerType::Controller),
+            ("com.shop.OrderServiceImpl", LayerType::Service),
+            ("com.shop.OrderRepository", LayerType::Repository),
+            ("com.shop.ReportJob", LayerType::Unknown),
+        ] {
+            graph.register_class(class, "", layer);
+        }
+        let call = |graph: &mut CallGraph, caller: (&str, &str), receiver: &str, method: &str| {
+            graph.add_call(
+                MethodSig::new_fqn(caller.0, caller.1),
+                MethodSig { class_fqn: format!("UNRESOLVED:{receiver}"), name: method.to_string() },
+                "",
+                1,
+            );
+        };
+        call(&mut graph, ("com.shop.OrderController", "list"), "orderService", "load");
+        call(&mut graph, ("com.shop.OrderServiceImpl", "load"), "orderRepository", "findAll");
+        call(&mut graph, ("com.shop.ReportJob", "run"), "reportService", "build");
+
+        HotnessIndex::build(&graph, &table, MAX_DEPTH)
+    }
+
+    #[test]
+    fn test_distance_through_interface_injection() {
+        let index = sample();
+        assert_eq!(index.distance("OrderController", "list"), Some(0));
+        assert_eq!(index.distance("OrderController", "health"), Some(0));
+        assert_eq!(index.distance("OrderServiceImpl", "load"), Some(1));
+        assert_eq!(index.distance("OrderRepository", "findAll"), Some(2));
+        assert_eq!(index.distance("ReportJob", "run"), None);
+    }
+
+    #[test]
+    fn test_max_depth_limits_reach() {
+        let mut graph = CallGraph::new();
+        graph.register_class("A", "", LayerType::Controller);
+        graph.register_class("B", "", LayerType::Service);
+        graph.add_call(MethodSig::new_fqn("A", "a"), MethodSig::new_fqn("B", "b"), "", 1);
+        graph.add_call(MethodSig::new_fqn("B", "b"), MethodSig { class_fqn: "UNRESOLVED:".into(), name: "c".into() }, "", 2);
+
+        let shallow = HotnessIndex::build(&graph, &SymbolTable::new(), 1);
+        assert_eq!(shallow.distance("B", "b"), Some(1));
+        assert_eq!(shallow.distance("B", "c"), None);
+
+        let deep = HotnessIndex::build(&graph, &SymbolTable::new(), MAX_DEPTH);
+        assert_eq!(deep.distance("B", "c"), Some(2));
+    }
+
+    #[test]
+    fn test_score_scale() {
+        assert_eq!(score(Some(0)), 100);
+        assert_eq!(score(Some(1)), 50);
+        assert_eq!(score(Some(2)), 33);
+        assert_eq!(score(None), 0);
+    }
+
+    #[test]
+    fn test_collect_spans_innermost_class() {
+        let code = "class Outer {\n    void a() {\n    }\n    static class Inner {\n        Inner() {}\n        void b() { run(); }\n    }\n}\n";
+        let spans = spans_of(code);
+        assert_eq!(spans, vec![
+            MethodSpan { class: "Outer".into(), method: "a".into(), fqn: "Outer.a".into(), start_line: 2, end_line: 3 },
+            MethodSpan { class: "Inner".into(), method: "Inner".into(), fqn: "Outer.Inner.Inner".into(), start_line: 5, end_line: 5 },
+            MethodSpan { class: "Inner".into(), method: "b".into(), fqn: "Outer.Inner.b".into(), start_line: 6, end_line: 6 },
+        ]);
+        assert_eq!(innermost(&spans, 6).map(|s| s.fqn.as_str()), Some("Outer.Inner.b"));
+        assert!(innermost(&spans, 4).is_none());
+    }
+
+    #[test]
+    fn test_collect_spans_fqn_includes_package() {
+        let spans = spans_of("package com.shop.order;\n\nclass OrderService {\n    void load() {}\n}\n");
+        assert_eq!(spans[0].fqn, "com.shop.order.OrderService.load");
+    }
+}
@@ -0,0 +1,234 @@
+//! Phase 1 索引基准 (`dev bench-index`)
+//!
+//! 生成指定数量的合成 Java 文件 (Spring 分层的 Service/Repository、字段注入、重载方法、SQL 常量，
+//! 简单名跨包重复)，分别用两两合并 ([`build_index_merged`]) 与分片并发写入 ([`build_index`])
+//! 构建符号表，记录耗时与构建期间的峰值常驻内存，同时核对两者的统计与抽样查询一致:
+//!
+//! ```text
+//! java-perf dev bench-index --files 50000
+//! ```
+//!
+//! 计时前先完整读取一遍语料，两种构建都在页缓存预热后执行。
+//!
+//! 峰值内存取自 Linux `/proc/self/status`：每次构建前经 `/proc/self/clear_refs` 重置 VmHWM，
+//! 记录构建期间峰值相对构建前 VmRSS 的增量 (含构建结果本身)。分配器会保留先前释放的内存供后续复用，
+//! 后执行的构建因此可能偏低，所以先测分片构建、后测两两合并，对比结果只会低估分片构建的节省。
+//! 其他平台或无法重置峰值时不报告内存。
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ast_engine::{build_index, build_index_merged, FileAnalyzers};
+use crate::rules::registry::RuleRegistry;
+use crate::symbol_table::{SymbolStats, SymbolTable};
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 每个包内的类数 (超过后简单名在下一个包中重复)
+const CLASSES_PER_PACKAGE: usize = 5000;
+
+/// 基准参数
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub files: usize,
+    /// 语料目录 (默认系统临时目录，结束后删除)
+    pub dir: Option<PathBuf>,
+}
+
+/// 基准结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchResult {
+    pub files: usize,
+    pub merged_ms: u64,
+    pub sharded_ms: u64,
+    /// 构建期间峰值常驻内存增量 (KiB，仅 Linux)
+    pub merged_peak_kib: Option<u64>,
+    pub sharded_peak_kib: Option<u64>,
+    pub stats: SymbolStats,
+    /// 两种构建的统计与抽样查询一致
+    pub consistent: bool,
+}
+
+/// 生成语料并对两种构建计时
+pub fn run(options: &BenchOptions) -> Result<BenchResult, BoxError> {
+    let (dir, temporary) = match &options.dir {
+        Some(dir) => (dir.clone(), false),
+        None => (std::env::temp_dir().join(format!("java-perf-bench-{}", std::process::id())), true),
+    };
+    let result = generate(&dir, options.files).and_then(|paths| measure(&paths));
+    if temporary {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    result
+}
+
+fn measure(paths: &[PathBuf]) -> Result<BenchResult, BoxError> {
+    let analyzers = FileAnalyzers::new(&RuleRegistry::default())?;
+    let files: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+    for path in &files {
+        std::fs::read(path)?;
+    }
+
+    let ((sharded, _, _), sharded_ms, sharded_peak_kib) = measured(|| build_index(&files, analyzers.java()));
+    let ((merged, _, _), merged_ms, merged_peak_kib) = measured(|| build_index_merged(&files, analyzers.java()));
+
+    let consistent = merged.stats() == sharded.stats() && sample_lookups(&merged) == sample_lookups(&sharded);
+    Ok(BenchResult {
+        files: files.len(),
+        merged_ms,
+        sharded_ms,
+        merged_peak_kib,
+        sharded_peak_kib,
+        stats: sharded.stats(),
+        consistent,
+    })
+}
+
+/// 执行一次构建，返回结果、耗时 (ms) 与峰值常驻内存增量 (KiB)
+fn measured<T>(build: impl FnOnce() -> T) -> (T, u64, Option<u64>) {
+    let baseline = reset_peak_rss().then(|| proc_status_kib("VmRSS:")).flatten();
+    let started = Instant::now();
+    let built = build();
+    let elapsed = started.elapsed().as_millis() as u64;
+    let peak = baseline.and_then(|baseline| Some(proc_status_kib("VmHWM:")?.saturating_sub(baseline)));
+    (built, elapsed, peak)
+}
+
+/// 把 VmHWM 重置为当前常驻内存 (Linux 4.0+)
+fn reset_peak_rss() -> bool {
+    std::fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// `/proc/self/status` 中以 KiB 计的字段 (如 `VmRSS:`)
+fn proc_status_kib(field: &str) -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(field))?;
+    line[field.len()..].trim().trim_end_matches("kB").trim().parse().ok()
+}
+
+/// 抽样查询: 简单名重复的类、字段类型、重载
+fn sample_lookups(table: &SymbolTable) -> Vec<String> {
+    let mut out = Vec::new();
+    for i in [0, 1, CLASSES_PER_PACKAGE / 2, CLASSES_PER_PACKAGE - 1] {
+        let class = format!("OrderService{i}");
+        let mut fqns: Vec<String> = table.lookup_by_simple_name(&class).iter().map(|info| info.fqn.clone()).collect();
+        fqns.sort();
+        out.extend(fqns);
+        out.extend(table.lookup_var_type(&class, "repository").map(|info| info.fqn.clone()));
+        out.extend(table.lookup_methods(&class, "find").iter().flat_map(|m| m.params.iter().map(|p| p.type_name.clone())));
+    }
+    out
+}
+
+/// 写入 `files` 个合成源文件 (一半 Service、一半 Repository)
+pub(crate) fn generate(dir: &Path, files: usize) -> Result<Vec<PathBuf>, BoxError> {
+    let mut paths = Vec::with_capacity(files);
+    for n in 0..files {
+        let (i, package) = (n / 2 % CLASSES_PER_PACKAGE, n / 2 / CLASSES_PER_PACKAGE);
+        let package_dir = dir.join(format!("com/bench/p{package}"));
+        if n.is_multiple_of(2 * CLASSES_PER_PACKAGE) {
+            std::fs::create_dir_all(&package_dir)?;
+        }
+        let (name, source) = if n.is_multiple_of(2) {
+            (format!("OrderService{i}"), service_source(package, i))
+        } else {
+            (format!("OrderRepository{i}"), repository_source(package, i))
+        };
+        let path = package_dir.join(format!("{name}.java"));
+        std::fs::write(&path, source)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn service_source(package: usize, i: usize) -> String {
+    format!(r#"package com.bench.p{package};
+
+import org.springframework.stereotype.Service;
+import java.util.List;
+
+@Service
+public class OrderService{i} {{
+    private static final String FIND_SQL = "select * from orders_{i} where id = ?";
+    private static final boolean AUDIT = {audit};
+
+    @Autowired
+    private OrderRepository{i} repository;
+
+    public Order find(Long id) {{
+        return repository.findById(id).orElseThrow();
+    }}
+
+    public Order find(String code) {{
+        return repository.findByCode(code);
+    }}
+
+    public void process(List<Long> ids) {{
+        for (Long id : ids) {{
+            repository.findById(id);
+        }}
+    }}
+}}
+"#, audit = i.is_multiple_of(2))
+}
+
+fn repository_source(package: usize, i: usize) -> String {
+    format!(r#"package com.bench.p{package};
+
+import org.springframework.stereotype.Repository;
+
+@Repository
+public class OrderRepository{i} extends JdbcDaoSupport {{
+    public Order findByCode(String code) {{
+        return getJdbcTemplate().queryForObject(FIND_BY_CODE, mapper, code);
+    }}
+}}
+"#)
+}
+
+/// 渲染结果
+pub fn render(result: &BenchResult, json_output: bool) -> Value {
+    if json_output {
+        return json!(result);
+    }
+    let speedup = result.merged_ms as f64 / result.sharded_ms.max(1) as f64;
+    let peak = |kib: Option<u64>| kib.map_or_else(|| "-".to_string(), |kib| format!("{:.1} MiB", kib as f64 / 1024.0));
+    json!(format!(
+        "## 📊 Phase 1 索引基准 ({} 个文件)\n\n\
+         | 构建方式 | 耗时 | 峰值内存增量 |\n|---------|------|------------|\n\
+         | 两两合并 (reduce + merge) | {} ms | {} |\n\
+         | 分片并发写入 (DashMap) | {} ms ({speedup:.2}x) | {} |\n\n\
+         符号表: {} 类 / {} 字段 / {} 方法 / {} 常量，两种构建结果{}",
+        result.files, result.merged_ms, peak(result.merged_peak_kib), result.sharded_ms, peak(result.sharded_peak_kib),
+        result.stats.classes, result.stats.fields, result.stats.methods, result.stats.constants,
+        if result.consistent { "一致 ✅" } else { "不一致 ❌" }
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_builds_consistent_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = run(&BenchOptions { files: 40, dir: Some(dir.path().to_path_buf()) }).unwrap();
+        assert_eq!(result.files, 40);
+        assert!(result.consistent);
+        // 每个 Service 的两个 find 重载 + process、每个 Repository 的 findByCode
+        assert_eq!(result.stats.classes, 40);
+        assert_eq!(result.stats.methods, 20 * 3 + 20);
+        if cfg!(target_os = "linux") {
+            assert!(proc_status_kib("VmRSS:").is_some_and(|kib| kib > 0));
+        }
+        if reset_peak_rss() {
+            assert!(result.sharded_peak_kib.is_some() && result.merged_peak_kib.is_some());
+        }
+        let text = render(&result, false);
+        assert!(text.as_str().unwrap().contains("两种构建结果一致"));
+        assert!(text.as_str().unwrap().contains("峰值内存增量"));
+    }
+}
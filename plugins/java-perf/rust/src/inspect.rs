@@ -0,0 +1,234 @@
+//! 类元数据查看 (`inspect class <name>`)
+//!
+//! 在 `symbols dump` 的索引视图 (TypeInfo、字段绑定、import、调用边) 之上补充：
+//!
+//! - 方法签名 (返回类型、参数、注解)
+//! - 该类当前的扫描问题 (带项目上下文分析，与 `scan` 一致)
+//!
+//! 用于回答 "这条规则为什么报/为什么没报"：字段是否解析到了 Repository、
+//! 调用边是否存在、方法是否被识别。
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use tree_sitter::Node;
+
+use crate::ast_engine::{build_index, locate_issue, relative_path, AstIssue, FileAnalyzers};
+use crate::dedup::{dedup, DedupPolicy};
+use crate::encoding::read_source;
+use crate::shared_path::FilePath;
+use crate::project_detector::detect_stack;
+use crate::rules::registry::RuleRegistry;
+use crate::scanner::tree_sitter_java::method_info;
+use crate::symbol_cache::{self, ProjectIndex};
+use crate::symbol_table::{MethodInfo, TypeInfo};
+
+type BoxError = Box<dyn std::error::Error>;
+
+fn text(node: Node, code: &str) -> String {
+    node.utf8_text(code.as_bytes()).unwrap_or("").to_string()
+}
+
+/// 查找名为 class_name 的类型声明 (含嵌套类型)
+fn find_type_declaration<'a>(root: Node<'a>, code: &str, class_name: &str) -> Option<Node<'a>> {
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "class_declaration" | "enum_declaration" | "interface_declaration" | "record_declaration")
+            && node.child_by_field_name("name").is_some_and(|n| text(n, code) == class_name)
+        {
+            return Some(node);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    None
+}
+
+/// 类型声明体中的方法与构造器 (不含嵌套类型的方法)
+pub fn collect_methods(declaration: Node, code: &str, class_name: &str) -> Vec<MethodInfo> {
+    let Some(body) = declaration.child_by_field_name("body") else {
+        return Vec::new();
+    };
+    let mut cursor = body.walk();
+    body.named_children(&mut cursor)
+        .filter(|n| matches!(n.kind(), "method_declaration" | "constructor_declaration"))
+        .filter_map(|method| method_info(method, code, class_name))
+        .collect()
+}
+
+fn method_signature(method: &MethodInfo) -> String {
+    let params: Vec<String> = method.params.iter().map(|p| format!("{} {}", p.type_name, p.name)).collect();
+    match &method.return_type {
+        Some(ret) => format!("{ret} {}({})", method.name, params.join(", ")),
+        None => format!("{}({})", method.name, params.join(", ")),
+    }
+}
+
+/// 按简单名或 FQN 查看类
+pub fn inspect_class(code_path: &str, name: &str) -> Result<Value, BoxError> {
+    let root = Path::new(code_path);
+    let registry = if root.is_dir() {
+        RuleRegistry::with_stack(&detect_stack(root))
+    } else {
+        RuleRegistry::default()
+    };
+    let analyzers = FileAnalyzers::new(&registry)?;
+
+    let files = if root.is_dir() { symbol_cache::java_files(root) } else { vec![root.to_path_buf()] };
+    let paths: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+    let (table, graph, imports) = build_index(&paths, analyzers.java());
+    let index = ProjectIndex { table, graph, imports, ..Default::default() };
+    Ok(inspect_indexed(root, name, &index, &analyzers))
+}
+
+/// 在已构建的项目索引上查看类 (daemon 复用常驻索引)
+pub(crate) fn inspect_indexed(root: &Path, name: &str, index: &ProjectIndex, analyzers: &FileAnalyzers) -> Value {
+    let classes: Vec<Value> = symbol_cache::find_classes(&index.table, name).into_iter()
+        .map(|info| {
+            let mut class = symbol_cache::describe_class(index, info);
+            let (methods, findings) = inspect_source(root, info, index, analyzers);
+            class["methods"] = json!(methods.iter().map(|m| json!({
+                "signature": method_signature(m),
+                "annotations": m.annotations,
+                "line": m.line,
+            })).collect::<Vec<_>>());
+            class["findings"] = json!(findings);
+            class
+        })
+        .collect();
+
+    json!({ "query": name, "classes": classes })
+}
+
+/// 解析类所在文件：方法列表 + 类声明范围内的问题
+fn inspect_source(root: &Path, info: &TypeInfo, index: &ProjectIndex, analyzers: &FileAnalyzers) -> (Vec<MethodInfo>, Vec<AstIssue>) {
+    let Ok(source) = read_source(info.file.as_std_path()) else {
+        return (Vec::new(), Vec::new());
+    };
+    let content = &source.content;
+
+    let parsed = analyzers.java().parse_with(content, |tree| {
+        find_type_declaration(tree.root_node(), content, &info.name).map(|decl| {
+            (collect_methods(decl, content, &info.name), decl.start_position().row + 1, decl.end_position().row + 1)
+        })
+    });
+    let Ok(Some((methods, start_line, end_line))) = parsed else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let rel_path = FilePath::new(&relative_path(root, info.file.as_std_path()));
+    let issues: Vec<AstIssue> = analyzers
+        .analyze_file(info.file.as_std_path(), content, Some(&index.table), Some(&index.graph))
+        .into_iter()
+        .map(|issue| locate_issue(issue, &rel_path, content))
+        .filter(|issue| start_line <= issue.line && issue.line <= end_line)
+        .collect();
+    let mut issues = dedup(issues, DedupPolicy::default());
+    issues.sort_by_key(|issue| issue.line);
+    (methods, issues)
+}
+
+/// 渲染：索引视图 + 方法 + 问题
+pub fn render(result: &Value, json_output: bool) -> Value {
+    if json_output {
+        return result.clone();
+    }
+    let classes = result["classes"].as_array().map(Vec::as_slice).unwrap_or_default();
+    if classes.is_empty() {
+        return json!(format!("❓ 未找到类 {}", result["query"].as_str().unwrap_or_default()));
+    }
+
+    let mut out = String::new();
+    for class in classes {
+        out.push_str(&symbol_cache::render_class(class));
+        out.push_str("\n### 方法\n");
+        for method in class["methods"].as_array().into_iter().flatten() {
+            let annotations: Vec<String> = method["annotations"].as_array().into_iter().flatten()
+                .filter_map(Value::as_str)
+                .map(|a| format!("@{a} "))
+                .collect();
+            out.push_str(&format!(
+                "- L{} {}{}\n",
+                method["line"], annotations.concat(), method["signature"].as_str().unwrap_or_default(),
+            ));
+        }
+        out.push_str("\n### 问题\n");
+        let findings = class["findings"].as_array().map(Vec::as_slice).unwrap_or_default();
+        if findings.is_empty() {
+            out.push_str("- (无)\n");
+        }
+        for issue in findings {
+            out.push_str(&format!(
+                "- [{}] L{} {} - {}\n",
+                issue["severity"].as_str().unwrap_or_default(), issue["line"],
+                issue["id"].as_str().unwrap_or_default(), issue["description"].as_str().unwrap_or_default(),
+            ));
+        }
+        out.push('\n');
+    }
+    json!(out.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("OrderRepository.java"),
+            "package com.shop;\n@Repository\npublic class OrderRepository {}\n").unwrap();
+        std::fs::write(dir.path().join("OrderController.java"),
+            "package com.shop;\n@RestController\npublic class OrderController {\n    @Autowired\n    private OrderService orderService;\n\n    @GetMapping\n    public void list(List<Long> ids) {\n        orderService.load(ids);\n    }\n}\n").unwrap();
+        std::fs::write(dir.path().join("OrderService.java"), r#"package com.shop;
+@Service
+public class OrderService {
+    @Autowired
+    private OrderRepository orderRepository;
+
+    public OrderService() {}
+
+    @Transactional
+    public List<Order> load(List<Long> ids, String... tags) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+        return null;
+    }
+}
+"#).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_inspect_class_relations() {
+        let dir = project();
+        let result = inspect_class(dir.path().to_str().unwrap(), "OrderService").unwrap();
+        let class = &result["classes"][0];
+        assert_eq!(class["fqn"], "com.shop.OrderService");
+        assert_eq!(class["fields"][0]["resolves_to"][0], "com.shop.OrderRepository");
+
+        let signatures: Vec<&str> = class["methods"].as_array().unwrap().iter()
+            .map(|m| m["signature"].as_str().unwrap())
+            .collect();
+        assert_eq!(signatures, vec!["OrderService()", "List<Order> load(List<Long> ids, String... tags)"]);
+        assert_eq!(class["methods"][1]["annotations"][0], "Transactional");
+
+        assert_eq!(class["callers"][0]["caller"], "com.shop.OrderController.list");
+        assert_eq!(class["calls"][0]["callee"], "UNRESOLVED:orderRepository.findById");
+        assert_eq!(class["findings"][0]["id"], "AUTOWIRED_FIELD");
+        assert_eq!(class["findings"][1]["id"], "N_PLUS_ONE");
+        assert_eq!(class["findings"][1]["line"], 12);
+
+        let text = render(&result, false);
+        let text = text.as_str().unwrap();
+        assert!(text.contains("- L9 @Transactional List<Order> load(List<Long> ids, String... tags)"), "{text}");
+        assert!(text.contains("] L12 N_PLUS_ONE"), "{text}");
+    }
+
+    #[test]
+    fn test_inspect_unknown_class() {
+        let dir = project();
+        let result = inspect_class(dir.path().to_str().unwrap(), "Missing").unwrap();
+        assert_eq!(render(&result, false), json!("❓ 未找到类 Missing"));
+    }
+}
@@ -0,0 +1,254 @@
+//! javax / jakarta 命名空间混用 (JAVAX_JAKARTA_MIXED)
+//!
+//! Jakarta EE 9 起 Servlet、JPA、Bean Validation 等规范的包名从 `javax.*` 改为 `jakarta.*`，
+//! Spring Boot 3 只支持 `jakarta.*`。迁移不彻底的项目中部分模块仍 import `javax.*` 并依赖旧 API 构件:
+//!
+//! - 运行时容器只认一套命名空间，另一套的注解静默失效 (`@javax.persistence.Entity` 不被 Hibernate 6
+//!   识别、`@javax.validation.Valid` 不触发校验)，或类找不到 (`ClassNotFoundException: javax.servlet.Filter`)
+//! - 两套 API 构件同时进入部署包，依赖树中重复
+//!
+//! 按规范统计各模块 (构建文件所在目录) Java 源码中的 import，同一规范在项目中两种命名空间都出现时，
+//! 对仍使用 `javax.*` 的每个模块报告一次，定位到该模块中引入旧命名空间的依赖声明 (`javax.*` API 构件、
+//! Hibernate 5、Spring Boot 2 starter 等)；找不到时定位到第一处 `javax` import。
+//! JDK 自带的 `javax.*` 包 (`javax.sql`、`javax.crypto`、`javax.transaction.xa`) 与 JSR-305 注解不计入。
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::ast_engine::{relative_path, AstIssue, ImportIndexMap, Severity};
+use crate::dependency_audit::{self, Declared};
+use crate::shared_path::FilePath;
+use crate::scanner::issue_builder::{fingerprint, line_snippet};
+
+pub const RULE_ID: &str = "JAVAX_JAKARTA_MIXED";
+
+/// 迁移到 jakarta 的规范: (规范名, 包名 (javax./jakarta. 之后), 提供 javax 版本 API 的构件 (groupId:artifactId 前缀))
+const SPECS: &[(&str, &str, &[&str])] = &[
+    ("Servlet", "servlet", &["javax.servlet:"]),
+    ("Persistence (JPA)", "persistence", &["javax.persistence:", "org.hibernate:hibernate-core", "org.hibernate:hibernate-entitymanager"]),
+    ("Bean Validation", "validation", &["javax.validation:", "org.hibernate.validator:hibernate-validator", "org.hibernate:hibernate-validator"]),
+    ("Common Annotations", "annotation", &["javax.annotation:javax.annotation-api"]),
+    ("Transactions", "transaction", &["javax.transaction:"]),
+    ("JAX-RS", "ws.rs", &["javax.ws.rs:"]),
+    ("JAXB", "xml.bind", &["javax.xml.bind:", "com.sun.xml.bind:"]),
+    ("CDI", "enterprise", &["javax.enterprise:"]),
+    ("Dependency Injection", "inject", &["javax.inject:"]),
+    ("JMS", "jms", &["javax.jms:"]),
+    ("Mail", "mail", &["javax.mail:", "com.sun.mail:javax.mail"]),
+    ("WebSocket", "websocket", &["javax.websocket:"]),
+    ("EJB", "ejb", &["javax.ejb:"]),
+    ("JSON-P", "json", &["javax.json:", "org.glassfish:javax.json"]),
+    ("Interceptors", "interceptor", &["javax.interceptor:"]),
+    ("Faces", "faces", &["javax.faces:"]),
+];
+
+/// `javax.annotation` 中迁移到 jakarta 的类型 (其余如 `Nullable` 属于 JSR-305，`processing` 属于 JDK)
+const MIGRATED_ANNOTATIONS: &[&str] = &["PostConstruct", "PreDestroy", "Resource", "Resources", "Generated", "Priority", "ManagedBean", "security", "sql"];
+
+/// 在 Spring Boot 2 中引入 javax 版本 API 的 starter 所涉及的规范
+const BOOT2_SPECS: &[&str] = &["servlet", "persistence", "validation", "annotation", "transaction"];
+
+/// import 所属的规范与命名空间 (true = javax)
+fn classify(import: &str) -> Option<(&'static str, bool)> {
+    let (javax, rest) = match (import.strip_prefix("javax."), import.strip_prefix("jakarta.")) {
+        (Some(rest), _) => (true, rest),
+        (_, Some(rest)) => (false, rest),
+        _ => return None,
+    };
+    let (_, package, _) = SPECS.iter().find(|(_, package, _)| {
+        rest == *package || rest.strip_prefix(package).is_some_and(|tail| tail.starts_with('.'))
+    })?;
+    let tail = rest[package.len()..].trim_start_matches('.');
+    let excluded = match *package {
+        "annotation" => javax && !MIGRATED_ANNOTATIONS.iter().any(|name| tail == *name || tail.starts_with(&format!("{name}."))),
+        "transaction" => tail == "xa" || tail.starts_with("xa."),
+        _ => false,
+    };
+    (!excluded).then_some((*package, javax))
+}
+
+fn spec_name(package: &str) -> &'static str {
+    SPECS.iter().find(|(_, p, _)| *p == package).map_or("", |(name, _, _)| *name)
+}
+
+/// 构件是否提供 `package` 规范的 javax 版本 API
+fn provides_javax(dep: &Declared, package: &str) -> bool {
+    let major = || dep.version.as_deref().and_then(|v| v.split('.').next()?.parse::<u32>().ok());
+    let listed = SPECS.iter()
+        .find(|(_, p, _)| *p == package)
+        .is_some_and(|(_, _, artifacts)| artifacts.iter().any(|a| dep.coordinate.starts_with(a)));
+    if listed {
+        // Hibernate 6 / Hibernate Validator 7 起为 jakarta
+        return match dep.coordinate.as_str() {
+            "org.hibernate:hibernate-core" | "org.hibernate:hibernate-entitymanager" => major().is_some_and(|m| m < 6),
+            c if c.ends_with(":hibernate-validator") => major().is_some_and(|m| m < 7),
+            _ => true,
+        };
+    }
+    dep.coordinate.starts_with("org.springframework.boot:spring-boot-starter")
+        && BOOT2_SPECS.contains(&package)
+        && major().is_some_and(|m| m < 3)
+}
+
+/// 模块中某规范的使用情况
+#[derive(Default)]
+struct Usage {
+    /// 使用 javax 的文件 (相对路径，有序)
+    javax: BTreeSet<String>,
+    jakarta: BTreeSet<String>,
+}
+
+/// 统计各模块的 import，报告仍使用 javax 的模块
+pub(crate) fn analyze(root: &Path, declared: &[Declared], imports: &ImportIndexMap) -> Vec<AstIssue> {
+    let mut modules: BTreeSet<String> = declared.iter().map(|dep| dep.module().to_string()).collect();
+    modules.insert(String::new());
+    let module_of = |rel: &str| -> String {
+        modules.iter()
+            .filter(|m| m.is_empty() || rel.starts_with(&format!("{m}/")))
+            .max_by_key(|m| m.len())
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    // 规范 -> 模块 -> 使用情况
+    let mut usage: BTreeMap<&str, BTreeMap<String, Usage>> = BTreeMap::new();
+    for (file, index) in imports {
+        let rel = relative_path(root, Path::new(file));
+        let module = module_of(&rel);
+        let names = index.explicit.values().chain(index.wildcards.iter());
+        for (package, javax) in names.filter_map(|name| classify(name)) {
+            let entry = usage.entry(package).or_default().entry(module.clone()).or_default();
+            if javax { &mut entry.javax } else { &mut entry.jakarta }.insert(rel.clone());
+        }
+    }
+    usage.retain(|_, by_module| {
+        by_module.values().any(|u| !u.javax.is_empty()) && by_module.values().any(|u| !u.jakarta.is_empty())
+    });
+
+    // 模块 -> 仍使用 javax 的规范
+    let mut stale: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (package, by_module) in &usage {
+        for module in by_module.iter().filter(|(_, u)| !u.javax.is_empty()).map(|(module, _)| module) {
+            stale.entry(module.as_str()).or_default().push(package);
+        }
+    }
+
+    let mut issues = Vec::new();
+    for (module, packages) in stale {
+        let specs: Vec<String> = packages.iter().map(|p| format!("{} (javax.{p})", spec_name(p))).collect();
+        let summary: Vec<String> = packages.iter()
+            .map(|package| {
+                let counts: Vec<String> = usage[package].iter()
+                    .map(|(m, u)| {
+                        let name = if m.is_empty() { "<根模块>" } else { m.as_str() };
+                        format!("{name}: javax {} / jakarta {}", u.javax.len(), u.jakarta.len())
+                    })
+                    .collect();
+                format!("{}: {}", spec_name(package), counts.join(", "))
+            })
+            .collect();
+        // 模块自身的声明优先，其次是根模块 (父 pom 中继承的依赖只在根模块计一次)
+        let candidates: Vec<&Declared> = declared.iter().filter(|dep| dep.module() == module)
+            .chain(declared.iter().filter(|dep| dep.module().is_empty()))
+            .collect();
+        let culprit = packages.iter()
+            .find_map(|package| candidates.iter().find(|dep| provides_javax(dep, package)).copied());
+        let name = if module.is_empty() { "根模块" } else { module };
+        let description = format!(
+            "{name} 仍使用 {}，项目其它代码已迁移到 jakarta.*: 运行时只认一套命名空间，另一套的注解静默失效或类找不到，两套 API 构件同时打包",
+            specs.join("、")
+        );
+        let issue = match culprit {
+            Some(dep) => dependency_audit::issue(root, RULE_ID, dep, description, format!("{}; 旧命名空间来自 {} ({})", summary.join("; "), dep.coordinate, dep.file)),
+            None => {
+                let first = usage[packages[0]][module].javax.iter().next().cloned().unwrap_or_default();
+                import_issue(root, &first, packages[0], description, summary.join("; "))
+            }
+        };
+        issues.extend(issue);
+    }
+    issues
+}
+
+/// 定位到 Java 文件中的第一处 javax import
+fn import_issue(root: &Path, rel: &str, package: &str, description: String, context: String) -> Option<AstIssue> {
+    let content = std::fs::read_to_string(root.join(rel)).ok()?;
+    let needle = format!("javax.{package}");
+    let line = content.lines()
+        .position(|line| line.trim_start().starts_with("import") && line.contains(&needle))
+        .map_or(1, |idx| idx + 1);
+    let snippet = line_snippet(&content, line);
+    Some(AstIssue {
+        severity: Severity::P1,
+        issue_type: RULE_ID.to_string(),
+        file: rel.rsplit('/').next().unwrap_or(rel).to_string(),
+        path: FilePath::new(rel),
+        line,
+        column: 0,
+        description,
+        context: Some(context),
+        fingerprint: fingerprint(RULE_ID, &needle, &snippet),
+        snippet,
+        hotness: 0,
+        method: None,
+        secondary: Vec::new(),
+        inactive: false,
+        suppressed: false,
+        suppression: None,
+        owners: Vec::new(),
+        suggested_patch: None,
+        triage: None,
+        age: None,
+        budget: None,
+        coverage: None,
+        debt: None,
+        sql: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol_table::ImportIndex;
+
+    #[test]
+    fn test_classify_skips_jdk_and_jsr305() {
+        assert_eq!(classify("javax.servlet.http.HttpServletRequest"), Some(("servlet", true)));
+        assert_eq!(classify("jakarta.persistence"), Some(("persistence", false)));
+        assert_eq!(classify("javax.annotation.PostConstruct"), Some(("annotation", true)));
+        assert_eq!(classify("javax.annotation.Nullable"), None);
+        assert_eq!(classify("javax.transaction.xa.XAResource"), None);
+        assert_eq!(classify("javax.sql.DataSource"), None);
+        assert_eq!(classify("javax.servletx.Foo"), None);
+    }
+
+    #[test]
+    fn test_reports_module_with_old_dependency() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("legacy")).unwrap();
+        std::fs::write(root.join("legacy/pom.xml"), "<project>\n<dependencies>\n<dependency><groupId>javax.servlet</groupId><artifactId>javax.servlet-api</artifactId></dependency>\n</dependencies></project>\n").unwrap();
+        let declared = vec![
+            Declared { coordinate: "jakarta.servlet:jakarta.servlet-api".into(), version: Some("6.0.0".into()), file: "pom.xml".into(), runtime: true },
+            Declared { coordinate: "javax.servlet:javax.servlet-api".into(), version: Some("4.0.1".into()), file: "legacy/pom.xml".into(), runtime: true },
+        ];
+        let index = |imports: &[&str]| ImportIndex::from_imports(imports.iter().map(|s| s.to_string()).collect(), None);
+        let imports: ImportIndexMap = [
+            (root.join("src/main/java/Api.java"), index(&["jakarta.servlet.Filter", "javax.sql.DataSource"])),
+            (root.join("legacy/src/main/java/Old.java"), index(&["javax.servlet.Filter", "javax.annotation.Nullable"])),
+            (root.join("legacy/src/main/java/Other.java"), index(&["javax.servlet.http.*"])),
+        ].into_iter().map(|(path, index)| (path.to_string_lossy().to_string(), index)).collect();
+
+        let issues = analyze(root, &declared, &imports);
+        assert_eq!(issues.len(), 1, "{issues:#?}");
+        assert_eq!((issues[0].path.as_str(), issues[0].line), ("legacy/pom.xml", 3));
+        assert!(issues[0].description.starts_with("legacy 仍使用 Servlet (javax.servlet)"), "{}", issues[0].description);
+        let context = issues[0].context.as_deref().unwrap();
+        assert!(context.contains("<根模块>: javax 0 / jakarta 1, legacy: javax 2 / jakarta 0"), "{context}");
+        assert!(context.ends_with("旧命名空间来自 javax.servlet:javax.servlet-api (legacy/pom.xml)"), "{context}");
+
+        // 只有 javax 时不算混用
+        let only_legacy: ImportIndexMap = imports.into_iter().filter(|(path, _)| path.contains("legacy")).collect();
+        assert!(analyze(root, &declared, &only_legacy).is_empty());
+    }
+}
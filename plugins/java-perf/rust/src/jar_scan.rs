@@ -0,0 +1,587 @@
+//! 依赖字节码扫描 (`jar-scan <path>`)
+//!
+//! 没有源码的第三方依赖无法走 Tree-sitter 分析，这里直接读取 class 文件，
+//! 根据常量池引用与字段/方法声明识别已知的库级性能隐患：
+//!
+//! | ID | 判定 |
+//! |----|------|
+//! | `SHARED_DATE_FORMAT` | static 的 SimpleDateFormat/DateFormat 字段 (非线程安全；类中有 synchronized 方法时多为加锁共享，高并发下串行化) |
+//! | `CACHED_THREAD_POOL` | 调用 `Executors.newCachedThreadPool` (线程数无上限) |
+//! | `EXPLICIT_GC` | 调用 `System.gc()` / `Runtime.gc()` |
+//! | `LEGACY_SYNC_COLLECTION` | Hashtable/Vector 类型的字段 (每次访问都加锁) |
+//!
+//! 输入可以是 `.jar`/`.war`/`.ear` (含 `WEB-INF/lib`、`BOOT-INF/lib` 中嵌套的 jar)、
+//! 单个 `.class` 或包含它们的目录。结果是库级建议 (advisory)，与源码问题分开报告。
+
+use std::collections::BTreeSet;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+
+use crate::ast_engine::relative_path;
+
+type BoxError = Box<dyn std::error::Error>;
+
+const ACC_STATIC: u16 = 0x0008;
+const ACC_SYNCHRONIZED: u16 = 0x0020;
+
+/// 字段或方法声明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub access: u16,
+    pub name: String,
+    pub descriptor: String,
+}
+
+/// class 文件中与规则相关的部分
+#[derive(Debug, Clone, Default)]
+pub struct ClassFile {
+    /// 内部名 (`com/acme/Util`)
+    pub name: String,
+    /// 父类内部名 (`java/lang/Object` 本身为 None)
+    pub super_class: Option<String>,
+    /// 实现的接口 (内部名)
+    pub interfaces: Vec<String>,
+    pub fields: Vec<Member>,
+    pub methods: Vec<Member>,
+    /// 常量池中的方法引用 (所属类内部名, 方法名)
+    pub method_refs: Vec<(String, String)>,
+}
+
+/// 库级建议
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Advisory {
+    /// 所在构件 (嵌套 jar 为 `app.war!/WEB-INF/lib/x.jar`)
+    pub artifact: String,
+    /// 类的 FQN
+    pub class: String,
+    pub id: &'static str,
+    pub detail: String,
+}
+
+/// 常量池条目 (只保留需要解引用的部分)
+#[derive(Clone)]
+enum Constant {
+    Utf8(String),
+    Class(u16),
+    NameAndType(u16),
+    MethodRef(u16, u16),
+    Other,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| format!("class 文件在偏移 {} 处截断", self.pos))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u1(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u2(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u4(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+fn utf8(pool: &[Constant], index: u16) -> Option<&str> {
+    match pool.get(index as usize) {
+        Some(Constant::Utf8(s)) => Some(s),
+        _ => None,
+    }
+}
+
+fn class_name(pool: &[Constant], index: u16) -> Option<&str> {
+    match pool.get(index as usize) {
+        Some(Constant::Class(name)) => utf8(pool, *name),
+        _ => None,
+    }
+}
+
+fn read_members(reader: &mut Reader, pool: &[Constant]) -> Result<Vec<Member>, String> {
+    let count = reader.u2()?;
+    let mut members = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let access = reader.u2()?;
+        let name = reader.u2()?;
+        let descriptor = reader.u2()?;
+        for _ in 0..reader.u2()? {
+            reader.u2()?;
+            let len = reader.u4()? as usize;
+            reader.take(len)?;
+        }
+        members.push(Member {
+            access,
+            name: utf8(pool, name).unwrap_or_default().to_string(),
+            descriptor: utf8(pool, descriptor).unwrap_or_default().to_string(),
+        });
+    }
+    Ok(members)
+}
+
+/// 解析 class 文件 (常量池、字段、方法；不解析字节码指令)
+pub fn parse_class(bytes: &[u8]) -> Result<ClassFile, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.u4()? != 0xCAFE_BABE {
+        return Err("不是 class 文件 (magic 不匹配)".to_string());
+    }
+    reader.take(4)?; // minor + major version
+
+    let count = reader.u2()?;
+    // 下标从 1 开始；Long/Double 占两个槽位
+    let mut pool = vec![Constant::Other; count as usize];
+    let mut index = 1;
+    while index < count as usize {
+        let tag = reader.u1()?;
+        pool[index] = match tag {
+            1 => {
+                let len = reader.u2()? as usize;
+                // Modified UTF-8 与标准 UTF-8 只在 \0 与补充字符上不同，类名/描述符不受影响
+                Constant::Utf8(String::from_utf8_lossy(reader.take(len)?).into_owned())
+            }
+            7 => Constant::Class(reader.u2()?),
+            10 | 11 => Constant::MethodRef(reader.u2()?, reader.u2()?),
+            12 => {
+                let name = reader.u2()?;
+                reader.u2()?;
+                Constant::NameAndType(name)
+            }
+            3 | 4 => { reader.take(4)?; Constant::Other }
+            5 | 6 => { reader.take(8)?; index += 1; Constant::Other }
+            8 | 16 | 19 | 20 => { reader.take(2)?; Constant::Other }
+            9 | 17 | 18 => { reader.take(4)?; Constant::Other }
+            15 => { reader.take(3)?; Constant::Other }
+            _ => return Err(format!("未知常量池标签 {tag}")),
+        };
+        index += 1;
+    }
+
+    reader.u2()?; // access_flags
+    let this_class = reader.u2()?;
+    let super_class = reader.u2()?;
+    let mut interfaces = Vec::new();
+    for _ in 0..reader.u2()? {
+        let index = reader.u2()?;
+        interfaces.extend(class_name(&pool, index).map(str::to_string));
+    }
+    let fields = read_members(&mut reader, &pool)?;
+    let methods = read_members(&mut reader, &pool)?;
+
+    let method_refs = pool.iter()
+        .filter_map(|c| match c {
+            Constant::MethodRef(class, name_and_type) => {
+                let owner = class_name(&pool, *class)?;
+                let Some(Constant::NameAndType(name)) = pool.get(*name_and_type as usize) else {
+                    return None;
+                };
+                Some((owner.to_string(), utf8(&pool, *name)?.to_string()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    Ok(ClassFile {
+        name: class_name(&pool, this_class).unwrap_or_default().to_string(),
+        super_class: class_name(&pool, super_class).map(str::to_string),
+        interfaces,
+        fields,
+        methods,
+        method_refs,
+    })
+}
+
+/// 对单个类应用规则
+pub fn check_class(class: &ClassFile, artifact: &str) -> Vec<Advisory> {
+    let fqn = class.name.replace('/', ".");
+    let advisory = |id: &'static str, detail: String| Advisory {
+        artifact: artifact.to_string(),
+        class: fqn.clone(),
+        id,
+        detail,
+    };
+    let mut advisories = Vec::new();
+
+    let synchronized: Vec<&str> = class.methods.iter()
+        .filter(|m| m.access & ACC_SYNCHRONIZED != 0)
+        .map(|m| m.name.as_str())
+        .collect();
+    for field in &class.fields {
+        match field.descriptor.as_str() {
+            "Ljava/text/SimpleDateFormat;" | "Ljava/text/DateFormat;" if field.access & ACC_STATIC != 0 => {
+                let detail = if synchronized.is_empty() {
+                    format!("static DateFormat 字段 `{}` 非线程安全", field.name)
+                } else {
+                    format!(
+                        "static DateFormat 字段 `{}` 通过 synchronized 方法 ({}) 共享，高并发下串行化",
+                        field.name, synchronized.join(", ")
+                    )
+                };
+                advisories.push(advisory("SHARED_DATE_FORMAT", detail));
+            }
+            "Ljava/util/Hashtable;" | "Ljava/util/Vector;" => {
+                advisories.push(advisory("LEGACY_SYNC_COLLECTION", format!("字段 `{}` 使用全方法加锁的 {}", field.name, field.descriptor.trim_start_matches("Ljava/util/").trim_end_matches(';'))));
+            }
+            _ => {}
+        }
+    }
+
+    for (owner, method) in &class.method_refs {
+        match (owner.as_str(), method.as_str()) {
+            ("java/util/concurrent/Executors", "newCachedThreadPool") => {
+                advisories.push(advisory("CACHED_THREAD_POOL", "Executors.newCachedThreadPool() 线程数无上限".to_string()));
+            }
+            ("java/lang/System" | "java/lang/Runtime", "gc") => {
+                advisories.push(advisory("EXPLICIT_GC", "显式触发 Full GC".to_string()));
+            }
+            _ => {}
+        }
+    }
+    advisories
+}
+
+/// class 文件遍历统计
+#[derive(Debug, Default)]
+pub(crate) struct ClassWalk {
+    pub artifacts: usize,
+    pub classes: usize,
+    /// 无法解析的 class/jar
+    pub errors: Vec<String>,
+}
+
+impl ClassWalk {
+    fn class(&mut self, bytes: &[u8], artifact: &str, entry: &str, visit: &mut dyn FnMut(ClassFile, &str)) {
+        match parse_class(bytes) {
+            Ok(class) => {
+                self.classes += 1;
+                visit(class, artifact);
+            }
+            Err(e) => self.errors.push(format!("{artifact}!/{entry}: {e}")),
+        }
+    }
+
+    fn archive<R: Read + Seek>(&mut self, reader: R, artifact: &str, visit: &mut dyn FnMut(ClassFile, &str)) {
+        let mut archive = match zip::ZipArchive::new(reader) {
+            Ok(archive) => archive,
+            Err(e) => {
+                self.errors.push(format!("{artifact}: {e}"));
+                return;
+            }
+        };
+        self.artifacts += 1;
+        for i in 0..archive.len() {
+            let Ok(mut entry) = archive.by_index(i) else {
+                continue;
+            };
+            let name = entry.name().to_string();
+            let is_class = name.ends_with(".class") && !name.ends_with("module-info.class");
+            let is_nested = name.ends_with(".jar");
+            if !is_class && !is_nested {
+                continue;
+            }
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            if let Err(e) = entry.read_to_end(&mut bytes) {
+                self.errors.push(format!("{artifact}!/{name}: {e}"));
+                continue;
+            }
+            if is_nested {
+                self.archive(Cursor::new(bytes), &format!("{artifact}!/{name}"), visit);
+            } else {
+                self.class(&bytes, artifact, &name, visit);
+            }
+        }
+    }
+}
+
+fn is_archive(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("jar" | "war" | "ear"))
+}
+
+/// 遍历 jar/war/ear (含嵌套 jar)、class 文件或目录中的全部类
+///
+/// 构件名为相对 `root` 的路径；目录中的 class 文件以所在目录为构件。
+pub(crate) fn walk_classes(root: &Path, visit: &mut dyn FnMut(ClassFile, &str)) -> ClassWalk {
+    let mut walk = ClassWalk::default();
+    let files: Vec<_> = if root.is_dir() {
+        WalkDir::new(root).into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect()
+    } else {
+        vec![root.to_path_buf()]
+    };
+    for file in files {
+        let rel = relative_path(root, &file);
+        if is_archive(&file) {
+            match std::fs::File::open(&file) {
+                Ok(reader) => walk.archive(std::io::BufReader::new(reader), &rel, visit),
+                Err(e) => walk.errors.push(format!("{rel}: {e}")),
+            }
+        } else if file.extension().and_then(|e| e.to_str()) == Some("class") {
+            let artifact = Path::new(&rel).parent()
+                .map(|p| p.to_string_lossy().into_owned())
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| ".".to_string());
+            match std::fs::read(&file) {
+                Ok(bytes) => walk.class(&bytes, &artifact, &rel, visit),
+                Err(e) => walk.errors.push(format!("{rel}: {e}")),
+            }
+        }
+    }
+    walk
+}
+
+/// 扫描 jar/war/ear、class 文件或目录
+pub fn jar_scan(path: &str) -> Result<Value, BoxError> {
+    let root = Path::new(path);
+    if !root.exists() {
+        return Err(format!("路径不存在: {path}").into());
+    }
+
+    let mut advisories = BTreeSet::new();
+    let scan = walk_classes(root, &mut |class, artifact| advisories.extend(check_class(&class, artifact)));
+
+    Ok(json!({
+        "path": path,
+        "artifacts": scan.artifacts,
+        "classes": scan.classes,
+        "errors": scan.errors,
+        "advisories": advisories,
+    }))
+}
+
+/// 渲染 jar-scan 结果 (按构件分组)
+pub fn render(result: &Value, json_output: bool) -> Value {
+    if json_output {
+        return result.clone();
+    }
+    let advisories = result["advisories"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let mut out = format!(
+        "## 📦 依赖字节码扫描\n\n**构件**: {} | **类**: {} | **建议**: {}\n\n",
+        result["artifacts"], result["classes"], advisories.len()
+    );
+    if advisories.is_empty() {
+        out.push_str("✅ 未发现已知的库级性能隐患\n");
+    }
+
+    let mut current = None;
+    for advisory in advisories {
+        let artifact = advisory["artifact"].as_str().unwrap_or_default();
+        if current != Some(artifact) {
+            out.push_str(&format!("### {artifact}\n\n"));
+            current = Some(artifact);
+        }
+        out.push_str(&format!(
+            "- **{}** `{}` - {}\n",
+            advisory["id"].as_str().unwrap_or_default(),
+            advisory["class"].as_str().unwrap_or_default(),
+            advisory["detail"].as_str().unwrap_or_default(),
+        ));
+    }
+
+    let errors = result["errors"].as_array().map(Vec::as_slice).unwrap_or_default();
+    if !errors.is_empty() {
+        out.push_str(&format!("\n*（{} 个文件无法解析，使用 --json 查看）*\n", errors.len()));
+    }
+    json!(out.trim_end())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// 构造最小 class 文件 (classpath 模块测试共用)
+    #[derive(Default)]
+    pub(crate) struct ClassBuilder {
+        pool: Vec<u8>,
+        count: u16,
+        fields: Vec<(u16, u16, u16)>,
+        methods: Vec<(u16, u16, u16)>,
+        super_class: Option<String>,
+        interfaces: Vec<String>,
+    }
+
+    impl ClassBuilder {
+        fn push(&mut self, bytes: &[u8], slots: u16) -> u16 {
+            self.pool.extend_from_slice(bytes);
+            self.count += slots;
+            self.count - slots + 1
+        }
+
+        fn utf8(&mut self, s: &str) -> u16 {
+            let mut bytes = vec![1];
+            bytes.extend_from_slice(&(s.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(s.as_bytes());
+            self.push(&bytes, 1)
+        }
+
+        fn class(&mut self, name: &str) -> u16 {
+            let name = self.utf8(name);
+            let [a, b] = name.to_be_bytes();
+            self.push(&[7, a, b], 1)
+        }
+
+        fn method_ref(&mut self, owner: &str, name: &str, descriptor: &str) {
+            let owner = self.class(owner).to_be_bytes();
+            let name = self.utf8(name).to_be_bytes();
+            let descriptor = self.utf8(descriptor).to_be_bytes();
+            let nt = self.push(&[12, name[0], name[1], descriptor[0], descriptor[1]], 1).to_be_bytes();
+            self.push(&[10, owner[0], owner[1], nt[0], nt[1]], 1);
+        }
+
+        fn field(mut self, access: u16, name: &str, descriptor: &str) -> Self {
+            let member = (access, self.utf8(name), self.utf8(descriptor));
+            self.fields.push(member);
+            self
+        }
+
+        pub(crate) fn extends(mut self, super_class: &str) -> Self {
+            self.super_class = Some(super_class.to_string());
+            self
+        }
+
+        pub(crate) fn implements(mut self, interface: &str) -> Self {
+            self.interfaces.push(interface.to_string());
+            self
+        }
+
+        fn method(mut self, access: u16, name: &str) -> Self {
+            let member = (access, self.utf8(name), self.utf8("()V"));
+            self.methods.push(member);
+            self
+        }
+
+        pub(crate) fn build(mut self, name: &str) -> Vec<u8> {
+            // Long 常量占两个槽位
+            self.push(&[5, 0, 0, 0, 0, 0, 0, 0, 42], 2);
+            let this = self.class(name);
+            let super_name = self.super_class.take().unwrap_or_else(|| "java/lang/Object".to_string());
+            let super_class = self.class(&super_name);
+            let interfaces: Vec<u16> = std::mem::take(&mut self.interfaces).iter().map(|i| self.class(i)).collect();
+
+            let mut out = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52];
+            out.extend_from_slice(&(self.count + 1).to_be_bytes());
+            out.extend_from_slice(&self.pool);
+            for value in [0x21, this, super_class, interfaces.len() as u16].into_iter().chain(interfaces) {
+                out.extend_from_slice(&u16::to_be_bytes(value));
+            }
+            for members in [&self.fields, &self.methods] {
+                out.extend_from_slice(&(members.len() as u16).to_be_bytes());
+                for (access, name, descriptor) in members {
+                    for value in [*access, *name, *descriptor, 0] {
+                        out.extend_from_slice(&value.to_be_bytes());
+                    }
+                }
+            }
+            out.extend_from_slice(&[0, 0]);
+            out
+        }
+    }
+
+    fn date_util() -> Vec<u8> {
+        ClassBuilder::default()
+            .field(ACC_STATIC, "FORMAT", "Ljava/text/SimpleDateFormat;")
+            .field(0, "cache", "Ljava/util/Hashtable;")
+            .method(ACC_SYNCHRONIZED, "format")
+            .build("com/acme/DateUtil")
+    }
+
+    fn pool_factory() -> Vec<u8> {
+        let mut builder = ClassBuilder::default();
+        builder.method_ref("java/util/concurrent/Executors", "newCachedThreadPool", "()Ljava/util/concurrent/ExecutorService;");
+        builder.method_ref("java/lang/System", "gc", "()V");
+        builder.field(0, "local", "Ljava/text/SimpleDateFormat;").build("com/acme/Pools")
+    }
+
+    #[test]
+    fn test_parse_class() {
+        let class = parse_class(&date_util()).unwrap();
+        assert_eq!(class.name, "com/acme/DateUtil");
+        assert_eq!(class.fields[0], Member { access: ACC_STATIC, name: "FORMAT".into(), descriptor: "Ljava/text/SimpleDateFormat;".into() });
+        assert_eq!(class.methods[0].name, "format");
+        assert_eq!(class.super_class.as_deref(), Some("java/lang/Object"));
+        assert!(class.interfaces.is_empty());
+
+        let repo = parse_class(&ClassBuilder::default()
+            .implements("org/springframework/data/jpa/repository/JpaRepository")
+            .build("com/acme/data/OrderRepository")).unwrap();
+        assert_eq!(repo.interfaces, vec!["org/springframework/data/jpa/repository/JpaRepository"]);
+
+        assert!(parse_class(b"not a class").is_err());
+        assert!(parse_class(&date_util()[..40]).is_err());
+    }
+
+    #[test]
+    fn test_check_class_rules() {
+        let ids = |bytes: Vec<u8>| -> Vec<&'static str> {
+            check_class(&parse_class(&bytes).unwrap(), "lib.jar").into_iter().map(|a| a.id).collect()
+        };
+        assert_eq!(ids(date_util()), vec!["SHARED_DATE_FORMAT", "LEGACY_SYNC_COLLECTION"]);
+        // 实例字段的 SimpleDateFormat 不报
+        assert_eq!(ids(pool_factory()), vec!["CACHED_THREAD_POOL", "EXPLICIT_GC"]);
+
+        let advisories = check_class(&parse_class(&date_util()).unwrap(), "lib.jar");
+        assert_eq!(advisories[0].class, "com.acme.DateUtil");
+        assert!(advisories[0].detail.contains("synchronized 方法 (format)"), "{}", advisories[0].detail);
+    }
+
+    pub(crate) fn jar(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, bytes) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_jar_scan_nested_war() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = jar(&[("com/acme/DateUtil.class", date_util()), ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n".to_vec())]);
+        let war = jar(&[
+            ("WEB-INF/classes/com/acme/Pools.class", pool_factory()),
+            ("WEB-INF/lib/acme-util.jar", lib),
+            ("WEB-INF/classes/Broken.class", b"\xCA\xFE\xBA\xBE".to_vec()),
+        ]);
+        std::fs::write(dir.path().join("app.war"), war).unwrap();
+
+        let result = jar_scan(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(result["artifacts"], 2);
+        assert_eq!(result["classes"], 2);
+        assert_eq!(result["errors"].as_array().unwrap().len(), 1);
+
+        let found: Vec<(&str, &str)> = result["advisories"].as_array().unwrap().iter()
+            .map(|a| (a["artifact"].as_str().unwrap(), a["id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(found, vec![
+            ("app.war", "CACHED_THREAD_POOL"),
+            ("app.war", "EXPLICIT_GC"),
+            ("app.war!/WEB-INF/lib/acme-util.jar", "LEGACY_SYNC_COLLECTION"),
+            ("app.war!/WEB-INF/lib/acme-util.jar", "SHARED_DATE_FORMAT"),
+        ]);
+
+        let text = render(&result, false);
+        let text = text.as_str().unwrap();
+        assert!(text.contains("### app.war!/WEB-INF/lib/acme-util.jar"), "{text}");
+        assert!(text.contains("1 个文件无法解析"), "{text}");
+    }
+}
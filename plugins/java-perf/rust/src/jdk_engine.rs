@@ -1,8 +1,17 @@
 //! JDK Engine - JDK CLI 工具集成
 //! 
 //! 🔬 法医取证：jstack, javap, jmap
+//!
+//! ⚙️ JVM 参数建议：按扫描发现的问题类型、JDK 版本与容器资源限制给出启动参数 ([`recommend_flags`])，
+//! 渲染为报告的"JVM 调优附录"。建议只覆盖代码问题能推出的方向 (humongous 分配、TLAB、线程栈等)，
+//! 已在 Dockerfile / 部署 YAML 中设置的参数不重复建议。
 
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::env;
 
@@ -208,3 +217,486 @@ pub fn analyze_heap(pid: u32) -> Result<Value, Box<dyn std::error::Error>> {
     
     Ok(json!(report))
 }
+
+// ============================================================================
+// JVM 参数建议
+// ============================================================================
+
+/// 容器资源限制与已设置的 JVM 参数 (k8s manifest / docker-compose / Dockerfile)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContainerLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    /// 资源限制所在文件 (相对路径)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// 已设置的 JVM 参数 (`-Xmx2g`、`-XX:+UseZGC` 等)，建议中不再重复
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub jvm_flags: Vec<String>,
+}
+
+impl ContainerLimits {
+    /// 已设置的参数名 (`-XX:+UseG1GC` → `UseG1GC`，`-Xmx2g` → `-Xmx`)
+    fn has_flag(&self, name: &str) -> bool {
+        self.jvm_flags.iter().any(|flag| flag_name(flag) == name)
+    }
+
+    /// 已设置的最大堆 (MiB)：`-Xmx`，或容器内存 × `MaxRAMPercentage`
+    fn max_heap_mb(&self) -> Option<u64> {
+        let value = |name: &str| self.jvm_flags.iter()
+            .find(|flag| flag_name(flag) == name)
+            .and_then(|flag| flag.split_once('=').map(|(_, v)| v).or_else(|| flag.strip_prefix(name)));
+        value("-Xmx").and_then(parse_memory_mb).or_else(|| {
+            let percent: f64 = value("MaxRAMPercentage")?.parse().ok()?;
+            Some((self.memory_mb? as f64 * percent / 100.0) as u64)
+        })
+    }
+
+    /// 显式指定了 G1 以外的收集器
+    fn non_g1_gc(&self) -> bool {
+        ["UseZGC", "UseShenandoahGC", "UseParallelGC", "UseSerialGC", "UseConcMarkSweepGC", "UseEpsilonGC"]
+            .iter()
+            .any(|gc| self.jvm_flags.iter().any(|flag| flag == &format!("-XX:+{gc}")))
+    }
+}
+
+/// 一条 JVM 参数建议
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlagAdvice {
+    /// 建议的参数 (多个时空格分隔)
+    pub flag: String,
+    pub reason: String,
+    /// 触发建议的问题类型及数量 (仅由容器限制触发时为空)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<(String, usize)>,
+}
+
+/// 报告的 JVM 调优附录
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JvmTuning {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jdk: Option<u32>,
+    pub container: ContainerLimits,
+    pub advice: Vec<FlagAdvice>,
+}
+
+impl JvmTuning {
+    pub fn is_empty(&self) -> bool {
+        self.advice.is_empty()
+    }
+}
+
+/// 生成建议时的环境
+struct TuningContext<'a> {
+    jdk: Option<u32>,
+    limits: &'a ContainerLimits,
+    /// 预计的最大堆 (MiB)：已设置的值，否则按容器内存的 75% (与基线建议一致)
+    heap_mb: Option<u64>,
+    /// 可用核数 (容器 CPU 限制向上取整，否则为本机核数)
+    cpus: usize,
+}
+
+/// 问题类型分组 → 参数建议 (分组内问题总数达到阈值时触发)
+struct Signal {
+    rules: &'static [&'static str],
+    threshold: usize,
+    advise: fn(&TuningContext) -> Option<(String, String)>,
+}
+
+static SIGNALS: &[Signal] = &[
+    // 大对象分配 → humongous 对象
+    Signal {
+        rules: &["LARGE_ARRAY", "FLUX_COLLECT_LIST", "UNBOUNDED_RESULT_LIST", "UNPAGED_LIST_ENDPOINT", "UNBOUNDED_REQUEST_BODY"],
+        threshold: 1,
+        advise: |ctx| {
+            if ctx.limits.non_g1_gc() {
+                return None;
+            }
+            let default = ctx.heap_mb.map(default_region_mb);
+            let suggested = default.map_or(16, |d| (d * 4).clamp(4, 32));
+            let g1 = if ctx.jdk.is_some_and(|v| v < 9) { "-XX:+UseG1GC " } else { "" };
+            let current = default.map_or_else(|| "按堆大小 / 2048 计算".to_string(), |d| format!("当前约 {d}m"));
+            Some((
+                format!("{g1}-XX:G1HeapRegionSize={suggested}m"),
+                format!("大数组与整批加载的集合超过半个 Region ({current}) 时成为 humongous 对象，直接进入老年代并可能触发 Full GC；增大 Region 使其按普通对象分配，GC 日志中 humongous 分配次数应随之下降"),
+            ))
+        },
+    },
+    // 高频短命对象 → TLAB
+    Signal {
+        rules: &["OBJECT_IN_LOOP", "STRING_CONCAT_LOOP", "LOG_STRING_CONCAT", "MANUAL_DTO_COPY", "REACTIVE_IN_LOOP"],
+        threshold: 5,
+        advise: |ctx| {
+            let log = if ctx.jdk.is_some_and(|v| v < 9) { "-XX:+PrintTLAB" } else { "-Xlog:gc+tlab=debug" };
+            Some((
+                "-XX:MinTLABSize=64k".to_string(),
+                format!("循环内大量创建短命对象，分配速率高时 TLAB 频繁耗尽、转入慢速分配路径；提高 TLAB 下限 (仍保留自适应调整)，用 {log} 观察 slow allocs 是否减少"),
+            ))
+        },
+    },
+    Signal {
+        rules: &["STRING_INTERN"],
+        threshold: 1,
+        advise: |ctx| {
+            let buckets = if ctx.jdk.is_some_and(|v| v < 11) { "60013" } else { "65536" };
+            Some((
+                "-XX:StringTableSize=1000003".to_string(),
+                format!("运行期 String.intern() 大量驻留字符串时默认 StringTable ({buckets} 桶) 的冲突链变长；先用 -XX:+PrintStringTableStatistics 确认驻留数量"),
+            ))
+        },
+    },
+    // 线程数不受控 → 线程栈
+    Signal {
+        rules: &["UNBOUNDED_POOL", "ASYNC_DEFAULT_POOL"],
+        threshold: 1,
+        advise: |ctx| {
+            let memory = ctx.limits.memory_mb.map_or_else(String::new, |m| format!("，容器限制 {m} MiB 包含这部分原生内存"));
+            let virtual_threads = if ctx.jdk.is_some_and(|v| v >= 21) { "；JDK 21 可改用虚拟线程" } else { "" };
+            Some((
+                "-Xss512k".to_string(),
+                format!("无界线程池 / 每任务一线程在突发流量下线程数不受控，每个线程栈默认 1m{memory}；确认没有深递归后减小线程栈{virtual_threads}"),
+            ))
+        },
+    },
+    // 堆缓慢增长
+    Signal {
+        rules: &["STATIC_COLLECTION", "CACHE_NO_EXPIRE", "THREADLOCAL_LEAK"],
+        threshold: 1,
+        advise: |ctx| {
+            let in_container = ctx.limits.memory_mb.is_some();
+            let exit = if in_container { " -XX:+ExitOnOutOfMemoryError" } else { "" };
+            let restart = if in_container { "；容器内 OOM 后直接退出由编排重启，避免进程半死不活" } else { "" };
+            Some((
+                format!("-XX:+HeapDumpOnOutOfMemoryError{exit}"),
+                format!("无界静态集合、无过期缓存、未 remove 的 ThreadLocal 会让堆缓慢增长；OOM 时保留堆转储以定位持有者{restart}"),
+            ))
+        },
+    },
+    Signal {
+        rules: &["SOFT_REFERENCE"],
+        threshold: 1,
+        advise: |_| Some((
+            "-XX:SoftRefLRUPolicyMSPerMB=100".to_string(),
+            "软引用按每 MB 空闲堆存活 1 秒 (默认 1000ms) 计算，大堆下软引用缓存长期不回收；调小使其更早释放".to_string(),
+        )),
+    },
+    Signal {
+        rules: &["FINALIZE_OVERRIDE"],
+        threshold: 1,
+        advise: |ctx| ctx.jdk.filter(|v| *v >= 18).map(|_| (
+            "--finalization=disabled".to_string(),
+            "finalize() 已废弃 (JEP 421)，带终结方法的对象至少两轮 GC 才能回收；先在测试环境用此参数验证移除终结后的行为".to_string(),
+        )),
+    },
+    // 锁竞争 → 先用 JFR 确认
+    Signal {
+        rules: &["SYNC_METHOD", "SYNC_BLOCK", "SLEEP_IN_LOCK", "HELD_LOCK_BLOCKING"],
+        threshold: 3,
+        advise: |ctx| ctx.jdk.filter(|v| *v >= 11).map(|_| (
+            "-XX:StartFlightRecording=settings=profile,filename=/tmp/app.jfr".to_string(),
+            "持锁范围较大的代码较多，用 JFR 记录 jdk.JavaMonitorEnter / jdk.ThreadPark 事件，确认实际竞争的锁后再调整粒度".to_string(),
+        )),
+    },
+    Signal {
+        rules: &["PARALLEL_STREAM_BLOCKING"],
+        threshold: 1,
+        advise: |ctx| Some((
+            format!("-Djava.util.concurrent.ForkJoinPool.common.parallelism={}", ctx.cpus * 2),
+            format!("parallelStream 中的阻塞调用会占满公共 ForkJoinPool (默认 {} 个线程)；放大并行度只是临时缓解，根本修复是改用独立线程池", ctx.cpus.saturating_sub(1).max(1)),
+        )),
+    },
+];
+
+/// 按问题类型统计、JDK 版本与容器限制生成 JVM 参数建议
+///
+/// `findings`: 问题类型 → 数量；`cpus`: 未设置容器 CPU 限制时使用的本机核数。已设置的参数不再建议。
+pub fn recommend_flags(findings: &BTreeMap<String, usize>, jdk: Option<u32>, limits: &ContainerLimits, cpus: usize) -> Vec<FlagAdvice> {
+    let mut advice = Vec::new();
+    let explicit_heap = ["-Xmx", "MaxRAMPercentage", "MaxRAM"].iter().any(|name| limits.has_flag(name));
+
+    // 容器基线: 默认最大堆只取容器内存的 1/4
+    if let Some(memory) = limits.memory_mb.filter(|_| !explicit_heap) {
+        let (flag, reason) = if jdk.is_some_and(|v| v < 10) {
+            (format!("-Xmx{}m", memory * 3 / 4), format!("容器内存限制 {memory} MiB；JDK 8u191 之前不识别容器限制，默认堆按宿主机内存计算，容易被 OOMKilled，显式设置为限制的 75%"))
+        } else {
+            ("-XX:MaxRAMPercentage=75.0".to_string(), format!("容器内存限制 {memory} MiB，默认最大堆只取 1/4 ({} MiB)", memory / 4))
+        };
+        advice.push(FlagAdvice { flag, reason, triggers: Vec::new() });
+    }
+    if let Some(limit) = limits.cpus.filter(|c| *c < 2.0) {
+        advice.push(FlagAdvice {
+            flag: "-XX:ActiveProcessorCount=2".to_string(),
+            reason: format!("容器 CPU 限制 {limit} 核，JVM 判定为非服务器级机器而改用 SerialGC，GC 与 JIT 线程也按 1 核设置；CPU 限制为可突发的配额时显式指定处理器数"),
+            triggers: Vec::new(),
+        });
+    }
+
+    let ctx = TuningContext {
+        jdk,
+        limits,
+        heap_mb: limits.max_heap_mb().or_else(|| limits.memory_mb.map(|m| m * 3 / 4)),
+        cpus: limits.cpus.map_or(cpus, |c| c.ceil().max(1.0) as usize),
+    };
+    for signal in SIGNALS {
+        let triggers: Vec<(String, usize)> = signal.rules.iter()
+            .filter_map(|rule| findings.get(*rule).map(|count| (rule.to_string(), *count)))
+            .collect();
+        if triggers.iter().map(|(_, count)| count).sum::<usize>() < signal.threshold {
+            continue;
+        }
+        if let Some((flag, reason)) = (signal.advise)(&ctx) {
+            advice.push(FlagAdvice { flag, reason, triggers });
+        }
+    }
+
+    // 去掉已设置的参数，全部已设置的建议整条省略
+    advice.retain_mut(|a| {
+        let remaining: Vec<&str> = a.flag.split_whitespace().filter(|flag| !limits.has_flag(flag_name(flag))).collect();
+        let remaining = remaining.join(" ");
+        a.flag = remaining;
+        !a.flag.is_empty()
+    });
+    advice
+}
+
+/// 参数名 (`-XX:+UseG1GC` / `-XX:G1HeapRegionSize=16m` → 去掉前缀、开关与取值；`-Xmx2g` → `-Xmx`；`-Dkey=v` → `-Dkey`)
+fn flag_name(flag: &str) -> &str {
+    if let Some(option) = flag.strip_prefix("-XX:") {
+        let option = option.trim_start_matches(['+', '-']);
+        return option.split_once('=').map_or(option, |(name, _)| name);
+    }
+    for sized in ["-Xmx", "-Xms", "-Xss"] {
+        if flag.starts_with(sized) {
+            return sized;
+        }
+    }
+    flag.split_once('=').map_or(flag, |(name, _)| name)
+}
+
+/// G1 默认 Region 大小 (MiB)：堆 / 2048 向下取 2 的幂，限制在 1~32
+fn default_region_mb(heap_mb: u64) -> u64 {
+    let target = (heap_mb / 2048).max(1);
+    (1u64 << (63 - target.leading_zeros())).clamp(1, 32)
+}
+
+/// 内存大小 (`2Gi` / `512Mi` / `1g` / `2048m` / 字节数) → MiB，单位一律按 1024 进制近似
+fn parse_memory_mb(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let mb = match unit.trim().trim_end_matches(['i', 'I', 'b', 'B']).to_ascii_lowercase().as_str() {
+        "" => number / (1024.0 * 1024.0),
+        "k" => number / 1024.0,
+        "m" => number,
+        "g" => number * 1024.0,
+        "t" => number * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    (mb >= 1.0).then_some(mb as u64)
+}
+
+/// CPU 限制 (`500m` / `1.5` / `2`) → 核数
+fn parse_cpus(value: &str) -> Option<f64> {
+    let cpus = match value.trim().strip_suffix('m') {
+        Some(milli) => milli.parse::<f64>().ok()? / 1000.0,
+        None => value.trim().parse().ok()?,
+    };
+    (cpus > 0.0).then_some(cpus)
+}
+
+static RE_JVM_FLAG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:^|[\s"'\[,=])(-XX:[+-]?[A-Za-z0-9]+(?:=[^\s"',\]]+)?|-X(?:mx|ms|ss)[0-9]+[kKmMgG]?)"#).unwrap()
+});
+
+/// YAML 中的容器资源限制: k8s `resources.limits` / compose `deploy.resources.limits` 下的 memory、cpu(s)，
+/// 以及 compose v2 的 `mem_limit` / `cpus`
+fn yaml_limits(content: &str) -> (Option<u64>, Option<f64>) {
+    let (mut memory, mut cpus) = (None, None);
+    let mut limits_indent: Option<usize> = None;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if limits_indent.is_some_and(|limits| indent <= limits) {
+            limits_indent = None;
+        }
+        let Some((key, value)) = trimmed.trim_start_matches("- ").split_once(':') else { continue };
+        let value = value.split(" #").next().unwrap_or("").trim().trim_matches(['"', '\'']);
+        match key.trim() {
+            "limits" => limits_indent = Some(indent),
+            "memory" if limits_indent.is_some() => memory = memory.or_else(|| parse_memory_mb(value)),
+            "cpu" if limits_indent.is_some() => cpus = cpus.or_else(|| parse_cpus(value)),
+            "mem_limit" => memory = memory.or_else(|| parse_memory_mb(value)),
+            "cpus" => cpus = cpus.or_else(|| parse_cpus(value)),
+            _ => {}
+        }
+    }
+    (memory, cpus)
+}
+
+/// 从 Dockerfile 与部署 YAML (k8s manifest / docker-compose，不含 application*.yml) 读取容器限制与已设置的 JVM 参数
+///
+/// 按路径排序，取第一个声明了内存或 CPU 限制的文件；JVM 参数从全部文件收集 (ENTRYPOINT、JAVA_OPTS 等)。
+pub fn read_container_limits(root: &Path, files: &[PathBuf]) -> ContainerLimits {
+    let mut candidates: Vec<&PathBuf> = files.iter()
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name == "Dockerfile" || name.starts_with("Dockerfile.")
+                || (!name.starts_with("application") && !name.starts_with("bootstrap") && (name.ends_with(".yml") || name.ends_with(".yaml")))
+        })
+        .collect();
+    candidates.sort();
+
+    let mut limits = ContainerLimits::default();
+    for path in candidates {
+        let Ok(source) = crate::encoding::read_source(path) else { continue };
+        for capture in RE_JVM_FLAG.captures_iter(&source.content) {
+            let flag = capture[1].to_string();
+            if !limits.jvm_flags.contains(&flag) {
+                limits.jvm_flags.push(flag);
+            }
+        }
+        if limits.source.is_some() || !matches!(path.extension().and_then(|e| e.to_str()), Some("yml" | "yaml")) {
+            continue;
+        }
+        let (memory, cpus) = yaml_limits(&source.content);
+        if memory.is_some() || cpus.is_some() {
+            limits.memory_mb = memory;
+            limits.cpus = cpus;
+            limits.source = Some(crate::ast_engine::relative_path(root, path));
+        }
+    }
+    limits
+}
+
+/// 渲染报告附录 (没有建议时返回空串)
+pub fn render_appendix(tuning: &JvmTuning) -> String {
+    if tuning.is_empty() {
+        return String::new();
+    }
+    let limits = &tuning.container;
+    let mut environment = vec![tuning.jdk.map_or_else(|| "JDK 版本未知".to_string(), |v| format!("JDK {v}"))];
+    if let Some(source) = &limits.source {
+        let mut parts = Vec::new();
+        if let Some(memory) = limits.memory_mb {
+            parts.push(format!("内存 {memory} MiB"));
+        }
+        if let Some(cpus) = limits.cpus {
+            parts.push(format!("CPU {cpus} 核"));
+        }
+        environment.push(format!("容器限制: {} (`{source}`)", parts.join(", ")));
+    }
+    if !limits.jvm_flags.is_empty() {
+        let flags: Vec<String> = limits.jvm_flags.iter().map(|f| format!("`{f}`")).collect();
+        environment.push(format!("已设置: {}", flags.join(" ")));
+    }
+
+    let mut out = format!("\n### ⚙️ 附录: JVM 调优建议\n\n{}\n\n| 参数 | 依据 | 说明 |\n|------|------|------|\n", environment.join(" · "));
+    for a in &tuning.advice {
+        let basis = if a.triggers.is_empty() {
+            "容器限制".to_string()
+        } else {
+            a.triggers.iter().map(|(rule, count)| format!("{rule} ×{count}")).collect::<Vec<_>>().join(", ")
+        };
+        out.push_str(&format!("| `{}` | {basis} | {} |\n", a.flag, a.reason));
+    }
+    out.push_str("\n> 建议值只是起点，需结合 GC 日志与压测结果验证后再写入启动参数。\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings(pairs: &[(&str, usize)]) -> BTreeMap<String, usize> {
+        pairs.iter().map(|(rule, count)| (rule.to_string(), *count)).collect()
+    }
+
+    #[test]
+    fn test_recommend_flags_by_findings_and_container() {
+        let limits = ContainerLimits { memory_mb: Some(4096), cpus: Some(1.5), ..Default::default() };
+        let found = findings(&[("LARGE_ARRAY", 2), ("OBJECT_IN_LOOP", 3), ("STRING_CONCAT_LOOP", 1), ("FINALIZE_OVERRIDE", 1), ("SYNC_METHOD", 1)]);
+        let advice = recommend_flags(&found, Some(21), &limits, 8);
+        let flags: Vec<&str> = advice.iter().map(|a| a.flag.as_str()).collect();
+        // 堆 3072m → 默认 Region 1m，建议 4m；分配类问题不足 5 个不建议 TLAB；锁问题不足 3 个
+        assert_eq!(flags, ["-XX:MaxRAMPercentage=75.0", "-XX:ActiveProcessorCount=2", "-XX:G1HeapRegionSize=4m", "--finalization=disabled"]);
+        assert_eq!(advice[2].triggers, vec![("LARGE_ARRAY".to_string(), 2)]);
+        assert!(advice[0].triggers.is_empty());
+
+        // JDK 8: 显式 -Xmx，G1 需同时开启，不支持 --finalization
+        let advice = recommend_flags(&found, Some(8), &limits, 8);
+        let flags: Vec<&str> = advice.iter().map(|a| a.flag.as_str()).collect();
+        assert_eq!(flags, ["-Xmx3072m", "-XX:ActiveProcessorCount=2", "-XX:+UseG1GC -XX:G1HeapRegionSize=4m"]);
+
+        // 已设置的参数不再建议；显式非 G1 收集器时不建议 Region 大小
+        let configured = ContainerLimits {
+            jvm_flags: vec!["-Xmx8g".to_string(), "-XX:+UseG1GC".to_string(), "-XX:G1HeapRegionSize=8m".to_string()],
+            ..Default::default()
+        };
+        assert!(recommend_flags(&findings(&[("LARGE_ARRAY", 1)]), Some(8), &configured, 8).is_empty());
+        let zgc = ContainerLimits { jvm_flags: vec!["-XX:+UseZGC".to_string()], ..Default::default() };
+        assert!(recommend_flags(&findings(&[("LARGE_ARRAY", 1)]), Some(21), &zgc, 8).is_empty());
+
+        // 无容器限制时按本机核数
+        let advice = recommend_flags(&findings(&[("PARALLEL_STREAM_BLOCKING", 1)]), None, &ContainerLimits::default(), 8);
+        assert_eq!(advice[0].flag, "-Djava.util.concurrent.ForkJoinPool.common.parallelism=16");
+    }
+
+    #[test]
+    fn test_read_container_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let deployment = dir.path().join("k8s/deployment.yaml");
+        std::fs::create_dir_all(deployment.parent().unwrap()).unwrap();
+        std::fs::write(&deployment, r#"
+spec:
+  template:
+    spec:
+      containers:
+        - name: order
+          env:
+            - name: JAVA_TOOL_OPTIONS
+              value: "-XX:+UseG1GC -XX:MaxRAMPercentage=70"
+          resources:
+            requests:
+              memory: "1Gi"
+              cpu: "250m"
+            limits:
+              memory: "2Gi"   # 峰值
+              cpu: "500m"
+"#).unwrap();
+        let dockerfile = dir.path().join("Dockerfile");
+        std::fs::write(&dockerfile, "FROM eclipse-temurin:17-jre\nENTRYPOINT [\"java\", \"-Xss512k\", \"-jar\", \"app.jar\"]\n").unwrap();
+        let application = dir.path().join("application.yml");
+        std::fs::write(&application, "limits:\n  memory: 64Mi\n").unwrap();
+
+        let limits = read_container_limits(dir.path(), &[application, dockerfile, deployment]);
+        assert_eq!(limits.memory_mb, Some(2048));
+        assert_eq!(limits.cpus, Some(0.5));
+        assert_eq!(limits.source.as_deref(), Some("k8s/deployment.yaml"));
+        assert_eq!(limits.jvm_flags, ["-Xss512k", "-XX:+UseG1GC", "-XX:MaxRAMPercentage=70"]);
+        assert_eq!(limits.max_heap_mb(), Some(1433));
+
+        assert_eq!(yaml_limits("services:\n  app:\n    mem_limit: 1g\n    cpus: 1.5\n"), (Some(1024), Some(1.5)));
+        assert_eq!(parse_memory_mb("536870912"), Some(512));
+        assert_eq!(default_region_mb(16384), 8);
+        assert_eq!(default_region_mb(512), 1);
+    }
+
+    #[test]
+    fn test_render_appendix() {
+        let tuning = JvmTuning {
+            jdk: Some(17),
+            container: ContainerLimits { memory_mb: Some(2048), source: Some("k8s/deployment.yaml".to_string()), ..Default::default() },
+            advice: recommend_flags(&findings(&[("STRING_INTERN", 2)]), Some(17), &ContainerLimits::default(), 4),
+        };
+        let appendix = render_appendix(&tuning);
+        assert!(appendix.contains("### ⚙️ 附录: JVM 调优建议\n\nJDK 17 · 容器限制: 内存 2048 MiB (`k8s/deployment.yaml`)"), "{appendix}");
+        assert!(appendix.contains("| `-XX:StringTableSize=1000003` | STRING_INTERN ×2 | "), "{appendix}");
+        assert!(render_appendix(&JvmTuning::default()).is_empty());
+    }
+}
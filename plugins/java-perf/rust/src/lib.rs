@@ -15,3 +15,62 @@ pub mod taint;
 pub mod symbol_table;
 pub mod project_detector;
 pub mod rules;
+pub mod encoding;
+pub mod verify;
+pub mod pool_advisor;
+pub mod static_init;
+pub mod build_hook;
+pub mod hotness;
+pub mod precommit;
+pub mod dedup;
+pub mod symbol_cache;
+pub mod dead_code;
+pub mod inspect;
+pub mod project_config;
+pub mod report;
+pub mod jar_scan;
+pub mod logging;
+pub mod pipeline;
+pub mod workspace;
+pub mod codeowners;
+pub mod analysis;
+pub mod baseline;
+pub mod services;
+pub mod classpath;
+pub mod method_rollup;
+pub mod corpus;
+pub mod explain;
+pub mod entry_scope;
+pub mod agent_config;
+pub mod symbol_shards;
+pub mod index_bench;
+pub mod config_env;
+pub mod blame_age;
+pub mod report_template;
+pub mod source_roots;
+pub mod goldens;
+pub mod category_report;
+pub mod dependency_audit;
+pub mod safe_mode;
+pub mod slo;
+pub mod checkpoint;
+pub mod coverage;
+pub mod diagnostics;
+pub mod daemon;
+pub mod usage_stats;
+pub mod todo_debt;
+pub mod sampling;
+pub mod region;
+pub mod sql_mapping;
+pub mod complexity;
+pub mod shared_path;
+pub mod rule_bench;
+pub mod jakarta_audit;
+pub mod parallelism;
+pub mod git;
+pub mod reachability;
+pub mod density;
+pub mod contention;
+
+pub use analysis::{AnalysisReport, Timing};
+pub use ast_engine::analyze_project;
@@ -0,0 +1,54 @@
+//! 扫描器自身的日志 (只写 stderr，stdout 留给报告)
+//!
+//! - `--log-level`: 级别或 EnvFilter 指令，支持按模块设置，如
+//!   `warn,java_perf::symbol_cache=debug`
+//! - `--log-format json`: 每行一个 JSON 对象，便于日志采集
+//!
+//! 扫描各阶段 (收集文件、索引、分析、后处理) 是 debug 级别的 span，
+//! 关闭时输出耗时 (`time.busy`)，排查慢扫描时使用 `--log-level debug`。
+
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// 日志格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// 人类可读文本
+    #[default]
+    Text,
+    /// JSON Lines
+    Json,
+}
+
+/// 解析 `--log-level` (级别或 EnvFilter 指令)
+pub fn filter(level: &str) -> Result<EnvFilter> {
+    EnvFilter::try_new(level).map_err(|e| anyhow!("无效的 --log-level `{level}`: {e}"))
+}
+
+/// 初始化全局日志 subscriber
+pub fn init(level: &str, format: LogFormat) -> Result<()> {
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter(level)?)
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
+    let result = match format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    };
+    result.map_err(|e| anyhow!("初始化日志失败: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_directives() {
+        assert!(filter("info").is_ok());
+        assert!(filter("warn,java_perf::symbol_cache=debug").is_ok());
+        let err = filter("java_perf=loud").unwrap_err().to_string();
+        assert!(err.contains("java_perf=loud"), "{err}");
+    }
+}
@@ -8,10 +8,65 @@ mod taint;
 mod symbol_table;
 mod project_detector;
 mod rules;
+mod encoding;
+mod verify;
+mod pool_advisor;
+mod static_init;
+mod build_hook;
+mod hotness;
+mod precommit;
+mod dedup;
+mod symbol_cache;
+mod dead_code;
+mod inspect;
+mod project_config;
+mod report;
+mod jar_scan;
+mod logging;
+mod pipeline;
+mod workspace;
+mod codeowners;
+mod analysis;
+mod baseline;
+mod services;
+mod classpath;
+mod method_rollup;
+mod corpus;
+mod explain;
+mod entry_scope;
+mod agent_config;
+mod symbol_shards;
+mod index_bench;
+mod config_env;
+mod blame_age;
+mod report_template;
+mod source_roots;
+mod goldens;
+mod category_report;
+mod dependency_audit;
+mod safe_mode;
+mod slo;
+mod checkpoint;
+mod coverage;
+mod diagnostics;
+mod daemon;
+mod usage_stats;
+mod todo_debt;
+mod sampling;
+mod region;
+mod sql_mapping;
+mod complexity;
+mod shared_path;
+mod rule_bench;
+mod jakarta_audit;
+mod parallelism;
+mod git;
+mod reachability;
+mod density;
+mod contention;
 
-use clap::Parser;
-use tracing::Level;
-use tracing_subscriber::FmtSubscriber;
+use clap::builder::FalseyValueParser;
+use clap::{CommandFactory, Parser};
 use anyhow::Result;
 
 // Re-export Command from cli module
@@ -23,28 +78,37 @@ use cli::Command;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    #[arg(long, default_value = "info")]
+    /// 日志级别或按模块的 EnvFilter 指令 (如 warn,java_perf::symbol_cache=debug)，日志只写 stderr
+    #[arg(long, global = true, env = "JAVAPERF_LOG_LEVEL", default_value = "info")]
     log_level: String,
 
+    /// 日志格式: text | json (JSON Lines)
+    #[arg(long, global = true, env = "JAVAPERF_LOG_FORMAT", value_enum, default_value = "text")]
+    log_format: logging::LogFormat,
+
     /// 输出 JSON 格式 (默认输出人类可读的 Markdown)
-    #[arg(long, global = true)]
+    #[arg(long, global = true, env = "JAVAPERF_JSON", value_parser = FalseyValueParser::new())]
     json: bool,
 
+    /// 覆盖选项 (可重复)，如 -D max_p1=20 -D severity_map.P0=Blocker；对应环境变量 JAVAPERF_MAX_P1 等，
+    /// 优先级: 命令行参数 > -D > JAVAPERF_* 环境变量 > .javaperf.toml
+    #[arg(short = 'D', global = true, value_name = "KEY=VALUE")]
+    define: Vec<String>,
+
     #[command(subcommand)]
     command: Command,
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    // -D 在解析前写入 JAVAPERF_* 环境变量，由 clap 与 project_config 读取
+    let argv: Vec<std::ffi::OsString> = std::env::args_os().collect();
+    let defines = config_env::defines(&argv).map_err(anyhow::Error::msg)?;
+    config_env::apply(&defines, &config_env::known_env(&Args::command())).map_err(anyhow::Error::msg)?;
+    let args = Args::parse_from(argv);
 
     // 初始化日志
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .with_writer(std::io::stderr)
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("setting default subscriber failed");
+    logging::init(&args.log_level, args.log_format)?;
+    tracing::debug!(defines = ?args.define, "config overrides");
 
     cli::handle_command(args.command, args.json)
 }
@@ -0,0 +1,170 @@
+//! 按方法汇总问题 (最差方法排行)
+//!
+//! 问题按所在方法 (祖先节点回溯得到的方法全限定名，见 `hotness::collect_spans`)
+//! 聚合，按加权严重度排序。修复通常按方法分配，排行比逐条问题列表更接近
+//! 技术负责人的工作方式。
+//!
+//! | 级别 | 权重 |
+//! |------|------|
+//! | P0 | [`P0_WEIGHT`] |
+//! | P1 | [`P1_WEIGHT`] |
+//!
+//! 同分时热度高 (离请求入口近) 的方法排在前面。
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::hotness::{self, MethodSpan};
+
+pub const P0_WEIGHT: usize = 10;
+pub const P1_WEIGHT: usize = 3;
+
+/// 报告中展示的方法数
+pub const TOP_METHODS: usize = 20;
+
+/// 单个方法的问题汇总
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MethodSummary {
+    /// 方法全限定名
+    pub method: String,
+    /// 所在文件 (相对路径)
+    pub path: String,
+    /// 方法内第一个问题的行号
+    pub line: usize,
+    pub p0: usize,
+    pub p1: usize,
+    /// 加权严重度
+    pub score: usize,
+    /// 方法内问题的最高热度
+    pub hotness: u8,
+    /// 规则 ID → 问题数
+    pub rules: BTreeMap<String, usize>,
+}
+
+/// 为问题填充所在方法 (取问题行所在的最内层方法)
+pub fn annotate(issues: &mut [AstIssue], spans: &HashMap<String, Vec<MethodSpan>>) {
+    for issue in issues.iter_mut() {
+        if let Some(span) = spans.get(issue.path.as_str()).and_then(|file_spans| hotness::innermost(file_spans, issue.line)) {
+            issue.method = Some(span.fqn.clone());
+        }
+    }
+}
+
+/// 按方法汇总，加权严重度降序，最多 `limit` 个
+pub fn worst_methods(issues: &[AstIssue], limit: usize) -> Vec<MethodSummary> {
+    let mut groups: BTreeMap<(&str, &str), MethodSummary> = BTreeMap::new();
+    for issue in issues {
+        let Some(method) = issue.method.as_deref() else {
+            continue;
+        };
+        let summary = groups.entry((issue.path.as_str(), method)).or_insert_with(|| MethodSummary {
+            method: method.to_string(),
+            path: issue.path.to_string(),
+            line: issue.line,
+            p0: 0,
+            p1: 0,
+            score: 0,
+            hotness: 0,
+            rules: BTreeMap::new(),
+        });
+        match issue.severity {
+            Severity::P0 => {
+                summary.p0 += 1;
+                summary.score += P0_WEIGHT;
+            }
+            Severity::P1 => {
+                summary.p1 += 1;
+                summary.score += P1_WEIGHT;
+            }
+        }
+        summary.line = summary.line.min(issue.line);
+        summary.hotness = summary.hotness.max(issue.hotness);
+        *summary.rules.entry(issue.issue_type.clone()).or_default() += 1;
+    }
+    let mut summaries: Vec<MethodSummary> = groups.into_values().collect();
+    // 稳定排序：同分同热度保持路径顺序
+    summaries.sort_by_key(|s| std::cmp::Reverse((s.score, s.hotness)));
+    summaries.truncate(limit);
+    summaries
+}
+
+/// Markdown 报告中的最差方法段落
+pub fn render_section(summaries: &[MethodSummary], p0_label: &str, p1_label: &str) -> String {
+    if summaries.is_empty() {
+        return String::new();
+    }
+    let mut section = format!("\n### 🎯 问题最集中的方法 (Top {})\n\n", summaries.len());
+    for (rank, summary) in summaries.iter().enumerate() {
+        let rules: Vec<String> = summary.rules.iter().map(|(id, n)| format!("{id} × {n}")).collect();
+        section.push_str(&format!(
+            "{}. **{}** - `{}:{}` - 分数 {} ({p0_label}: {}, {p1_label}: {}; {})\n",
+            rank + 1, summary.method, summary.path, summary.line, summary.score, summary.p0, summary.p1, rules.join(", ")
+        ));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, severity: Severity, path: &str, line: usize) -> AstIssue {
+        serde_json::from_value(serde_json::json!({
+            "severity": severity, "id": id, "file": "", "path": path, "line": line, "description": ""
+        })).unwrap()
+    }
+
+    fn span(fqn: &str, start_line: usize, end_line: usize) -> MethodSpan {
+        let mut parts = fqn.rsplit('.');
+        let method = parts.next().unwrap().to_string();
+        let class = parts.next().unwrap().to_string();
+        MethodSpan { class, method, fqn: fqn.to_string(), start_line, end_line }
+    }
+
+    #[test]
+    fn test_annotate_uses_innermost_method() {
+        let spans = HashMap::from([("A.java".to_string(), vec![
+            span("com.shop.A.outer", 1, 20),
+            span("com.shop.A.Inner.run", 5, 8),
+        ])]);
+        let mut issues = vec![
+            issue("N_PLUS_ONE", Severity::P0, "A.java", 6),
+            issue("EMPTY_CATCH", Severity::P1, "A.java", 12),
+            issue("EMPTY_CATCH", Severity::P1, "A.java", 30),
+            issue("EMPTY_CATCH", Severity::P1, "B.java", 6),
+        ];
+        annotate(&mut issues, &spans);
+        let methods: Vec<Option<&str>> = issues.iter().map(|i| i.method.as_deref()).collect();
+        assert_eq!(methods, vec![Some("com.shop.A.Inner.run"), Some("com.shop.A.outer"), None, None]);
+    }
+
+    #[test]
+    fn test_worst_methods_ranked_by_weighted_severity() {
+        let mut issues = Vec::new();
+        let mut push = |id: &str, severity: Severity, path: &str, line: usize, method: &str| {
+            let mut i = issue(id, severity, path, line);
+            i.method = Some(method.to_string());
+            issues.push(i);
+        };
+        // 2×P0 = 20 > 4×P1 = 12 > 1×P1 = 3；不在方法内的问题不参与汇总
+        for line in 10..14 {
+            push("EMPTY_CATCH", Severity::P1, "Report.java", line, "Report.build");
+        }
+        push("N_PLUS_ONE", Severity::P0, "Order.java", 7, "Order.load");
+        push("N_PLUS_ONE", Severity::P0, "Order.java", 5, "Order.load");
+        push("SYNC_METHOD", Severity::P1, "Order.java", 20, "Order.save");
+        issues.push(issue("EMPTY_CATCH", Severity::P0, "Order.java", 1));
+
+        let top = worst_methods(&issues, TOP_METHODS);
+        let keys: Vec<(&str, usize)> = top.iter().map(|s| (s.method.as_str(), s.score)).collect();
+        assert_eq!(keys, vec![("Order.load", 20), ("Report.build", 12), ("Order.save", 3)]);
+        assert_eq!((top[0].p0, top[0].line), (2, 5));
+        assert_eq!(top[0].rules.get("N_PLUS_ONE"), Some(&2));
+        assert_eq!(worst_methods(&issues, 1).len(), 1);
+
+        let section = render_section(&top, "P0", "P1");
+        assert!(section.contains("1. **Order.load** - `Order.java:5` - 分数 20"), "{section}");
+    }
+}
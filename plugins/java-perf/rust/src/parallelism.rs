@@ -0,0 +1,232 @@
+//! 扫描并行度 (`scan --jobs N`)
+//!
+//! Phase 1 索引使用 rayon 全局线程池，Phase 2 流水线使用同样数量的工作线程。未指定 `--jobs` 时:
+//!
+//! - 以进程可用的核数为上限 (`available_parallelism`，已计入 CPU 亲和性，`taskset`/`numactl`
+//!   绑定到部分核或 NUMA 节点时只计这些核)
+//! - 再按 cgroup CPU 配额封顶 (v2 `cpu.max`，v1 `cpu.cfs_quota_us` / `cpu.cfs_period_us`)，
+//!   CI 容器限制为 2 核而宿主机有 64 核时只启动 2 个工作线程，避免配额耗尽后整组线程被限流
+//!
+//! 配额按进程自己所在的 cgroup 读取: 从 `/proc/self/cgroup` 得到路径，在 `/sys/fs/cgroup` 下
+//! 从该 cgroup 逐级向上直到挂载根，取各级配额的最小值 (systemd slice、k8s pod 等嵌套 cgroup
+//! 的上级配额同样生效)。容器内未启用 cgroup 命名空间时该路径在挂载点下不存在，逐级向上
+//! 会落到挂载根，即容器自身的 cgroup。
+//!
+//! 实际使用的并行度写入报告 (JSON `stats.parallelism`) 与扫描统计；线程池建议与 JVM 参数建议
+//! 使用同样按配额封顶的核数 ([`Parallelism::cpus`])，不受 `--jobs` 影响。
+
+use std::path::Path;
+
+use serde::Serialize;
+
+/// cgroup 文件系统挂载点
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// 进程所在的 cgroup
+const SELF_CGROUP: &str = "/proc/self/cgroup";
+
+/// 并行度来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobsSource {
+    /// `--jobs` / `JAVAPERF_JOBS`
+    Flag,
+    /// cgroup CPU 配额
+    Cgroup,
+    /// 进程可用的核数
+    Host,
+}
+
+/// 本次扫描的并行度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Parallelism {
+    /// 工作线程数
+    pub jobs: usize,
+    pub source: JobsSource,
+    /// 进程可用的核数 (已计入 CPU 亲和性)
+    pub host_cpus: usize,
+    /// cgroup CPU 配额 (核，未限制时为 None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgroup_cpus: Option<f64>,
+    /// rayon 全局线程池的线程数 (同一进程中先前已初始化时可能与 jobs 不同)
+    pub rayon_threads: usize,
+}
+
+impl Parallelism {
+    /// 按 `--jobs`、cgroup 配额与可用核数确定并行度
+    pub fn detect(requested: Option<usize>) -> Self {
+        Self::resolve(requested, host_cpus(), self_cgroup_cpu_limit())
+    }
+
+    /// 按 cgroup 配额封顶的可用核数 (不受 `--jobs` 影响)
+    pub fn cpus(&self) -> usize {
+        cap_cpus(self.host_cpus, self.cgroup_cpus)
+    }
+
+    fn resolve(requested: Option<usize>, host_cpus: usize, cgroup_cpus: Option<f64>) -> Self {
+        let quota = cgroup_cpus.map(|cpus| (cpus.ceil() as usize).max(1));
+        let (jobs, source) = match (requested, quota) {
+            (Some(jobs), _) => (jobs.max(1), JobsSource::Flag),
+            (None, Some(quota)) if quota < host_cpus => (quota, JobsSource::Cgroup),
+            (None, _) => (host_cpus.max(1), JobsSource::Host),
+        };
+        Self { jobs, source, host_cpus, cgroup_cpus, rayon_threads: jobs }
+    }
+
+    /// 按 jobs 初始化 rayon 全局线程池 (进程内只能初始化一次，已初始化时保持原线程数)
+    pub fn install(mut self) -> Self {
+        if let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(self.jobs).build_global() {
+            tracing::debug!(error = %e, "rayon global pool already initialized");
+        }
+        self.rayon_threads = rayon::current_num_threads();
+        self
+    }
+}
+
+/// 按 cgroup 配额封顶的可用核数 (未持有 [`Parallelism`] 时使用)
+pub fn available_cpus() -> usize {
+    cap_cpus(host_cpus(), self_cgroup_cpu_limit())
+}
+
+fn cap_cpus(host_cpus: usize, cgroup_cpus: Option<f64>) -> usize {
+    let quota = cgroup_cpus.map_or(usize::MAX, |cpus| (cpus.ceil() as usize).max(1));
+    host_cpus.min(quota).max(1)
+}
+
+fn host_cpus() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+}
+
+fn self_cgroup_cpu_limit() -> Option<f64> {
+    let self_cgroup = std::fs::read_to_string(SELF_CGROUP).unwrap_or_default();
+    cgroup_cpu_limit(Path::new(CGROUP_ROOT), &self_cgroup)
+}
+
+/// 进程所在 cgroup 的 CPU 配额 (核)；未限制或读取失败时为 None
+///
+/// `self_cgroup` 为 `/proc/self/cgroup` 的内容: v2 为 `0::<path>`，v1 为
+/// `<id>:<controllers>:<path>` (controllers 含 `cpu` 的那一行)。读取不到时按根 cgroup 处理。
+fn cgroup_cpu_limit(root: &Path, self_cgroup: &str) -> Option<f64> {
+    let mut unified = "";
+    let mut v1: Vec<(&str, &str)> = Vec::new();
+    for line in self_cgroup.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (Some(id), Some(controllers), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        if id == "0" && controllers.is_empty() {
+            unified = path;
+        } else if controllers.split(',').any(|c| c == "cpu") {
+            v1.push((controllers, path));
+        }
+    }
+    if let Some(limit) = min_along(root, unified, |dir| {
+        std::fs::read_to_string(dir.join("cpu.max")).ok().map(|max| parse_cpu_max(&max))
+    }) {
+        return limit;
+    }
+    if v1.is_empty() {
+        v1 = vec![("cpu", ""), ("cpu,cpuacct", "")];
+    }
+    v1.iter().find_map(|(controllers, path)| {
+        // 挂载目录通常与 controllers 同名，部分发行版只有 cpu 或 cpu,cpuacct 链接
+        let mount = [*controllers, "cpu", "cpu,cpuacct"].into_iter()
+            .map(|dir| root.join(dir))
+            .find(|dir| dir.is_dir())?;
+        min_along(&mount, path, |dir| {
+            let quota = std::fs::read_to_string(dir.join("cpu.cfs_quota_us")).ok()?;
+            let period = std::fs::read_to_string(dir.join("cpu.cfs_period_us")).ok()?;
+            Some(quota_cpus(quota.trim().parse().ok()?, period.trim().parse().ok()?))
+        })
+    }).flatten()
+}
+
+/// 从 `mount/path` 逐级向上到 `mount`，取各级配额的最小值
+///
+/// `read` 在该级没有配额文件时返回 None；整条路径都没有配额文件时返回 None，
+/// 有文件但都不限制时返回 `Some(None)`。`path` 中的 `..` (进程在 cgroup 命名空间之外) 按挂载根处理。
+fn min_along(mount: &Path, path: &str, read: impl Fn(&Path) -> Option<Option<f64>>) -> Option<Option<f64>> {
+    let relative = Path::new(path.trim_start_matches('/'));
+    let relative = if path.split('/').any(|c| c == "..") { Path::new("") } else { relative };
+    let limits: Vec<Option<f64>> = relative.ancestors().filter_map(|level| read(&mount.join(level))).collect();
+    if limits.is_empty() {
+        return None;
+    }
+    Some(limits.into_iter().flatten().reduce(f64::min))
+}
+
+/// cgroup v2 `cpu.max`: `<quota> <period>`，quota 为 `max` 表示不限制
+fn parse_cpu_max(content: &str) -> Option<f64> {
+    let mut fields = content.split_whitespace();
+    let quota = fields.next()?;
+    let period = fields.next().map_or(Some(100_000), |p| p.parse().ok())?;
+    quota_cpus(quota.parse().ok()?, period)
+}
+
+/// quota / period，quota 为负 (v1 的 -1) 或 period 为 0 时视为不限制
+fn quota_cpus(quota: i64, period: i64) -> Option<f64> {
+    (quota > 0 && period > 0).then(|| quota as f64 / period as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cgroup_quota_caps_default_jobs() {
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("150000 100000\n"), Some(1.5));
+        assert_eq!(quota_cpus(-1, 100_000), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(cgroup_cpu_limit(dir.path(), ""), None);
+        std::fs::create_dir_all(dir.path().join("cpu,cpuacct")).unwrap();
+        std::fs::write(dir.path().join("cpu,cpuacct/cpu.cfs_quota_us"), "200000\n").unwrap();
+        std::fs::write(dir.path().join("cpu,cpuacct/cpu.cfs_period_us"), "100000\n").unwrap();
+        assert_eq!(cgroup_cpu_limit(dir.path(), ""), Some(2.0));
+        std::fs::write(dir.path().join("cpu.max"), "max 100000\n").unwrap();
+        assert_eq!(cgroup_cpu_limit(dir.path(), ""), None);
+
+        let capped = Parallelism::resolve(None, 64, Some(1.5));
+        assert_eq!((capped.jobs, capped.source), (2, JobsSource::Cgroup));
+        let host = Parallelism::resolve(None, 8, Some(16.0));
+        assert_eq!((host.jobs, host.source), (8, JobsSource::Host));
+        let flag = Parallelism::resolve(Some(3), 64, Some(1.5));
+        assert_eq!((flag.jobs, flag.source), (3, JobsSource::Flag));
+        assert_eq!(flag.cpus(), 2);
+        assert_eq!(host.cpus(), 8);
+    }
+
+    #[test]
+    fn test_cgroup_quota_from_self_cgroup() {
+        let write = |dir: &Path, file: &str, content: &str| {
+            std::fs::create_dir_all(dir).unwrap();
+            std::fs::write(dir.join(file), content).unwrap();
+        };
+
+        // v2: 读取进程自己的 cgroup，上级 slice 的更严配额同样生效
+        let v2 = tempfile::tempdir().unwrap();
+        let pod = v2.path().join("kubepods.slice/pod1");
+        write(v2.path(), "cpu.max", "max 100000\n");
+        write(&pod, "cpu.max", "400000 100000\n");
+        write(&pod.join("app"), "cpu.max", "max 100000\n");
+        write(&v2.path().join("other"), "cpu.max", "50000 100000\n");
+        assert_eq!(cgroup_cpu_limit(v2.path(), "0::/kubepods.slice/pod1/app\n"), Some(4.0));
+        write(v2.path(), "kubepods.slice/cpu.max", "300000 100000\n");
+        assert_eq!(cgroup_cpu_limit(v2.path(), "0::/kubepods.slice/pod1/app\n"), Some(3.0));
+        // 容器内无 cgroup 命名空间: 宿主机路径在挂载点下不存在，落到挂载根
+        assert_eq!(cgroup_cpu_limit(v2.path(), "0::/docker/abc\n"), None);
+        assert_eq!(cgroup_cpu_limit(v2.path(), "0::/../../other\n"), None);
+
+        // v1: 按 controllers 找到 cpu 层级的挂载点
+        let v1 = tempfile::tempdir().unwrap();
+        let mount = v1.path().join("cpu,cpuacct");
+        write(&mount.join("ci/job"), "cpu.cfs_quota_us", "150000\n");
+        write(&mount.join("ci/job"), "cpu.cfs_period_us", "100000\n");
+        write(&mount, "cpu.cfs_quota_us", "-1\n");
+        write(&mount, "cpu.cfs_period_us", "100000\n");
+        let self_cgroup = "12:memory:/ci/job\n4:cpu,cpuacct:/ci/job\n0::/ci/job\n";
+        assert_eq!(cgroup_cpu_limit(v1.path(), self_cgroup), Some(1.5));
+        assert_eq!(cgroup_cpu_limit(v1.path(), "4:cpu,cpuacct:/\n"), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! 有界扫描流水线 (Phase 2)
+//!
+//! ```text
+//! IO 线程 (读取/转码) ──有界通道──▶ 工作线程 (解析/分析) ──有界通道──▶ 写入者 (聚合)
+//! ```
+//!
+//! 两级通道都有容量上限：分析跟不上时 IO 线程阻塞在发送上 (背压)，
+//! 同一时刻驻留内存的源文件数不超过 `通道容量 + 线程数`，与仓库规模无关。
+//! 聚合只在调用线程中进行，不需要锁。
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crossbeam_channel::bounded;
+
+/// 流水线并发参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineOptions {
+    /// 读取文件的线程数
+    pub io_threads: usize,
+    /// 解析/分析的线程数
+    pub workers: usize,
+    /// 每级通道的容量 (已读取未分析的文件数、已分析未聚合的结果数)
+    pub capacity: usize,
+}
+
+impl Default for PipelineOptions {
+    fn default() -> Self {
+        Self::with_workers(crate::parallelism::available_cpus())
+    }
+}
+
+impl PipelineOptions {
+    /// 按工作线程数确定 IO 线程数与通道容量 (见 parallelism 模块)
+    pub fn with_workers(workers: usize) -> Self {
+        let workers = workers.max(1);
+        Self {
+            io_threads: workers.clamp(1, 4),
+            workers,
+            capacity: workers * 2,
+        }
+    }
+}
+
+/// 运行流水线
+///
+/// - `read`: 在 IO 线程中执行，返回 None 表示跳过该文件
+/// - `analyze`: 在工作线程中执行
+/// - `write`: 在调用线程中按完成顺序执行 (顺序不确定，需要稳定输出时由调用方排序)
+pub fn run<S, R>(
+    paths: &[PathBuf],
+    options: PipelineOptions,
+    read: impl Fn(&Path) -> Option<S> + Sync,
+    analyze: impl Fn(&Path, S) -> R + Sync,
+    mut write: impl FnMut(R),
+) where
+    S: Send,
+    R: Send,
+{
+    let capacity = options.capacity.max(1);
+    let (source_tx, source_rx) = bounded::<(&Path, S)>(capacity);
+    let (result_tx, result_rx) = bounded::<R>(capacity);
+    let next = AtomicUsize::new(0);
+    let (read, analyze, next) = (&read, &analyze, &next);
+
+    std::thread::scope(|scope| {
+        for _ in 0..options.io_threads.max(1) {
+            let source_tx = source_tx.clone();
+            scope.spawn(move || {
+                while let Some(path) = paths.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let Some(source) = read(path) else { continue };
+                    // 接收端全部退出 (工作线程 panic) 时停止读取
+                    if source_tx.send((path.as_path(), source)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        for _ in 0..options.workers.max(1) {
+            let (source_rx, result_tx) = (source_rx.clone(), result_tx.clone());
+            scope.spawn(move || {
+                for (path, source) in source_rx {
+                    if result_tx.send(analyze(path, source)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        // 只保留各线程持有的端点，全部线程结束后 result_rx 迭代终止
+        drop((source_tx, source_rx, result_tx));
+
+        for result in result_rx {
+            write(result);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_processes_every_readable_path() {
+        let paths: Vec<PathBuf> = (0..200).map(|i| PathBuf::from(format!("F{i}.java"))).collect();
+        let options = PipelineOptions { io_threads: 3, workers: 4, capacity: 2 };
+        let mut seen = Vec::new();
+        run(
+            &paths,
+            options,
+            // 跳过编号为 7 的倍数的文件
+            |path| {
+                let n: usize = path.to_str()?.trim_start_matches('F').trim_end_matches(".java").parse().ok()?;
+                (!n.is_multiple_of(7)).then_some(n)
+            },
+            |_, n| n * 2,
+            |r| seen.push(r),
+        );
+        seen.sort();
+        let expected: Vec<usize> = (0..200).filter(|n: &usize| !n.is_multiple_of(7)).map(|n| n * 2).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_pipeline_bounds_in_flight_sources() {
+        let paths: Vec<PathBuf> = (0..100).map(|i| PathBuf::from(i.to_string())).collect();
+        let options = PipelineOptions { io_threads: 2, workers: 1, capacity: 1 };
+        // 已读取但尚未聚合的文件数
+        let in_flight = AtomicUsize::new(0);
+        let mut peak = 0;
+        run(
+            &paths,
+            options,
+            |_| {
+                in_flight.fetch_add(1, Ordering::SeqCst);
+                Some(())
+            },
+            |_, ()| (),
+            |()| {
+                peak = peak.max(in_flight.load(Ordering::SeqCst));
+                std::thread::sleep(std::time::Duration::from_micros(200));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            },
+        );
+        // 通道 2 × 1 + 读取线程 2 + 工作线程 1 + 写入者 1
+        assert!(peak <= 6, "peak in-flight {peak}");
+    }
+}
@@ -0,0 +1,1018 @@
+//! 线程池容量建议 (Thread Pool Sizing Advisor)
+//!
+//! 结合三类信息给出线程池大小建议:
+//! 1. **代码**: `new ThreadPoolExecutor(..)`、`Executors.newFixedThreadPool(n)`、
+//!    Spring `ThreadPoolTaskExecutor` 的 setter 参数
+//! 2. **配置**: `spring.task.execution.pool.*` (`@Async` 默认线程池) 与
+//!    `spring.datasource.hikari.maximum-pool-size`
+//! 3. **负载类型**: 提交到线程池的任务 (`submit`/`execute`/`supplyAsync(.., pool)`/`@Async`)
+//!    中是否存在阻塞调用 (DAO/RPC/JDBC/文件 IO/sleep)，区分 IO 密集与 CPU 密集
+//!
+//! 公式 (《Java 并发编程实战》8.2):
+//! - CPU 密集: `N_threads = N_cpu + 1`
+//! - IO 密集:  `N_threads = N_cpu × (1 + W/C)`，W/C 为等待时间与计算时间之比
+//!
+//! 任务与线程池按名称关联 (字段名 / 局部变量名 / `@Bean` 方法名)，同文件优先。
+//! 这是启发式匹配，建议值需结合压测数据修正。
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::scanner::const_eval;
+use crate::scanner::node_walk::{ancestors, NodeWalk, METHOD_DECLARATIONS};
+
+/// Spring Boot `@Async` 默认线程池的 Bean 名
+const DEFAULT_ASYNC_EXECUTOR: &str = "applicationTaskExecutor";
+/// `CompletableFuture.*Async` 不传线程池时使用的公共池
+const COMMON_POOL: &str = "ForkJoinPool.commonPool";
+/// IO 密集任务默认假设的 W/C (90% 时间在等待 IO)
+const ASSUMED_WAIT_COMPUTE_RATIO: usize = 9;
+/// `Integer.MAX_VALUE`
+const INT_MAX: u32 = i32::MAX as u32;
+
+/// 线程数参数 (常量可求值时 value 有值，否则保留表达式原文)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolSize {
+    pub value: Option<u32>,
+    pub expr: String,
+}
+
+impl PoolSize {
+    fn fixed(value: u32) -> Self {
+        let expr = if value == INT_MAX { "Integer.MAX_VALUE".to_string() } else { value.to_string() };
+        Self { value: Some(value), expr }
+    }
+
+    fn from_node(node: Node, code: &str) -> Self {
+        Self {
+            value: eval_int(node, code),
+            expr: node.utf8_text(code.as_bytes()).unwrap_or("").to_string(),
+        }
+    }
+}
+
+/// 工作队列类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueKind {
+    /// LinkedBlockingQueue() / DelayedWorkQueue / 容量 Integer.MAX_VALUE
+    Unbounded,
+    Bounded(u32),
+    /// SynchronousQueue (不排队，直接创建线程)
+    Synchronous,
+    Unknown,
+}
+
+/// 代码或配置中声明的线程池
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolSite {
+    /// 关联名: @Bean 名 / 字段名 / 变量名
+    pub name: String,
+    /// 构造方式，如 "ThreadPoolExecutor"、"Executors.newFixedThreadPool"
+    pub kind: String,
+    pub core: PoolSize,
+    pub max: PoolSize,
+    pub queue: QueueKind,
+    pub path: String,
+    pub line: usize,
+}
+
+impl PoolSite {
+    /// 稳态工作线程数: 无界队列下 max 永不生效，取 core
+    fn effective_threads(&self) -> Option<u32> {
+        match self.queue {
+            QueueKind::Unbounded => self.core.value,
+            _ => self.max.value.or(self.core.value),
+        }
+    }
+}
+
+/// 提交到线程池的任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskUsage {
+    /// 目标线程池名
+    pub executor: String,
+    pub path: String,
+    pub line: usize,
+    /// 任务体中的阻塞调用 (如 "orderRepository.findById")
+    pub blocking: Vec<String>,
+    /// 阻塞调用中是否包含数据库访问 (受连接池大小约束)
+    pub uses_db: bool,
+}
+
+/// 单文件收集结果
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FileUsage {
+    pub pools: Vec<PoolSite>,
+    pub tasks: Vec<TaskUsage>,
+}
+
+/// application.yml/properties 中与线程池相关的配置
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExecutorConfig {
+    pub core_size: Option<u32>,
+    pub max_size: Option<u32>,
+    pub queue_capacity: Option<u32>,
+    pub hikari_max: Option<u32>,
+    /// 定义 spring.task.execution.pool 的配置文件 (相对路径)
+    pub source: Option<String>,
+}
+
+/// 负载类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Workload {
+    IoBound,
+    CpuBound,
+    /// 未找到提交到该线程池的任务
+    Unknown,
+}
+
+/// 单个线程池的容量建议
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolAdvice {
+    pub pool: PoolSite,
+    pub workload: Workload,
+    pub task_count: usize,
+    pub blocking_calls: Vec<String>,
+    pub recommendation: String,
+    pub formula: String,
+    pub warnings: Vec<String>,
+}
+
+// ============================================================================
+// 代码收集
+// ============================================================================
+
+/// 快速预筛: 不含线程池相关关键字的文件无需再次解析
+pub fn is_candidate(code: &str) -> bool {
+    ["Executor", "ThreadPool", "Async", "executor", "pool"].iter().any(|k| code.contains(k))
+}
+
+/// 从已解析的 Java 语法树中收集线程池声明与任务提交
+///
+/// `is_dao_var` 用于借助 SymbolTable 判断接收者是否为 DAO 字段。
+pub fn collect(root: Node, code: &str, rel_path: &str, is_dao_var: &dyn Fn(&str) -> bool) -> FileUsage {
+    let mut usage = FileUsage::default();
+    let methods = method_bodies(root, code);
+    let ctx = TaskContext { code, methods: &methods, is_dao_var };
+
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            "object_creation_expression" => {
+                if let Some(site) = pool_from_creation(node, code, rel_path) {
+                    usage.pools.push(site);
+                }
+            }
+            "method_invocation" => {
+                if let Some(site) = pool_from_factory(node, code, rel_path) {
+                    usage.pools.push(site);
+                } else if let Some(task) = task_from_invocation(node, &ctx, rel_path) {
+                    usage.tasks.push(task);
+                }
+            }
+            "method_declaration" => {
+                if let Some(task) = task_from_async_method(node, &ctx, rel_path) {
+                    usage.tasks.push(task);
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    usage
+}
+
+fn text<'a>(node: Node, code: &'a str) -> &'a str {
+    node.utf8_text(code.as_bytes()).unwrap_or("")
+}
+
+fn args(invocation: Node<'_>) -> Vec<Node<'_>> {
+    invocation.child_by_field_name("arguments")
+        .map(|list| {
+            let mut cursor = list.walk();
+            list.named_children(&mut cursor).collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 求值整数参数: 字面量、`Integer.MAX_VALUE` 与文件内可折叠的常量表达式 (见 const_eval)
+fn eval_int(node: Node, code: &str) -> Option<u32> {
+    u32::try_from(const_eval::eval_int(node, code)?.value).ok()
+}
+
+/// 类型名 (去掉泛型参数与包名)
+fn simple_type_name(node: Node, code: &str) -> String {
+    let name = text(node, code);
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit('.').next().unwrap_or(name).trim().to_string()
+}
+
+/// 方法上的注解 (名称, 第一个字符串参数)
+fn method_annotations(method: Node, code: &str) -> Vec<(String, Option<String>)> {
+    let mut result = Vec::new();
+    let mut cursor = method.walk();
+    let Some(modifiers) = method.children(&mut cursor).find(|c| c.kind() == "modifiers") else {
+        return result;
+    };
+    let mut cursor = modifiers.walk();
+    for ann in modifiers.children(&mut cursor) {
+        if !matches!(ann.kind(), "annotation" | "marker_annotation") {
+            continue;
+        }
+        let Some(name) = ann.child_by_field_name("name") else { continue };
+        let value = ann.child_by_field_name("arguments").and_then(|list| first_string(list, code));
+        result.push((text(name, code).to_string(), value));
+    }
+    result
+}
+
+/// 注解参数中的第一个字符串字面量 (`@Bean("x")` / `@Bean(name = "x")`)
+fn first_string(node: Node, code: &str) -> Option<String> {
+    if node.kind() == "string_literal" {
+        return Some(text(node, code).trim_matches('"').to_string());
+    }
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    children.into_iter().find_map(|c| first_string(c, code))
+}
+
+/// 线程池的关联名: @Bean 方法 > 变量/字段名 > 赋值左值
+fn pool_name(node: Node, code: &str) -> String {
+    if let Some(method) = NodeWalk::new(code).ancestor_of_kind(node, METHOD_DECLARATIONS) {
+        if let Some((_, value)) = method_annotations(method, code).into_iter().find(|(n, _)| n == "Bean") {
+            let method_name = method.child_by_field_name("name").map(|n| text(n, code)).unwrap_or("");
+            return value.unwrap_or_else(|| method_name.to_string());
+        }
+    }
+    ancestors(node)
+        .take_while(|n| !matches!(n.kind(), "method_declaration" | "class_body"))
+        .find_map(|n| match n.kind() {
+            "variable_declarator" => n.child_by_field_name("name").map(|name| text(name, code).to_string()),
+            "assignment_expression" => n.child_by_field_name("left").map(|left| text(left, code).trim_start_matches("this.").to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "(匿名)".to_string())
+}
+
+/// 队列参数类型
+fn queue_kind(node: Node, code: &str) -> QueueKind {
+    if node.kind() != "object_creation_expression" {
+        return QueueKind::Unknown;
+    }
+    let Some(type_node) = node.child_by_field_name("type") else {
+        return QueueKind::Unknown;
+    };
+    let capacity = args(node).first().and_then(|a| eval_int(*a, code));
+    match (simple_type_name(type_node, code).as_str(), capacity) {
+        ("SynchronousQueue", _) => QueueKind::Synchronous,
+        ("LinkedBlockingQueue" | "LinkedBlockingDeque", Some(c)) if c < INT_MAX => QueueKind::Bounded(c),
+        ("LinkedBlockingQueue" | "LinkedBlockingDeque" | "PriorityBlockingQueue" | "LinkedTransferQueue", _) => QueueKind::Unbounded,
+        ("ArrayBlockingQueue", Some(c)) => QueueKind::Bounded(c),
+        _ => QueueKind::Unknown,
+    }
+}
+
+/// `new ThreadPoolExecutor(..)` / `new ScheduledThreadPoolExecutor(n)` / `new ThreadPoolTaskExecutor()`
+fn pool_from_creation(node: Node, code: &str, rel_path: &str) -> Option<PoolSite> {
+    let type_name = simple_type_name(node.child_by_field_name("type")?, code);
+    let arguments = args(node);
+    let line = node.start_position().row + 1;
+
+    let (core, max, queue) = match type_name.as_str() {
+        "ThreadPoolExecutor" if arguments.len() >= 5 => (
+            PoolSize::from_node(arguments[0], code),
+            PoolSize::from_node(arguments[1], code),
+            queue_kind(arguments[4], code),
+        ),
+        "ScheduledThreadPoolExecutor" if !arguments.is_empty() => (
+            PoolSize::from_node(arguments[0], code),
+            PoolSize::fixed(INT_MAX),
+            QueueKind::Unbounded,
+        ),
+        "ThreadPoolTaskExecutor" | "ThreadPoolTaskScheduler" => return Some(spring_executor(node, code, rel_path, &type_name)),
+        _ => return None,
+    };
+
+    Some(PoolSite {
+        name: pool_name(node, code),
+        kind: type_name,
+        core,
+        max,
+        queue,
+        path: rel_path.to_string(),
+        line,
+    })
+}
+
+/// Spring ThreadPoolTaskExecutor: 默认值 core=1, max/queue=Integer.MAX_VALUE，
+/// 再应用同一方法内对该变量的 setter
+fn spring_executor(node: Node, code: &str, rel_path: &str, type_name: &str) -> PoolSite {
+    let mut site = PoolSite {
+        name: pool_name(node, code),
+        kind: type_name.to_string(),
+        core: PoolSize::fixed(1),
+        max: PoolSize::fixed(INT_MAX),
+        queue: QueueKind::Unbounded,
+        path: rel_path.to_string(),
+        line: node.start_position().row + 1,
+    };
+
+    let var_name = node.parent()
+        .filter(|p| p.kind() == "variable_declarator")
+        .and_then(|p| p.child_by_field_name("name"))
+        .map(|n| text(n, code).to_string());
+    let (Some(var_name), Some(method)) = (var_name, NodeWalk::new(code).ancestor_of_kind(node, METHOD_DECLARATIONS)) else {
+        return site;
+    };
+
+    let mut stack = vec![method];
+    while let Some(n) = stack.pop() {
+        if n.kind() == "method_invocation"
+            && n.child_by_field_name("object").map(|o| text(o, code)) == Some(var_name.as_str())
+        {
+            let setter = n.child_by_field_name("name").map(|s| text(s, code)).unwrap_or("");
+            if let Some(arg) = args(n).first() {
+                let size = PoolSize::from_node(*arg, code);
+                match setter {
+                    "setCorePoolSize" => site.core = size,
+                    "setPoolSize" => {
+                        // ThreadPoolTaskScheduler: 固定大小
+                        site.max = size.clone();
+                        site.core = size;
+                    }
+                    "setMaxPoolSize" => site.max = size,
+                    "setQueueCapacity" => site.queue = match size.value {
+                        Some(0) => QueueKind::Synchronous,
+                        Some(c) if c < INT_MAX => QueueKind::Bounded(c),
+                        Some(_) => QueueKind::Unbounded,
+                        None => QueueKind::Unknown,
+                    },
+                    _ => {}
+                }
+            }
+        }
+        let mut cursor = n.walk();
+        stack.extend(n.children(&mut cursor));
+    }
+    site
+}
+
+/// `Executors.newXxxThreadPool(..)`
+fn pool_from_factory(node: Node, code: &str, rel_path: &str) -> Option<PoolSite> {
+    if node.child_by_field_name("object").map(|o| text(o, code)) != Some("Executors") {
+        return None;
+    }
+    let factory = text(node.child_by_field_name("name")?, code);
+    let arguments = args(node);
+    let first = || arguments.first().map(|a| PoolSize::from_node(*a, code));
+
+    let (core, max, queue) = match factory {
+        "newFixedThreadPool" => {
+            let size = first()?;
+            (size.clone(), size, QueueKind::Unbounded)
+        }
+        "newScheduledThreadPool" => (first()?, PoolSize::fixed(INT_MAX), QueueKind::Unbounded),
+        "newSingleThreadExecutor" | "newSingleThreadScheduledExecutor" => {
+            (PoolSize::fixed(1), PoolSize::fixed(1), QueueKind::Unbounded)
+        }
+        "newCachedThreadPool" => (PoolSize::fixed(0), PoolSize::fixed(INT_MAX), QueueKind::Synchronous),
+        _ => return None,
+    };
+
+    Some(PoolSite {
+        name: pool_name(node, code),
+        kind: format!("Executors.{factory}"),
+        core,
+        max,
+        queue,
+        path: rel_path.to_string(),
+        line: node.start_position().row + 1,
+    })
+}
+
+struct TaskContext<'a> {
+    code: &'a str,
+    /// 同文件方法名 -> 方法体 (任务中调用本类方法时展开一层)
+    methods: &'a HashMap<String, Node<'a>>,
+    is_dao_var: &'a dyn Fn(&str) -> bool,
+}
+
+fn method_bodies<'a>(root: Node<'a>, code: &str) -> HashMap<String, Node<'a>> {
+    let mut methods = HashMap::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "method_declaration" {
+            if let (Some(name), Some(body)) = (node.child_by_field_name("name"), node.child_by_field_name("body")) {
+                methods.entry(text(name, code).to_string()).or_insert(body);
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    methods
+}
+
+const SUBMIT_METHODS: &[&str] = &[
+    "submit", "execute", "invokeAll", "invokeAny",
+    "schedule", "scheduleAtFixedRate", "scheduleWithFixedDelay",
+];
+
+/// `pool.submit(task)` / `CompletableFuture.supplyAsync(task, pool)`
+fn task_from_invocation(node: Node, ctx: &TaskContext, rel_path: &str) -> Option<TaskUsage> {
+    let code = ctx.code;
+    let object = text(node.child_by_field_name("object")?, code);
+    let method = text(node.child_by_field_name("name")?, code);
+    let arguments = args(node);
+
+    let (executor, task) = if object == "CompletableFuture" && matches!(method, "supplyAsync" | "runAsync") {
+        let task = *arguments.first()?;
+        let executor = arguments.get(1)
+            .map(|e| text(*e, code).trim_start_matches("this.").to_string())
+            .unwrap_or_else(|| COMMON_POOL.to_string());
+        (executor, task)
+    } else if SUBMIT_METHODS.contains(&method) {
+        // 只接受简单接收者 (字段/变量)，排除 jdbcTemplate.execute(sql) 这类调用
+        let receiver = object.trim_start_matches("this.");
+        if receiver.contains('.') || receiver.contains('(') {
+            return None;
+        }
+        // 只接受可见任务体: lambda / 方法引用 / 匿名类 (Runnable 变量无法判定负载)
+        let task = *arguments.first()?;
+        let visible = match task.kind() {
+            "lambda_expression" | "method_reference" => true,
+            "object_creation_expression" => {
+                let mut cursor = task.walk();
+                let has_body = task.children(&mut cursor).any(|c| c.kind() == "class_body");
+                has_body
+            }
+            _ => false,
+        };
+        if !visible {
+            return None;
+        }
+        (receiver.to_string(), task)
+    } else {
+        return None;
+    };
+
+    let (blocking, uses_db) = blocking_calls(task, ctx);
+    Some(TaskUsage {
+        executor,
+        path: rel_path.to_string(),
+        line: node.start_position().row + 1,
+        blocking,
+        uses_db,
+    })
+}
+
+/// `@Async` / `@Async("orderExecutor")` 方法
+fn task_from_async_method(node: Node, ctx: &TaskContext, rel_path: &str) -> Option<TaskUsage> {
+    let (_, value) = method_annotations(node, ctx.code).into_iter().find(|(n, _)| n == "Async")?;
+    let body = node.child_by_field_name("body")?;
+    let (blocking, uses_db) = blocking_calls(body, ctx);
+    Some(TaskUsage {
+        executor: value.unwrap_or_else(|| DEFAULT_ASYNC_EXECUTOR.to_string()),
+        path: rel_path.to_string(),
+        line: node.start_position().row + 1,
+        blocking,
+        uses_db,
+    })
+}
+
+const BLOCKING_METHODS: &[&str] = &[
+    "executeQuery", "executeUpdate", "executeBatch",
+    "getForObject", "getForEntity", "postForObject", "postForEntity", "exchange",
+    "readAllBytes", "readAllLines", "readString", "writeString", "newInputStream", "newOutputStream",
+    "block", "blockFirst", "blockLast",
+];
+
+const BLOCKING_RECEIVER_SUFFIXES: &[&str] = &[
+    "repository", "repo", "dao", "mapper", "jdbctemplate", "connection", "statement", "session",
+    "client", "resttemplate", "feign",
+];
+
+const DB_RECEIVER_SUFFIXES: &[&str] = &[
+    "repository", "repo", "dao", "mapper", "jdbctemplate", "connection", "statement", "session",
+];
+
+const BLOCKING_TYPES: &[&str] = &[
+    "FileInputStream", "FileOutputStream", "FileReader", "FileWriter", "RandomAccessFile", "Socket",
+];
+
+/// 任务体中的阻塞调用 (展开一层同文件方法调用)，返回 (调用列表, 是否访问数据库)
+fn blocking_calls(task: Node, ctx: &TaskContext) -> (Vec<String>, bool) {
+    let code = ctx.code;
+    let mut found: Vec<String> = Vec::new();
+    let mut uses_db = false;
+
+    let mut roots = vec![task];
+    // 方法引用 / 直接传入的 Runnable 变量: 展开同文件方法
+    if task.kind() == "method_reference" {
+        let name = text(task, code).rsplit("::").next().unwrap_or("");
+        roots.extend(ctx.methods.get(name).copied());
+    }
+
+    let mut expanded = 0;
+    let mut stack = roots;
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            "method_invocation" => {
+                let method = node.child_by_field_name("name").map(|n| text(n, code)).unwrap_or("");
+                let receiver = node.child_by_field_name("object").map(|o| text(o, code)).unwrap_or("");
+                let receiver = receiver.trim_start_matches("this.");
+                let lower = receiver.to_lowercase();
+
+                let is_db = (ctx.is_dao_var)(receiver) || DB_RECEIVER_SUFFIXES.iter().any(|s| lower.ends_with(s));
+                let is_blocking = (receiver == "Thread" && method == "sleep")
+                    || BLOCKING_METHODS.contains(&method)
+                    || is_db
+                    || BLOCKING_RECEIVER_SUFFIXES.iter().any(|s| lower.ends_with(s));
+
+                if is_blocking {
+                    uses_db |= is_db || matches!(method, "executeQuery" | "executeUpdate" | "executeBatch");
+                    let label = if receiver.is_empty() { method.to_string() } else { format!("{receiver}.{method}") };
+                    if !found.contains(&label) {
+                        found.push(label);
+                    }
+                } else if (receiver.is_empty() || receiver == "this") && expanded < 8 {
+                    // 本类方法只展开一层，避免递归
+                    if let Some(body) = ctx.methods.get(method) {
+                        if !has_ancestor(node, *body) {
+                            expanded += 1;
+                            stack.push(*body);
+                        }
+                    }
+                }
+            }
+            "object_creation_expression" => {
+                if let Some(type_node) = node.child_by_field_name("type") {
+                    let type_name = simple_type_name(type_node, code);
+                    if BLOCKING_TYPES.contains(&type_name.as_str()) {
+                        let label = format!("new {type_name}");
+                        if !found.contains(&label) {
+                            found.push(label);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    (found, uses_db)
+}
+
+fn has_ancestor(node: Node, ancestor: Node) -> bool {
+    std::iter::once(node).chain(ancestors(node)).any(|n| n.id() == ancestor.id())
+}
+
+// ============================================================================
+// 配置读取
+// ============================================================================
+
+/// 读取 application*.yml / application*.properties 中的线程池与连接池配置
+///
+/// 按路径排序后依次合并，`application-{profile}` 覆盖 `application`。
+pub fn read_config(root: &Path, files: &[PathBuf]) -> ExecutorConfig {
+    let mut candidates: Vec<&PathBuf> = files.iter()
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name.starts_with("application")
+                && [".yml", ".yaml", ".properties"].iter().any(|ext| name.ends_with(ext))
+        })
+        .collect();
+    candidates.sort_by_key(|p| (p.file_name().map(|n| n.len()), p.to_path_buf()));
+
+    let mut config = ExecutorConfig::default();
+    for path in candidates {
+        let Ok(source) = crate::encoding::read_source(path) else { continue };
+        let flat = if path.extension().and_then(|e| e.to_str()) == Some("properties") {
+            flatten_properties(&source.content)
+        } else {
+            flatten_yaml(&source.content)
+        };
+
+        let get = |key: &str| flat.get(key).and_then(|v| v.trim().parse::<u32>().ok());
+        let before = (config.core_size, config.max_size, config.queue_capacity);
+        config.core_size = get("spring.task.execution.pool.core-size").or(config.core_size);
+        config.max_size = get("spring.task.execution.pool.max-size").or(config.max_size);
+        config.queue_capacity = get("spring.task.execution.pool.queue-capacity").or(config.queue_capacity);
+        config.hikari_max = get("spring.datasource.hikari.maximum-pool-size").or(config.hikari_max);
+        if before != (config.core_size, config.max_size, config.queue_capacity) {
+            config.source = Some(crate::ast_engine::relative_path(root, path));
+        }
+    }
+    config
+}
+
+fn flatten_properties(content: &str) -> BTreeMap<String, String> {
+    content.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('!'))
+        .filter_map(|l| l.split_once(['=', ':']))
+        .map(|(k, v)| (normalize_key(k.trim()), v.trim().to_string()))
+        .collect()
+}
+
+/// 展开 YAML 为点分 key (支持 `---` 多文档)
+fn flatten_yaml(content: &str) -> BTreeMap<String, String> {
+    use serde::Deserialize;
+
+    fn walk(prefix: &str, value: &serde_yaml::Value, out: &mut BTreeMap<String, String>) {
+        match value {
+            serde_yaml::Value::Mapping(map) => {
+                for (k, v) in map {
+                    let key = match k {
+                        serde_yaml::Value::String(s) => s.clone(),
+                        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+                    };
+                    let full = if prefix.is_empty() { key } else { format!("{prefix}.{key}") };
+                    walk(&full, v, out);
+                }
+            }
+            serde_yaml::Value::String(s) => { out.insert(normalize_key(prefix), s.clone()); }
+            serde_yaml::Value::Number(n) => { out.insert(normalize_key(prefix), n.to_string()); }
+            serde_yaml::Value::Bool(b) => { out.insert(normalize_key(prefix), b.to_string()); }
+            _ => {}
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    for doc in serde_yaml::Deserializer::from_str(content) {
+        if let Ok(value) = serde_yaml::Value::deserialize(doc) {
+            walk("", &value, &mut out);
+        }
+    }
+    out
+}
+
+/// Spring relaxed binding: `corePoolSize`/`core_size` → `core-size`
+fn normalize_key(key: &str) -> String {
+    let mut out = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('-');
+            out.push(c.to_ascii_lowercase());
+        } else if c == '_' {
+            out.push('-');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// ============================================================================
+// 建议生成
+// ============================================================================
+
+/// 汇总所有文件的收集结果，生成线程池建议
+///
+/// `cpus`: 估算使用的核数 (通常为当前机器核数，报告中注明)
+pub fn advise(usages: Vec<FileUsage>, config: &ExecutorConfig, cpus: usize) -> Vec<PoolAdvice> {
+    let mut pools: Vec<PoolSite> = Vec::new();
+    let mut tasks: Vec<TaskUsage> = Vec::new();
+    for usage in usages {
+        pools.extend(usage.pools);
+        tasks.extend(usage.tasks);
+    }
+
+    // @Async 默认线程池 / spring.task.execution 配置
+    let uses_default_async = tasks.iter().any(|t| t.executor == DEFAULT_ASYNC_EXECUTOR || t.executor == "taskExecutor");
+    let has_config = config.core_size.is_some() || config.max_size.is_some() || config.queue_capacity.is_some();
+    if (uses_default_async || has_config) && !pools.iter().any(|p| p.name == DEFAULT_ASYNC_EXECUTOR || p.name == "taskExecutor") {
+        pools.push(PoolSite {
+            name: DEFAULT_ASYNC_EXECUTOR.to_string(),
+            kind: "spring.task.execution".to_string(),
+            core: PoolSize::fixed(config.core_size.unwrap_or(8)),
+            max: PoolSize::fixed(config.max_size.unwrap_or(INT_MAX)),
+            queue: match config.queue_capacity {
+                Some(0) => QueueKind::Synchronous,
+                Some(c) if c < INT_MAX => QueueKind::Bounded(c),
+                _ => QueueKind::Unbounded,
+            },
+            path: config.source.clone().unwrap_or_else(|| "(Spring Boot 默认)".to_string()),
+            line: 0,
+        });
+    }
+
+    // 公共池上的阻塞任务
+    if tasks.iter().any(|t| t.executor == COMMON_POOL && !t.blocking.is_empty()) {
+        let size = cpus.saturating_sub(1).max(1) as u32;
+        pools.push(PoolSite {
+            name: COMMON_POOL.to_string(),
+            kind: "ForkJoinPool".to_string(),
+            core: PoolSize { value: Some(size), expr: "N_cpu - 1".to_string() },
+            max: PoolSize { value: Some(size), expr: "N_cpu - 1".to_string() },
+            queue: QueueKind::Unbounded,
+            path: "(JVM 全局)".to_string(),
+            line: 0,
+        });
+    }
+
+    pools.sort_by(|a, b| (&a.path, a.line, &a.name).cmp(&(&b.path, b.line, &b.name)));
+
+    pools.into_iter()
+        .map(|pool| {
+            let matched: Vec<&TaskUsage> = {
+                let by_name: Vec<&TaskUsage> = tasks.iter().filter(|t| names_match(&t.executor, &pool.name)).collect();
+                let same_file: Vec<&TaskUsage> = by_name.iter().copied().filter(|t| t.path == pool.path).collect();
+                if same_file.is_empty() { by_name } else { same_file }
+            };
+            advise_pool(pool, &matched, config, cpus)
+        })
+        .collect()
+}
+
+fn names_match(task_executor: &str, pool_name: &str) -> bool {
+    task_executor == pool_name
+        || (pool_name == DEFAULT_ASYNC_EXECUTOR && task_executor == "taskExecutor")
+}
+
+fn advise_pool(pool: PoolSite, tasks: &[&TaskUsage], config: &ExecutorConfig, cpus: usize) -> PoolAdvice {
+    let mut blocking_calls: Vec<String> = Vec::new();
+    for call in tasks.iter().flat_map(|t| t.blocking.iter()) {
+        if !blocking_calls.contains(call) {
+            blocking_calls.push(call.clone());
+        }
+    }
+    let uses_db = tasks.iter().any(|t| t.uses_db);
+
+    let workload = if tasks.is_empty() {
+        Workload::Unknown
+    } else if blocking_calls.is_empty() {
+        Workload::CpuBound
+    } else {
+        Workload::IoBound
+    };
+
+    let cpu_size = cpus + 1;
+    let io_size = cpus * (1 + ASSUMED_WAIT_COMPUTE_RATIO);
+    let db_cap = if uses_db { config.hikari_max } else { None };
+
+    let (recommendation, formula) = match workload {
+        Workload::CpuBound => (
+            format!("core = max = {cpu_size}"),
+            format!("CPU 密集: N_cpu + 1 = {cpus} + 1 = {cpu_size}"),
+        ),
+        Workload::IoBound => {
+            let mut formula = format!(
+                "IO 密集: N_cpu × (1 + W/C) = {cpus} × (1 + {ASSUMED_WAIT_COMPUTE_RATIO}) = {io_size} \
+                (假设 W/C ≈ {ASSUMED_WAIT_COMPUTE_RATIO}，请以压测实测的等待/计算耗时比修正)"
+            );
+            let size = match db_cap {
+                Some(cap) if (cap as usize) < io_size => {
+                    formula.push_str(&format!("；任务访问数据库，受 hikari maximum-pool-size = {cap} 限制"));
+                    cap as usize
+                }
+                _ => io_size,
+            };
+            (format!("core = max = {size}，使用有界队列"), formula)
+        }
+        Workload::Unknown => (
+            format!("CPU 密集取 {cpu_size}，IO 密集取 {io_size}"),
+            format!(
+                "未找到提交到该线程池的任务，无法判定负载类型: N_cpu + 1 = {cpu_size} / N_cpu × (1 + W/C) = {io_size} (W/C ≈ {ASSUMED_WAIT_COMPUTE_RATIO})"
+            ),
+        ),
+    };
+
+    let warnings = pool_warnings(&pool, workload, db_cap, cpus);
+
+    PoolAdvice {
+        pool,
+        workload,
+        task_count: tasks.len(),
+        blocking_calls,
+        recommendation,
+        formula,
+        warnings,
+    }
+}
+
+fn pool_warnings(pool: &PoolSite, workload: Workload, db_cap: Option<u32>, cpus: usize) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let (core, max) = (pool.core.value, pool.max.value);
+
+    if let (Some(c), Some(m)) = (core, max) {
+        if c > m {
+            warnings.push(format!("corePoolSize ({c}) > maximumPoolSize ({m})，构造时抛出 IllegalArgumentException"));
+        }
+        if pool.queue == QueueKind::Unbounded && m > c && m < INT_MAX {
+            warnings.push(format!("无界队列下 maximumPoolSize ({m}) 不会生效，线程数固定为 {c}"));
+        }
+    }
+    match pool.queue {
+        QueueKind::Unbounded if pool.name != COMMON_POOL => {
+            warnings.push("无界队列: 任务积压时队列持续增长，可能 OOM".to_string());
+        }
+        QueueKind::Synchronous if max == Some(INT_MAX) => {
+            warnings.push("SynchronousQueue + 无上限 maximumPoolSize: 突发流量下线程数失控".to_string());
+        }
+        _ => {}
+    }
+
+    let threads = pool.effective_threads().map(|t| t as usize);
+    match (workload, threads) {
+        (Workload::CpuBound, Some(t)) if t > cpus * 2 && t < INT_MAX as usize => {
+            warnings.push(format!("CPU 密集任务使用 {t} 个线程 (N_cpu = {cpus})，上下文切换开销大"));
+        }
+        (Workload::IoBound, Some(t)) if t < cpus => {
+            warnings.push(format!("IO 密集任务只有 {t} 个线程 (N_cpu = {cpus})，等待 IO 时 CPU 利用率不足"));
+        }
+        _ => {}
+    }
+    if let (Some(cap), Some(t)) = (db_cap, threads) {
+        if t > cap as usize {
+            warnings.push(format!("线程数 {t} 超过数据库连接池 maximum-pool-size ({cap})，多余线程阻塞在获取连接"));
+        }
+    }
+    if pool.name == COMMON_POOL && workload == Workload::IoBound {
+        warnings.push("阻塞任务提交到 ForkJoinPool.commonPool (全局共享)，会拖慢所有 parallelStream/CompletableFuture".to_string());
+    }
+
+    warnings
+}
+
+/// 渲染报告章节 (无线程池时返回空串)
+pub fn render_section(advice: &[PoolAdvice], cpus: usize) -> String {
+    if advice.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("### 🧵 线程池容量建议 (按 N_cpu = {cpus} 估算)\n\n");
+    for a in advice {
+        let location = if a.pool.line > 0 {
+            format!("{}:{}", a.pool.path, a.pool.line)
+        } else {
+            a.pool.path.clone()
+        };
+        let queue = match a.pool.queue {
+            QueueKind::Unbounded => "无界".to_string(),
+            QueueKind::Bounded(c) => format!("有界({c})"),
+            QueueKind::Synchronous => "SynchronousQueue".to_string(),
+            QueueKind::Unknown => "未知".to_string(),
+        };
+        let workload = match a.workload {
+            Workload::IoBound => format!("IO 密集 ({} 个任务, 阻塞调用: {})", a.task_count,
+                a.blocking_calls.iter().take(5).cloned().collect::<Vec<_>>().join(", ")),
+            Workload::CpuBound => format!("CPU 密集 ({} 个任务, 无阻塞调用)", a.task_count),
+            Workload::Unknown => "负载未知".to_string(),
+        };
+
+        out.push_str(&format!(
+            "- **{}** (`{}`, `{}`) core={} max={} queue={} → {}\n",
+            a.pool.name, a.pool.kind, location, a.pool.core.expr, a.pool.max.expr, queue, workload
+        ));
+        out.push_str(&format!("  - 建议: {}\n", a.recommendation));
+        out.push_str(&format!("  - 公式: {}\n", a.formula));
+        for w in &a.warnings {
+            out.push_str(&format!("  - ⚠️ {w}\n"));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_code(code: &str) -> FileUsage {
+        let analyzer = crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(code, |tree| collect(tree.root_node(), code, "A.java", &|_| false)).unwrap()
+    }
+
+    #[test]
+    fn test_collect_thread_pool_executor() {
+        let code = r#"
+            public class Pools {
+                private final ExecutorService ioPool = new ThreadPoolExecutor(
+                    4, 16, 60L, TimeUnit.SECONDS, new LinkedBlockingQueue<>());
+                private final ExecutorService fixed = Executors.newFixedThreadPool(Runtime.getRuntime().availableProcessors());
+                private final ExecutorService bounded = new ThreadPoolExecutor(2, 2, 0, TimeUnit.SECONDS, new ArrayBlockingQueue<>(100));
+            }
+        "#;
+        let usage = collect_code(code);
+        let find = |name: &str| usage.pools.iter().find(|p| p.name == name).unwrap();
+
+        let io = find("ioPool");
+        assert_eq!((io.core.value, io.max.value, io.queue), (Some(4), Some(16), QueueKind::Unbounded));
+        let fixed = find("fixed");
+        assert_eq!(fixed.core.value, None);
+        assert!(fixed.core.expr.contains("availableProcessors"));
+        assert_eq!(find("bounded").queue, QueueKind::Bounded(100));
+    }
+
+    #[test]
+    fn test_collect_spring_executor_bean() {
+        let code = r#"
+            @Configuration
+            public class AsyncConfig {
+                @Bean("orderExecutor")
+                public Executor orderExecutor() {
+                    ThreadPoolTaskExecutor executor = new ThreadPoolTaskExecutor();
+                    executor.setCorePoolSize(20);
+                    executor.setMaxPoolSize(10);
+                    executor.setQueueCapacity(500);
+                    return executor;
+                }
+            }
+        "#;
+        let usage = collect_code(code);
+        let pool = &usage.pools[0];
+        assert_eq!(pool.name, "orderExecutor");
+        assert_eq!((pool.core.value, pool.max.value, pool.queue), (Some(20), Some(10), QueueKind::Bounded(500)));
+
+        let advice = advise(vec![usage], &ExecutorConfig::default(), 8);
+        assert!(advice[0].warnings.iter().any(|w| w.contains("IllegalArgumentException")));
+    }
+
+    #[test]
+    fn test_io_bound_tasks_with_local_method_expansion() {
+        let code = r#"
+            public class ReportService {
+                private final ExecutorService pool = Executors.newFixedThreadPool(4);
+
+                public void run(List<Long> ids) {
+                    for (Long id : ids) {
+                        pool.submit(() -> load(id));
+                    }
+                    pool.submit(this::compute);
+                }
+                private void load(Long id) { orderRepository.findById(id); }
+                private int compute() { return 1 + 1; }
+            }
+        "#;
+        let usage = collect_code(code);
+        assert_eq!(usage.tasks.len(), 2);
+        assert!(usage.tasks.iter().any(|t| t.blocking == vec!["orderRepository.findById".to_string()] && t.uses_db));
+
+        let config = ExecutorConfig { hikari_max: Some(20), ..Default::default() };
+        let advice = advise(vec![usage], &config, 8);
+        assert_eq!(advice[0].workload, Workload::IoBound);
+        // 8 × (1 + 9) = 80，受 hikari 20 限制
+        assert!(advice[0].recommendation.starts_with("core = max = 20"), "{}", advice[0].recommendation);
+        assert!(advice[0].warnings.iter().any(|w| w.contains("CPU 利用率不足")));
+    }
+
+    #[test]
+    fn test_cpu_bound_and_unknown_workload() {
+        let code = r#"
+            public class Calc {
+                private final ExecutorService cpu = Executors.newFixedThreadPool(64);
+                private final ExecutorService idle = Executors.newSingleThreadExecutor();
+                void go() { cpu.execute(() -> Math.sqrt(42)); }
+            }
+        "#;
+        let advice = advise(vec![collect_code(code)], &ExecutorConfig::default(), 4);
+        let cpu = advice.iter().find(|a| a.pool.name == "cpu").unwrap();
+        assert_eq!(cpu.workload, Workload::CpuBound);
+        assert_eq!(cpu.recommendation, "core = max = 5");
+        assert!(cpu.warnings.iter().any(|w| w.contains("上下文切换")));
+        assert_eq!(advice.iter().find(|a| a.pool.name == "idle").unwrap().workload, Workload::Unknown);
+    }
+
+    #[test]
+    fn test_async_default_executor_and_common_pool() {
+        let code = r#"
+            public class Notifier {
+                @Async
+                public void send() { restTemplate.postForObject(url, body, String.class); }
+                public void fire() { CompletableFuture.runAsync(() -> Thread.sleep(100)); }
+            }
+        "#;
+        let config = ExecutorConfig { core_size: Some(2), queue_capacity: Some(50), ..Default::default() };
+        let advice = advise(vec![collect_code(code)], &config, 4);
+
+        let default = advice.iter().find(|a| a.pool.name == DEFAULT_ASYNC_EXECUTOR).unwrap();
+        assert_eq!(default.pool.queue, QueueKind::Bounded(50));
+        assert_eq!(default.workload, Workload::IoBound);
+        let common = advice.iter().find(|a| a.pool.name == COMMON_POOL).unwrap();
+        assert!(common.warnings.iter().any(|w| w.contains("commonPool")));
+    }
+
+    #[test]
+    fn test_read_config_yaml_and_properties() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("application.yml"), "spring:\n  task:\n    execution:\n      pool:\n        core-size: 4\n        max-size: 16\n  datasource:\n    hikari:\n      maximumPoolSize: 30\n").unwrap();
+        std::fs::write(dir.path().join("application-prod.properties"), "spring.task.execution.pool.queue-capacity=200\n").unwrap();
+        let files = vec![dir.path().join("application-prod.properties"), dir.path().join("application.yml")];
+
+        let config = read_config(dir.path(), &files);
+        assert_eq!(config.core_size, Some(4));
+        assert_eq!(config.max_size, Some(16));
+        assert_eq!(config.queue_capacity, Some(200));
+        assert_eq!(config.hikari_max, Some(30));
+        assert_eq!(config.source.as_deref(), Some("application-prod.properties"));
+    }
+}
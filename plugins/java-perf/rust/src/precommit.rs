@@ -0,0 +1,253 @@
+//! 提交前检查 (`precommit`)
+//!
+//! 只分析 git 暂存区中的文件，内容取自 index (`git show :path`) 而不是工作区，
+//! 保证检查的正是即将提交的版本 (部分暂存的文件不会被工作区改动干扰)。
+//!
+//! 全量 Phase 1 索引对 hook 来说太慢，这里复用项目索引缓存
+//! (`.javaperf/symbols.bin`，见 symbol_cache)：只重新索引内容变化的工作区文件，
+//! 暂存文件则用暂存内容重新索引后替代缓存中的条目。
+//!
+//! `precommit --install` 写入 `.git/hooks/pre-commit`；存在 P0 时以非零状态退出阻止提交。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ast_engine::{index_file, locate_issue, AstIssue, FileAnalyzers, Severity};
+use crate::dedup::{dedup, DedupPolicy};
+use crate::encoding::{decode_bytes, SourceText};
+use crate::git;
+use crate::shared_path::FilePath;
+use crate::project_config;
+use crate::project_detector::detect_stack;
+use crate::rules::registry::RuleRegistry;
+use crate::symbol_cache;
+
+/// 生成的 hook 中的标记行，用于识别可安全覆盖的旧 hook
+const HOOK_MARKER: &str = "# java-perf pre-commit hook";
+
+type BoxError = Box<dyn std::error::Error>;
+
+// ============================================================================
+// git 交互
+// ============================================================================
+
+/// 仓库根目录 (`git rev-parse --show-toplevel`)
+pub fn repo_root(path: &Path) -> Result<PathBuf, BoxError> {
+    let out = git::run(path, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(String::from_utf8_lossy(&out).trim()))
+}
+
+/// 暂存区中新增/修改/重命名的文件 (相对仓库根，不含删除)
+pub fn staged_files(root: &Path) -> Result<Vec<String>, BoxError> {
+    let out = git::run(root, &["diff", "--cached", "--name-only", "--diff-filter=ACMR", "-z"])?;
+    Ok(out.split(|b| *b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect())
+}
+
+/// 读取文件在暂存区中的内容
+pub fn staged_content(root: &Path, rel_path: &str) -> Result<SourceText, BoxError> {
+    let bytes = git::run(root, &["show", &format!(":{rel_path}")])?;
+    Ok(decode_bytes(&bytes))
+}
+
+// ============================================================================
+// 检查
+// ============================================================================
+
+/// 提交前检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PrecommitResult {
+    /// 分析的暂存文件数
+    pub files: usize,
+    /// 本次是否重建了符号表缓存
+    pub symbols_rebuilt: bool,
+    pub issues: Vec<AstIssue>,
+}
+
+impl PrecommitResult {
+    pub fn p0_count(&self) -> usize {
+        self.issues.iter().filter(|i| matches!(i.severity, Severity::P0)).count()
+    }
+
+    /// 存在 P0 时阻止提交
+    pub fn blocks_commit(&self) -> bool {
+        self.p0_count() > 0
+    }
+}
+
+/// 分析暂存区中的文件
+///
+/// refresh_symbols: 强制重建符号表缓存
+pub fn run(path: &str, refresh_symbols: bool) -> Result<PrecommitResult, BoxError> {
+    let root = repo_root(Path::new(path))?;
+    let staged: Vec<String> = staged_files(&root)?
+        .into_iter()
+        .filter(|rel| FileAnalyzers::is_target(Path::new(rel)))
+        .collect();
+    if staged.is_empty() {
+        return Ok(PrecommitResult { files: 0, symbols_rebuilt: false, issues: Vec::new() });
+    }
+
+    let config = project_config::load(&root)?;
+    let mut registry = RuleRegistry::with_stack(&detect_stack(&root));
+    registry.apply_overrides(&config.rule_overrides)?;
+    let exclusions = config.exclusions()?;
+    let analyzers = FileAnalyzers::new(&registry)?.with_exclusions(&root, exclusions);
+
+    let mut sources: Vec<(String, PathBuf, SourceText)> = Vec::new();
+    for rel in staged {
+        let source = staged_content(&root, &rel)?;
+        let abs = root.join(&rel);
+        sources.push((rel, abs, source));
+    }
+
+    // 缓存符号表 (暂存文件除外) + 暂存文件的最新符号
+    let staged_java: HashSet<PathBuf> = sources.iter()
+        .filter(|(rel, _, _)| rel.ends_with(".java"))
+        .map(|(_, abs, _)| abs.clone())
+        .collect();
+    let worktree = symbol_cache::java_files(&root);
+    let paths: Vec<&Path> = worktree.iter().map(PathBuf::as_path).collect();
+    let index = symbol_cache::build(&root, &paths, analyzers.java(), refresh_symbols, &staged_java);
+    let symbols_rebuilt = index.stats.rebuilt;
+    let mut table = index.table;
+    for (_, abs, source) in sources.iter().filter(|(_, abs, _)| staged_java.contains(abs)) {
+        let (file_table, _, _) = index_file(abs, &source.content, analyzers.java());
+        table.merge(file_table);
+    }
+
+    let mut issues: Vec<AstIssue> = sources.iter()
+        .flat_map(|(rel, abs, source)| {
+            analyzers.analyze_file(abs, &source.content, Some(&table), None)
+                .into_iter()
+                .map(|issue| locate_issue(issue, &FilePath::new(rel), &source.content))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    issues.sort_by(|a, b| (&a.path, a.line, &a.issue_type).cmp(&(&b.path, b.line, &b.issue_type)));
+    let issues = dedup(issues, DedupPolicy::default());
+
+    Ok(PrecommitResult { files: sources.len(), symbols_rebuilt, issues })
+}
+
+/// 渲染结果：人类可读模式每个问题一行，便于在终端中快速浏览
+pub fn render(result: &PrecommitResult, json_output: bool) -> Value {
+    let p0 = result.p0_count();
+    let p1 = result.issues.len() - p0;
+
+    if json_output {
+        return json!({
+            "files": result.files,
+            "symbols_rebuilt": result.symbols_rebuilt,
+            "p0": p0,
+            "p1": p1,
+            "blocked": result.blocks_commit(),
+            "issues": result.issues,
+        });
+    }
+
+    if result.issues.is_empty() {
+        return json!(format!("✅ java-perf precommit: {} 个暂存文件未发现问题", result.files));
+    }
+
+    let mut report = format!("java-perf precommit: {} 个暂存文件 | P0: {p0} | P1: {p1}\n", result.files);
+    for issue in &result.issues {
+        let emoji = match issue.severity {
+            Severity::P0 => "🔴",
+            Severity::P1 => "🟡",
+        };
+        report.push_str(&format!("{emoji} {}:{} {} - {}\n", issue.path, issue.line, issue.issue_type, issue.description));
+    }
+    if result.blocks_commit() {
+        report.push_str("❌ 存在 P0 问题，提交已阻止 (确认无误可使用 git commit --no-verify 跳过)\n");
+    }
+    json!(report.trim_end())
+}
+
+// ============================================================================
+// hook 安装
+// ============================================================================
+
+/// 写入 pre-commit hook，返回 hook 路径
+///
+/// 已存在非 java-perf 生成的 hook 时需要 force 才会覆盖。
+pub fn install(path: &str, binary: &str, force: bool) -> Result<PathBuf, BoxError> {
+    let root = repo_root(Path::new(path))?;
+    // 支持 worktree 与 core.hooksPath
+    let hooks_dir = root.join(String::from_utf8_lossy(&git::run(&root, &["rev-parse", "--git-path", "hooks"])?).trim());
+    let hook = hooks_dir.join("pre-commit");
+
+    if let Ok(existing) = std::fs::read_to_string(&hook) {
+        if !existing.contains(HOOK_MARKER) && !force {
+            return Err(format!("{} 已存在且不是 java-perf 生成的，使用 --force 覆盖", hook.display()).into());
+        }
+    }
+
+    std::fs::create_dir_all(&hooks_dir)?;
+    std::fs::write(&hook, hook_script(binary))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(hook)
+}
+
+fn hook_script(binary: &str) -> String {
+    format!("#!/bin/sh\n{HOOK_MARKER} (java-perf precommit --install)\n# 跳过检查: git commit --no-verify\nexec \"{binary}\" precommit\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        git::run(dir.path(), &["init", "-q"]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_analyzes_staged_content_not_worktree() {
+        let dir = git_repo();
+        let root = dir.path();
+        std::fs::write(root.join("Worker.java"), "public class Worker {\n    void run() {\n        Executors.newCachedThreadPool();\n    }\n}\n").unwrap();
+        git::run(root, &["add", "Worker.java"]).unwrap();
+        // 工作区已修复但未暂存：仍应报告暂存版本中的问题
+        std::fs::write(root.join("Worker.java"), "public class Worker {\n}\n").unwrap();
+        std::fs::write(root.join("Untracked.java"), "public class Untracked {\n    void run() { Executors.newCachedThreadPool(); }\n}\n").unwrap();
+
+        let result = run(root.to_str().unwrap(), false).unwrap();
+        assert_eq!(result.files, 1);
+        assert!(result.symbols_rebuilt);
+        let ids: Vec<_> = result.issues.iter().map(|i| (i.path.as_str(), i.line, i.issue_type.as_str())).collect();
+        assert!(ids.contains(&("Worker.java", 3, "UNBOUNDED_POOL")), "{ids:?}");
+        assert!(result.issues.iter().all(|i| i.path == "Worker.java"));
+
+        // 第二次运行复用缓存
+        assert!(symbol_cache::cache_path(root).exists());
+        assert!(!run(root.to_str().unwrap(), false).unwrap().symbols_rebuilt);
+        assert!(run(root.to_str().unwrap(), true).unwrap().symbols_rebuilt);
+    }
+
+    #[test]
+    fn test_install_hook_respects_existing() {
+        let dir = git_repo();
+        let root = dir.path().to_str().unwrap();
+        let hook = install(root, "/opt/java-perf", false).unwrap();
+        let script = std::fs::read_to_string(&hook).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("exec \"/opt/java-perf\" precommit"));
+        // 自己生成的 hook 可以直接覆盖
+        assert!(install(root, "java-perf", false).is_ok());
+
+        std::fs::write(&hook, "#!/bin/sh\nnpm run lint\n").unwrap();
+        assert!(install(root, "java-perf", false).is_err());
+        assert!(install(root, "java-perf", true).is_ok());
+    }
+}
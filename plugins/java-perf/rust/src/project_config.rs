@@ -0,0 +1,377 @@
+//! 项目配置 (`.javaperf.toml`，位于扫描根目录)
+//!
+//! ```toml
+//! # 输出时使用组织内部的严重级别名称 (不影响规则定义与 P0/P1 判定)
+//! [severity_map]
+//! P0 = "Blocker"
+//! P1 = "Major"
+//!
+//! # 对指定目录关闭单条规则 (glob 匹配相对扫描根的路径)
+//! [[rule_overrides]]
+//! id = "EMPTY_CATCH"
+//! exclude = ["**/generated/**", "**/legacy/**"]
+//!
+//! # id 也可以是规则类别 (`--categories` 的取值)，对该类全部规则生效；
+//! # 文件上的全部规则都被排除时不再执行任何 Query
+//! [[rule_overrides]]
+//! id = "reactive"
+//! exclude = ["**/legacy/**"]
+//!
+//! # 启用默认关闭的可选规则 (或以 enabled = false 关闭任意规则)
+//! [[rule_overrides]]
+//! id = "JDBC_FETCH_SIZE_REQUIRED"
+//! enabled = true
+//!
+//! # 噪音较大的新规则先按指纹抽样 10% 报告 (见 sampling 模块)
+//! [[rule_overrides]]
+//! id = "MANUAL_DTO_COPY"
+//! sample_rate = 0.1
+//!
+//! # 基线存放在团队服务器 (见 baseline 模块；token 取自 JAVAPERF_BASELINE_TOKEN)
+//! [baseline]
+//! server = "https://javaperf.example.com"
+//! project = "order-service"
+//!
+//! # 循环内 BigDecimal 运算中视为金额等精确计算的变量名片段 (替换默认列表，不区分大小写)
+//! [bigdecimal]
+//! exact_names = ["amount", "price", "rate"]
+//!
+//! # 跨文件深度分析只在入口可达的类上进行 (见 reachability 模块；`scan --exhaustive` 忽略)
+//! [reachability]
+//! entrypoints = ["*Controller", "com.example.job.*"]
+//!
+//! # 本地使用统计 (见 usage_stats 模块；只写本地文件，默认关闭)
+//! [stats]
+//! enabled = true
+//! ```
+//!
+//! 配置文件不存在时使用默认值；存在但格式错误时报错，避免配置被静默忽略。
+//! `severity_map`、`baseline` 与 `stats.enabled` 可由 `JAVAPERF_*` 环境变量或 `-D` 覆盖 (见 config_env)。
+
+use std::path::Path;
+
+use clap::ValueEnum;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::ast_engine::Severity;
+use crate::rules::registry::{known_rule_ids, RuleCategory};
+use crate::sampling::RuleSampling;
+use crate::config_env::{BASELINE_PROJECT_ENV, BASELINE_SERVER_ENV, SEVERITY_P0_ENV, SEVERITY_P1_ENV, STATS_ENABLED_ENV};
+
+/// 配置文件名
+pub const CONFIG_FILE: &str = ".javaperf.toml";
+
+/// 项目配置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub severity_map: SeverityMap,
+    #[serde(default)]
+    pub rule_overrides: Vec<RuleOverride>,
+    /// 共享基线服务器 (未配置时基线只存放在本地 .javaperf/baseline.json)
+    #[serde(default)]
+    pub baseline: Option<BaselineServer>,
+    /// 本地使用统计 (默认关闭)
+    #[serde(default)]
+    pub stats: StatsConfig,
+    /// BIGDECIMAL_LOOP_ARITHMETIC 配置
+    #[serde(default)]
+    pub bigdecimal: BigDecimalConfig,
+    /// 深度分析的入口类 (未配置时对全部文件执行深度分析)
+    #[serde(default)]
+    pub reachability: ReachabilityConfig,
+}
+
+/// 可达性裁剪配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReachabilityConfig {
+    /// 入口类 (简单名或全限定名，可用 `*` 通配)
+    #[serde(default)]
+    pub entrypoints: Vec<String>,
+}
+
+/// BigDecimal 规则配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BigDecimalConfig {
+    /// 视为精确计算的变量名片段，所在语句含这些名称的运算不报告 (未设置时见 rule_handlers::DEFAULT_EXACT_NAMES)
+    #[serde(default)]
+    pub exact_names: Option<Vec<String>>,
+}
+
+/// 使用统计配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatsConfig {
+    /// 每次 scan 结束后追加一行统计
+    #[serde(default)]
+    pub enabled: bool,
+    /// 统计文件 (相对扫描根，默认 `.javaperf/stats.jsonl`)
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+/// 基线服务器配置
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BaselineServer {
+    /// 服务器地址，如 `https://javaperf.example.com`
+    pub server: String,
+    /// 基线所属项目 (多个仓库使用同一 project 即共享基线)
+    pub project: String,
+    /// server 来自环境变量或 `-D`，而不是被扫描仓库中的配置文件 (决定是否发送 token，见 baseline 模块)
+    #[serde(skip)]
+    pub from_env: bool,
+}
+
+/// 单条规则 (或一个规则类别) 的覆盖配置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleOverride {
+    /// 规则 ID 或类别名 (`performance`、`reactive` 等)
+    pub id: String,
+    /// 不运行该规则的路径 (glob，相对扫描根，`/` 分隔)
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// 启用/关闭该规则 (未设置时保持默认；可选规则默认关闭，见 RuleRegistry::apply_overrides)
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// 只报告该比例的问题 (0~1，按指纹确定性抽样，见 sampling 模块)
+    #[serde(default)]
+    pub sample_rate: Option<f64>,
+}
+
+/// 编译后的按路径排除规则
+///
+/// 在扫描阶段查询：被排除的规则对该文件根本不运行，而不是事后过滤结果。
+#[derive(Debug, Clone, Default)]
+pub struct RuleExclusions {
+    rules: Vec<(String, GlobSet)>,
+}
+
+impl RuleExclusions {
+    /// 编译 rule_overrides 中的 exclude 模式 (模式无效时报错)
+    pub fn compile(overrides: &[RuleOverride]) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for rule in overrides.iter().filter(|r| !r.exclude.is_empty()) {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &rule.exclude {
+                let glob = Glob::new(pattern)
+                    .map_err(|e| format!("rule_overrides {} 的 exclude 模式 {pattern:?} 无效: {e}", rule.id))?;
+                builder.add(glob);
+            }
+            let set = builder.build().map_err(|e| format!("rule_overrides {}: {e}", rule.id))?;
+            match RuleCategory::from_str(rule.id.trim(), true) {
+                Ok(category) => rules.extend(known_rule_ids()
+                    .filter(|(c, _)| *c == category)
+                    .map(|(_, id)| (id.to_string(), set.clone()))),
+                Err(_) => rules.push((rule.id.clone(), set)),
+            }
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `rel_path` 上不运行的规则 ID
+    pub fn excluded_for(&self, rel_path: &str) -> Vec<&str> {
+        self.rules.iter()
+            .filter(|(_, set)| set.is_match(rel_path))
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+}
+
+/// 严重级别显示名映射 (仅作用于输出)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityMap {
+    #[serde(rename = "P0", default, skip_serializing_if = "Option::is_none")]
+    pub p0: Option<String>,
+    #[serde(rename = "P1", default, skip_serializing_if = "Option::is_none")]
+    pub p1: Option<String>,
+}
+
+impl SeverityMap {
+    /// 是否配置了任何映射
+    pub fn is_empty(&self) -> bool {
+        self.p0.is_none() && self.p1.is_none()
+    }
+
+    /// 严重级别的显示名 (未映射时为 "P0"/"P1")
+    pub fn label(&self, severity: Severity) -> &str {
+        match severity {
+            Severity::P0 => self.p0.as_deref().unwrap_or("P0"),
+            Severity::P1 => self.p1.as_deref().unwrap_or("P1"),
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// 编译按路径排除规则
+    pub fn exclusions(&self) -> Result<RuleExclusions, String> {
+        RuleExclusions::compile(&self.rule_overrides)
+    }
+
+    /// 编译按规则抽样比例
+    pub fn sampling(&self) -> Result<RuleSampling, String> {
+        RuleSampling::compile(&self.rule_overrides)
+    }
+
+    /// 合并环境变量覆盖 (空值视为未设置)
+    fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+        let lookup = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+        if let Some(label) = lookup(SEVERITY_P0_ENV) {
+            self.severity_map.p0 = Some(label);
+        }
+        if let Some(label) = lookup(SEVERITY_P1_ENV) {
+            self.severity_map.p1 = Some(label);
+        }
+        if let Some(enabled) = lookup(STATS_ENABLED_ENV) {
+            self.stats.enabled = match enabled.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => true,
+                "0" | "false" | "no" | "off" => false,
+                _ => return Err(format!("{STATS_ENABLED_ENV}={enabled}: 应为 true/false")),
+            };
+        }
+        let (server, project) = (lookup(BASELINE_SERVER_ENV), lookup(BASELINE_PROJECT_ENV));
+        match (self.baseline.as_mut(), server, project) {
+            (_, None, None) => {}
+            (Some(baseline), server, project) => {
+                if let Some(server) = server {
+                    baseline.server = server;
+                    baseline.from_env = true;
+                }
+                baseline.project = project.unwrap_or_else(|| baseline.project.clone());
+            }
+            (None, Some(server), Some(project)) => self.baseline = Some(BaselineServer { server, project, from_env: true }),
+            (None, _, _) => {
+                return Err(format!("未配置 [baseline] 时 {BASELINE_SERVER_ENV} 与 {BASELINE_PROJECT_ENV} 需同时设置"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 读取 root 下的配置 (root 为文件时不读取)，再合并 `JAVAPERF_*` 环境变量
+pub fn load(root: &Path) -> Result<ProjectConfig, Box<dyn std::error::Error>> {
+    load_with_env(root, |name| std::env::var(name).ok())
+}
+
+fn load_with_env(root: &Path, lookup: impl Fn(&str) -> Option<String>) -> Result<ProjectConfig, Box<dyn std::error::Error>> {
+    let path = root.join(CONFIG_FILE);
+    let mut config: ProjectConfig = if root.is_dir() && path.is_file() {
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| format!("{} 格式错误: {e}", path.display()))?
+    } else {
+        ProjectConfig::default()
+    };
+    config.apply_env(lookup)?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_severity_map() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load(dir.path()).unwrap(), ProjectConfig::default());
+
+        std::fs::write(dir.path().join(CONFIG_FILE), "[severity_map]\nP0 = \"Blocker\"\n").unwrap();
+        let config = load(dir.path()).unwrap();
+        assert_eq!(config.severity_map.label(Severity::P0), "Blocker");
+        assert_eq!(config.severity_map.label(Severity::P1), "P1");
+
+        std::fs::write(dir.path().join(CONFIG_FILE), "[bigdecimal]\nexact_names = [\"Rate\"]\n").unwrap();
+        assert_eq!(load(dir.path()).unwrap().bigdecimal.exact_names, Some(vec!["Rate".to_string()]));
+    }
+
+    #[test]
+    fn test_rule_override_exclusions() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), r#"
+            [[rule_overrides]]
+            id = "EMPTY_CATCH"
+            exclude = ["**/generated/**", "legacy/*.java"]
+
+            [[rule_overrides]]
+            id = "N_PLUS_ONE"
+            exclude = ["**/generated/**"]
+
+            [[rule_overrides]]
+            id = "JDBC_FETCH_SIZE_REQUIRED"
+            enabled = true
+            sample_rate = 0.25
+        "#).unwrap();
+        let config = load(dir.path()).unwrap();
+        assert_eq!(config.rule_overrides[2].enabled, Some(true));
+        assert_eq!(config.rule_overrides[2].sample_rate, Some(0.25));
+        assert_eq!(config.rule_overrides[0].enabled, None);
+        let exclusions = config.exclusions().unwrap();
+        assert_eq!(exclusions.excluded_for("src/main/java/generated/Dto.java"), vec!["EMPTY_CATCH", "N_PLUS_ONE"]);
+        assert_eq!(exclusions.excluded_for("legacy/Old.java"), vec!["EMPTY_CATCH"]);
+        assert!(exclusions.excluded_for("src/main/java/OrderService.java").is_empty());
+
+        let by_category = vec![RuleOverride { id: "Reactive".into(), exclude: vec!["**/legacy/**".into()], enabled: None, sample_rate: None }];
+        let by_category = RuleExclusions::compile(&by_category).unwrap();
+        let excluded = by_category.excluded_for("src/legacy/Old.java");
+        assert!(excluded.contains(&"FLUX_BLOCK") && excluded.contains(&"SINKS_MANY"), "{excluded:?}");
+        assert!(!excluded.contains(&"N_PLUS_ONE"));
+
+        let invalid = vec![RuleOverride { id: "EMPTY_CATCH".into(), exclude: vec!["src/[".into()], enabled: None, sample_rate: None }];
+        let err = RuleExclusions::compile(&invalid).unwrap_err();
+        assert!(err.contains("EMPTY_CATCH"), "{err}");
+    }
+
+    #[test]
+    fn test_env_overrides_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), r#"
+            [severity_map]
+            P0 = "Blocker"
+            P1 = "Major"
+
+            [baseline]
+            server = "https://javaperf.example.com"
+            project = "order-service"
+        "#).unwrap();
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+
+        let config = load_with_env(dir.path(), env(&[(SEVERITY_P1_ENV, "Minor"), (BASELINE_PROJECT_ENV, "order-ci"), (SEVERITY_P0_ENV, " ")])).unwrap();
+        assert_eq!(config.severity_map.label(Severity::P0), "Blocker");
+        assert_eq!(config.severity_map.label(Severity::P1), "Minor");
+        let baseline = config.baseline.unwrap();
+        assert_eq!((baseline.server.as_str(), baseline.project.as_str()), ("https://javaperf.example.com", "order-ci"));
+        assert!(!baseline.from_env);
+        let config = load_with_env(dir.path(), env(&[(BASELINE_SERVER_ENV, "https://ci.example.com")])).unwrap();
+        assert!(config.baseline.unwrap().from_env);
+
+        // 没有配置文件时环境变量单独生效；基线服务器需要 server 与 project
+        let empty = tempfile::tempdir().unwrap();
+        let config = load_with_env(empty.path(), env(&[(BASELINE_SERVER_ENV, "https://ci"), (BASELINE_PROJECT_ENV, "p")])).unwrap();
+        let baseline = config.baseline.unwrap();
+        assert_eq!((baseline.server.as_str(), baseline.from_env), ("https://ci", true));
+        let err = load_with_env(empty.path(), env(&[(BASELINE_SERVER_ENV, "https://ci")])).unwrap_err().to_string();
+        assert!(err.contains(BASELINE_PROJECT_ENV), "{err}");
+
+        assert!(load_with_env(empty.path(), env(&[(STATS_ENABLED_ENV, "yes")])).unwrap().stats.enabled);
+        assert!(load_with_env(empty.path(), env(&[(STATS_ENABLED_ENV, "maybe")])).is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), "[severity_map]\nP2 = \"Minor\"\n").unwrap();
+        let err = load(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("格式错误"), "{err}");
+    }
+}
@@ -2,6 +2,7 @@
 // 项目侦测模块 - 识别技术栈与版本
 // ============================================================================
 
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::fs;
 use std::str::FromStr;
@@ -103,6 +104,9 @@ use quick_xml::Reader;
 /// 
 /// # Returns
 /// * `Result<Vec<MavenDependency>>` - List of parsed dependencies or error
+///
+/// 只返回本文件中的原始声明；技术栈检测使用 [`MavenResolver`] 得到的有效依赖。
+#[allow(dead_code)]
 pub fn parse_maven_pom(content: &str) -> Result<Vec<MavenDependency>, String> {
     let mut reader = Reader::from_str(content);
     reader.trim_text(true);
@@ -218,6 +222,332 @@ impl PartialDependency {
     }
 }
 
+// ============================================================================
+// Maven 有效依赖 (parent 继承 / 属性插值 / dependencyManagement + BOM)
+// ============================================================================
+
+/// `<parent>` 声明
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MavenParent {
+    pub group_id: String,
+    pub artifact_id: String,
+    pub version: String,
+    /// None 表示默认 `../pom.xml`；空串 (`<relativePath/>`) 表示只从仓库查找
+    pub relative_path: Option<String>,
+}
+
+/// 单个 pom.xml 的原始模型 (未插值、未继承)
+#[derive(Debug, Clone, Default)]
+pub struct MavenPom {
+    pub group_id: Option<String>,
+    pub artifact_id: Option<String>,
+    pub version: Option<String>,
+    pub parent: Option<MavenParent>,
+    pub properties: HashMap<String, String>,
+    /// `<project><dependencies>` (不含 plugin/profile 内的依赖)
+    pub dependencies: Vec<MavenDependency>,
+    /// `<dependencyManagement>`；scope 为 Import 的条目是 BOM
+    pub managed: Vec<MavenDependency>,
+}
+
+/// 按元素路径解析 pom.xml 中与依赖版本相关的部分
+pub fn parse_maven_model(content: &str) -> Result<MavenPom, String> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut pom = MavenPom::default();
+    let mut path: Vec<String> = Vec::new();
+    let mut parent = MavenParent::default();
+    let mut has_parent = false;
+    let mut current: Option<PartialDependency> = None;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                path.push(name);
+                match project_path(&path).as_slice() {
+                    ["parent"] => has_parent = true,
+                    ["dependencies", "dependency"]
+                    | ["dependencyManagement", "dependencies", "dependency"] => {
+                        current = Some(PartialDependency::default());
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(ref e)) if e.name().as_ref() == b"relativePath" && project_path(&path) == ["parent"] => {
+                parent.relative_path = Some(String::new());
+            }
+            Ok(Event::End(_)) => {
+                match project_path(&path).as_slice() {
+                    ["dependencies", "dependency"] => {
+                        if let Some(dep) = current.take().and_then(PartialDependency::into_dependency) {
+                            pom.dependencies.push(dep);
+                        }
+                    }
+                    ["dependencyManagement", "dependencies", "dependency"] => {
+                        if let Some(dep) = current.take().and_then(PartialDependency::into_dependency) {
+                            pom.managed.push(dep);
+                        }
+                    }
+                    _ => {}
+                }
+                path.pop();
+            }
+            Ok(Event::Text(ref e)) => {
+                let text = e.unescape().map_err(|e| e.to_string())?.trim().to_string();
+                match project_path(&path).as_slice() {
+                    ["groupId"] => pom.group_id = Some(text),
+                    ["artifactId"] => pom.artifact_id = Some(text),
+                    ["version"] => pom.version = Some(text),
+                    ["parent", "groupId"] => parent.group_id = text,
+                    ["parent", "artifactId"] => parent.artifact_id = text,
+                    ["parent", "version"] => parent.version = text,
+                    ["parent", "relativePath"] => parent.relative_path = Some(text),
+                    ["properties", key] => {
+                        pom.properties.insert(key.to_string(), text);
+                    }
+                    [.., "dependency", field] => {
+                        if let Some(dep) = current.as_mut() {
+                            match *field {
+                                "groupId" => dep.group_id = Some(text),
+                                "artifactId" => dep.artifact_id = Some(text),
+                                "version" => dep.version = Some(text),
+                                "scope" => dep.scope = Some(text),
+                                _ => {}
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parse error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if has_parent {
+        pom.parent = Some(parent);
+    }
+    Ok(pom)
+}
+
+/// 相对 `<project>` 的元素路径 (兼容缺少根元素的 pom 片段)
+fn project_path(path: &[String]) -> Vec<&str> {
+    let rel = match path.first() {
+        Some(root) if root == "project" => &path[1..],
+        _ => path,
+    };
+    rel.iter().map(String::as_str).collect()
+}
+
+/// 本地 Maven 仓库 (`~/.m2/repository`)，不存在时返回 None
+pub fn default_m2_repository() -> Option<std::path::PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    let repo = Path::new(&home).join(".m2").join("repository");
+    repo.is_dir().then_some(repo)
+}
+
+/// 构建文件的读取范围
+///
+/// 默认不限制；`--safe-mode` (见 safe_mode 模块) 时只读取扫描根目录内 (解析符号链接后) 的构建文件，
+/// parent `relativePath`、`<module>`、settings include 指向根目录之外时忽略，也不读取本地 Maven 仓库。
+#[derive(Debug, Clone, Default)]
+pub struct BuildScope {
+    /// 规范化的扫描根目录 (None 为不限制)
+    confined_to: Option<std::path::PathBuf>,
+}
+
+impl BuildScope {
+    /// 限制在 root 内
+    pub fn confined(root: &Path) -> Self {
+        Self { confined_to: Some(root.canonicalize().unwrap_or_else(|_| root.to_path_buf())) }
+    }
+
+    /// 是否允许读取该路径
+    pub fn admits(&self, path: &Path) -> bool {
+        match &self.confined_to {
+            Some(root) => path.canonicalize().is_ok_and(|resolved| resolved.starts_with(root)),
+            None => true,
+        }
+    }
+
+    /// 该范围内的 Maven 解析器
+    pub fn maven_resolver(&self) -> MavenResolver {
+        let m2_repository = if self.confined_to.is_some() { None } else { default_m2_repository() };
+        MavenResolver { m2_repository, scope: self.clone() }
+    }
+}
+
+/// 继承链 + BOM 展开的最大深度 (防止错误配置导致的循环)
+const MAX_POM_DEPTH: usize = 10;
+
+/// 合并继承链后的有效模型
+#[derive(Debug, Clone, Default)]
+pub struct EffectivePom {
+    /// 已合并继承链并加入 project.* 内置属性
+    pub properties: HashMap<String, String>,
+    /// groupId:artifactId -> 版本 (已插值；显式声明优先于 BOM 导入)
+    pub managed_versions: HashMap<String, String>,
+    /// 继承链上的 parent 与导入的 BOM: groupId:artifactId -> 版本 (本地找不到的 parent/BOM 也记录)
+    pub platforms: HashMap<String, String>,
+    /// 本模块及继承自 parent 的依赖，版本已插值并从 dependencyManagement 补全
+    pub dependencies: Vec<MavenDependency>,
+}
+
+impl EffectivePom {
+    /// JDK 版本 (`java.version` / `maven.compiler.release` / `maven.compiler.target`)
+    pub fn jdk_version(&self) -> Option<String> {
+        ["java.version", "maven.compiler.release", "maven.compiler.target"].iter()
+            .filter_map(|key| self.properties.get(*key))
+            .find(|v| !v.contains("${"))
+            .cloned()
+    }
+}
+
+/// Maven 有效依赖解析器
+///
+/// parent 先按 relativePath (默认 `../pom.xml`) 在本地查找，坐标不匹配时再查本地仓库；
+/// import BOM 只从本地仓库查找 (离线、只读，找不到时保留 None 而不是猜测)。
+pub struct MavenResolver {
+    m2_repository: Option<std::path::PathBuf>,
+    scope: BuildScope,
+}
+
+impl MavenResolver {
+    /// 不限制读取范围的解析器 (扫描时经 [`BuildScope::maven_resolver`] 创建)
+    #[allow(dead_code)]
+    pub fn new(m2_repository: Option<std::path::PathBuf>) -> Self {
+        Self { m2_repository, scope: BuildScope::default() }
+    }
+
+    /// 解析 pom.xml 的有效依赖
+    pub fn resolve(&self, pom_path: &Path) -> Result<EffectivePom, String> {
+        let content = fs::read_to_string(pom_path).map_err(|e| format!("{}: {e}", pom_path.display()))?;
+        let pom = parse_maven_model(&content)?;
+        Ok(self.effective(pom, pom_path.parent(), 0))
+    }
+
+    fn effective(&self, pom: MavenPom, dir: Option<&Path>, depth: usize) -> EffectivePom {
+        // 1. 继承链：parent 的属性/管理版本/依赖在前，子模块覆盖
+        let mut effective = match (&pom.parent, depth < MAX_POM_DEPTH) {
+            (Some(parent), true) => self.load_parent(parent, dir)
+                .map(|(parent_pom, parent_dir)| self.effective(parent_pom, parent_dir.as_deref(), depth + 1))
+                .unwrap_or_default(),
+            _ => EffectivePom::default(),
+        };
+
+        let parent_version = pom.parent.as_ref().map(|p| p.version.clone());
+        let group_id = pom.group_id.clone().or_else(|| pom.parent.as_ref().map(|p| p.group_id.clone()));
+        let version = pom.version.clone().or(parent_version.clone());
+        effective.properties.extend(pom.properties.clone());
+        if let Some(parent) = &pom.parent {
+            effective.platforms.insert(format!("{}:{}", parent.group_id, parent.artifact_id), parent.version.clone());
+        }
+        for (key, value) in [
+            ("project.groupId", group_id),
+            ("project.artifactId", pom.artifact_id.clone()),
+            ("project.version", version.clone()),
+            ("version", version.clone()),
+            ("pom.version", version),
+            ("project.parent.version", parent_version),
+        ] {
+            match value {
+                Some(value) => effective.properties.insert(key.to_string(), value),
+                None => effective.properties.remove(key),
+            };
+        }
+
+        // 2. dependencyManagement：BOM 先导入，显式条目覆盖
+        let mut imported: HashMap<String, String> = HashMap::new();
+        let mut explicit: HashMap<String, String> = HashMap::new();
+        for managed in &pom.managed {
+            let group = interpolate(&managed.group_id, &effective.properties);
+            let artifact = interpolate(&managed.artifact_id, &effective.properties);
+            let Some(version) = managed.version.as_deref().and_then(|v| interpolate(v, &effective.properties)) else {
+                continue;
+            };
+            let (Some(group), Some(artifact)) = (group, artifact) else { continue };
+            if managed.scope == DependencyScope::Import {
+                effective.platforms.insert(format!("{group}:{artifact}"), version.clone());
+                if depth < MAX_POM_DEPTH {
+                    if let Some(bom) = self.load_from_repository(&group, &artifact, &version) {
+                        imported.extend(self.effective(bom, None, depth + 1).managed_versions);
+                    }
+                }
+            } else {
+                explicit.insert(format!("{group}:{artifact}"), version);
+            }
+        }
+        effective.managed_versions.extend(imported);
+        effective.managed_versions.extend(explicit);
+
+        // 3. 依赖：插值并从管理版本补全
+        for dep in pom.dependencies {
+            let group_id = interpolate(&dep.group_id, &effective.properties).unwrap_or(dep.group_id);
+            let artifact_id = interpolate(&dep.artifact_id, &effective.properties).unwrap_or(dep.artifact_id);
+            let key = format!("{group_id}:{artifact_id}");
+            let version = match dep.version {
+                Some(v) => interpolate(&v, &effective.properties),
+                None => effective.managed_versions.get(&key).cloned(),
+            };
+            // 子模块重新声明的依赖覆盖继承自 parent 的同坐标依赖
+            effective.dependencies.retain(|d| d.group_id != group_id || d.artifact_id != artifact_id);
+            effective.dependencies.push(MavenDependency { group_id, artifact_id, version, scope: dep.scope });
+        }
+        effective
+    }
+
+    /// 查找 parent pom：relativePath 优先 (坐标需匹配)，否则本地仓库
+    fn load_parent(&self, parent: &MavenParent, dir: Option<&Path>) -> Option<(MavenPom, Option<std::path::PathBuf>)> {
+        let relative = parent.relative_path.clone().unwrap_or_else(|| "../pom.xml".to_string());
+        if let (Some(dir), false) = (dir, relative.is_empty()) {
+            let mut candidate = dir.join(&relative);
+            if candidate.is_dir() {
+                candidate = candidate.join("pom.xml");
+            }
+            if !self.scope.admits(&candidate) {
+                tracing::debug!(path = %candidate.display(), "parent pom 不在读取范围内");
+            } else if let Some(pom) = fs::read_to_string(&candidate).ok().and_then(|c| parse_maven_model(&c).ok()) {
+                let group_id = pom.group_id.clone().or_else(|| pom.parent.as_ref().map(|p| p.group_id.clone()));
+                if pom.artifact_id.as_deref() == Some(parent.artifact_id.as_str()) && group_id.as_deref() == Some(parent.group_id.as_str()) {
+                    return Some((pom, candidate.parent().map(Path::to_path_buf)));
+                }
+            }
+        }
+        self.load_from_repository(&parent.group_id, &parent.artifact_id, &parent.version)
+            .map(|pom| (pom, None))
+    }
+
+    /// `~/.m2/repository/<group 路径>/<artifact>/<version>/<artifact>-<version>.pom`
+    fn load_from_repository(&self, group_id: &str, artifact_id: &str, version: &str) -> Option<MavenPom> {
+        let repo = self.m2_repository.as_ref()?;
+        let path = repo.join(group_id.replace('.', "/"))
+            .join(artifact_id)
+            .join(version)
+            .join(format!("{artifact_id}-{version}.pom"));
+        let content = fs::read_to_string(path).ok()?;
+        parse_maven_model(&content).ok()
+    }
+}
+
+/// 展开 `${prop}` (支持嵌套引用)；仍有无法解析的占位符时返回 None
+fn interpolate(value: &str, properties: &HashMap<String, String>) -> Option<String> {
+    let mut result = value.to_string();
+    for _ in 0..MAX_POM_DEPTH {
+        let Some(start) = result.find("${") else {
+            return Some(result);
+        };
+        let end = start + result[start..].find('}')?;
+        let replacement = properties.get(&result[start + 2..end])?.clone();
+        result.replace_range(start..=end, &replacement);
+    }
+    (!result.contains("${")).then_some(result)
+}
+
 /// Filter dependencies to only include main (non-test) scope
 pub fn filter_main_dependencies(deps: &[MavenDependency]) -> Vec<&MavenDependency> {
     deps.iter().filter(|d| d.scope.is_main_scope()).collect()
@@ -343,11 +673,37 @@ pub fn detect_stack_from_maven_deps(deps: &[MavenDependency]) -> DetectedStack {
         if artifact == "lombok" || group == "org.projectlombok" {
             stack.has_lombok = true;
         }
+
+        // RPC 客户端 (超时审计规则门控)
+        if group == "io.grpc" || artifact.starts_with("grpc-") {
+            stack.has_grpc = true;
+        }
+        if group == "io.github.openfeign" || artifact.contains("openfeign") || artifact.starts_with("feign-") {
+            stack.has_feign = true;
+        }
+
+        // 非 Spring 框架 (Quarkus / Micronaut)
+        detect_framework(&mut stack, group, artifact);
     }
     
     stack
 }
 
+/// Quarkus / Micronaut 依赖识别；Vert.x 与 Mutiny 的响应式依赖同时标记 is_reactive
+fn detect_framework(stack: &mut DetectedStack, group: &str, artifact: &str) {
+    if group == "io.quarkus" || group.starts_with("io.quarkus.") || artifact.starts_with("quarkus-") {
+        stack.is_quarkus = true;
+    }
+    if group == "io.micronaut" || group.starts_with("io.micronaut.") || artifact.starts_with("micronaut-") {
+        stack.is_micronaut = true;
+    }
+    if artifact.starts_with("mutiny")
+        || matches!(artifact, "quarkus-vertx-web" | "quarkus-reactive-routes" | "quarkus-hibernate-reactive-panache")
+        || (group == "io.vertx" && artifact.starts_with("vertx-web")) {
+        stack.is_reactive = true;
+    }
+}
+
 // ============================================================================
 // Gradle Build File Parsing (Requirements 3.4)
 // ============================================================================
@@ -447,6 +803,255 @@ fn parse_dependency_string(dep_str: &str, config_str: &str) -> Option<GradleDepe
     }
 }
 
+// ============================================================================
+// Gradle 版本目录 (gradle/libs.versions.toml) 与约定插件
+// ============================================================================
+
+/// 版本目录中的一个库
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogLibrary {
+    pub group: String,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Gradle 版本目录 (`gradle/<name>.versions.toml`，默认 `libs`)
+///
+/// 别名统一存为访问器形式：`spring-boot-starter-web` / `spring_boot_starter_web`
+/// 都对应构建脚本中的 `libs.spring.boot.starter.web`。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionCatalog {
+    pub name: String,
+    pub libraries: HashMap<String, CatalogLibrary>,
+    /// 访问器形式的 bundle 名 -> 库别名 (访问器形式)
+    pub bundles: HashMap<String, Vec<String>>,
+}
+
+impl VersionCatalog {
+    /// 解析 `libs.xxx` / `libs.bundles.xxx` 引用 (不含目录名)
+    pub fn resolve(&self, accessor: &str) -> Vec<&CatalogLibrary> {
+        let accessor = accessor.trim_end_matches(".get").trim_end_matches(".asProvider");
+        if let Some(bundle) = accessor.strip_prefix("bundles.") {
+            return self.bundles.get(bundle).into_iter().flatten()
+                .filter_map(|alias| self.libraries.get(alias))
+                .collect();
+        }
+        self.libraries.get(accessor).into_iter().collect()
+    }
+}
+
+/// 别名 → 访问器形式 (`-`/`_` 分隔统一为 `.`)
+fn catalog_accessor(alias: &str) -> String {
+    alias.trim().trim_matches('"').replace(['-', '_'], ".")
+}
+
+/// 去掉 TOML 行尾注释 (引号内的 `#` 保留)
+fn strip_toml_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// 解析版本目录 TOML ([versions] / [libraries] / [bundles]，[plugins] 忽略)
+///
+/// 只支持版本目录用到的 TOML 子集：字符串、内联表、(可跨行的) 字符串数组。
+pub fn parse_version_catalog(name: &str, content: &str) -> VersionCatalog {
+    let entry = Regex::new(r#"^\s*("[^"]+"|[A-Za-z0-9_.-]+)\s*=\s*(.+)$"#).expect("valid regex");
+    let string_field = Regex::new(r#"([A-Za-z0-9_.-]+)\s*=\s*"([^"]*)""#).expect("valid regex");
+    let quoted = Regex::new(r#""([^"]+)""#).expect("valid regex");
+
+    let mut versions: HashMap<String, String> = HashMap::new();
+    let mut raw_libraries: Vec<(String, String)> = Vec::new();
+    let mut catalog = VersionCatalog { name: name.to_string(), ..Default::default() };
+
+    let mut section = String::new();
+    let mut pending: Option<(String, String)> = None;
+    for line in content.lines() {
+        let line = strip_toml_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        // 跨行数组 (bundles)
+        if let Some((key, mut value)) = pending.take() {
+            value.push_str(line);
+            if value.contains(']') {
+                catalog.bundles.insert(catalog_accessor(&key), quoted.captures_iter(&value).map(|c| catalog_accessor(&c[1])).collect());
+            } else {
+                pending = Some((key, value));
+            }
+            continue;
+        }
+        if line.starts_with('[') {
+            section = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            continue;
+        }
+        let Some(caps) = entry.captures(line) else { continue };
+        let key = caps[1].trim_matches('"').to_string();
+        let value = caps[2].trim().to_string();
+
+        match section.as_str() {
+            "versions" => {
+                let version = if value.starts_with('{') {
+                    // { strictly = "..", prefer = ".." } / { require = ".." }
+                    string_field.captures_iter(&value)
+                        .find(|c| matches!(&c[1], "require" | "strictly" | "prefer"))
+                        .map(|c| c[2].to_string())
+                } else {
+                    Some(value.trim_matches('"').to_string())
+                };
+                if let Some(version) = version {
+                    versions.insert(key, version);
+                }
+            }
+            "libraries" => raw_libraries.push((key, value)),
+            "bundles" => {
+                if value.contains(']') {
+                    catalog.bundles.insert(catalog_accessor(&key), quoted.captures_iter(&value).map(|c| catalog_accessor(&c[1])).collect());
+                } else {
+                    pending = Some((key, value));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (alias, value) in raw_libraries {
+        let library = if value.starts_with('{') {
+            let fields: HashMap<String, String> = string_field.captures_iter(&value)
+                .map(|c| (c[1].to_string(), c[2].to_string()))
+                .collect();
+            let (group, name) = match fields.get("module").and_then(|m| m.split_once(':')) {
+                Some((group, name)) => (group.to_string(), name.to_string()),
+                None => match (fields.get("group"), fields.get("name")) {
+                    (Some(group), Some(name)) => (group.clone(), name.clone()),
+                    _ => continue,
+                },
+            };
+            let version = fields.get("version.ref").and_then(|r| versions.get(r).cloned())
+                .or_else(|| fields.get("version").cloned())
+                .or_else(|| fields.iter()
+                    .find(|(k, _)| matches!(k.as_str(), "require" | "strictly" | "prefer"))
+                    .map(|(_, v)| v.clone()));
+            CatalogLibrary { group, name, version }
+        } else {
+            let notation = value.trim_matches('"');
+            let mut parts = notation.split(':');
+            let (Some(group), Some(name)) = (parts.next(), parts.next()) else { continue };
+            CatalogLibrary { group: group.to_string(), name: name.to_string(), version: parts.next().map(str::to_string) }
+        };
+        catalog.libraries.insert(catalog_accessor(&alias), library);
+    }
+    catalog
+}
+
+/// 读取 `gradle/*.versions.toml` (目录名取文件名前缀，`libs` 排在最前)
+pub fn load_version_catalogs(root: &Path) -> Vec<VersionCatalog> {
+    let mut catalogs: Vec<VersionCatalog> = fs::read_dir(root.join("gradle"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let file_name = e.file_name().to_string_lossy().to_string();
+            let name = file_name.strip_suffix(".versions.toml")?.to_string();
+            let content = fs::read_to_string(e.path()).ok()?;
+            Some(parse_version_catalog(&name, &content))
+        })
+        .collect();
+    catalogs.sort_by(|a, b| (a.name != "libs", &a.name).cmp(&(b.name != "libs", &b.name)));
+    catalogs
+}
+
+/// 提取构建脚本中对版本目录的依赖引用
+///
+/// 支持 `implementation(libs.spring.boot.starter.web)`、`api libs.bundles.web`、
+/// `implementation(platform(libs.spring.boot.bom))`，以及约定插件中的
+/// `versionCatalogs.named("libs").findLibrary("alias").get()`。
+pub fn parse_catalog_references(content: &str, catalogs: &[VersionCatalog]) -> Vec<GradleDependency> {
+    if catalogs.is_empty() {
+        return Vec::new();
+    }
+    let accessor_ref = Regex::new(
+        r#"^\s*(implementation|testImplementation|compileOnly|runtimeOnly|testCompileOnly|testRuntimeOnly|api|annotationProcessor)\s*\(?\s*(?:(?:platform|enforcedPlatform)\s*\(\s*)?([A-Za-z_]\w*)\.([A-Za-z0-9_.]+)"#
+    ).expect("valid regex");
+    let find_ref = Regex::new(
+        r#"^\s*(implementation|testImplementation|compileOnly|runtimeOnly|testCompileOnly|testRuntimeOnly|api|annotationProcessor)\s*\(?.*\.find(Library|Bundle)\(\s*"([^"]+)"\s*\)"#
+    ).expect("valid regex");
+
+    let mut dependencies = Vec::new();
+    let mut push = |config: &str, libraries: Vec<&CatalogLibrary>| {
+        let configuration: GradleConfiguration = config.parse().unwrap_or_default();
+        for lib in libraries {
+            dependencies.push(GradleDependency {
+                group: lib.group.clone(),
+                name: lib.name.clone(),
+                version: lib.version.clone(),
+                configuration: configuration.clone(),
+            });
+        }
+    };
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("");
+        if let Some(caps) = find_ref.captures(line) {
+            // 约定插件中目录通过变量获取，按默认目录优先查找
+            let accessor = catalog_accessor(&caps[3]);
+            let accessor = if &caps[2] == "Bundle" { format!("bundles.{accessor}") } else { accessor };
+            if let Some(libraries) = catalogs.iter().map(|c| c.resolve(&accessor)).find(|l| !l.is_empty()) {
+                push(&caps[1], libraries);
+            }
+        } else if let Some(caps) = accessor_ref.captures(line) {
+            if let Some(catalog) = catalogs.iter().find(|c| c.name == caps[2]) {
+                push(&caps[1], catalog.resolve(&caps[3]));
+            }
+        }
+    }
+    dependencies
+}
+
+/// 参与技术栈检测的 Gradle 构建脚本：
+/// 根脚本、settings 中 include 的子模块脚本、buildSrc / build-logic 下的约定插件
+pub(crate) fn gradle_build_scripts(root: &Path, scope: &BuildScope) -> Vec<std::path::PathBuf> {
+    let is_script = |p: &Path| {
+        let name = p.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        (name.ends_with(".gradle") || name.ends_with(".gradle.kts")) && !name.starts_with("settings.gradle")
+    };
+
+    let mut scripts: Vec<std::path::PathBuf> = ["build.gradle", "build.gradle.kts"].iter()
+        .map(|f| root.join(f))
+        .filter(|p| p.is_file())
+        .collect();
+    for module in detect_modules_in(root, scope) {
+        for file in ["build.gradle", "build.gradle.kts"] {
+            let path = root.join(&module).join(file);
+            if path.is_file() {
+                scripts.push(path);
+            }
+        }
+    }
+    for dir in ["buildSrc", "build-logic"] {
+        let base = root.join(dir);
+        if !base.is_dir() {
+            continue;
+        }
+        let found = walkdir::WalkDir::new(&base)
+            .max_depth(8)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != "build" && e.file_name() != ".gradle")
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && is_script(e.path()))
+            .map(|e| e.into_path());
+        scripts.extend(found);
+    }
+    scripts.retain(|script| scope.admits(script));
+    scripts
+}
+
 /// Filter Gradle dependencies to only include main (non-test) configurations
 pub fn filter_main_gradle_dependencies(deps: &[GradleDependency]) -> Vec<&GradleDependency> {
     deps.iter().filter(|d| d.configuration.is_main_configuration()).collect()
@@ -489,6 +1094,16 @@ pub fn detect_stack_from_gradle_deps(deps: &[GradleDependency]) -> DetectedStack
         if name == "lombok" || group == "org.projectlombok" {
             stack.has_lombok = true;
         }
+
+        // RPC 客户端 (超时审计规则门控)
+        if group == "io.grpc" || name.starts_with("grpc-") {
+            stack.has_grpc = true;
+        }
+        if group == "io.github.openfeign" || name.contains("openfeign") || name.starts_with("feign-") {
+            stack.has_feign = true;
+        }
+
+        detect_framework(&mut stack, group, name);
     }
     
     stack
@@ -502,24 +1117,149 @@ pub struct DetectedStack {
     pub is_maven: bool,
     pub is_gradle: bool,
     pub has_lombok: bool,
+    pub has_grpc: bool,         // grpc-java
+    pub has_feign: bool,        // OpenFeign / Spring Cloud OpenFeign
+    #[serde(default)]
+    pub is_quarkus: bool,
+    #[serde(default)]
+    pub is_micronaut: bool,
     pub jdk_version: String,    // "8", "11", "17", "21"
     pub build_tool: String,     // "maven" or "gradle"
+    /// 框架版本 ("spring-boot" / "quarkus" / "micronaut" -> "3.2.1")，来自 parent、BOM、Gradle 插件或依赖版本
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub framework_versions: BTreeMap<String, String>,
+}
+
+impl DetectedStack {
+    /// 应用框架名称 (Spring Boot 优先，混用时以其为准)
+    pub fn framework(&self) -> &'static str {
+        if self.is_spring_boot {
+            "Spring Boot"
+        } else if self.is_quarkus {
+            "Quarkus"
+        } else if self.is_micronaut {
+            "Micronaut"
+        } else {
+            "Java Application"
+        }
+    }
+
+    /// JDK 主版本号 ("1.8" → 8, "17" → 17, "21.0.2" → 21)
+    pub fn jdk_major(&self) -> Option<u32> {
+        let version = self.jdk_version.trim();
+        let major = match version.strip_prefix("1.") {
+            Some(rest) => rest.split('.').next()?,
+            None => version.split('.').next()?,
+        };
+        major.parse().ok()
+    }
+
+    /// 框架的 (主版本, 次版本) ("3.2.1" → (3, 2)，"2.7.18.RELEASE" → (2, 7))；未检测到时为 None
+    pub fn framework_version(&self, framework: &str) -> Option<(u32, u32)> {
+        let mut parts = self.framework_versions.get(framework)?.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor: String = parts.next().unwrap_or("0").chars().take_while(char::is_ascii_digit).collect();
+        Some((major, minor.parse().unwrap_or(0)))
+    }
+}
+
+/// 框架版本来源: (版本键, Maven parent/BOM 坐标, Gradle 插件 ID, 依赖 groupId)
+///
+/// 依次取 parent/BOM、插件、同 groupId 依赖的版本；Micronaut 的 Gradle 插件版本与框架版本不一致，不作为来源。
+const FRAMEWORK_SOURCES: &[(&str, &[&str], Option<&str>, &str)] = &[
+    ("spring-boot", &["org.springframework.boot:spring-boot-starter-parent", "org.springframework.boot:spring-boot-dependencies"],
+        Some("org.springframework.boot"), "org.springframework.boot"),
+    ("quarkus", &["io.quarkus:quarkus-bom", "io.quarkus.platform:quarkus-bom"], Some("io.quarkus"), "io.quarkus"),
+    ("micronaut", &["io.micronaut.platform:micronaut-parent", "io.micronaut:micronaut-parent", "io.micronaut.platform:micronaut-platform", "io.micronaut:micronaut-bom"],
+        None, "io.micronaut"),
+];
+
+/// 版本号以数字开头 (排除未解析的 `${...}` 占位符)
+fn is_concrete_version(version: &str) -> bool {
+    version.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// 从 Maven 有效模型提取框架版本
+fn maven_framework_versions(effective: &EffectivePom) -> BTreeMap<String, String> {
+    let mut versions = BTreeMap::new();
+    for (framework, platforms, _, group) in FRAMEWORK_SOURCES {
+        let from_platform = platforms.iter()
+            .find_map(|coord| effective.platforms.get(*coord).or_else(|| effective.managed_versions.get(*coord)));
+        let from_dependency = || effective.dependencies.iter()
+            .filter(|d| d.group_id == *group && d.scope.is_main_scope())
+            .find_map(|d| d.version.as_ref());
+        if let Some(version) = from_platform.or_else(from_dependency).filter(|v| is_concrete_version(v)) {
+            versions.insert(framework.to_string(), version.clone());
+        }
+    }
+    versions
+}
+
+/// 从 Gradle 构建脚本 (插件声明、platform/依赖版本) 与 gradle.properties 提取框架版本
+fn gradle_framework_versions(content: &str, deps: &[GradleDependency], properties: &HashMap<String, String>) -> BTreeMap<String, String> {
+    let mut versions = BTreeMap::new();
+    for (framework, platforms, plugin, group) in FRAMEWORK_SOURCES {
+        let from_platform = || deps.iter()
+            .filter(|d| platforms.contains(&format!("{}:{}", d.group, d.name).as_str()))
+            .find_map(|d| d.version.clone());
+        let from_plugin = || plugin.and_then(|id| {
+            let pattern = format!(r#"id\s*\(?\s*["']{}["']\s*\)?\s*version\s*\(?\s*["']([^"']+)["']"#, regex::escape(id));
+            Regex::new(&pattern).ok()?.captures(content).map(|c| c[1].to_string())
+        });
+        let from_dependency = || deps.iter()
+            .filter(|d| d.group == *group && d.configuration.is_main_configuration())
+            .find_map(|d| d.version.clone());
+        let version = from_platform().or_else(from_plugin).or_else(from_dependency)
+            .map(|v| resolve_gradle_property(&v, properties));
+        if let Some(version) = version.filter(|v| is_concrete_version(v)) {
+            versions.insert(framework.to_string(), version);
+        }
+    }
+    versions
+}
+
+/// `$springBootVersion` / `${springBootVersion}` 从 gradle.properties 取值
+fn resolve_gradle_property(version: &str, properties: &HashMap<String, String>) -> String {
+    let key = version.trim_start_matches('$').trim_start_matches('{').trim_end_matches('}');
+    match version.starts_with('$') {
+        true => properties.get(key).cloned().unwrap_or_else(|| version.to_string()),
+        false => version.to_string(),
+    }
+}
+
+/// 读取 gradle.properties 的 key=value
+fn gradle_properties(root: &Path, scope: &BuildScope) -> HashMap<String, String> {
+    let path = root.join("gradle.properties");
+    let Some(content) = scope.admits(&path).then(|| fs::read_to_string(&path).ok()).flatten() else {
+        return HashMap::new();
+    };
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
 }
 
 
 /// 扫描项目目录，检测技术栈
 pub fn detect_stack(root: &Path) -> DetectedStack {
+    detect_stack_in(root, &BuildScope::default())
+}
+
+/// 同 [`detect_stack`]，只读取 scope 内的构建文件
+pub fn detect_stack_in(root: &Path, scope: &BuildScope) -> DetectedStack {
     let mut stack = DetectedStack::default();
     
     // 1. 检测构建工具
     if root.join("pom.xml").exists() {
         stack.is_maven = true;
         stack.build_tool = "maven".to_string();
-        analyze_maven(root, &mut stack);
-    } else if root.join("build.gradle").exists() || root.join("build.gradle.kts").exists() {
+        analyze_maven(root, scope, &mut stack);
+    } else if ["build.gradle", "build.gradle.kts", "settings.gradle", "settings.gradle.kts"].iter().any(|f| root.join(f).exists()) {
         stack.is_gradle = true;
         stack.build_tool = "gradle".to_string();
-        analyze_gradle(root, &mut stack);
+        analyze_gradle(root, scope, &mut stack);
     }
     
     // 2. 默认值兜底
@@ -530,18 +1270,30 @@ pub fn detect_stack(root: &Path) -> DetectedStack {
     stack
 }
 
-fn analyze_maven(root: &Path, stack: &mut DetectedStack) {
-    if let Ok(content) = fs::read_to_string(root.join("pom.xml")) {
+fn analyze_maven(root: &Path, scope: &BuildScope, stack: &mut DetectedStack) {
+    let pom_path = root.join("pom.xml");
+    if let Some(content) = scope.admits(&pom_path).then(|| fs::read_to_string(&pom_path).ok()).flatten() {
+        // 有效依赖: 合并 parent 继承的依赖，版本经属性插值与 dependencyManagement/BOM 补全
+        let effective = scope.maven_resolver().resolve(&pom_path);
+
         // Use structured XML parsing to extract dependencies (Requirements 3.1, 3.2)
         // This properly filters out test-scoped and commented dependencies
-        match parse_maven_pom(&content) {
-            Ok(deps) => {
+        match effective {
+            Ok(effective) => {
                 // Detect stack from parsed dependencies (excludes test scope)
-                let detected = detect_stack_from_maven_deps(&deps);
+                let detected = detect_stack_from_maven_deps(&effective.dependencies);
                 stack.is_spring_boot = detected.is_spring_boot;
                 stack.is_spring_mvc = detected.is_spring_mvc;
                 stack.is_reactive = detected.is_reactive;
                 stack.has_lombok = detected.has_lombok;
+                stack.has_grpc = detected.has_grpc;
+                stack.has_feign = detected.has_feign;
+                stack.is_quarkus = detected.is_quarkus;
+                stack.is_micronaut = detected.is_micronaut;
+                if let Some(jdk) = effective.jdk_version() {
+                    stack.jdk_version = jdk;
+                }
+                stack.framework_versions = maven_framework_versions(&effective);
             }
             Err(_) => {
                 // Fall back to simple string matching if XML parsing fails
@@ -558,34 +1310,51 @@ fn analyze_maven(root: &Path, stack: &mut DetectedStack) {
                 if content.contains("lombok") {
                     stack.has_lombok = true;
                 }
+                stack.has_grpc = content.contains("<groupId>io.grpc</groupId>");
+                stack.has_feign = content.contains("feign");
+                stack.is_quarkus = content.contains("<groupId>io.quarkus");
+                stack.is_micronaut = content.contains("<groupId>io.micronaut");
             }
         }
         
-        // Extract JDK version from properties (still use string matching as it's in <properties>)
-        if content.contains("<java.version>17") || content.contains("<target>17") {
-            stack.jdk_version = "17".to_string();
-        } else if content.contains("<java.version>21") || content.contains("<target>21") {
-            stack.jdk_version = "21".to_string();
-        } else if content.contains("<java.version>11") || content.contains("<target>11") {
-            stack.jdk_version = "11".to_string();
+        // JDK 版本无法从 (继承后的) properties 解析时，退化为字符串匹配 (如 compiler plugin 的 <target>)
+        if stack.jdk_version.is_empty() {
+            if content.contains("<java.version>17") || content.contains("<target>17") {
+                stack.jdk_version = "17".to_string();
+            } else if content.contains("<java.version>21") || content.contains("<target>21") {
+                stack.jdk_version = "21".to_string();
+            } else if content.contains("<java.version>11") || content.contains("<target>11") {
+                stack.jdk_version = "11".to_string();
+            }
         }
     }
 }
 
-fn analyze_gradle(root: &Path, stack: &mut DetectedStack) {
-    let gradle_files = ["build.gradle", "build.gradle.kts"];
-    for file in gradle_files {
-        if let Ok(content) = fs::read_to_string(root.join(file)) {
+fn analyze_gradle(root: &Path, scope: &BuildScope, stack: &mut DetectedStack) {
+    let catalogs = load_version_catalogs(root);
+    let properties = gradle_properties(root, scope);
+    for script in gradle_build_scripts(root, scope) {
+        if let Ok(content) = fs::read_to_string(&script) {
             // Use structured parsing to extract dependencies (Requirements 3.4)
             // This properly filters out testImplementation and other test configurations
             match parse_gradle_build(&content) {
-                Ok(deps) => {
+                Ok(mut deps) => {
+                    // 版本目录引用 (libs.xxx) 解析为实际坐标
+                    deps.extend(parse_catalog_references(&content, &catalogs));
                     // Detect stack from parsed dependencies (excludes test configurations)
                     let detected = detect_stack_from_gradle_deps(&deps);
                     stack.is_spring_boot = stack.is_spring_boot || detected.is_spring_boot;
                     stack.is_spring_mvc = stack.is_spring_mvc || detected.is_spring_mvc;
                     stack.is_reactive = stack.is_reactive || detected.is_reactive;
                     stack.has_lombok = stack.has_lombok || detected.has_lombok;
+                    stack.has_grpc = stack.has_grpc || detected.has_grpc;
+                    stack.has_feign = stack.has_feign || detected.has_feign;
+                    stack.is_quarkus = stack.is_quarkus || detected.is_quarkus;
+                    stack.is_micronaut = stack.is_micronaut || detected.is_micronaut;
+                    // 根构建脚本在前，先检测到的版本优先
+                    for (framework, version) in gradle_framework_versions(&content, &deps, &properties) {
+                        stack.framework_versions.entry(framework).or_insert(version);
+                    }
                 }
                 Err(_) => {
                     // Fall back to simple string matching if parsing fails
@@ -596,6 +1365,18 @@ fn analyze_gradle(root: &Path, stack: &mut DetectedStack) {
                     if content.contains("webflux") || content.contains("reactor") {
                         stack.is_reactive = true;
                     }
+                    if content.contains("io.grpc") {
+                        stack.has_grpc = true;
+                    }
+                    if content.contains("feign") {
+                        stack.has_feign = true;
+                    }
+                    if content.contains("io.quarkus") {
+                        stack.is_quarkus = true;
+                    }
+                    if content.contains("io.micronaut") {
+                        stack.is_micronaut = true;
+                    }
                 }
             }
             
@@ -609,12 +1390,119 @@ fn analyze_gradle(root: &Path, stack: &mut DetectedStack) {
     }
 }
 
+// ============================================================================
+// 多模块布局 (Maven <modules> / Gradle settings include)
+// ============================================================================
+
+/// 解析 pom.xml 中的 `<modules><module>` 声明 (含 profile 内声明，去重保序)
+pub fn parse_maven_modules(content: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut modules: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+    let mut in_modules = false;
+    let mut in_module = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name().as_ref() {
+                b"modules" => in_modules = true,
+                b"module" if in_modules => in_module = true,
+                _ => {}
+            },
+            Ok(Event::End(ref e)) => match e.name().as_ref() {
+                b"modules" => in_modules = false,
+                b"module" => in_module = false,
+                _ => {}
+            },
+            Ok(Event::Text(ref e)) if in_module => {
+                if let Ok(text) = e.unescape() {
+                    let module = text.trim().trim_end_matches('/').to_string();
+                    if !module.is_empty() && !modules.contains(&module) {
+                        modules.push(module);
+                    }
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    modules
+}
+
+/// 解析 settings.gradle(.kts) 中的 include 声明 (`':app:core'` → `app/core`)
+pub fn parse_gradle_includes(content: &str) -> Vec<String> {
+    let quoted = Regex::new(r#"["']([^"']+)["']"#).expect("valid regex");
+    let mut modules: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let Some(args) = line.strip_prefix("include") else { continue };
+        // includeBuild / includeFlat 不是子模块
+        if !args.starts_with([' ', '(', '\t']) {
+            continue;
+        }
+        for caps in quoted.captures_iter(args) {
+            let module = caps[1].trim_start_matches(':').replace(':', "/");
+            if !module.is_empty() && !modules.contains(&module) {
+                modules.push(module);
+            }
+        }
+    }
+    modules
+}
+
+/// 检测多模块项目的子模块目录 (相对根目录，'/' 分隔)；单模块项目返回空
+///
+/// Maven 聚合 pom 递归展开 (`parent/child`)；Gradle 读取 settings.gradle(.kts)。
+pub fn detect_modules(root: &Path) -> Vec<String> {
+    detect_modules_in(root, &BuildScope::default())
+}
+
+/// 同 [`detect_modules`]，忽略 scope 之外的模块目录
+pub fn detect_modules_in(root: &Path, scope: &BuildScope) -> Vec<String> {
+    fn maven(dir: &Path, prefix: &str, scope: &BuildScope, modules: &mut Vec<String>) {
+        let pom = dir.join("pom.xml");
+        let Some(content) = scope.admits(&pom).then(|| fs::read_to_string(&pom).ok()).flatten() else { return };
+        for module in parse_maven_modules(&content) {
+            let path = if prefix.is_empty() { module.clone() } else { format!("{prefix}/{module}") };
+            if modules.contains(&path) || !scope.admits(&dir.join(&module)) {
+                continue;
+            }
+            modules.push(path.clone());
+            maven(&dir.join(&module), &path, scope, modules);
+        }
+    }
+
+    let mut modules = Vec::new();
+    if root.join("pom.xml").exists() {
+        maven(root, "", scope, &mut modules);
+    } else {
+        for settings in ["settings.gradle", "settings.gradle.kts"] {
+            let path = root.join(settings);
+            if let Some(content) = scope.admits(&path).then(|| fs::read_to_string(&path).ok()).flatten() {
+                modules.extend(parse_gradle_includes(&content).into_iter().filter(|m| scope.admits(&root.join(m))));
+            }
+        }
+    }
+    modules
+}
+
 /// 根据检测到的技术栈生成分析指导策略
 pub fn generate_strategy_hint(stack: &DetectedStack) -> String {
     let mut hints = Vec::new();
     
-    hints.push(format!("Project Type: {} (JDK {})", 
-        if stack.is_spring_boot { "Spring Boot" } else { "Java Application" }, 
+    let version = match stack.framework() {
+        "Spring Boot" => stack.framework_versions.get("spring-boot"),
+        "Quarkus" => stack.framework_versions.get("quarkus"),
+        "Micronaut" => stack.framework_versions.get("micronaut"),
+        _ => None,
+    };
+    hints.push(format!("Project Type: {}{} (JDK {})", 
+        stack.framework(), 
+        version.map(|v| format!(" {v}")).unwrap_or_default(),
         stack.jdk_version
     ));
     
@@ -631,9 +1519,20 @@ pub fn generate_strategy_hint(stack: &DetectedStack) -> String {
         }
     }
     
+    if !stack.is_spring_boot && (stack.is_quarkus || stack.is_micronaut) {
+        hints.push(format!("- **{}**: CDI-style beans (`@ApplicationScoped`/`@Singleton` + `@Inject`); JAX-RS `@Path` / Micronaut `@Controller` are entry points. Watch blocking calls on the Vert.x/Netty event loop.", stack.framework()));
+        if stack.is_quarkus {
+            hints.push("- **Panache**: `PanacheRepository` / `PanacheEntity` queries inside loops are N+1 just like Spring Data.".to_string());
+        }
+    }
+
     if stack.has_lombok {
         hints.push("- **Lombok**: Be aware of generated code (equals/hashCode) performance impacts.".to_string());
     }
+
+    if stack.has_grpc || stack.has_feign {
+        hints.push("- **RPC Clients**: Verify every gRPC stub call sets a deadline and Feign builders configure `Request.Options`.".to_string());
+    }
     
     hints.join("\n")
 }
@@ -641,6 +1540,16 @@ pub fn generate_strategy_hint(stack: &DetectedStack) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_jdk_major() {
+        let mut stack = DetectedStack::default();
+        assert_eq!(stack.jdk_major(), None);
+        for (version, expected) in [("1.8", 8), ("8", 8), ("17", 17), ("21.0.2", 21)] {
+            stack.jdk_version = version.to_string();
+            assert_eq!(stack.jdk_major(), Some(expected), "version {version}");
+        }
+    }
     use std::fs::File;
     use std::io::Write;
     use tempfile::tempdir;
@@ -703,6 +1612,13 @@ mod tests {
             if artifact == "lombok" || group == "org.projectlombok" {
                 stack.has_lombok = true;
             }
+            if group == "io.grpc" || artifact.starts_with("grpc-") {
+                stack.has_grpc = true;
+            }
+            if group == "io.github.openfeign" || artifact.contains("openfeign") || artifact.starts_with("feign-") {
+                stack.has_feign = true;
+            }
+            detect_framework(&mut stack, group, artifact);
         }
         
         stack
@@ -726,6 +1642,342 @@ mod tests {
         stack
     }
 
+    #[test]
+    fn test_detect_modules() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("pom.xml"), r#"
+            <project>
+                <modules>
+                    <module>common</module>
+                    <module>services/</module>
+                    <!-- <module>legacy</module> -->
+                </modules>
+            </project>
+        "#).unwrap();
+        std::fs::create_dir_all(dir.path().join("services")).unwrap();
+        std::fs::write(dir.path().join("services/pom.xml"),
+            "<project><modules><module>order</module><module>payment</module></modules></project>").unwrap();
+        assert_eq!(detect_modules(dir.path()), vec!["common", "services", "services/order", "services/payment"]);
+
+        let includes = parse_gradle_includes(r#"
+            rootProject.name = 'shop'
+            include 'api', ':app:core'
+            include("web")
+            includeBuild("build-logic")
+            // include 'legacy'
+        "#);
+        assert_eq!(includes, vec!["api", "app/core", "web"]);
+    }
+
+    #[test]
+    fn test_parse_maven_model() {
+        let pom = parse_maven_model(r#"
+            <project>
+                <parent>
+                    <groupId>com.shop</groupId>
+                    <artifactId>shop-parent</artifactId>
+                    <version>1.0.0</version>
+                    <relativePath/>
+                </parent>
+                <artifactId>order</artifactId>
+                <properties><grpc.version>1.60.0</grpc.version></properties>
+                <dependencyManagement><dependencies>
+                    <dependency>
+                        <groupId>org.springframework.boot</groupId>
+                        <artifactId>spring-boot-dependencies</artifactId>
+                        <version>3.2.1</version>
+                        <type>pom</type>
+                        <scope>import</scope>
+                    </dependency>
+                </dependencies></dependencyManagement>
+                <dependencies>
+                    <dependency><groupId>io.grpc</groupId><artifactId>grpc-stub</artifactId><version>${grpc.version}</version></dependency>
+                </dependencies>
+                <build><plugins><plugin><dependencies>
+                    <dependency><groupId>org.ow2.asm</groupId><artifactId>asm</artifactId></dependency>
+                </dependencies></plugin></plugins></build>
+            </project>
+        "#).unwrap();
+        let parent = pom.parent.unwrap();
+        assert_eq!((parent.artifact_id.as_str(), parent.relative_path.as_deref()), ("shop-parent", Some("")));
+        assert_eq!(pom.properties["grpc.version"], "1.60.0");
+        assert_eq!(pom.managed.len(), 1);
+        assert_eq!(pom.managed[0].scope, DependencyScope::Import);
+        assert_eq!(pom.dependencies.len(), 1, "plugin 依赖不是项目依赖");
+    }
+
+    #[test]
+    fn test_resolve_parent_properties_and_bom() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        // 本地仓库中的 Spring Boot BOM (BOM 自身通过 parent + 属性声明版本)
+        let bom_dir = root.join("m2/org/springframework/boot/spring-boot-dependencies/3.2.1");
+        std::fs::create_dir_all(&bom_dir).unwrap();
+        std::fs::write(bom_dir.join("spring-boot-dependencies-3.2.1.pom"), r#"
+            <project>
+                <groupId>org.springframework.boot</groupId>
+                <artifactId>spring-boot-dependencies</artifactId>
+                <version>3.2.1</version>
+                <properties><lombok.version>1.18.30</lombok.version></properties>
+                <dependencyManagement><dependencies>
+                    <dependency><groupId>org.springframework.boot</groupId><artifactId>spring-boot-starter-web</artifactId><version>${project.version}</version></dependency>
+                    <dependency><groupId>org.projectlombok</groupId><artifactId>lombok</artifactId><version>${lombok.version}</version></dependency>
+                </dependencies></dependencyManagement>
+            </project>
+        "#).unwrap();
+
+        std::fs::create_dir_all(root.join("app/order")).unwrap();
+        std::fs::write(root.join("app/pom.xml"), r#"
+            <project>
+                <groupId>com.shop</groupId>
+                <artifactId>shop-parent</artifactId>
+                <version>2.0.0</version>
+                <properties>
+                    <java.version>21</java.version>
+                    <lombok.version>1.18.32</lombok.version>
+                </properties>
+                <dependencyManagement><dependencies>
+                    <dependency>
+                        <groupId>org.springframework.boot</groupId>
+                        <artifactId>spring-boot-dependencies</artifactId>
+                        <version>3.2.1</version>
+                        <type>pom</type>
+                        <scope>import</scope>
+                    </dependency>
+                    <dependency><groupId>org.projectlombok</groupId><artifactId>lombok</artifactId><version>${lombok.version}</version></dependency>
+                </dependencies></dependencyManagement>
+                <dependencies>
+                    <dependency><groupId>org.projectlombok</groupId><artifactId>lombok</artifactId><scope>provided</scope></dependency>
+                </dependencies>
+            </project>
+        "#).unwrap();
+        std::fs::write(root.join("app/order/pom.xml"), r#"
+            <project>
+                <parent>
+                    <groupId>com.shop</groupId>
+                    <artifactId>shop-parent</artifactId>
+                    <version>2.0.0</version>
+                </parent>
+                <artifactId>order</artifactId>
+                <dependencies>
+                    <dependency><groupId>org.springframework.boot</groupId><artifactId>spring-boot-starter-web</artifactId></dependency>
+                    <dependency><groupId>${project.groupId}</groupId><artifactId>common</artifactId><version>${project.version}</version></dependency>
+                    <dependency><groupId>io.grpc</groupId><artifactId>grpc-stub</artifactId><version>${grpc.version}</version></dependency>
+                </dependencies>
+            </project>
+        "#).unwrap();
+
+        let effective = MavenResolver::new(Some(root.join("m2"))).resolve(&root.join("app/order/pom.xml")).unwrap();
+        let versions: Vec<_> = effective.dependencies.iter()
+            .map(|d| (d.artifact_id.as_str(), d.version.as_deref()))
+            .collect();
+        assert_eq!(versions, vec![
+            ("lombok", Some("1.18.32")),                  // 继承自 parent，显式管理版本优先于 BOM
+            ("spring-boot-starter-web", Some("3.2.1")),   // BOM 导入
+            ("common", Some("2.0.0")),                    // project.* 内置属性 (继承 parent 的 groupId/version)
+            ("grpc-stub", None),                          // 未定义属性不猜测
+        ]);
+        assert_eq!(effective.dependencies[2].group_id, "com.shop");
+        assert_eq!(effective.jdk_version().as_deref(), Some("21"));
+
+        // 无本地仓库时 BOM 管理的版本保持 None
+        let offline = MavenResolver::new(None).resolve(&root.join("app/order/pom.xml")).unwrap();
+        assert_eq!(offline.dependencies[1].version, None);
+
+        let stack = detect_stack(&root.join("app/order"));
+        assert!(stack.is_spring_mvc && stack.has_lombok && stack.has_grpc);
+        assert_eq!(stack.jdk_version, "21");
+
+        // 限制在模块目录内 (--safe-mode)：不读取 ../pom.xml 与本地仓库
+        let scope = BuildScope::confined(&root.join("app/order"));
+        assert!(!scope.admits(&root.join("app/pom.xml")) && scope.admits(&root.join("app/order/pom.xml")));
+        let confined = scope.maven_resolver().resolve(&root.join("app/order/pom.xml")).unwrap();
+        let artifacts: Vec<&str> = confined.dependencies.iter().map(|d| d.artifact_id.as_str()).collect();
+        assert_eq!(artifacts, ["spring-boot-starter-web", "common", "grpc-stub"]);
+        let stack = detect_stack_in(&root.join("app/order"), &scope);
+        assert!(stack.is_spring_mvc && !stack.has_lombok);
+    }
+
+    #[test]
+    fn test_parse_version_catalog() {
+        let catalog = parse_version_catalog("libs", r#"
+[versions]
+spring-boot = "3.2.1"
+grpc = { strictly = "1.60.0" }
+
+[libraries]
+spring-boot-starter-webflux = { module = "org.springframework.boot:spring-boot-starter-webflux", version.ref = "spring-boot" }
+grpc_stub = { group = "io.grpc", name = "grpc-stub", version.ref = "grpc" }
+lombok = "org.projectlombok:lombok:1.18.30"  # comment
+"jackson-databind" = { module = "com.fasterxml.jackson.core:jackson-databind" }
+
+[bundles]
+rpc = [
+    "grpc-stub",
+    "jackson-databind",
+]
+
+[plugins]
+spring-boot = { id = "org.springframework.boot", version.ref = "spring-boot" }
+"#);
+        let webflux = &catalog.libraries["spring.boot.starter.webflux"];
+        assert_eq!((webflux.name.as_str(), webflux.version.as_deref()), ("spring-boot-starter-webflux", Some("3.2.1")));
+        assert_eq!(catalog.libraries["grpc.stub"].version.as_deref(), Some("1.60.0"));
+        assert_eq!(catalog.libraries["lombok"].version.as_deref(), Some("1.18.30"));
+        assert_eq!(catalog.libraries["jackson.databind"].version, None);
+        let rpc: Vec<_> = catalog.resolve("bundles.rpc").iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(rpc, vec!["grpc-stub", "jackson-databind"]);
+        assert_eq!(catalog.libraries.len(), 4, "plugins 不是依赖");
+    }
+
+    #[test]
+    fn test_parse_catalog_references() {
+        let catalog = parse_version_catalog("libs", r#"
+[libraries]
+spring-boot-bom = "org.springframework.boot:spring-boot-dependencies:3.2.1"
+spring-boot-starter-web = { module = "org.springframework.boot:spring-boot-starter-web" }
+reactor-test = "io.projectreactor:reactor-test:3.6.0"
+feign-core = "io.github.openfeign:feign-core:13.1"
+"#);
+        let deps = parse_catalog_references(r#"
+dependencies {
+    implementation(platform(libs.spring.boot.bom))
+    implementation(libs.spring.boot.starter.web)
+    testImplementation libs.reactor.test
+    // implementation(libs.feign.core)
+    implementation(project(":core"))
+    api(versionCatalog.findLibrary("feign-core").get())
+}
+"#, &[catalog]);
+        let names: Vec<_> = deps.iter().map(|d| (d.name.as_str(), &d.configuration)).collect();
+        assert_eq!(names, vec![
+            ("spring-boot-dependencies", &GradleConfiguration::Implementation),
+            ("spring-boot-starter-web", &GradleConfiguration::Implementation),
+            ("reactor-test", &GradleConfiguration::TestImplementation),
+            ("feign-core", &GradleConfiguration::Api),
+        ]);
+    }
+
+    #[test]
+    fn test_detect_stack_gradle_catalog_and_convention_plugins() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        // 根目录只有 settings，依赖分散在子模块与 buildSrc 约定插件中
+        std::fs::write(root.join("settings.gradle.kts"), "include(\":app\")\n").unwrap();
+        std::fs::create_dir_all(root.join("gradle")).unwrap();
+        std::fs::write(root.join("gradle/libs.versions.toml"), r#"
+[libraries]
+spring-boot-starter-webflux = "org.springframework.boot:spring-boot-starter-webflux:3.2.1"
+lombok = "org.projectlombok:lombok:1.18.30"
+"#).unwrap();
+        std::fs::create_dir_all(root.join("app")).unwrap();
+        std::fs::write(root.join("app/build.gradle.kts"), "dependencies {\n    implementation(libs.spring.boot.starter.webflux)\n}\n").unwrap();
+        std::fs::create_dir_all(root.join("buildSrc/src/main/kotlin")).unwrap();
+        std::fs::write(root.join("buildSrc/src/main/kotlin/java-conventions.gradle.kts"),
+            "val libs = versionCatalogs.named(\"libs\")\ndependencies {\n    compileOnly(libs.findLibrary(\"lombok\").get())\n}\n").unwrap();
+
+        let stack = detect_stack(root);
+        assert!(stack.is_gradle);
+        assert!(stack.is_spring_boot && stack.is_reactive);
+        assert!(stack.has_lombok);
+        assert_eq!(stack.framework_versions.get("spring-boot").map(String::as_str), Some("3.2.1"));
+    }
+
+    #[test]
+    fn test_detect_framework_versions() {
+        // parent 不在本地也记录其版本
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("pom.xml"), r#"<project>
+            <parent>
+                <groupId>org.springframework.boot</groupId>
+                <artifactId>spring-boot-starter-parent</artifactId>
+                <version>2.7.18</version>
+            </parent>
+            <artifactId>app</artifactId>
+            <dependencies>
+                <dependency><groupId>org.springframework.boot</groupId><artifactId>spring-boot-starter-web</artifactId></dependency>
+            </dependencies>
+        </project>"#).unwrap();
+        let stack = detect_stack(dir.path());
+        assert_eq!(stack.framework_version("spring-boot"), Some((2, 7)));
+        assert!(generate_strategy_hint(&stack).contains("Spring Boot 2.7.18 (JDK"));
+
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("pom.xml"), r#"<project>
+            <artifactId>app</artifactId>
+            <properties><quarkus.platform.version>3.6.4</quarkus.platform.version></properties>
+            <dependencyManagement><dependencies><dependency>
+                <groupId>io.quarkus.platform</groupId><artifactId>quarkus-bom</artifactId>
+                <version>${quarkus.platform.version}</version><type>pom</type><scope>import</scope>
+            </dependency></dependencies></dependencyManagement>
+            <dependencies><dependency><groupId>io.quarkus</groupId><artifactId>quarkus-rest</artifactId></dependency></dependencies>
+        </project>"#).unwrap();
+        assert_eq!(detect_stack(dir.path()).framework_versions.get("quarkus").map(String::as_str), Some("3.6.4"));
+
+        // Gradle 插件版本取自 gradle.properties；未解析的占位符不记录
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("gradle.properties"), "# versions\nspringBootVersion=3.2.0\n").unwrap();
+        std::fs::write(dir.path().join("build.gradle.kts"), r#"
+            plugins {
+                id("org.springframework.boot") version "${springBootVersion}"
+            }
+            dependencies {
+                implementation("org.springframework.boot:spring-boot-starter-web")
+                implementation("io.micronaut:micronaut-http:${micronautVersion}")
+            }
+        "#).unwrap();
+        let stack = detect_stack(dir.path());
+        assert_eq!(stack.framework_version("spring-boot"), Some((3, 2)));
+        assert!(!stack.framework_versions.contains_key("micronaut"));
+        assert_eq!(DetectedStack::default().framework_version("spring-boot"), None);
+    }
+
+    #[test]
+    fn test_detect_rpc_clients() {
+        let deps = vec![
+            MavenDependency::new("io.grpc", "grpc-stub"),
+            MavenDependency::new("io.github.openfeign", "feign-core").with_scope(DependencyScope::Test),
+        ];
+        let stack = detect_stack_from_maven_deps(&deps);
+        assert!(stack.has_grpc);
+        assert!(!stack.has_feign, "test-scoped Feign should be ignored");
+
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("build.gradle"), r#"
+            dependencies {
+                implementation 'org.springframework.cloud:spring-cloud-starter-openfeign:4.1.0'
+            }
+        "#).unwrap();
+        let stack = detect_stack(dir.path());
+        assert!(stack.has_feign && !stack.has_grpc);
+        assert!(generate_strategy_hint(&stack).contains("Request.Options"));
+    }
+
+    #[test]
+    fn test_detect_quarkus_and_micronaut() {
+        let deps = vec![
+            MavenDependency::new("io.quarkus", "quarkus-hibernate-orm-panache"),
+            MavenDependency::new("io.quarkus", "quarkus-resteasy-reactive"),
+            MavenDependency::new("io.micronaut.test", "micronaut-test-junit5").with_scope(DependencyScope::Test),
+        ];
+        let stack = detect_stack_from_maven_deps(&deps);
+        assert!(stack.is_quarkus && !stack.is_micronaut && !stack.is_spring_boot);
+        assert!(!stack.is_reactive, "RESTEasy Reactive 端点默认运行在 worker 线程");
+        assert_eq!(stack.framework(), "Quarkus");
+
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("build.gradle.kts"), r#"
+            dependencies {
+                implementation("io.micronaut:micronaut-http-server-netty")
+                implementation("io.micronaut.data:micronaut-data-jdbc")
+            }
+        "#).unwrap();
+        let stack = detect_stack(dir.path());
+        assert!(stack.is_micronaut && !stack.is_quarkus);
+        let hint = generate_strategy_hint(&stack);
+        assert!(hint.contains("Project Type: Micronaut") && hint.contains("@Controller"), "{hint}");
+    }
+
     #[test]
     fn test_detect_spring_boot_maven() {
         let dir = tempdir().unwrap();
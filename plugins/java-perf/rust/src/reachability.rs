@@ -0,0 +1,172 @@
+//! 类可达性裁剪 (`scan --entrypoints` / `.javaperf.toml` 的 `[reachability]`)
+//!
+//! 跨文件的深度分析 (沿调用图追踪的规则、静态初始化成环、跨文件常量条件) 的开销随项目规模增长，
+//! 而离线工具、生成代码、废弃模块中的结果通常无人处理。配置入口类后，先从入口沿 CallGraph 做
+//! 类级 BFS (接收者展开到实现类的方式同 hotness)，深度分析只在可达类所在的文件上进行:
+//!
+//! | 分析 | 不可达文件上 |
+//! |------|-------------|
+//! | [`DEEP_RULES`] (持锁调用链、并行流阻塞追踪、切面覆盖范围) | 不运行 |
+//! | 静态初始化依赖 (STATIC_INIT_CYCLE / STATIC_INIT_HEAVY) | 不收集 |
+//! | 常量条件的不可达分支 (dead_code) | 只用本文件的常量，不经 SymbolTable 跨文件解析 |
+//!
+//! 其余规则照常运行。`--exhaustive` 忽略入口配置，对全部文件执行深度分析。
+//!
+//! ```toml
+//! [reachability]
+//! entrypoints = ["*Controller", "com.example.job.*", "Application"]
+//! ```
+//!
+//! 入口为类的简单名或全限定名，可使用 `*` 通配；某个入口未匹配任何类时报错，避免拼写错误让裁剪范围悄悄变小。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+
+use crate::hotness;
+use crate::shared_path::FilePath;
+use crate::symbol_table::SymbolTable;
+use crate::taint::{CallGraph, CallSite};
+
+/// 沿调用图跨文件追踪、只在可达文件上运行的规则
+pub const DEEP_RULES: &[&str] = &["HELD_LOCK_BLOCKING", "PARALLEL_STREAM_BLOCKING", "AOP_HOT_PATH"];
+
+/// 从入口类可达的类集合
+#[derive(Debug, Default)]
+pub struct ClassReachability {
+    /// 可达类 (简单名)
+    classes: HashSet<String>,
+    /// 可达类所在的源文件
+    files: HashSet<FilePath>,
+    entrypoints: Vec<String>,
+    /// 入口匹配到的类数
+    seeds: usize,
+    total_classes: usize,
+}
+
+/// 报告中的可达性裁剪摘要
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ReachabilitySummary {
+    pub entrypoints: Vec<String>,
+    /// 入口匹配到的类数
+    pub seeds: usize,
+    /// 可达类数 (含入口)
+    pub classes: usize,
+    /// 索引中的类数
+    pub total_classes: usize,
+    /// 执行深度分析的 Java 文件数
+    pub files: usize,
+}
+
+impl ClassReachability {
+    /// 从匹配入口的类出发做类级 BFS；入口模式无效或未匹配任何类时返回错误
+    pub fn build(graph: &CallGraph, table: &SymbolTable, entrypoints: &[String]) -> Result<Self, String> {
+        let patterns = compile(entrypoints)?;
+        let simple = |name: &str| name.rsplit('.').next().unwrap_or(name).to_string();
+
+        // 调用方类 (简单名) -> 调用点
+        let mut calls: HashMap<String, Vec<&CallSite>> = HashMap::new();
+        for (sig, sites) in &graph.outgoing {
+            calls.entry(sig.simple_class_name().to_string()).or_default().extend(sites);
+        }
+
+        let mut reach = Self { entrypoints: entrypoints.to_vec(), ..Self::default() };
+        let mut queue: VecDeque<String> = VecDeque::new();
+        let mut matched = vec![false; entrypoints.len()];
+        let mut known: HashSet<String> = HashSet::new();
+        for class in graph.class_index.keys() {
+            let name = simple(class);
+            known.insert(name.clone());
+            let hits = patterns.matches(class);
+            let hits = if hits.is_empty() { patterns.matches(&name) } else { hits };
+            for i in &hits {
+                matched[*i] = true;
+            }
+            if !hits.is_empty() && reach.classes.insert(name.clone()) {
+                reach.seeds += 1;
+                queue.push_back(name);
+            }
+        }
+        reach.total_classes = known.len();
+        if let Some(i) = matched.iter().position(|m| !m) {
+            return Err(format!("入口 {:?} 未匹配项目中的任何类", entrypoints[i]));
+        }
+
+        while let Some(class) = queue.pop_front() {
+            for site in calls.get(&class).into_iter().flatten() {
+                for target in hotness::callee_targets(graph, table, site) {
+                    let name = target.simple_class_name().to_string();
+                    if reach.classes.insert(name.clone()) {
+                        queue.push_back(name);
+                    }
+                }
+            }
+        }
+        reach.files = graph.class_index.iter()
+            .filter(|(class, _)| reach.classes.contains(class.rsplit('.').next().unwrap_or(class)))
+            .map(|(_, file)| file.clone())
+            .collect();
+        Ok(reach)
+    }
+
+    /// 是否在该文件上执行深度分析 (可达类所在的 Java 文件与全部非 Java 文件)
+    pub fn includes_file(&self, path: &Path) -> bool {
+        path.extension().and_then(|e| e.to_str()) != Some("java") || path.to_str().is_some_and(|file| self.files.contains(file))
+    }
+
+    pub fn summary(&self) -> ReachabilitySummary {
+        ReachabilitySummary {
+            entrypoints: self.entrypoints.clone(),
+            seeds: self.seeds,
+            classes: self.classes.len(),
+            total_classes: self.total_classes,
+            files: self.files.len(),
+        }
+    }
+}
+
+fn compile(entrypoints: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in entrypoints {
+        builder.add(Glob::new(pattern.trim()).map_err(|e| format!("入口模式 {pattern:?} 无效: {e}"))?);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::taint::{LayerType, MethodSig};
+
+    #[test]
+    fn test_reachable_classes_from_entrypoints() {
+        let file = |name: &str| FilePath::from(format!("/reach/shop/{name}"));
+        let mut graph = CallGraph::new();
+        graph.register_class("com.shop.web.OrderController", file("OrderController.java"), LayerType::Controller);
+        graph.register_class("com.shop.service.OrderService", file("OrderService.java"), LayerType::Service);
+        graph.register_class("com.shop.tools.Migrator", file("Migrator.java"), LayerType::Unknown);
+        graph.add_call(
+            MethodSig::new_fqn("com.shop.web.OrderController", "create"),
+            MethodSig::new_fqn("com.shop.service.OrderService", "save"),
+            file("OrderController.java"), 5,
+        );
+        graph.add_call(
+            MethodSig::new_fqn("com.shop.tools.Migrator", "run"),
+            MethodSig::new_fqn("com.shop.service.OrderService", "save"),
+            file("Migrator.java"), 9,
+        );
+        let table = SymbolTable::new();
+
+        let reach = ClassReachability::build(&graph, &table, &["*Controller".to_string()]).unwrap();
+        assert!(reach.includes_file(Path::new("/reach/shop/OrderService.java")));
+        assert!(!reach.includes_file(Path::new("/reach/shop/Migrator.java")));
+        assert!(reach.includes_file(Path::new("application.yml")));
+        let summary = reach.summary();
+        assert_eq!((summary.seeds, summary.classes, summary.total_classes, summary.files), (1, 2, 3, 2));
+
+        let err = ClassReachability::build(&graph, &table, &["*Controller".to_string(), "com.shop.job.*".to_string()]).unwrap_err();
+        assert!(err.contains("com.shop.job.*"), "{err}");
+    }
+}
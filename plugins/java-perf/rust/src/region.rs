@@ -0,0 +1,277 @@
+//! 局部分析 (`analyze_range`)：编辑器中修改一个方法后只重新分析该方法
+//!
+//! 几千行的类每次保存都整文件分析会明显卡顿。给定修改的字节范围:
+//!
+//! - 取包含该范围的最外层方法/构造器/初始化块作为分析区域 (其中的 lambda、匿名类随之一起分析)；
+//!   范围不在任何方法内 (字段、类声明) 时退化为整文件分析
+//! - 区域之外的方法体替换为空白 (保留换行与花括号)，import、字段与方法签名不变，规则只在区域内匹配；
+//!   字段类型、跨文件的调用关系来自已缓存的项目索引 (daemon 的 `analyze_range`)
+//! - 只返回区域内的问题，行号、列号与原文件一致
+//!
+//! 依赖同一文件中其它方法体的判断 (如其它方法是否加锁) 在局部分析中看不到这些方法体，以整文件扫描为准。
+//!
+//! 编辑器每次保存都会调用，分析器 (已编译的 Query) 与项目索引由调用方持有并复用，这里不再编译规则。
+
+use std::ops::Range;
+use std::path::Path;
+
+use serde::Serialize;
+use tree_sitter::Node;
+
+use crate::ast_engine::{locate_issue, AstIssue, FileAnalyzers};
+use crate::dedup::{dedup, DedupPolicy};
+use crate::hotness;
+use crate::shared_path::FilePath;
+use crate::symbol_table::SymbolTable;
+use crate::taint::CallGraph;
+
+/// 实际分析的区域
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Region {
+    /// method / constructor / initializer / file
+    pub kind: &'static str,
+    /// 方法全限定名 (初始化块与整文件为 None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// 局部分析结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeAnalysis {
+    pub region: Region,
+    /// 区域内的问题 (按行排序)
+    pub issues: Vec<AstIssue>,
+}
+
+/// 用调用方持有的分析器与项目索引分析文件 `file` 中的一段
+///
+/// `code` 为该文件的 (可能未保存的) 内容，`rel_path` 为问题中使用的相对路径；
+/// 没有项目索引时跨文件规则按单文件判断。
+pub fn analyze_range(
+    analyzers: &FileAnalyzers,
+    file: &Path,
+    rel_path: &str,
+    code: &str,
+    byte_range: Range<usize>,
+    symbol_table: Option<&SymbolTable>,
+    call_graph: Option<&CallGraph>,
+) -> Result<RangeAnalysis, String> {
+    if file.extension().and_then(|e| e.to_str()) != Some("java") {
+        return Err(format!("{rel_path}: 局部分析只支持 Java 文件"));
+    }
+    let Range { start, end } = byte_range;
+    if start > end || end > code.len() || !code.is_char_boundary(start) || !code.is_char_boundary(end) {
+        return Err(format!("字节范围 {start}..{end} 无效 (文件共 {} 字节)", code.len()));
+    }
+    let (region, masked) = analyzers.java()
+        .parse_with(code, |tree| {
+            let region = locate(tree.root_node(), code, start..end);
+            let masked = mask_outside(tree.root_node(), code, &region);
+            (region, masked)
+        })
+        .map_err(|e| format!("{rel_path}: {e}"))?;
+    let issue_path = FilePath::new(rel_path);
+    let issues: Vec<AstIssue> = analyzers.analyze_file(file, &masked, symbol_table, call_graph)
+        .into_iter()
+        .map(|issue| locate_issue(issue, &issue_path, code))
+        .filter(|issue| region.start_line <= issue.line && issue.line <= region.end_line)
+        .collect();
+    let mut issues = dedup(issues, DedupPolicy::default());
+    issues.sort_by_key(|issue| (issue.line, issue.column));
+    Ok(RangeAnalysis { region, issues })
+}
+
+/// 成员的种类与方法体 (方法、构造器、静态/实例初始化块)
+fn member_body(node: Node) -> Option<(&'static str, Node)> {
+    match node.kind() {
+        "method_declaration" => node.child_by_field_name("body").map(|body| ("method", body)),
+        "constructor_declaration" | "compact_constructor_declaration" => {
+            node.child_by_field_name("body").map(|body| ("constructor", body))
+        }
+        "static_initializer" => {
+            let mut cursor = node.walk();
+            let body = node.children(&mut cursor).find(|child| child.kind() == "block");
+            body.map(|body| ("initializer", body))
+        }
+        "block" if node.parent().is_some_and(|p| matches!(p.kind(), "class_body" | "enum_body_declarations")) => {
+            Some(("initializer", node))
+        }
+        _ => None,
+    }
+}
+
+/// 包含字节范围的最外层成员；没有时为整个文件
+pub fn locate(root: Node, code: &str, range: Range<usize>) -> Region {
+    let mut node = root;
+    loop {
+        if let Some((kind, _)) = member_body(node) {
+            let start_line = node.start_position().row + 1;
+            let end_line = node.end_position().row + 1;
+            let method = (kind != "initializer")
+                .then(|| {
+                    hotness::collect_spans(root, code).into_iter()
+                        .find(|span| span.start_line == start_line && span.end_line == end_line)
+                        .map(|span| span.fqn)
+                })
+                .flatten();
+            return Region { kind, method, start_byte: node.start_byte(), end_byte: node.end_byte(), start_line, end_line };
+        }
+        let mut cursor = node.walk();
+        let child = node.children(&mut cursor)
+            .find(|child| child.start_byte() <= range.start && range.end <= child.end_byte());
+        match child {
+            Some(child) => node = child,
+            None => break,
+        }
+    }
+    Region {
+        kind: "file",
+        method: None,
+        start_byte: 0,
+        end_byte: code.len(),
+        start_line: 1,
+        end_line: code.lines().count().max(1),
+    }
+}
+
+/// 区域之外的方法体替换为空白 (保留换行与花括号，字节偏移与行列不变)
+pub fn mask_outside(root: Node, code: &str, region: &Region) -> String {
+    if region.kind == "file" {
+        return code.to_string();
+    }
+    let mut bytes = code.as_bytes().to_vec();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.start_byte() == region.start_byte && node.end_byte() == region.end_byte {
+            continue;
+        }
+        if let Some((_, body)) = member_body(node) {
+            if body.end_byte() <= region.start_byte || body.start_byte() >= region.end_byte {
+                // 花括号之间的内容
+                let inner = body.start_byte() + 1..body.end_byte().saturating_sub(1);
+                for byte in bytes.get_mut(inner).unwrap_or_default() {
+                    if !matches!(*byte, b'\n' | b'\r') {
+                        *byte = b' ';
+                    }
+                }
+                continue;
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    // 只替换了花括号之间的完整字符
+    String::from_utf8(bytes).expect("masked source stays UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = r#"package com.shop;
+
+public class OrderService {
+    private OrderRepository orderRepository;
+
+    static {
+        // 初始化
+    }
+
+    public void loadAll(List<Long> ids) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+    }
+
+    public String join(List<String> parts) {
+        String out = "";
+        for (String part : parts) {
+            out += part;
+        }
+        ids.forEach(id -> {
+            out += id;
+        });
+        return out;
+    }
+}
+"#;
+
+    fn offset(needle: &str) -> Range<usize> {
+        let start = CODE.find(needle).unwrap();
+        start..start + needle.len()
+    }
+
+    #[test]
+    fn test_locate_outermost_member() {
+        let analyzer = crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer::new().unwrap();
+        let locate_at = |range: Range<usize>| analyzer.parse_with(CODE, |tree| locate(tree.root_node(), CODE, range)).unwrap();
+
+        // lambda 内的修改分析整个外层方法
+        let region = locate_at(offset("out += id;"));
+        assert_eq!((region.kind, region.method.as_deref()), ("method", Some("com.shop.OrderService.join")));
+        assert_eq!((region.start_line, region.end_line), (16, 25));
+        assert_eq!(locate_at(offset("// 初始化")).kind, "initializer");
+        let field = locate_at(offset("private OrderRepository"));
+        assert_eq!((field.kind, field.start_line), ("file", 1));
+
+        let masked = analyzer.parse_with(CODE, |tree| mask_outside(tree.root_node(), CODE, &region)).unwrap();
+        assert_eq!(masked.len(), CODE.len());
+        assert_eq!(masked.lines().count(), CODE.lines().count());
+        assert!(!masked.contains("findById") && !masked.contains("初始化"), "{masked}");
+        assert!(masked.contains("public void loadAll(List<Long> ids) {") && masked.contains("out += id;"));
+    }
+
+    #[test]
+    fn test_analyze_range_reports_region_only() {
+        let analyzers = FileAnalyzers::new(&crate::rules::registry::RuleRegistry::default()).unwrap();
+        let rel_path = "src/main/java/com/shop/OrderService.java";
+        let analyze_range = |code: &str, range: Range<usize>| {
+            analyze_range(&analyzers, Path::new(rel_path), rel_path, code, range, None, None)
+        };
+        let full = analyze_range(CODE, 0..CODE.len()).unwrap();
+        assert_eq!(full.region.kind, "file");
+        let ids: Vec<&str> = full.issues.iter().map(|i| i.issue_type.as_str()).collect();
+        assert!(ids.contains(&"STRING_CONCAT_LOOP"), "{ids:?}");
+        assert!(ids.contains(&"N_PLUS_ONE"), "{ids:?}");
+
+        let partial = analyze_range(CODE, offset("out += part;")).unwrap();
+        assert_eq!(partial.issues.first().map(|i| i.path.as_str()), Some(rel_path));
+        assert!(partial.issues.iter().all(|i| i.issue_type != "N_PLUS_ONE"));
+        // 位置与整文件分析一致
+        for issue in &partial.issues {
+            assert!(full.issues.iter().any(|f| f.issue_type == issue.issue_type && f.line == issue.line && f.column == issue.column), "{issue:?}");
+        }
+        assert!(partial.issues.iter().any(|i| i.issue_type == "STRING_CONCAT_LOOP" && i.line == 19));
+
+        assert!(analyze_range(CODE, 5..CODE.len() + 1).is_err());
+        let yaml = Path::new("application.yml");
+        assert!(super::analyze_range(&analyzers, yaml, "application.yml", CODE, 0..1, None, None).is_err());
+    }
+
+    #[test]
+    fn test_analyze_range_uses_project_index() {
+        // 循环中调用的接收者只有通过项目索引才能识别为 DAO
+        let code = "package com.shop;\n\npublic class CartService {\n    private final CartStore store;\n\n    public void load(List<Long> ids) {\n        for (Long id : ids) {\n            store.fetch(id);\n        }\n    }\n}\n";
+        let store = "package com.shop;\n\n@Repository\npublic interface CartStore {\n    Cart fetch(Long id);\n}\n";
+        let analyzers = FileAnalyzers::new(&crate::rules::registry::RuleRegistry::default()).unwrap();
+        let mut table = SymbolTable::new();
+        let mut graph = CallGraph::new();
+        for (path, content) in [("CartStore.java", store), ("CartService.java", code)] {
+            let (file_table, file_graph, _) = crate::ast_engine::index_file(Path::new(path), content, analyzers.java());
+            table.merge(file_table);
+            graph.merge(file_graph);
+        }
+        let start = code.find("store.fetch").unwrap();
+        let file = Path::new("CartService.java");
+        let n_plus_one = |table: Option<&SymbolTable>, graph: Option<&CallGraph>| {
+            analyze_range(&analyzers, file, "CartService.java", code, start..start + 5, table, graph).unwrap()
+                .issues.iter().filter(|i| i.issue_type == "N_PLUS_ONE").count()
+        };
+        assert_eq!(n_plus_one(Some(&table), Some(&graph)), 1);
+        assert_eq!(n_plus_one(None, None), 0);
+    }
+}
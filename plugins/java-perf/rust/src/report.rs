@@ -0,0 +1,370 @@
+//! 标准格式导出 (`scan --format sarif|checkstyle|rdjson`)
+//!
+//! | 格式 | 用途 | 严重级别 |
+//! |------|------|----------|
+//! | SARIF 2.1.0 | GitHub Code Scanning / IDE | `level`: P0 → error, P1 → warning；映射名写入 `properties.severity` |
+//! | Checkstyle XML | Jenkins / reviewdog | `severity`: P0 → error, P1 → warning；映射名作为 message 前缀 |
+//! | rdjson | reviewdog (`-f=rdjson`) 发表 PR review 评论 | `severity`: P0 → ERROR, P1 → WARNING；映射名作为 message 前缀 |
+//!
+//! 各格式的 level/severity 取值由规范限定，组织自定义的级别名称
+//! (`.javaperf.toml` 的 `[severity_map]`) 只能通过附加字段携带。
+//!
+//! `--include-suppressed` 时被抑制的问题在 SARIF 中作为带 `suppressions`
+//! (kind: inSource) 的 result 输出；Checkstyle 与 rdjson 没有抑制的概念，不输出。
+//!
+//! 问题指纹写入 SARIF `partialFingerprints` (`javaPerf/v1`)，代码移动后 Code Scanning 仍能关联同一告警。
+//! 内置规则包版本 (见 rules::version) 写入 `tool.driver.properties.rulesPackVersion`。
+//!
+//! 带确定性修复 (`suggested_patch`，见 scanner::patch) 的问题在 SARIF 中附带 `fixes`：
+//! diff 中每处连续改动为一个按整行删除/插入的 replacement。rdjson 中同样的改动作为 `suggestions`，
+//! reviewdog 在 GitHub 上将其发布为可一键采纳的 suggested change。
+
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use quick_xml::escape::escape;
+use serde_json::{json, Value};
+
+use crate::ast_engine::{AstIssue, ProjectScan, Severity};
+use crate::rules::version;
+use crate::scanner::patch;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// SARIF 2.1.0 (JSON)
+    Sarif,
+    /// Checkstyle XML
+    Checkstyle,
+    /// reviewdog Diagnostic Format (JSON)
+    Rdjson,
+}
+
+const TOOL_NAME: &str = "java-perf";
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::P0 => "error",
+        Severity::P1 => "warning",
+    }
+}
+
+/// 按格式导出扫描结果
+pub fn export(scan: &ProjectScan, format: ExportFormat) -> Value {
+    match format {
+        ExportFormat::Sarif => to_sarif(scan),
+        ExportFormat::Checkstyle => json!(to_checkstyle(scan)),
+        ExportFormat::Rdjson => to_rdjson(scan),
+    }
+}
+
+/// SARIF 2.1.0
+pub fn to_sarif(scan: &ProjectScan) -> Value {
+    // 规则表：每个规则 ID 一条，取首个问题的描述
+    let mut rules: BTreeMap<&str, &AstIssue> = BTreeMap::new();
+    for issue in scan.issues.iter().chain(&scan.suppressed) {
+        rules.entry(issue.issue_type.as_str()).or_insert(issue);
+    }
+    let rule_index: BTreeMap<&str, usize> = rules.keys().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    let rules: Vec<Value> = rules.values()
+        .map(|issue| json!({
+            "id": issue.issue_type,
+            "shortDescription": { "text": issue.description },
+            "defaultConfiguration": { "level": level(issue.severity) },
+        }))
+        .collect();
+
+    let results: Vec<Value> = scan.issues.iter()
+        .chain(&scan.suppressed)
+        .map(|issue| {
+            let mut properties = json!({
+                "severity": scan.severity_map.label(issue.severity),
+                "hotness": issue.hotness,
+            });
+            if !issue.secondary.is_empty() {
+                properties["secondary"] = json!(issue.secondary);
+            }
+            if issue.inactive {
+                properties["inactive"] = json!(true);
+            }
+            let mut result = json!({
+                "ruleId": issue.issue_type,
+                "ruleIndex": rule_index[issue.issue_type.as_str()],
+                "level": level(issue.severity),
+                "message": { "text": issue.description },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": issue.path },
+                        "region": { "startLine": issue.line, "startColumn": issue.column + 1 },
+                    }
+                }],
+                "properties": properties,
+            });
+            if !issue.fingerprint.is_empty() {
+                result["partialFingerprints"] = json!({ "javaPerf/v1": issue.fingerprint });
+            }
+            if let Some(diff) = &issue.suggested_patch {
+                let replacements: Vec<Value> = patch::replacements(diff).into_iter()
+                    .map(|r| json!({
+                        "deletedRegion": {
+                            "startLine": r.start_line, "startColumn": 1,
+                            "endLine": r.start_line + r.deleted, "endColumn": 1,
+                        },
+                        "insertedContent": { "text": r.inserted },
+                    }))
+                    .collect();
+                result["fixes"] = json!([{
+                    "description": { "text": format!("{} 的确定性修复", issue.issue_type) },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": issue.path },
+                        "replacements": replacements,
+                    }],
+                }]);
+            }
+            if let Some(source) = &issue.suppression {
+                result["suppressions"] = json!([{
+                    "kind": "inSource",
+                    "location": {
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": issue.path },
+                            "region": { "startLine": source.line },
+                        }
+                    },
+                    "properties": { "directive": source.kind },
+                }]);
+            }
+            result
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "properties": { "rulesPackVersion": version::current() },
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// Checkstyle XML (按文件分组)
+pub fn to_checkstyle(scan: &ProjectScan) -> String {
+    let mut files: BTreeMap<&str, Vec<&AstIssue>> = BTreeMap::new();
+    for issue in &scan.issues {
+        files.entry(issue.path.as_str()).or_default().push(issue);
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n");
+    for (path, issues) in files {
+        out.push_str(&format!("  <file name=\"{}\">\n", escape(path)));
+        for issue in issues {
+            let message = format!("[{}] {}", scan.severity_map.label(issue.severity), issue.description);
+            out.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"{TOOL_NAME}.{}\"/>\n",
+                issue.line, issue.column + 1, level(issue.severity), escape(&message), escape(&issue.issue_type),
+            ));
+        }
+        out.push_str("  </file>\n");
+    }
+    out.push_str("</checkstyle>");
+    out
+}
+
+/// reviewdog Diagnostic Format (rdjson)
+pub fn to_rdjson(scan: &ProjectScan) -> Value {
+    let diagnostics: Vec<Value> = scan.issues.iter()
+        .map(|issue| {
+            let severity = match issue.severity {
+                Severity::P0 => "ERROR",
+                Severity::P1 => "WARNING",
+            };
+            let mut diagnostic = json!({
+                "message": format!("[{}] {}", scan.severity_map.label(issue.severity), issue.description),
+                "location": {
+                    "path": issue.path,
+                    "range": { "start": { "line": issue.line, "column": issue.column + 1 } },
+                },
+                "severity": severity,
+                "code": { "value": issue.issue_type },
+            });
+            if let Some(diff) = &issue.suggested_patch {
+                // 按整行替换: [start_line, start_line + deleted) 的内容替换为 inserted
+                diagnostic["suggestions"] = patch::replacements(diff).into_iter()
+                    .map(|r| json!({
+                        "range": {
+                            "start": { "line": r.start_line, "column": 1 },
+                            "end": { "line": r.start_line + r.deleted, "column": 1 },
+                        },
+                        "text": r.inserted,
+                    }))
+                    .collect();
+            }
+            diagnostic
+        })
+        .collect();
+
+    json!({
+        "source": { "name": TOOL_NAME },
+        "diagnostics": diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_config::SeverityMap;
+    use crate::rules::suppression::{SuppressionKind, SuppressionSource};
+    use std::collections::HashMap;
+
+    fn scan(issues: Vec<AstIssue>) -> ProjectScan {
+        ProjectScan {
+            file_count: 1,
+            issues,
+            encoding_stats: HashMap::new(),
+            adjustments: Vec::new(),
+            pool_advice: Vec::new(),
+            cpus: 4,
+            severity_map: SeverityMap { p0: None, p1: Some("Minor".to_string()) },
+            suppressed: Vec::new(),
+            baselined: 0,
+            parse_degraded: Vec::new(),
+            source_shapes: Default::default(),
+            loc: Default::default(),
+        }
+    }
+
+    fn issue(id: &str, severity: Severity, line: usize, description: &str) -> AstIssue {
+        AstIssue {
+            severity,
+            issue_type: id.to_string(),
+            file: "A.java".to_string(),
+            path: "src/A.java".into(),
+            line,
+            column: 4,
+            description: description.to_string(),
+            context: None,
+            snippet: String::new(),
+            fingerprint: String::new(),
+            hotness: 0,
+            method: None,
+            secondary: Vec::new(),
+            inactive: false,
+            suppressed: false,
+            suppression: None,
+            owners: Vec::new(),
+            suggested_patch: None,
+            triage: None,
+            age: None,
+            budget: None,
+            coverage: None,
+            debt: None,
+            sql: None,
+        }
+    }
+
+    #[test]
+    fn test_sarif_rules_and_levels() {
+        let sarif = to_sarif(&scan(vec![
+            issue("N_PLUS_ONE", Severity::P0, 3, "loop query"),
+            issue("LOG_STRING_CONCAT", Severity::P1, 5, "log concat"),
+            issue("N_PLUS_ONE", Severity::P0, 9, "loop query"),
+        ]));
+        let run = &sarif["runs"][0];
+        let rules: Vec<&str> = run["tool"]["driver"]["rules"].as_array().unwrap().iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(rules, vec!["LOG_STRING_CONCAT", "N_PLUS_ONE"]);
+        assert_eq!(run["tool"]["driver"]["properties"]["rulesPackVersion"], version::current());
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!((&results[0]["ruleIndex"], &results[0]["level"]), (&json!(1), &json!("error")));
+        assert_eq!(results[0]["properties"]["severity"], "P0");
+        assert_eq!(results[1]["properties"]["severity"], "Minor");
+        assert_eq!(results[1]["locations"][0]["physicalLocation"]["region"]["startColumn"], 5);
+        assert!(results[0].get("partialFingerprints").is_none());
+    }
+
+    #[test]
+    fn test_sarif_partial_fingerprints() {
+        let mut fingerprinted = issue("N_PLUS_ONE", Severity::P0, 3, "loop query");
+        fingerprinted.fingerprint = "0123456789abcdef".to_string();
+        let sarif = to_sarif(&scan(vec![fingerprinted]));
+        assert_eq!(sarif["runs"][0]["results"][0]["partialFingerprints"]["javaPerf/v1"], "0123456789abcdef");
+    }
+
+    #[test]
+    fn test_sarif_fixes_from_patch() {
+        let mut fixable = issue("LOG_STRING_CONCAT", Severity::P1, 2, "log concat");
+        fixable.suggested_patch = Some(patch::unified_diff(
+            "src/A.java",
+            "@@ -1,3 +1,3 @@\n void f() {\n-    log.info(\"id=\" + id);\n+    log.info(\"id={}\", id);\n }\n",
+        ));
+        let sarif = to_sarif(&scan(vec![fixable.clone(), issue("N_PLUS_ONE", Severity::P0, 9, "loop query")]));
+        let results = &sarif["runs"][0]["results"];
+        let change = &results[0]["fixes"][0]["artifactChanges"][0];
+        assert_eq!(change["artifactLocation"]["uri"], "src/A.java");
+        let replacement = &change["replacements"][0];
+        assert_eq!((&replacement["deletedRegion"]["startLine"], &replacement["deletedRegion"]["endLine"]), (&json!(2), &json!(3)));
+        assert_eq!(replacement["insertedContent"]["text"], "    log.info(\"id={}\", id);\n");
+        assert!(results[1].get("fixes").is_none());
+
+        let rdjson = to_rdjson(&scan(vec![fixable]));
+        let suggestion = &rdjson["diagnostics"][0]["suggestions"][0];
+        assert_eq!((&suggestion["range"]["start"]["line"], &suggestion["range"]["end"]["line"]), (&json!(2), &json!(3)));
+        assert_eq!(suggestion["text"], "    log.info(\"id={}\", id);\n");
+    }
+
+    #[test]
+    fn test_sarif_suppressed_results() {
+        let mut suppressed = issue("N_PLUS_ONE", Severity::P0, 8, "loop query");
+        suppressed.suppressed = true;
+        suppressed.suppression = Some(SuppressionSource { kind: SuppressionKind::NextLineComment, line: 7 });
+        let mut project = scan(vec![issue("LOG_STRING_CONCAT", Severity::P1, 5, "log concat")]);
+        project.suppressed.push(suppressed);
+
+        let sarif = to_sarif(&project);
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].get("suppressions").is_none());
+        let suppression = &results[1]["suppressions"][0];
+        assert_eq!(suppression["kind"], "inSource");
+        assert_eq!(suppression["properties"]["directive"], "next-line-comment");
+        assert_eq!(suppression["location"]["physicalLocation"]["region"]["startLine"], 7);
+
+        // Checkstyle 只输出活跃问题
+        assert!(!to_checkstyle(&project).contains("N_PLUS_ONE"));
+    }
+
+    #[test]
+    fn test_checkstyle_escapes_messages() {
+        let xml = to_checkstyle(&scan(vec![issue("LOG_STRING_CONCAT", Severity::P1, 5, "\"a\" + <b>")]));
+        assert!(xml.contains("<file name=\"src/A.java\">"), "{xml}");
+        assert!(xml.contains("message=\"[Minor] &quot;a&quot; + &lt;b&gt;\""), "{xml}");
+        assert!(xml.contains("severity=\"warning\""), "{xml}");
+    }
+
+    #[test]
+    fn test_rdjson_diagnostics() {
+        let rdjson = to_rdjson(&scan(vec![
+            issue("N_PLUS_ONE", Severity::P0, 3, "loop query"),
+            issue("LOG_STRING_CONCAT", Severity::P1, 5, "log concat"),
+        ]));
+        assert_eq!(rdjson["source"]["name"], TOOL_NAME);
+        let diagnostics = rdjson["diagnostics"].as_array().unwrap();
+        assert_eq!((&diagnostics[0]["severity"], &diagnostics[1]["severity"]), (&json!("ERROR"), &json!("WARNING")));
+        assert_eq!(diagnostics[1]["message"], "[Minor] log concat");
+        assert_eq!(diagnostics[1]["code"]["value"], "LOG_STRING_CONCAT");
+        assert_eq!(diagnostics[1]["location"]["path"], "src/A.java");
+        assert_eq!(diagnostics[1]["location"]["range"]["start"], json!({ "line": 5, "column": 5 }));
+        assert!(diagnostics[0].get("suggestions").is_none());
+    }
+}
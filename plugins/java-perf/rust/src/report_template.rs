@@ -0,0 +1,121 @@
+//! 自定义报告模板 (`scan --template report.md.j2`)
+//!
+//! 平台团队可以用 Jinja2 语法 (minijinja) 把扫描结果渲染成内部评审文档的格式，
+//! 不必再对 `--json` 输出做后处理。模板上下文:
+//!
+//! | 变量 | 内容 |
+//! |------|------|
+//! | `path` / `files` / `p0` / `p1` / `stack` / `stats` / `issues` ... | 与 `--json` 输出相同 (见 `ast_engine::render_json`) |
+//! | `markdown` | 默认 Markdown 报告 (是否完整取决于 `--full`)，可嵌在自定义页眉/页脚之间 |
+//! | `generated_at` | 生成时间 (RFC 3339) |
+//! | `tool` | `{ name, version }` |
+//!
+//! 模板文件名以 `.html`/`.htm`/`.xml` 结尾 (可再带 `.j2`/`.jinja` 后缀) 时输出自动 HTML 转义；
+//! 同目录下的其他模板可通过 `{% include %}` / `{% extends %}` / `{% import %}` 引用。
+
+use std::path::Path;
+
+use minijinja::{path_loader, Environment, Value};
+use serde_json::json;
+
+use crate::analysis::AnalysisReport;
+use crate::ast_engine::{render_json, render_report, ScanOptions};
+
+/// 用模板渲染分析报告
+pub fn render(
+    template: &Path,
+    analysis: &AnalysisReport,
+    compact: bool,
+    max_p1: usize,
+    options: &ScanOptions,
+) -> Result<String, String> {
+    let name = template.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("模板路径无效: {}", template.display()))?;
+    if !template.is_file() {
+        return Err(format!("模板文件不存在: {}", template.display()));
+    }
+    let dir = template.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+
+    let mut env = Environment::new();
+    env.set_loader(path_loader(dir));
+    // 报告写入文件时保留模板末尾的换行
+    env.set_keep_trailing_newline(true);
+    let template = env.get_template(name).map_err(|e| describe(&e))?;
+    template.render(context(analysis, compact, max_p1, options)).map_err(|e| describe(&e))
+}
+
+/// 模板上下文: `--json` 输出 + 默认 Markdown 报告 + 生成信息
+fn context(analysis: &AnalysisReport, compact: bool, max_p1: usize, options: &ScanOptions) -> Value {
+    let mut context = render_json(analysis, options);
+    context["markdown"] = render_report(analysis, compact, max_p1, options);
+    context["generated_at"] = json!(chrono::Local::now().to_rfc3339());
+    context["tool"] = json!({ "name": "java-perf", "version": env!("CARGO_PKG_VERSION") });
+    Value::from_serialize(&context)
+}
+
+/// 错误信息带模板名与行号 (minijinja 的 Display 已包含 `(in name:line)`)，并附上嵌套原因
+fn describe(err: &minijinja::Error) -> String {
+    let mut message = format!("模板渲染失败: {err}");
+    let mut source = std::error::Error::source(err);
+    while let Some(cause) = source {
+        message.push_str(&format!("\n  原因: {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast_engine::analyze_project;
+
+    fn analysis(dir: &Path) -> AnalysisReport {
+        std::fs::write(dir.join("OrderService.java"), r#"
+            public class OrderService {
+                public void load(List<Long> ids) {
+                    for (Long id : ids) {
+                        orderRepository.findById(id);
+                    }
+                }
+            }
+        "#).unwrap();
+        analyze_project(dir.to_str().unwrap(), &ScanOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_render_markdown_and_html_templates() {
+        let project = tempfile::tempdir().unwrap();
+        let report = analysis(project.path());
+        let templates = tempfile::tempdir().unwrap();
+
+        std::fs::write(templates.path().join("header.j2"), "# ACME 性能评审 ({{ tool.name }})\n").unwrap();
+        std::fs::write(templates.path().join("review.md.j2"), concat!(
+            "{% include \"header.j2\" %}",
+            "P0={{ p0 }} P1={{ p1 }}\n",
+            "{% for issue in issues %}- [{{ issue.severity }}] {{ issue.id }} {{ issue.path }}:{{ issue.line }}\n{% endfor %}",
+        )).unwrap();
+        let markdown = render(&templates.path().join("review.md.j2"), &report, false, 10, &ScanOptions::default()).unwrap();
+        assert!(markdown.starts_with("# ACME 性能评审 (java-perf)\nP0=1"), "{markdown}");
+        assert!(markdown.contains("- [P0] N_PLUS_ONE OrderService.java:5"), "{markdown}");
+
+        // .html 模板自动转义 (描述中的 < > 等)
+        std::fs::write(templates.path().join("review.html.j2"), "<pre>{{ markdown }}</pre><i>{{ \"<b>\" }}</i>").unwrap();
+        let html = render(&templates.path().join("review.html.j2"), &report, true, 10, &ScanOptions::default()).unwrap();
+        assert!(html.contains("<i>&lt;b&gt;</i>"), "{html}");
+    }
+
+    #[test]
+    fn test_render_errors_name_template_and_line() {
+        let project = tempfile::tempdir().unwrap();
+        let report = analysis(project.path());
+        let templates = tempfile::tempdir().unwrap();
+        let broken = templates.path().join("broken.md.j2");
+        std::fs::write(&broken, "line 1\n{% for issue in issues %}\n").unwrap();
+        let err = render(&broken, &report, true, 10, &ScanOptions::default()).unwrap_err();
+        assert!(err.contains("syntax error") && err.contains("broken.md.j2:2"), "{err}");
+
+        let err = render(&templates.path().join("missing.j2"), &report, true, 10, &ScanOptions::default()).unwrap_err();
+        assert!(err.contains("模板文件不存在"), "{err}");
+    }
+}
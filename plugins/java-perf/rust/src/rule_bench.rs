@@ -0,0 +1,208 @@
+//! 规则执行基准 (`dev bench-rules`)
+//!
+//! 在 `dev bench-index` 的合成语料上逐文件运行全部 Java 规则 (单线程，不使用符号表)，对比五种配置:
+//!
+//! | 配置 | 说明 |
+//! |------|------|
+//! | 注册表顺序 | 规则按注册表中的顺序执行 |
+//! | 估计代价顺序 | 按 Query 谓词估计的代价排序 (没有耗时记录时的默认顺序) |
+//! | 实测代价顺序 | 按预热轮收集的耗时排序 (相当于 `scan --rule-timings` 之后的扫描) |
+//! | 规则计时 | 开启 `--rule-timings`，得出计时本身的开销与各规则耗时 |
+//! | 按类别排除 | Repository 文件排除全部规则类别，这些文件不再解析、不执行任何 Query |
+//!
+//! ```text
+//! java-perf dev bench-rules --files 2000
+//! ```
+//!
+//! 计时前先开启计时完整运行一轮预热，得到实测代价顺序使用的耗时记录；之后共 [`ROUNDS`] 轮，
+//! 每轮各配置各跑一遍且起始配置轮换，抵消页缓存、CPU 频率等随时间漂移的影响，各配置取中位数。
+//! 除按类别排除外，各配置的问题必须完全一致 (执行顺序与计时不影响结果)。
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ast_engine::FileAnalyzers;
+use crate::index_bench;
+use crate::project_config::{RuleExclusions, RuleOverride};
+use crate::rules::registry::{RuleCategory, RuleRegistry};
+use crate::rules::timing::{RuleTiming, TimingProfile};
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 计时轮数 (每轮各配置各一遍)
+const ROUNDS: usize = 5;
+/// 结果中列出的最耗时规则数
+const TOP_RULES: usize = 5;
+
+/// 基准参数
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub files: usize,
+    /// 语料目录 (默认系统临时目录，结束后删除)
+    pub dir: Option<PathBuf>,
+}
+
+/// 基准结果 (耗时为各轮中位数)
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchResult {
+    pub files: usize,
+    pub rounds: usize,
+    pub registry_order_ms: u64,
+    pub estimated_order_ms: u64,
+    pub measured_order_ms: u64,
+    pub timed_ms: u64,
+    pub category_excluded_ms: u64,
+    /// 按类别排除后跳过的文件数
+    pub skipped_files: usize,
+    /// 规则计时配置中平均耗时最高的规则
+    pub slowest: Vec<RuleTiming>,
+    /// 实测代价顺序与注册表顺序不同 (耗时记录改变了执行顺序)
+    pub measured_reordered: bool,
+    /// 除按类别排除外各配置的问题一致
+    pub consistent: bool,
+}
+
+/// 生成语料并对各配置计时
+pub fn run(options: &BenchOptions) -> Result<BenchResult, BoxError> {
+    let (dir, temporary) = match &options.dir {
+        Some(dir) => (dir.clone(), false),
+        None => (std::env::temp_dir().join(format!("java-perf-bench-rules-{}", std::process::id())), true),
+    };
+    let result = index_bench::generate(&dir, options.files).and_then(|paths| measure(&dir, &paths));
+    if temporary {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    result
+}
+
+/// 一轮分析的耗时与问题 (文件, 行, 规则)
+fn pass(analyzers: &FileAnalyzers, sources: &[(PathBuf, String)]) -> (u64, Vec<(usize, usize, String)>) {
+    let started = Instant::now();
+    let mut issues = Vec::new();
+    for (n, (path, content)) in sources.iter().enumerate() {
+        issues.extend(analyzers.analyze_file(path, content, None, None).into_iter().map(|issue| (n, issue.line, issue.id)));
+    }
+    (started.elapsed().as_millis() as u64, issues)
+}
+
+fn median(mut samples: Vec<u64>) -> u64 {
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+fn measure(root: &Path, paths: &[PathBuf]) -> Result<BenchResult, BoxError> {
+    let registry = RuleRegistry::default();
+    let sources = paths.iter()
+        .map(|path| Ok((path.clone(), std::fs::read_to_string(path)?)))
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+
+    let overrides: Vec<RuleOverride> = RuleCategory::value_variants().iter()
+        .map(|category| RuleOverride {
+            id: category.name().to_string(),
+            exclude: vec!["**/OrderRepository*.java".to_string()],
+            enabled: None,
+            sample_rate: None,
+        })
+        .collect();
+
+    // 预热轮同时收集实测代价顺序使用的耗时记录
+    let warmup = FileAnalyzers::new(&registry)?.with_rule_timings();
+    let (_, baseline) = pass(&warmup, &sources);
+    let mut profile = TimingProfile::default();
+    profile.record(&warmup.java().rule_timings());
+
+    let configs = [
+        FileAnalyzers::new(&registry)?.with_rule_order(None),
+        FileAnalyzers::new(&registry)?,
+        FileAnalyzers::new(&registry)?.with_rule_order(Some(&profile)),
+        FileAnalyzers::new(&registry)?.with_rule_order(Some(&profile)).with_rule_timings(),
+        FileAnalyzers::new(&registry)?.with_exclusions(root, RuleExclusions::compile(&overrides)?),
+    ];
+    let measured_reordered = configs[2].java().rule_order() != configs[0].java().rule_order();
+
+    let mut samples: [Vec<u64>; 5] = Default::default();
+    let mut consistent = true;
+    for round in 0..ROUNDS {
+        for offset in 0..configs.len() {
+            let config = (round + offset) % configs.len();
+            let (ms, issues) = pass(&configs[config], &sources);
+            samples[config].push(ms);
+            if config < 4 {
+                consistent &= issues == baseline;
+            }
+        }
+    }
+    let [registry_order, estimated_order, measured_order, timed, category_excluded] = samples;
+    let skipped_files = paths.iter()
+        .filter(|path| path.file_name().is_some_and(|name| name.to_string_lossy().starts_with("OrderRepository")))
+        .count();
+
+    let mut slowest = configs[3].java().rule_timings();
+    slowest.sort_by_key(|timing| std::cmp::Reverse(timing.cost.mean_ns()));
+    slowest.truncate(TOP_RULES);
+
+    Ok(BenchResult {
+        files: paths.len(),
+        rounds: ROUNDS,
+        registry_order_ms: median(registry_order),
+        estimated_order_ms: median(estimated_order),
+        measured_order_ms: median(measured_order),
+        timed_ms: median(timed),
+        category_excluded_ms: median(category_excluded),
+        skipped_files,
+        slowest,
+        measured_reordered,
+        consistent,
+    })
+}
+
+/// 渲染结果
+pub fn render(result: &BenchResult, json_output: bool) -> Value {
+    if json_output {
+        return json!(result);
+    }
+    let ratio = |ms: u64| ms as f64 / result.registry_order_ms.max(1) as f64;
+    let slowest: Vec<String> = result.slowest.iter()
+        .map(|timing| format!("| {} | {} µs |", timing.id, timing.mean_us))
+        .collect();
+    json!(format!(
+        "## 📊 规则执行基准 ({} 个文件，{} 轮交替执行取中位数)\n\n\
+         | 配置 | 耗时 |\n|------|------|\n\
+         | 注册表顺序 | {} ms |\n\
+         | 估计代价顺序 | {} ms ({:.2}x) |\n\
+         | 实测代价顺序 | {} ms ({:.2}x) |\n\
+         | 规则计时 (--rule-timings) | {} ms ({:.2}x) |\n\
+         | 按类别排除 ({} 个文件跳过) | {} ms ({:.2}x) |\n\n\
+         各执行顺序与开启计时的结果{}\n\n\
+         | 平均耗时最高的规则 | 每文件 |\n|------|------|\n{}",
+        result.files, result.rounds, result.registry_order_ms,
+        result.estimated_order_ms, ratio(result.estimated_order_ms),
+        result.measured_order_ms, ratio(result.measured_order_ms),
+        result.timed_ms, ratio(result.timed_ms),
+        result.skipped_files, result.category_excluded_ms, ratio(result.category_excluded_ms),
+        if result.consistent { "一致 ✅" } else { "不一致 ❌" },
+        slowest.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_order_and_timings_keep_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = run(&BenchOptions { files: 20, dir: Some(dir.path().to_path_buf()) }).unwrap();
+        assert_eq!((result.files, result.skipped_files, result.rounds), (20, 10, ROUNDS));
+        assert!(result.consistent);
+        assert!(result.measured_reordered);
+        assert_eq!(result.slowest.len(), TOP_RULES);
+        // 只有计时配置累计耗时：每轮 20 个文件
+        assert!(result.slowest.iter().all(|timing| timing.cost.files == (20 * ROUNDS) as u64));
+        assert!(render(&result, false).as_str().unwrap().contains("各执行顺序与开启计时的结果一致"));
+    }
+}
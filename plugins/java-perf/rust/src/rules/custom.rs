@@ -0,0 +1,192 @@
+//! 运行时注册的规则 (库调用方扩展)
+//!
+//! 内置规则的处理器由 `create_handler` 按规则 ID 静态分派。嵌入 java-perf 的程序
+//! 可以在分析前通过 [`CustomRules`] 注册自己的规则，无需修改本 crate:
+//!
+//! - [`CustomRules::register_rule`]: 新规则 = Tree-sitter Query + `RuleHandler`
+//! - [`CustomRules::register_handler`]: 替换已有规则 (内置或自定义) 的处理器，Query 不变
+//!
+//! 外部 `.scm` 规则包 (见 `pack` 模块) 加载后也注册为自定义规则，不可信规则包额外
+//! 带执行限制 ([`ExecLimits`])。
+//!
+//! 注册结果通过 `ScanOptions::custom_rules` 传给 `analyze_project`，或用
+//! `RuleRegistry::register_custom` 直接合并进注册表。自定义规则不属于任何类别，
+//! 使用 `--rules` / `--categories` 筛选时不运行。
+//!
+//! ```ignore
+//! let mut custom = CustomRules::new();
+//! custom.register_rule("LEGACY_DATE", Severity::P1,
+//!     r#"(object_creation_expression type: (type_identifier) @type (#eq? @type "Date")) @new"#,
+//!     "java.util.Date 可变且非线程安全，使用 java.time",
+//!     Box::new(SimpleMatchHandler { line_capture: "new" }))?;
+//! let options = ScanOptions { custom_rules: custom, ..Default::default() };
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tree_sitter::Query;
+
+use crate::scanner::grammar;
+use crate::scanner::rule_handlers::RuleHandler;
+use crate::scanner::Severity;
+
+/// 自定义规则定义 (Query 规则，处理器见 [`CustomRules::handler`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomRuleDef {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub query: &'static str,
+    pub description: &'static str,
+}
+
+/// 单条规则每个文件的 Query 执行限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecLimits {
+    /// 同时进行中的匹配上限 (`QueryCursor::set_match_limit`)
+    pub match_limit: u32,
+    /// 匹配耗时上限，超时后丢弃该文件剩余的匹配
+    pub timeout: Duration,
+}
+
+/// 运行时注册的规则与处理器
+#[derive(Clone, Default)]
+pub struct CustomRules {
+    rules: Vec<CustomRuleDef>,
+    handlers: Vec<(&'static str, Arc<dyn RuleHandler>)>,
+    limits: Vec<(&'static str, ExecLimits)>,
+}
+
+impl fmt::Debug for CustomRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomRules")
+            .field("rules", &self.rules)
+            .field("handlers", &self.handlers.iter().map(|(id, _)| *id).collect::<Vec<_>>())
+            .field("limits", &self.limits)
+            .finish()
+    }
+}
+
+// 注册入口只供库调用方使用 (CLI 不注册自定义规则)
+#[allow(dead_code)]
+impl CustomRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.handlers.is_empty() && self.limits.is_empty()
+    }
+
+    /// 注册新规则；ID 重复或 Query 无法编译时报错
+    pub fn register_rule(
+        &mut self,
+        id: &'static str,
+        severity: Severity,
+        query: &'static str,
+        description: &'static str,
+        handler: Box<dyn RuleHandler>,
+    ) -> Result<&mut Self, String> {
+        if self.rules.iter().any(|r| r.id == id) {
+            return Err(format!("规则 {id} 已注册"));
+        }
+        Query::new(&grammar::language(), &grammar::active().adapt(query))
+            .map_err(|e| format!("规则 {id} 的 Query 无法编译: {e}"))?;
+        self.rules.push(CustomRuleDef { id, severity, query, description });
+        self.register_handler(id, handler);
+        Ok(self)
+    }
+
+    /// 为规则 ID 注册处理器 (后注册的覆盖先注册的)
+    pub fn register_handler(&mut self, id: &'static str, handler: Box<dyn RuleHandler>) -> &mut Self {
+        self.handlers.retain(|(existing, _)| *existing != id);
+        self.handlers.push((id, Arc::from(handler)));
+        self
+    }
+
+    /// 为规则设置执行限制 (后设置的覆盖先设置的)
+    pub fn set_limits(&mut self, id: &'static str, limits: ExecLimits) -> &mut Self {
+        self.limits.retain(|(existing, _)| *existing != id);
+        self.limits.push((id, limits));
+        self
+    }
+
+    /// 规则的执行限制 (未设置时不限制)
+    pub fn limits(&self, id: &str) -> Option<ExecLimits> {
+        self.limits.iter().find(|(existing, _)| *existing == id).map(|(_, limits)| *limits)
+    }
+
+    /// 注册的新规则 (按注册顺序)
+    pub fn rules(&self) -> &[CustomRuleDef] {
+        &self.rules
+    }
+
+    /// 注册了处理器的规则 ID
+    pub fn handler_ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.handlers.iter().map(|(id, _)| *id)
+    }
+
+    /// 规则 ID 对应的处理器
+    pub fn handler(&self, id: &str) -> Option<Arc<dyn RuleHandler>> {
+        self.handlers.iter().find(|(existing, _)| *existing == id).map(|(_, h)| Arc::clone(h))
+    }
+
+    /// 合并另一组注册 (同 ID 以 `other` 为准)
+    pub fn merge(&mut self, other: &CustomRules) {
+        for rule in &other.rules {
+            self.rules.retain(|r| r.id != rule.id);
+            self.rules.push(rule.clone());
+        }
+        for (id, handler) in &other.handlers {
+            self.handlers.retain(|(existing, _)| existing != id);
+            self.handlers.push((id, Arc::clone(handler)));
+        }
+        for (id, limits) in &other.limits {
+            self.set_limits(id, *limits);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::rule_handlers::SimpleMatchHandler;
+
+    const DATE_QUERY: &str = r#"
+        (object_creation_expression
+            type: (type_identifier) @type
+            (#eq? @type "Date")) @new
+    "#;
+
+    fn date_handler() -> Box<dyn RuleHandler> {
+        Box::new(SimpleMatchHandler { line_capture: "new" })
+    }
+
+    #[test]
+    fn test_register_rule_validates_id_and_query() {
+        let mut custom = CustomRules::new();
+        assert!(custom.is_empty());
+        custom.register_rule("LEGACY_DATE", Severity::P0, DATE_QUERY, "new Date()", date_handler()).unwrap();
+        assert_eq!(custom.rules().len(), 1);
+        assert!(custom.handler("LEGACY_DATE").is_some());
+
+        let err = custom.register_rule("LEGACY_DATE", Severity::P1, DATE_QUERY, "dup", date_handler()).unwrap_err();
+        assert!(err.contains("已注册"), "{err}");
+        let err = custom.register_rule("BROKEN", Severity::P1, "(method_invocation", "broken", date_handler()).unwrap_err();
+        assert!(err.contains("BROKEN"), "{err}");
+        assert!(custom.handler("BROKEN").is_none());
+    }
+
+    #[test]
+    fn test_handler_override_and_merge() {
+        let mut base = CustomRules::new();
+        base.register_handler("EMPTY_CATCH", date_handler());
+        let mut other = CustomRules::new();
+        other.register_rule("LEGACY_DATE", Severity::P0, DATE_QUERY, "new Date()", date_handler()).unwrap();
+        other.register_handler("EMPTY_CATCH", date_handler());
+        base.merge(&other);
+        assert_eq!(base.rules().len(), 1);
+        assert_eq!(format!("{base:?}").matches("EMPTY_CATCH").count(), 1);
+    }
+}
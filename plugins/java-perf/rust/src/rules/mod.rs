@@ -1,5 +1,10 @@
 //! 规则模块
 //!
-//! 包含规则抑制机制
+//! 包含规则抑制机制、规则注册表、运行时注册的自定义规则、外部规则包、规则包版本与规则执行耗时
 
 pub mod suppression;
+pub mod registry;
+pub mod custom;
+pub mod pack;
+pub mod version;
+pub mod timing;
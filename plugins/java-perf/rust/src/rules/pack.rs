@@ -0,0 +1,315 @@
+//! 外部规则包 (`scan --rule-pack DIR`)
+//!
+//! 规则包是一个目录，每个 `.scm` 文件是一条规则：文件头的 `;` 注释给出元数据，
+//! 其余是 Tree-sitter Query，用 `@match` 捕获标记报告位置：
+//!
+//! ```text
+//! ; id: LEGACY_DATE
+//! ; severity: P1
+//! ; description: java.util.Date 可变且非线程安全，使用 java.time
+//! ((object_creation_expression type: (type_identifier) @t (#eq? @t "Date")) @match)
+//! ```
+//!
+//! 规则包可能随被扫描的仓库一起提供，默认按不可信处理 (沙箱):
+//!
+//! | 限制 | 上限 |
+//! |------|------|
+//! | 规则包目录与 `.scm` 文件 (解析符号链接后) 位于扫描目录内 | - |
+//! | 单条 Query 大小 | [`MAX_QUERY_BYTES`] |
+//! | 单条 Query 的模式数 / 括号嵌套深度 | [`MAX_PATTERNS`] / [`MAX_DEPTH`] |
+//! | 每个文件的进行中匹配数 / 匹配耗时 | [`MATCH_LIMIT`] / [`TIMEOUT`] |
+//!
+//! 规则包只含声明式 Query，统一由 `SimpleMatchHandler` 报告，不执行代码，也不读取
+//! 被扫描文件之外的内容。`--trusted` 跳过以上限制 (规则包由自己维护时使用)。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tree_sitter::Query;
+
+use crate::rules::custom::{CustomRules, ExecLimits};
+use crate::scanner::grammar;
+use crate::scanner::rule_handlers::SimpleMatchHandler;
+use crate::scanner::Severity;
+
+pub const MAX_QUERY_BYTES: usize = 16 * 1024;
+pub const MAX_PATTERNS: usize = 32;
+pub const MAX_DEPTH: usize = 32;
+pub const MATCH_LIMIT: u32 = 1024;
+pub const TIMEOUT: Duration = Duration::from_millis(200);
+
+/// 标记报告位置的捕获名
+pub const MATCH_CAPTURE: &str = "match";
+
+/// 规则包的加载权限
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    /// 扫描目录 (规范化路径)
+    root: PathBuf,
+    trusted: bool,
+}
+
+impl Sandbox {
+    pub fn new(root: &Path, trusted: bool) -> Result<Self, String> {
+        let root = root.canonicalize().map_err(|e| format!("扫描目录 {} 不可访问: {e}", root.display()))?;
+        Ok(Self { root, trusted })
+    }
+
+    /// 解析符号链接；不可信时要求位于扫描目录内
+    fn check_path(&self, path: &Path) -> Result<PathBuf, String> {
+        let resolved = path.canonicalize().map_err(|e| format!("{} 不可访问: {e}", path.display()))?;
+        if !self.trusted && !resolved.starts_with(&self.root) {
+            return Err(format!(
+                "{} 位于扫描目录 {} 之外 (确认规则包可信后使用 --trusted)",
+                path.display(), self.root.display()
+            ));
+        }
+        Ok(resolved)
+    }
+
+    /// 编译前的复杂度检查 (大小、嵌套深度)
+    fn check_source(&self, file: &Path, source: &str) -> Result<(), String> {
+        if self.trusted {
+            return Ok(());
+        }
+        if source.len() > MAX_QUERY_BYTES {
+            return Err(format!("{}: Query 超过 {MAX_QUERY_BYTES} 字节", file.display()));
+        }
+        let depth = nesting_depth(source);
+        if depth > MAX_DEPTH {
+            return Err(format!("{}: Query 嵌套深度 {depth} 超过 {MAX_DEPTH}", file.display()));
+        }
+        Ok(())
+    }
+
+    /// 编译后的复杂度检查 (模式数)
+    fn check_query(&self, file: &Path, query: &Query) -> Result<(), String> {
+        if !self.trusted && query.pattern_count() > MAX_PATTERNS {
+            return Err(format!("{}: Query 含 {} 个模式，超过 {MAX_PATTERNS}", file.display(), query.pattern_count()));
+        }
+        Ok(())
+    }
+
+    /// 不可信规则的执行限制
+    fn limits(&self) -> Option<ExecLimits> {
+        (!self.trusted).then_some(ExecLimits { match_limit: MATCH_LIMIT, timeout: TIMEOUT })
+    }
+}
+
+/// `.scm` 文件头的元数据
+#[derive(Debug, PartialEq, Eq)]
+struct RuleHeader {
+    id: String,
+    severity: Severity,
+    description: Option<String>,
+}
+
+impl RuleHeader {
+    /// 解析文件开头连续的 `; key: value` 注释 (id 缺省取文件名，severity 缺省 P1)
+    fn parse(file: &Path, source: &str) -> Result<Self, String> {
+        let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+        let mut header = RuleHeader { id: stem.to_uppercase().replace('-', "_"), severity: Severity::P1, description: None };
+        let comments = source.lines().map(str::trim).take_while(|l| l.is_empty() || l.starts_with(';'));
+        for line in comments {
+            let Some((key, value)) = line.trim_start_matches(';').split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "id" => header.id = value.to_string(),
+                "severity" => header.severity = match value.to_ascii_uppercase().as_str() {
+                    "P0" => Severity::P0,
+                    "P1" => Severity::P1,
+                    _ => return Err(format!("{}: 未知严重级别 {value} (P0 | P1)", file.display())),
+                },
+                "description" => header.description = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        let valid = header.id.starts_with(|c: char| c.is_ascii_uppercase())
+            && header.id.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(format!("{}: 规则 ID {} 只能包含大写字母、数字与下划线", file.display(), header.id));
+        }
+        Ok(header)
+    }
+}
+
+/// 加载规则包目录中的 `.scm` 规则 (目录内按文件名排序)
+///
+/// 规则 ID 在所有规则包中必须唯一。
+pub fn load(dirs: &[PathBuf], sandbox: &Sandbox) -> Result<CustomRules, String> {
+    let mut rules = CustomRules::new();
+    for dir in dirs {
+        let resolved = sandbox.check_path(dir)?;
+        if !resolved.is_dir() {
+            return Err(format!("规则包 {} 不是目录", dir.display()));
+        }
+        let mut files: Vec<PathBuf> = fs::read_dir(&resolved)
+            .map_err(|e| format!("读取规则包 {} 失败: {e}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "scm"))
+            .collect();
+        files.sort();
+        for file in files {
+            load_rule(&file, sandbox, &mut rules)?;
+        }
+    }
+    Ok(rules)
+}
+
+fn load_rule(file: &Path, sandbox: &Sandbox, rules: &mut CustomRules) -> Result<(), String> {
+    let resolved = sandbox.check_path(file)?;
+    // 读取前先按文件大小拒绝，避免读入超大文件
+    let size = fs::metadata(&resolved).map_err(|e| format!("{}: {e}", file.display()))?.len();
+    if !sandbox.trusted && size > MAX_QUERY_BYTES as u64 {
+        return Err(format!("{}: Query 超过 {MAX_QUERY_BYTES} 字节", file.display()));
+    }
+    let source = fs::read_to_string(&resolved).map_err(|e| format!("读取 {} 失败: {e}", file.display()))?;
+    let header = RuleHeader::parse(file, &source)?;
+    sandbox.check_source(file, &source)?;
+
+    let query = Query::new(&grammar::language(), &grammar::active().adapt(&source))
+        .map_err(|e| format!("{}: Query 无法编译: {e}", file.display()))?;
+    sandbox.check_query(file, &query)?;
+    if query.capture_index_for_name(MATCH_CAPTURE).is_none() {
+        return Err(format!("{}: Query 缺少 @{MATCH_CAPTURE} 捕获 (报告位置)", file.display()));
+    }
+
+    // 规则定义与内置规则一样是 'static；规则包每个进程只加载一次
+    let id: &'static str = Box::leak(header.id.into_boxed_str());
+    let description: &'static str = header.description.map_or(id, |d| Box::leak(d.into_boxed_str()));
+    let query: &'static str = Box::leak(source.into_boxed_str());
+    rules.register_rule(id, header.severity, query, description, Box::new(SimpleMatchHandler { line_capture: MATCH_CAPTURE }))
+        .map_err(|e| format!("{}: {e}", file.display()))?;
+    if let Some(limits) = sandbox.limits() {
+        rules.set_limits(id, limits);
+    }
+    Ok(())
+}
+
+/// Query 的括号嵌套深度 (跳过字符串与 `;` 注释)
+fn nesting_depth(source: &str) -> usize {
+    let (mut depth, mut max) = (0usize, 0usize);
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => { chars.next(); }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            ';' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '(' | '[' => {
+                depth += 1;
+                max = max.max(depth);
+            }
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::registry::RuleRegistry;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+    const LEGACY_DATE: &str = "; id: LEGACY_DATE\n; severity: P0\n; description: 使用 java.time\n\
+        ((object_creation_expression type: (type_identifier) @t (#eq? @t \"Date\")) @match)\n";
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_load_pack_inside_scan_root() {
+        let root = tempfile::tempdir().unwrap();
+        let pack = root.path().join(".javaperf/rules");
+        write(&pack, "legacy-date.scm", LEGACY_DATE);
+        write(&pack, "no-header.scm", "((throw_statement) @match)");
+        write(&pack, "README.md", "not a rule");
+
+        let rules = load(std::slice::from_ref(&pack), &Sandbox::new(root.path(), false).unwrap()).unwrap();
+        let ids: Vec<(&str, Severity)> = rules.rules().iter().map(|r| (r.id, r.severity)).collect();
+        assert_eq!(ids, vec![("LEGACY_DATE", Severity::P0), ("NO_HEADER", Severity::P1)]);
+        assert_eq!(rules.limits("LEGACY_DATE"), Some(ExecLimits { match_limit: MATCH_LIMIT, timeout: TIMEOUT }));
+
+        let trusted = load(&[pack], &Sandbox::new(root.path(), true).unwrap()).unwrap();
+        assert_eq!(trusted.limits("LEGACY_DATE"), None);
+    }
+
+    #[test]
+    fn test_untrusted_pack_outside_scan_root() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        write(outside.path(), "legacy.scm", LEGACY_DATE);
+
+        let err = load(&[outside.path().to_path_buf()], &Sandbox::new(root.path(), false).unwrap()).unwrap_err();
+        assert!(err.contains("--trusted"), "{err}");
+        assert!(load(&[outside.path().to_path_buf()], &Sandbox::new(root.path(), true).unwrap()).is_ok());
+
+        // 扫描目录内的符号链接指向目录外的文件
+        #[cfg(unix)]
+        {
+            let pack = root.path().join("rules");
+            fs::create_dir_all(&pack).unwrap();
+            std::os::unix::fs::symlink(outside.path().join("legacy.scm"), pack.join("legacy.scm")).unwrap();
+            let err = load(&[pack], &Sandbox::new(root.path(), false).unwrap()).unwrap_err();
+            assert!(err.contains("之外"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_untrusted_query_complexity_limits() {
+        let root = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(root.path(), false).unwrap();
+        let check = |name: &str, content: &str| {
+            let pack = root.path().join(name);
+            write(&pack, "rule.scm", content);
+            load(&[pack], &sandbox).unwrap_err()
+        };
+
+        let deep = format!("{}(identifier) @match{}", "(block ".repeat(MAX_DEPTH + 1), ")".repeat(MAX_DEPTH + 1));
+        assert!(check("deep", &deep).contains("嵌套深度"));
+        let many = "((identifier) @match)\n".repeat(MAX_PATTERNS + 1);
+        assert!(check("many", &many).contains("模式"));
+        let large = format!("; {}\n((identifier) @match)", "x".repeat(MAX_QUERY_BYTES));
+        assert!(check("large", &large).contains("字节"));
+        assert!(check("capture", "((identifier) @id)").contains("@match"));
+        assert!(check("severity", "; severity: P2\n((identifier) @match)").contains("P2"));
+        assert!(check("id", "; id: legacy\n((identifier) @match)").contains("legacy"));
+        // 字符串与注释中的括号不计入深度
+        assert_eq!(nesting_depth("; ((((\n((identifier) @i (#eq? @i \"((\"))"), 2);
+    }
+
+    #[test]
+    fn test_pack_rule_reports_issue() {
+        let root = tempfile::tempdir().unwrap();
+        write(root.path(), "legacy.scm", LEGACY_DATE);
+        let rules = load(&[root.path().to_path_buf()], &Sandbox::new(root.path(), false).unwrap()).unwrap();
+
+        let mut registry = RuleRegistry::default();
+        registry.register_custom(&rules).unwrap();
+        assert!(registry.exec_limits("LEGACY_DATE").is_some());
+        let analyzer = JavaTreeSitterAnalyzer::with_registry(&registry).unwrap();
+        let code = "class A {\n    void f() {\n        Object d = new Date();\n    }\n}\n";
+        let issues = analyzer.analyze_with_context(code, Path::new("A.java"), None, None).unwrap();
+        let hits: Vec<(&str, usize)> = issues.iter().filter(|i| i.id == "LEGACY_DATE").map(|i| (i.id.as_str(), i.line)).collect();
+        assert_eq!(hits, vec![("LEGACY_DATE", 3)]);
+    }
+}
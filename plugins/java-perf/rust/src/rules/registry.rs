@@ -0,0 +1,1839 @@
+//! 规则注册表
+//!
+//! 集中管理 Java AST 规则定义 (id / 严重级别 / Tree-sitter Query / 描述)，
+//! 并根据 `DetectedStack` 调整严重级别与启用状态：
+//!
+//! - **响应式 (WebFlux/Reactor)**: BLOCKING_IO 升级为 P0 (阻塞 event loop)，
+//!   THREADLOCAL_LEAK 降级为 P1 (链路跨线程执行，ThreadLocal 本身不可靠)
+//!   并启用默认关闭的 Reactor 调度器审计规则 (SCHEDULERS_ELASTIC / PARALLEL_SCHEDULER_BLOCKING / JDBC_NO_BOUNDED_ELASTIC)
+//! - **Servlet (Spring MVC)**: 关闭仅适用于 Reactor 的 PARALLEL_NO_RUN_ON
+//! - **JDK 21-23**: SYNC_BLOCK 升级为 P0 (虚拟线程 Carrier Thread Pinning，JDK 24 起由 JEP 491 修复)
+//! - **grpc-java / OpenFeign 依赖**: 启用默认关闭的 GRPC_NO_DEADLINE / FEIGN_NO_OPTIONS
+//! - **框架版本** (`DetectedStack::framework_versions`): 规则声明适用的版本范围 ([`VERSION_GATES`])，
+//!   范围内关闭规则或为问题追加版本相关说明 (如 Spring Boot 3 已移除 `server.tomcat.max-threads`)
+//!
+//! 调整结果记录在 `adjustments()` 中，由报告输出，避免"规则为何变严"的困惑。
+//!
+//! 可选规则 (如 JDBC_FETCH_SIZE_REQUIRED) 默认关闭，由 `.javaperf.toml` 的 `rule_overrides`
+//! (`enabled = true`) 经 `apply_overrides` 启用，同样记入调整记录。
+//!
+//! `scan --rules` / `--categories` 通过 `apply_filter` 在编译 Query 之前禁用范围外的规则，
+//! 配置文件、Dockerfile、静态初始化等不经 Query 的规则通过 `selects()` 判断。
+//!
+//! 库调用方注册的规则与处理器 (见 `custom` 模块) 通过 `register_custom` 合并进来。
+
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::project_config::RuleOverride;
+use crate::project_detector::DetectedStack;
+use crate::rules::custom::{CustomRules, ExecLimits};
+use crate::rules::version;
+use crate::scanner::held_lock;
+use crate::scanner::rule_handlers::RuleHandler;
+use crate::scanner::Severity;
+
+/// 规则定义
+#[derive(Debug, Clone)]
+pub struct RuleDefinition {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub query: &'static str,
+    pub description: &'static str,
+    pub enabled: bool,
+    /// 强制严重级别 - 覆盖 handler 的动态判定 (如 THREADLOCAL_LEAK 的 P0/P1 分级)
+    pub severity_override: Option<Severity>,
+    /// 框架版本门控 (见 [`VERSION_GATES`])
+    pub versions: &'static [VersionGate],
+}
+
+/// 框架版本范围 `[since, until)` 内对规则的调整
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionGate {
+    /// `DetectedStack::framework_versions` 的键 ("spring-boot" / "quarkus" / "micronaut")
+    pub framework: &'static str,
+    /// 起始版本 (主, 次)，含
+    pub since: Option<(u32, u32)>,
+    /// 截止版本 (主, 次)，不含
+    pub until: Option<(u32, u32)>,
+    pub effect: GateEffect,
+}
+
+/// 版本门控的效果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateEffect {
+    /// 关闭规则 (仅 Query 规则)，附原因
+    Disable(&'static str),
+    /// 为问题描述追加说明 (Query 规则与配置文件规则)
+    Note(&'static str),
+}
+
+impl VersionGate {
+    pub fn contains(&self, version: (u32, u32)) -> bool {
+        self.since.is_none_or(|since| version >= since) && self.until.is_none_or(|until| version < until)
+    }
+
+    /// 范围与效果的说明，如 `spring-boot 2.3 - 3.0 (不含): 追加说明 ...`
+    pub fn describe(&self) -> String {
+        let range = match (self.since, self.until) {
+            (Some((a, b)), Some((c, d))) => format!("{a}.{b} - {c}.{d} (不含)"),
+            (Some((a, b)), None) => format!("{a}.{b}+"),
+            (None, Some((c, d))) => format!("< {c}.{d}"),
+            (None, None) => "全部版本".to_string(),
+        };
+        match self.effect {
+            GateEffect::Disable(reason) => format!("{} {range}: 关闭 - {reason}", self.framework),
+            GateEffect::Note(note) => format!("{} {range}: 追加说明 - {note}", self.framework),
+        }
+    }
+}
+
+/// 适用范围随框架版本变化的规则 (未检测到框架版本时不调整)
+pub const VERSION_GATES: &[(&str, &[VersionGate])] = &[
+    ("ASYNC_DEFAULT_POOL", &[VersionGate {
+        framework: "spring-boot", since: Some((2, 1)), until: None,
+        effect: GateEffect::Note("Spring Boot 2.1+ 默认使用自动配置的 applicationTaskExecutor: 8 个核心线程、队列无界，任务积压时占满内存"),
+    }]),
+    ("SCHEDULERS_ELASTIC", &[VersionGate {
+        framework: "spring-boot", since: Some((3, 0)), until: None,
+        effect: GateEffect::Disable("Spring Boot 3 (Reactor 3.5) 已移除 Schedulers.elastic()"),
+    }]),
+    ("TOMCAT_THREADS_LOW", &[
+        VersionGate {
+            framework: "spring-boot", since: Some((2, 3)), until: Some((3, 0)),
+            effect: GateEffect::Note("Spring Boot 2.3 起该属性更名为 server.tomcat.threads.max，旧名已废弃"),
+        },
+        VersionGate {
+            framework: "spring-boot", since: Some((3, 0)), until: None,
+            effect: GateEffect::Note("Spring Boot 3 已移除 server.tomcat.max-threads，该配置不生效 (实际为默认 200)，请改用 server.tomcat.threads.max"),
+        },
+    ]),
+    ("JPA_OPEN_IN_VIEW", &[VersionGate {
+        framework: "spring-boot", since: Some((2, 0)), until: None,
+        effect: GateEffect::Note("Spring Boot 2.0+ 未配置时同样默认开启 (启动日志 WARN)，需显式设为 false"),
+    }]),
+];
+
+/// 规则的版本门控 (未登记时为空)
+fn version_gates(id: &str) -> &'static [VersionGate] {
+    VERSION_GATES.iter().find(|(rule, _)| *rule == id).map_or(&[], |(_, gates)| *gates)
+}
+
+/// 规则类别 (`scan --categories`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleCategory {
+    /// 查询、循环、字符串等执行效率
+    Performance,
+    /// 锁、线程池、异步等待
+    Concurrency,
+    /// 内存泄漏与无界缓存
+    Memory,
+    /// 连接、流与 RPC 超时
+    Resource,
+    /// Reactor / WebFlux
+    Reactive,
+    /// 异常处理、依赖注入、启动与 native image 兼容性
+    Reliability,
+    /// 配置文件与 Dockerfile
+    Config,
+}
+
+/// 配置文件 (yml/properties) 规则
+pub const CONFIG_FILE_RULES: &[&str] = &[
+    "DB_POOL_SMALL", "DB_CONNECTION_TIMEOUT_MISSING", "DB_CONNECTION_TIMEOUT_LONG", "TOMCAT_THREADS_LOW",
+    "JPA_OPEN_IN_VIEW", "JPA_SHOW_SQL_PROD", "DEBUG_LOG_IN_PROD", "REDIS_TIMEOUT_MISSING",
+];
+
+/// 构建文件依赖规则 (见 dependency_audit、jakarta_audit 模块)
+pub const DEPENDENCY_RULES: &[&str] = &["DEP_VERSION_CONFLICT", "DEP_OVERLAPPING_LIBS", "JAVAX_JAKARTA_MIXED"];
+
+/// Dockerfile 规则
+pub const DOCKERFILE_RULES: &[&str] = &[
+    "DOCKER_LATEST_TAG", "DOCKER_NO_TAG", "DOCKER_SENSITIVE_ENV", "DOCKER_ADD_URL",
+    "DOCKER_MANY_LAYERS", "DOCKER_APT_NO_CLEAN",
+];
+
+/// 规则类别表 (含不经 Tree-sitter Query 的规则)
+const RULE_CATEGORIES: &[(RuleCategory, &[&str])] = &[
+    (RuleCategory::Performance, &[
+        "N_PLUS_ONE", "N_PLUS_ONE_WHILE", "N_PLUS_ONE_FOREACH", "FIND_BY_ID_IN_LOOP", "NESTED_LOOP", "NESTED_LOOP_MIXED",
+        "OBJECT_IN_LOOP", "STRING_INTERN", "CACHEABLE_NO_KEY", "TRANSACTIONAL_REQUIRES_NEW",
+        "LOG_STRING_CONCAT", "STRING_CONCAT_LOOP", "SELECT_STAR", "LIKE_LEADING_WILDCARD",
+        "STREAM_COUNT_ZERO", "PARALLEL_STREAM_SMALL", "ENTITY_EQUALS_COLLECTION",
+        "SQL_UPDATE_NO_WHERE", "SQL_CARTESIAN_JOIN", "SQL_ORDER_BY_NO_LIMIT", "SQL_FUNCTION_ON_COLUMN",
+        "UNPAGED_LIST_ENDPOINT", "RESOURCE_LOAD_HOT_PATH", "UNBOUNDED_RESULT_LIST",
+        "AOP_HOT_PATH", "MANUAL_DTO_COPY", "BIGDECIMAL_FROM_DOUBLE", "BIGDECIMAL_LOOP_ARITHMETIC",
+    ]),
+    (RuleCategory::Concurrency, &[
+        "SYNC_METHOD", "SLEEP_IN_LOCK", "LOCK_METHOD_CALL", "SYNC_BLOCK", "DOUBLE_CHECKED_LOCKING",
+        "ATOMIC_SPIN", "VOLATILE_ARRAY", "SIMPLE_DATE_FORMAT", "RANDOM_SHARED",
+        "FUTURE_GET_NO_TIMEOUT", "AWAIT_NO_TIMEOUT", "COMPLETABLE_JOIN", "COMPLETABLE_GET_NO_TIMEOUT",
+        "ASYNC_DEFAULT_POOL", "SCHEDULED_FIXED_RATE", "UNBOUNDED_POOL", "BLOCKING_IO", "PARALLEL_STREAM_BLOCKING",
+        "SYNC_COLLECTION_MISUSE", "HELD_LOCK_BLOCKING",
+    ]),
+    (RuleCategory::Memory, &[
+        "THREADLOCAL_LEAK", "STATIC_COLLECTION", "CACHE_NO_EXPIRE", "SOFT_REFERENCE", "FINALIZE_OVERRIDE", "LARGE_ARRAY",
+        "UNBOUNDED_REQUEST_BODY", "JDBC_FETCH_SIZE_REQUIRED",
+    ]),
+    (RuleCategory::Resource, &[
+        "STREAM_RESOURCE_LEAK", "DATASOURCE_NO_POOL", "HTTP_CLIENT_TIMEOUT", "GRPC_NO_DEADLINE", "FEIGN_NO_OPTIONS",
+    ]),
+    (RuleCategory::Reactive, &[
+        "FLUX_BLOCK", "SUBSCRIBE_NO_ERROR", "FLUX_COLLECT_LIST", "PARALLEL_NO_RUN_ON", "EMITTER_UNBOUNDED", "SINKS_MANY",
+        "REACTIVE_IN_LOOP", "SCHEDULERS_ELASTIC", "PARALLEL_SCHEDULER_BLOCKING", "JDBC_NO_BOUNDED_ELASTIC",
+    ]),
+    (RuleCategory::Reliability, &[
+        "EMPTY_CATCH", "SYSTEM_EXIT", "RUNTIME_EXEC", "AUTOWIRED_FIELD", "DI_AMBIGUOUS_BEAN", "TRANSACTION_SELF_CALL",
+        "STREAM_FIND_GET", "OPTIONAL_BARE_GET", "GRAALVM_CLASS_FORNAME", "GRAALVM_METHOD_INVOKE", "GRAALVM_PROXY",
+        "STATIC_INIT_CYCLE", "STATIC_INIT_HEAVY", "NPE_RISK", "BIGDECIMAL_EQUALS",
+    ]),
+    (RuleCategory::Config, CONFIG_FILE_RULES),
+    (RuleCategory::Config, DOCKERFILE_RULES),
+    (RuleCategory::Config, DEPENDENCY_RULES),
+];
+
+/// 规则命中时报告的问题 ID (循环变体查询以统一 ID 报告)
+pub fn reported_id(id: &str) -> &str {
+    match id {
+        "N_PLUS_ONE_WHILE" | "N_PLUS_ONE_FOREACH" => "N_PLUS_ONE",
+        "NESTED_LOOP_MIXED" => "NESTED_LOOP",
+        _ => id,
+    }
+}
+
+/// 规则所属类别
+pub fn category_of(id: &str) -> Option<RuleCategory> {
+    RULE_CATEGORIES.iter()
+        .find(|(_, ids)| ids.contains(&id))
+        .map(|(category, _)| *category)
+}
+
+/// 类别表中登记的全部规则 ID (含不经 Query 的规则)
+pub fn known_rule_ids() -> impl Iterator<Item = (RuleCategory, &'static str)> {
+    RULE_CATEGORIES.iter().flat_map(|(category, ids)| ids.iter().map(move |id| (*category, *id)))
+}
+
+impl RuleCategory {
+    /// 报告中的类别标题
+    pub fn title(self) -> &'static str {
+        match self {
+            RuleCategory::Performance => "⚡ 执行效率",
+            RuleCategory::Concurrency => "🔒 并发与锁",
+            RuleCategory::Memory => "🧠 内存",
+            RuleCategory::Resource => "🔌 资源与超时",
+            RuleCategory::Reactive => "🌊 响应式",
+            RuleCategory::Reliability => "🛡️ 可靠性",
+            RuleCategory::Config => "⚙️ 配置与部署",
+        }
+    }
+
+    /// 小写名称 (与 `--categories` 取值一致)
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleCategory::Performance => "performance",
+            RuleCategory::Concurrency => "concurrency",
+            RuleCategory::Memory => "memory",
+            RuleCategory::Resource => "resource",
+            RuleCategory::Reactive => "reactive",
+            RuleCategory::Reliability => "reliability",
+            RuleCategory::Config => "config",
+        }
+    }
+}
+
+/// 规则的修复建议 (报告中各类别"优先处理"清单使用，按报告 ID 查询)
+const FIX_SUGGESTIONS: &[(&str, &str)] = &[
+    // Performance
+    ("N_PLUS_ONE", "改为循环外批量查询 (IN 查询 / findAllById) 后按 key 取用"),
+    ("FIND_BY_ID_IN_LOOP", "用 findAllById 一次取回后转为 Map 按 id 取用"),
+    ("NESTED_LOOP", "把内层集合预先转为 Map/Set，复杂度降到 O(N+M)"),
+    ("OBJECT_IN_LOOP", "把不变对象提到循环外创建或复用"),
+    ("STRING_INTERN", "去掉 intern()，需要去重时用有界的自定义缓存"),
+    ("CACHEABLE_NO_KEY", "为 @Cacheable 显式指定 key (SpEL) 或 keyGenerator"),
+    ("TRANSACTIONAL_REQUIRES_NEW", "确认确实需要独立事务，否则去掉 REQUIRES_NEW 避免额外连接占用"),
+    ("LOG_STRING_CONCAT", "改用占位符 log.debug(\"x={}\", x)，避免日志级别关闭时仍拼接"),
+    ("STRING_CONCAT_LOOP", "循环外创建 StringBuilder，循环内 append"),
+    ("SELECT_STAR", "只查询需要的列"),
+    ("LIKE_LEADING_WILDCARD", "改为后缀通配或全文索引"),
+    ("STREAM_COUNT_ZERO", "改用 anyMatch()/noneMatch() 或集合 isEmpty()"),
+    ("PARALLEL_STREAM_SMALL", "小集合改用 stream()"),
+    ("ENTITY_EQUALS_COLLECTION", "equals/hashCode 只比较 ID 或业务键"),
+    ("SQL_UPDATE_NO_WHERE", "补充 WHERE 条件，批量更新需显式确认"),
+    ("SQL_CARTESIAN_JOIN", "补充 JOIN 条件"),
+    ("SQL_ORDER_BY_NO_LIMIT", "ORDER BY 配合 LIMIT/分页使用"),
+    ("SQL_FUNCTION_ON_COLUMN", "把函数移到比较值一侧，或为表达式建函数索引"),
+    ("UNPAGED_LIST_ENDPOINT", "接口接收 Pageable 或 page/size 参数"),
+    ("RESOURCE_LOAD_HOT_PATH", "启动时加载资源并缓存为字段"),
+    ("UNBOUNDED_RESULT_LIST", "改为分页或流式查询，或设置 fetch size/最大行数"),
+    ("AOP_HOT_PATH", "用 @annotation 或具体类型收窄切点，阻塞调用移出通知，参数序列化放到日志级别判断内或只记录摘要"),
+    ("MANUAL_DTO_COPY", "改用 MapStruct 等编译期生成的 Mapper (避免 BeanUtils.copyProperties 的反射开销)，批量转换用 List 映射方法"),
+    ("BIGDECIMAL_FROM_DOUBLE", "字面量改用字符串构造 new BigDecimal(\"0.1\")，double 变量改用 BigDecimal.valueOf(x)"),
+    ("BIGDECIMAL_LOOP_ARITHMETIC", "不涉及金额等十进制精确计算时循环内改用 long/double (或按最小单位以 long 计数)，循环结束后再转换为 BigDecimal"),
+    // Concurrency
+    ("SYNC_METHOD", "缩小到只保护共享状态的代码块，或改用并发集合/细粒度锁"),
+    ("SLEEP_IN_LOCK", "把 sleep 移出同步块，等待改用 Condition/wait"),
+    ("LOCK_METHOD_CALL", "lock() 后紧跟 try，在 finally 中 unlock()"),
+    ("HELD_LOCK_BLOCKING", "锁内只读写共享状态，把远程调用/IO/数据库访问移到锁外；必须嵌套加锁时全局固定加锁顺序"),
+    ("SYNC_BLOCK", "缩小同步范围；JDK 21+ 虚拟线程场景改用 ReentrantLock"),
+    ("DOUBLE_CHECKED_LOCKING", "字段声明为 volatile，或改用 Holder 类延迟初始化"),
+    ("ATOMIC_SPIN", "高竞争计数改用 LongAdder"),
+    ("VOLATILE_ARRAY", "改用 AtomicIntegerArray/AtomicReferenceArray"),
+    ("SIMPLE_DATE_FORMAT", "改用线程安全的 DateTimeFormatter"),
+    ("RANDOM_SHARED", "改用 ThreadLocalRandom.current()"),
+    ("FUTURE_GET_NO_TIMEOUT", "改用 get(timeout, unit) 并处理 TimeoutException"),
+    ("AWAIT_NO_TIMEOUT", "改用带超时的 await/tryAcquire 并处理超时"),
+    ("COMPLETABLE_JOIN", "改用 orTimeout() 或 get(timeout, unit)"),
+    ("COMPLETABLE_GET_NO_TIMEOUT", "改用 get(timeout, unit) 或 orTimeout()"),
+    ("ASYNC_DEFAULT_POOL", "@Async(\"executorName\") 指定有界线程池"),
+    ("SCHEDULED_FIXED_RATE", "改用 fixedDelay，或为任务加互斥锁"),
+    ("UNBOUNDED_POOL", "改用 ThreadPoolExecutor，配置核心/最大线程数与有界队列"),
+    ("BLOCKING_IO", "改用 NIO (Files/Channels)，或在专用线程池中执行"),
+    ("PARALLEL_STREAM_BLOCKING", "阻塞任务改用专用线程池 + CompletableFuture"),
+    ("SYNC_COLLECTION_MISUSE", "复合操作在 synchronized(集合) 内完成，或改用 Concurrent 集合"),
+    // Memory
+    ("THREADLOCAL_LEAK", "在 finally 中调用 remove()"),
+    ("STATIC_COLLECTION", "改用 Caffeine 等带容量上限与过期策略的缓存"),
+    ("CACHE_NO_EXPIRE", "配置 maximumSize 与 expireAfterWrite/Access"),
+    ("SOFT_REFERENCE", "改用有界缓存，避免依赖 GC 时机回收"),
+    ("FINALIZE_OVERRIDE", "改用 Cleaner 或 try-with-resources"),
+    ("LARGE_ARRAY", "分块处理、复用缓冲区或使用对象池"),
+    ("UNBOUNDED_REQUEST_BODY", "为请求体集合加 @Size(max) 校验"),
+    ("JDBC_FETCH_SIZE_REQUIRED", "设置 fetchSize 或改为分页查询"),
+    // Resource
+    ("STREAM_RESOURCE_LEAK", "改用 try-with-resources"),
+    ("DATASOURCE_NO_POOL", "改用 HikariCP 等连接池的 DataSource"),
+    ("HTTP_CLIENT_TIMEOUT", "显式配置连接超时与读取超时"),
+    ("GRPC_NO_DEADLINE", "每次调用前 withDeadlineAfter()"),
+    ("FEIGN_NO_OPTIONS", "配置 Request.Options 的连接/读取超时"),
+    // Reactive
+    ("FLUX_BLOCK", "保持响应式链路，用 flatMap/zip 组合结果"),
+    ("SUBSCRIBE_NO_ERROR", "subscribe 时提供 error consumer，或在链路中 onErrorResume"),
+    ("FLUX_COLLECT_LIST", "改用 buffer/window 分批处理"),
+    ("PARALLEL_NO_RUN_ON", "parallel() 后追加 runOn(Schedulers.parallel())"),
+    ("EMITTER_UNBOUNDED", "改用 Sinks 并配置背压策略"),
+    ("SINKS_MANY", "为 Sinks.many() 选择有界的背压策略"),
+    ("REACTIVE_IN_LOOP", "改用 Flux.fromIterable + flatMap 并指定并发度"),
+    ("SCHEDULERS_ELASTIC", "改用 Schedulers.boundedElastic()"),
+    ("PARALLEL_SCHEDULER_BLOCKING", "阻塞调用改到 Schedulers.boundedElastic()"),
+    ("JDBC_NO_BOUNDED_ELASTIC", "追加 subscribeOn(Schedulers.boundedElastic())"),
+    // Reliability
+    ("EMPTY_CATCH", "记录日志并处理或重新抛出异常"),
+    ("SYSTEM_EXIT", "抛出异常交由上层处理，去掉 System.exit()"),
+    ("RUNTIME_EXEC", "改用 ProcessBuilder 并校验参数"),
+    ("AUTOWIRED_FIELD", "改为构造器注入"),
+    ("DI_AMBIGUOUS_BEAN", "注入点加 @Qualifier，或为默认实现加 @Primary"),
+    ("TRANSACTION_SELF_CALL", "把被调方法移到另一个 Bean，或注入自身代理 (@Lazy 自注入 / AopContext.currentProxy()) 调用"),
+    ("STREAM_FIND_GET", "改用 orElse/orElseThrow"),
+    ("OPTIONAL_BARE_GET", "改用 orElse/orElseThrow/map"),
+    ("GRAALVM_CLASS_FORNAME", "在 reflect-config.json 中登记反射目标"),
+    ("GRAALVM_METHOD_INVOKE", "在 reflect-config.json 中登记反射方法"),
+    ("GRAALVM_PROXY", "在 proxy-config.json 中登记代理接口"),
+    ("STATIC_INIT_CYCLE", "打破类之间静态初始化的相互依赖"),
+    ("STATIC_INIT_HEAVY", "把重量级初始化改为延迟加载"),
+    ("NPE_RISK", "调用前判空，或改用 Optional 返回值"),
+    ("BIGDECIMAL_EQUALS", "按数值比较改用 a.compareTo(b) == 0 (两侧可能为 null 时先判空)；需要标度也相等时才使用 equals"),
+    // Config
+    ("DB_POOL_SMALL", "按并发量调大连接池 maximum-pool-size"),
+    ("DB_CONNECTION_TIMEOUT_MISSING", "配置 connection-timeout"),
+    ("DB_CONNECTION_TIMEOUT_LONG", "把 connection-timeout 降到数秒以内"),
+    ("TOMCAT_THREADS_LOW", "按负载调大 server.tomcat.threads.max"),
+    ("JPA_OPEN_IN_VIEW", "设置 spring.jpa.open-in-view=false"),
+    ("JPA_SHOW_SQL_PROD", "生产环境关闭 show-sql"),
+    ("DEBUG_LOG_IN_PROD", "生产环境日志级别调为 INFO 及以上"),
+    ("REDIS_TIMEOUT_MISSING", "配置 Redis 命令超时"),
+    ("DOCKER_LATEST_TAG", "基础镜像使用固定版本标签"),
+    ("DOCKER_NO_TAG", "基础镜像使用固定版本标签"),
+    ("DOCKER_SENSITIVE_ENV", "敏感信息改用构建/运行时 secret"),
+    ("DOCKER_ADD_URL", "改用 RUN curl 下载并校验"),
+    ("DOCKER_MANY_LAYERS", "合并相邻 RUN 指令"),
+    ("DOCKER_APT_NO_CLEAN", "同一 RUN 中清理 apt 缓存"),
+    ("DEP_VERSION_CONFLICT", "在父 pom dependencyManagement / 版本目录中统一版本"),
+    ("DEP_OVERLAPPING_LIBS", "统一为一个库，移除或 exclude 其余依赖"),
+    ("JAVAX_JAKARTA_MIXED", "将剩余模块的 import 与依赖迁移到 jakarta.* (可用 OpenRewrite JavaxMigrationToJakarta 配方)，移除 javax API 构件"),
+];
+
+/// 规则的修复建议 (未登记时为 None，调用方回退到问题描述)
+pub fn fix_suggestion(id: &str) -> Option<&'static str> {
+    let id = reported_id(id);
+    FIX_SUGGESTIONS.iter().find(|(rule, _)| *rule == id).map(|(_, fix)| *fix)
+}
+
+/// 规则筛选 (`scan --rules` / `--categories`)：两者取并集，均为空时不筛选
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleFilter {
+    rules: Vec<&'static str>,
+    categories: Vec<RuleCategory>,
+}
+
+impl RuleFilter {
+    /// 规则 ID 不区分大小写；未知 ID 报错，避免拼错后静默扫描 0 条规则
+    pub fn new(rules: &[String], categories: &[RuleCategory]) -> Result<Self, String> {
+        let mut ids = Vec::new();
+        let mut unknown = Vec::new();
+        for rule in rules.iter().map(|r| r.trim()).filter(|r| !r.is_empty()) {
+            match RULE_CATEGORIES.iter().flat_map(|(_, ids)| ids.iter()).find(|id| id.eq_ignore_ascii_case(rule)) {
+                Some(id) => ids.push(*id),
+                None => unknown.push(rule),
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(format!("未知规则 ID: {} (规则列表见 RULES.md)", unknown.join(", ")));
+        }
+        Ok(Self { rules: ids, categories: categories.to_vec() })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.categories.is_empty()
+    }
+
+    /// 规则是否在筛选范围内 (循环变体随其报告 ID 一起选中)
+    pub fn selects(&self, id: &str) -> bool {
+        self.is_empty()
+            || self.rules.contains(&id)
+            || self.rules.contains(&reported_id(id))
+            || category_of(id).is_some_and(|c| self.categories.contains(&c))
+    }
+}
+
+/// 技术栈导致的规则调整记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StackAdjustment {
+    pub rule_id: &'static str,
+    /// 变化描述，如 "P1 → P0" 或 "disabled"
+    pub change: String,
+    pub reason: &'static str,
+}
+
+/// 注册表统计 (本次扫描实际使用的规则)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RegistryStats {
+    /// 内置 Query 规则总数
+    pub total: usize,
+    /// 启用的规则 (技术栈禁用、--rules/--categories 筛选之后)
+    pub enabled: usize,
+    /// 技术栈调整条数
+    pub adjusted: usize,
+    /// 内置规则包版本 (见 `rules::version`，不含自定义规则与 `--rule-pack`)
+    pub pack: &'static str,
+}
+
+/// 规则注册表
+#[derive(Debug, Clone)]
+pub struct RuleRegistry {
+    rules: Vec<RuleDefinition>,
+    adjustments: Vec<StackAdjustment>,
+    filter: RuleFilter,
+    /// 运行时注册的处理器 (优先于 create_handler)
+    custom: CustomRules,
+    /// 未单独设置执行限制的规则统一使用的限制 (`--safe-mode`)
+    default_limits: Option<ExecLimits>,
+    /// 框架版本门控追加到问题描述的说明 (规则 ID, 说明)
+    version_notes: Vec<(&'static str, &'static str)>,
+    /// HELD_LOCK_BLOCKING 沿调用图追踪的层数 (`--lock-depth`)
+    lock_depth: usize,
+    /// BIGDECIMAL_LOOP_ARITHMETIC 视为精确计算的变量名片段 (`.javaperf.toml` 的 `[bigdecimal] exact_names`)
+    exact_names: Option<Vec<String>>,
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self {
+            rules: builtin_rules(),
+            adjustments: Vec::new(),
+            filter: RuleFilter::default(),
+            custom: CustomRules::default(),
+            default_limits: None,
+            version_notes: Vec::new(),
+            lock_depth: held_lock::DEFAULT_DEPTH,
+            exact_names: None,
+        }
+    }
+}
+
+impl RuleRegistry {
+    /// 根据检测到的技术栈构建注册表
+    pub fn with_stack(stack: &DetectedStack) -> Self {
+        let mut registry = Self::default();
+        registry.apply_stack(stack);
+        registry
+    }
+
+    /// 启用全部内置规则 (含依赖门控与可选规则)，供 golden 测试逐条验证 Query
+    pub fn all_enabled() -> Self {
+        let mut registry = Self::default();
+        registry.rules.iter_mut().for_each(|rule| rule.enabled = true);
+        registry
+    }
+
+    /// 所有规则 (含已禁用)
+    pub fn rules(&self) -> &[RuleDefinition] {
+        &self.rules
+    }
+
+    /// 启用的规则
+    pub fn enabled_rules(&self) -> impl Iterator<Item = &RuleDefinition> {
+        self.rules.iter().filter(|r| r.enabled)
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, id: &str) -> Option<&RuleDefinition> {
+        self.rules.iter().find(|r| r.id == id)
+    }
+
+    /// 合并运行时注册的规则与处理器 (在 apply_filter 之前调用)
+    ///
+    /// 新规则 ID 与已有规则重复、或替换处理器的规则不存在时报错。
+    pub fn register_custom(&mut self, custom: &CustomRules) -> Result<(), String> {
+        for def in custom.rules() {
+            if self.rules.iter().any(|r| r.id == def.id) {
+                return Err(format!("自定义规则 {} 与已有规则重复", def.id));
+            }
+            self.rules.push(RuleDefinition {
+                id: def.id,
+                severity: def.severity,
+                query: def.query,
+                description: def.description,
+                enabled: true,
+                severity_override: None,
+                versions: &[],
+            });
+        }
+        let replaced: Vec<&str> = self.rules.iter().map(|r| r.id).collect();
+        if let Some(unknown) = custom.handler_ids().find(|id| !replaced.contains(id)) {
+            return Err(format!("未知规则 ID: {unknown} (register_handler 只能替换已有规则的处理器)"));
+        }
+        self.custom.merge(custom);
+        Ok(())
+    }
+
+    /// 运行时注册的处理器 (未注册时由 create_handler 按 ID 创建)
+    pub fn handler(&self, id: &str) -> Option<Arc<dyn RuleHandler>> {
+        self.custom.handler(id)
+    }
+
+    /// 规则的 Query 执行限制 (不可信规则包的规则，或 [`Self::limit_all`] 设置的限制)
+    pub fn exec_limits(&self, id: &str) -> Option<ExecLimits> {
+        self.custom.limits(id).or(self.default_limits)
+    }
+
+    /// 为所有未单独设置限制的规则设置执行限制 (见 safe_mode 模块)
+    pub fn limit_all(&mut self, limits: ExecLimits) {
+        self.default_limits = Some(limits);
+    }
+
+    /// 设置 HELD_LOCK_BLOCKING 沿调用图追踪的层数
+    pub fn set_lock_depth(&mut self, depth: usize) {
+        self.lock_depth = depth;
+    }
+
+    pub fn lock_depth(&self) -> usize {
+        self.lock_depth
+    }
+
+    /// 替换 BIGDECIMAL_LOOP_ARITHMETIC 视为精确计算的变量名片段 (不区分大小写)
+    pub fn set_exact_names(&mut self, names: Vec<String>) {
+        self.exact_names = Some(names);
+    }
+
+    pub fn exact_names(&self) -> Option<&[String]> {
+        self.exact_names.as_deref()
+    }
+
+    /// 技术栈调整记录
+    pub fn adjustments(&self) -> &[StackAdjustment] {
+        &self.adjustments
+    }
+
+    /// 规则统计 (用于 AnalysisReport)
+    pub fn stats(&self) -> RegistryStats {
+        RegistryStats {
+            total: self.rules.len(),
+            enabled: self.enabled_rules().count(),
+            adjusted: self.adjustments.len(),
+            pack: version::current(),
+        }
+    }
+
+    /// 禁用筛选范围外的规则 (在编译 Query 之前调用，范围外的规则不参与匹配)
+    pub fn apply_filter(&mut self, filter: RuleFilter) {
+        for rule in &mut self.rules {
+            if !filter.selects(rule.id) {
+                rule.enabled = false;
+            }
+        }
+        self.filter = filter;
+    }
+
+    /// 规则是否在本次扫描范围内 (供配置文件/Dockerfile/静态初始化等不经 Query 的规则使用)
+    pub fn selects(&self, id: &str) -> bool {
+        self.filter.selects(id)
+    }
+
+    pub fn filter(&self) -> &RuleFilter {
+        &self.filter
+    }
+
+    /// 按技术栈调整严重级别/启用状态
+    pub fn apply_stack(&mut self, stack: &DetectedStack) {
+        if stack.is_reactive {
+            self.force_severity("BLOCKING_IO", Severity::P0,
+                "响应式项目中阻塞 IO 会占用 event loop 线程");
+            self.force_severity("THREADLOCAL_LEAK", Severity::P1,
+                "响应式链路跨线程执行，ThreadLocal 泄漏影响低于 Servlet 线程池");
+            for id in REACTOR_SCHEDULER_RULES {
+                self.enable(id, "检测到 WebFlux/Reactor");
+            }
+        } else if stack.is_spring_mvc {
+            self.disable("PARALLEL_NO_RUN_ON",
+                "Servlet 项目中 parallel() 多为 Stream API，非 Reactor");
+        }
+
+        // Quarkus / Micronaut (未同时使用 Spring Boot): 按框架的线程模型调整
+        if !stack.is_spring_boot && (stack.is_quarkus || stack.is_micronaut) {
+            if !stack.is_reactive {
+                self.disable("PARALLEL_NO_RUN_ON",
+                    "Quarkus/Micronaut 项目未引入 Reactor，parallel() 多为 Stream API");
+            }
+            if stack.is_micronaut {
+                if !stack.is_reactive {
+                    self.force_severity("BLOCKING_IO", Severity::P0,
+                        "Micronaut 控制器未标注 @ExecuteOn 时运行在 Netty event loop 上");
+                }
+                self.disable("ASYNC_DEFAULT_POOL",
+                    "Micronaut @Async 默认使用有界的 scheduled 线程池，非 SimpleAsyncTaskExecutor");
+            }
+        }
+
+        if stack.has_grpc {
+            self.enable("GRPC_NO_DEADLINE", "检测到 grpc-java 依赖");
+        }
+        if stack.has_feign {
+            self.enable("FEIGN_NO_OPTIONS", "检测到 OpenFeign 依赖");
+        }
+
+        if let Some(major) = stack.jdk_major() {
+            if (21..24).contains(&major) {
+                self.force_severity("SYNC_BLOCK", Severity::P0,
+                    "JDK 21-23 虚拟线程下 synchronized 导致 Carrier Thread Pinning");
+            }
+        }
+
+        for (id, gates) in VERSION_GATES {
+            let applied = gates.iter().filter(|gate| stack.framework_version(gate.framework).is_some_and(|v| gate.contains(v)));
+            for gate in applied {
+                match gate.effect {
+                    GateEffect::Disable(reason) => self.disable(id, reason),
+                    GateEffect::Note(note) => {
+                        self.version_notes.push((id, note));
+                        self.adjustments.push(StackAdjustment { rule_id: id, change: "note".to_string(), reason: note });
+                    }
+                }
+            }
+        }
+    }
+
+    /// 框架版本门控为该规则的问题追加的说明
+    pub fn version_note(&self, id: &str) -> Option<&'static str> {
+        let id = reported_id(id);
+        self.version_notes.iter().find(|(rule, _)| *rule == id).map(|(_, note)| *note)
+    }
+
+    /// 按 `.javaperf.toml` rule_overrides 的 `enabled` 启用/关闭规则或整个类别 (在 apply_filter 之前调用)
+    pub fn apply_overrides(&mut self, overrides: &[RuleOverride]) -> Result<(), String> {
+        for rule in overrides {
+            let Some(enabled) = rule.enabled else { continue };
+            if let Ok(category) = RuleCategory::from_str(rule.id.trim(), true) {
+                let ids: Vec<&'static str> = self.rules.iter().map(|r| r.id).filter(|id| category_of(id) == Some(category)).collect();
+                for id in ids {
+                    if enabled {
+                        self.enable(id, "项目配置 .javaperf.toml 按类别启用");
+                    } else {
+                        self.disable(id, "项目配置 .javaperf.toml 按类别关闭");
+                    }
+                }
+                continue;
+            }
+            let id = self.rules.iter().map(|r| r.id).find(|id| id.eq_ignore_ascii_case(rule.id.trim()))
+                .ok_or_else(|| format!("rule_overrides: 未知规则 ID {} (规则列表见 RULES.md)", rule.id))?;
+            if enabled {
+                self.enable(id, "项目配置 .javaperf.toml 启用");
+            } else {
+                self.disable(id, "项目配置 .javaperf.toml 关闭");
+            }
+        }
+        Ok(())
+    }
+
+    fn force_severity(&mut self, id: &'static str, severity: Severity, reason: &'static str) {
+        if let Some(rule) = self.rules.iter_mut().find(|r| r.id == id) {
+            let before = rule.severity;
+            rule.severity = severity;
+            rule.severity_override = Some(severity);
+            let change = if before == severity {
+                format!("固定为 {severity:?}")
+            } else {
+                format!("{before:?} → {severity:?}")
+            };
+            self.adjustments.push(StackAdjustment { rule_id: id, change, reason });
+        }
+    }
+
+    fn enable(&mut self, id: &'static str, reason: &'static str) {
+        if let Some(rule) = self.rules.iter_mut().find(|r| r.id == id) {
+            if !rule.enabled {
+                rule.enabled = true;
+                self.adjustments.push(StackAdjustment {
+                    rule_id: id,
+                    change: "enabled".to_string(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    fn disable(&mut self, id: &'static str, reason: &'static str) {
+        if let Some(rule) = self.rules.iter_mut().find(|r| r.id == id) {
+            if rule.enabled {
+                rule.enabled = false;
+                self.adjustments.push(StackAdjustment {
+                    rule_id: id,
+                    change: "disabled".to_string(),
+                    reason,
+                });
+            }
+        }
+    }
+}
+
+/// Reactor 调度器审计规则: 只在响应式项目中启用 (Servlet 项目中的 Mono/Flux 多为边缘用法)
+const REACTOR_SCHEDULER_RULES: &[&str] = &["SCHEDULERS_ELASTIC", "PARALLEL_SCHEDULER_BLOCKING", "JDBC_NO_BOUNDED_ELASTIC"];
+
+/// 依赖门控规则: 默认禁用，检测到对应依赖/技术栈时由 apply_stack 启用
+const DEPENDENCY_GATED_RULES: &[&str] = &[
+    "GRPC_NO_DEADLINE", "FEIGN_NO_OPTIONS",
+    "SCHEDULERS_ELASTIC", "PARALLEL_SCHEDULER_BLOCKING", "JDBC_NO_BOUNDED_ELASTIC",
+];
+
+/// 可选规则: 团队约定类检查，默认禁用，在 `.javaperf.toml` rule_overrides 中 `enabled = true` 启用
+const OPT_IN_RULES: &[&str] = &["JDBC_FETCH_SIZE_REQUIRED"];
+
+/// 内置 Java AST 规则
+fn builtin_rules() -> Vec<RuleDefinition> {
+    builtin_rule_defs()
+        .into_iter()
+        .map(|(id, severity, query, description)| RuleDefinition {
+            id,
+            severity,
+            query,
+            description,
+            enabled: !DEPENDENCY_GATED_RULES.contains(&id) && !OPT_IN_RULES.contains(&id),
+            severity_override: None,
+            versions: version_gates(id),
+        })
+        .collect()
+}
+
+/// JdbcTemplate / jOOQ 返回 List 的查询调用 (规则74-75 共用)
+const LIST_QUERY_CALL: &str = r#"
+    (method_invocation
+        name: (identifier) @method_name
+        (#match? @method_name "^(queryForList|query|fetch|fetchInto|fetchMaps)$")
+    ) @call
+"#;
+
+/// 以 SELECT / UPDATE / DELETE 开头的字符串字面量 (规则63-66 共用，由 handler 解析 SQL)
+const SQL_LITERAL_QUERY: &str = r#"
+    ((string_literal) @str
+     (#match? @str "^\"+\\s*(?i:select|update|delete)\\s"))
+"#;
+
+/// 规则定义表: (id, 严重级别, Query, 描述)
+fn builtin_rule_defs() -> Vec<(&'static str, Severity, &'static str, &'static str)> {
+    vec![
+        // 规则1: N_PLUS_ONE - for 循环内的调用
+        ("N_PLUS_ONE", Severity::P0, r#"
+            (for_statement
+                body: (block
+                    (expression_statement
+                        (method_invocation
+                            name: (identifier) @method_name
+                        ) @call
+                    )
+                )
+            )
+        "#, "for 循环内调用方法 (可能是 N+1 问题)"),
+        
+        // 规则1b: N_PLUS_ONE_WHILE - while 循环内的调用
+        ("N_PLUS_ONE_WHILE", Severity::P0, r#"
+            (while_statement
+                body: (block
+                    (expression_statement
+                        (method_invocation
+                            name: (identifier) @method_name
+                        ) @call
+                    )
+                )
+            )
+        "#, "while 循环内调用方法 (可能是 N+1 问题)"),
+        
+        // 规则1c: N_PLUS_ONE_FOREACH - 增强型 for 循环内的调用
+        ("N_PLUS_ONE_FOREACH", Severity::P0, r#"
+            (enhanced_for_statement
+                body: (block
+                    (expression_statement
+                        (method_invocation
+                            name: (identifier) @method_name
+                        ) @call
+                    )
+                )
+            )
+        "#, "foreach 循环内调用方法 (可能是 N+1 问题)"),
+
+        // 规则1d: FIND_BY_ID_IN_LOOP - 循环/迭代 lambda 内 findById(...).get()/orElseThrow()
+        // 所在循环与改写建议由 handler 判断 (含声明语句与 stream().map 中的调用)
+        ("FIND_BY_ID_IN_LOOP", Severity::P0, r#"
+            (method_invocation
+                object: (method_invocation
+                    name: (identifier) @finder
+                    (#eq? @finder "findById")
+                ) @find
+                name: (identifier) @unwrap
+                (#match? @unwrap "^(get|orElseThrow)$")
+            ) @call
+        "#, "循环内逐个 findById 取实体 (N+1)，建议 findAllById 批量查询后按 id 取用"),
+        
+        // 规则2: NESTED_LOOP - for 嵌套 for
+        ("NESTED_LOOP", Severity::P0, r#"
+            (for_statement
+                body: (block
+                    (for_statement) @inner_loop
+                )
+            )
+        "#, "嵌套 for 循环 (可能导致 O(N^2) 复杂度)"),
+        
+        // 规则2b: NESTED_LOOP_FOREACH - for 嵌套 enhanced_for 或反之
+        ("NESTED_LOOP_MIXED", Severity::P0, r#"
+            [
+                (for_statement body: (block (enhanced_for_statement) @inner_loop))
+                (enhanced_for_statement body: (block (for_statement) @inner_loop))
+                (enhanced_for_statement body: (block (enhanced_for_statement) @inner_loop))
+            ]
+        "#, "嵌套循环 (可能导致 O(N^2) 复杂度)"),
+        
+        // 规则3: SYNC_METHOD (方法级同步)
+        ("SYNC_METHOD", Severity::P0, r#"
+            (method_declaration
+                (modifiers) @mods
+            )
+        "#, "Synchronized 方法级锁 (建议改用细粒度锁)"),
+        
+        // 规则4: THREADLOCAL_LEAK (P0)
+        ("THREADLOCAL_LEAK", Severity::P0, r#"
+            (method_invocation
+                object: (identifier) @var_name
+                name: (identifier) @method
+                (#eq? @method "set")
+            ) @set_call
+        "#, "ThreadLocal.set() 后未在同一方法内调用 remove()"),
+        
+        // 规则5: STREAM_RESOURCE_LEAK - try 块内创建流但未在 finally 中关闭
+        ("STREAM_RESOURCE_LEAK", Severity::P1, r#"
+            (try_statement
+                body: (block
+                    (local_variable_declaration
+                        type: (_) @type_name
+                        declarator: (variable_declarator
+                            name: (identifier) @var_name
+                            value: (object_creation_expression) @creation
+                        )
+                    )
+                )
+            ) @try_block
+        "#, "try 块内创建资源，请确保在 finally 中关闭或使用 try-with-resources"),
+        
+        // 规则6: SLEEP_IN_LOCK - synchronized 块内调用 sleep (P0)
+        ("SLEEP_IN_LOCK", Severity::P0, r#"
+            (synchronized_statement
+                body: (block
+                    (expression_statement
+                        (method_invocation
+                            object: (identifier) @class_name
+                            name: (identifier) @method_name
+                            (#eq? @class_name "Thread")
+                            (#eq? @method_name "sleep")
+                        )
+                    )
+                )
+            ) @sync_block
+        "#, "synchronized 块内调用 Thread.sleep()，持锁睡眠导致其他线程阻塞"),
+        
+        // 规则7: LOCK_METHOD_CALL - 检测 ReentrantLock.lock() 调用 (P0)
+        ("LOCK_METHOD_CALL", Severity::P0, r#"
+            (method_invocation
+                object: (identifier) @lock_var
+                name: (identifier) @method
+                (#eq? @method "lock")
+            ) @lock_call
+        "#, "ReentrantLock.lock() 调用，请确保 unlock() 在 finally 块中"),
+        
+        // ====== v7.0 AST 迁移规则 ======
+        
+        // 规则8: @Async 无参数 (使用默认线程池)
+        ("ASYNC_DEFAULT_POOL", Severity::P1, r#"
+            (method_declaration
+                (modifiers
+                    (marker_annotation
+                        name: (identifier) @ann_name
+                        (#eq? @ann_name "Async")
+                    )
+                )
+            ) @method
+        "#, "@Async 未指定线程池，使用默认 SimpleAsyncTaskExecutor"),
+        
+        // 规则9: @Scheduled(fixedRate) 任务堆积风险
+        ("SCHEDULED_FIXED_RATE", Severity::P1, r#"
+            (method_declaration
+                (modifiers
+                    (annotation
+                        name: (identifier) @ann_name
+                        arguments: (annotation_argument_list
+                            (element_value_pair
+                                key: (identifier) @key
+                                (#eq? @key "fixedRate")
+                            )
+                        )
+                        (#eq? @ann_name "Scheduled")
+                    )
+                )
+            ) @method
+        "#, "@Scheduled(fixedRate) 任务可能堆积，考虑使用 fixedDelay"),
+        
+        // 规则10: @Autowired 字段注入
+        ("AUTOWIRED_FIELD", Severity::P1, r#"
+            (field_declaration
+                (modifiers
+                    (marker_annotation
+                        name: (identifier) @ann_name
+                        (#eq? @ann_name "Autowired")
+                    )
+                )
+            ) @field
+        "#, "@Autowired 字段注入不利于测试，建议使用构造器注入"),
+        
+        // 规则11: Flux/Mono.block() 阻塞调用
+        ("FLUX_BLOCK", Severity::P0, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#match? @method_name "^(block|blockFirst|blockLast)$")
+            ) @call
+        "#, "Flux/Mono.block() 阻塞调用，可能导致死锁"),
+        
+        // 规则12: subscribe() 检测 - 需要检查参数数量
+        ("SUBSCRIBE_NO_ERROR", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                arguments: (argument_list) @args
+                (#eq? @method_name "subscribe")
+            ) @call
+        "#, "subscribe() 可能未处理 error，建议添加 error consumer"),
+        
+        // 规则13: collectList() 可能导致 OOM
+        ("FLUX_COLLECT_LIST", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "collectList")
+            ) @call
+        "#, "collectList() 可能导致 OOM，考虑使用 buffer 或 window"),
+        
+        // 规则14: parallel() 未指定 runOn
+        ("PARALLEL_NO_RUN_ON", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "parallel")
+            ) @call
+        "#, "parallel() 建议配合 runOn(Schedulers.parallel()) 使用"),
+        
+        // ====== 更多 AST 迁移规则 (第二批) ======
+        
+        // 规则15: 重写 finalize() 方法 - 简化查询，只匹配方法名
+        ("FINALIZE_OVERRIDE", Severity::P0, r#"
+            (method_declaration
+                type: (void_type)
+                name: (identifier) @method_name
+                (#eq? @method_name "finalize")
+            ) @method
+        "#, "重写 finalize() 已废弃，影响 GC 性能"),
+        
+        // 规则16: String.intern() 调用
+        ("STRING_INTERN", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "intern")
+            ) @call
+        "#, "String.intern() 可能导致元空间溢出"),
+        
+        // 规则17: new SoftReference 使用
+        ("SOFT_REFERENCE", Severity::P1, r#"
+            (object_creation_expression
+                type: (generic_type
+                    (type_identifier) @type_name
+                    (#eq? @type_name "SoftReference")
+                )
+            ) @creation
+        "#, "SoftReference 可能导致 Full GC 时大量对象被回收"),
+        
+        // 规则18: 循环内创建对象
+        ("OBJECT_IN_LOOP", Severity::P1, r#"
+            [
+                (for_statement body: (block (local_variable_declaration declarator: (variable_declarator value: (object_creation_expression) @creation))))
+                (enhanced_for_statement body: (block (local_variable_declaration declarator: (variable_declarator value: (object_creation_expression) @creation))))
+                (while_statement body: (block (local_variable_declaration declarator: (variable_declarator value: (object_creation_expression) @creation))))
+            ]
+        "#, "循环内创建对象，可能导致 GC 压力"),
+        
+        // 规则19: @Cacheable 未指定 key
+        ("CACHEABLE_NO_KEY", Severity::P1, r#"
+            (method_declaration
+                (modifiers
+                    (annotation
+                        name: (identifier) @ann_name
+                        arguments: (annotation_argument_list) @args
+                        (#eq? @ann_name "Cacheable")
+                    )
+                )
+            ) @method
+        "#, "@Cacheable 建议明确指定 key 避免缓存冲突"),
+        
+        // 规则20: @Transactional(propagation = REQUIRES_NEW)
+        ("TRANSACTIONAL_REQUIRES_NEW", Severity::P1, r#"
+            (method_declaration
+                (modifiers
+                    (annotation
+                        name: (identifier) @ann_name
+                        arguments: (annotation_argument_list
+                            (element_value_pair
+                                key: (identifier) @key
+                                value: (_) @value
+                                (#eq? @key "propagation")
+                            )
+                        )
+                        (#eq? @ann_name "Transactional")
+                    )
+                )
+            ) @method
+        "#, "@Transactional 事务传播设置，请确保理解嵌套事务行为"),
+        
+        // ====== 第三批 AST 迁移规则 ======
+        
+        // 规则21: Future.get() 无超时
+        ("FUTURE_GET_NO_TIMEOUT", Severity::P0, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                arguments: (argument_list) @args
+                (#eq? @method_name "get")
+            ) @call
+        "#, "Future.get() 无超时参数，可能永久阻塞"),
+        
+        // 规则22: await()/acquire() 无超时
+        ("AWAIT_NO_TIMEOUT", Severity::P0, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                arguments: (argument_list) @args
+                (#match? @method_name "^(await|acquire)$")
+            ) @call
+        "#, "await()/acquire() 无超时参数，可能永久阻塞"),
+        
+        // 规则23: CompletableFuture.join() 无超时
+        ("COMPLETABLE_JOIN", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "join")
+            ) @call
+        "#, "CompletableFuture.join() 无超时，可能永久阻塞"),
+        
+        // 规则24: 日志字符串拼接
+        ("LOG_STRING_CONCAT", Severity::P1, r#"
+            (method_invocation
+                object: (identifier) @obj
+                name: (identifier) @method_name
+                arguments: (argument_list
+                    (binary_expression
+                        operator: "+"
+                    ) @concat
+                )
+                (#match? @obj "^(log|logger|LOG|LOGGER)$")
+                (#match? @method_name "^(debug|info|warn|error|trace)$")
+            ) @call
+        "#, "日志使用字符串拼接，建议使用占位符 log.info(\"x={}\", x)"),
+        
+        // 规则25: synchronized 代码块 (提醒检查范围 + Virtual Thread Pinning)
+        ("SYNC_BLOCK", Severity::P1, r#"
+            (synchronized_statement
+                (parenthesized_expression) @lock_obj
+                body: (block) @body
+            ) @sync
+        "#, "synchronized 代码块，请确保锁范围最小化。注意: JDK 21+ Virtual Threads 下会导致 Carrier Thread Pinning"),
+        
+        // 规则26: EmitterProcessor.create() 无界
+        ("EMITTER_UNBOUNDED", Severity::P0, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                arguments: (argument_list) @args
+                (#eq? @class_name "EmitterProcessor")
+                (#eq? @method_name "create")
+            ) @call
+        "#, "EmitterProcessor.create() 无界背压，可能导致 OOM"),
+        
+        // ====== 第四批 AST 迁移规则 (最终批次) ======
+        
+        // 规则27: Executors.newCachedThreadPool 等无界线程池
+        ("UNBOUNDED_POOL", Severity::P0, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                (#eq? @class_name "Executors")
+                (#match? @method_name "^(newCachedThreadPool|newScheduledThreadPool|newSingleThreadExecutor)$")
+            ) @call
+        "#, "Executors 无界线程池，建议使用 ThreadPoolExecutor 配置有界队列"),
+        
+        // 规则28: 空 catch 块
+        ("EMPTY_CATCH", Severity::P0, r#"
+            (catch_clause
+                body: (block) @body
+            ) @catch
+        "#, "catch 块可能为空或仅打印，请正确处理异常"),
+        
+        // 规则29: new FileInputStream/FileOutputStream
+        ("BLOCKING_IO", Severity::P1, r#"
+            (object_creation_expression
+                type: (type_identifier) @type_name
+                (#match? @type_name "^File(Input|Output)Stream$")
+            ) @creation
+        "#, "FileInputStream/FileOutputStream 同步阻塞 IO，考虑使用 NIO"),
+        
+        // 规则30: AtomicInteger/AtomicLong 高竞争
+        ("ATOMIC_SPIN", Severity::P1, r#"
+            (object_creation_expression
+                type: (type_identifier) @type_name
+                (#match? @type_name "^Atomic(Integer|Long)$")
+            ) @creation
+        "#, "AtomicInteger/Long 高竞争时考虑使用 LongAdder"),
+        
+        // 规则31: Sinks.many() 无背压
+        ("SINKS_MANY", Severity::P1, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                (#eq? @class_name "Sinks")
+                (#eq? @method_name "many")
+            ) @call
+        "#, "Sinks.many() 需要配置背压策略"),
+        
+        // 规则32: Caffeine/CacheBuilder.newBuilder()
+        ("CACHE_NO_EXPIRE", Severity::P1, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                (#match? @class_name "^(Caffeine|CacheBuilder)$")
+                (#eq? @method_name "newBuilder")
+            ) @call
+        "#, "Cache.newBuilder() 请确保配置了过期策略和最大大小"),
+        
+        // 规则33: static Map/List/Set 无界缓存
+        ("STATIC_COLLECTION", Severity::P0, r#"
+            (field_declaration
+                (modifiers) @mods
+                type: (generic_type
+                    (type_identifier) @type_name
+                    (#match? @type_name "^(Map|HashMap|ConcurrentHashMap|List|ArrayList|Set|HashSet)$")
+                )
+            ) @field
+        "#, "static 集合作为缓存需配置大小限制和过期策略"),
+        
+        // 规则34: DriverManager.getConnection 直连
+        ("DATASOURCE_NO_POOL", Severity::P1, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                (#eq? @class_name "DriverManager")
+                (#eq? @method_name "getConnection")
+            ) @call
+        "#, "DriverManager.getConnection 直接获取连接，建议使用连接池"),
+        
+        // ====== 最终批次 AST 规则 ======
+        
+        // 规则35: 循环内字符串 += 拼接
+        ("STRING_CONCAT_LOOP", Severity::P1, r#"
+            [
+                (for_statement body: (block (expression_statement (assignment_expression left: (_) @var operator: "+=" right: (_) @value)) @assign))
+                (enhanced_for_statement body: (block (expression_statement (assignment_expression left: (_) @var operator: "+=" right: (_) @value)) @assign))
+                (while_statement body: (block (expression_statement (assignment_expression left: (_) @var operator: "+=" right: (_) @value)) @assign))
+            ]
+        "#, "循环内使用 += 拼接字符串，建议使用 StringBuilder"),
+        
+        // 规则36: 大数组分配 new byte[1000000] / new byte[SIZE] - 长度由 handler 做常量折叠
+        ("LARGE_ARRAY", Severity::P1, r#"
+            (array_creation_expression
+                type: (integral_type) @type_name
+                dimensions: (dimensions_expr
+                    (_) @size
+                )
+            ) @creation
+        "#, "大数组分配可能导致 Full GC，考虑对象池或分块处理"),
+
+        // ====== v8.0 Java 现代化规则 ======
+        // 注意: VIRTUAL_THREAD_PINNING 已合并到 SYNC_BLOCK 规则中
+        //       避免同一位置重复报告
+
+        // 规则37: GraalVM Class.forName 检测
+        ("GRAALVM_CLASS_FORNAME", Severity::P1, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                (#eq? @class_name "Class")
+                (#eq? @method_name "forName")
+            ) @call
+        "#, "[GraalVM] Class.forName 需要配置 reflect-config.json"),
+        
+        // 规则39: GraalVM Method.invoke 检测
+        ("GRAALVM_METHOD_INVOKE", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "invoke")
+            ) @call
+        "#, "[GraalVM] Method.invoke 需要配置反射元数据"),
+        
+        // 规则40: GraalVM Proxy.newProxyInstance 检测
+        ("GRAALVM_PROXY", Severity::P1, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                (#eq? @class_name "Proxy")
+                (#eq? @method_name "newProxyInstance")
+            ) @call
+        "#, "[GraalVM] Proxy.newProxyInstance 需要配置 proxy-config.json"),
+
+        // ====== v9.0 新增高价值规则 ======
+
+        // 规则41: Double-Checked Locking 反模式
+        ("DOUBLE_CHECKED_LOCKING", Severity::P0, r#"
+            (if_statement
+                consequence: (block
+                    (synchronized_statement
+                        body: (block
+                            (if_statement) @inner_if
+                        )
+                    )
+                )
+            ) @outer_if
+        "#, "Double-Checked Locking 反模式，需要 volatile 或使用 Holder 模式"),
+
+        // 规则42: CompletableFuture.get() 无超时
+        ("COMPLETABLE_GET_NO_TIMEOUT", Severity::P0, r#"
+            (method_invocation
+                object: (_) @obj
+                name: (identifier) @method_name
+                arguments: (argument_list) @args
+                (#eq? @method_name "get")
+            ) @call
+        "#, "CompletableFuture.get() 无超时参数，可能导致线程永久阻塞"),
+
+        // 规则43: 自调用绕过 Spring 代理 - 被调方法的代理注解由 handler 从本类声明或 CallGraph 判断
+        ("TRANSACTION_SELF_CALL", Severity::P0, r#"
+            [
+                (method_invocation
+                    !object
+                    name: (identifier) @method_name
+                ) @call
+                (method_invocation
+                    object: (this)
+                    name: (identifier) @method_name
+                ) @call
+            ]
+        "#, "经 this 调用本类的 @Transactional/@Cacheable/@Async 方法不经过 Spring 代理，事务/缓存/异步不生效（自调用问题）"),
+
+        // 规则44: volatile 数组元素访问
+        ("VOLATILE_ARRAY", Severity::P1, r#"
+            (field_declaration
+                (modifiers) @mods
+                type: (array_type) @array_type
+            ) @field
+        "#, "volatile 数组只保证引用可见性，元素操作不具备原子性"),
+
+        // 规则45: System.exit() 调用
+        ("SYSTEM_EXIT", Severity::P0, r#"
+            (method_invocation
+                object: (identifier) @class_name
+                name: (identifier) @method_name
+                (#eq? @class_name "System")
+                (#eq? @method_name "exit")
+            ) @call
+        "#, "System.exit() 会终止 JVM，不应在生产代码中使用"),
+
+        // 规则46: Runtime.getRuntime().exec() 命令注入风险
+        ("RUNTIME_EXEC", Severity::P0, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "exec")
+            ) @call
+        "#, "Runtime.exec() 存在命令注入风险，请使用 ProcessBuilder"),
+
+        // 规则47: SimpleDateFormat 非线程安全
+        ("SIMPLE_DATE_FORMAT", Severity::P1, r#"
+            (object_creation_expression
+                type: (type_identifier) @type_name
+                (#eq? @type_name "SimpleDateFormat")
+            ) @creation
+        "#, "SimpleDateFormat 非线程安全，考虑使用 DateTimeFormatter (Java 8+)"),
+
+        // 规则48: Random 在多线程环境
+        ("RANDOM_SHARED", Severity::P1, r#"
+            (field_declaration
+                (modifiers) @mods
+                type: (type_identifier) @type_name
+                (#eq? @type_name "Random")
+            ) @field
+        "#, "共享 Random 实例在高并发下性能差，考虑使用 ThreadLocalRandom"),
+
+        // ====== v9.1 从 Regex 迁移的 SQL 检测规则 ======
+
+        // 规则49: SELECT * 检测 - 匹配包含 "SELECT *" 的字符串字面量
+        // 以及引用 SQL 常量 (static final String / 枚举常量) 的调用点
+        ("SELECT_STAR", Severity::P1, r#"
+            ((string_literal) @str
+             (#match? @str "SELECT\\s+\\*\\s+FROM"))
+
+            (argument_list [(identifier) (field_access)] @const_ref)
+            (method_invocation object: [(identifier) (field_access)] @const_ref)
+        "#, "SELECT * 查询，建议明确指定字段以减少数据传输"),
+
+        // 规则50: LIKE 前导通配符 - 匹配 LIKE '%xxx' 模式 (字面量 + SQL 常量引用)
+        ("LIKE_LEADING_WILDCARD", Severity::P0, r#"
+            ((string_literal) @str
+             (#match? @str "LIKE\\s+['\"]%"))
+
+            (argument_list [(identifier) (field_access)] @const_ref)
+            (method_invocation object: [(identifier) (field_access)] @const_ref)
+        "#, "LIKE '%xxx' 前导通配符导致无法使用索引，引发全表扫描"),
+
+        // 规则51: HTTP 客户端使用检测 - 提醒检查超时配置
+        ("HTTP_CLIENT_TIMEOUT", Severity::P1, r#"
+            (method_invocation
+                object: [
+                    (identifier) @obj
+                    (method_invocation) @obj
+                ]
+                name: (identifier) @method
+                (#match? @obj "(HttpClient|RestTemplate|OkHttp|WebClient)")
+            ) @call
+        "#, "HTTP 客户端使用，请确认已配置连接超时和读取超时"),
+
+        // 规则52: 同类型多 Bean 注入歧义 - 注入点 (字段/构造参数) 由 handler 借助 SymbolTable 聚合候选
+        ("DI_AMBIGUOUS_BEAN", Severity::P0, r#"
+            (field_declaration
+                (modifiers) @mods
+                type: (type_identifier) @type_name
+                declarator: (variable_declarator name: (identifier) @var_name)
+            ) @inject
+            (constructor_declaration
+                parameters: (formal_parameters
+                    (formal_parameter
+                        type: (type_identifier) @type_name
+                        name: (identifier) @var_name
+                    ) @inject
+                )
+            )
+        "#, "同类型存在多个 Bean 且注入点无 @Qualifier/@Primary，启动时抛出 NoUniqueBeanDefinitionException"),
+
+        // 规则53: stream().findFirst().get() - Stream 终端操作返回的 Optional 直接 get()
+        ("STREAM_FIND_GET", Severity::P1, r#"
+            (method_invocation
+                object: (method_invocation name: (identifier) @prev)
+                name: (identifier) @method_name
+                arguments: (argument_list) @args
+                (#eq? @method_name "get")
+                (#match? @prev "^(findFirst|findAny|min|max|reduce)$")
+            ) @call
+        "#, "findFirst()/findAny() 结果直接 get()，无匹配时抛出 NoSuchElementException，应使用 orElse/orElseThrow"),
+
+        // 规则54: Optional.get() 裸调用 - 变量类型/来源由 handler 借助 CallChain 判断
+        ("OPTIONAL_BARE_GET", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                arguments: (argument_list) @args
+                (#eq? @method_name "get")
+            ) @call
+        "#, "Optional.get() 前无 isPresent()/isEmpty() 检查，应使用 orElse/orElseThrow/map"),
+
+        // 规则55: stream().count() == 0 - 遍历整个流只为判断是否为空
+        ("STREAM_COUNT_ZERO", Severity::P1, r#"
+            (binary_expression
+                left: (method_invocation name: (identifier) @method_name (#eq? @method_name "count")) @call
+                right: (decimal_integer_literal) @num
+            ) @cmp
+            (binary_expression
+                left: (decimal_integer_literal) @num
+                right: (method_invocation name: (identifier) @method_name (#eq? @method_name "count")) @call
+            ) @cmp
+        "#, "stream().count() 与 0 比较会遍历整个流，应使用 anyMatch()/noneMatch() 或 isEmpty()"),
+
+        // 规则56: gRPC stub 调用无 deadline (依赖门控) - stub 来源由 handler 借助 CallChain 判断
+        ("GRPC_NO_DEADLINE", Severity::P0, r#"
+            (method_invocation
+                name: (identifier) @method_name
+            ) @call
+        "#, "gRPC stub 调用未设置 deadline (withDeadlineAfter)，下游无响应时调用线程永久等待"),
+
+        // 规则57: Feign.builder() 未配置 Request.Options (依赖门控)
+        ("FEIGN_NO_OPTIONS", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#match? @method_name "^(target|build)$")
+            ) @call
+        "#, "Feign.builder() 未配置 Request.Options，使用默认超时 (连接 10s / 读取 60s)，下游变慢时长时间占用请求线程"),
+
+        // 规则58: parallelStream() 中执行阻塞 IO/DB 调用 - 阻塞调用由 handler 结合 SymbolTable/CallGraph 判断
+        ("PARALLEL_STREAM_BLOCKING", Severity::P0, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "parallelStream")
+            ) @call
+        "#, "parallelStream() 的 lambda 中执行阻塞 IO/数据库调用，占满公共 ForkJoinPool，拖慢 JVM 内所有并行流与 CompletableFuture 默认任务"),
+
+        // 规则59: 对元素个数固定的小集合使用 parallelStream()
+        ("PARALLEL_STREAM_SMALL", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#eq? @method_name "parallelStream")
+            ) @call
+        "#, "对元素个数固定的小集合使用 parallelStream()，任务拆分与线程调度开销大于并行收益，应使用 stream()"),
+
+        // 规则60: @Entity/@Document 的 equals/hashCode 包含集合字段 - 方法体与 Lombok 注解参数由 handler 解析
+        ("ENTITY_EQUALS_COLLECTION", Severity::P1, r#"
+            (class_declaration
+                (modifiers [
+                    (marker_annotation name: (identifier) @ann_name)
+                    (annotation name: (identifier) @ann_name)
+                ])
+                (#match? @ann_name "^(Entity|Document)$")
+            ) @class
+        "#, "实体 equals/hashCode 包含集合字段：懒加载集合在事务外触发 LazyInitializationException，每次哈希遍历整个集合 (O(N))，放入 HashSet 后集合变化导致找不到元素，应只比较 ID/业务键"),
+
+        // 规则61: Collections.synchronizedXxx 字段被无锁遍历或先查后写 - 同一字段在类中的调用点由 handler 汇总
+        ("SYNC_COLLECTION_MISUSE", Severity::P1, r#"
+            (field_declaration
+                declarator: (variable_declarator
+                    name: (identifier) @field_name
+                    value: (method_invocation
+                        object: (identifier) @cls
+                        name: (identifier) @factory
+                        (#eq? @cls "Collections")
+                        (#match? @factory "^synchronized(Map|SortedMap|NavigableMap|List|Set|SortedSet|NavigableSet|Collection)$")
+                    )
+                )
+            ) @field
+        "#, "Collections.synchronizedXxx 只保证单次调用原子：未加锁的遍历可能抛 ConcurrentModificationException，先查后写存在竞态，且所有访问争用同一把锁"),
+
+        // 规则62: 命令式循环内组装并订阅/阻塞响应式管道 - 响应式接收者与循环祖先由 handler 判断
+        ("REACTIVE_IN_LOOP", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#match? @method_name "^(subscribe|block|blockFirst|blockLast|toFuture)$")
+            ) @call
+        "#, "循环内逐次组装并订阅/阻塞 Mono/Flux：subscribe 使请求并发失控，block 使请求完全串行，应使用 Flux.fromIterable + flatMap 并指定并发度"),
+
+        // ====== SQL 执行计划启发式 (sqlparser 解析字面量) ======
+
+        // 规则63: UPDATE / DELETE 缺少 WHERE
+        ("SQL_UPDATE_NO_WHERE", Severity::P0, SQL_LITERAL_QUERY,
+            "UPDATE/DELETE 没有 WHERE 条件，会修改或删除全表并长时间持有表锁"),
+
+        // 规则64: 笛卡尔积 - CROSS JOIN、无 ON 的 JOIN、逗号连接缺少等值条件
+        ("SQL_CARTESIAN_JOIN", Severity::P0, SQL_LITERAL_QUERY,
+            "多表连接缺少连接条件产生笛卡尔积，结果行数为各表行数乘积"),
+
+        // 规则65: 列表查询 ORDER BY 无 LIMIT - 方法返回类型由 handler 判断
+        ("SQL_ORDER_BY_NO_LIMIT", Severity::P1, SQL_LITERAL_QUERY,
+            "列表查询排序但不限制行数，数据增长后全量排序并返回，建议分页或加 LIMIT"),
+
+        // 规则66: WHERE 中对列使用函数 - DATE(col) / LOWER(col) / CAST(col ...)
+        ("SQL_FUNCTION_ON_COLUMN", Severity::P1, SQL_LITERAL_QUERY,
+            "WHERE 条件对列使用函数导致索引失效，建议改写为范围条件或使用函数索引"),
+
+        // ====== Controller 接口 ======
+
+        // 规则67: 列表接口缺少分页 - 返回类型、分页参数与端点路径由 handler 解析
+        ("UNPAGED_LIST_ENDPOINT", Severity::P1, r#"
+            (method_declaration
+                (modifiers [
+                    (marker_annotation name: (identifier) @ann_name)
+                    (annotation name: (identifier) @ann_name)
+                ])
+                (#match? @ann_name "^(GetMapping|PostMapping|RequestMapping)$")
+            ) @method
+        "#, "列表接口返回集合但不接收分页参数，数据增长后单次响应的查询、序列化与内存占用均无上限，建议接收 Pageable 或 page/size 参数"),
+
+        // 规则68: @RequestBody 接收无上限集合 - 参数类型与 DTO 集合字段的 @Size 由 handler 检查
+        ("UNBOUNDED_REQUEST_BODY", Severity::P1, r#"
+            (formal_parameter
+                (modifiers [
+                    (marker_annotation name: (identifier) @ann_name)
+                    (annotation name: (identifier) @ann_name)
+                ])
+                (#eq? @ann_name "RequestBody")
+            ) @param
+        "#, "请求体集合没有 @Size(max) 上限，单个请求即可提交任意多元素，放大下游批量查询/写入与内存占用"),
+
+        // ====== Reactor 调度器审计 (仅响应式项目启用) ======
+
+        // 规则69: Schedulers.elastic() - 线程数无上限，Reactor 3.4 起废弃
+        ("SCHEDULERS_ELASTIC", Severity::P1, r#"
+            (method_invocation
+                object: (identifier) @obj
+                (#eq? @obj "Schedulers")
+                name: (identifier) @method_name
+                (#match? @method_name "^(elastic|newElastic)$")
+            ) @call
+        "#, "Schedulers.elastic() 为每个阻塞任务按需创建线程且没有上限 (Reactor 3.4 起废弃)，突发负载下线程数失控，应改用 Schedulers.boundedElastic()"),
+
+        // 规则70: parallel/single 调度器上的阻塞调用 - 调度器作用范围与阻塞调用由 handler 判断
+        ("PARALLEL_SCHEDULER_BLOCKING", Severity::P0, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#match? @method_name "^(publishOn|subscribeOn|runOn)$")
+                arguments: (argument_list
+                    (method_invocation
+                        object: (identifier) @sched_obj
+                        (#eq? @sched_obj "Schedulers")
+                        name: (identifier) @scheduler
+                        (#match? @scheduler "^(parallel|single|newParallel|newSingle)$")))
+            ) @call
+        "#, "parallel/single 调度器只有 CPU 核数个线程，用于非阻塞计算；在其上执行 JDBC/阻塞调用会占满调度器，拖慢所有共用它的管道"),
+
+        // 规则71: 包装 JDBC 调用的 Mono/Flux 未指定 subscribeOn - 包装内容与链上的 subscribeOn 由 handler 判断
+        ("JDBC_NO_BOUNDED_ELASTIC", Severity::P0, r#"
+            (method_invocation
+                object: (identifier) @obj
+                (#match? @obj "^(Mono|Flux)$")
+                name: (identifier) @method_name
+                (#match? @method_name "^(fromCallable|fromSupplier|fromRunnable|defer|using)$")
+            ) @call
+        "#, "fromCallable 等包装的 JDBC/阻塞 Repository 调用在订阅线程上执行，WebFlux 中即 Netty event loop，应追加 subscribeOn(Schedulers.boundedElastic())"),
+
+        // 规则72: 请求路径上加载类路径资源 - 接收者类型、所在循环/层级与启动期方法由 handler 判断
+        ("RESOURCE_LOAD_HOT_PATH", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method_name
+                (#match? @method_name "^(getBundle|getResourceAsStream|getResource|getResources|getSystemResourceAsStream|getSystemResource|load|loadFromXML)$")
+            ) @call
+        "#, "请求处理方法或循环内加载 ResourceBundle/类路径资源/Properties，每次都查找类路径并解析文件，应在启动时加载并缓存"),
+
+        // 规则73: @Nullable 返回值未判空解引用 - 被调方法注解由 handler 经 SymbolTable 解析，仅方法内分析
+        ("NPE_RISK", Severity::P1, r#"
+            (method_invocation
+                object: (_) @object
+                name: (identifier) @method_name
+            ) @call
+        "#, "在 @Nullable 方法的返回值上直接调用方法 (或局部变量未判空)，返回 null 时抛出 NullPointerException"),
+
+        // 规则74-75: DAO 层 JdbcTemplate/jOOQ 列表查询 - API、行数限制与 fetch size 由 handler 判断
+        ("UNBOUNDED_RESULT_LIST", Severity::P1, LIST_QUERY_CALL,
+            "DAO 层 JdbcTemplate/jOOQ 列表查询未限制行数 (无 LIMIT/setMaxRows/limit())，数据增长后一次加载全部结果，内存与耗时随表增长"),
+        ("JDBC_FETCH_SIZE_REQUIRED", Severity::P1, LIST_QUERY_CALL,
+            "Repository 列表查询未显式设置 fetch size，也未使用 ResultSetExtractor/RowCallbackHandler 等流式处理 (可选规则)"),
+
+        // 规则76: 宽泛切点上的阻塞/序列化通知 - @Aspect、切点范围与通知内容由 handler 判断
+        ("AOP_HOT_PATH", Severity::P1, r#"
+            (method_declaration
+                (modifiers
+                    (annotation
+                        name: (identifier) @ann_name
+                        (#match? @ann_name "^(Around|Before|After|AfterReturning|AfterThrowing)$")
+                    )
+                )
+            ) @method
+        "#, "切点按包通配全部方法的切面通知内有阻塞调用或序列化全部参数，每个被拦截的请求路径方法都要承担这部分开销"),
+
+        // 规则77: 实体与 DTO 之间手写逐字段复制 - 连续条数、数据类与所在层级/循环由 handler 判断
+        ("MANUAL_DTO_COPY", Severity::P1, r#"
+            (expression_statement
+                (method_invocation
+                    object: (identifier)
+                    name: (identifier) @setter
+                    (#match? @setter "^set[A-Z]")
+                    arguments: (argument_list (method_invocation))
+                )
+            ) @copy
+        "#, "Controller/Service 中手写 setX(getX()) 逐字段复制实体与 DTO，字段增删容易漏改；在循环或流中逐元素转换时属于性能敏感路径"),
+
+        // 规则78: 持锁区域 (synchronized 方法/块、lock() 后的 try 块) - 经调用图追踪由 handler 判断
+        ("HELD_LOCK_BLOCKING", Severity::P0, r#"
+            [
+                (method_declaration (modifiers "synchronized")) @held
+                (synchronized_statement) @held
+                (method_invocation
+                    name: (identifier) @lock_method
+                    (#match? @lock_method "^(lock|lockInterruptibly)$")
+                ) @held
+            ]
+        "#, "持锁期间调用的方法在调用链深处获取其他锁或执行阻塞 IO/数据库访问，持锁时间被拉长到网络往返级别"),
+
+        // 规则79: 由 double 构造 BigDecimal - 参数为浮点字面量/double 变量由 handler 判断
+        ("BIGDECIMAL_FROM_DOUBLE", Severity::P1, r#"
+            (object_creation_expression
+                type: (type_identifier) @type_name
+                (#eq? @type_name "BigDecimal")
+                arguments: (argument_list . (_))
+            ) @creation
+        "#, "new BigDecimal(double) 取到浮点数的二进制近似值，精度错误且非标度值长达五十多位，后续运算更慢"),
+
+        // 规则80: 大循环内 BigDecimal/BigInteger 运算 - 接收者类型、循环规模与精确计算变量名由 handler 判断
+        ("BIGDECIMAL_LOOP_ARITHMETIC", Severity::P1, r#"
+            (method_invocation
+                object: (_)
+                name: (identifier) @method
+                (#match? @method "^(add|subtract|multiply|divide|remainder|pow|negate|abs|mod|sqrt|setScale|round|max|min|movePointLeft|movePointRight|stripTrailingZeros)$")
+            ) @call
+        "#, "大循环内的 BigDecimal/BigInteger 运算每次都分配新对象，不涉及十进制精确计算时比 long/double 慢一到两个数量级"),
+
+        // 规则81: BigDecimal 用 equals 比较数值 - 接收者/参数类型由 handler 判断
+        ("BIGDECIMAL_EQUALS", Severity::P1, r#"
+            (method_invocation
+                name: (identifier) @method
+                (#eq? @method "equals")
+                arguments: (argument_list)
+            ) @call
+        "#, "BigDecimal.equals 同时比较标度，2.0 与 2.00 不相等，按数值比较应使用 compareTo"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boot(version: &str) -> DetectedStack {
+        let mut stack = stack(false, true, "17");
+        stack.framework_versions.insert("spring-boot".into(), version.into());
+        stack
+    }
+
+    fn stack(reactive: bool, mvc: bool, jdk: &str) -> DetectedStack {
+        DetectedStack {
+            is_spring_boot: true,
+            is_spring_mvc: mvc,
+            is_reactive: reactive,
+            jdk_version: jdk.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_registry_has_unique_ids() {
+        let registry = RuleRegistry::default();
+        let mut ids: Vec<_> = registry.rules().iter().map(|r| r.id).collect();
+        let total = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), total, "rule ids must be unique");
+        assert!(registry.rules().iter().all(|r| {
+            r.enabled != (DEPENDENCY_GATED_RULES.contains(&r.id) || OPT_IN_RULES.contains(&r.id))
+                && r.severity_override.is_none()
+        }));
+        assert!(registry.adjustments().is_empty());
+    }
+
+    #[test]
+    fn test_every_rule_has_category() {
+        let registry = RuleRegistry::default();
+        let missing: Vec<_> = registry.rules().iter().map(|r| r.id).filter(|id| category_of(id).is_none()).collect();
+        assert!(missing.is_empty(), "rules without category: {missing:?}");
+    }
+
+    #[test]
+    fn test_every_categorized_rule_has_fix_suggestion() {
+        let missing: Vec<_> = RULE_CATEGORIES.iter()
+            .flat_map(|(_, ids)| ids.iter())
+            .filter(|id| fix_suggestion(id).is_none())
+            .collect();
+        assert!(missing.is_empty(), "rules without fix suggestion: {missing:?}");
+        assert_eq!(fix_suggestion("N_PLUS_ONE_WHILE"), fix_suggestion("N_PLUS_ONE"));
+        for category in RuleCategory::value_variants() {
+            assert_eq!(category.to_possible_value().unwrap().get_name(), category.name());
+        }
+    }
+
+    #[test]
+    fn test_register_custom_rules() {
+        use crate::rules::custom::CustomRules;
+        use crate::scanner::rule_handlers::SimpleMatchHandler;
+
+        let handler = || Box::new(SimpleMatchHandler { line_capture: "new" });
+        let query = r#"((object_creation_expression type: (type_identifier) @t (#eq? @t "Date")) @new)"#;
+        let mut custom = CustomRules::new();
+        custom.register_rule("LEGACY_DATE", Severity::P1, query, "new Date()", handler()).unwrap();
+        custom.register_handler("EMPTY_CATCH", handler());
+
+        let mut registry = RuleRegistry::default();
+        let total = registry.stats().total;
+        registry.register_custom(&custom).unwrap();
+        assert_eq!(registry.stats().total, total + 1);
+        assert!(registry.get("LEGACY_DATE").is_some_and(|r| r.enabled));
+        assert!(registry.handler("EMPTY_CATCH").is_some() && registry.handler("N_PLUS_ONE").is_none());
+        // 再次合并：新规则 ID 与已有规则重复
+        assert!(registry.register_custom(&custom).unwrap_err().contains("LEGACY_DATE"));
+
+        let mut clash = CustomRules::new();
+        clash.register_rule("SYSTEM_EXIT", Severity::P1, query, "clash", handler()).unwrap();
+        assert!(RuleRegistry::default().register_custom(&clash).is_err());
+    }
+
+    #[test]
+    fn test_rule_filter() {
+        let filter = RuleFilter::new(&["n_plus_one".to_string(), " EMPTY_CATCH".to_string()], &[RuleCategory::Concurrency]).unwrap();
+        assert!(filter.selects("N_PLUS_ONE") && filter.selects("EMPTY_CATCH") && filter.selects("SYNC_BLOCK"));
+        assert!(filter.selects("N_PLUS_ONE_FOREACH") && filter.selects("N_PLUS_ONE_WHILE"));
+        assert!(!filter.selects("SELECT_STAR") && !filter.selects("DOCKER_LATEST_TAG"));
+
+        let err = RuleFilter::new(&["N_PLUS_ONE".to_string(), "NPLUS1".to_string()], &[]).unwrap_err();
+        assert!(err.contains("NPLUS1") && !err.contains("N_PLUS_ONE"), "{err}");
+
+        let mut registry = RuleRegistry::default();
+        registry.apply_filter(RuleFilter::new(&[], &[RuleCategory::Reactive]).unwrap());
+        let enabled: Vec<_> = registry.enabled_rules().map(|r| r.id).collect();
+        assert!(enabled.contains(&"FLUX_BLOCK") && !enabled.contains(&"N_PLUS_ONE"), "{enabled:?}");
+        assert!(registry.selects("FLUX_BLOCK") && !registry.selects("STATIC_INIT_CYCLE"));
+        assert!(RuleRegistry::default().selects("STATIC_INIT_CYCLE"));
+    }
+
+    #[test]
+    fn test_reactive_stack_adjustments() {
+        let registry = RuleRegistry::with_stack(&stack(true, false, "17"));
+        assert_eq!(registry.get("BLOCKING_IO").unwrap().severity, Severity::P0);
+        assert_eq!(registry.get("THREADLOCAL_LEAK").unwrap().severity_override, Some(Severity::P1));
+        assert!(registry.get("PARALLEL_NO_RUN_ON").unwrap().enabled);
+        assert_eq!(registry.adjustments().len(), 2 + REACTOR_SCHEDULER_RULES.len());
+    }
+
+    #[test]
+    fn test_servlet_stack_disables_reactor_only_rules() {
+        let registry = RuleRegistry::with_stack(&stack(false, true, "17"));
+        assert!(!registry.get("PARALLEL_NO_RUN_ON").unwrap().enabled);
+        assert!(registry.enabled_rules().all(|r| r.id != "PARALLEL_NO_RUN_ON"));
+        assert_eq!(registry.get("BLOCKING_IO").unwrap().severity, Severity::P1);
+    }
+
+    #[test]
+    fn test_quarkus_micronaut_stack_adjustments() {
+        let quarkus = DetectedStack { is_quarkus: true, ..Default::default() };
+        let registry = RuleRegistry::with_stack(&quarkus);
+        assert!(!registry.get("PARALLEL_NO_RUN_ON").unwrap().enabled);
+        assert!(registry.get("ASYNC_DEFAULT_POOL").unwrap().enabled);
+        assert_eq!(registry.get("BLOCKING_IO").unwrap().severity, Severity::P1);
+
+        let micronaut = DetectedStack { is_micronaut: true, ..Default::default() };
+        let registry = RuleRegistry::with_stack(&micronaut);
+        assert_eq!(registry.get("BLOCKING_IO").unwrap().severity, Severity::P0);
+        assert!(!registry.get("ASYNC_DEFAULT_POOL").unwrap().enabled);
+        assert_eq!(registry.adjustments().len(), 3);
+
+        // 与 Spring Boot 混用时以 Spring 语义为准
+        let registry = RuleRegistry::with_stack(&DetectedStack { is_micronaut: true, ..stack(false, true, "17") });
+        assert!(registry.get("ASYNC_DEFAULT_POOL").unwrap().enabled);
+    }
+
+    #[test]
+    fn test_rpc_rules_gated_by_dependencies() {
+        let registry = RuleRegistry::with_stack(&stack(false, true, "17"));
+        assert!(!registry.get("GRPC_NO_DEADLINE").unwrap().enabled);
+        assert!(!registry.get("FEIGN_NO_OPTIONS").unwrap().enabled);
+
+        let registry = RuleRegistry::with_stack(&DetectedStack { has_grpc: true, ..stack(false, true, "17") });
+        assert!(registry.get("GRPC_NO_DEADLINE").unwrap().enabled);
+        assert!(!registry.get("FEIGN_NO_OPTIONS").unwrap().enabled);
+        assert!(registry.adjustments().iter().any(|a| a.rule_id == "GRPC_NO_DEADLINE" && a.change == "enabled"));
+    }
+
+    #[test]
+    fn test_apply_overrides_toggles_rules() {
+        let toggle = |id: &str, enabled| RuleOverride { id: id.into(), exclude: Vec::new(), enabled: Some(enabled), sample_rate: None };
+        let mut registry = RuleRegistry::default();
+        assert!(!registry.get("JDBC_FETCH_SIZE_REQUIRED").unwrap().enabled);
+
+        registry.apply_overrides(&[
+            toggle("jdbc_fetch_size_required", true),
+            toggle("EMPTY_CATCH", false),
+            RuleOverride { id: "N_PLUS_ONE".into(), exclude: vec!["**/legacy/**".into()], enabled: None, sample_rate: None },
+        ]).unwrap();
+        assert!(registry.get("JDBC_FETCH_SIZE_REQUIRED").unwrap().enabled);
+        assert!(!registry.get("EMPTY_CATCH").unwrap().enabled);
+        assert!(registry.get("N_PLUS_ONE").unwrap().enabled);
+        let changes: Vec<_> = registry.adjustments().iter().map(|a| (a.rule_id, a.change.as_str())).collect();
+        assert_eq!(changes, vec![("JDBC_FETCH_SIZE_REQUIRED", "enabled"), ("EMPTY_CATCH", "disabled")]);
+
+        let err = registry.apply_overrides(&[toggle("NO_SUCH_RULE", true)]).unwrap_err();
+        assert!(err.contains("NO_SUCH_RULE"), "{err}");
+
+        registry.apply_overrides(&[toggle("reactive", false)]).unwrap();
+        assert!(!registry.get("FLUX_BLOCK").unwrap().enabled && !registry.get("SINKS_MANY").unwrap().enabled);
+        assert!(registry.get("N_PLUS_ONE").unwrap().enabled);
+    }
+
+    #[test]
+    fn test_reactor_scheduler_rules_gated_by_reactive_stack() {
+        assert!(REACTOR_SCHEDULER_RULES.iter().all(|id| DEPENDENCY_GATED_RULES.contains(id)));
+
+        let registry = RuleRegistry::with_stack(&stack(false, true, "17"));
+        assert!(REACTOR_SCHEDULER_RULES.iter().all(|id| !registry.get(id).unwrap().enabled));
+
+        let registry = RuleRegistry::with_stack(&stack(true, false, "17"));
+        assert!(REACTOR_SCHEDULER_RULES.iter().all(|id| registry.get(id).unwrap().enabled));
+        assert!(registry.adjustments().iter().any(|a| a.rule_id == "JDBC_NO_BOUNDED_ELASTIC" && a.change == "enabled"));
+    }
+
+    #[test]
+    fn test_jdk21_escalates_sync_block() {
+        let registry = RuleRegistry::with_stack(&stack(false, false, "21"));
+        assert_eq!(registry.get("SYNC_BLOCK").unwrap().severity, Severity::P0);
+
+        // JDK 24 起 synchronized 不再 pin carrier thread
+        let registry = RuleRegistry::with_stack(&stack(false, false, "24"));
+        assert_eq!(registry.get("SYNC_BLOCK").unwrap().severity, Severity::P1);
+
+        let registry = RuleRegistry::with_stack(&stack(false, false, "1.8"));
+        assert_eq!(registry.get("SYNC_BLOCK").unwrap().severity, Severity::P1);
+    }
+
+    #[test]
+    fn test_framework_version_gates() {
+        assert!(VERSION_GATES.iter().all(|(id, _)| RULE_CATEGORIES.iter().any(|(_, ids)| ids.contains(id))));
+        assert_eq!(RuleRegistry::default().get("ASYNC_DEFAULT_POOL").unwrap().versions.len(), 1);
+
+        // 未检测到版本: 不调整
+        let registry = RuleRegistry::with_stack(&stack(false, true, "17"));
+        assert_eq!(registry.version_note("TOMCAT_THREADS_LOW"), None);
+
+        let registry = RuleRegistry::with_stack(&boot("2.7.18"));
+        assert!(registry.version_note("TOMCAT_THREADS_LOW").unwrap().contains("2.3 起"));
+        assert!(registry.version_note("ASYNC_DEFAULT_POOL").is_some());
+        let mut reactive = boot("2.7.18");
+        reactive.is_reactive = true;
+        assert!(RuleRegistry::with_stack(&reactive).get("SCHEDULERS_ELASTIC").unwrap().enabled);
+
+        let registry = RuleRegistry::with_stack(&boot("3.2.1"));
+        assert!(registry.version_note("TOMCAT_THREADS_LOW").unwrap().contains("不生效"));
+        reactive.framework_versions.insert("spring-boot".into(), "3.2.1".into());
+        let registry = RuleRegistry::with_stack(&reactive);
+        assert!(!registry.get("SCHEDULERS_ELASTIC").unwrap().enabled);
+        assert!(registry.adjustments().iter().any(|a| a.rule_id == "SCHEDULERS_ELASTIC" && a.change == "disabled"));
+
+        let registry = RuleRegistry::with_stack(&boot("2.0.9.RELEASE"));
+        assert_eq!(registry.version_note("ASYNC_DEFAULT_POOL"), None);
+        assert!(registry.version_note("JPA_OPEN_IN_VIEW").is_some());
+        assert_eq!(version_gates("TOMCAT_THREADS_LOW")[0].describe().split(':').next(), Some("spring-boot 2.3 - 3.0 (不含)"));
+        assert!(version_gates("ASYNC_DEFAULT_POOL")[0].describe().starts_with("spring-boot 2.1+: 追加说明"));
+    }
+}
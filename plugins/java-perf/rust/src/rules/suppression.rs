@@ -14,10 +14,14 @@
 //! 3. 文件级抑制
 //!    // java-perf-ignore-file: RULE_ID
 //!    // java-perf-ignore-file (抑制所有规则)
+//!
+//! 被抑制的问题记录抑制来源 (SuppressionSource)，`scan --include-suppressed`
+//! 时随报告输出，便于审计。
 
 use std::collections::{HashMap, HashSet};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 /// 抑制指令正则
 static SUPPRESS_COMMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
@@ -49,15 +53,36 @@ pub struct Suppression {
     pub rule_ids: HashSet<String>,
 }
 
+/// 抑制来源的形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SuppressionKind {
+    /// `// java-perf-ignore:`
+    Comment,
+    /// `// java-perf-ignore-next-line:`
+    NextLineComment,
+    /// `@SuppressWarnings("java-perf:...")`
+    Annotation,
+    /// `// java-perf-ignore-file`
+    File,
+}
+
+/// 抑制来源 (指令形式 + 指令所在行)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuppressionSource {
+    pub kind: SuppressionKind,
+    pub line: usize,
+}
+
 /// 文件抑制上下文
 #[derive(Debug, Default)]
 pub struct SuppressionContext {
-    /// 行号 -> 该行被抑制的规则
-    line_suppressions: HashMap<usize, HashSet<String>>,
-    /// 文件级抑制的规则
-    file_suppressions: HashSet<String>,
-    /// 是否抑制文件中的所有规则
-    suppress_all_file: bool,
+    /// 行号 -> 作用于该行的抑制 (规则集合, 来源)
+    line_suppressions: HashMap<usize, Vec<(HashSet<String>, SuppressionSource)>>,
+    /// 文件级抑制的规则 -> 来源
+    file_suppressions: HashMap<String, SuppressionSource>,
+    /// 抑制文件中所有规则的指令
+    suppress_all_file: Option<SuppressionSource>,
 }
 
 impl SuppressionContext {
@@ -72,22 +97,27 @@ impl SuppressionContext {
             if let Some(suppression) = parse_comment_suppression(line) {
                 match suppression.suppression_type {
                     SuppressionType::Line => {
+                        let source = SuppressionSource { kind: SuppressionKind::Comment, line: line_number };
                         ctx.line_suppressions
                             .entry(line_number)
                             .or_default()
-                            .extend(suppression.rule_ids);
+                            .push((suppression.rule_ids, source));
                     }
                     SuppressionType::NextLine => {
+                        let source = SuppressionSource { kind: SuppressionKind::NextLineComment, line: line_number };
                         ctx.line_suppressions
                             .entry(line_number + 1)
                             .or_default()
-                            .extend(suppression.rule_ids);
+                            .push((suppression.rule_ids, source));
                     }
                     SuppressionType::File => {
+                        let source = SuppressionSource { kind: SuppressionKind::File, line: line_number };
                         if suppression.rule_ids.is_empty() {
-                            ctx.suppress_all_file = true;
+                            ctx.suppress_all_file.get_or_insert(source);
                         } else {
-                            ctx.file_suppressions.extend(suppression.rule_ids);
+                            for rule_id in suppression.rule_ids {
+                                ctx.file_suppressions.entry(rule_id).or_insert(source);
+                            }
                         }
                     }
                 }
@@ -97,10 +127,11 @@ impl SuppressionContext {
             if let Some(rule_ids) = parse_annotation_suppression(line) {
                 // 注解抑制应用于接下来的元素（方法/字段/类）
                 // 这里简化处理，假设注解在声明的前一行
+                let source = SuppressionSource { kind: SuppressionKind::Annotation, line: line_number };
                 ctx.line_suppressions
                     .entry(line_number + 1)
                     .or_default()
-                    .extend(rule_ids);
+                    .push((rule_ids, source));
             }
         }
 
@@ -108,31 +139,34 @@ impl SuppressionContext {
     }
 
     /// 检查指定规则在指定行是否被抑制
+    #[allow(dead_code)]
     pub fn is_suppressed(&self, rule_id: &str, line: usize) -> bool {
+        self.suppression_for(rule_id, line).is_some()
+    }
+
+    /// 指定规则在指定行的抑制来源 (未被抑制时为 None)
+    pub fn suppression_for(&self, rule_id: &str, line: usize) -> Option<SuppressionSource> {
         // 文件级全部抑制
-        if self.suppress_all_file {
-            return true;
+        if let Some(source) = self.suppress_all_file {
+            return Some(source);
         }
 
         // 文件级特定规则抑制
-        if self.file_suppressions.contains(rule_id) {
-            return true;
+        if let Some(source) = self.file_suppressions.get(rule_id) {
+            return Some(*source);
         }
 
-        // 行级抑制
-        if let Some(suppressed_rules) = self.line_suppressions.get(&line) {
-            // 空集合表示抑制所有
-            if suppressed_rules.is_empty() || suppressed_rules.contains(rule_id) {
-                return true;
-            }
-        }
-
-        false
+        // 行级抑制 (空集合表示抑制所有)
+        self.line_suppressions.get(&line)?
+            .iter()
+            .find(|(rules, _)| rules.is_empty() || rules.contains(rule_id))
+            .map(|(_, source)| *source)
     }
 
     /// 检查文件是否完全被抑制
+    #[allow(dead_code)]
     pub fn is_file_suppressed(&self) -> bool {
-        self.suppress_all_file
+        self.suppress_all_file.is_some()
     }
 }
 
@@ -277,4 +311,15 @@ mod tests {
         assert!(ctx.is_suppressed("NESTED_LOOP", 2));
         assert!(!ctx.is_suppressed("SYNC_METHOD", 2));
     }
+
+    #[test]
+    fn test_suppression_source() {
+        let code = "// java-perf-ignore-file: SYNC_METHOD\nclass A {\n    // java-perf-ignore-next-line: N_PLUS_ONE\n    void f() {}\n    @SuppressWarnings(\"java-perf:NESTED_LOOP\")\n    void g() {}\n}\n";
+        let ctx = SuppressionContext::parse(code);
+        let source = |kind, line| Some(SuppressionSource { kind, line });
+        assert_eq!(ctx.suppression_for("SYNC_METHOD", 9), source(SuppressionKind::File, 1));
+        assert_eq!(ctx.suppression_for("N_PLUS_ONE", 4), source(SuppressionKind::NextLineComment, 3));
+        assert_eq!(ctx.suppression_for("NESTED_LOOP", 6), source(SuppressionKind::Annotation, 5));
+        assert_eq!(ctx.suppression_for("NESTED_LOOP", 4), None);
+    }
 }
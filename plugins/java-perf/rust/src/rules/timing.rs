@@ -0,0 +1,148 @@
+//! 规则执行耗时与执行顺序 (`scan --rule-timings`)
+//!
+//! 开启 `--rule-timings` 时，分析器为每条 Query 规则累计它在各文件上的匹配与处理器耗时，
+//! 报告中按累计耗时从高到低输出 (JSON `rule_timings`)，并累加到 `.javaperf/rule-timings.json`。
+//! 不开启时分析过程不计时，也不做任何原子计数。
+//!
+//! Java 文件上的规则按代价从低到高执行: 代价取该文件中记录的实测平均耗时，没有记录的规则
+//! 按 Query 中的谓词估计 ([`static_cost`]，纯结构 Query 在前，带 `#match?` 正则谓词的在后)。
+//! 执行顺序不影响结果，同一文件的问题仍按注册表顺序输出。文件上的全部规则都被 rule_overrides
+//! 排除时 (如按类别排除生成代码目录) 不再解析、不执行任何 Query。`dev bench-rules` 在合成语料上
+//! 对比注册表顺序、估计/实测代价顺序、开启计时与按类别排除的耗时。
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::symbol_cache::CACHE_DIR;
+
+/// 耗时记录文件 (位于 `.javaperf/`)
+pub const TIMINGS_FILE: &str = "rule-timings.json";
+
+/// 没有谓词的 Query 的估计耗时 (纳秒/文件)
+const BASE_COST_NS: u64 = 20_000;
+/// 每个文本谓词 (`#eq?` / `#any-of?` 等) 增加的估计耗时
+const TEXT_PREDICATE_NS: u64 = 5_000;
+/// 每个正则谓词 (`#match?` / `#not-match?`) 增加的估计耗时
+const REGEX_PREDICATE_NS: u64 = 40_000;
+
+/// 单条规则的耗时
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleCost {
+    /// 执行过该规则的文件数
+    pub files: u64,
+    /// 累计耗时 (微秒)
+    pub total_us: u64,
+}
+
+impl RuleCost {
+    /// 每个文件的平均耗时 (纳秒)
+    pub fn mean_ns(self) -> u64 {
+        self.total_us.saturating_mul(1000) / self.files.max(1)
+    }
+}
+
+/// 报告中的单条规则耗时 (JSON `rule_timings`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleTiming {
+    pub id: &'static str,
+    #[serde(flatten)]
+    pub cost: RuleCost,
+    /// 每个文件的平均耗时 (微秒)
+    pub mean_us: u64,
+}
+
+impl RuleTiming {
+    pub fn new(id: &'static str, cost: RuleCost) -> Self {
+        Self { id, cost, mean_us: cost.mean_ns() / 1000 }
+    }
+}
+
+/// 项目的规则耗时记录 (`.javaperf/rule-timings.json`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingProfile {
+    /// 规则 ID → 历次 `--rule-timings` 扫描的累计耗时
+    pub rules: BTreeMap<String, RuleCost>,
+}
+
+impl TimingProfile {
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(CACHE_DIR).join(TIMINGS_FILE)
+    }
+
+    /// 读取项目的耗时记录 (不存在或无法解析时为空)
+    pub fn load(root: &Path) -> Self {
+        std::fs::read_to_string(Self::path(root)).ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, root: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(root.join(CACHE_DIR))?;
+        let text = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(Self::path(root), text)
+    }
+
+    /// 累加一次扫描的耗时 (未执行过的规则不记录)
+    pub fn record(&mut self, timings: &[RuleTiming]) {
+        for timing in timings.iter().filter(|t| t.cost.files > 0) {
+            let entry = self.rules.entry(timing.id.to_string()).or_default();
+            entry.files += timing.cost.files;
+            entry.total_us += timing.cost.total_us;
+        }
+    }
+
+    /// 规则的代价 (纳秒/文件)：有实测记录时取平均耗时，否则按 Query 估计
+    pub fn cost(&self, id: &str, query: &str) -> u64 {
+        match self.rules.get(id) {
+            Some(cost) if cost.files > 0 => cost.mean_ns(),
+            _ => static_cost(query),
+        }
+    }
+}
+
+/// 按 Query 中的谓词估计规则代价 (纳秒/文件)
+pub fn static_cost(query: &str) -> u64 {
+    let regex = query.matches("#match?").count() + query.matches("#not-match?").count();
+    let text = query.matches("#eq?").count() + query.matches("#not-eq?").count()
+        + query.matches("#any-of?").count() + query.matches("#not-any-of?").count();
+    BASE_COST_NS + regex as u64 * REGEX_PREDICATE_NS + text as u64 * TEXT_PREDICATE_NS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_timing_mean() {
+        let timing = RuleTiming::new("N_PLUS_ONE", RuleCost { files: 4, total_us: 50 });
+        assert_eq!(timing.cost.mean_ns(), 12_500);
+        assert_eq!(timing.mean_us, 12);
+        assert_eq!(RuleCost::default().mean_ns(), 0);
+
+        let json = serde_json::to_value(&timing).unwrap();
+        assert_eq!(json, serde_json::json!({"id": "N_PLUS_ONE", "files": 4, "total_us": 50, "mean_us": 12}));
+    }
+
+    #[test]
+    fn test_measured_cost_overrides_estimate() {
+        let structural = "(for_statement body: (block) @body)";
+        let regex = r#"(method_invocation name: (identifier) @m (#match? @m "^(find|get)"))"#;
+        assert!(static_cost(structural) < static_cost(regex));
+
+        let mut profile = TimingProfile::default();
+        profile.record(&[RuleTiming::new("REGEX_RULE", RuleCost { files: 4, total_us: 20 })]);
+        profile.record(&[RuleTiming::new("REGEX_RULE", RuleCost { files: 6, total_us: 30 }), RuleTiming::new("IDLE", RuleCost::default())]);
+        assert_eq!(profile.rules["REGEX_RULE"], RuleCost { files: 10, total_us: 50 });
+        assert!(!profile.rules.contains_key("IDLE"));
+        // 实测 5µs/文件，排在估计 20µs 的结构查询之前
+        assert_eq!(profile.cost("REGEX_RULE", regex), 5_000);
+        assert_eq!(profile.cost("OTHER", structural), static_cost(structural));
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(TimingProfile::load(dir.path()), TimingProfile::default());
+        profile.save(dir.path()).unwrap();
+        assert_eq!(TimingProfile::load(dir.path()), profile);
+    }
+}
@@ -0,0 +1,349 @@
+//! 规则包版本 (`rules changelog --from <版本>`)
+//!
+//! 工具升级后比对前后两份报告时，问题数的变化可能来自代码，也可能来自规则本身 (新增规则、
+//! 严重级别调整、Query 扩大了匹配范围)。每份报告都带上内置规则包版本 (JSON `stats.rules.pack`、
+//! SARIF `tool.driver.properties.rulesPackVersion`、Markdown 报告末尾)，版本是全部内置规则定义的哈希:
+//!
+//! - Query 规则: 严重级别、默认启用状态、Query (空白归一化)、描述、类别与框架版本门控
+//! - 不经 Query 的规则 (配置文件、Dockerfile、静态初始化等): 规则 ID 与类别
+//!
+//! 历次发布的规则定义快照记录在 `resources/rules-history.json` (编译时嵌入)，`rules changelog`
+//! 据此列出从给定版本到当前版本之间新增、移除与变更的规则，附上发布时登记的说明。
+//! 修改规则定义后 `cargo test` 会提示把新快照追加到历史文件 (`notes` 中说明变更原因)。
+//!
+//! 自定义规则 (`register_custom`) 与 `--rule-pack` 外部规则包不计入版本。
+
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::rules::registry::{self, RuleRegistry};
+use crate::scanner::Severity;
+
+/// 版本号长度 (十六进制位数)
+const VERSION_LEN: usize = 12;
+
+static HISTORY: Lazy<Vec<PackRelease>> = Lazy::new(|| {
+    serde_json::from_str(include_str!("../../resources/rules-history.json"))
+        .expect("resources/rules-history.json is valid")
+});
+
+static CURRENT: Lazy<PackRelease> = Lazy::new(|| PackRelease::new(env!("CARGO_PKG_VERSION"), snapshot()));
+
+/// 单条规则的定义快照
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSnapshot {
+    /// 类别名 (`--categories` 取值)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 以下字段只有 Query 规则有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Query 哈希 (空白归一化)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 框架版本门控 (`VersionGate::describe`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gates: Vec<String>,
+}
+
+/// 一次发布的规则包
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackRelease {
+    /// 规则包版本 (规则定义的哈希)
+    pub version: String,
+    /// 发布时的工具版本
+    pub tool_version: String,
+    /// 规则 ID → 定义快照
+    pub rules: BTreeMap<String, RuleSnapshot>,
+    /// 规则 ID → 本次变更的说明
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notes: BTreeMap<String, String>,
+}
+
+impl PackRelease {
+    fn new(tool_version: &str, rules: BTreeMap<String, RuleSnapshot>) -> Self {
+        Self { version: pack_hash(&rules), tool_version: tool_version.to_string(), rules, notes: BTreeMap::new() }
+    }
+}
+
+/// 规则变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// 单条规则的变化
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleChange {
+    pub id: String,
+    pub kind: ChangeKind,
+    /// 变化的定义项，如 "严重级别 P1 → P0"
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    /// 期间各次发布登记的说明
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// 当前描述 (移除的规则为旧描述)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// `rules changelog` 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Changelog {
+    pub from: String,
+    pub from_tool: String,
+    pub to: String,
+    pub to_tool: String,
+    pub changes: Vec<RuleChange>,
+}
+
+/// 当前内置规则包版本
+pub fn current() -> &'static str {
+    &CURRENT.version
+}
+
+/// 当前内置规则的定义快照
+fn snapshot() -> BTreeMap<String, RuleSnapshot> {
+    let mut rules: BTreeMap<String, RuleSnapshot> = registry::known_rule_ids()
+        .map(|(category, id)| (id.to_string(), RuleSnapshot { category: Some(category.name().to_string()), ..Default::default() }))
+        .collect();
+    for rule in RuleRegistry::default().rules() {
+        let entry = rules.entry(rule.id.to_string()).or_default();
+        entry.severity = Some(rule.severity_override.unwrap_or(rule.severity));
+        entry.enabled = Some(rule.enabled);
+        entry.query = Some(fnv_hex(&rule.query.split_whitespace().collect::<Vec<_>>().join(" ")));
+        entry.description = Some(rule.description.to_string());
+        entry.gates = rule.versions.iter().map(|gate| gate.describe()).collect();
+    }
+    rules
+}
+
+/// 规则定义的哈希 (前 [`VERSION_LEN`] 位)
+fn pack_hash(rules: &BTreeMap<String, RuleSnapshot>) -> String {
+    let canonical = serde_json::to_string(rules).expect("rule snapshot serializes");
+    fnv_hex(&canonical)[..VERSION_LEN].to_string()
+}
+
+/// 固定的 FNV-1a，保证不同平台/编译器版本的结果一致
+fn fnv_hex(text: &str) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let hash = text.bytes().fold(OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+    format!("{hash:016x}")
+}
+
+/// 按规则包版本 (可只写前缀) 或工具版本查找历史发布
+fn find_release<'a>(history: &'a [PackRelease], from: &str) -> Option<(usize, &'a PackRelease)> {
+    let from = from.trim().trim_start_matches('v');
+    if from.is_empty() {
+        return None;
+    }
+    // 同一工具版本可能登记了多次规则变更，取最后一次
+    history.iter().enumerate().rev()
+        .find(|(_, release)| release.tool_version == from)
+        .or_else(|| history.iter().enumerate().rev().find(|(_, release)| release.version.starts_with(from)))
+}
+
+/// 从 `from` 到当前规则包的变化
+pub fn changelog(from: &str) -> Result<Changelog, String> {
+    changelog_between(&HISTORY, &CURRENT, from)
+}
+
+fn changelog_between(history: &[PackRelease], current: &PackRelease, from: &str) -> Result<Changelog, String> {
+    let (index, base) = find_release(history, from).ok_or_else(|| {
+        let known: Vec<String> = history.iter().map(|r| format!("{} ({})", r.version, r.tool_version)).collect();
+        format!("未知规则包版本 {from} (已知: {})", known.join(", "))
+    })?;
+    let later = &history[index + 1..];
+    let notes_of = |id: &str| -> Vec<String> {
+        later.iter().filter_map(|release| release.notes.get(id).cloned()).collect()
+    };
+
+    let mut changes = Vec::new();
+    for (id, rule) in &current.rules {
+        match base.rules.get(id) {
+            None => changes.push(RuleChange {
+                id: id.clone(),
+                kind: ChangeKind::Added,
+                details: rule.severity.map(|s| vec![format!("{s:?}")]).unwrap_or_default(),
+                notes: notes_of(id),
+                description: rule.description.clone(),
+            }),
+            Some(old) if old != rule => changes.push(RuleChange {
+                id: id.clone(),
+                kind: ChangeKind::Changed,
+                details: differences(old, rule),
+                notes: notes_of(id),
+                description: rule.description.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (id, old) in &base.rules {
+        if !current.rules.contains_key(id) {
+            changes.push(RuleChange {
+                id: id.clone(),
+                kind: ChangeKind::Removed,
+                details: Vec::new(),
+                notes: notes_of(id),
+                description: old.description.clone(),
+            });
+        }
+    }
+    Ok(Changelog {
+        from: base.version.clone(),
+        from_tool: base.tool_version.clone(),
+        to: current.version.clone(),
+        to_tool: current.tool_version.clone(),
+        changes,
+    })
+}
+
+/// 两个快照间变化的定义项
+fn differences(old: &RuleSnapshot, new: &RuleSnapshot) -> Vec<String> {
+    let show = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let enabled = |value: Option<bool>| show(value.map(|on| if on { "默认启用" } else { "默认关闭" }.to_string()));
+    let mut details = Vec::new();
+    if old.category != new.category {
+        details.push(format!("类别 {} → {}", show(old.category.clone()), show(new.category.clone())));
+    }
+    if old.severity != new.severity {
+        let level = |s: Option<Severity>| show(s.map(|s| format!("{s:?}")));
+        details.push(format!("严重级别 {} → {}", level(old.severity), level(new.severity)));
+    }
+    if old.enabled != new.enabled {
+        details.push(format!("{} → {}", enabled(old.enabled), enabled(new.enabled)));
+    }
+    if old.query != new.query {
+        details.push("Query 变更 (匹配范围可能变化)".to_string());
+    }
+    if old.description != new.description {
+        details.push(format!("描述: {} → {}", show(old.description.clone()), show(new.description.clone())));
+    }
+    if old.gates != new.gates {
+        details.push(format!("版本门控: [{}] → [{}]", old.gates.join("; "), new.gates.join("; ")));
+    }
+    details
+}
+
+/// 渲染 `rules changelog`
+pub fn render(changelog: &Changelog, json_output: bool) -> Value {
+    if json_output {
+        return json!(changelog);
+    }
+    let mut out = format!(
+        "## 📏 规则变更 `{}` (v{}) → `{}` (v{})\n",
+        changelog.from, changelog.from_tool, changelog.to, changelog.to_tool
+    );
+    if changelog.changes.is_empty() {
+        out.push_str("\n✅ 规则定义相同，两份报告的差异来自代码变化\n");
+        return json!(out);
+    }
+    for (kind, title) in [(ChangeKind::Added, "➕ 新增"), (ChangeKind::Changed, "✏️ 变更"), (ChangeKind::Removed, "➖ 移除")] {
+        let changes: Vec<&RuleChange> = changelog.changes.iter().filter(|c| c.kind == kind).collect();
+        if changes.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {title} ({})\n\n", changes.len()));
+        for change in changes {
+            let details = if change.details.is_empty() { String::new() } else { format!(" [{}]", change.details.join("；")) };
+            let description = change.description.as_deref().filter(|_| kind != ChangeKind::Changed)
+                .map_or_else(String::new, |d| format!(" - {d}"));
+            out.push_str(&format!("- **{}**{details}{description}\n", change.id));
+            for note in &change.notes {
+                out.push_str(&format!("  - 💬 {note}\n"));
+            }
+        }
+    }
+    json!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(severity: Severity, query: &str) -> RuleSnapshot {
+        RuleSnapshot {
+            category: Some("performance".to_string()),
+            severity: Some(severity),
+            enabled: Some(true),
+            query: Some(fnv_hex(query)),
+            description: Some("desc".to_string()),
+            gates: Vec::new(),
+        }
+    }
+
+    fn release(tool: &str, rules: &[(&str, RuleSnapshot)], notes: &[(&str, &str)]) -> PackRelease {
+        let mut release = PackRelease::new(tool, rules.iter().map(|(id, r)| (id.to_string(), r.clone())).collect());
+        release.notes = notes.iter().map(|(id, n)| (id.to_string(), n.to_string())).collect();
+        release
+    }
+
+    #[test]
+    fn test_history_records_current_pack() {
+        let latest = HISTORY.last().expect("history is not empty");
+        let mut entry = CURRENT.clone();
+        entry.notes.insert("RULE_ID".to_string(), "变更说明".to_string());
+        assert!(
+            latest.rules == CURRENT.rules,
+            "规则定义已变化，把以下快照追加到 resources/rules-history.json (notes 填写变更说明):\n{}",
+            serde_json::to_string_pretty(&entry).unwrap()
+        );
+        assert_eq!(latest.version, current());
+        for release in HISTORY.iter() {
+            assert_eq!(release.version, pack_hash(&release.rules), "{} 的版本与快照不符", release.tool_version);
+        }
+    }
+
+    #[test]
+    fn test_changelog_between_releases() {
+        let v1 = release("1.0.0", &[("A", rule(Severity::P1, "(a)")), ("B", rule(Severity::P1, "(b)"))], &[]);
+        let v2 = release("1.1.0", &[("A", rule(Severity::P0, "(a)")), ("B", rule(Severity::P1, "(b)"))],
+            &[("A", "生产事故复盘后升级")]);
+        let v3 = release("1.2.0", &[
+            ("A", rule(Severity::P0, "(a)")),
+            ("C", rule(Severity::P1, "(c)")),
+        ], &[("C", "新规则")]);
+        let history = vec![v1.clone(), v2.clone(), v3.clone()];
+
+        let log = changelog_between(&history, &v3, "1.0.0").unwrap();
+        assert_eq!((log.from.as_str(), log.to.as_str()), (v1.version.as_str(), v3.version.as_str()));
+        let summary: Vec<(&str, ChangeKind)> = log.changes.iter().map(|c| (c.id.as_str(), c.kind)).collect();
+        assert_eq!(summary, vec![("A", ChangeKind::Changed), ("C", ChangeKind::Added), ("B", ChangeKind::Removed)]);
+        assert_eq!(log.changes[0].details, vec!["严重级别 P1 → P0"]);
+        assert_eq!(log.changes[0].notes, vec!["生产事故复盘后升级"]);
+
+        // 版本号前缀同样可用；之后的发布才计入说明
+        let log = changelog_between(&history, &v3, &v2.version[..6]).unwrap();
+        assert_eq!(log.changes.len(), 2);
+        assert!(log.changes.iter().all(|c| c.id != "A"));
+        assert!(changelog_between(&history, &v3, "v1.2.0").unwrap().changes.is_empty());
+        assert!(changelog_between(&history, &v3, "0.9.0").unwrap_err().contains("1.0.0"));
+
+        let text = render(&changelog_between(&history, &v3, "1.0.0").unwrap(), false);
+        let text = text.as_str().unwrap();
+        assert!(text.contains("### ➕ 新增 (1)") && text.contains("- **C** [P1] - desc"), "{text}");
+        assert!(text.contains("- **A** [严重级别 P1 → P0]\n  - 💬 生产事故复盘后升级"), "{text}");
+    }
+
+    #[test]
+    fn test_snapshot_covers_query_and_other_rules() {
+        let mut rules = snapshot();
+        assert!(rules.contains_key("N_PLUS_ONE") && rules.contains_key("DOCKER_ADD_URL"));
+        assert!(rules["N_PLUS_ONE"].query.is_some() && rules["DOCKER_ADD_URL"].query.is_none());
+        assert_eq!(pack_hash(&rules), current());
+        rules.get_mut("N_PLUS_ONE").unwrap().description = Some("changed".to_string());
+        assert_ne!(pack_hash(&rules), current());
+    }
+}
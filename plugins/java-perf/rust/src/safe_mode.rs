@@ -0,0 +1,167 @@
+//! 安全模式 (`scan --safe-mode`)：扫描下载的不可信代码库
+//!
+//! Skill 可能被指向任意下载的仓库，仓库内容 (源码、构建文件、`.javaperf.toml`) 都由第三方控制。
+//! 安全模式下:
+//!
+//! | 限制 | 说明 |
+//! |------|------|
+//! | 不启动外部进程 | 拒绝 `--git` / `--blame-age` (git)；忽略 `[baseline] server` (curl)，只读本地基线 |
+//! | 单文件大小 | 超过 [`MAX_FILE_BYTES`] 的文件跳过 |
+//! | 单文件解析耗时 | 超过 [`PARSE_TIMEOUT`] 放弃解析 (记为解析失败) |
+//! | 单条规则匹配 | 每个文件最多 [`RULE_LIMITS`] 个进行中匹配、限时，超出后丢弃剩余匹配 |
+//! | 符号链接 | 解析后位于扫描根目录之外的文件与目录跳过 |
+//! | 构建文件中的路径 | Maven parent `relativePath`、`<module>`、Gradle `include` 指向根目录之外时不读取；不读取本地 Maven 仓库 |
+//!
+//! 规则包 (`--rule-pack`) 始终在沙箱中运行 (与 `--trusted` 互斥)。被跳过的文件汇总在报告中。
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::rules::custom::ExecLimits;
+
+/// 单文件大小上限
+pub const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// 单文件解析耗时上限
+pub const PARSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 内置规则每个文件的 Query 执行限制
+pub const RULE_LIMITS: ExecLimits = ExecLimits { match_limit: 4096, timeout: Duration::from_millis(500) };
+
+/// 文件被跳过的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// 符号链接指向扫描根目录之外
+    OutsideRoot,
+    /// 超过 [`MAX_FILE_BYTES`]
+    TooLarge,
+}
+
+/// 被跳过的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: SkipReason,
+}
+
+/// 安全模式的执行情况 (报告中的 `safe_mode`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SafeModeSummary {
+    /// 按路径排序
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// 扫描范围约束 (记录被跳过的文件，遍历与读取线程共用)
+#[derive(Debug)]
+pub struct SafeMode {
+    /// 扫描根目录 (规范化路径)
+    roots: Vec<PathBuf>,
+    skipped: Mutex<Vec<SkippedFile>>,
+}
+
+impl SafeMode {
+    pub fn new<'a>(roots: impl IntoIterator<Item = &'a Path>) -> Result<Self, String> {
+        let roots = roots.into_iter()
+            .map(|root| root.canonicalize().map_err(|e| format!("扫描目录 {} 不可访问: {e}", root.display())))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { roots, skipped: Mutex::new(Vec::new()) })
+    }
+
+    /// 路径 (解析符号链接后) 位于某个扫描根目录内
+    pub fn contains(&self, path: &Path) -> bool {
+        path.canonicalize().is_ok_and(|resolved| self.roots.iter().any(|root| resolved.starts_with(root)))
+    }
+
+    /// 遍历时是否进入该条目：符号链接须指向根目录内
+    pub fn admits_entry(&self, entry: &walkdir::DirEntry) -> bool {
+        if !entry.path_is_symlink() || self.contains(entry.path()) {
+            return true;
+        }
+        tracing::warn!(path = %entry.path().display(), "--safe-mode: 符号链接指向扫描目录之外，已跳过");
+        self.skip(entry.path(), SkipReason::OutsideRoot);
+        false
+    }
+
+    /// 读取前检查文件大小
+    pub fn admits_size(&self, path: &Path) -> bool {
+        match std::fs::metadata(path) {
+            Ok(meta) if meta.len() > MAX_FILE_BYTES => {
+                tracing::warn!(path = %path.display(), bytes = meta.len(), "--safe-mode: 文件超过大小上限，已跳过");
+                self.skip(path, SkipReason::TooLarge);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn skip(&self, path: &Path, reason: SkipReason) {
+        let path = path.to_string_lossy().replace('\\', "/");
+        self.skipped.lock().unwrap_or_else(|e| e.into_inner()).push(SkippedFile { path, reason });
+    }
+
+    /// 汇总被跳过的文件 (`relative` 把绝对路径转为报告路径)
+    pub fn summary(&self, relative: impl Fn(&Path) -> String) -> SafeModeSummary {
+        let mut skipped: Vec<SkippedFile> = self.skipped.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|file| SkippedFile { path: relative(Path::new(&file.path)), reason: file.reason })
+            .collect();
+        skipped.sort_by(|a, b| a.path.cmp(&b.path));
+        SafeModeSummary { skipped }
+    }
+}
+
+/// Markdown 报告中的安全模式说明
+pub fn render_line(summary: &SafeModeSummary) -> String {
+    if summary.skipped.is_empty() {
+        return "\n*（安全模式: 未执行外部命令，未读取扫描目录之外的文件）*\n".to_string();
+    }
+    let count = |reason| summary.skipped.iter().filter(|f| f.reason == reason).count();
+    format!(
+        "\n*（安全模式: 跳过 {} 个指向扫描目录之外的符号链接、{} 个超过 {} MB 的文件）*\n",
+        count(SkipReason::OutsideRoot), count(SkipReason::TooLarge), MAX_FILE_BYTES / (1024 * 1024)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_outside_root_and_large_files_are_skipped() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("Secret.java"), "class Secret {}").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/A.java"), "class A {}").unwrap();
+        std::fs::write(root.join("src/Big.java"), vec![b' '; MAX_FILE_BYTES as usize + 1]).unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.join("escape")).unwrap();
+        std::os::unix::fs::symlink(root.join("src/A.java"), root.join("Alias.java")).unwrap();
+
+        let safe = SafeMode::new([root]).unwrap();
+        let mut files: Vec<String> = walkdir::WalkDir::new(root)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| safe.admits_entry(e))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && safe.admits_size(e.path()))
+            .map(|e| crate::ast_engine::relative_path(root, e.path()))
+            .collect();
+        files.sort();
+        // 指向根目录内的符号链接照常扫描
+        assert_eq!(files, ["Alias.java", "src/A.java"]);
+
+        let summary = safe.summary(|p| crate::ast_engine::relative_path(root, p));
+        assert_eq!(summary.skipped, [
+            SkippedFile { path: "escape".into(), reason: SkipReason::OutsideRoot },
+            SkippedFile { path: "src/Big.java".into(), reason: SkipReason::TooLarge },
+        ]);
+        assert!(render_line(&summary).contains("跳过 1 个指向扫描目录之外的符号链接、1 个超过 2 MB 的文件"));
+        assert!(render_line(&SafeModeSummary::default()).contains("未执行外部命令"));
+    }
+}
@@ -0,0 +1,159 @@
+//! 按规则抽样报告 (灰度启用噪音较大的新规则)
+//!
+//! 新规则命中很多时，先只看一部分结果评估信噪比，再决定是否全量启用:
+//!
+//! ```toml
+//! [[rule_overrides]]
+//! id = "MANUAL_DTO_COPY"
+//! sample_rate = 0.1
+//! ```
+//!
+//! 抽样按问题指纹 (位置无关，见 scanner::issue_builder) 的哈希决定，同一问题在每次扫描中
+//! 要么一直被报告、要么一直不报告，代码移动不影响；调大比例时已报告的问题保持报告。
+//! 各规则的抽样比例、命中数与报告数写入报告 (JSON: `sampling`) 与使用统计 (见 usage_stats)。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast_engine::AstIssue;
+use crate::project_config::RuleOverride;
+use crate::symbol_cache::content_hash;
+
+/// 规则的抽样结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampledRule {
+    pub id: String,
+    pub rate: f64,
+    /// 抽样前的问题数
+    pub matched: usize,
+    /// 抽样后报告的问题数
+    pub reported: usize,
+}
+
+/// 编译后的各规则抽样比例
+#[derive(Debug, Clone, Default)]
+pub struct RuleSampling {
+    /// 规则 ID (大写) → 比例
+    rates: BTreeMap<String, f64>,
+}
+
+impl RuleSampling {
+    /// 读取 rule_overrides 中的 sample_rate (须在 (0, 1] 内)
+    pub fn compile(overrides: &[RuleOverride]) -> Result<Self, String> {
+        let mut rates = BTreeMap::new();
+        for rule in overrides {
+            let Some(rate) = rule.sample_rate else { continue };
+            if !(rate > 0.0 && rate <= 1.0) {
+                return Err(format!("rule_overrides {} 的 sample_rate = {rate} 无效: 应在 (0, 1] 内，关闭规则请用 enabled = false", rule.id));
+            }
+            // 比例为 1 即全量报告，不记为抽样
+            if rate < 1.0 {
+                rates.insert(rule.id.trim().to_ascii_uppercase(), rate);
+            }
+        }
+        Ok(Self { rates })
+    }
+
+    /// 按指纹抽样，返回各抽样规则的统计 (按规则 ID 排序，未命中的规则报告数为 0)
+    pub fn apply(&self, issues: &mut Vec<AstIssue>) -> Vec<SampledRule> {
+        if self.rates.is_empty() {
+            return Vec::new();
+        }
+        let mut stats: BTreeMap<&str, SampledRule> = self.rates.iter()
+            .map(|(id, rate)| (id.as_str(), SampledRule { id: id.clone(), rate: *rate, matched: 0, reported: 0 }))
+            .collect();
+        issues.retain(|issue| {
+            let Some(stat) = stats.get_mut(issue.issue_type.as_str()) else { return true };
+            stat.matched += 1;
+            let keep = keeps(issue, stat.rate);
+            stat.reported += usize::from(keep);
+            keep
+        });
+        stats.into_values().collect()
+    }
+}
+
+/// 问题是否落在抽样范围内 (指纹哈希均匀映射到 [0, 1))
+fn keeps(issue: &AstIssue, rate: f64) -> bool {
+    let key = if issue.fingerprint.is_empty() {
+        format!("{}|{}|{}", issue.issue_type, issue.path, issue.snippet)
+    } else {
+        issue.fingerprint.clone()
+    };
+    let position = (finalize(content_hash(key.as_bytes())) >> 11) as f64 / (1u64 << 53) as f64;
+    position < rate
+}
+
+/// FNV-1a 的高位对相近的输入分布不均，再做一次 murmur3 fmix64 混合
+fn finalize(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+/// 报告中的抽样说明 (没有抽样规则时为空)
+pub fn render_line(sampled: &[SampledRule]) -> String {
+    if sampled.is_empty() {
+        return String::new();
+    }
+    let parts: Vec<String> = sampled.iter()
+        .map(|s| format!("{} {}% (报告 {}/{})", s.id, format_rate(s.rate), s.reported, s.matched))
+        .collect();
+    format!("**抽样**: {} (按指纹抽样，其余未报告)\n\n", parts.join(", "))
+}
+
+/// 比例的百分数 (`0.1` → `10`，`0.025` → `2.5`)
+pub fn format_rate(rate: f64) -> String {
+    let percent = format!("{:.2}", rate * 100.0);
+    percent.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, fingerprint: &str) -> AstIssue {
+        serde_json::from_value(serde_json::json!({
+            "severity": "P1", "id": id, "file": "", "path": "A.java", "line": 1, "description": "", "fingerprint": fingerprint
+        })).unwrap()
+    }
+
+    fn sample_rate(id: &str, rate: f64) -> RuleOverride {
+        RuleOverride { id: id.into(), exclude: Vec::new(), enabled: None, sample_rate: Some(rate) }
+    }
+
+    #[test]
+    fn test_sampling_is_deterministic_and_monotonic() {
+        let issues: Vec<AstIssue> = (0..1000).map(|i| issue("MANUAL_DTO_COPY", &format!("{i:016x}"))).collect();
+        let run = |rate: f64| {
+            let sampling = RuleSampling::compile(&[sample_rate("manual_dto_copy", rate)]).unwrap();
+            let mut kept = issues.clone();
+            kept.push(issue("N_PLUS_ONE", "ffff"));
+            let stats = sampling.apply(&mut kept);
+            (kept, stats)
+        };
+        let (tenth, stats) = run(0.1);
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats[0].matched, stats[0].reported), (1000, tenth.len() - 1));
+        assert!((60..140).contains(&stats[0].reported), "{}", stats[0].reported);
+        assert!(tenth.iter().any(|i| i.issue_type == "N_PLUS_ONE"), "其它规则不受影响");
+        assert_eq!(run(0.1).0.len(), tenth.len());
+
+        // 调大比例时已报告的问题保持报告
+        let (half, _) = run(0.5);
+        assert!(tenth.iter().all(|i| half.iter().any(|h| h.fingerprint == i.fingerprint)));
+    }
+
+    #[test]
+    fn test_compile_rejects_invalid_rates() {
+        assert!(RuleSampling::compile(&[sample_rate("X", 0.0)]).unwrap_err().contains("enabled = false"));
+        assert!(RuleSampling::compile(&[sample_rate("X", 1.5)]).is_err());
+        assert!(RuleSampling::compile(&[sample_rate("X", f64::NAN)]).is_err());
+        assert!(RuleSampling::compile(&[sample_rate("X", 1.0)]).unwrap().rates.is_empty());
+        assert_eq!(format_rate(0.1), "10");
+        assert_eq!(format_rate(0.025), "2.5");
+    }
+}
@@ -0,0 +1,161 @@
+//! 链式调用展开工具
+//!
+//! Tree-sitter 中 `list.stream().filter(f).findFirst().get()` 是嵌套的 method_invocation:
+//! 最外层是 `get`，其 object 是 `findFirst(...)`，依此类推。规则经常需要
+//! "这条链里有没有 stream()"、"get 之前是不是 findFirst" 这类判断，
+//! 这里把嵌套结构展开为从内到外的线性链，供各 RuleHandler 复用。
+
+use tree_sitter::Node;
+
+use super::node_walk::ancestors;
+
+/// 链中的一次调用
+#[derive(Debug, Clone, Copy)]
+pub struct ChainLink<'t> {
+    pub name: &'t str,
+    pub arg_count: usize,
+    pub node: Node<'t>,
+}
+
+/// 展开后的调用链 (links 按调用顺序，从最内层到最外层)
+#[derive(Debug, Clone)]
+pub struct CallChain<'t> {
+    /// 链的起点 (非调用表达式，如 `list` / `this.orders` / `Stream`)，静态调用无接收者时为 None
+    pub receiver: Option<Node<'t>>,
+    pub links: Vec<ChainLink<'t>>,
+}
+
+impl<'t> CallChain<'t> {
+    /// 从任意一个 method_invocation 节点向内展开 (不向外扩展)
+    pub fn from_invocation(node: Node<'t>, code: &'t str) -> Self {
+        let mut links = Vec::new();
+        let mut receiver = None;
+        let mut current = Some(node);
+
+        while let Some(n) = current.filter(|n| n.kind() == "method_invocation") {
+            let name = n.child_by_field_name("name")
+                .and_then(|name| name.utf8_text(code.as_bytes()).ok())
+                .unwrap_or("");
+            let arg_count = n.child_by_field_name("arguments")
+                .map(|args| args.named_child_count())
+                .unwrap_or(0);
+            links.push(ChainLink { name, arg_count, node: n });
+
+            current = n.child_by_field_name("object");
+            if let Some(object) = current.filter(|o| o.kind() != "method_invocation") {
+                receiver = Some(object);
+            }
+        }
+
+        links.reverse();
+        Self { receiver, links }
+    }
+
+    /// 从链中任意一环展开整条链 (先向外找到最外层调用)
+    pub fn enclosing(node: Node<'t>, code: &'t str) -> Self {
+        Self::from_invocation(outermost_call(node), code)
+    }
+
+    /// 方法名序列 (从内到外)
+    pub fn names(&self) -> impl Iterator<Item = &'t str> + '_ {
+        self.links.iter().map(|l| l.name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.links.iter().any(|l| l.name == name)
+    }
+
+    pub fn contains_any(&self, names: &[&str]) -> bool {
+        self.links.iter().any(|l| names.contains(&l.name))
+    }
+
+    /// 最后一环
+    pub fn last(&self) -> Option<&ChainLink<'t>> {
+        self.links.last()
+    }
+
+    /// 倒数第 n 环 (0 = 最后一环)
+    pub fn nth_from_end(&self, n: usize) -> Option<&ChainLink<'t>> {
+        self.links.len().checked_sub(n + 1).and_then(|i| self.links.get(i))
+    }
+
+    /// 接收者源码文本
+    pub fn receiver_text(&self, code: &'t str) -> Option<&'t str> {
+        self.receiver.and_then(|r| r.utf8_text(code.as_bytes()).ok())
+    }
+
+    /// 是否为 Stream 链 (集合 `.stream()`、`Stream.of`、`Arrays.stream` 等)
+    pub fn is_stream(&self, code: &'t str) -> bool {
+        self.contains_any(&["stream", "parallelStream"])
+            || matches!(self.receiver_text(code), Some("Stream" | "IntStream" | "LongStream" | "DoubleStream"))
+    }
+}
+
+/// 向外找到链的最外层调用 (当前节点是父调用的 object 时继续向上)
+pub fn outermost_call(node: Node) -> Node {
+    let mut current = node;
+    for parent in ancestors(node) {
+        let is_object = parent.kind() == "method_invocation"
+            && parent.child_by_field_name("object").map(|o| o.id()) == Some(current.id());
+        if !is_object {
+            break;
+        }
+        current = parent;
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_first_call<R>(code: &str, f: impl FnOnce(Node, &str) -> R) -> R {
+        let analyzer = super::super::tree_sitter_java::JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(code, |tree| {
+            let mut stack = vec![tree.root_node()];
+            while let Some(n) = stack.pop() {
+                if n.kind() == "method_invocation" {
+                    return f(n, code);
+                }
+                let mut cursor = n.walk();
+                stack.extend(n.children(&mut cursor));
+            }
+            panic!("no method_invocation in {code}");
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_flatten_stream_chain() {
+        let code = "class A { void f() { orders.stream().filter(o -> o.ok()).findFirst().get(); } }";
+        with_first_call(code, |node, code| {
+            let chain = CallChain::from_invocation(node, code);
+            assert_eq!(chain.names().collect::<Vec<_>>(), vec!["stream", "filter", "findFirst", "get"]);
+            assert_eq!(chain.receiver_text(code), Some("orders"));
+            assert_eq!(chain.links[1].arg_count, 1);
+            assert_eq!(chain.nth_from_end(1).unwrap().name, "findFirst");
+            assert!(chain.is_stream(code));
+        });
+    }
+
+    #[test]
+    fn test_enclosing_from_inner_link() {
+        let code = "class A { void f() { Stream.of(1, 2).count(); } }";
+        with_first_call(code, |node, code| {
+            // 先序遍历先遇到最外层 count()，取其内层 of() 再向外展开
+            let inner = node.child_by_field_name("object").unwrap();
+            let chain = CallChain::enclosing(inner, code);
+            assert_eq!(chain.names().collect::<Vec<_>>(), vec!["of", "count"]);
+            assert!(chain.is_stream(code));
+        });
+    }
+
+    #[test]
+    fn test_static_call_without_receiver() {
+        let code = "class A { void f() { compute(1).get(); } }";
+        with_first_call(code, |node, code| {
+            let chain = CallChain::from_invocation(node, code);
+            assert!(chain.receiver.is_none());
+            assert!(!chain.is_stream(code));
+        });
+    }
+}
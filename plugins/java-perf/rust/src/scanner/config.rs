@@ -1,4 +1,4 @@
-use super::{CodeAnalyzer, Issue, Severity};
+use super::{CodeAnalyzer, Issue, Severity, leading_whitespace_chars};
 use std::path::Path;
 use anyhow::Result;
 use serde::Deserialize;
@@ -254,9 +254,13 @@ impl CodeAnalyzer for LineBasedConfigAnalyzer {
                                     severity: rule.severity,
                                     file: file_name.clone(),
                                     line: line_num + 1,
+                                    column: leading_whitespace_chars(line),
                                     description: format!("{} (Value: {})", rule.description, value_part),
                                     context: Some(line.to_string()),
                                     confidence: None, // Config rules don't use confidence
+                                    snippet: String::new(),
+                                    fingerprint: String::new(),
+                                    patch: None,
                                 });
                              }
                     }
@@ -291,9 +295,13 @@ impl LineBasedConfigAnalyzer {
                     severity: Severity::P1,
                     file: file_name.to_string(),
                     line: 0, // 结构化解析无法获取行号
+                    column: 0,
                     description: format!("数据库连接池过小: {} (建议 >= 10)", pool_size),
                     context: Some(format!("maximum-pool-size: {}", pool_size)),
                     confidence: None, // Config rules don't use confidence
+                    snippet: String::new(),
+                    fingerprint: String::new(),
+                    patch: None,
                 });
             }
         }
@@ -305,9 +313,13 @@ impl LineBasedConfigAnalyzer {
                     severity: Severity::P1,
                     file: file_name.to_string(),
                     line: 0,
+                    column: 0,
                     description: format!("连接超时过长: {}ms (建议 <= 30000)", timeout),
                     context: Some(format!("connection-timeout: {}", timeout)),
                     confidence: None, // Config rules don't use confidence
+                    snippet: String::new(),
+                    fingerprint: String::new(),
+                    patch: None,
                 });
             }
         }
@@ -319,9 +331,13 @@ impl LineBasedConfigAnalyzer {
                 severity: Severity::P0,
                 file: file_name.to_string(),
                 line: 0,
+                column: 0,
                 description: "JPA open-in-view=true 会导致延迟加载问题".to_string(),
                 context: Some("open-in-view: true".to_string()),
                 confidence: None, // Config rules don't use confidence
+                snippet: String::new(),
+                fingerprint: String::new(),
+                patch: None,
             });
         }
         
@@ -331,9 +347,13 @@ impl LineBasedConfigAnalyzer {
                 severity: Severity::P1,
                 file: file_name.to_string(),
                 line: 0,
+                column: 0,
                 description: "JPA show-sql=true 影响性能".to_string(),
                 context: Some("show-sql: true".to_string()),
                 confidence: None, // Config rules don't use confidence
+                snippet: String::new(),
+                fingerprint: String::new(),
+                patch: None,
             });
         }
         
@@ -345,9 +365,13 @@ impl LineBasedConfigAnalyzer {
                     severity: Severity::P1,
                     file: file_name.to_string(),
                     line: 0,
+                    column: 0,
                     description: format!("Tomcat 最大线程数过低: {} (默认 200)", threads),
                     context: Some(format!("max-threads: {}", threads)),
                     confidence: None, // Config rules don't use confidence
+                    snippet: String::new(),
+                    fingerprint: String::new(),
+                    patch: None,
                 });
             }
         }
@@ -0,0 +1,308 @@
+//! 整数常量折叠
+//!
+//! 规模类规则 (大数组、大循环、线程池容量) 只认字面量时，`new byte[SIZE]`、
+//! `i < MAX_BATCH` 这类写法全部漏报。这里在单个文件内对整数表达式做简单的常量传播:
+//!
+//! - 字面量: 十进制/十六进制/八进制/二进制，支持 `_` 分隔与 L 后缀
+//! - 运算: `+ - * / % << >> >>> & | ^`、一元 `+ -`、`(int)`/`(long)` 强制转换、括号
+//! - 局部变量: 使用点之前、所在块 (含外层块) 中声明且为 final 或事实上 final 的初值
+//! - 字段: 外层类中 final 字段 / 接口常量的初值，支持 `SIZE`、`this.SIZE`、`Outer.SIZE`
+//! - `Integer.MAX_VALUE` / `Long.MAX_VALUE`
+//!
+//! 不跨文件解析 (其他类的常量由 SymbolTable 负责)，溢出或除零视为不可求值。
+
+use tree_sitter::Node;
+
+use super::node_walk::{ancestors, NodeWalk};
+
+/// 常量引用的最大展开深度 (防止 `A = B; B = A` 之类的循环)
+const MAX_DEPTH: usize = 8;
+
+const SCOPE_BOUNDARIES: &[&str] = &["method_declaration", "constructor_declaration", "lambda_expression", "class_body"];
+
+/// 求值结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstValue {
+    pub value: i64,
+    /// 展开的常量引用 (`SIZE = 2_000_000`)，按出现顺序；为空表示表达式本身只含字面量
+    pub bindings: Vec<String>,
+}
+
+impl ConstValue {
+    /// 附加到 Issue context 的求值依据
+    pub fn evidence(&self) -> Option<String> {
+        (!self.bindings.is_empty()).then(|| self.bindings.join(", "))
+    }
+}
+
+/// 求值整数表达式，无法静态确定时返回 None
+pub fn eval_int(node: Node, code: &str) -> Option<ConstValue> {
+    let mut bindings = Vec::new();
+    let value = eval(node, code, &mut bindings, 0)?;
+    Some(ConstValue { value, bindings })
+}
+
+fn eval(node: Node, code: &str, bindings: &mut Vec<String>, depth: usize) -> Option<i64> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    match node.kind() {
+        "decimal_integer_literal" | "hex_integer_literal" | "octal_integer_literal" | "binary_integer_literal" => {
+            parse_literal(text(node, code))
+        }
+        "parenthesized_expression" => eval(node.named_child(0)?, code, bindings, depth),
+        "cast_expression" => {
+            let target = text(node.child_by_field_name("type")?, code);
+            let value = eval(node.child_by_field_name("value")?, code, bindings, depth)?;
+            match target {
+                "int" => i32::try_from(value).ok().map(i64::from),
+                "long" => Some(value),
+                _ => None,
+            }
+        }
+        "unary_expression" => {
+            let value = eval(node.child_by_field_name("operand")?, code, bindings, depth)?;
+            match node.child_by_field_name("operator")?.kind() {
+                "-" => value.checked_neg(),
+                "+" => Some(value),
+                _ => None,
+            }
+        }
+        "binary_expression" => {
+            let left = eval(node.child_by_field_name("left")?, code, bindings, depth)?;
+            let right = eval(node.child_by_field_name("right")?, code, bindings, depth)?;
+            let shift = || u32::try_from(right).ok().filter(|s| *s < 64);
+            match node.child_by_field_name("operator")?.kind() {
+                "+" => left.checked_add(right),
+                "-" => left.checked_sub(right),
+                "*" => left.checked_mul(right),
+                "/" => left.checked_div(right),
+                "%" => left.checked_rem(right),
+                "<<" => left.checked_shl(shift()?),
+                ">>" => left.checked_shr(shift()?),
+                ">>>" => Some(((left as u64) >> shift()?) as i64),
+                "&" => Some(left & right),
+                "|" => Some(left | right),
+                "^" => Some(left ^ right),
+                _ => None,
+            }
+        }
+        "identifier" => {
+            let name = text(node, code);
+            let value = match resolve_local(node, name, code) {
+                Some(local) => local?,
+                None => resolve_field(node, name, code)?,
+            };
+            let result = eval(value, code, bindings, depth + 1)?;
+            bindings.push(format!("{} = {}", name, text(value, code)));
+            Some(result)
+        }
+        "field_access" => {
+            let object = node.child_by_field_name("object")?;
+            let field = node.child_by_field_name("field")?;
+            match (text(object, code), text(field, code)) {
+                ("Integer", "MAX_VALUE") => Some(i32::MAX.into()),
+                ("Long", "MAX_VALUE") => Some(i64::MAX),
+                (owner, name) if owner == "this" || NodeWalk::new(code).type_chain(node).contains(&owner) => {
+                    let value = resolve_field(node, name, code)?;
+                    let result = eval(value, code, bindings, depth + 1)?;
+                    bindings.push(format!("{} = {}", text(node, code), text(value, code)));
+                    Some(result)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 整数字面量 (去掉 `_` 与 L 后缀)
+fn parse_literal(literal: &str) -> Option<i64> {
+    let digits = literal.trim_end_matches(['L', 'l']).replace('_', "");
+    let (digits, radix) = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        (hex.to_string(), 16)
+    } else if let Some(bin) = digits.strip_prefix("0b").or_else(|| digits.strip_prefix("0B")) {
+        (bin.to_string(), 2)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (digits[1..].to_string(), 8)
+    } else {
+        (digits, 10)
+    };
+    // 十六进制/八进制/二进制可表示负数 (0xFFFFFFFF 为 int -1)，按无符号位模式解析
+    match radix {
+        10 => digits.parse().ok(),
+        _ => u64::from_str_radix(&digits, radix).ok().map(|v| v as i64),
+    }
+}
+
+/// 使用点之前、所在块链上声明的局部变量初值 (需 final 或事实上 final)
+///
+/// 外层 None = 不是局部变量或参数 (继续查字段)，内层 None = 是局部变量或参数但值不可知。
+fn resolve_local<'t>(usage: Node<'t>, name: &str, code: &str) -> Option<Option<Node<'t>>> {
+    for scope in ancestors(usage) {
+        if SCOPE_BOUNDARIES.contains(&scope.kind()) {
+            // 参数遮蔽字段: 值由调用方决定
+            if declares_parameter(scope, name, code) {
+                return Some(None);
+            }
+            // lambda 可以引用外层方法的 (事实上) final 局部变量
+            if scope.kind() != "lambda_expression" {
+                return None;
+            }
+        }
+        if scope.kind() == "block" {
+            let mut cursor = scope.walk();
+            let declaration = scope.named_children(&mut cursor)
+                .take_while(|stmt| stmt.end_byte() <= usage.start_byte())
+                .filter(|stmt| stmt.kind() == "local_variable_declaration")
+                .filter_map(|stmt| declarator(stmt, name, code).map(|value| (stmt, value)))
+                .last();
+            if let Some((stmt, value)) = declaration {
+                let assigned = !has_final_modifier(stmt, code) && is_reassigned(scope, name, code);
+                return Some(value.filter(|_| !assigned));
+            }
+        }
+    }
+    None
+}
+
+/// 外层类 (由内到外) 中 final 字段或接口常量的初值
+fn resolve_field<'t>(usage: Node<'t>, name: &str, code: &str) -> Option<Node<'t>> {
+    for n in ancestors(usage) {
+        if matches!(n.kind(), "class_body" | "interface_body" | "enum_body_declarations") {
+            let interface = n.kind() == "interface_body";
+            let mut cursor = n.walk();
+            for member in n.named_children(&mut cursor) {
+                let constant = match member.kind() {
+                    "field_declaration" => has_final_modifier(member, code),
+                    "constant_declaration" => interface,
+                    _ => false,
+                };
+                if let Some(value) = declarator(member, name, code) {
+                    // 同名字段非 final: 值可能被修改
+                    return value.filter(|_| constant);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 声明中名为 `name` 的声明符的初值 (外层 None = 未声明，内层 None = 无初值)
+fn declarator<'t>(declaration: Node<'t>, name: &str, code: &str) -> Option<Option<Node<'t>>> {
+    let mut cursor = declaration.walk();
+    let found = declaration.children_by_field_name("declarator", &mut cursor)
+        .find(|d| d.child_by_field_name("name").is_some_and(|id| text(id, code) == name))?;
+    // int[] a = ... 之类的数组维度声明不参与整数求值
+    Some(found.child_by_field_name("value").filter(|_| found.child_by_field_name("dimensions").is_none()))
+}
+
+fn has_final_modifier(declaration: Node, code: &str) -> bool {
+    let mut cursor = declaration.walk();
+    let modifiers = declaration.children(&mut cursor).find(|c| c.kind() == "modifiers");
+    modifiers.is_some_and(|m| text(m, code).split_whitespace().any(|w| w == "final"))
+}
+
+fn declares_parameter(scope: Node, name: &str, code: &str) -> bool {
+    let Some(params) = scope.child_by_field_name("parameters") else {
+        return false;
+    };
+    if params.kind() == "identifier" {
+        return text(params, code) == name;
+    }
+    let mut cursor = params.walk();
+    let declared = params.named_children(&mut cursor)
+        .filter_map(|p| if p.kind() == "identifier" { Some(p) } else { p.child_by_field_name("name") })
+        .any(|id| text(id, code) == name);
+    declared
+}
+
+/// 块内是否对 `name` 赋值或自增/自减
+fn is_reassigned(scope: Node, name: &str, code: &str) -> bool {
+    let mut found = false;
+    visit(scope, &mut |n| {
+        let target = match n.kind() {
+            "assignment_expression" => n.child_by_field_name("left"),
+            "update_expression" => n.named_child(0),
+            _ => None,
+        };
+        found |= target.is_some_and(|t| t.kind() == "identifier" && text(t, code) == name);
+    });
+    found
+}
+
+fn text<'c>(node: Node, code: &'c str) -> &'c str {
+    node.utf8_text(code.as_bytes()).unwrap_or("")
+}
+
+fn visit<'t>(node: Node<'t>, f: &mut dyn FnMut(Node<'t>)) {
+    f(node);
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        visit(child, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `marker(expr)` 调用参数的求值结果
+    fn eval_marker(class_body: &str) -> Option<ConstValue> {
+        let code = format!("class Buffers {{ {} }}", class_body);
+        let analyzer = super::super::tree_sitter_java::JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(&code, |tree| {
+            let mut argument = None;
+            visit(tree.root_node(), &mut |n| {
+                if n.kind() == "method_invocation" && n.child_by_field_name("name").is_some_and(|id| text(id, &code) == "marker") {
+                    argument = n.child_by_field_name("arguments").and_then(|args| args.named_child(0));
+                }
+            });
+            eval_int(argument.expect("marker(..) call"), &code)
+        }).unwrap()
+    }
+
+    fn value(class_body: &str) -> Option<i64> {
+        eval_marker(class_body).map(|c| c.value)
+    }
+
+    #[test]
+    fn test_literals_and_arithmetic() {
+        assert_eq!(value("void m() { marker(2_000_000); }"), Some(2_000_000));
+        assert_eq!(value("void m() { marker(0x10_0000L); }"), Some(1 << 20));
+        assert_eq!(value("void m() { marker(0b1010 + 010); }"), Some(18));
+        assert_eq!(value("void m() { marker((int) (4 * 1024 * 1024) / 2 - -1); }"), Some(2 * 1024 * 1024 + 1));
+        assert_eq!(value("void m() { marker(1 << 20 | 3); }"), Some((1 << 20) | 3));
+        assert_eq!(value("void m() { marker(Integer.MAX_VALUE); }"), Some(i32::MAX.into()));
+        // 溢出、除零与非整数不可求值
+        assert_eq!(value("void m() { marker((int) 3_000_000_000L); }"), None);
+        assert_eq!(value("void m() { marker(10 / 0); }"), None);
+        assert_eq!(value("void m() { marker(1.5); }"), None);
+    }
+
+    #[test]
+    fn test_field_constants() {
+        let c = eval_marker("static final int MB = 1024 * 1024; static final int SIZE = 2 * MB; void m() { marker(SIZE); }").unwrap();
+        assert_eq!(c.value, 2 * 1024 * 1024);
+        assert_eq!(c.evidence().unwrap(), "MB = 1024 * 1024, SIZE = 2 * MB");
+
+        assert_eq!(value("private final int cap = 64; void m() { marker(this.cap * 2); }"), Some(128));
+        assert_eq!(value("static final int N = 8; static class Inner { void m() { marker(Buffers.N); } }"), Some(8));
+        assert_eq!(value("interface Limits { int MAX = 100; void m() { marker(MAX); } }"), Some(100));
+        // 非 final 字段、循环引用
+        assert_eq!(value("static int SIZE = 10; void m() { marker(SIZE); }"), None);
+        assert_eq!(value("static final int A = B; static final int B = A; void m() { marker(A); }"), None);
+    }
+
+    #[test]
+    fn test_local_constants() {
+        assert_eq!(value("void m() { int n = 1000; final int size = n * 3; marker(size); }"), Some(3000));
+        assert_eq!(value("void m() { int n = 1000; run(() -> marker(n)); }"), Some(1000));
+        // 重新赋值、参数遮蔽字段、兄弟块中的声明
+        assert_eq!(value("void m() { int n = 1000; n++; marker(n); }"), None);
+        assert_eq!(value("static final int n = 5; void m(int n) { marker(n); }"), None);
+        assert_eq!(value("void m() { { int n = 1000; } marker(n); }"), None);
+        // 局部变量遮蔽字段
+        assert_eq!(value("static final int n = 5; void m() { final int n = 7; marker(n); }"), Some(7));
+    }
+}
@@ -1,4 +1,4 @@
-use super::{CodeAnalyzer, Issue, Severity};
+use super::{CodeAnalyzer, Issue, Severity, leading_whitespace_chars};
 use std::path::Path;
 use anyhow::Result;
 use once_cell::sync::Lazy;
@@ -111,9 +111,13 @@ impl CodeAnalyzer for DockerfileAnalyzer {
                         severity: rule.severity,
                         file: file_name.clone(),
                         line: line_num + 1,
+                        column: leading_whitespace_chars(line),
                         description: rule.description.to_string(),
                         context: Some(trimmed.chars().take(60).collect()),
                         confidence: None, // Dockerfile rules don't use confidence
+                        snippet: String::new(),
+                        fingerprint: String::new(),
+                        patch: None,
                     });
                 }
             }
@@ -137,9 +141,13 @@ impl CodeAnalyzer for DockerfileAnalyzer {
                 severity: Severity::P1,
                 file: file_name.clone(),
                 line: 1,
+                column: 0,
                 description: format!("有 {run_count} 个 RUN 命令，建议使用 && 合并减少层数"),
                 context: None,
                 confidence: None, // Dockerfile rules don't use confidence
+                snippet: String::new(),
+                fingerprint: String::new(),
+                patch: None,
             });
         }
 
@@ -150,9 +158,13 @@ impl CodeAnalyzer for DockerfileAnalyzer {
                 severity: Severity::P1,
                 file: file_name.clone(),
                 line: 1,
+                column: 0,
                 description: "apt-get install 后未清理缓存，镜像体积增大".to_string(),
                 context: None,
                 confidence: None, // Dockerfile rules don't use confidence
+                snippet: String::new(),
+                fingerprint: String::new(),
+                patch: None,
             });
         }
 
@@ -0,0 +1,152 @@
+//! tree-sitter-java 语法版本：固定版本 + 查询集回退
+//!
+//! 规则 Query 按 tree-sitter-java 0.21 的节点名编写。语法升级会改名或删除节点 (如 0.23 删除了
+//! `comment` 超类型，只保留 `line_comment` / `block_comment`)，此前这类 Query 编译失败后只打印一行警告，
+//! 规则被静默丢弃。
+//!
+//! - 默认链接 0.21 (`grammar-pinned` 特性)；`--no-default-features --features grammar-next` 改为链接 0.23。
+//!   两版语法导出同名的 C 符号，不能同时链接
+//! - 每个 [`QuerySet`] 是基准 Query 在某个语法版本下的节点名改写。进程内首次构建分析器时按顺序
+//!   (与链接版本一致的优先) 编译全部内置 Query，选用全部能编译的查询集，并记录当前语法与查询集
+//! - 所有查询集都有 Query 无法编译时选失败最少的一个，逐条警告被跳过的规则
+
+use std::borrow::Cow;
+
+use once_cell::sync::OnceCell;
+use tree_sitter::Language;
+
+use super::tree_sitter_java::JavaTreeSitterAnalyzer;
+use crate::diagnostics::{Diagnostic, DiagnosticKind};
+
+#[cfg(all(feature = "grammar-pinned", feature = "grammar-next"))]
+compile_error!("grammar-pinned 与 grammar-next 只能启用其一 (两版语法的 C 符号同名)");
+
+#[cfg(not(any(feature = "grammar-pinned", feature = "grammar-next")))]
+compile_error!("需要启用 grammar-pinned 或 grammar-next 特性之一");
+
+/// 链接的 tree-sitter-java 版本
+#[cfg(feature = "grammar-pinned")]
+pub const LINKED: &str = "0.21";
+#[cfg(feature = "grammar-next")]
+pub const LINKED: &str = "0.23";
+
+/// 链接的 Java 语法
+#[cfg(feature = "grammar-pinned")]
+pub fn language() -> Language {
+    tree_sitter_java::language()
+}
+
+/// 链接的 Java 语法
+#[cfg(feature = "grammar-next")]
+pub fn language() -> Language {
+    // 0.23 起语法 crate 只导出与 tree-sitter 版本无关的 LanguageFn
+    // SAFETY: LanguageFn 返回指向静态 TSLanguage 的指针，其 ABI 版本 (14) 在 tree-sitter 0.22 支持范围内
+    unsafe {
+        let raw = (tree_sitter_java_next::LANGUAGE.into_raw())();
+        Language::from_raw(raw as *const tree_sitter::ffi::TSLanguage)
+    }
+}
+
+/// 基准 Query 在某个语法版本下的改写
+#[derive(Debug)]
+pub struct QuerySet {
+    /// 适配的 tree-sitter-java 版本
+    pub grammar: &'static str,
+    /// (基准写法, 该版本写法)
+    rewrites: &'static [(&'static str, &'static str)],
+}
+
+impl QuerySet {
+    /// 按本查询集改写 Query 源码
+    pub fn adapt<'q>(&self, query: &'q str) -> Cow<'q, str> {
+        self.rewrites.iter().fold(Cow::Borrowed(query), |query, (from, to)| {
+            if query.contains(from) { Cow::Owned(query.replace(from, to)) } else { query }
+        })
+    }
+}
+
+/// 支持的查询集 (第一个为基准)
+pub static QUERY_SETS: &[QuerySet] = &[
+    QuerySet { grammar: "0.21", rewrites: &[] },
+    QuerySet { grammar: "0.23", rewrites: &[("(comment)", "[(line_comment) (block_comment)]")] },
+];
+
+static ACTIVE: OnceCell<&'static QuerySet> = OnceCell::new();
+
+/// 当前语法下选用的查询集 (进程内首次调用时探测并记录)
+pub fn active() -> &'static QuerySet {
+    ACTIVE.get_or_init(|| {
+        let language = language();
+        let set = select(QUERY_SETS, |set| JavaTreeSitterAnalyzer::failing_queries(&language, set));
+        tracing::info!(grammar = LINKED, abi = language.version(), query_set = set.grammar, "tree-sitter-java 语法");
+        set
+    })
+}
+
+/// 选用的查询集与链接的语法版本不一致时的诊断 (规则按改写后的 Query 执行，匹配结果可能有差异)
+pub fn mismatch() -> Option<Diagnostic> {
+    let set = active();
+    (set.grammar != LINKED).then(|| Diagnostic::new(
+        DiagnosticKind::GrammarMismatch,
+        format!("链接 tree-sitter-java {LINKED}，规则按查询集 {} 改写后执行，建议运行 rules verify-goldens 确认匹配结果", set.grammar),
+    ))
+}
+
+/// 选择查询集: 与链接版本一致的优先，取第一个全部能编译的；都不能时取失败最少的并逐条警告
+fn select(sets: &[QuerySet], failing: impl Fn(&QuerySet) -> Vec<String>) -> &QuerySet {
+    let mut ordered: Vec<&QuerySet> = sets.iter().collect();
+    ordered.sort_by_key(|set| set.grammar != LINKED);
+    let mut best: Option<(&QuerySet, Vec<String>)> = None;
+    for set in ordered {
+        let failed = failing(set);
+        if failed.is_empty() {
+            return set;
+        }
+        tracing::debug!(query_set = set.grammar, failed = failed.len(), "查询集在当前语法下有 Query 无法编译");
+        if best.as_ref().is_none_or(|(_, least)| failed.len() < least.len()) {
+            best = Some((set, failed));
+        }
+    }
+    let (set, failed) = best.expect("至少有一个查询集");
+    for query in &failed {
+        tracing::warn!(query = %query, grammar = LINKED, query_set = set.grammar, "Query 在所有查询集下都无法编译，已跳过");
+    }
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Query;
+
+    #[test]
+    fn test_adapt_compiles_on_linked_grammar() {
+        let query = "(class_body (comment) @c)";
+        assert_eq!(QUERY_SETS[0].adapt(query), query);
+        let adapted = QUERY_SETS[1].adapt(query);
+        assert_eq!(adapted, "(class_body [(line_comment) (block_comment)] @c)");
+        assert!(Query::new(&language(), &adapted).is_ok());
+        // 内置 Query 在链接的语法下全部能编译
+        assert!(JavaTreeSitterAnalyzer::failing_queries(&language(), active()).is_empty());
+        assert_eq!(active().grammar, LINKED);
+        assert!(mismatch().is_none());
+    }
+
+    #[test]
+    fn test_select_falls_back_to_compiling_set() {
+        static SETS: &[QuerySet] = &[
+            QuerySet { grammar: LINKED, rewrites: &[("(identifier)", "(renamed_identifier)")] },
+            QuerySet { grammar: "next", rewrites: &[] },
+        ];
+        let failing = |set: &QuerySet| {
+            let query = set.adapt("(method_declaration name: (identifier) @name)");
+            match Query::new(&language(), &query) {
+                Ok(_) => Vec::new(),
+                Err(_) => vec!["RULE".to_string()],
+            }
+        };
+        assert_eq!(select(SETS, failing).grammar, "next");
+        // 都无法编译时取失败最少的
+        assert_eq!(select(&SETS[..1], failing).grammar, LINKED);
+    }
+}
@@ -0,0 +1,231 @@
+//! 持锁期间的跨方法阻塞 (HELD_LOCK_BLOCKING)
+//!
+//! SYNC_METHOD / SLEEP_IN_LOCK 等规则只看持锁代码里的直接调用。持锁时调用的本项目方法若在几层之后
+//! 又获取另一把锁 (各线程加锁顺序不一致时死锁)，或执行阻塞 IO / 数据库访问，持锁时间同样被拉长到
+//! 网络往返级别，而单看任何一个文件都发现不了:
+//!
+//! - Phase 1 ([`method_effects`]) 登记每个方法直接执行的加锁与阻塞调用，存入 CallGraph (随索引缓存)
+//! - 持锁区域 ([`held_region`]): synchronized 方法体、synchronized 块、`lock.lock()` 后紧跟的 try 块
+//! - 处理器对区域内的每个调用沿 CallGraph 查找 (`--lock-depth`，默认 [`DEFAULT_DEPTH`] 层)，
+//!   报告第一条到达危险操作的调用路径
+//!
+//! 锁以 `类名.this` / `类名.class` / `类名.字段` 标识，标识相同即视为同一把锁 (重入不报告)。
+//! lambda 与内部类的代码不一定在持锁线程上执行，不计入。
+
+use tree_sitter::Node;
+
+use super::node_walk::NodeWalk;
+use super::rule_handlers::{BLOCKING_APIS, BLOCKING_METHODS};
+use crate::taint::MethodEffects;
+
+/// 默认沿调用图追踪的层数
+pub const DEFAULT_DEPTH: usize = 3;
+
+/// 阻塞直到获取锁的调用
+pub(crate) const LOCK_METHODS: &[&str] = &["lock", "lockInterruptibly"];
+
+/// 源码中可能有加锁或阻塞调用 (Phase 1 跳过无关文件的额外解析；与 BLOCKING_APIS 保持一致)
+pub fn may_have_effects(code: &str) -> bool {
+    ["synchronized", "lock", "sleep", "Template", "Files", "block"].iter().any(|hint| code.contains(hint))
+}
+
+fn text<'t>(node: Node, code: &'t str) -> &'t str {
+    node.utf8_text(code.as_bytes()).unwrap_or("")
+}
+
+/// 锁对象表达式的标识 (`this` → `类名.this`，`lock` / `this.lock` → `类名.lock`，`Foo.class` 与 `Foo.LOCK` 原样)
+pub fn lock_key(class: &str, expr: &str) -> String {
+    let expr = expr.trim().trim_start_matches('(').trim_end_matches(')').trim();
+    let expr = expr.strip_prefix("this.").unwrap_or(expr);
+    if expr.contains('.') { expr.to_string() } else { format!("{class}.{expr}") }
+}
+
+fn has_modifier(decl: Node, modifier: &str) -> bool {
+    let mut cursor = decl.walk();
+    let found = decl.children(&mut cursor)
+        .filter(|c| c.kind() == "modifiers")
+        .any(|mods| {
+            let mut cursor = mods.walk();
+            let found = mods.children(&mut cursor).any(|m| m.kind() == modifier);
+            found
+        });
+    found
+}
+
+/// 在持锁线程上直接执行的节点 (不进入 lambda 与内部类)
+fn visit_direct<'t>(node: Node<'t>, f: &mut impl FnMut(Node<'t>)) {
+    f(node);
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if !matches!(child.kind(), "lambda_expression" | "class_body") {
+            visit_direct(child, f);
+        }
+    }
+}
+
+/// 区域内直接执行的方法调用
+pub fn region_calls(region: Node) -> Vec<Node> {
+    let mut calls = Vec::new();
+    visit_direct(region, &mut |node| {
+        if node.kind() == "method_invocation" {
+            calls.push(node);
+        }
+    });
+    calls
+}
+
+/// 已知的阻塞调用 (`接收者.方法`)
+fn blocking_call(receiver: &str, method: &str) -> Option<String> {
+    let receiver = receiver.strip_prefix("this.").unwrap_or(receiver);
+    let receiver_lower = receiver.to_lowercase();
+    let known = BLOCKING_APIS.iter().any(|(r, methods)| receiver_lower.contains(r) && methods.contains(&method));
+    (known || BLOCKING_METHODS.contains(&method)).then(|| format!("{receiver}.{method}"))
+}
+
+/// 每个方法 (类名, 方法名) 直接执行的加锁与阻塞调用，没有时不列出
+pub fn method_effects(root: Node, code: &str) -> Vec<(String, String, MethodEffects)> {
+    let mut result = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+        if !matches!(node.kind(), "method_declaration" | "constructor_declaration") {
+            continue;
+        }
+        let (Some(class), Some(name)) = (NodeWalk::new(code).enclosing_type_name(node), node.child_by_field_name("name")) else { continue };
+        let mut effects = MethodEffects::default();
+        if has_modifier(node, "synchronized") {
+            let target = if has_modifier(node, "static") { "class" } else { "this" };
+            effects.locks.push(format!("{class}.{target}"));
+        }
+        if let Some(body) = node.child_by_field_name("body") {
+            visit_direct(body, &mut |n| match n.kind() {
+                "synchronized_statement" => {
+                    if let Some(lock) = n.named_child(0).filter(|c| c.kind() == "parenthesized_expression") {
+                        effects.locks.push(lock_key(class, text(lock, code)));
+                    }
+                }
+                "method_invocation" => {
+                    let method = n.child_by_field_name("name").map_or("", |m| text(m, code));
+                    let Some(object) = n.child_by_field_name("object") else { return };
+                    if LOCK_METHODS.contains(&method) {
+                        effects.locks.push(lock_key(class, text(object, code)));
+                    } else if let Some(call) = blocking_call(text(object, code), method) {
+                        effects.blocking.push(call);
+                    }
+                }
+                _ => {}
+            });
+        }
+        if !effects.is_empty() {
+            result.push((class.to_string(), text(name, code).to_string(), effects));
+        }
+    }
+    result
+}
+
+/// 持锁区域: (锁标识, 区域节点)
+///
+/// `node` 为 synchronized 方法、synchronized 块，或 `lock()` 调用 (其后紧跟的 try 块为区域)。
+pub fn held_region<'t>(node: Node<'t>, class: &str, code: &str) -> Option<(String, Node<'t>)> {
+    match node.kind() {
+        "method_declaration" if has_modifier(node, "synchronized") => {
+            let target = if has_modifier(node, "static") { "class" } else { "this" };
+            Some((format!("{class}.{target}"), node.child_by_field_name("body")?))
+        }
+        "synchronized_statement" => {
+            let lock = node.named_child(0).filter(|c| c.kind() == "parenthesized_expression")?;
+            Some((lock_key(class, text(lock, code)), node.child_by_field_name("body")?))
+        }
+        "method_invocation" => {
+            let object = node.child_by_field_name("object")?;
+            let statement = node.parent().filter(|p| p.kind() == "expression_statement")?;
+            let mut next = statement.next_named_sibling();
+            while let Some(sibling) = next.filter(|s| matches!(s.kind(), "line_comment" | "block_comment" | "comment")) {
+                next = sibling.next_named_sibling();
+            }
+            let try_block = next.filter(|s| s.kind() == "try_statement")?;
+            Some((lock_key(class, text(object, code)), try_block.child_by_field_name("body")?))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(code: &str) -> tree_sitter::Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&crate::scanner::grammar::language()).unwrap();
+        parser.parse(code, None).unwrap()
+    }
+
+    #[test]
+    fn test_method_effects() {
+        let code = r#"
+public class PriceClient {
+    private final ReentrantLock lock = new ReentrantLock();
+
+    public synchronized Price cached(String sku) { return cache.get(sku); }
+
+    public static synchronized void reset() {}
+
+    public Price fetch(String sku) {
+        synchronized (this.cache) {
+            executor.submit(() -> restTemplate.getForObject(url, Price.class));
+        }
+        lock.lock();
+        return this.restTemplate.getForObject(url, Price.class, sku);
+    }
+
+    public int size() { return cache.size(); }
+}
+"#;
+        let tree = parse(code);
+        let mut effects = method_effects(tree.root_node(), code);
+        effects.sort_by(|a, b| a.1.cmp(&b.1));
+        let summary: Vec<(&str, Vec<&str>, Vec<&str>)> = effects.iter()
+            .map(|(_, method, e)| (method.as_str(), e.locks.iter().map(String::as_str).collect(), e.blocking.iter().map(String::as_str).collect()))
+            .collect();
+        // lambda 内的调用不在持锁线程上执行
+        assert_eq!(summary, vec![
+            ("cached", vec!["PriceClient.this"], vec![]),
+            ("fetch", vec!["PriceClient.cache", "PriceClient.lock"], vec!["restTemplate.getForObject"]),
+            ("reset", vec!["PriceClient.class"], vec![]),
+        ]);
+    }
+
+    #[test]
+    fn test_held_region() {
+        let code = r#"
+class Ledger {
+    void post() {
+        lock.lock();
+        // 记账
+        try {
+            audit.record();
+        } finally {
+            lock.unlock();
+        }
+        other.lock();
+        audit.flush();
+    }
+}
+"#;
+        let tree = parse(code);
+        let class_body = tree.root_node().named_child(0).and_then(|c| c.child_by_field_name("body")).unwrap();
+        let body = class_body.named_child(0).and_then(|m| m.child_by_field_name("body")).unwrap();
+        let calls = region_calls(body);
+        let lock_calls: Vec<Node> = calls.iter().copied()
+            .filter(|c| c.child_by_field_name("name").is_some_and(|n| text(n, code) == "lock"))
+            .collect();
+        let (lock, region) = held_region(lock_calls[0], "Ledger", code).unwrap();
+        assert_eq!(lock, "Ledger.lock");
+        let names: Vec<&str> = region_calls(region).iter().map(|c| text(c.child_by_field_name("name").unwrap(), code)).collect();
+        assert_eq!(names, ["record"]);
+        // 后面没有 try 块
+        assert!(held_region(lock_calls[1], "Ledger", code).is_none());
+        assert_eq!(lock_key("Ledger", "(Ledger.class)"), "Ledger.class");
+    }
+}
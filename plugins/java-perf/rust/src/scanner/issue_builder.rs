@@ -0,0 +1,186 @@
+//! Issue 构造
+//!
+//! 各 RuleHandler 只决定"报告哪个节点、附加什么上下文"，位置相关字段统一由
+//! [`IssueBuilder`] 从锚点节点推导，避免手写时漏填列号或取错文件名:
+//!
+//! | 字段 | 来源 |
+//! |------|------|
+//! | file | `ctx.file_path` 的文件名 |
+//! | line / column | 锚点节点起始位置 (列为字符列，见 [`node_column`]) |
+//! | snippet | 锚点所在行源码 (去除首尾空白，超过 [`SNIPPET_MAX_WIDTH`] 显示列时截断) |
+//! | fingerprint | 规则 ID + 所在类 + 归一化的行源码，不含行号，代码上下移动时保持不变 |
+//! | patch | 处理器给出的确定性修复 ([`IssueBuilder::patch`]，可选) |
+
+use std::path::Path;
+
+use tree_sitter::Node;
+
+use super::patch::{hunks, Edit};
+use super::rule_handlers::RuleContext;
+use super::{node_column, truncate_display, Confidence, Issue, Severity};
+
+/// 片段的最大显示宽度 (压缩/生成代码的超长行只保留开头)
+pub const SNIPPET_MAX_WIDTH: usize = 200;
+
+/// 规则元数据 (RuleRegistry 中的定义，传给 RuleHandler)
+#[derive(Debug, Clone, Copy)]
+pub struct RuleMeta<'a> {
+    pub id: &'a str,
+    pub severity: Severity,
+    pub description: &'a str,
+}
+
+/// 从锚点节点构造 Issue
+pub struct IssueBuilder {
+    issue: Issue,
+    scope: String,
+}
+
+impl IssueBuilder {
+    /// 位置、文件名、行源码取自 `node`，ID/级别/描述取自规则定义
+    pub fn new(rule: &RuleMeta, node: Node, ctx: &RuleContext) -> Self {
+        let line = node.start_position().row + 1;
+        Self {
+            issue: Issue {
+                id: rule.id.to_string(),
+                severity: rule.severity,
+                file: file_name(ctx.file_path),
+                line,
+                column: node_column(ctx.code, node),
+                description: rule.description.to_string(),
+                context: None,
+                confidence: None,
+                snippet: line_snippet(ctx.code, line),
+                fingerprint: String::new(),
+                patch: None,
+            },
+            scope: ctx.current_class.to_string(),
+        }
+    }
+
+    /// 覆盖规则 ID (多条查询归并为同一 ID，如 N_PLUS_ONE_WHILE → N_PLUS_ONE)
+    pub fn id(mut self, id: &str) -> Self {
+        self.issue.id = id.to_string();
+        self
+    }
+
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.issue.severity = severity;
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.issue.description = description.into();
+        self
+    }
+
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.issue.context = Some(context.into());
+        self
+    }
+
+    pub fn confidence(mut self, confidence: Option<Confidence>) -> Self {
+        self.issue.confidence = confidence;
+        self
+    }
+
+    /// 附带确定性修复 (替换无法生成 diff 时忽略)
+    pub fn patch(mut self, code: &str, edits: &[Edit]) -> Self {
+        self.issue.patch = hunks(code, edits);
+        self
+    }
+
+    pub fn build(mut self) -> Issue {
+        self.issue.fingerprint = fingerprint(&self.issue.id, &self.scope, &self.issue.snippet);
+        self.issue
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// 第 `line` 行 (1-based) 源码，去除首尾空白并按显示宽度截断
+pub fn line_snippet(code: &str, line: usize) -> String {
+    line.checked_sub(1)
+        .and_then(|idx| code.lines().nth(idx))
+        .map(|text| truncate_display(text.trim(), SNIPPET_MAX_WIDTH))
+        .unwrap_or_default()
+}
+
+/// 位置无关的问题指纹 (16 位十六进制)
+///
+/// 行源码中的空白被归一化，缩进或格式调整不影响指纹。使用固定的 FNV-1a 而不是
+/// `DefaultHasher`，保证不同版本/平台的输出一致，可用于 baseline 与 SARIF partialFingerprints。
+pub fn fingerprint(rule_id: &str, scope: &str, snippet: &str) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let normalized = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut hash = OFFSET;
+    for part in [rule_id, scope, normalized.as_str()] {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+    #[test]
+    fn test_builder_derives_positions() {
+        let code = "class A {\n    void m() {\n        call();\n    }\n}";
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issue = analyzer.parse_with(code, |tree| {
+            let body = tree.root_node().named_child(0).unwrap().child_by_field_name("body").unwrap();
+            let method = body.named_child(0).unwrap();
+            let ctx = RuleContext {
+                code,
+                file_path: Path::new("/src/main/A.java"),
+                current_class: "A",
+                symbol_table: None,
+                call_graph: None,
+            };
+            let rule = RuleMeta { id: "RULE", severity: Severity::P1, description: "desc" };
+            IssueBuilder::new(&rule, method, &ctx).context("m()").build()
+        }).unwrap();
+
+        assert_eq!((issue.file.as_str(), issue.line, issue.column), ("A.java", 2, 4));
+        assert_eq!(issue.snippet, "void m() {");
+        assert_eq!(issue.context.as_deref(), Some("m()"));
+        assert_eq!(issue.fingerprint, fingerprint("RULE", "A", "void m() {"));
+    }
+
+    #[test]
+    fn test_line_snippet_truncates_long_lines() {
+        let long = format!("var s = \"{}\";", "订单".repeat(200));
+        let code = format!("class A {{\n\t{long}\n}}");
+        let snippet = line_snippet(&code, 2);
+        assert!(snippet.starts_with("var s = \"订单") && snippet.ends_with("..."), "{snippet}");
+        assert!(crate::scanner::display_width(&snippet) <= SNIPPET_MAX_WIDTH);
+        assert_eq!(line_snippet(&code, 3), "}");
+        assert_eq!(line_snippet(&code, 9), "");
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_whitespace_only() {
+        let base = fingerprint("N_PLUS_ONE", "OrderService", "repo.findById(id);");
+        assert_eq!(base.len(), 16);
+        assert_eq!(base, fingerprint("N_PLUS_ONE", "OrderService", "\trepo.findById(id);  "));
+        assert_eq!(
+            fingerprint("N_PLUS_ONE", "OrderService", "Order o = repo.findById(id);"),
+            fingerprint("N_PLUS_ONE", "OrderService", "Order o  =\trepo.findById(id);"),
+        );
+        assert_ne!(base, fingerprint("N_PLUS_ONE", "UserService", "repo.findById(id);"));
+        assert_ne!(base, fingerprint("OBJECT_IN_LOOP", "OrderService", "repo.findById(id);"));
+        // 字段边界参与哈希: ("ab", "c") 与 ("a", "bc") 不同
+        assert_ne!(fingerprint("ab", "c", ""), fingerprint("a", "bc", ""));
+    }
+}
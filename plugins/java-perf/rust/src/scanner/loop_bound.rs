@@ -0,0 +1,290 @@
+//! 循环规模推断
+//!
+//! 循环内规则 (N+1、循环内创建对象) 的危害与迭代次数成正比。这里从循环头提取
+//! "迭代次数明显很大" 的证据，供各循环规则升级严重级别:
+//!
+//! - 计数循环与常量比较且上界超过 [`LARGE_LITERAL_BOUND`]: `for (int i = 0; i < 10000; i++)`、
+//!   `i < MAX_BATCH` (常量由 const_eval 在文件内折叠)
+//! - 遍历全表查询结果: `for (Order o : repo.findAll())`、`orders = repo.findAll(); for (o : orders)`
+//! - 遍历请求批量参数: `@RequestBody List<Long> ids`、`request.getItems()`
+//!
+//! 只看循环头本身与所在方法内的局部声明，不做跨方法推断。
+
+use tree_sitter::Node;
+
+use super::call_chain::CallChain;
+use super::const_eval;
+use super::node_walk::{ancestors, NodeWalk, METHOD_DECLARATIONS};
+
+/// 字面量上界超过该值视为大循环
+pub const LARGE_LITERAL_BOUND: u64 = 1000;
+
+/// 全表查询方法名前缀
+const FIND_ALL_PREFIXES: &[&str] = &["findAll", "selectAll", "listAll", "selectList"];
+
+/// 请求体注解 (参数的批量大小由调用方决定)
+const REQUEST_BATCH_ANNOTATIONS: &[&str] = &["RequestBody", "RequestParam"];
+
+const LOOP_KINDS: &[&str] = &["for_statement", "enhanced_for_statement", "while_statement", "do_statement"];
+
+/// 大循环证据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopBound {
+    /// 与常量比较的计数循环
+    Literal(u64),
+    /// 遍历全表查询结果 (查询调用文本)
+    FindAll(String),
+    /// 遍历请求批量参数 (参数名)
+    RequestBatch(String),
+}
+
+impl LoopBound {
+    /// 附加到 Issue context 的证据说明
+    pub fn evidence(&self) -> String {
+        match self {
+            LoopBound::Literal(n) => format!("循环上界 {}", n),
+            LoopBound::FindAll(call) => format!("遍历全表查询 {}", call),
+            LoopBound::RequestBatch(param) => format!("遍历请求批量参数 {}", param),
+        }
+    }
+}
+
+/// 节点所在的各层循环 (由内到外，止于方法/lambda 边界)
+pub fn enclosing_loops(node: Node) -> Vec<Node> {
+    ancestors(node)
+        .take_while(|n| !matches!(n.kind(), "method_declaration" | "constructor_declaration" | "lambda_expression" | "class_body"))
+        .filter(|n| LOOP_KINDS.contains(&n.kind()))
+        .collect()
+}
+
+/// 节点所在循环中第一个大循环证据 (内层优先)
+pub fn large_enclosing_bound(node: Node, code: &str) -> Option<LoopBound> {
+    enclosing_loops(node).into_iter().find_map(|l| large_bound(l, code))
+}
+
+/// 单个循环的大循环证据
+pub fn large_bound(loop_node: Node, code: &str) -> Option<LoopBound> {
+    match loop_node.kind() {
+        "enhanced_for_statement" => {
+            let iterable = loop_node.child_by_field_name("value")?;
+            collection_bound(iterable, loop_node, code)
+        }
+        "for_statement" | "while_statement" | "do_statement" => {
+            let condition = loop_node.child_by_field_name("condition")?;
+            condition_bound(condition, loop_node, code)
+        }
+        _ => None,
+    }
+}
+
+/// 循环条件中的上界: 字面量或集合的 size()/length
+fn condition_bound(condition: Node, loop_node: Node, code: &str) -> Option<LoopBound> {
+    let condition = unwrap_parens(condition);
+    if condition.kind() != "binary_expression" {
+        return None;
+    }
+    let operator = condition.child_by_field_name("operator")?.kind();
+    if !matches!(operator, "<" | "<=" | ">" | ">=" | "!=") {
+        return None;
+    }
+    let left = condition.child_by_field_name("left")?;
+    let right = condition.child_by_field_name("right")?;
+
+    for side in [right, left] {
+        if let Some(n) = integer_constant(side, code) {
+            return (n > LARGE_LITERAL_BOUND).then_some(LoopBound::Literal(n));
+        }
+        if let Some(collection) = size_receiver(side, code) {
+            if let Some(bound) = collection_bound(collection, loop_node, code) {
+                return Some(bound);
+            }
+        }
+    }
+    None
+}
+
+/// 集合表达式的来源证据
+fn collection_bound(expr: Node, loop_node: Node, code: &str) -> Option<LoopBound> {
+    let expr = unwrap_parens(expr);
+    match expr.kind() {
+        "method_invocation" => {
+            let chain = CallChain::from_invocation(expr, code);
+            if chain.names().any(is_find_all) {
+                return Some(LoopBound::FindAll(text(expr, code)));
+            }
+            // request.getItems() 的接收者是请求参数
+            let receiver = chain.receiver.filter(|r| r.kind() == "identifier")?;
+            request_batch_param(text(receiver, code).as_str(), loop_node, code)
+        }
+        "identifier" => {
+            let name = text(expr, code);
+            if let Some(call) = local_find_all(&name, loop_node, code) {
+                return Some(LoopBound::FindAll(format!("{} = {}", name, call)));
+            }
+            request_batch_param(&name, loop_node, code)
+        }
+        _ => None,
+    }
+}
+
+fn is_find_all(name: &str) -> bool {
+    FIND_ALL_PREFIXES.iter().any(|p| name.starts_with(p))
+}
+
+/// 方法内在循环之前声明、由全表查询初始化的局部变量
+fn local_find_all(name: &str, loop_node: Node, code: &str) -> Option<String> {
+    let body = enclosing_method(loop_node, code)?.child_by_field_name("body")?;
+    let mut found = None;
+    visit(body, &mut |n| {
+        if found.is_some() || n.start_byte() >= loop_node.start_byte() || n.kind() != "variable_declarator" {
+            return;
+        }
+        let declares = n.child_by_field_name("name").is_some_and(|id| text(id, code) == name);
+        let value = n.child_by_field_name("value").filter(|v| v.kind() == "method_invocation");
+        if let (true, Some(value)) = (declares, value) {
+            if CallChain::from_invocation(value, code).names().any(is_find_all) {
+                found = Some(text(value, code));
+            }
+        }
+    });
+    found
+}
+
+/// 名为 `name` 且带请求体注解的方法参数
+fn request_batch_param(name: &str, loop_node: Node, code: &str) -> Option<LoopBound> {
+    let params = enclosing_method(loop_node, code)?.child_by_field_name("parameters")?;
+    let mut cursor = params.walk();
+    let param = params.named_children(&mut cursor)
+        .filter(|p| p.kind() == "formal_parameter")
+        .find(|p| p.child_by_field_name("name").is_some_and(|id| text(id, code) == name))?;
+
+    let mut cursor = param.walk();
+    let modifiers = param.children(&mut cursor).find(|c| c.kind() == "modifiers")?;
+    let mut cursor = modifiers.walk();
+    let annotation = modifiers.named_children(&mut cursor)
+        .filter(|a| matches!(a.kind(), "marker_annotation" | "annotation"))
+        .filter_map(|a| a.child_by_field_name("name"))
+        .map(|n| text(n, code))
+        .find(|n| REQUEST_BATCH_ANNOTATIONS.contains(&n.as_str()))?;
+    Some(LoopBound::RequestBatch(format!("@{} {}", annotation, name)))
+}
+
+/// 所在方法；先遇到类体 (初始化块、字段初始化器) 时为 None
+fn enclosing_method<'t>(node: Node<'t>, code: &str) -> Option<Node<'t>> {
+    NodeWalk::new(code).ancestor_of_kind(node, &["method_declaration", "constructor_declaration", "class_body"])
+        .filter(|n| METHOD_DECLARATIONS.contains(&n.kind()))
+}
+
+/// `items.size()` / `items.length` 的集合表达式
+fn size_receiver<'t>(node: Node<'t>, code: &str) -> Option<Node<'t>> {
+    let (member, expected) = match node.kind() {
+        "method_invocation" => (node.child_by_field_name("name")?, "size"),
+        "field_access" => (node.child_by_field_name("field")?, "length"),
+        _ => return None,
+    };
+    if text(member, code) != expected {
+        return None;
+    }
+    node.child_by_field_name("object")
+}
+
+/// 非负整数常量 (字面量或文件内可折叠的常量表达式)
+fn integer_constant(node: Node, code: &str) -> Option<u64> {
+    let value = const_eval::eval_int(node, code)?.value;
+    u64::try_from(value).ok()
+}
+
+fn unwrap_parens(mut node: Node) -> Node {
+    while node.kind() == "parenthesized_expression" {
+        match node.named_child(0) {
+            Some(inner) => node = inner,
+            None => break,
+        }
+    }
+    node
+}
+
+fn text(node: Node, code: &str) -> String {
+    node.utf8_text(code.as_bytes()).unwrap_or("").to_string()
+}
+
+fn visit<'t>(node: Node<'t>, f: &mut dyn FnMut(Node<'t>)) {
+    f(node);
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        visit(child, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 方法体中名为 `marker()` 的调用所在循环的证据
+    fn bound_at_marker(body: &str) -> Option<LoopBound> {
+        let code = format!("class A {{ {} }}", body);
+        let analyzer = super::super::tree_sitter_java::JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(&code, |tree| {
+            let mut marker = None;
+            visit(tree.root_node(), &mut |n| {
+                if n.kind() == "method_invocation" && n.child_by_field_name("name").is_some_and(|id| text(id, &code) == "marker") {
+                    marker = Some(n);
+                }
+            });
+            large_enclosing_bound(marker.expect("marker() call"), &code)
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_literal_bounds() {
+        assert_eq!(
+            bound_at_marker("void m() { for (int i = 0; i < 10_000; i++) { marker(); } }"),
+            Some(LoopBound::Literal(10000))
+        );
+        assert_eq!(
+            bound_at_marker("void m() { long n = 0; while (5000L > n) { marker(); n++; } }"),
+            Some(LoopBound::Literal(5000))
+        );
+        assert_eq!(
+            bound_at_marker("static final int BATCH = 500; void m() { for (int i = 0; i < BATCH * 20; i++) { marker(); } }"),
+            Some(LoopBound::Literal(10000))
+        );
+        assert_eq!(bound_at_marker("void m() { for (int i = 0; i < 1000; i++) { marker(); } }"), None);
+        assert_eq!(bound_at_marker("void m() { for (int i = 0; i < max; i++) { marker(); } }"), None);
+    }
+
+    #[test]
+    fn test_find_all_bounds() {
+        let bound = bound_at_marker("void m() { for (Order o : orderRepo.findAll()) { marker(); } }");
+        assert_eq!(bound, Some(LoopBound::FindAll("orderRepo.findAll()".into())));
+
+        let bound = bound_at_marker(
+            "void m() { List<Order> orders = orderRepo.findAll(); for (int i = 0; i < orders.size(); i++) { marker(); } }",
+        );
+        assert_eq!(bound, Some(LoopBound::FindAll("orders = orderRepo.findAll()".into())));
+
+        // 外层大循环、内层小循环: 仍取外层证据
+        let bound = bound_at_marker(
+            "void m() { List<Order> orders = repo.findAll(); for (Order o : orders) { for (Item it : o.getItems()) { marker(); } } }",
+        );
+        assert_eq!(bound, Some(LoopBound::FindAll("orders = repo.findAll()".into())));
+
+        assert_eq!(bound_at_marker("void m() { for (Order o : repo.findByStatus(s)) { marker(); } }"), None);
+    }
+
+    #[test]
+    fn test_request_batch_bounds() {
+        let bound = bound_at_marker("void m(@RequestBody List<Long> ids) { for (Long id : ids) { marker(); } }");
+        assert_eq!(bound, Some(LoopBound::RequestBatch("@RequestBody ids".into())));
+
+        let bound = bound_at_marker("void m(@RequestBody BatchRequest req) { for (Item it : req.getItems()) { marker(); } }");
+        assert_eq!(bound, Some(LoopBound::RequestBatch("@RequestBody req".into())));
+
+        assert_eq!(bound_at_marker("void m(List<Long> ids) { for (Long id : ids) { marker(); } }"), None);
+        // lambda 内不继承外层循环
+        assert_eq!(
+            bound_at_marker("void m() { for (Order o : repo.findAll()) { run(() -> marker()); } }"),
+            None
+        );
+    }
+}
@@ -1,12 +1,24 @@
 use serde::{Serialize, Deserialize};
 use std::path::Path;
 use anyhow::Result;
+use unicode_width::UnicodeWidthChar;
 
 pub mod tree_sitter_java;
+pub mod grammar;         // tree-sitter-java 语法版本与查询集回退
 pub mod config;
 pub mod dockerfile;
 pub mod rule_handlers;  // v9.2: RuleHandler trait 解耦规则处理
 pub mod queries;        // v9.4: 外部化 Query 加载
+pub mod call_chain;
+pub mod loop_bound;
+pub mod held_lock;
+pub mod node_walk;      // 处理器共用的祖先/后代遍历
+pub mod const_eval;
+pub mod sql_plan;
+pub mod issue_builder;
+pub mod parse_health;
+pub mod source_shape;
+pub mod patch;
 
 /// 严重级别
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -50,6 +62,95 @@ pub struct Issue {
     /// - `None`: Confidence not applicable for this rule type
     #[serde(skip_serializing_if = "Option::is_none")]
     pub confidence: Option<Confidence>,
+    /// 问题所在行源码 (去除首尾空白)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub snippet: String,
+    /// 位置无关的指纹 (见 issue_builder::fingerprint)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub fingerprint: String,
+    /// 确定性修复的 unified diff hunk (不含文件头，见 patch 模块)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<String>,
+}
+
+// ============================================================================
+// 位置映射
+// ============================================================================
+//
+// Tree-sitter 的 Point.column 是 UTF-8 字节偏移。源文件在解析前已统一转码为
+// UTF-8 (见 crate::encoding)，因此这里把字节列换算为字符列，保证含中文注释/
+// 字符串的行上报告的列号与编辑器一致。
+
+/// 节点起始位置的字符列 (0-based)
+pub fn node_column(code: &str, node: tree_sitter::Node) -> usize {
+    byte_to_char_column(code, node.start_byte(), node.start_position().column)
+}
+
+/// 将行内字节列换算为字符列
+///
+/// `byte_offset` 为全文字节偏移，`byte_column` 为该偏移在行内的字节列。
+/// 偏移越界或落在多字节字符中间时退化为按字节列返回，不会 panic。
+pub fn byte_to_char_column(code: &str, byte_offset: usize, byte_column: usize) -> usize {
+    let line_start = byte_offset.saturating_sub(byte_column);
+    code.get(line_start..byte_offset)
+        .map(|prefix| prefix.chars().count())
+        .unwrap_or(byte_column)
+}
+
+/// 行首空白字符数 (行级规则的列号)
+pub fn leading_whitespace_chars(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+// ============================================================================
+// 显示宽度
+// ============================================================================
+//
+// 片段与 context 在终端/Markdown 表格中展示，截断按显示列而不是字节计算:
+// Tab 展开到下一个制表位，CJK 等宽字符占 2 列，组合字符占 0 列。
+// 截断总在字符边界上进行，且只复制保留的部分 (压缩后的超长单行不会整行复制)。
+
+/// Tab 制表位间隔
+pub const TAB_WIDTH: usize = 4;
+
+/// 从显示列 `column` 写入字符 `c` 后的显示列
+fn advance_column(column: usize, c: char) -> usize {
+    match c {
+        '\t' => column + TAB_WIDTH - column % TAB_WIDTH,
+        _ => column + c.width().unwrap_or(0),
+    }
+}
+
+/// 文本的显示宽度 (Tab 展开、宽字符计 2 列)
+pub fn display_width(text: &str) -> usize {
+    text.chars().fold(0, advance_column)
+}
+
+/// 按显示宽度截断: 超出 `max_width` 时保留前缀并追加 `...` (总宽度不超过 `max_width`)
+///
+/// Tab 展开为空格，保证结果的宽度与展示一致。
+pub fn truncate_display(text: &str, max_width: usize) -> String {
+    const ELLIPSIS: &str = "...";
+    let ellipsis = display_width(text) > max_width && max_width >= ELLIPSIS.len();
+    let budget = if ellipsis { max_width - ELLIPSIS.len() } else { max_width };
+
+    let mut out = String::new();
+    let mut column = 0;
+    for c in text.chars() {
+        let next = advance_column(column, c);
+        if next > budget {
+            break;
+        }
+        match c {
+            '\t' => out.extend(std::iter::repeat_n(' ', next - column)),
+            _ => out.push(c),
+        }
+        column = next;
+    }
+    if ellipsis {
+        out.push_str(ELLIPSIS);
+    }
+    out
 }
 
 /// 代码分析器 Trait
@@ -61,3 +162,54 @@ pub trait CodeAnalyzer {
     /// 分析代码并返回问题列表
     fn analyze(&self, code: &str, file_path: &Path) -> Result<Vec<Issue>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_to_char_column_ascii() {
+        let code = "int a = 1;\nint b = 2;";
+        // "b" 位于第二行第 4 个字节
+        let offset = code.find('b').unwrap();
+        assert_eq!(byte_to_char_column(code, offset, 4), 4);
+    }
+
+    #[test]
+    fn test_byte_to_char_column_multibyte() {
+        let code = "String s = \"中文\"; call();";
+        let offset = code.find("call").unwrap();
+        // 两个中文字符各占 3 字节，字符列比字节列少 4
+        assert_eq!(byte_to_char_column(code, offset, offset), offset - 4);
+    }
+
+    #[test]
+    fn test_byte_to_char_column_out_of_range() {
+        assert_eq!(byte_to_char_column("abc", 10, 2), 2);
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("call();"), 7);
+        assert_eq!(display_width("中文"), 4);
+        assert_eq!(display_width("a\tb"), 5, "Tab 展开到第 4 列");
+        assert_eq!(display_width("e\u{301}"), 1, "组合字符不占列");
+    }
+
+    #[test]
+    fn test_truncate_display() {
+        assert_eq!(truncate_display("short", 10), "short");
+        assert_eq!(truncate_display("SELECT * FROM orders", 12), "SELECT * ...");
+        // 多字节字符不在中间切开，宽字符按 2 列计
+        assert_eq!(truncate_display("查询订单列表", 9), "查询订...");
+        assert_eq!(truncate_display("\tx = 1;", 20), "    x = 1;");
+        assert_eq!(truncate_display("abcdef", 2), "ab", "宽度不足以放下省略号时直接截断");
+    }
+
+    #[test]
+    fn test_leading_whitespace_chars() {
+        assert_eq!(leading_whitespace_chars("    key: value"), 4);
+        assert_eq!(leading_whitespace_chars("\tFROM x"), 1);
+        assert_eq!(leading_whitespace_chars("FROM x"), 0);
+    }
+}
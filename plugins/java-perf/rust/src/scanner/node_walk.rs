@@ -0,0 +1,168 @@
+//! AST 祖先/后代遍历 (处理器通过 `ctx.walk()` 获取)
+//!
+//! 处理器常见的三类遍历集中在这里，避免各自手写 parent 循环与递归查找:
+//!
+//! - 向上: [`NodeWalk::ancestors`] / [`NodeWalk::ancestor_of_kind`] (所在方法、类、lambda、语句)
+//! - 向下: [`NodeWalk::any_descendant`] / [`NodeWalk::find_descendants`] (先序，含节点自身)
+//! - 名称: [`NodeWalk::enclosing_type_name`] / [`NodeWalk::enclosing_method_fqn`]
+//!   (`包名.外层类型链.方法名`，与 hotness / method_rollup 的方法全限定名一致)
+
+use tree_sitter::Node;
+
+/// 类型声明 (匿名类不在其中)
+pub const TYPE_DECLARATIONS: &[&str] = &["class_declaration", "interface_declaration", "enum_declaration", "record_declaration"];
+
+/// 方法与构造器声明
+pub const METHOD_DECLARATIONS: &[&str] = &["method_declaration", "constructor_declaration"];
+
+/// 由近到远的祖先 (不含节点自身)，不需要源码文本的调用方直接使用
+pub fn ancestors<'t>(node: Node<'t>) -> impl Iterator<Item = Node<'t>> {
+    std::iter::successors(node.parent(), |n| n.parent())
+}
+
+/// 绑定源码的遍历工具 (复制开销可忽略)
+#[derive(Debug, Clone, Copy)]
+pub struct NodeWalk<'a> {
+    code: &'a str,
+}
+
+impl<'a> NodeWalk<'a> {
+    pub fn new(code: &'a str) -> Self {
+        Self { code }
+    }
+
+    pub fn text(self, node: Node) -> &'a str {
+        node.utf8_text(self.code.as_bytes()).unwrap_or("")
+    }
+
+    /// 由近到远的祖先 (不含节点自身)
+    pub fn ancestors<'t>(self, node: Node<'t>) -> impl Iterator<Item = Node<'t>> {
+        ancestors(node)
+    }
+
+    /// 最近的指定类型祖先 (不含节点自身)
+    pub fn ancestor_of_kind<'t>(self, node: Node<'t>, kinds: &[&str]) -> Option<Node<'t>> {
+        self.ancestors(node).find(|n| kinds.contains(&n.kind()))
+    }
+
+    /// 子树中 (先序，含自身) 是否有满足条件的节点，找到即停止
+    ///
+    /// 显式栈遍历，深层嵌套的表达式 (长字符串拼接等) 不会耗尽调用栈。
+    pub fn any_descendant(self, node: Node, pred: &mut dyn FnMut(Node) -> bool) -> bool {
+        let mut stack = vec![node];
+        while let Some(n) = stack.pop() {
+            if pred(n) {
+                return true;
+            }
+            let mut cursor = n.walk();
+            let children: Vec<_> = n.children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        false
+    }
+
+    /// 子树中 (先序，含自身) 满足条件的全部节点
+    pub fn find_descendants<'t>(self, node: Node<'t>, mut pred: impl FnMut(Node<'t>) -> bool) -> Vec<Node<'t>> {
+        let mut found = Vec::new();
+        let mut stack = vec![node];
+        while let Some(n) = stack.pop() {
+            if pred(n) {
+                found.push(n);
+            }
+            let mut cursor = n.walk();
+            let children: Vec<_> = n.children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        found
+    }
+
+    /// 所在类型声明的简单名
+    pub fn enclosing_type_name(self, node: Node) -> Option<&'a str> {
+        self.ancestor_of_kind(node, TYPE_DECLARATIONS)
+            .and_then(|decl| decl.child_by_field_name("name"))
+            .map(|name| self.text(name))
+    }
+
+    /// 外层类型链 (由外到内)，匿名类不计入
+    pub fn type_chain(self, node: Node) -> Vec<&'a str> {
+        let mut chain = Vec::new();
+        let mut current = node;
+        while let Some(decl) = self.ancestor_of_kind(current, TYPE_DECLARATIONS) {
+            chain.extend(decl.child_by_field_name("name").map(|name| self.text(name)));
+            current = decl;
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// 文件的包名 (`root` 为 program 节点)
+    pub fn package_name(self, root: Node) -> Option<&'a str> {
+        let mut cursor = root.walk();
+        let package = root.children(&mut cursor).find(|n| n.kind() == "package_declaration")?;
+        let mut inner = package.walk();
+        let name = package.named_children(&mut inner)
+            .find(|n| matches!(n.kind(), "scoped_identifier" | "identifier"))?;
+        Some(self.text(name))
+    }
+
+    /// 节点所在方法/构造器 (节点本身是方法声明时取自身) 的全限定名: `com.shop.Outer.Inner.run`
+    pub fn enclosing_method_fqn(self, node: Node) -> Option<String> {
+        let method = if METHOD_DECLARATIONS.contains(&node.kind()) { node } else { self.ancestor_of_kind(node, METHOD_DECLARATIONS)? };
+        self.method_fqn(method, self.package_name(self.root(method)))
+    }
+
+    /// 语法树的根节点 (program)
+    pub fn root<'t>(self, node: Node<'t>) -> Node<'t> {
+        self.ancestors(node).last().unwrap_or(node)
+    }
+
+    /// 方法声明的全限定名 (包名由调用方给出，逐个方法计算时避免重复查找)
+    pub fn method_fqn(self, method: Node, package: Option<&str>) -> Option<String> {
+        let name = self.text(method.child_by_field_name("name")?);
+        let mut parts = self.type_chain(method);
+        if parts.is_empty() {
+            return None;
+        }
+        if let Some(package) = package {
+            parts.insert(0, package);
+        }
+        parts.push(name);
+        Some(parts.join("."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(code: &str) -> tree_sitter::Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&crate::scanner::grammar::language()).unwrap();
+        parser.parse(code, None).unwrap()
+    }
+
+    #[test]
+    fn test_walk_ancestors_descendants_and_fqn() {
+        let code = "package com.shop;\n\nclass Outer {\n    static class Inner {\n        void run() { repo.find(1); log(2); }\n    }\n    Outer() { new Runnable() { public void run() { tick(); } }; }\n}\n";
+        let tree = parse(code);
+        let walk = NodeWalk::new(code);
+        let calls = walk.find_descendants(tree.root_node(), |n| n.kind() == "method_invocation");
+        let names: Vec<&str> = calls.iter().map(|c| walk.text(c.child_by_field_name("name").unwrap())).collect();
+        assert_eq!(names, vec!["find", "log", "tick"]);
+
+        let find = calls[0];
+        assert_eq!(walk.ancestor_of_kind(find, METHOD_DECLARATIONS).map(|m| m.start_position().row), Some(4));
+        assert_eq!(walk.enclosing_type_name(find), Some("Inner"));
+        assert_eq!(walk.type_chain(find), vec!["Outer", "Inner"]);
+        assert_eq!(walk.enclosing_method_fqn(find).as_deref(), Some("com.shop.Outer.Inner.run"));
+        // 匿名类中的方法归入外层类型
+        assert_eq!(walk.enclosing_method_fqn(calls[2]).as_deref(), Some("com.shop.Outer.run"));
+        assert!(walk.any_descendant(tree.root_node(), &mut |n| walk.text(n) == "repo"));
+        let kinds: Vec<&str> = walk.ancestors(find).map(|n| n.kind()).collect();
+        assert_eq!(kinds.first(), Some(&"expression_statement"));
+        assert_eq!(kinds.iter().filter(|k| **k == "class_declaration").count(), 2);
+        assert_eq!(kinds.last(), Some(&"program"));
+        assert_eq!(walk.root(find).kind(), "program");
+        assert!(walk.enclosing_method_fqn(tree.root_node()).is_none());
+    }
+}
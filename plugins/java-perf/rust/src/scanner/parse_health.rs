@@ -0,0 +1,175 @@
+//! 解析健康度
+//!
+//! Tree-sitter 遇到语法错误不会失败，而是产出带 `ERROR` / `MISSING` 节点的树。
+//! 错误区域内的节点结构不可信 (方法体被吞进 ERROR、循环被拆散)，规则在这里
+//! 要么漏报、要么误报。这里统计错误节点与覆盖范围，供 Phase 2:
+//!
+//! - 报告降级文件 (`stats.parse_degraded`)，`--strict-parse` 时以失败退出
+//! - 区域恢复: 只保留错误区域之外的问题，其余方法照常分析
+//! - 错误覆盖超过 [`FAILED_RATIO`] 时整个文件视为解析失败，不报告问题
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use super::source_shape::SourceShape;
+
+/// 错误区域覆盖源码的比例达到该值时视为解析失败
+pub const FAILED_RATIO: f64 = 0.5;
+
+/// 文件解析状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseStatus {
+    Clean,
+    /// 含错误节点，错误区域外的问题照常报告
+    Degraded,
+    /// 无法解析或错误覆盖过大，不报告问题
+    Failed,
+}
+
+/// 单个文件的解析健康度
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseHealth {
+    /// ERROR 节点 (最外层) 与 MISSING 节点数
+    pub error_nodes: usize,
+    /// ERROR 节点覆盖的字节数
+    pub error_bytes: usize,
+    pub total_bytes: usize,
+    /// 错误区域的行范围 (1-based，闭区间)，按起始行排序
+    pub error_lines: Vec<(usize, usize)>,
+    /// 顶层结构 (默认包 / 仅注解类型等，见 source_shape)
+    pub shape: SourceShape,
+}
+
+impl ParseHealth {
+    /// 统计语法树中的错误节点
+    pub fn of(root: Node, code: &str) -> Self {
+        let mut health = Self { total_bytes: code.len(), shape: SourceShape::of(root), ..Default::default() };
+        if root.has_error() {
+            health.collect(root);
+        }
+        health.error_lines.sort_unstable();
+        health
+    }
+
+    /// Parser 未能产出语法树 (超时/取消)
+    pub fn unparsed(code: &str) -> Self {
+        let lines = code.lines().count().max(1);
+        Self { error_nodes: 1, error_bytes: code.len(), total_bytes: code.len(), error_lines: vec![(1, lines)], ..Default::default() }
+    }
+
+    fn collect(&mut self, node: Node) {
+        if node.is_error() || node.is_missing() {
+            self.error_nodes += 1;
+            self.error_bytes += node.end_byte() - node.start_byte();
+            let (start, end) = (node.start_position(), node.end_position());
+            // 结束于行首时该行不属于错误区域
+            let end_row = if end.column == 0 && end.row > start.row { end.row - 1 } else { end.row };
+            self.error_lines.push((start.row + 1, end_row + 1));
+            // ERROR 内部的节点不再单独计数
+            return;
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.has_error() {
+                self.collect(child);
+            }
+        }
+    }
+
+    /// 错误区域覆盖源码的比例
+    pub fn error_ratio(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.error_bytes as f64 / self.total_bytes as f64
+    }
+
+    pub fn status(&self) -> ParseStatus {
+        if self.error_nodes == 0 {
+            ParseStatus::Clean
+        } else if self.error_ratio() >= FAILED_RATIO {
+            ParseStatus::Failed
+        } else {
+            ParseStatus::Degraded
+        }
+    }
+
+    pub fn first_error_line(&self) -> Option<usize> {
+        self.error_lines.first().map(|(start, _)| *start)
+    }
+
+    /// 行是否落在错误区域内 (该行的问题不可信)
+    pub fn covers_line(&self, line: usize) -> bool {
+        self.error_lines.iter().any(|(start, end)| (*start..=*end).contains(&line))
+    }
+
+    /// 区域恢复: 该行的问题是否保留
+    pub fn keeps_line(&self, line: usize) -> bool {
+        match self.status() {
+            ParseStatus::Clean => true,
+            ParseStatus::Degraded => !self.covers_line(line),
+            ParseStatus::Failed => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(code: &str) -> ParseHealth {
+        let analyzer = super::super::tree_sitter_java::JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(code, |tree| ParseHealth::of(tree.root_node(), code)).unwrap()
+    }
+
+    #[test]
+    fn test_clean_file() {
+        let h = health("class A { void run() { int x = 1; } }");
+        assert_eq!(h.status(), ParseStatus::Clean);
+        assert_eq!(h.error_nodes, 0);
+        assert!(h.keeps_line(1));
+        assert_eq!(h.first_error_line(), None);
+    }
+
+    #[test]
+    fn test_error_region_is_localized() {
+        let code = r#"
+class A {
+    void good() {
+        int x = 1;
+    }
+
+    void broken() {
+        int y = = = ;
+    }
+
+    void alsoGood() {
+        int z = 3;
+    }
+}
+"#;
+        let h = health(code);
+        assert_eq!(h.status(), ParseStatus::Degraded);
+        assert!(h.error_nodes >= 1);
+        assert_eq!(h.first_error_line(), Some(8));
+        assert!(!h.keeps_line(8));
+        assert!(h.keeps_line(4));
+        assert!(h.keeps_line(12));
+    }
+
+    #[test]
+    fn test_mostly_garbage_is_failed() {
+        let h = health("@@@ ### $$$ %%% ^^^ &&& *** ((( ))) !!! ~~~");
+        assert_eq!(h.status(), ParseStatus::Failed);
+        assert!(!h.keeps_line(1));
+        assert!(h.error_ratio() >= FAILED_RATIO);
+    }
+
+    #[test]
+    fn test_unparsed_covers_everything() {
+        let h = ParseHealth::unparsed("a\nb\nc");
+        assert_eq!(h.status(), ParseStatus::Failed);
+        assert_eq!(h.error_lines, vec![(1, 3)]);
+    }
+}
@@ -0,0 +1,231 @@
+//! 确定性修复的 unified diff
+//!
+//! 改写方式唯一的规则 (LOG_STRING_CONCAT、SIMPLE_DATE_FORMAT、STRING_CONCAT_LOOP) 由处理器
+//! 以字节区间替换 ([`Edit`]) 描述修复，这里转换为可直接 `git apply` 的 unified diff:
+//!
+//! | 函数 | 调用方 | 输出 |
+//! |------|--------|------|
+//! | [`hunks`] | RuleHandler (经 `IssueBuilder::patch`) | hunk 部分，每处改动带 [`CONTEXT_LINES`] 行上下文 |
+//! | [`unified_diff`] | ast_engine (相对路径确定后) | 加上 `--- a/路径` / `+++ b/路径` 文件头 |
+//! | [`replacements`] | report (SARIF `fixes`) | 按行的删除区间与插入内容 |
+
+use std::ops::Range;
+
+use tree_sitter::Node;
+
+/// hunk 上下文行数 (同 `diff -u`)
+pub const CONTEXT_LINES: usize = 3;
+
+/// 源码字节区间替换
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl Edit {
+    /// 替换节点源码
+    pub fn replace(node: Node, text: impl Into<String>) -> Self {
+        Self { range: node.byte_range(), text: text.into() }
+    }
+
+    /// 在字节偏移处插入
+    pub fn insert(at: usize, text: impl Into<String>) -> Self {
+        Self { range: at..at, text: text.into() }
+    }
+}
+
+/// 按行的替换 (SARIF replacement)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReplacement {
+    /// 起始行 (1-based)
+    pub start_line: usize,
+    /// 删除的行数 (0 = 在起始行前插入)
+    pub deleted: usize,
+    /// 插入的内容 (每行以换行结尾)
+    pub inserted: String,
+}
+
+/// 由替换生成 unified diff 的 hunk 部分
+///
+/// 替换区间重叠、越界、不在字符边界上或位于文件末尾之后时返回 None。
+pub fn hunks(code: &str, edits: &[Edit]) -> Option<String> {
+    let mut edits: Vec<&Edit> = edits.iter().collect();
+    edits.sort_by_key(|e| (e.range.start, e.range.end));
+    if edits.is_empty()
+        || edits.windows(2).any(|w| w[0].range.end > w[1].range.start)
+        || edits.iter().any(|e| code.get(e.range.clone()).is_none())
+    {
+        return None;
+    }
+
+    let lines: Vec<&str> = code.split_terminator('\n').collect();
+    let line_starts: Vec<usize> = std::iter::once(0).chain(code.match_indices('\n').map(|(i, _)| i + 1)).collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+    let line_end = |line: usize| line_starts.get(line + 1).copied().unwrap_or(code.len());
+
+    // 触及同一行的替换归为一组: (首行, 末行, 改写后的行)
+    let mut groups: Vec<(usize, usize, Vec<&Edit>)> = Vec::new();
+    for edit in edits {
+        let first = line_of(edit.range.start);
+        if first >= lines.len() {
+            return None;
+        }
+        let last = line_of(edit.range.end.max(edit.range.start + 1) - 1).min(lines.len().saturating_sub(1)).max(first);
+        match groups.last_mut() {
+            Some(group) if first <= group.1 => {
+                group.1 = group.1.max(last);
+                group.2.push(edit);
+            }
+            _ => groups.push((first, last, vec![edit])),
+        }
+    }
+    // 改写前后相同的首尾行作为上下文: (首行, 删除行数, 新行)
+    let groups: Vec<(usize, usize, Vec<String>)> = groups.into_iter()
+        .map(|(first, last, edits)| {
+            let base = line_starts[first];
+            let mut text = code[base..line_end(last)].to_string();
+            for edit in edits.iter().rev() {
+                text.replace_range(edit.range.start - base..edit.range.end - base, &edit.text);
+            }
+            let mut new_lines: Vec<String> = text.split_terminator('\n').map(str::to_string).collect();
+            let (mut first, mut deleted) = (first, last - first + 1);
+            while deleted > 0 && new_lines.last().is_some_and(|l| l == lines[first + deleted - 1]) {
+                new_lines.pop();
+                deleted -= 1;
+            }
+            let common = new_lines.iter().zip(&lines[first..first + deleted]).take_while(|(new, old)| new == *old).count();
+            new_lines.drain(..common);
+            first += common;
+            deleted -= common;
+            (first, deleted, new_lines)
+        })
+        .collect();
+
+    // 上下文重叠的组合并为一个 hunk
+    let mut out = String::new();
+    let mut delta: isize = 0;
+    let mut i = 0;
+    while i < groups.len() {
+        let mut j = i;
+        while j + 1 < groups.len() && groups[j + 1].0 <= groups[j].0 + groups[j].1 + 2 * CONTEXT_LINES {
+            j += 1;
+        }
+        let start = groups[i].0.saturating_sub(CONTEXT_LINES);
+        let end = (groups[j].0 + groups[j].1 + CONTEXT_LINES).min(lines.len());
+        let mut body = String::new();
+        let (mut old_count, mut new_count) = (0usize, 0usize);
+        let mut line = start;
+        for (first, deleted, new_lines) in &groups[i..=j] {
+            for context in &lines[line..*first] {
+                body.push_str(&format!(" {context}\n"));
+            }
+            for old in &lines[*first..first + deleted] {
+                body.push_str(&format!("-{old}\n"));
+            }
+            for new in new_lines {
+                body.push_str(&format!("+{new}\n"));
+            }
+            old_count += first - line + deleted;
+            new_count += first - line + new_lines.len();
+            line = first + deleted;
+        }
+        for context in &lines[line..end] {
+            body.push_str(&format!(" {context}\n"));
+        }
+        old_count += end - line;
+        new_count += end - line;
+
+        let new_start = (start as isize + 1 + delta).max(1);
+        out.push_str(&format!("@@ -{},{old_count} +{new_start},{new_count} @@\n", start + 1));
+        out.push_str(&body);
+        delta += new_count as isize - old_count as isize;
+        i = j + 1;
+    }
+    Some(out)
+}
+
+/// 加上文件头的完整 diff (`path` 为相对扫描根目录的路径)
+pub fn unified_diff(path: &str, hunks: &str) -> String {
+    format!("--- a/{path}\n+++ b/{path}\n{hunks}")
+}
+
+/// 把 diff 还原为按行的替换 (连续的 `-`/`+` 行为一处)
+pub fn replacements(diff: &str) -> Vec<LineReplacement> {
+    let mut result = Vec::new();
+    let mut old_line = 0;
+    let mut current: Option<LineReplacement> = None;
+    for line in diff.lines() {
+        if line.starts_with("---") || line.starts_with("+++") {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix("@@ -") {
+            result.extend(current.take());
+            old_line = header.split([',', ' ']).next().and_then(|n| n.parse().ok()).unwrap_or(1);
+            continue;
+        }
+        match line.as_bytes().first() {
+            Some(b'-' | b'+') => {
+                let replacement = current.get_or_insert_with(|| LineReplacement { start_line: old_line, deleted: 0, inserted: String::new() });
+                if let Some(inserted) = line.strip_prefix('+') {
+                    replacement.inserted.push_str(inserted);
+                    replacement.inserted.push('\n');
+                } else {
+                    replacement.deleted += 1;
+                    old_line += 1;
+                }
+            }
+            _ => {
+                result.extend(current.take());
+                old_line += 1;
+            }
+        }
+    }
+    result.extend(current);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+
+    fn at(line: usize) -> usize {
+        CODE.match_indices('\n').nth(line.wrapping_sub(2)).map_or(0, |(i, _)| i + 1)
+    }
+
+    #[test]
+    fn test_hunks_merge_nearby_edits() {
+        // 第 2 行替换，第 5 行前插入：上下文重叠，合并为一个 hunk；未改变的行作为上下文
+        let edits = [
+            Edit { range: at(2)..at(2) + 1, text: "B".into() },
+            Edit::insert(at(5), "x\n"),
+        ];
+        let hunks = hunks(CODE, &edits).unwrap();
+        assert_eq!(hunks, "@@ -1,7 +1,8 @@\n a\n-b\n+B\n c\n d\n+x\n e\n f\n g\n");
+
+        // 相距较远的改动分为两个 hunk，第二个 hunk 的新行号计入第一个的行数变化
+        let far = [Edit::insert(at(1), "0\n"), Edit { range: at(12)..at(12) + 1, text: "L".into() }];
+        let hunks = super::hunks(CODE, &far).unwrap();
+        assert!(hunks.starts_with("@@ -1,3 +1,4 @@\n+0\n a\n b\n c\n"), "{hunks}");
+        assert!(hunks.contains("@@ -9,4 +10,4 @@\n i\n j\n k\n-l\n+L\n"), "{hunks}");
+
+        assert!(super::hunks(CODE, &[]).is_none());
+        assert!(super::hunks(CODE, &[Edit { range: 0..3, text: String::new() }, Edit::insert(1, "x")]).is_none());
+    }
+
+    #[test]
+    fn test_replacements_roundtrip() {
+        let edits = [
+            Edit { range: at(2)..at(2) + 1, text: "B".into() },
+            Edit::insert(at(5), "x\n"),
+        ];
+        let diff = unified_diff("src/A.java", &hunks(CODE, &edits).unwrap());
+        assert!(diff.starts_with("--- a/src/A.java\n+++ b/src/A.java\n@@"));
+        assert_eq!(replacements(&diff), vec![
+            LineReplacement { start_line: 2, deleted: 1, inserted: "B\n".into() },
+            LineReplacement { start_line: 5, deleted: 0, inserted: "x\n".into() },
+        ]);
+    }
+}
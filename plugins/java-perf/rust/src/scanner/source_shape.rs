@@ -0,0 +1,140 @@
+//! 源文件形态 (默认包 / package-info / 仅注解类型 / BOM)
+//!
+//! 这些文件在 Phase 1/Phase 2 中与普通源文件走同一条路径，这里只负责识别并计数，
+//! 让报告能说明它们已被分析 (而不是静默跳过):
+//!
+//! - 默认包: 没有 package 声明，类型以简单名登记为 FQN
+//! - `package-info.java`: 只有包级注解，没有类型声明，不登记符号
+//! - 仅注解类型: 文件内只有 `@interface` 声明，登记为类型但没有方法体可分析
+//! - BOM: 读取时已剥离 (见 [`crate::encoding`])
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+/// 单个文件的顶层结构
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceShape {
+    pub has_package: bool,
+    /// 顶层类型声明数 (class / interface / enum / record / @interface)
+    pub types: usize,
+    /// 其中的 `@interface` 声明数
+    pub annotation_types: usize,
+}
+
+impl SourceShape {
+    pub fn of(root: Node) -> Self {
+        let mut shape = Self::default();
+        let mut cursor = root.walk();
+        for child in root.children(&mut cursor) {
+            match child.kind() {
+                "package_declaration" => shape.has_package = true,
+                "annotation_type_declaration" => {
+                    shape.types += 1;
+                    shape.annotation_types += 1;
+                }
+                "class_declaration" | "interface_declaration" | "enum_declaration" | "record_declaration" => {
+                    shape.types += 1;
+                }
+                _ => {}
+            }
+        }
+        shape
+    }
+
+    /// 有类型声明但没有 package 声明
+    pub fn is_default_package(&self) -> bool {
+        !self.has_package && self.types > 0
+    }
+
+    pub fn is_annotation_only(&self) -> bool {
+        self.types > 0 && self.types == self.annotation_types
+    }
+}
+
+/// 扫描中各形态的文件数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ShapeStats {
+    pub default_package: usize,
+    pub package_info: usize,
+    pub annotation_only: usize,
+    pub bom: usize,
+}
+
+impl ShapeStats {
+    /// 计入一个 Java 文件 (`shape` 为 None 表示未能解析)
+    pub fn record(&mut self, file_name: &str, shape: Option<SourceShape>, bom: bool) {
+        if bom {
+            self.bom += 1;
+        }
+        if file_name == "package-info.java" {
+            self.package_info += 1;
+            return;
+        }
+        let Some(shape) = shape else { return };
+        if shape.is_default_package() {
+            self.default_package += 1;
+        }
+        if shape.is_annotation_only() {
+            self.annotation_only += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Markdown 摘要行 (没有特殊形态的文件时返回空串)
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let parts: Vec<String> = [
+            ("默认包", self.default_package),
+            ("package-info", self.package_info),
+            ("仅注解类型", self.annotation_only),
+            ("BOM", self.bom),
+        ]
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(label, count)| format!("{label} × {count}"))
+        .collect();
+        format!("**特殊文件**: {} (均已分析)\n\n", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+    fn shape(code: &str) -> SourceShape {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(code, |tree| SourceShape::of(tree.root_node())).unwrap()
+    }
+
+    #[test]
+    fn test_shapes() {
+        let plain = shape("package com.shop;\nclass A {}");
+        assert!(!plain.is_default_package() && !plain.is_annotation_only());
+
+        assert!(shape("import java.util.List;\nclass A {}").is_default_package());
+        assert!(shape("package com.shop;\n@Retention(RUNTIME)\npublic @interface Audited { String value(); }").is_annotation_only());
+        assert!(!shape("package com.shop;\n@interface A {}\nclass B {}").is_annotation_only());
+
+        // package-info: 只有包级注解与 package 声明
+        let info = shape("@NonNullApi\npackage com.shop;\nimport org.springframework.lang.NonNullApi;\n");
+        assert!(info.has_package && info.types == 0);
+        assert!(!info.is_default_package());
+    }
+
+    #[test]
+    fn test_stats_record_and_render() {
+        let mut stats = ShapeStats::default();
+        assert_eq!(stats.render(), "");
+        stats.record("package-info.java", Some(SourceShape { has_package: true, ..Default::default() }), false);
+        stats.record("NoPackage.java", Some(SourceShape { types: 1, ..Default::default() }), true);
+        stats.record("Broken.java", None, false);
+        assert_eq!(stats, ShapeStats { default_package: 1, package_info: 1, annotation_only: 0, bom: 1 });
+        assert_eq!(stats.render(), "**特殊文件**: 默认包 × 1, package-info × 1, BOM × 1 (均已分析)\n\n");
+    }
+}
@@ -0,0 +1,412 @@
+//! 字符串字面量 SQL 的执行计划启发式检查
+//!
+//! 用 sqlparser 解析代码中的 SQL 字面量 (含 `"..." + "..."` 拼接与 `@Query`)，
+//! 在语法树上识别常见的执行计划问题:
+//!
+//! - UPDATE / DELETE 缺少 WHERE: 修改或删除全表
+//! - 笛卡尔积: `CROSS JOIN`、无 ON 的 JOIN、逗号连接的表之间没有等值连接条件
+//! - ORDER BY 无 LIMIT: 列表查询的排序结果集随数据增长无上限
+//! - WHERE 中对列使用函数: `DATE(created_at) = ?` 使列上的索引失效
+//!
+//! 无法解析的文本 (动态拼接的片段、方言特有语法) 一律不报告。
+
+use sqlparser::ast::{
+    BinaryOperator, Delete, Expr, FromTable, FunctionArg, FunctionArgExpr, FunctionArguments,
+    JoinConstraint, JoinOperator, Query, Select, SetExpr, Statement, TableFactor, TableWithJoins,
+};
+use sqlparser::dialect::{Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect};
+use sqlparser::parser::Parser;
+use tree_sitter::Node;
+
+/// 检查项 (每项对应一条规则)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlCheck {
+    /// SQL_UPDATE_NO_WHERE
+    MissingWhere,
+    /// SQL_CARTESIAN_JOIN
+    CartesianJoin,
+    /// SQL_ORDER_BY_NO_LIMIT
+    OrderByNoLimit,
+    /// SQL_FUNCTION_ON_COLUMN
+    FunctionOnColumn,
+}
+
+/// 检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlFinding {
+    pub check: SqlCheck,
+    /// 附加到 Issue context 的说明
+    pub detail: String,
+}
+
+/// 解析 SQL 并执行全部检查 (解析失败时返回空)
+pub fn analyze(sql: &str) -> Vec<SqlFinding> {
+    let dialects: [&dyn Dialect; 3] = [&GenericDialect {}, &MySqlDialect {}, &PostgreSqlDialect {}];
+    let Some(statements) = dialects.iter().find_map(|d| Parser::parse_sql(*d, sql).ok()) else {
+        return Vec::new();
+    };
+
+    let mut findings = Vec::new();
+    for statement in &statements {
+        match statement {
+            Statement::Query(query) => {
+                check_order_by(query, &mut findings);
+                check_query(query, &mut findings);
+            }
+            Statement::Update { table, selection, from, .. } => {
+                match selection {
+                    Some(selection) => check_where(selection, &mut findings),
+                    None => findings.push(SqlFinding {
+                        check: SqlCheck::MissingWhere,
+                        detail: format!("UPDATE {table} 无 WHERE 条件，将修改全表"),
+                    }),
+                }
+                if let Some(from) = from {
+                    check_tables(std::slice::from_ref(from), selection.as_ref(), &mut findings);
+                }
+            }
+            Statement::Delete(delete) => check_delete(delete, &mut findings),
+            _ => {}
+        }
+    }
+    findings
+}
+
+/// 代码中以 `node` (字符串字面量) 开头的 SQL 文本
+///
+/// `"SELECT ... " + "FROM ..."` 拼接的字面量合并为一条；拼接中的变量替换为 `?`
+/// (替换后通常无法解析，即不报告)。只从最左侧的字面量取一次，避免重复报告；
+/// `sb.append(...)` 与 `sql += ...` 增量构建的片段不完整，返回 None。
+pub fn sql_text(node: Node, code: &str) -> Option<String> {
+    let mut top = node;
+    while let Some(parent) = top.parent().filter(|p| is_concat(*p, code)) {
+        top = parent;
+    }
+
+    let mut parts = Vec::new();
+    collect_concat(top, code, &mut parts);
+    if parts.first().is_none_or(|(id, _)| *id != Some(node.id())) {
+        return None;
+    }
+    if let Some(parent) = top.parent() {
+        let incremental = match parent.kind() {
+            "argument_list" => parent.parent()
+                .and_then(|call| call.child_by_field_name("name"))
+                .is_some_and(|name| name.utf8_text(code.as_bytes()) == Ok("append")),
+            "assignment_expression" => parent.child_by_field_name("operator")
+                .is_some_and(|op| op.utf8_text(code.as_bytes()) == Ok("+=")),
+            _ => false,
+        };
+        if incremental {
+            return None;
+        }
+    }
+    Some(parts.into_iter().map(|(_, text)| text).collect::<Vec<_>>().join(""))
+}
+
+fn is_concat(node: Node, code: &str) -> bool {
+    node.kind() == "binary_expression"
+        && node.child_by_field_name("operator").is_some_and(|op| op.utf8_text(code.as_bytes()) == Ok("+"))
+}
+
+/// 拼接表达式的叶子: (字面量节点 id, 文本)
+fn collect_concat(node: Node, code: &str, parts: &mut Vec<(Option<usize>, String)>) {
+    if is_concat(node, code) {
+        for field in ["left", "right"] {
+            if let Some(child) = node.child_by_field_name(field) {
+                collect_concat(child, code, parts);
+            }
+        }
+    } else if node.kind() == "string_literal" {
+        let raw = node.utf8_text(code.as_bytes()).unwrap_or("");
+        parts.push((Some(node.id()), unquote(raw)));
+    } else {
+        parts.push((None, " ? ".to_string()));
+    }
+}
+
+/// 去掉引号 (含文本块 `"""`) 并把常见转义还原为空白
+fn unquote(raw: &str) -> String {
+    let quote = if raw.starts_with("\"\"\"") { "\"\"\"" } else { "\"" };
+    let body = raw.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)).unwrap_or(raw);
+    body.replace("\\n", " ").replace("\\t", " ").replace("\\\"", "\"")
+}
+
+fn check_delete(delete: &Delete, findings: &mut Vec<SqlFinding>) {
+    let tables = match &delete.from {
+        FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables) => tables,
+    };
+    match &delete.selection {
+        Some(selection) => check_where(selection, findings),
+        None if delete.using.is_none() => {
+            let names: Vec<String> = tables.iter().map(|t| t.relation.to_string()).collect();
+            findings.push(SqlFinding {
+                check: SqlCheck::MissingWhere,
+                detail: format!("DELETE FROM {} 无 WHERE 条件，将删除全表", names.join(", ")),
+            });
+        }
+        None => {}
+    }
+    check_tables(tables, delete.selection.as_ref(), findings);
+}
+
+/// 顶层查询有 ORDER BY 但没有 LIMIT / FETCH / TOP
+fn check_order_by(query: &Query, findings: &mut Vec<SqlFinding>) {
+    let Some(order_by) = query.order_by.as_ref().filter(|o| !o.exprs.is_empty()) else { return };
+    let limited = query.limit.is_some()
+        || query.fetch.is_some()
+        || matches!(query.body.as_ref(), SetExpr::Select(select) if select.top.is_some());
+    if limited {
+        return;
+    }
+    let columns: Vec<String> = order_by.exprs.iter().map(|e| e.expr.to_string()).collect();
+    findings.push(SqlFinding {
+        check: SqlCheck::OrderByNoLimit,
+        detail: format!("ORDER BY {} 无 LIMIT/FETCH，排序结果集随数据增长无上限", columns.join(", ")),
+    });
+}
+
+fn check_query(query: &Query, findings: &mut Vec<SqlFinding>) {
+    check_set_expr(&query.body, findings);
+}
+
+fn check_set_expr(body: &SetExpr, findings: &mut Vec<SqlFinding>) {
+    match body {
+        SetExpr::Select(select) => check_select(select, findings),
+        SetExpr::Query(query) => check_query(query, findings),
+        SetExpr::SetOperation { left, right, .. } => {
+            check_set_expr(left, findings);
+            check_set_expr(right, findings);
+        }
+        _ => {}
+    }
+}
+
+fn check_select(select: &Select, findings: &mut Vec<SqlFinding>) {
+    check_tables(&select.from, select.selection.as_ref(), findings);
+    if let Some(selection) = &select.selection {
+        check_where(selection, findings);
+    }
+}
+
+/// 笛卡尔积: 显式 JOIN 缺少连接条件，或逗号连接的表之间没有等值条件
+fn check_tables(from: &[TableWithJoins], selection: Option<&Expr>, findings: &mut Vec<SqlFinding>) {
+    // 逗号连接的每一项为一组；组内 JOIN 的别名归入同组
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let labels: Vec<String> = from.iter().map(|item| item.relation.to_string()).collect();
+    for item in from {
+        let mut names = vec![qualifier(&item.relation)];
+        for join in &item.joins {
+            let constraint = match &join.join_operator {
+                JoinOperator::CrossJoin => None,
+                JoinOperator::Inner(c) | JoinOperator::LeftOuter(c)
+                | JoinOperator::RightOuter(c) | JoinOperator::FullOuter(c) => Some(c),
+                _ => Some(&JoinConstraint::Natural),
+            };
+            // JPQL 关联路径 (JOIN o.items i) 由映射提供连接条件
+            let association = matches!(&join.relation, TableFactor::Table { name, .. }
+                if name.0.len() > 1 && names.iter().any(|n| n.eq_ignore_ascii_case(&name.0[0].value)));
+            if !association && matches!(constraint, None | Some(JoinConstraint::None)) {
+                findings.push(SqlFinding {
+                    check: SqlCheck::CartesianJoin,
+                    detail: format!("{} 与 {} 之间没有连接条件 (笛卡尔积)", item.relation, join.relation),
+                });
+            }
+            names.push(qualifier(&join.relation));
+        }
+        groups.push(names);
+    }
+    if groups.len() < 2 {
+        return;
+    }
+
+    // 等值连接条件合并分组 (并查集)；出现无法归属的列比较时保守地不报告
+    let mut parent: Vec<usize> = (0..groups.len()).collect();
+    let mut unknown = false;
+    if let Some(selection) = selection {
+        let mut pairs = Vec::new();
+        join_pairs(selection, &mut pairs, &mut unknown);
+        let group_of = |q: &str| groups.iter().position(|names| names.iter().any(|n| n.eq_ignore_ascii_case(q)));
+        for (a, b) in pairs {
+            match (group_of(&a), group_of(&b)) {
+                (Some(a), Some(b)) => {
+                    let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+                    parent[ra] = rb;
+                }
+                _ => unknown = true,
+            }
+        }
+    }
+    if unknown {
+        return;
+    }
+    let root = find(&mut parent, 0);
+    let detached: Vec<&str> = (1..groups.len())
+        .filter(|i| find(&mut parent, *i) != root)
+        .map(|i| labels[i].as_str())
+        .collect();
+    if !detached.is_empty() {
+        findings.push(SqlFinding {
+            check: SqlCheck::CartesianJoin,
+            detail: format!("{} 与 {} 之间没有连接条件 (笛卡尔积)", labels[0], detached.join(", ")),
+        });
+    }
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+/// 表的引用名: 别名优先，否则取表名最后一段
+fn qualifier(relation: &TableFactor) -> String {
+    match relation {
+        TableFactor::Table { name, alias, .. } => alias.as_ref()
+            .map(|a| a.name.value.clone())
+            .or_else(|| name.0.last().map(|i| i.value.clone()))
+            .unwrap_or_default(),
+        TableFactor::Derived { alias, .. } => alias.as_ref().map(|a| a.name.value.clone()).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// WHERE 中 `a.x = b.y` 形式的连接条件 (限定名对)
+fn join_pairs(expr: &Expr, pairs: &mut Vec<(String, String)>, unknown: &mut bool) {
+    match expr {
+        Expr::BinaryOp { left, op: BinaryOperator::And | BinaryOperator::Or, right } => {
+            join_pairs(left, pairs, unknown);
+            join_pairs(right, pairs, unknown);
+        }
+        Expr::Nested(inner) => join_pairs(inner, pairs, unknown),
+        Expr::BinaryOp { left, op: BinaryOperator::Eq, right } => match (left.as_ref(), right.as_ref()) {
+            // 同一张表内的列比较 (a.x = a.y) 不是连接条件
+            (Expr::CompoundIdentifier(a), Expr::CompoundIdentifier(b))
+                if a.len() > 1 && b.len() > 1 && !a[0].value.eq_ignore_ascii_case(&b[0].value) =>
+            {
+                pairs.push((a[0].value.clone(), b[0].value.clone()));
+            }
+            (Expr::CompoundIdentifier(a), Expr::CompoundIdentifier(b)) if a.len() > 1 && b.len() > 1 => {}
+            (Expr::Identifier(_) | Expr::CompoundIdentifier(_), Expr::Identifier(_) | Expr::CompoundIdentifier(_)) => {
+                *unknown = true;
+            }
+            _ => {}
+        },
+        // 子查询 / EXISTS 中的关联条件无法简单归属
+        Expr::Exists { .. } | Expr::InSubquery { .. } => *unknown = true,
+        _ => {}
+    }
+}
+
+/// WHERE 中对列使用函数的比较 (每条 SQL 只报告第一处)
+fn check_where(selection: &Expr, findings: &mut Vec<SqlFinding>) {
+    if findings.iter().any(|f| f.check == SqlCheck::FunctionOnColumn) {
+        return;
+    }
+    if let Some(wrapped) = function_comparison(selection) {
+        findings.push(SqlFinding {
+            check: SqlCheck::FunctionOnColumn,
+            detail: format!("WHERE 中 {wrapped} 对列使用函数，列上的索引无法使用"),
+        });
+    }
+}
+
+fn function_comparison(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::BinaryOp { left, op: BinaryOperator::And | BinaryOperator::Or, right } => {
+            function_comparison(left).or_else(|| function_comparison(right))
+        }
+        Expr::Nested(inner) | Expr::UnaryOp { expr: inner, .. } => function_comparison(inner),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq | BinaryOperator::NotEq | BinaryOperator::Lt
+                | BinaryOperator::LtEq | BinaryOperator::Gt | BinaryOperator::GtEq,
+            right,
+        } => [left, right].into_iter().find(|side| wraps_column(side)).map(|side| side.to_string()),
+        Expr::Like { expr, .. } | Expr::ILike { expr, .. } | Expr::Between { expr, .. } | Expr::InList { expr, .. }
+            if wraps_column(expr) => Some(expr.to_string()),
+        _ => None,
+    }
+}
+
+/// 表达式是否为作用在列上的函数 (`LOWER(email)`、`CAST(id AS CHAR)`、`DATE(o.created_at)`)
+fn wraps_column(expr: &Expr) -> bool {
+    match expr {
+        Expr::Function(function) => match &function.args {
+            FunctionArguments::List(list) => list.args.iter().any(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) | FunctionArg::Named { arg: FunctionArgExpr::Expr(e), .. } => {
+                    is_column(e) || wraps_column(e)
+                }
+                _ => false,
+            }),
+            _ => false,
+        },
+        Expr::Cast { expr, .. } | Expr::Extract { expr, .. } | Expr::Substring { expr, .. }
+        | Expr::Trim { expr, .. } | Expr::Ceil { expr, .. } | Expr::Floor { expr, .. } => {
+            is_column(expr) || wraps_column(expr)
+        }
+        _ => false,
+    }
+}
+
+fn is_column(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(sql: &str) -> Vec<SqlCheck> {
+        analyze(sql).into_iter().map(|f| f.check).collect()
+    }
+
+    #[test]
+    fn test_missing_where() {
+        assert_eq!(checks("UPDATE orders SET status = 'CLOSED'"), vec![SqlCheck::MissingWhere]);
+        assert_eq!(checks("DELETE FROM audit_log"), vec![SqlCheck::MissingWhere]);
+        assert!(checks("UPDATE orders SET status = ? WHERE id = ?").is_empty());
+        assert!(checks("DELETE FROM audit_log WHERE created_at < :cutoff").is_empty());
+    }
+
+    #[test]
+    fn test_cartesian_join() {
+        assert_eq!(checks("SELECT o.id, u.name FROM orders o, users u WHERE o.status = ?"), vec![SqlCheck::CartesianJoin]);
+        assert_eq!(checks("SELECT * FROM orders CROSS JOIN users"), vec![SqlCheck::CartesianJoin]);
+        assert!(checks("SELECT o.id FROM orders o, users u WHERE o.user_id = u.id AND o.status = ?").is_empty());
+        assert!(checks("SELECT o.id FROM orders o JOIN users u ON o.user_id = u.id").is_empty());
+        // 无法归属的列比较: 保守不报
+        assert!(checks("SELECT * FROM orders, users WHERE user_id = id").is_empty());
+        // JPQL 关联路径
+        assert!(checks("SELECT o FROM Order o JOIN o.items i WHERE i.sku = :sku").is_empty());
+    }
+
+    #[test]
+    fn test_order_by_without_limit() {
+        let findings = analyze("SELECT id, name FROM users WHERE status = ? ORDER BY created_at DESC");
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].detail.contains("created_at"), "{}", findings[0].detail);
+        assert!(checks("SELECT id FROM users ORDER BY id LIMIT 20").is_empty());
+        assert!(checks("SELECT id FROM users ORDER BY id OFFSET 0 ROWS FETCH NEXT 20 ROWS ONLY").is_empty());
+    }
+
+    #[test]
+    fn test_function_on_column() {
+        let findings = analyze("SELECT id FROM orders WHERE DATE(created_at) = ? AND status = ?");
+        assert_eq!(findings.iter().map(|f| f.check).collect::<Vec<_>>(), vec![SqlCheck::FunctionOnColumn]);
+        assert!(findings[0].detail.contains("DATE(created_at)"), "{}", findings[0].detail);
+        assert_eq!(checks("UPDATE users SET x = 1 WHERE LOWER(email) = ?"), vec![SqlCheck::FunctionOnColumn]);
+        assert_eq!(checks("SELECT id FROM t WHERE CAST(code AS CHAR) LIKE ?"), vec![SqlCheck::FunctionOnColumn]);
+        // 函数作用在参数上不影响索引
+        assert!(checks("SELECT id FROM users WHERE email = LOWER(?) AND created_at > NOW()").is_empty());
+    }
+
+    #[test]
+    fn test_unparseable_sql_is_ignored() {
+        assert!(analyze("DELETE FROM  ? ").is_empty());
+        assert!(analyze("not sql at all").is_empty());
+    }
+}
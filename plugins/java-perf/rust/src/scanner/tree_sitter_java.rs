@@ -1,12 +1,49 @@
 use super::{CodeAnalyzer, Issue, Severity};
 use super::rule_handlers::RuleContext;  // v9.3: 导入 RuleContext
+use super::issue_builder::RuleMeta;
+use super::parse_health::{ParseHealth, ParseStatus};
+use super::grammar::{self, QuerySet};
+use super::node_walk::NodeWalk;
+use crate::diagnostics::{self, Diagnostic, DiagnosticKind};
+use crate::encoding::strip_bom;
+use crate::shared_path::FilePath;
 use std::path::Path;
 use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use anyhow::{Result, anyhow};
-use tree_sitter::{Parser, Query, QueryCursor, Tree};
-use crate::symbol_table::{TypeInfo, VarBinding, ImportIndex}; // Import TypeInfo and ImportIndex
+use tree_sitter::{Node, Parser, Query, QueryCursor, Tree};
+use crate::symbol_table::{TypeInfo, VarBinding, ImportIndex, StringConstant, MethodInfo, ParamInfo}; // Import TypeInfo and ImportIndex
 use crate::symbol_table::SymbolTable;
-use crate::rules::suppression::SuppressionContext;
+use crate::rules::suppression::{SuppressionContext, SuppressionSource};
+use crate::rules::custom::ExecLimits;
+use crate::rules::registry::{reported_id, RuleRegistry};
+use crate::rules::timing::{RuleCost, RuleTiming, TimingProfile};
+
+/// 被抑制的问题及其抑制来源
+pub type SuppressedIssue = (Issue, SuppressionSource);
+
+/// 单个 Query 匹配的评估过程 (`scan --explain`)
+#[derive(Debug)]
+pub struct MatchTrace {
+    /// (捕获名, 源码文本, 起始行)
+    pub captures: Vec<(String, String, usize)>,
+    /// 捕获中的调用接收者与标识符 (供符号解析说明)
+    pub receivers: Vec<String>,
+    /// 处理器输出 (None = 处理器判定不报告)
+    pub issue: Option<Issue>,
+}
+
+/// 单条规则在指定行的评估过程
+#[derive(Debug)]
+pub struct RuleTrace {
+    pub rule: &'static str,
+    pub severity_override: Option<Severity>,
+    /// 落在目标行的匹配 (报告行或捕获起始行)
+    pub matches: Vec<MatchTrace>,
+    /// 本文件中其它匹配所在行
+    pub other_lines: Vec<usize>,
+}
 
 // ============================================================================
 // P0 优化: thread_local Parser 复用
@@ -24,7 +61,7 @@ thread_local! {
 }
 
 /// 获取或初始化线程本地 Parser
-fn with_parser<F, R>(language: &tree_sitter::Language, f: F) -> Result<R>
+fn with_parser<F, R>(language: &tree_sitter::Language, timeout: Option<Duration>, f: F) -> Result<R>
 where
     F: FnOnce(&mut Parser) -> Result<R>,
 {
@@ -40,24 +77,53 @@ where
         }
 
         let parser = parser_opt.as_mut().unwrap();
+        // 线程局部 Parser 为各分析器共用，每次按调用方设置超时 (0 为不限制)；
+        // 超时中断的解析会保留进度，需 reset 后再解析下一个文件
+        parser.reset();
+        parser.set_timeout_micros(timeout.map_or(0, |t| t.as_micros() as u64));
         f(parser)
     })
 }
 
+/// 固定查询的编译函数 (结构、调用点等非规则查询)
+type FixedQueryFn = fn(&tree_sitter::Language, &QuerySet) -> Result<Query>;
+
 /// 预编译的规则 (v9.3: 集成 RuleHandler)
 struct CompiledRule {
     id: &'static str,
     severity: Severity,
+    /// 技术栈强制的严重级别 (覆盖 handler 的动态判定)
+    severity_override: Option<Severity>,
     query: Query,
+    /// Query 原文 (估计执行代价，见 rules::timing)
+    source: &'static str,
     description: &'static str,
     /// v9.3: 规则处理器 (替代 match rule.id 分支)
     handler: Box<dyn super::rule_handlers::RuleHandler>,
+    /// 不可信规则包的执行限制 (见 rules::pack)
+    limits: Option<ExecLimits>,
+    /// 已执行的文件数与累计耗时 (纳秒)，只在 `--rule-timings` 时累计，见 rules::timing
+    files: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl CompiledRule {
+    /// 新建查询游标 (应用匹配数上限)
+    fn cursor(&self) -> QueryCursor {
+        let mut cursor = QueryCursor::new();
+        if let Some(limits) = self.limits {
+            cursor.set_match_limit(limits.match_limit);
+        }
+        cursor
+    }
 }
 
 pub struct JavaTreeSitterAnalyzer {
     language: tree_sitter::Language,
     /// 预编译的查询 (在 new() 时编译一次)
     compiled_rules: Vec<CompiledRule>,
+    /// 规则执行顺序 (compiled_rules 下标，按代价从低到高，见 rules::timing)
+    order: Vec<usize>,
     /// 结构提取查询 (用于 Phase 1)
     structure_query: Query,
     /// 调用点提取查询 (用于 CallGraph 构建) - v9.4
@@ -66,628 +132,160 @@ pub struct JavaTreeSitterAnalyzer {
     import_query: Query,
     /// package 声明查询 (用于 FQN 构建) - v9.6
     package_query: Query,
+    /// 字符串常量查询 (用于 SQL 常量解析)
+    constant_query: Query,
+    /// 方法/构造器声明查询 (用于方法签名与返回类型)
+    method_query: Query,
+    /// 单文件解析超时 (`--safe-mode`)，超时视为解析失败
+    parse_timeout: Option<Duration>,
+    /// 累计各规则耗时 (`--rule-timings`)
+    record_timings: bool,
 }
 
 impl JavaTreeSitterAnalyzer {
     pub fn new() -> Result<Self> {
-        let language = tree_sitter_java::language();
+        Self::with_registry(&RuleRegistry::default())
+    }
+
+    /// 使用指定规则注册表创建分析器 (如按 DetectedStack 调整后的注册表)
+    pub fn with_registry(registry: &RuleRegistry) -> Result<Self> {
+        let language = grammar::language();
+        let query_set = grammar::active();
         
         // 预编译所有查询
-        let compiled_rules = Self::compile_rules(&language)?;
-        let structure_query = Self::compile_structure_query(&language)?;
-        let call_site_query = Self::compile_call_site_query(&language)?; // v9.4: 调用点提取
-        let import_query = Self::compile_import_query(&language)?;       // v9.5: import 解析
-        let package_query = Self::compile_package_query(&language)?;     // v9.6: package 声明
+        let compiled_rules = Self::compile_rules(&language, query_set, registry)?;
+        let structure_query = Self::compile_structure_query(&language, query_set)?;
+        let call_site_query = Self::compile_call_site_query(&language, query_set)?; // v9.4: 调用点提取
+        let import_query = Self::compile_import_query(&language, query_set)?;       // v9.5: import 解析
+        let package_query = Self::compile_package_query(&language, query_set)?;     // v9.6: package 声明
+        let constant_query = Self::compile_constant_query(&language, query_set)?;
+        let method_query = Self::compile_method_query(&language, query_set)?;
         
-        Ok(Self {
+        let mut analyzer = Self {
             language,
+            order: Vec::new(),
             compiled_rules,
             structure_query,
             call_site_query,
             import_query,
             package_query,
-        })
+            constant_query,
+            method_query,
+            parse_timeout: None,
+            record_timings: false,
+        };
+        analyzer.order_rules(Some(&TimingProfile::default()));
+        Ok(analyzer)
     }
 
-    /// 编译规则查询 (只在初始化时调用一次)
-    fn compile_rules(language: &tree_sitter::Language) -> Result<Vec<CompiledRule>> {
-        let rule_defs = vec![
-            // 规则1: N_PLUS_ONE - for 循环内的调用
-            ("N_PLUS_ONE", Severity::P0, r#"
-                (for_statement
-                    body: (block
-                        (expression_statement
-                            (method_invocation
-                                name: (identifier) @method_name
-                            ) @call
-                        )
-                    )
-                )
-            "#, "for 循环内调用方法 (可能是 N+1 问题)"),
-            
-            // 规则1b: N_PLUS_ONE_WHILE - while 循环内的调用
-            ("N_PLUS_ONE_WHILE", Severity::P0, r#"
-                (while_statement
-                    body: (block
-                        (expression_statement
-                            (method_invocation
-                                name: (identifier) @method_name
-                            ) @call
-                        )
-                    )
-                )
-            "#, "while 循环内调用方法 (可能是 N+1 问题)"),
-            
-            // 规则1c: N_PLUS_ONE_FOREACH - 增强型 for 循环内的调用
-            ("N_PLUS_ONE_FOREACH", Severity::P0, r#"
-                (enhanced_for_statement
-                    body: (block
-                        (expression_statement
-                            (method_invocation
-                                name: (identifier) @method_name
-                            ) @call
-                        )
-                    )
-                )
-            "#, "foreach 循环内调用方法 (可能是 N+1 问题)"),
-            
-            // 规则2: NESTED_LOOP - for 嵌套 for
-            ("NESTED_LOOP", Severity::P0, r#"
-                (for_statement
-                    body: (block
-                        (for_statement) @inner_loop
-                    )
-                )
-            "#, "嵌套 for 循环 (可能导致 O(N^2) 复杂度)"),
-            
-            // 规则2b: NESTED_LOOP_FOREACH - for 嵌套 enhanced_for 或反之
-            ("NESTED_LOOP_MIXED", Severity::P0, r#"
-                [
-                    (for_statement body: (block (enhanced_for_statement) @inner_loop))
-                    (enhanced_for_statement body: (block (for_statement) @inner_loop))
-                    (enhanced_for_statement body: (block (enhanced_for_statement) @inner_loop))
-                ]
-            "#, "嵌套循环 (可能导致 O(N^2) 复杂度)"),
-            
-            // 规则3: SYNC_METHOD (方法级同步)
-            ("SYNC_METHOD", Severity::P0, r#"
-                (method_declaration
-                    (modifiers) @mods
-                )
-            "#, "Synchronized 方法级锁 (建议改用细粒度锁)"),
-            
-            // 规则4: THREADLOCAL_LEAK (P0)
-            ("THREADLOCAL_LEAK", Severity::P0, r#"
-                (method_invocation
-                    object: (identifier) @var_name
-                    name: (identifier) @method
-                    (#eq? @method "set")
-                ) @set_call
-            "#, "ThreadLocal.set() 后未在同一方法内调用 remove()"),
-            
-            // 规则5: STREAM_RESOURCE_LEAK - try 块内创建流但未在 finally 中关闭
-            ("STREAM_RESOURCE_LEAK", Severity::P1, r#"
-                (try_statement
-                    body: (block
-                        (local_variable_declaration
-                            type: (_) @type_name
-                            declarator: (variable_declarator
-                                name: (identifier) @var_name
-                                value: (object_creation_expression) @creation
-                            )
-                        )
-                    )
-                ) @try_block
-            "#, "try 块内创建资源，请确保在 finally 中关闭或使用 try-with-resources"),
-            
-            // 规则6: SLEEP_IN_LOCK - synchronized 块内调用 sleep (P0)
-            ("SLEEP_IN_LOCK", Severity::P0, r#"
-                (synchronized_statement
-                    body: (block
-                        (expression_statement
-                            (method_invocation
-                                object: (identifier) @class_name
-                                name: (identifier) @method_name
-                                (#eq? @class_name "Thread")
-                                (#eq? @method_name "sleep")
-                            )
-                        )
-                    )
-                ) @sync_block
-            "#, "synchronized 块内调用 Thread.sleep()，持锁睡眠导致其他线程阻塞"),
-            
-            // 规则7: LOCK_METHOD_CALL - 检测 ReentrantLock.lock() 调用 (P0)
-            ("LOCK_METHOD_CALL", Severity::P0, r#"
-                (method_invocation
-                    object: (identifier) @lock_var
-                    name: (identifier) @method
-                    (#eq? @method "lock")
-                ) @lock_call
-            "#, "ReentrantLock.lock() 调用，请确保 unlock() 在 finally 块中"),
-            
-            // ====== v7.0 AST 迁移规则 ======
-            
-            // 规则8: @Async 无参数 (使用默认线程池)
-            ("ASYNC_DEFAULT_POOL", Severity::P1, r#"
-                (method_declaration
-                    (modifiers
-                        (marker_annotation
-                            name: (identifier) @ann_name
-                            (#eq? @ann_name "Async")
-                        )
-                    )
-                ) @method
-            "#, "@Async 未指定线程池，使用默认 SimpleAsyncTaskExecutor"),
-            
-            // 规则9: @Scheduled(fixedRate) 任务堆积风险
-            ("SCHEDULED_FIXED_RATE", Severity::P1, r#"
-                (method_declaration
-                    (modifiers
-                        (annotation
-                            name: (identifier) @ann_name
-                            arguments: (annotation_argument_list
-                                (element_value_pair
-                                    key: (identifier) @key
-                                    (#eq? @key "fixedRate")
-                                )
-                            )
-                            (#eq? @ann_name "Scheduled")
-                        )
-                    )
-                ) @method
-            "#, "@Scheduled(fixedRate) 任务可能堆积，考虑使用 fixedDelay"),
-            
-            // 规则10: @Autowired 字段注入
-            ("AUTOWIRED_FIELD", Severity::P1, r#"
-                (field_declaration
-                    (modifiers
-                        (marker_annotation
-                            name: (identifier) @ann_name
-                            (#eq? @ann_name "Autowired")
-                        )
-                    )
-                ) @field
-            "#, "@Autowired 字段注入不利于测试，建议使用构造器注入"),
-            
-            // 规则11: Flux/Mono.block() 阻塞调用
-            ("FLUX_BLOCK", Severity::P0, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    (#match? @method_name "^(block|blockFirst|blockLast)$")
-                ) @call
-            "#, "Flux/Mono.block() 阻塞调用，可能导致死锁"),
-            
-            // 规则12: subscribe() 检测 - 需要检查参数数量
-            ("SUBSCRIBE_NO_ERROR", Severity::P1, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    arguments: (argument_list) @args
-                    (#eq? @method_name "subscribe")
-                ) @call
-            "#, "subscribe() 可能未处理 error，建议添加 error consumer"),
-            
-            // 规则13: collectList() 可能导致 OOM
-            ("FLUX_COLLECT_LIST", Severity::P1, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    (#eq? @method_name "collectList")
-                ) @call
-            "#, "collectList() 可能导致 OOM，考虑使用 buffer 或 window"),
-            
-            // 规则14: parallel() 未指定 runOn
-            ("PARALLEL_NO_RUN_ON", Severity::P1, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    (#eq? @method_name "parallel")
-                ) @call
-            "#, "parallel() 建议配合 runOn(Schedulers.parallel()) 使用"),
-            
-            // ====== 更多 AST 迁移规则 (第二批) ======
-            
-            // 规则15: 重写 finalize() 方法 - 简化查询，只匹配方法名
-            ("FINALIZE_OVERRIDE", Severity::P0, r#"
-                (method_declaration
-                    type: (void_type)
-                    name: (identifier) @method_name
-                    (#eq? @method_name "finalize")
-                ) @method
-            "#, "重写 finalize() 已废弃，影响 GC 性能"),
-            
-            // 规则16: String.intern() 调用
-            ("STRING_INTERN", Severity::P1, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    (#eq? @method_name "intern")
-                ) @call
-            "#, "String.intern() 可能导致元空间溢出"),
-            
-            // 规则17: new SoftReference 使用
-            ("SOFT_REFERENCE", Severity::P1, r#"
-                (object_creation_expression
-                    type: (generic_type
-                        (type_identifier) @type_name
-                        (#eq? @type_name "SoftReference")
-                    )
-                ) @creation
-            "#, "SoftReference 可能导致 Full GC 时大量对象被回收"),
-            
-            // 规则18: 循环内创建对象
-            ("OBJECT_IN_LOOP", Severity::P1, r#"
-                [
-                    (for_statement body: (block (local_variable_declaration declarator: (variable_declarator value: (object_creation_expression) @creation))))
-                    (enhanced_for_statement body: (block (local_variable_declaration declarator: (variable_declarator value: (object_creation_expression) @creation))))
-                    (while_statement body: (block (local_variable_declaration declarator: (variable_declarator value: (object_creation_expression) @creation))))
-                ]
-            "#, "循环内创建对象，可能导致 GC 压力"),
-            
-            // 规则19: @Cacheable 未指定 key
-            ("CACHEABLE_NO_KEY", Severity::P1, r#"
-                (method_declaration
-                    (modifiers
-                        (annotation
-                            name: (identifier) @ann_name
-                            arguments: (annotation_argument_list) @args
-                            (#eq? @ann_name "Cacheable")
-                        )
-                    )
-                ) @method
-            "#, "@Cacheable 建议明确指定 key 避免缓存冲突"),
-            
-            // 规则20: @Transactional(propagation = REQUIRES_NEW)
-            ("TRANSACTIONAL_REQUIRES_NEW", Severity::P1, r#"
-                (method_declaration
-                    (modifiers
-                        (annotation
-                            name: (identifier) @ann_name
-                            arguments: (annotation_argument_list
-                                (element_value_pair
-                                    key: (identifier) @key
-                                    value: (_) @value
-                                    (#eq? @key "propagation")
-                                )
-                            )
-                            (#eq? @ann_name "Transactional")
-                        )
-                    )
-                ) @method
-            "#, "@Transactional 事务传播设置，请确保理解嵌套事务行为"),
-            
-            // ====== 第三批 AST 迁移规则 ======
-            
-            // 规则21: Future.get() 无超时
-            ("FUTURE_GET_NO_TIMEOUT", Severity::P0, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    arguments: (argument_list) @args
-                    (#eq? @method_name "get")
-                ) @call
-            "#, "Future.get() 无超时参数，可能永久阻塞"),
-            
-            // 规则22: await()/acquire() 无超时
-            ("AWAIT_NO_TIMEOUT", Severity::P0, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    arguments: (argument_list) @args
-                    (#match? @method_name "^(await|acquire)$")
-                ) @call
-            "#, "await()/acquire() 无超时参数，可能永久阻塞"),
-            
-            // 规则23: CompletableFuture.join() 无超时
-            ("COMPLETABLE_JOIN", Severity::P1, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    (#eq? @method_name "join")
-                ) @call
-            "#, "CompletableFuture.join() 无超时，可能永久阻塞"),
-            
-            // 规则24: 日志字符串拼接
-            ("LOG_STRING_CONCAT", Severity::P1, r#"
-                (method_invocation
-                    object: (identifier) @obj
-                    name: (identifier) @method_name
-                    arguments: (argument_list
-                        (binary_expression
-                            operator: "+"
-                        ) @concat
-                    )
-                    (#match? @obj "^(log|logger|LOG|LOGGER)$")
-                    (#match? @method_name "^(debug|info|warn|error|trace)$")
-                ) @call
-            "#, "日志使用字符串拼接，建议使用占位符 log.info(\"x={}\", x)"),
-            
-            // 规则25: synchronized 代码块 (提醒检查范围 + Virtual Thread Pinning)
-            ("SYNC_BLOCK", Severity::P1, r#"
-                (synchronized_statement
-                    (parenthesized_expression) @lock_obj
-                    body: (block) @body
-                ) @sync
-            "#, "synchronized 代码块，请确保锁范围最小化。注意: JDK 21+ Virtual Threads 下会导致 Carrier Thread Pinning"),
-            
-            // 规则26: EmitterProcessor.create() 无界
-            ("EMITTER_UNBOUNDED", Severity::P0, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    arguments: (argument_list) @args
-                    (#eq? @class_name "EmitterProcessor")
-                    (#eq? @method_name "create")
-                ) @call
-            "#, "EmitterProcessor.create() 无界背压，可能导致 OOM"),
-            
-            // ====== 第四批 AST 迁移规则 (最终批次) ======
-            
-            // 规则27: Executors.newCachedThreadPool 等无界线程池
-            ("UNBOUNDED_POOL", Severity::P0, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    (#eq? @class_name "Executors")
-                    (#match? @method_name "^(newCachedThreadPool|newScheduledThreadPool|newSingleThreadExecutor)$")
-                ) @call
-            "#, "Executors 无界线程池，建议使用 ThreadPoolExecutor 配置有界队列"),
-            
-            // 规则28: 空 catch 块
-            ("EMPTY_CATCH", Severity::P0, r#"
-                (catch_clause
-                    body: (block) @body
-                ) @catch
-            "#, "catch 块可能为空或仅打印，请正确处理异常"),
-            
-            // 规则29: new FileInputStream/FileOutputStream
-            ("BLOCKING_IO", Severity::P1, r#"
-                (object_creation_expression
-                    type: (type_identifier) @type_name
-                    (#match? @type_name "^File(Input|Output)Stream$")
-                ) @creation
-            "#, "FileInputStream/FileOutputStream 同步阻塞 IO，考虑使用 NIO"),
-            
-            // 规则30: AtomicInteger/AtomicLong 高竞争
-            ("ATOMIC_SPIN", Severity::P1, r#"
-                (object_creation_expression
-                    type: (type_identifier) @type_name
-                    (#match? @type_name "^Atomic(Integer|Long)$")
-                ) @creation
-            "#, "AtomicInteger/Long 高竞争时考虑使用 LongAdder"),
-            
-            // 规则31: Sinks.many() 无背压
-            ("SINKS_MANY", Severity::P1, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    (#eq? @class_name "Sinks")
-                    (#eq? @method_name "many")
-                ) @call
-            "#, "Sinks.many() 需要配置背压策略"),
-            
-            // 规则32: Caffeine/CacheBuilder.newBuilder()
-            ("CACHE_NO_EXPIRE", Severity::P1, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    (#match? @class_name "^(Caffeine|CacheBuilder)$")
-                    (#eq? @method_name "newBuilder")
-                ) @call
-            "#, "Cache.newBuilder() 请确保配置了过期策略和最大大小"),
-            
-            // 规则33: static Map/List/Set 无界缓存
-            ("STATIC_COLLECTION", Severity::P0, r#"
-                (field_declaration
-                    (modifiers) @mods
-                    type: (generic_type
-                        (type_identifier) @type_name
-                        (#match? @type_name "^(Map|HashMap|ConcurrentHashMap|List|ArrayList|Set|HashSet)$")
-                    )
-                ) @field
-            "#, "static 集合作为缓存需配置大小限制和过期策略"),
-            
-            // 规则34: DriverManager.getConnection 直连
-            ("DATASOURCE_NO_POOL", Severity::P1, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    (#eq? @class_name "DriverManager")
-                    (#eq? @method_name "getConnection")
-                ) @call
-            "#, "DriverManager.getConnection 直接获取连接，建议使用连接池"),
-            
-            // ====== 最终批次 AST 规则 ======
-            
-            // 规则35: 循环内字符串 += 拼接
-            ("STRING_CONCAT_LOOP", Severity::P1, r#"
-                [
-                    (for_statement body: (block (expression_statement (assignment_expression left: (_) @var operator: "+=" right: (_) @value)) @assign))
-                    (enhanced_for_statement body: (block (expression_statement (assignment_expression left: (_) @var operator: "+=" right: (_) @value)) @assign))
-                    (while_statement body: (block (expression_statement (assignment_expression left: (_) @var operator: "+=" right: (_) @value)) @assign))
-                ]
-            "#, "循环内使用 += 拼接字符串，建议使用 StringBuilder"),
-            
-            // 规则36: 大数组分配 new byte[1000000]
-            ("LARGE_ARRAY", Severity::P1, r#"
-                (array_creation_expression
-                    type: (integral_type) @type_name
-                    dimensions: (dimensions_expr
-                        (decimal_integer_literal) @size
-                    )
-                ) @creation
-            "#, "大数组分配可能导致 Full GC，考虑对象池或分块处理"),
-
-            // ====== v8.0 Java 现代化规则 ======
-            // 注意: VIRTUAL_THREAD_PINNING 已合并到 SYNC_BLOCK 规则中
-            //       避免同一位置重复报告
-
-            // 规则37: GraalVM Class.forName 检测
-            ("GRAALVM_CLASS_FORNAME", Severity::P1, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    (#eq? @class_name "Class")
-                    (#eq? @method_name "forName")
-                ) @call
-            "#, "[GraalVM] Class.forName 需要配置 reflect-config.json"),
-            
-            // 规则39: GraalVM Method.invoke 检测
-            ("GRAALVM_METHOD_INVOKE", Severity::P1, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    (#eq? @method_name "invoke")
-                ) @call
-            "#, "[GraalVM] Method.invoke 需要配置反射元数据"),
-            
-            // 规则40: GraalVM Proxy.newProxyInstance 检测
-            ("GRAALVM_PROXY", Severity::P1, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    (#eq? @class_name "Proxy")
-                    (#eq? @method_name "newProxyInstance")
-                ) @call
-            "#, "[GraalVM] Proxy.newProxyInstance 需要配置 proxy-config.json"),
-
-            // ====== v9.0 新增高价值规则 ======
-
-            // 规则41: Double-Checked Locking 反模式
-            ("DOUBLE_CHECKED_LOCKING", Severity::P0, r#"
-                (if_statement
-                    consequence: (block
-                        (synchronized_statement
-                            body: (block
-                                (if_statement) @inner_if
-                            )
-                        )
-                    )
-                ) @outer_if
-            "#, "Double-Checked Locking 反模式，需要 volatile 或使用 Holder 模式"),
-
-            // 规则42: CompletableFuture.get() 无超时
-            ("COMPLETABLE_GET_NO_TIMEOUT", Severity::P0, r#"
-                (method_invocation
-                    object: (_) @obj
-                    name: (identifier) @method_name
-                    arguments: (argument_list) @args
-                    (#eq? @method_name "get")
-                ) @call
-            "#, "CompletableFuture.get() 无超时参数，可能导致线程永久阻塞"),
-
-            // 规则43: @Transactional 自调用问题
-            ("TRANSACTION_SELF_CALL", Severity::P0, r#"
-                (method_declaration
-                    (modifiers
-                        (annotation
-                            name: (identifier) @ann_name
-                            (#eq? @ann_name "Transactional")
-                        )
-                    )
-                    name: (identifier) @method_name
-                    body: (block
-                        (expression_statement
-                            (method_invocation
-                                name: (identifier) @called_method
-                            )
-                        )
-                    )
-                ) @method
-            "#, "@Transactional 方法内部调用其他方法，可能导致事务失效（自调用问题）"),
-
-            // 规则44: volatile 数组元素访问
-            ("VOLATILE_ARRAY", Severity::P1, r#"
-                (field_declaration
-                    (modifiers) @mods
-                    type: (array_type) @array_type
-                ) @field
-            "#, "volatile 数组只保证引用可见性，元素操作不具备原子性"),
-
-            // 规则45: System.exit() 调用
-            ("SYSTEM_EXIT", Severity::P0, r#"
-                (method_invocation
-                    object: (identifier) @class_name
-                    name: (identifier) @method_name
-                    (#eq? @class_name "System")
-                    (#eq? @method_name "exit")
-                ) @call
-            "#, "System.exit() 会终止 JVM，不应在生产代码中使用"),
-
-            // 规则46: Runtime.getRuntime().exec() 命令注入风险
-            ("RUNTIME_EXEC", Severity::P0, r#"
-                (method_invocation
-                    name: (identifier) @method_name
-                    (#eq? @method_name "exec")
-                ) @call
-            "#, "Runtime.exec() 存在命令注入风险，请使用 ProcessBuilder"),
-
-            // 规则47: SimpleDateFormat 非线程安全
-            ("SIMPLE_DATE_FORMAT", Severity::P1, r#"
-                (object_creation_expression
-                    type: (type_identifier) @type_name
-                    (#eq? @type_name "SimpleDateFormat")
-                ) @creation
-            "#, "SimpleDateFormat 非线程安全，考虑使用 DateTimeFormatter (Java 8+)"),
-
-            // 规则48: Random 在多线程环境
-            ("RANDOM_SHARED", Severity::P1, r#"
-                (field_declaration
-                    (modifiers) @mods
-                    type: (type_identifier) @type_name
-                    (#eq? @type_name "Random")
-                ) @field
-            "#, "共享 Random 实例在高并发下性能差，考虑使用 ThreadLocalRandom"),
-
-            // ====== v9.1 从 Regex 迁移的 SQL 检测规则 ======
-
-            // 规则49: SELECT * 检测 - 匹配包含 "SELECT *" 的字符串字面量
-            ("SELECT_STAR", Severity::P1, r#"
-                (string_literal) @str
-                (#match? @str "SELECT\\s+\\*\\s+FROM")
-            "#, "SELECT * 查询，建议明确指定字段以减少数据传输"),
-
-            // 规则50: LIKE 前导通配符 - 匹配 LIKE '%xxx' 模式
-            ("LIKE_LEADING_WILDCARD", Severity::P0, r#"
-                (string_literal) @str
-                (#match? @str "LIKE\\s+['\"]%")
-            "#, "LIKE '%xxx' 前导通配符导致无法使用索引，引发全表扫描"),
-
-            // 规则51: HTTP 客户端使用检测 - 提醒检查超时配置
-            ("HTTP_CLIENT_TIMEOUT", Severity::P1, r#"
-                (method_invocation
-                    object: [
-                        (identifier) @obj
-                        (method_invocation) @obj
-                    ]
-                    name: (identifier) @method
-                    (#match? @obj "(HttpClient|RestTemplate|OkHttp|WebClient)")
-                ) @call
-            "#, "HTTP 客户端使用，请确认已配置连接超时和读取超时"),
-        ];
+    /// 设置单文件解析超时 (见 safe_mode 模块)
+    pub fn set_parse_timeout(&mut self, timeout: Duration) {
+        self.parse_timeout = Some(timeout);
+    }
+
+    /// 按耗时记录 (没有记录的规则按 Query 估计) 从低到高排列规则执行顺序；None 为注册表顺序
+    pub fn order_rules(&mut self, profile: Option<&TimingProfile>) {
+        let rules = &self.compiled_rules;
+        let mut order: Vec<usize> = (0..rules.len()).collect();
+        if let Some(profile) = profile {
+            let costs: Vec<u64> = rules.iter().map(|rule| profile.cost(rule.id, rule.source)).collect();
+            order.sort_by_key(|&i| costs[i]);
+        }
+        self.order = order;
+    }
+
+    /// 当前的规则执行顺序 (规则 ID)
+    pub fn rule_order(&self) -> Vec<&'static str> {
+        self.order.iter().map(|&i| self.compiled_rules[i].id).collect()
+    }
+
+    /// 累计各规则的执行耗时 (见 [`Self::rule_timings`])；关闭时分析过程不计时
+    pub fn set_record_timings(&mut self, enabled: bool) {
+        self.record_timings = enabled;
+    }
+
+    /// 各规则已累计的耗时 (注册表顺序，未开启计时时全为 0)
+    pub fn rule_timings(&self) -> Vec<RuleTiming> {
+        self.compiled_rules.iter()
+            .map(|rule| RuleTiming::new(rule.id, RuleCost {
+                files: rule.files.load(Ordering::Relaxed),
+                total_us: rule.nanos.load(Ordering::Relaxed) / 1000,
+            }))
+            .collect()
+    }
 
-        let mut compiled = Vec::with_capacity(rule_defs.len());
+    /// 编译规则查询 (只在初始化时调用一次)
+    ///
+    /// 只编译注册表中启用的规则，严重级别取注册表调整后的值；Query 按选用的查询集改写 (见 grammar 模块)
+    fn compile_rules(language: &tree_sitter::Language, query_set: &QuerySet, registry: &RuleRegistry) -> Result<Vec<CompiledRule>> {
+        let mut compiled = Vec::with_capacity(registry.rules().len());
 
-        for (id, severity, query_str, description) in rule_defs {
+        for def in registry.enabled_rules() {
+            let (id, severity, query_str, description) = (def.id, def.severity, def.query, def.description);
             // v9.3: 防御性编程 - 验证 Query 编译
-            let query = match Query::new(language, query_str) {
+            let query = match Query::new(language, &query_set.adapt(query_str)) {
                 Ok(q) => q,
                 Err(e) => {
-                    // 记录错误但不崩溃，跳过这个规则
-                    eprintln!("[WARN] Failed to compile query for rule '{}': {}", id, e);
+                    // 记录诊断但不崩溃，跳过这个规则
+                    diagnostics::report(Diagnostic::new(DiagnosticKind::SkippedRule, format!(
+                        "Query 在 tree-sitter-java {} (查询集 {}) 下无法编译，规则未执行: {e}", grammar::LINKED, query_set.grammar
+                    )).rule(id));
                     continue;
                 }
             };
 
-            // v9.3: 使用 create_handler 获取规则处理器
-            let handler = super::rule_handlers::create_handler(id);
+            // v9.3: 使用 create_handler 获取规则处理器 (运行时注册的处理器优先)
+            let handler: Box<dyn super::rule_handlers::RuleHandler> = match registry.handler(id) {
+                Some(custom) => Box::new(custom),
+                None if id == "HELD_LOCK_BLOCKING" => {
+                    Box::new(super::rule_handlers::HeldLockHandler { max_depth: registry.lock_depth() })
+                }
+                None if id == "BIGDECIMAL_LOOP_ARITHMETIC" && registry.exact_names().is_some() => {
+                    Box::new(super::rule_handlers::BigNumberLoopHandler {
+                        exact_names: registry.exact_names().unwrap_or_default().iter().map(|n| n.to_lowercase()).collect(),
+                    })
+                }
+                None => super::rule_handlers::create_handler(id),
+            };
 
             compiled.push(CompiledRule {
                 id,
                 severity,
+                severity_override: def.severity_override,
                 query,
+                source: query_str,
                 description,
                 handler,
+                limits: registry.exec_limits(id),
+                files: AtomicU64::new(0),
+                nanos: AtomicU64::new(0),
             });
         }
 
         Ok(compiled)
     }
 
+    /// 查询集在指定语法下无法编译的内置 Query (规则 ID 或固定查询名)，供 grammar 模块选择查询集
+    pub(crate) fn failing_queries(language: &tree_sitter::Language, query_set: &QuerySet) -> Vec<String> {
+        let registry = RuleRegistry::all_enabled();
+        let mut failed: Vec<String> = registry.rules().iter()
+            .filter(|rule| Query::new(language, &query_set.adapt(rule.query)).is_err())
+            .map(|rule| rule.id.to_string())
+            .collect();
+        let fixed: [(&str, FixedQueryFn); 6] = [
+            ("structure", Self::compile_structure_query),
+            ("call_site", Self::compile_call_site_query),
+            ("import", Self::compile_import_query),
+            ("package", Self::compile_package_query),
+            ("constant", Self::compile_constant_query),
+            ("method", Self::compile_method_query),
+        ];
+        failed.extend(fixed.iter().filter(|(_, compile)| compile(language, query_set).is_err()).map(|(name, _)| name.to_string()));
+        failed
+    }
+
     /// 编译结构化查询 (Phase 1)
-    fn compile_structure_query(language: &tree_sitter::Language) -> Result<Query> {
+    fn compile_structure_query(language: &tree_sitter::Language, query_set: &QuerySet) -> Result<Query> {
         let query_str = r#"
             (class_declaration 
                 name: (identifier) @class_name
@@ -697,35 +295,34 @@ impl JavaTreeSitterAnalyzer {
                 name: (identifier) @iface_name
                 (modifiers (marker_annotation name: (identifier) @iface_ann))?
             )
+            (annotation_type_declaration
+                name: (identifier) @iface_name
+                (modifiers (marker_annotation name: (identifier) @iface_ann))?
+            )
             (field_declaration
                 (modifiers (marker_annotation name: (identifier) @field_ann))?
                 type: (_) @field_type
                 declarator: (variable_declarator name: (identifier) @field_name)
             )
         "#;
-        Query::new(language, query_str).map_err(|e| anyhow!("Failed to compile structure query: {e}"))
+        Query::new(language, &query_set.adapt(query_str)).map_err(|e| anyhow!("Failed to compile structure query: {e}"))
     }
 
     /// 编译调用点提取查询 (用于 CallGraph 构建) - v9.4
-    fn compile_call_site_query(language: &tree_sitter::Language) -> Result<Query> {
+    ///
+    /// 匹配任意位置的方法调用 (语句、条件、参数、lambda 内)，
+    /// 接收者形态与所在方法在提取时解析 (见 `call_receiver`)。
+    fn compile_call_site_query(language: &tree_sitter::Language, query_set: &QuerySet) -> Result<Query> {
         let query_str = r#"
-            (method_declaration
-                name: (identifier) @caller_method
-                body: (block
-                    (expression_statement
-                        (method_invocation
-                            object: (identifier) @receiver
-                            name: (identifier) @callee_method
-                        ) @call
-                    )
-                )
-            )
+            (method_invocation
+                name: (identifier) @callee_method
+            ) @call
         "#;
-        Query::new(language, query_str).map_err(|e| anyhow!("Failed to compile call site query: {e}"))
+        Query::new(language, &query_set.adapt(query_str)).map_err(|e| anyhow!("Failed to compile call site query: {e}"))
     }
 
     /// 编译 Import 提取查询 (v9.5)
-    fn compile_import_query(language: &tree_sitter::Language) -> Result<Query> {
+    fn compile_import_query(language: &tree_sitter::Language, query_set: &QuerySet) -> Result<Query> {
         let query_str = r#"
             (import_declaration
                 [
@@ -734,12 +331,12 @@ impl JavaTreeSitterAnalyzer {
                 ]
             )
         "#;
-        Query::new(language, query_str).map_err(|e| anyhow!("Failed to compile import query: {e}"))
+        Query::new(language, &query_set.adapt(query_str)).map_err(|e| anyhow!("Failed to compile import query: {e}"))
     }
 
     /// 编译 Package 声明提取查询 (v9.6)
     /// Handles both multi-segment packages (scoped_identifier) and single-segment packages (identifier)
-    fn compile_package_query(language: &tree_sitter::Language) -> Result<Query> {
+    fn compile_package_query(language: &tree_sitter::Language, query_set: &QuerySet) -> Result<Query> {
         let query_str = r#"
             (package_declaration
                 [
@@ -748,17 +345,222 @@ impl JavaTreeSitterAnalyzer {
                 ]
             )
         "#;
-        Query::new(language, query_str).map_err(|e| anyhow!("Failed to compile package query: {e}"))
+        Query::new(language, &query_set.adapt(query_str)).map_err(|e| anyhow!("Failed to compile package query: {e}"))
+    }
+
+    /// 编译字符串常量提取查询
+    ///
+    /// 类字段需 static final (在提取时检查)，接口常量隐式 static final
+    fn compile_constant_query(language: &tree_sitter::Language, query_set: &QuerySet) -> Result<Query> {
+        let query_str = r#"
+            (field_declaration
+                (modifiers) @mods
+                declarator: (variable_declarator
+                    name: (identifier) @const_name
+                    value: (_) @const_value
+                )
+            ) @const_decl
+            (constant_declaration
+                declarator: (variable_declarator
+                    name: (identifier) @const_name
+                    value: (_) @const_value
+                )
+            ) @const_decl
+            (enum_constant
+                name: (identifier) @const_name
+                arguments: (argument_list) @enum_args
+            ) @const_decl
+        "#;
+        Query::new(language, &query_set.adapt(query_str)).map_err(|e| anyhow!("Failed to compile constant query: {e}"))
+    }
+
+    /// 编译方法签名提取查询 (Phase 1)
+    fn compile_method_query(language: &tree_sitter::Language, query_set: &QuerySet) -> Result<Query> {
+        let query_str = r#"
+            (method_declaration
+                type: (_) @return_type
+                name: (identifier) @method_name
+                parameters: (formal_parameters) @params
+            ) @method
+            (constructor_declaration
+                name: (identifier) @method_name
+                parameters: (formal_parameters) @params
+            ) @method
+        "#;
+        Query::new(language, &query_set.adapt(query_str)).map_err(|e| anyhow!("Failed to compile method query: {e}"))
+    }
+}
+
+/// 求值字符串常量表达式
+///
+/// 支持字符串字面量 (含文本块)、`+` 拼接与括号，其他表达式返回 None。
+pub(crate) fn string_value(node: tree_sitter::Node, code: &str) -> Option<String> {
+    match node.kind() {
+        "string_literal" => {
+            let text = node.utf8_text(code.as_bytes()).ok()?;
+            let inner = text.strip_prefix("\"\"\"").and_then(|t| t.strip_suffix("\"\"\""))
+                .or_else(|| text.strip_prefix('"').and_then(|t| t.strip_suffix('"')))?;
+            Some(inner.to_string())
+        }
+        "binary_expression" => {
+            let op = node.child_by_field_name("operator")?;
+            if op.kind() != "+" {
+                return None;
+            }
+            let left = string_value(node.child_by_field_name("left")?, code)?;
+            let right = string_value(node.child_by_field_name("right")?, code)?;
+            Some(left + &right)
+        }
+        "parenthesized_expression" => string_value(node.named_child(0)?, code),
+        _ => None,
     }
 }
 
+/// 字段声明上的注解名 (`@Size(max = 100)` → "Size")
+fn field_annotations(decl: tree_sitter::Node, code: &str) -> Vec<String> {
+    let mut cursor = decl.walk();
+    let Some(modifiers) = decl.children(&mut cursor).find(|n| n.kind() == "modifiers") else {
+        return Vec::new();
+    };
+    let mut cursor = modifiers.walk();
+    let annotations = modifiers.children(&mut cursor)
+        .filter(|n| matches!(n.kind(), "annotation" | "marker_annotation"))
+        .filter_map(|ann| ann.child_by_field_name("name")?.utf8_text(code.as_bytes()).ok())
+        .map(|name| name.rsplit('.').next().unwrap_or(name).to_string())
+        .collect();
+    annotations
+}
+
+/// 补充类声明信息: 父类/接口 (Bean 候选聚合) 与带参数注解 (`@Service("name")`)
+///
+/// 结构查询对注解的捕获不完整 (带参数注解不匹配)，这里按声明节点补齐注解名及组件注解的显式 Bean 名。
+fn enrich_type_declaration(info: &mut TypeInfo, root: tree_sitter::Node, code: &str) {
+    let mut cursor = root.walk();
+    let Some(decl) = root.children(&mut cursor).find(|n| {
+        matches!(n.kind(), "class_declaration" | "interface_declaration" | "annotation_type_declaration")
+            && n.child_by_field_name("name").and_then(|name| name.utf8_text(code.as_bytes()).ok()) == Some(info.name.as_str())
+    }) else {
+        return;
+    };
+
+    let simple_name = |node: tree_sitter::Node| -> String {
+        let text = node.utf8_text(code.as_bytes()).unwrap_or("");
+        let text = text.split('<').next().unwrap_or(text);
+        text.rsplit('.').next().unwrap_or(text).trim().to_string()
+    };
+
+    // superclass: (superclass (type_identifier)) / super_interfaces: (super_interfaces (type_list ...))
+    // 接口: (extends_interfaces (type_list ...))，非字段节点
+    let mut cursor = decl.walk();
+    let extends_interfaces = decl.children(&mut cursor).find(|n| n.kind() == "extends_interfaces");
+    let supertype_nodes = ["superclass", "interfaces"].map(|field| decl.child_by_field_name(field));
+    for node in supertype_nodes.into_iter().chain([extends_interfaces]).flatten() {
+        let mut stack = vec![node];
+        while let Some(n) = stack.pop() {
+            if matches!(n.kind(), "type_identifier" | "scoped_type_identifier" | "generic_type") {
+                info.add_supertype(&simple_name(n));
+                continue;
+            }
+            // 逆序入栈，保持源码顺序
+            let mut c = n.walk();
+            let children: Vec<_> = n.named_children(&mut c).collect();
+            stack.extend(children.into_iter().rev());
+        }
+    }
+
+    let mut cursor = decl.walk();
+    let Some(modifiers) = decl.children(&mut cursor).find(|n| n.kind() == "modifiers") else {
+        return;
+    };
+    let mut cursor = modifiers.walk();
+    for ann in modifiers.children(&mut cursor).filter(|n| matches!(n.kind(), "annotation" | "marker_annotation")) {
+        let Some(name) = ann.child_by_field_name("name").and_then(|n| n.utf8_text(code.as_bytes()).ok()) else {
+            continue;
+        };
+        if !info.annotations.iter().any(|a| a == name) {
+            info.add_annotation(name);
+        }
+        if matches!(name, "Service" | "Component" | "Repository" | "Controller" | "RestController") {
+            // @Service("x") 或 @Service(value = "x")
+            let mut stack: Vec<tree_sitter::Node> = ann.child_by_field_name("arguments").into_iter().collect();
+            while let Some(n) = stack.pop() {
+                if n.kind() == "string_literal" {
+                    info.bean_name = string_value(n, code);
+                    break;
+                }
+                let mut c = n.walk();
+                stack.extend(n.named_children(&mut c));
+            }
+        }
+    }
+}
+
+/// 声明节点上的注解名 (不含 @)
+pub(crate) fn declared_annotations(declaration: Node, code: &str) -> Vec<String> {
+    let mut cursor = declaration.walk();
+    let Some(modifiers) = declaration.children(&mut cursor).find(|c| c.kind() == "modifiers") else {
+        return Vec::new();
+    };
+    let mut cursor = modifiers.walk();
+    modifiers.children(&mut cursor)
+        .filter(|c| matches!(c.kind(), "marker_annotation" | "annotation"))
+        .filter_map(|c| c.child_by_field_name("name").and_then(|n| n.utf8_text(code.as_bytes()).ok()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 方法/构造器声明的签名 (构造器没有返回类型)
+pub(crate) fn method_info(method: Node, code: &str, class_name: &str) -> Option<MethodInfo> {
+    let text = |n: Node| n.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+    let name = text(method.child_by_field_name("name")?);
+    let params = method.child_by_field_name("parameters")
+        .map(|list| {
+            let mut cursor = list.walk();
+            list.named_children(&mut cursor)
+                .filter(|p| matches!(p.kind(), "formal_parameter" | "spread_parameter"))
+                .map(|p| {
+                    let type_name = p.child_by_field_name("type")
+                        .map(text)
+                        .unwrap_or_else(|| {
+                            // spread_parameter: `String... args` 没有 type 字段
+                            let mut cursor = p.walk();
+                            let ty = p.named_children(&mut cursor)
+                                .find(|c| c.kind() != "modifiers" && c.kind() != "variable_declarator")
+                                .map(text)
+                                .unwrap_or_default();
+                            format!("{ty}...")
+                        });
+                    let name = p.child_by_field_name("name")
+                        .or_else(|| {
+                            let mut cursor = p.walk();
+                            let declarator = p.named_children(&mut cursor).find(|c| c.kind() == "variable_declarator");
+                            declarator.and_then(|d| d.child_by_field_name("name"))
+                        })
+                        .map(text)
+                        .unwrap_or_default();
+                    ParamInfo { name, type_name }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(MethodInfo {
+        name,
+        class: class_name.to_string(),
+        return_type: method.child_by_field_name("type").map(text),
+        params,
+        annotations: declared_annotations(method, code),
+        line: method.start_position().row + 1,
+    })
+}
+
 // Test-only public API methods
 #[cfg(test)]
 impl JavaTreeSitterAnalyzer {
     /// 提取 Import 列表 (v9.5)
     #[allow(dead_code)]
     pub fn extract_imports(&self, code: &str) -> Result<Vec<String>> {
-        crate::scanner::tree_sitter_java::with_parser(&self.language, |parser| {
+        let code = strip_bom(code);
+        crate::scanner::tree_sitter_java::with_parser(&self.language, self.parse_timeout, |parser| {
             let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
             let root_node = tree.root_node();
             let mut imports = Vec::new();
@@ -782,7 +584,8 @@ impl JavaTreeSitterAnalyzer {
     /// 
     /// Returns the package name if present, or None for default package
     pub fn extract_package(&self, code: &str) -> Result<Option<String>> {
-        crate::scanner::tree_sitter_java::with_parser(&self.language, |parser| {
+        let code = strip_bom(code);
+        crate::scanner::tree_sitter_java::with_parser(&self.language, self.parse_timeout, |parser| {
             let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
             let root_node = tree.root_node();
             
@@ -821,17 +624,24 @@ impl JavaTreeSitterAnalyzer {
     /// - TypeInfo: Class/interface information
     /// - Vec<VarBinding>: Field bindings
     /// - ImportIndex: Import resolution index for FQN resolution
+    #[allow(dead_code)]
     pub fn extract_symbols(&self, code: &str, file_path: &Path) -> Result<(Option<TypeInfo>, Vec<VarBinding>, ImportIndex)> {
-        with_parser(&self.language, |parser| {
+        self.extract_symbols_for(code, &FilePath::try_from(file_path)?)
+    }
+
+    /// 同 extract_symbols，路径由调用方创建 (索引单个文件时各条目共享同一份路径)
+    pub(crate) fn extract_symbols_for(&self, code: &str, file: &FilePath) -> Result<(Option<TypeInfo>, Vec<VarBinding>, ImportIndex)> {
+        let code = strip_bom(code);
+        with_parser(&self.language, self.parse_timeout, |parser| {
             let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
-            self.extract_symbols_from_tree(&tree, code, file_path)
+            self.extract_symbols_from_tree(&tree, code, file)
         })
     }
 
     /// 从已解析的 Tree 中提取符号 (支持单次解析优化)
     /// 
     /// v9.6: Now also extracts package declaration and builds ImportIndex
-    fn extract_symbols_from_tree(&self, tree: &Tree, code: &str, file_path: &Path) -> Result<(Option<TypeInfo>, Vec<VarBinding>, ImportIndex)> {
+    pub(crate) fn extract_symbols_from_tree(&self, tree: &Tree, code: &str, file: &FilePath) -> Result<(Option<TypeInfo>, Vec<VarBinding>, ImportIndex)> {
         let mut query_cursor = QueryCursor::new();
         let matches = query_cursor.matches(&self.structure_query, tree.root_node(), code.as_bytes());
 
@@ -845,40 +655,38 @@ impl JavaTreeSitterAnalyzer {
         let imports = self.extract_imports_from_tree(tree, code)?;
         let mut import_index = ImportIndex::from_imports(imports, package.clone());
 
+        // 同名捕获在各模式间共享索引 (class / interface / @interface)
+        let index = |name: &str| self.structure_query.capture_index_for_name(name);
+        let type_name_idx = [index("class_name"), index("iface_name")];
+        let type_ann_idx = [index("class_ann"), index("iface_ann")];
+
         for m in matches {
-            // Class/Interface Declaration
-            if let Some(idx) = self.structure_query.capture_index_for_name("class_name")
-                .or_else(|| self.structure_query.capture_index_for_name("iface_name")) {
-                
-                for capture in m.captures {
-                    if capture.index == idx {
-                        let name = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
-                        if type_info.is_none() {
-                            // v9.6: Create TypeInfo with package for proper FQN
-                            type_info = Some(TypeInfo::new_with_package(
-                                &name,
-                                package.as_deref(),
-                                file_path.to_path_buf(),
-                                capture.node.start_position().row + 1,
-                            ));
-                            // Add local class to ImportIndex for same-package resolution
-                            import_index.add_local_class(&name);
-                        }
+            // Class/Interface/Annotation Type Declaration
+            for capture in m.captures {
+                if type_name_idx.contains(&Some(capture.index)) {
+                    let name = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+                    if type_info.is_none() {
+                        // v9.6: Create TypeInfo with package for proper FQN
+                        type_info = Some(TypeInfo::new_with_package(
+                            &name,
+                            package.as_deref(),
+                            file,
+                            capture.node.start_position().row + 1,
+                        ));
+                        // Add local class to ImportIndex for same-package resolution
+                        import_index.add_local_class(&name);
                     }
                 }
             }
             
             // Annotations (Add to TypeInfo)
-            if let Some(idx) = self.structure_query.capture_index_for_name("class_ann")
-                .or_else(|| self.structure_query.capture_index_for_name("iface_ann")) {
-                 for capture in m.captures {
-                    if capture.index == idx {
-                        let ann = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
-                        if let Some(info) = &mut type_info {
-                            info.add_annotation(&ann);
-                        }
+            for capture in m.captures {
+                if type_ann_idx.contains(&Some(capture.index)) {
+                    let ann = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+                    if let Some(info) = &mut type_info {
+                        info.add_annotation(&ann);
                     }
-                 }
+                }
             }
 
             // Fields
@@ -888,6 +696,7 @@ impl JavaTreeSitterAnalyzer {
             if let (Some(name_idx), Some(type_idx)) = (field_name_idx, field_type_idx) {
                  let mut f_name = String::new();
                  let mut f_type = String::new();
+                 let mut f_annotations = Vec::new();
                  
                  for capture in m.captures {
                      if capture.index == name_idx {
@@ -895,15 +704,24 @@ impl JavaTreeSitterAnalyzer {
                      }
                      if capture.index == type_idx {
                          f_type = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+                         f_annotations = capture.node.parent()
+                             .map(|decl| field_annotations(decl, code))
+                             .unwrap_or_default();
                      }
                  }
                  
                  if !f_name.is_empty() {
-                     bindings.push(VarBinding::new(&f_name, &f_type, true));
+                     let mut binding = VarBinding::new(&f_name, &f_type, true);
+                     binding.annotations = f_annotations;
+                     bindings.push(binding);
                  }
             }
         }
 
+        if let Some(info) = &mut type_info {
+            enrich_type_declaration(info, tree.root_node(), code);
+        }
+
         Ok((type_info, bindings, import_index))
     }
 
@@ -940,50 +758,161 @@ impl JavaTreeSitterAnalyzer {
         Ok(imports)
     }
 
+    /// 使用 thread_local Parser 解析，Tree 交给调用方在多个分析步骤间共用；解析失败或超时为 None
+    ///
+    /// 扫描时每个 Java 文件只解析一次: Phase 1 的各提取步骤与 Phase 2 的规则分析、
+    /// 线程池/静态初始化/锁竞争等项目级收集都使用同一棵树。
+    pub fn parse(&self, code: &str) -> Option<Tree> {
+        with_parser(&self.language, self.parse_timeout, |parser| Ok(parser.parse(code, None))).ok().flatten()
+    }
+
+    /// 使用 thread_local Parser 解析后交给调用方遍历 (单独分析一个片段时使用)
+    pub fn parse_with<R>(&self, code: &str, f: impl FnOnce(&Tree) -> R) -> Result<R> {
+        with_parser(&self.language, self.parse_timeout, |parser| {
+            let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
+            Ok(f(&tree))
+        })
+    }
+
+    /// 提取方法签名 (所有具名类型中的方法与构造器，含嵌套类型)
+    #[allow(dead_code)]
+    pub fn extract_methods(&self, code: &str) -> Result<Vec<MethodInfo>> {
+        let code = strip_bom(code);
+        with_parser(&self.language, self.parse_timeout, |parser| {
+            let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
+            Ok(self.extract_methods_from_tree(&tree, code))
+        })
+    }
+
+    pub(crate) fn extract_methods_from_tree(&self, tree: &Tree, code: &str) -> Vec<MethodInfo> {
+        let Some(method_idx) = self.method_query.capture_index_for_name("method") else {
+            return Vec::new();
+        };
+        let mut query_cursor = QueryCursor::new();
+        query_cursor.matches(&self.method_query, tree.root_node(), code.as_bytes())
+            .filter_map(|m| m.captures.iter().find(|c| c.index == method_idx).map(|c| c.node))
+            .filter_map(|method| method_info(method, code, NodeWalk::new(code).enclosing_type_name(method)?))
+            .collect()
+    }
+
+    /// 提取字符串常量 (`static final String` 字段、接口常量、枚举常量的字符串参数)
+    #[allow(dead_code)]
+    pub fn extract_constants(&self, code: &str, file_path: &Path) -> Result<Vec<StringConstant>> {
+        self.extract_constants_for(code, &FilePath::try_from(file_path)?)
+    }
+
+    /// 同 extract_constants，路径由调用方创建并共享
+    pub(crate) fn extract_constants_for(&self, code: &str, file: &FilePath) -> Result<Vec<StringConstant>> {
+        let code = strip_bom(code);
+        with_parser(&self.language, self.parse_timeout, |parser| {
+            let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
+            Ok(self.extract_constants_from_tree(&tree, code, file))
+        })
+    }
+
+    pub(crate) fn extract_constants_from_tree(&self, tree: &Tree, code: &str, file: &FilePath) -> Vec<StringConstant> {
+        let mut constants = Vec::new();
+        let mut query_cursor = QueryCursor::new();
+        let matches = query_cursor.matches(&self.constant_query, tree.root_node(), code.as_bytes());
+
+        let mods_idx = self.constant_query.capture_index_for_name("mods");
+        let name_idx = self.constant_query.capture_index_for_name("const_name");
+        let value_idx = self.constant_query.capture_index_for_name("const_value");
+        let args_idx = self.constant_query.capture_index_for_name("enum_args");
+        let decl_idx = self.constant_query.capture_index_for_name("const_decl");
+
+        for m in matches {
+            let mut name = None;
+            let mut value = None;
+            let mut decl = None;
+            let mut is_constant = true;
+
+            for capture in m.captures {
+                let idx = Some(capture.index);
+                if idx == mods_idx {
+                    let mods = capture.node.utf8_text(code.as_bytes()).unwrap_or("");
+                    is_constant = mods.contains("static") && mods.contains("final");
+                } else if idx == name_idx {
+                    name = Some(capture.node);
+                } else if idx == value_idx {
+                    value = string_value(capture.node, code);
+                } else if idx == args_idx {
+                    // 枚举常量: 所有字符串参数 (如 code + sql)，换行分隔
+                    let mut walker = capture.node.walk();
+                    let parts: Vec<String> = capture.node.named_children(&mut walker)
+                        .filter_map(|arg| string_value(arg, code))
+                        .collect();
+                    if !parts.is_empty() {
+                        value = Some(parts.join("\n"));
+                    }
+                } else if idx == decl_idx {
+                    decl = Some(capture.node);
+                }
+            }
+
+            let (Some(name), Some(value), Some(decl)) = (name, value, decl) else { continue };
+            if !is_constant {
+                continue;
+            }
+            let Some(class) = NodeWalk::new(code).enclosing_type_name(decl).map(str::to_string) else { continue };
+
+            constants.push(StringConstant {
+                class,
+                name: name.utf8_text(code.as_bytes()).unwrap_or("").to_string(),
+                value,
+                file: file.clone(),
+                line: name.start_position().row + 1,
+            });
+        }
+
+        constants
+    }
+
     /// 提取调用点信息 (用于 CallGraph 构建) - v9.4
     /// 
     /// 返回: Vec<(caller_method, receiver, callee_method, line)>
+    #[allow(dead_code)]
     pub fn extract_call_sites(&self, code: &str, file_path: &Path) -> Result<Vec<(String, String, String, usize)>> {
-        with_parser(&self.language, |parser| {
+        let code = strip_bom(code);
+        with_parser(&self.language, self.parse_timeout, |parser| {
             let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
             self.extract_call_sites_from_tree(&tree, code, file_path)
         })
     }
 
     /// 从已解析的 Tree 中提取调用点
-    fn extract_call_sites_from_tree(&self, tree: &Tree, code: &str, _file_path: &Path) -> Result<Vec<(String, String, String, usize)>> {
+    pub(crate) fn extract_call_sites_from_tree(&self, tree: &Tree, code: &str, _file_path: &Path) -> Result<Vec<(String, String, String, usize)>> {
         let mut call_sites = Vec::new();
         let mut query_cursor = QueryCursor::new();
         let matches = query_cursor.matches(&self.call_site_query, tree.root_node(), code.as_bytes());
 
-        let caller_idx = self.call_site_query.capture_index_for_name("caller_method");
-        let receiver_idx = self.call_site_query.capture_index_for_name("receiver");
         let callee_idx = self.call_site_query.capture_index_for_name("callee_method");
         let call_idx = self.call_site_query.capture_index_for_name("call");
 
         for m in matches {
-            let mut caller_method = String::new();
-            let mut receiver = String::new();
             let mut callee_method = String::new();
-            let mut line = 0;
+            let mut call_node = None;
 
             for capture in m.captures {
-                if Some(capture.index) == caller_idx {
-                    caller_method = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
-                }
-                if Some(capture.index) == receiver_idx {
-                    receiver = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
-                }
                 if Some(capture.index) == callee_idx {
                     callee_method = capture.node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
                 }
                 if Some(capture.index) == call_idx {
-                    line = capture.node.start_position().row + 1;
+                    call_node = Some(capture.node);
                 }
             }
 
-            if !caller_method.is_empty() && !callee_method.is_empty() {
-                call_sites.push((caller_method, receiver, callee_method, line));
+            let Some(call) = call_node else { continue };
+            // 只记录方法体内的调用 (字段初始化等没有调用方)
+            let walk = NodeWalk::new(code);
+            let Some(caller_method) = walk.ancestor_of_kind(call, &["method_declaration"])
+                .and_then(|method| method.child_by_field_name("name"))
+                .map(|name| walk.text(name).to_string()) else { continue };
+            // 无法确定接收者绑定的调用 (如链式调用中间结果) 不建边
+            let Some(receiver) = call_receiver(call, code) else { continue };
+
+            if !callee_method.is_empty() {
+                call_sites.push((caller_method, receiver, callee_method, call.start_position().row + 1));
             }
         }
 
@@ -1000,10 +929,84 @@ impl JavaTreeSitterAnalyzer {
         symbol_table: Option<&SymbolTable>,
         call_graph: Option<&crate::taint::CallGraph>,
     ) -> Result<Vec<Issue>> {
-        with_parser(&self.language, |parser| {
-            let tree = parser.parse(code, None).ok_or_else(|| anyhow!("Failed to parse code"))?;
-            self.analyze_tree_with_context(&tree, code, file_path, symbol_table, call_graph)
-        })
+        self.analyze_with_suppressed(code, file_path, symbol_table, call_graph)
+            .map(|(issues, _)| issues)
+    }
+
+    /// 同 analyze_with_context，额外返回被抑制的问题及其抑制来源
+    pub fn analyze_with_suppressed(
+        &self,
+        code: &str,
+        file_path: &Path,
+        symbol_table: Option<&SymbolTable>,
+        call_graph: Option<&crate::taint::CallGraph>,
+    ) -> Result<(Vec<Issue>, Vec<SuppressedIssue>)> {
+        self.analyze_excluding(code, file_path, symbol_table, call_graph, &[])
+    }
+
+    /// 同 analyze_with_suppressed，`excluded` 中的规则对该文件不运行 (.javaperf.toml rule_overrides)
+    pub fn analyze_excluding(
+        &self,
+        code: &str,
+        file_path: &Path,
+        symbol_table: Option<&SymbolTable>,
+        call_graph: Option<&crate::taint::CallGraph>,
+        excluded: &[&str],
+    ) -> Result<(Vec<Issue>, Vec<SuppressedIssue>)> {
+        // 全部规则都被排除 (如按类别排除的目录) 时不必解析
+        if self.excludes_all(excluded) {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        self.analyze_checked(code, file_path, symbol_table, call_graph, excluded)
+            .map(|(issues, suppressed, _)| (issues, suppressed))
+    }
+
+    /// 文件上排除的规则是否覆盖了全部已编译规则
+    fn excludes_all(&self, excluded: &[&str]) -> bool {
+        !excluded.is_empty() && self.compiled_rules.iter().all(|rule| excluded.contains(&rule.id))
+    }
+
+    /// 同 analyze_excluding，额外返回解析健康度
+    ///
+    /// 含语法错误的文件只保留错误区域之外的问题；解析失败 (见 [`ParseStatus::Failed`])
+    /// 时不报告问题。
+    pub fn analyze_checked(
+        &self,
+        code: &str,
+        file_path: &Path,
+        symbol_table: Option<&SymbolTable>,
+        call_graph: Option<&crate::taint::CallGraph>,
+        excluded: &[&str],
+    ) -> Result<(Vec<Issue>, Vec<SuppressedIssue>, ParseHealth)> {
+        let code = strip_bom(code);
+        let tree = with_parser(&self.language, self.parse_timeout, |parser| Ok(parser.parse(code, None)))?;
+        self.analyze_parsed(tree.as_ref(), code, file_path, symbol_table, call_graph, excluded)
+    }
+
+    /// 同 analyze_checked，使用调用方已解析的 Tree (见 [`Self::parse`]；None 为解析失败或超时)
+    ///
+    /// `code` 须为解析该 Tree 的原文。
+    pub fn analyze_parsed(
+        &self,
+        tree: Option<&Tree>,
+        code: &str,
+        file_path: &Path,
+        symbol_table: Option<&SymbolTable>,
+        call_graph: Option<&crate::taint::CallGraph>,
+        excluded: &[&str],
+    ) -> Result<(Vec<Issue>, Vec<SuppressedIssue>, ParseHealth)> {
+        let Some(tree) = tree else {
+            return Ok((Vec::new(), Vec::new(), ParseHealth::unparsed(code)));
+        };
+        let health = ParseHealth::of(tree.root_node(), code);
+        if health.status() == ParseStatus::Failed {
+            return Ok((Vec::new(), Vec::new(), health));
+        }
+        let (mut issues, mut suppressed) =
+            self.analyze_tree_with_context(tree, code, file_path, symbol_table, call_graph, excluded)?;
+        issues.retain(|issue| health.keeps_line(issue.line));
+        suppressed.retain(|(issue, _)| health.keeps_line(issue.line));
+        Ok((issues, suppressed, health))
     }
 
     /// 从已解析的 Tree 中进行深度分析 (支持单次解析优化)
@@ -1015,8 +1018,13 @@ impl JavaTreeSitterAnalyzer {
         file_path: &Path,
         symbol_table: Option<&SymbolTable>,
         call_graph: Option<&crate::taint::CallGraph>,
-    ) -> Result<Vec<Issue>> {
+        excluded: &[&str],
+    ) -> Result<(Vec<Issue>, Vec<SuppressedIssue>)> {
+        if self.excludes_all(excluded) {
+            return Ok((Vec::new(), Vec::new()));
+        }
         let root_node = tree.root_node();
+        // (规则在注册表中的下标, 问题)：按代价顺序执行，按注册表顺序输出
         let mut issues = Vec::new();
 
         // 获取当前类名 (用于 is_dao_call 上下文)
@@ -1032,57 +1040,249 @@ impl JavaTreeSitterAnalyzer {
         };
 
         // 使用预编译的查询 (不再每次编译)
-        for rule in &self.compiled_rules {
-            let mut query_cursor = QueryCursor::new();
+        for &index in &self.order {
+            let rule = &self.compiled_rules[index];
+            if excluded.contains(&rule.id) {
+                continue;
+            }
+            let started = self.record_timings.then(Instant::now);
+            let mut query_cursor = rule.cursor();
+            let deadline = rule.limits.map(|limits| Instant::now() + limits.timeout);
             let matches = query_cursor.matches(&rule.query, root_node, code.as_bytes());
+            let meta = RuleMeta { id: rule.id, severity: rule.severity, description: rule.description };
 
             // v9.3: 使用多态分发替代巨型 match
             for m in matches {
-                if let Some(issue) = rule.handler.handle(
-                    &rule.query,
-                    &m,
-                    rule.id,
-                    rule.severity,
-                    rule.description,
-                    &rule_ctx,
-                ) {
-                    issues.push(issue);
+                if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+                    diagnostics::report(Diagnostic::new(DiagnosticKind::RuleLimit, "匹配超时，跳过该文件剩余匹配")
+                        .rule(rule.id).file(file_path.display().to_string()));
+                    break;
+                }
+                if let Some(mut issue) = rule.handler.handle(&rule.query, &m, &meta, &rule_ctx) {
+                    if let Some(forced) = rule.severity_override {
+                        issue.severity = forced;
+                    }
+                    // 变体查询 (N_PLUS_ONE_WHILE 等) 以统一 ID 报告，按报告 ID 再排除一次
+                    if !excluded.contains(&issue.id.as_str()) {
+                        issues.push((index, issue));
+                    }
                 }
             }
+            if query_cursor.did_exceed_match_limit() {
+                diagnostics::report(Diagnostic::new(DiagnosticKind::RuleLimit, "超过匹配数上限，结果可能不完整")
+                    .rule(rule.id).file(file_path.display().to_string()));
+            }
+            if let Some(started) = started {
+                rule.files.fetch_add(1, Ordering::Relaxed);
+                rule.nanos.fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+            }
         }
+        issues.sort_by_key(|(index, _)| *index);
 
-        // 应用规则抑制机制 - 过滤被抑制的问题
+        // 应用规则抑制机制 - 分离被抑制的问题 (保留抑制来源供审计)
         let suppression_ctx = SuppressionContext::parse(code);
-
-        // 如果整个文件被抑制，返回空列表
-        if suppression_ctx.is_file_suppressed() {
-            return Ok(Vec::new());
+        let mut active = Vec::new();
+        let mut suppressed = Vec::new();
+        for (_, issue) in issues {
+            match suppression_ctx.suppression_for(&issue.id, issue.line) {
+                Some(source) => suppressed.push((issue, source)),
+                None => active.push(issue),
+            }
         }
 
-        // 过滤被抑制的规则
-        let filtered_issues: Vec<Issue> = issues
-            .into_iter()
-            .filter(|issue| !suppression_ctx.is_suppressed(&issue.id, issue.line))
-            .collect();
+        Ok((active, suppressed))
+    }
 
-        Ok(filtered_issues)
+    /// 单条规则的原始 Query 匹配与处理器报告的问题 (golden 测试，见 goldens 模块)
+    ///
+    /// 每个匹配取起点最前 (同起点取最长) 的捕获范围；不使用符号表、不应用抑制。
+    /// 规则未编译 (未知或未启用) 时返回 None。
+    pub fn rule_matches(&self, code: &str, file_path: &Path, rule_id: &str) -> Result<Option<(Vec<tree_sitter::Range>, Vec<Issue>)>> {
+        let Some(rule) = self.compiled_rules.iter().find(|r| r.id == rule_id) else {
+            return Ok(None);
+        };
+        let code = strip_bom(code);
+        let current_class = file_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let rule_ctx = RuleContext { code, file_path, current_class: &current_class, symbol_table: None, call_graph: None };
+        let meta = RuleMeta { id: rule.id, severity: rule.severity, description: rule.description };
+        self.parse_with(code, |tree| {
+            let mut spans = Vec::new();
+            let mut issues = Vec::new();
+            let mut query_cursor = rule.cursor();
+            for m in query_cursor.matches(&rule.query, tree.root_node(), code.as_bytes()) {
+                let widest = m.captures.iter()
+                    .map(|c| c.node)
+                    .min_by_key(|node| (node.start_byte(), std::cmp::Reverse(node.end_byte())));
+                spans.extend(widest.map(|node| node.range()));
+                issues.extend(rule.handler.handle(&rule.query, &m, &meta, &rule_ctx));
+            }
+            Some((spans, issues))
+        })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
+    /// 重放 `rule_id` (含以该 ID 报告的变体查询) 在 `line` 上的匹配与处理器判定
+    ///
+    /// 不应用抑制与区域恢复，由调用方逐步说明。
+    pub fn trace_line(&self, tree: &Tree, rule_ctx: &RuleContext, rule_id: &str, line: usize) -> Vec<RuleTrace> {
+        let code = rule_ctx.code;
+        let text = |node: Node| node.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+
+        let mut traces = Vec::new();
+        for rule in self.compiled_rules.iter().filter(|r| r.id == rule_id || reported_id(r.id) == rule_id) {
+            let meta = RuleMeta { id: rule.id, severity: rule.severity, description: rule.description };
+            let mut trace = RuleTrace { rule: rule.id, severity_override: rule.severity_override, matches: Vec::new(), other_lines: Vec::new() };
+            let mut query_cursor = rule.cursor();
+            for m in query_cursor.matches(&rule.query, tree.root_node(), code.as_bytes()) {
+                let mut issue = rule.handler.handle(&rule.query, &m, &meta, rule_ctx);
+                if let (Some(issue), Some(forced)) = (issue.as_mut(), rule.severity_override) {
+                    issue.severity = forced;
+                }
+                let first_line = m.captures.iter().map(|c| c.node.start_position().row + 1).min().unwrap_or(0);
+                let at_line = match &issue {
+                    Some(issue) => issue.line == line,
+                    None => m.captures.iter().any(|c| c.node.start_position().row + 1 == line),
+                };
+                if !at_line {
+                    trace.other_lines.push(issue.as_ref().map_or(first_line, |i| i.line));
+                    continue;
+                }
 
-    #[test]
-    fn test_n_plus_one_detection() {
-        let code = r#"
-            public class Test {
-                public void process() {
-                    for (int i = 0; i < 10; i++) {
-                        repository.save(i);
-                        userDao.findById(i);
-                        System.out.println(i);
+                let names = rule.query.capture_names();
+                let mut receivers = Vec::new();
+                for capture in m.captures {
+                    let node = capture.node;
+                    let receiver = match node.kind() {
+                        "method_invocation" => node.child_by_field_name("object").map(text),
+                        // 方法名捕获 (如 @method_name) 不是接收者
+                        "identifier" if node.parent().and_then(|p| p.child_by_field_name("name")) != Some(node) => Some(text(node)),
+                        _ => None,
+                    };
+                    if let Some(receiver) = receiver.filter(|r| !receivers.contains(r)) {
+                        receivers.push(receiver);
+                    }
+                }
+                trace.matches.push(MatchTrace {
+                    captures: m.captures.iter()
+                        .map(|c| (names[c.index as usize].to_string(), text(c.node), c.node.start_position().row + 1))
+                        .collect(),
+                    receivers,
+                    issue,
+                });
+            }
+            trace.other_lines.sort_unstable();
+            trace.other_lines.dedup();
+            traces.push(trace);
+        }
+        traces
+    }
+}
+
+/// 调用的接收者绑定 (字段名 / 类名 / "this")
+///
+/// | 形态 | 接收者 |
+/// |------|--------|
+/// | `repo.find()` / `Helper.run()` | `repo` / `Helper` |
+/// | `find()` / `this.find()` | `this` |
+/// | `this.repo.find()` / `Outer.this.repo.find()` | `repo` |
+/// | `getRepo().find()` / `this.getRepo().find()` | `repo` (按 getter 命名推断字段) |
+/// | `((OrderRepository) bean).find()` | `OrderRepository` (转型目标类型) |
+///
+/// 其它形态 (链式调用中间结果、数组元素等) 返回 None。
+fn call_receiver(call: Node, code: &str) -> Option<String> {
+    match call.child_by_field_name("object") {
+        None => Some("this".to_string()),
+        Some(object) => receiver_binding(object, code),
+    }
+}
+
+fn receiver_binding(node: Node, code: &str) -> Option<String> {
+    let text = |n: Node| n.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+    match node.kind() {
+        "identifier" => Some(text(node)),
+        "this" => Some("this".to_string()),
+        "parenthesized_expression" => node.named_child(0).and_then(|inner| receiver_binding(inner, code)),
+        "cast_expression" => node.child_by_field_name("type").map(|ty| {
+            let ty = text(ty);
+            let raw = ty.split('<').next().unwrap_or(&ty).trim();
+            raw.rsplit('.').next().unwrap_or(raw).to_string()
+        }),
+        // this.repo / Outer.this.repo
+        "field_access" => {
+            let object = node.child_by_field_name("object")?;
+            let is_this = object.kind() == "this"
+                || (object.kind() == "field_access" && object.child_by_field_name("field").is_some_and(|f| f.kind() == "this"));
+            if is_this {
+                node.child_by_field_name("field").map(text)
+            } else {
+                None
+            }
+        }
+        // getRepo() / this.getRepo()：无参 getter 视为字段访问
+        "method_invocation" => {
+            let on_self = node.child_by_field_name("object").is_none_or(|o| o.kind() == "this");
+            let no_args = node.child_by_field_name("arguments").is_some_and(|a| a.named_child_count() == 0);
+            let name = text(node.child_by_field_name("name")?);
+            let field = name.strip_prefix("get").filter(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))?;
+            if !on_self || !no_args {
+                return None;
+            }
+            let mut chars = field.chars();
+            chars.next().map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_rule_order_follows_measured_cost() {
+        let code = r#"
+            public class OrderService {
+                public synchronized void process(List<Long> ids) {
+                    for (Long id : ids) {
+                        repository.findById(id);
+                        String key = "order-" + id;
+                        Pattern.compile(key);
+                    }
+                }
+            }
+        "#;
+        let file = PathBuf::from("OrderService.java");
+        let mut analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let ids = |issues: Vec<Issue>| issues.into_iter().map(|i| (i.id, i.line)).collect::<Vec<_>>();
+
+        // 默认按 Query 估计: 带正则谓词的规则排在纯结构规则之后
+        let estimated = analyzer.rule_order();
+        let expected = ids(analyzer.analyze(code, &file).unwrap());
+        assert!(!expected.is_empty());
+        analyzer.order_rules(None);
+        let registry = analyzer.rule_order();
+        assert_ne!(estimated, registry);
+        assert_eq!(ids(analyzer.analyze(code, &file).unwrap()), expected);
+
+        // 实测记录中估计最贵的规则实际最快，排到最前；问题仍按注册表顺序输出
+        let cheapest = *estimated.last().unwrap();
+        let mut profile = TimingProfile::default();
+        profile.record(&[RuleTiming::new(cheapest, RuleCost { files: 100, total_us: 1 })]);
+        analyzer.order_rules(Some(&profile));
+        assert_eq!(analyzer.rule_order()[0], cheapest);
+        assert_eq!(analyzer.rule_order()[1..], estimated[..estimated.len() - 1]);
+        assert_eq!(ids(analyzer.analyze(code, &file).unwrap()), expected);
+    }
+
+    #[test]
+    fn test_n_plus_one_detection() {
+        let code = r#"
+            public class Test {
+                public void process() {
+                    for (int i = 0; i < 10; i++) {
+                        repository.save(i);
+                        userDao.findById(i);
+                        System.out.println(i);
                     }
                 }
             }
@@ -1100,6 +1300,66 @@ mod tests {
         assert!(issues[1].context.as_ref().unwrap().contains("findById"));
     }
 
+    #[test]
+    fn test_extract_methods() {
+        let code = r#"
+            public class OrderService {
+                public OrderService(OrderRepository repo) { }
+                @Transactional
+                public List<Order> find(Long id, String... tags) { return null; }
+                void run() { }
+                static class Helper {
+                    OrderRepository repo() { return null; }
+                }
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let methods = analyzer.extract_methods(code).unwrap();
+        let summary: Vec<(String, String, Option<String>, usize)> = methods.iter()
+            .map(|m| (m.class.clone(), m.name.clone(), m.return_type.clone(), m.params.len()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("OrderService".to_string(), "OrderService".to_string(), None, 1),
+            ("OrderService".to_string(), "find".to_string(), Some("List<Order>".to_string()), 2),
+            ("OrderService".to_string(), "run".to_string(), Some("void".to_string()), 0),
+            ("Helper".to_string(), "repo".to_string(), Some("OrderRepository".to_string()), 0),
+        ]);
+        let find = &methods[1];
+        assert_eq!(find.annotations, vec!["Transactional"]);
+        assert_eq!((find.params[1].name.as_str(), find.params[1].type_name.as_str()), ("tags", "String..."));
+    }
+
+    #[test]
+    fn test_n_plus_one_chained_receiver() {
+        let code = r#"
+            public class CheckoutService {
+                public void run(List<Long> ids) {
+                    for (Long id : ids) {
+                        orderService.getStore().load(id);
+                    }
+                }
+            }
+        "#;
+        let mut table = SymbolTable::new();
+        let mut store = TypeInfo::new_with_package("OrderStore", Some("com.shop"), "OrderStore.java", 1);
+        store.add_annotation("Repository");
+        table.register_class_fqn(store);
+        table.register_field("CheckoutService", VarBinding::new("orderService", "OrderService", true));
+
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let file = PathBuf::from("CheckoutService.java");
+        let service = "class OrderService { OrderStore getStore() { return store; } }";
+        for method in analyzer.extract_methods(service).unwrap() {
+            table.register_method("OrderService", method);
+        }
+
+        let issues = analyzer.analyze_with_context(code, &file, Some(&table), None).unwrap();
+        let n1: Vec<_> = issues.iter().filter(|i| i.id == "N_PLUS_ONE").collect();
+        assert_eq!(n1.len(), 1, "{issues:?}");
+        assert_eq!(n1[0].confidence, Some(super::super::Confidence::High));
+        assert!(n1[0].context.as_deref().unwrap().starts_with("orderService.getStore().load()"));
+    }
+
     #[test]
     fn test_extract_call_sites() {
         let code = r#"
@@ -1138,6 +1398,43 @@ mod tests {
         assert_eq!(call_sites[2].2, "save");
     }
 
+    #[test]
+    fn test_extract_call_sites_receiver_shapes() {
+        let code = r#"
+            public class OrderService {
+                public List<Order> list(Object bean) {
+                    this.orderRepository.findAll();
+                    getCache().evict("orders");
+                    ((OrderRepository) bean).flush();
+                    if (validate()) {
+                        return this.orderMapper.toDto(orderRepository.findRecent());
+                    }
+                    items.stream().map(Item::id).count();
+                    return List.of();
+                }
+            }
+        "#;
+
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let call_sites = analyzer.extract_call_sites(code, &PathBuf::from("OrderService.java")).unwrap();
+        let shapes: Vec<(&str, &str, usize)> = call_sites.iter()
+            .map(|(_, receiver, callee, line)| (receiver.as_str(), callee.as_str(), *line))
+            .collect();
+
+        assert_eq!(shapes, vec![
+            ("orderRepository", "findAll", 4),
+            ("this", "getCache", 5),
+            ("cache", "evict", 5),
+            ("OrderRepository", "flush", 6),
+            ("this", "validate", 7),
+            ("orderMapper", "toDto", 8),
+            ("orderRepository", "findRecent", 8),
+            ("items", "stream", 10),
+            ("List", "of", 11),
+        ]);
+        assert!(call_sites.iter().all(|(caller, ..)| caller == "list"));
+    }
+
     #[test]
     fn test_nested_loop_detection() {
         let code = r#"
@@ -1228,6 +1525,74 @@ mod tests {
         assert_eq!(safe_issues.len(), 0, "Should NOT detect safe usage due to remove()");
     }
 
+    #[test]
+    fn test_stream_resource_leak_released_later() {
+        let code = r#"
+            public class Exporter {
+                void leak(File f) {
+                    try {
+                        InputStream in = new FileInputStream(f);
+                        copy(in);
+                    } catch (IOException e) {
+                        log.warn("copy failed", e);
+                    }
+                }
+
+                void wrapped(File f) throws IOException {
+                    try {
+                        InputStream raw = new FileInputStream(f);
+                        try (BufferedReader reader = new BufferedReader(new InputStreamReader(raw))) {
+                            reader.lines().forEach(this::emit);
+                        }
+                    } finally {
+                        done();
+                    }
+                }
+
+                void reused(File f) throws IOException {
+                    try {
+                        OutputStream out = new FileOutputStream(f);
+                        try (out) {
+                            write(out);
+                        }
+                    } finally {
+                        done();
+                    }
+                }
+
+                void cleanup(String url) {
+                    try {
+                        Connection conn = new PooledConnection(url);
+                        try {
+                            conn.execute();
+                        } catch (SQLException | IOException e) {
+                            conn.close();
+                            throw new IllegalStateException(e);
+                        } finally {
+                            IOUtils.closeQuietly(conn);
+                        }
+                    } catch (IllegalStateException e) {
+                        retry();
+                    }
+                }
+
+                void closedTooEarly(File f) {
+                    try {
+                        Reader in = new FileReader(f);
+                        in.close();
+                    } catch (IOException e) {
+                        retry();
+                    }
+                }
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("Exporter.java")).unwrap();
+        let lines: Vec<usize> = issues.iter().filter(|i| i.id == "STREAM_RESOURCE_LEAK").map(|i| i.line).collect();
+        // 只有 leak 与 try 块内直接 close (异常时不关闭) 的 closedTooEarly
+        assert_eq!(lines, vec![5, 52], "{issues:?}");
+    }
+
     #[test]
     fn test_n_plus_one_while_loop() {
         let code = r#"
@@ -1596,6 +1961,18 @@ mod tests {
         assert!(issues.iter().any(|i| i.id == "SELECT_STAR"), "Should detect SELECT * in SQL string");
     }
 
+    #[test]
+    fn test_sql_context_truncates_on_char_boundary() {
+        // 第 50 字节落在中文字符中间: 按字节切片会 panic
+        let code = "public class OrderDao {\n    String sql = \"SELECT * FROM t_order /* 按用户查询订单列表并按时间排序 */ WHERE user_id = ?\";\n}\n";
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("OrderDao.java")).unwrap();
+        let issue = issues.iter().find(|i| i.id == "SELECT_STAR").expect("SELECT_STAR expected");
+        let context = issue.context.as_deref().unwrap();
+        assert!(context.starts_with("\"SELECT * FROM t_order") && context.ends_with("..."), "{context}");
+        assert!(crate::scanner::display_width(context) <= 50);
+    }
+
     #[test]
     fn test_like_leading_wildcard_detection() {
         // 测试 LIKE '%xxx' 前导通配符检测
@@ -1615,6 +1992,1297 @@ mod tests {
         assert!(issues.iter().any(|i| i.id == "LIKE_LEADING_WILDCARD"), "Should detect LIKE '%' leading wildcard");
     }
 
+    #[test]
+    fn test_extract_constants() {
+        let code = r#"
+            public class OrderSql {
+                public static final String FIND_ALL = "SELECT * FROM orders";
+                static final String BY_NAME = "SELECT id FROM orders " + ("WHERE name LIKE '%" + "x'");
+                private String notConstant = "SELECT * FROM tmp";
+                static final int LIMIT = 10;
+
+                enum Query {
+                    ALL("all", "SELECT * FROM orders"),
+                    NONE;
+                }
+            }
+            interface Sqls {
+                String COUNT = "SELECT count(*) FROM orders";
+            }
+        "#;
+
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let constants = analyzer.extract_constants(code, &PathBuf::from("OrderSql.java")).unwrap();
+        let find = |class: &str, name: &str| constants.iter().find(|c| c.class == class && c.name == name);
+
+        assert_eq!(find("OrderSql", "FIND_ALL").unwrap().value, "SELECT * FROM orders");
+        assert_eq!(find("OrderSql", "FIND_ALL").unwrap().line, 3);
+        assert_eq!(find("OrderSql", "BY_NAME").unwrap().value, "SELECT id FROM orders WHERE name LIKE '%x'");
+        assert_eq!(find("Query", "ALL").unwrap().value, "all\nSELECT * FROM orders");
+        assert_eq!(find("Sqls", "COUNT").unwrap().value, "SELECT count(*) FROM orders");
+        assert!(find("OrderSql", "notConstant").is_none());
+        assert!(find("OrderSql", "LIMIT").is_none());
+        assert!(find("Query", "NONE").is_none());
+    }
+
+    #[test]
+    fn test_sql_constant_reference_resolved() {
+        let sql_code = r#"
+            public class OrderSql {
+                public static final String FIND_ALL = "SELECT * " + "FROM orders";
+                public static final String SEARCH = "SELECT id FROM orders WHERE name LIKE '%" + "?'";
+            }
+        "#;
+        let dao_code = r#"
+            public class OrderDao {
+                public List<Order> all() {
+                    return jdbcTemplate.query(OrderSql.FIND_ALL, mapper);
+                }
+                public List<Order> search() {
+                    return jdbcTemplate.query(OrderSql.SEARCH, mapper);
+                }
+            }
+        "#;
+
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let mut table = SymbolTable::new();
+        for constant in analyzer.extract_constants(sql_code, &PathBuf::from("OrderSql.java")).unwrap() {
+            table.register_constant(constant);
+        }
+
+        let dao_file = PathBuf::from("OrderDao.java");
+        let issues = analyzer.analyze_with_context(dao_code, &dao_file, Some(&table), None).unwrap();
+        let select_star = issues.iter().find(|i| i.id == "SELECT_STAR").expect("SELECT_STAR via constant");
+        assert_eq!(select_star.line, 4);
+        assert!(select_star.context.as_deref().unwrap().contains("OrderSql.FIND_ALL 定义于 OrderSql.java:3"));
+        assert!(issues.iter().any(|i| i.id == "LIKE_LEADING_WILDCARD" && i.line == 7));
+
+        // 无符号表时无法解析常量，不报告引用
+        let issues = analyzer.analyze(dao_code, &dao_file).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "SELECT_STAR" || i.id == "LIKE_LEADING_WILDCARD"));
+    }
+
+    #[test]
+    fn test_di_ambiguous_bean() {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let mut table = SymbolTable::new();
+        for (file, code) in [
+            ("AlipayService.java", "@Service(\"alipay\")\npublic class AlipayService implements PaymentService {}"),
+            ("WechatPayService.java", "@Service\npublic class WechatPayService extends BasePay implements PaymentService, Closeable {}"),
+        ] {
+            let (info, _, _) = analyzer.extract_symbols(code, &PathBuf::from(file)).unwrap();
+            table.register_class_fqn(info.unwrap());
+        }
+        let wechat = table.lookup_by_simple_name("WechatPayService")[0];
+        assert_eq!(wechat.supertypes, vec!["BasePay", "PaymentService", "Closeable"]);
+        assert_eq!(table.lookup_by_simple_name("AlipayService")[0].bean_name.as_deref(), Some("alipay"));
+
+        let code = r#"
+            @Service
+            public class CheckoutService {
+                @Autowired
+                private PaymentService paymentService;
+                @Autowired
+                private PaymentService wechatPayService;
+                @Autowired @Qualifier("alipay")
+                private PaymentService qualified;
+
+                public CheckoutService(PaymentService payment) {}
+            }
+        "#;
+        let issues = analyzer.analyze_with_context(code, &PathBuf::from("CheckoutService.java"), Some(&table), None).unwrap();
+        let di: Vec<_> = issues.iter().filter(|i| i.id == "DI_AMBIGUOUS_BEAN").collect();
+        assert_eq!(di.len(), 3, "{di:?}");
+        assert!(di.iter().any(|i| i.line == 4 && i.severity == Severity::P0));
+        assert!(di.iter().any(|i| i.line == 6 && i.severity == Severity::P1), "by-name fallback should be P1");
+        assert!(di.iter().any(|i| i.line == 11 && i.severity == Severity::P0), "constructor injection");
+        assert!(di[0].context.as_deref().unwrap().contains("2 个候选 Bean"));
+
+        // @Primary 消除歧义
+        let primary = "@Service\n@Primary\npublic class DefaultPay implements PaymentService {}";
+        let (info, _, _) = analyzer.extract_symbols(primary, &PathBuf::from("DefaultPay.java")).unwrap();
+        table.register_class_fqn(info.unwrap());
+        let issues = analyzer.analyze_with_context(code, &PathBuf::from("CheckoutService.java"), Some(&table), None).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "DI_AMBIGUOUS_BEAN"));
+    }
+
+    #[test]
+    fn test_quarkus_beans_and_panache_repository() {
+        use crate::symbol_table::LayerType;
+
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let mut table = SymbolTable::new();
+        for (file, code) in [
+            ("StripeGateway.java", "@ApplicationScoped\npublic class StripeGateway implements Gateway {}"),
+            ("AdyenGateway.java", "@ApplicationScoped\npublic class AdyenGateway implements Gateway {}"),
+            ("OrderRepo.java", "@ApplicationScoped\npublic class OrderRepo implements PanacheRepository<Order> {}"),
+            ("OrderResource.java", "@Path(\"/orders\")\npublic class OrderResource {}"),
+        ] {
+            let (info, _, _) = analyzer.extract_symbols(code, &PathBuf::from(file)).unwrap();
+            table.register_class_fqn(info.unwrap());
+        }
+        assert_eq!(table.lookup_by_simple_name("OrderRepo")[0].layer, LayerType::Repository);
+        assert_eq!(table.lookup_by_simple_name("OrderResource")[0].layer, LayerType::Controller);
+        assert_eq!(table.lookup_by_simple_name("StripeGateway")[0].layer, LayerType::Service);
+
+        let code = r#"
+            @ApplicationScoped
+            public class CheckoutService {
+                @Inject
+                Gateway gateway;
+
+                public CheckoutService(OrderRepo orders) {}
+            }
+        "#;
+        let issues = analyzer.analyze_with_context(code, &PathBuf::from("CheckoutService.java"), Some(&table), None).unwrap();
+        let di: Vec<_> = issues.iter().filter(|i| i.id == "DI_AMBIGUOUS_BEAN").collect();
+        assert_eq!(di.len(), 1, "{di:?}");
+        assert!(di[0].line == 4 && di[0].context.as_deref().unwrap().contains("2 个候选 Bean"));
+    }
+
+    #[test]
+    fn test_optional_and_stream_chain_rules() {
+        let code = r#"
+            public class OrderService {
+                private Optional<Order> cached;
+
+                public Order first(List<Order> orders) {
+                    Order o = orders.stream().filter(x -> x.paid()).findFirst().get();
+                    Order r = repo.findById(1L).get();
+                    Optional<Order> maybe = lookup();
+                    Order m = maybe.get();
+                    Order c = this.cached.get();
+                    Order f = future.get();
+                    return o;
+                }
+
+                public Order guarded(Optional<Order> opt) {
+                    if (opt.isPresent()) {
+                        return opt.get();
+                    }
+                    return null;
+                }
+
+                public boolean checks(List<Order> orders) {
+                    boolean none = orders.stream().filter(x -> x.paid()).count() == 0;
+                    boolean any = 0 < orders.stream().count();
+                    boolean many = orders.stream().count() > 5;
+                    long db = repo.count();
+                    return db == 0 && none && any && many;
+                }
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("OrderService.java")).unwrap();
+        let lines = |id: &str| -> Vec<usize> {
+            issues.iter().filter(|i| i.id == id).map(|i| i.line).collect()
+        };
+
+        assert_eq!(lines("STREAM_FIND_GET"), vec![6]);
+        let find_get = issues.iter().find(|i| i.id == "STREAM_FIND_GET").unwrap();
+        assert_eq!(find_get.context.as_deref(), Some("stream().filter().findFirst().get()"));
+
+        assert_eq!(lines("OPTIONAL_BARE_GET"), vec![7, 9, 10], "guarded opt.get() should be skipped");
+        // Optional.get() 不再误报为 Future.get() 无超时
+        assert_eq!(lines("FUTURE_GET_NO_TIMEOUT"), vec![11]);
+
+        assert_eq!(lines("STREAM_COUNT_ZERO"), vec![23, 24]);
+        let count = issues.iter().filter(|i| i.id == "STREAM_COUNT_ZERO").collect::<Vec<_>>();
+        assert!(count[0].context.as_deref().unwrap().ends_with("noneMatch(predicate)"));
+        assert!(count[1].context.as_deref().unwrap().ends_with("!isEmpty()"));
+    }
+
+    #[test]
+    fn test_rpc_client_deadline_rules() {
+        use crate::project_detector::DetectedStack;
+
+        let code = r#"
+            public class OrderClient {
+                private final OrderServiceGrpc.OrderServiceBlockingStub stub;
+                private final PriceServiceGrpc.PriceServiceBlockingStub priceStub =
+                    PriceServiceGrpc.newBlockingStub(channel).withDeadlineAfter(3, TimeUnit.SECONDS);
+
+                public Order get(long id) {
+                    Order o = stub.getOrder(req(id));
+                    Order d = stub.withDeadlineAfter(2, TimeUnit.SECONDS).getOrder(req(id)).toBuilder().build();
+                    Price p = priceStub.getPrice(req(id));
+                    Stock s = StockServiceGrpc.newBlockingStub(channel).getStock(req(id));
+                    return o;
+                }
+
+                InventoryApi inventory() {
+                    return Feign.builder().decoder(new JacksonDecoder()).target(InventoryApi.class, url);
+                }
+
+                BillingApi billing() {
+                    return Feign.builder().options(new Request.Options(1000, 3000)).target(BillingApi.class, url);
+                }
+            }
+        "#;
+        let file = PathBuf::from("OrderClient.java");
+
+        // 未检测到依赖时不启用
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &file).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "GRPC_NO_DEADLINE" || i.id == "FEIGN_NO_OPTIONS"));
+
+        let stack = DetectedStack { has_grpc: true, has_feign: true, ..Default::default() };
+        let analyzer = JavaTreeSitterAnalyzer::with_registry(&RuleRegistry::with_stack(&stack)).unwrap();
+        let issues = analyzer.analyze(code, &file).unwrap();
+
+        let grpc: Vec<_> = issues.iter().filter(|i| i.id == "GRPC_NO_DEADLINE").collect();
+        assert_eq!(grpc.iter().map(|i| i.line).collect::<Vec<_>>(), vec![8, 11], "{grpc:?}");
+        assert_eq!(grpc[0].context.as_deref(), Some("客户端: OrderServiceGrpc.OrderServiceBlockingStub.getOrder()"));
+        assert_eq!(grpc[1].context.as_deref(), Some("客户端: StockServiceGrpc.getStock()"));
+
+        let feign: Vec<_> = issues.iter().filter(|i| i.id == "FEIGN_NO_OPTIONS").collect();
+        assert_eq!(feign.len(), 1, "{feign:?}");
+        assert_eq!((feign[0].line, feign[0].context.as_deref()), (16, Some("客户端: InventoryApi")));
+    }
+
+    #[test]
+    fn test_parallel_stream_rules() {
+        use crate::taint::{CallGraph, LayerType, MethodSig};
+
+        let code = r#"
+            public class OrderService {
+                private final ForkJoinPool pool = new ForkJoinPool(4);
+
+                public void run(List<Long> ids) {
+                    ids.parallelStream().map(id -> orderRepo.findById(id)).collect(toList());
+                    ids.parallelStream().forEach(id -> restTemplate.getForObject(url, String.class, id));
+                    ids.parallelStream().map(this::load).count();
+                    ids.parallelStream().map(id -> id * 2).count();
+                    pool.submit(() -> ids.parallelStream().forEach(id -> Thread.sleep(10)));
+                    List.of(1, 2, 3).parallelStream().map(i -> i + 1).count();
+                    List<String> names = Arrays.asList("a", "b");
+                    names.parallelStream().forEach(System.out::println);
+                }
+
+                Order load(Long id) { return null; }
+            }
+        "#;
+        let file = PathBuf::from("OrderService.java");
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let lines = |issues: &[Issue], id: &str| -> Vec<usize> {
+            issues.iter().filter(|i| i.id == id).map(|i| i.line).collect()
+        };
+
+        // 无符号表: 已知阻塞 API + 命名启发式；自建 ForkJoinPool 中的并行流跳过
+        let issues = analyzer.analyze(code, &file).unwrap();
+        assert_eq!(lines(&issues, "PARALLEL_STREAM_BLOCKING"), vec![6, 7]);
+        let heuristic = issues.iter().find(|i| i.id == "PARALLEL_STREAM_BLOCKING").unwrap();
+        assert_eq!(heuristic.confidence, Some(super::super::Confidence::Low));
+        assert_eq!(heuristic.context.as_deref(), Some("parallelStream() 中阻塞调用: orderRepo.findById()"));
+
+        assert_eq!(lines(&issues, "PARALLEL_STREAM_SMALL"), vec![11, 13]);
+        let small: Vec<_> = issues.iter().filter(|i| i.id == "PARALLEL_STREAM_SMALL").collect();
+        assert_eq!(small[0].context.as_deref(), Some("数据源 List.of() 仅 3 个元素"));
+        assert_eq!(small[1].context.as_deref(), Some("数据源 names = Arrays.asList() 仅 2 个元素"));
+
+        // CallGraph 验证: this::load 经 5 层内调用到达 Repository
+        let mut graph = CallGraph::new();
+        graph.register_class("OrderRepository", "OrderRepository.java", LayerType::Repository);
+        graph.add_call(
+            MethodSig::new_fqn("OrderService", "load"),
+            MethodSig::new_fqn("OrderRepository", "findById"),
+            file.to_str().unwrap(),
+            16,
+        );
+        let table = SymbolTable::new();
+        let issues = analyzer.analyze_with_context(code, &file, Some(&table), Some(&graph)).unwrap();
+        assert_eq!(lines(&issues, "PARALLEL_STREAM_BLOCKING"), vec![6, 7, 8]);
+        assert_eq!(issues.iter().find(|i| i.id == "PARALLEL_STREAM_BLOCKING").unwrap().confidence, Some(super::super::Confidence::Medium));
+        let traced = issues.iter().find(|i| i.id == "PARALLEL_STREAM_BLOCKING" && i.line == 8).unwrap();
+        assert_eq!(
+            traced.context.as_deref(),
+            Some("parallelStream() 中阻塞调用: load() [调用链验证: OrderService.load → OrderRepository.findById]"),
+        );
+    }
+
+    #[test]
+    fn test_reactor_scheduler_rules() {
+        use crate::project_detector::DetectedStack;
+
+        let code = r#"
+            public class ReportService {
+                private final Scheduler legacy = Schedulers.elastic();
+
+                public Mono<List<Order>> orders() {
+                    return Mono.fromCallable(() -> jdbcTemplate.query(SQL, mapper));
+                }
+                public Mono<List<Order>> scheduled() {
+                    return Mono.fromCallable(() -> jdbcTemplate.query(SQL, mapper)).subscribeOn(Schedulers.boundedElastic());
+                }
+                public Mono<Order> later(long id) {
+                    Mono<Order> order = Mono.fromCallable(() -> orderRepository.findById(id).orElseThrow());
+                    return order.subscribeOn(Schedulers.boundedElastic());
+                }
+                public Mono<Order> unscheduled(long id) {
+                    return Mono.fromSupplier(() -> orderRepository.getReferenceById(id));
+                }
+                public Flux<Order> reactive() {
+                    return Flux.defer(() -> reactiveOrders.findAll());
+                }
+                public Flux<Price> prices(Flux<Long> ids) {
+                    return ids.publishOn(Schedulers.parallel()).map(id -> restTemplate.getForObject(url, Price.class, id));
+                }
+                public Mono<Order> parallelSource(long id) {
+                    return Mono.fromCallable(() -> jdbcTemplate.queryForObject(SQL, mapper, id)).subscribeOn(Schedulers.parallel());
+                }
+                public Flux<Order> switched(Flux<Long> ids) {
+                    return ids.publishOn(Schedulers.parallel()).map(this::key)
+                        .publishOn(Schedulers.boundedElastic()).map(k -> jdbcTemplate.queryForObject(SQL, mapper, k));
+                }
+            }
+        "#;
+        let file = PathBuf::from("ReportService.java");
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let mut table = SymbolTable::new();
+        for (name, supertype) in [("OrderRepository", "JpaRepository"), ("ReactiveOrders", "ReactiveCrudRepository")] {
+            let mut info = TypeInfo::new_with_package(name, Some("com.shop"), format!("{name}.java"), 1);
+            info.supertypes.push(supertype.to_string());
+            table.register_class_fqn(info);
+        }
+        table.register_field("ReportService", VarBinding::new("orderRepository", "OrderRepository", true));
+        table.register_field("ReportService", VarBinding::new("reactiveOrders", "ReactiveOrders", true));
+
+        // 非响应式项目不启用
+        let issues = analyzer.analyze_with_context(code, &file, Some(&table), None).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "SCHEDULERS_ELASTIC" || i.id == "JDBC_NO_BOUNDED_ELASTIC"));
+
+        let stack = DetectedStack { is_reactive: true, ..Default::default() };
+        let analyzer = JavaTreeSitterAnalyzer::with_registry(&RuleRegistry::with_stack(&stack)).unwrap();
+        let issues = analyzer.analyze_with_context(code, &file, Some(&table), None).unwrap();
+        let lines = |id: &str| issues.iter().filter(|i| i.id == id).map(|i| i.line).collect::<Vec<_>>();
+        let context = |id: &str, line: usize| {
+            issues.iter().find(|i| i.id == id && i.line == line).and_then(|i| i.context.clone()).unwrap_or_default()
+        };
+
+        assert_eq!(lines("SCHEDULERS_ELASTIC"), vec![3]);
+        assert_eq!(lines("JDBC_NO_BOUNDED_ELASTIC"), vec![6, 16], "{issues:?}");
+        assert!(context("JDBC_NO_BOUNDED_ELASTIC", 6).starts_with("Mono.fromCallable() 包装的阻塞调用 jdbcTemplate.query()"));
+        assert!(context("JDBC_NO_BOUNDED_ELASTIC", 16).contains("orderRepository.getReferenceById()"));
+
+        assert_eq!(lines("PARALLEL_SCHEDULER_BLOCKING"), vec![22, 25], "{issues:?}");
+        assert!(context("PARALLEL_SCHEDULER_BLOCKING", 22).starts_with("publishOn(Schedulers.parallel()) 上执行阻塞调用 restTemplate.getForObject()"));
+        assert!(context("PARALLEL_SCHEDULER_BLOCKING", 25).starts_with("subscribeOn(Schedulers.parallel()) 上执行阻塞调用 jdbcTemplate.queryForObject()"));
+    }
+
+    #[test]
+    fn test_resource_load_hot_path() {
+        use crate::taint::{CallGraph, LayerType};
+
+        let code = r#"
+            @RestController
+            public class MessageController {
+                private static final ResourceBundle MESSAGES = ResourceBundle.getBundle("messages");
+                private final Properties limits = new Properties();
+
+                @PostConstruct
+                void loadLimits() throws IOException {
+                    limits.load(getClass().getResourceAsStream("/limits.properties"));
+                }
+                @GetMapping("/greeting")
+                public String greeting(Locale locale) {
+                    return ResourceBundle.getBundle("messages", locale).getString("hello");
+                }
+                public void reload() throws IOException {
+                    limits.load(cacheLoader.load("limits"));
+                }
+            }
+            class TemplateRenderer {
+                public List<String> render(List<String> names) throws IOException {
+                    List<String> out = new ArrayList<>();
+                    for (String name : names) {
+                        Properties props = new Properties();
+                        props.load(TemplateRenderer.class.getResourceAsStream("/tpl/" + name));
+                        out.add(props.getProperty("body"));
+                    }
+                    return out;
+                }
+                public String single() {
+                    return new Scanner(getClass().getResourceAsStream("/tpl/single")).next();
+                }
+            }
+            class HealthResource {
+                public String version() throws IOException {
+                    return new String(Thread.currentThread().getContextClassLoader().getResourceAsStream("VERSION").readAllBytes());
+                }
+            }
+        "#;
+        let file = PathBuf::from("MessageController.java");
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        // HealthResource 没有注解，层级来自 CallGraph (如 JAX-RS 资源在其他文件登记)
+        let mut graph = CallGraph::new();
+        graph.register_class("HealthResource", "HealthResource.java", LayerType::Controller);
+        let issues = analyzer.analyze_with_context(code, &file, None, Some(&graph)).unwrap();
+        let hits: Vec<(usize, String)> = issues.iter()
+            .filter(|i| i.id == "RESOURCE_LOAD_HOT_PATH")
+            .map(|i| (i.line, i.context.clone().unwrap_or_default()))
+            .collect();
+        let lines: Vec<usize> = hits.iter().map(|(line, _)| *line).collect();
+
+        // 字段初始化器、@PostConstruct、循环外的普通方法与非 Properties 的 load() 不报告
+        assert_eq!(lines, vec![13, 16, 24, 24, 35], "{hits:?}");
+        assert!(hits[0].1.starts_with("ResourceBundle.getBundle() 位于请求处理方法 MessageController.greeting()"), "{hits:?}");
+        assert!(hits[2].1.starts_with("props.load() 位于循环内"), "{hits:?}");
+        assert!(hits[4].1.contains("请求处理方法 HealthResource.version()"), "{hits:?}");
+    }
+
+    #[test]
+    fn test_transaction_self_call_through_proxy() {
+        use crate::taint::{CallGraph, ProxyKind};
+
+        let code = r#"
+            @Service
+            public class OrderService extends BaseService {
+                public void placeOrder(Order order) {
+                    validate(order);
+                    this.save(order);
+                    audit();
+                }
+                @Transactional
+                public void save(Order order) { repository.save(order); }
+                @Transactional
+                public void batch(List<Order> orders) { orders.forEach(o -> save(o)); }
+                @Cacheable("prices")
+                public Price price(String sku) { return loader.load(sku); }
+                public Price cheapest(List<String> skus) { return price(skus.get(0)); }
+                private void validate(Order order) {}
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let file = PathBuf::from("OrderService.java");
+        let hits = |table: Option<&SymbolTable>, graph: Option<&CallGraph>| -> Vec<(usize, String)> {
+            analyzer.analyze_with_context(code, &file, table, graph).unwrap().into_iter()
+                .filter(|i| i.id == "TRANSACTION_SELF_CALL")
+                .map(|i| (i.line, i.context.unwrap_or_default()))
+                .collect()
+        };
+
+        // 事务方法之间的自调用已在事务中；未注解的 validate() 不报告
+        let single = hits(None, None);
+        assert_eq!(single.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![6, 15], "{single:?}");
+        assert!(single[0].1.starts_with("OrderService.placeOrder() 经 this 调用 @Transactional 方法 save()"), "{single:?}");
+        assert!(single[1].1.contains("缓存不生效"), "{single:?}");
+
+        // 父类中声明的 @Async 方法: 由 Phase 1 在 CallGraph 中标记
+        let mut table = SymbolTable::new();
+        let mut info = TypeInfo::new("OrderService", file.to_str().unwrap(), 2);
+        info.add_supertype("BaseService");
+        table.register_class(info);
+        let mut graph = CallGraph::new();
+        graph.mark_proxied(crate::taint::MethodSig::new("BaseService", "audit"), ProxyKind::Async);
+        let project = hits(Some(&table), Some(&graph));
+        assert_eq!(project.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![6, 7, 15], "{project:?}");
+        assert!(project[1].1.contains("@Async 方法 audit()"), "{project:?}");
+    }
+
+    #[test]
+    fn test_aop_hot_path() {
+        let code = r#"
+            @Aspect
+            @Component
+            public class AuditAspect {
+                @Pointcut("execution(* com.example..*.*(..))")
+                public void everything() {}
+
+                @Around("everything()")
+                public Object audit(ProceedingJoinPoint pjp) throws Throwable {
+                    log.info("call {} args={}", pjp.getSignature(), mapper.writeValueAsString(pjp.getArgs()));
+                    Object result = pjp.proceed();
+                    auditRepository.save(new AuditLog(pjp.getSignature().getName()));
+                    return result;
+                }
+                @Before("within(com.example..*) && @annotation(Audited)")
+                public void annotated(JoinPoint jp) { Thread.sleep(10); }
+                @AfterReturning(pointcut = "execution(* com.example.web.*.*(..))", returning = "ret")
+                public void trace(JoinPoint jp, Object ret) {
+                    if (log.isDebugEnabled()) { log.debug(JSON.toJSONString(jp.getArgs())); }
+                }
+                @After("execution(* com.example.web.OrderController.list(..))")
+                public void narrow() { Thread.sleep(10); }
+            }
+            class NotAnAspect {
+                @Around("execution(* *(..))")
+                public Object around(ProceedingJoinPoint pjp) throws Throwable { Thread.sleep(1); return pjp.proceed(); }
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let file = PathBuf::from("AuditAspect.java");
+        let mut table = SymbolTable::new();
+        let mut repository = TypeInfo::new("AuditRepository", "AuditRepository.java", 1);
+        repository.add_annotation("Repository");
+        table.register_class(repository);
+        table.register_field("AuditAspect", VarBinding::new("auditRepository", "AuditRepository", true));
+        let mut graph = crate::taint::CallGraph::new();
+        for (class, layer) in [
+            ("com.example.web.OrderController", crate::taint::LayerType::Controller),
+            ("com.example.service.OrderService", crate::taint::LayerType::Service),
+            ("org.thirdparty.Client", crate::taint::LayerType::Unknown),
+        ] {
+            graph.register_class(class, "X.java", layer);
+        }
+        let issues = analyzer.analyze_with_context(code, &file, Some(&table), Some(&graph)).unwrap();
+        let hits: Vec<(usize, String)> = issues.iter()
+            .filter(|i| i.id == "AOP_HOT_PATH")
+            .map(|i| (i.line, i.context.clone().unwrap_or_default()))
+            .collect();
+
+        // @annotation 限定、日志级别判断内的序列化、具体方法切点与非切面类不报告
+        assert_eq!(hits.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![8], "{hits:?}");
+        let context = &hits[0].1;
+        assert!(context.starts_with("@Around(\"everything()\") 覆盖 2 个类 (含 1 个 Controller)"), "{context}");
+        assert!(context.contains("mapper.writeValueAsString(pjp.getArgs()) 序列化全部参数/返回值"), "{context}");
+        assert!(context.contains("auditRepository.save() 阻塞调用"), "{context}");
+
+        // 切点不覆盖任何项目类时不报告
+        let mut other = crate::taint::CallGraph::new();
+        other.register_class("org.other.Main", "Main.java", crate::taint::LayerType::Unknown);
+        let issues = analyzer.analyze_with_context(code, &file, Some(&table), Some(&other)).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "AOP_HOT_PATH"));
+    }
+
+    #[test]
+    fn test_manual_dto_copy() {
+        let code = r#"
+            @Service
+            public class OrderService {
+                public OrderDto detail(Order order) {
+                    OrderDto dto = new OrderDto();
+                    dto.setId(order.getId());
+                    dto.setNo(order.getNo());
+                    // 金额
+                    dto.setAmount(order.getAmount());
+                    dto.setPaid(order.isPaid());
+                    dto.setCreatedAt(order.getCreatedAt());
+                    return dto;
+                }
+                public List<OrderDto> list(List<Order> orders) {
+                    return orders.stream().map(this::toDto).collect(Collectors.toList());
+                }
+                private OrderDto toDto(Order o) {
+                    OrderDto d = new OrderDto();
+                    d.setId(o.getId()); d.setNo(o.getNo()); d.setAmount(o.getAmount());
+                    d.setPaid(o.isPaid()); d.setCreatedAt(o.getCreatedAt());
+                    return d;
+                }
+                public void export(List<Line> lines, Summary summary) {
+                    for (Line line : lines) {
+                        LineView view = new LineView();
+                        view.setSku(line.sku()); view.setQty(line.qty()); view.setPrice(line.price());
+                        view.setName(line.name()); view.setTotal(line.total());
+                        summary.add(view);
+                    }
+                }
+                public void partial(Order order, OrderDto dto) {
+                    dto.setId(order.getId()); dto.setNo(order.getNo()); dto.setAmount(order.getAmount());
+                    dto.setPaid(order.isPaid());
+                }
+                public void notData(Order order, Invoice invoice) {
+                    invoice.setA(order.getA()); invoice.setB(order.getB()); invoice.setC(order.getC());
+                    invoice.setD(order.getD()); invoice.setE(order.getE());
+                }
+            }
+            record Line(String sku, int qty, long price, String name, long total) {}
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("OrderService.java")).unwrap();
+        let hits: Vec<(usize, String)> = issues.iter()
+            .filter(|i| i.id == "MANUAL_DTO_COPY")
+            .map(|i| (i.line, i.context.clone().unwrap_or_default()))
+            .collect();
+
+        // 同一复制块只在第一条报告；不足 5 个字段、两侧都不是数据类时不报告
+        assert_eq!(hits.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![6, 19, 26], "{hits:?}");
+        assert_eq!(hits[0].1, "OrderService.detail() 手写复制 5 个字段 order → dto (OrderDto)");
+        assert!(hits[1].1.contains("转换方法在第 15 行的循环/流中逐元素调用"), "{}", hits[1].1);
+        // record 访问器作为来源
+        assert!(hits[2].1.starts_with("OrderService.export() 手写复制 5 个字段 line → view (Line)，位于循环内"), "{}", hits[2].1);
+
+        // 非 Controller/Service 类不报告
+        let plain = code.replace("@Service\n            public class OrderService", "public class OrderMapping");
+        let issues = analyzer.analyze(&plain, &PathBuf::from("OrderMapping.java")).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "MANUAL_DTO_COPY"));
+    }
+
+    #[test]
+    fn test_held_lock_blocking() {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = [
+            ("OrderCache.java", r#"
+package com.shop;
+@Service
+public class OrderCache {
+    @Autowired
+    private PriceService priceService;
+    private final Object lock = new Object();
+
+    public synchronized void refresh(String sku) {
+        priceService.reload(sku);
+    }
+
+    public void evict(String sku) {
+        synchronized (lock) {
+            helper();
+        }
+    }
+
+    public synchronized void touch() {
+        mark();
+    }
+
+    private void helper() { mark(); }
+
+    private synchronized void mark() {}
+}
+"#),
+            ("PriceService.java", r#"
+package com.shop;
+@Service
+public class PriceService {
+    @Autowired
+    private PriceClient client;
+
+    public void reload(String sku) { client.fetch(sku); }
+}
+"#),
+            ("PriceClient.java", r#"
+package com.shop;
+@Component
+public class PriceClient {
+    private RestTemplate restTemplate;
+
+    public Price fetch(String sku) { return restTemplate.getForObject("/price/" + sku, Price.class); }
+}
+"#),
+        ];
+        let paths: Vec<PathBuf> = sources.iter().map(|(name, code)| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, code).unwrap();
+            path
+        }).collect();
+        let path_refs: Vec<&std::path::Path> = paths.iter().map(PathBuf::as_path).collect();
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let (table, graph, _) = crate::ast_engine::build_index(&path_refs, &analyzer);
+
+        let hits = |analyzer: &JavaTreeSitterAnalyzer| -> Vec<(usize, String)> {
+            analyzer.analyze_with_context(sources[0].1, &paths[0], Some(&table), Some(&graph)).unwrap()
+                .into_iter()
+                .filter(|i| i.id == "HELD_LOCK_BLOCKING")
+                .map(|i| (i.line, i.context.unwrap_or_default()))
+                .collect()
+        };
+        // 重入同一把锁 (touch → mark) 不报告
+        let found = hits(&analyzer);
+        assert_eq!(found.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![10, 15], "{found:?}");
+        assert!(found[0].1.contains("持有 OrderCache.this 时调用 PriceService.reload → PriceClient.fetch"), "{}", found[0].1);
+        assert!(found[0].1.contains("restTemplate.getForObject"), "{}", found[0].1);
+        assert!(found[1].1.contains("持有 OrderCache.lock") && found[1].1.contains("OrderCache.this"), "{}", found[1].1);
+
+        // 阻塞调用在第 2 层，限制为 1 层时不报告
+        let mut registry = RuleRegistry::default();
+        registry.set_lock_depth(1);
+        let shallow = JavaTreeSitterAnalyzer::with_registry(&registry).unwrap();
+        assert!(hits(&shallow).is_empty());
+    }
+
+    #[test]
+    fn test_bigdecimal_rules() {
+        let code = r#"
+public class Ledger {
+    private double rate;
+
+    public BigDecimal total(List<Row> rows, double ratio, BigDecimal base) {
+        BigDecimal a = new BigDecimal(0.1);
+        BigDecimal b = new BigDecimal(this.rate);
+        BigDecimal c = new BigDecimal("0.1");
+        BigDecimal score = BigDecimal.ZERO;
+        BigDecimal amount = BigDecimal.ZERO;
+        for (int i = 0; i < 100000; i++) {
+            score = score.add(BigDecimal.valueOf(i)).multiply(base);
+            score = score.setScale(2, RoundingMode.HALF_UP);
+            amount = amount.add(base);
+        }
+        for (Row row : rows) {
+            score = score.add(base);
+        }
+        if (a.equals(BigDecimal.ZERO) || !base.equals(c) || Objects.equals(b, base) || row.equals(base.toString())) {
+            return new BigDecimal(ratio);
+        }
+        return score;
+    }
+}
+"#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("Ledger.java")).unwrap();
+        let found = |id: &str| -> Vec<&Issue> { issues.iter().filter(|i| i.id == id).collect() };
+
+        let from_double = found("BIGDECIMAL_FROM_DOUBLE");
+        assert_eq!(from_double.iter().map(|i| i.line).collect::<Vec<_>>(), vec![6, 7, 20], "{from_double:?}");
+        let literal = from_double[0].patch.as_deref().unwrap();
+        assert!(literal.contains("+        BigDecimal a = new BigDecimal(\"0.1\");"), "{literal}");
+        assert!(from_double[1].patch.as_deref().unwrap().contains("BigDecimal.valueOf(this.rate)"));
+        assert_eq!(from_double[2].confidence, Some(super::super::Confidence::High));
+
+        // 大循环内报告一次 (第一处运算，共 2 处；含 amount 的语句与无规模证据的循环不计)
+        let arithmetic = found("BIGDECIMAL_LOOP_ARITHMETIC");
+        assert_eq!(arithmetic.iter().map(|i| i.line).collect::<Vec<_>>(), vec![12], "{arithmetic:?}");
+        let context = arithmetic[0].context.as_deref().unwrap();
+        assert!(context.contains("循环内 2 处 BigDecimal 运算") && context.contains("循环上界 100000"), "{context}");
+        assert_eq!(arithmetic[0].confidence, Some(super::super::Confidence::Medium));
+
+        let equals = found("BIGDECIMAL_EQUALS");
+        assert_eq!(equals.len(), 3, "{equals:?}");
+        assert!(equals[0].patch.as_deref().unwrap().contains("a.compareTo(BigDecimal.ZERO) == 0"));
+        assert!(equals[1].patch.as_deref().unwrap().contains("base.compareTo(c) != 0"));
+        assert_eq!(equals[2].patch, None);
+
+        // 配置的精确计算变量名替换默认列表
+        let mut registry = RuleRegistry::default();
+        registry.set_exact_names(vec!["Score".into()]);
+        let configured = JavaTreeSitterAnalyzer::with_registry(&registry).unwrap();
+        let issues = configured.analyze(code, &PathBuf::from("Ledger.java")).unwrap();
+        let lines: Vec<_> = issues.iter().filter(|i| i.id == "BIGDECIMAL_LOOP_ARITHMETIC").map(|i| i.line).collect();
+        assert_eq!(lines, vec![14]);
+    }
+
+    #[test]
+    fn test_npe_risk_nullable_return() {
+        let directory = r#"
+            public class UserDirectory {
+                @Nullable
+                public User findUser(String id) { return cache.get(id); }
+                public User getUser(String id) { return users.get(id); }
+                @org.springframework.lang.Nullable
+                public static Config lookup(String key) { return null; }
+                @CheckForNull
+                public User byEmail(String email) { return null; }
+                public User byEmail(String email, boolean strict) { return null; }
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let mut table = SymbolTable::new();
+        for method in analyzer.extract_methods(directory).unwrap() {
+            table.register_method("UserDirectory", method);
+        }
+        table.register_field("OrderService", VarBinding::new("userDirectory", "UserDirectory", true));
+
+        let code = r#"
+            public class OrderService {
+                public String direct(String id) {
+                    return userDirectory.findUser(id).getName();
+                }
+                public String local(String id) {
+                    User user = userDirectory.findUser(id);
+                    log.info("loaded {}", id);
+                    return user.getName();
+                }
+                public String guarded(String id) {
+                    User user = userDirectory.findUser(id);
+                    if (user == null) {
+                        return "";
+                    }
+                    return user.getName();
+                }
+                public String required(String id) {
+                    User user = userDirectory.findUser(id);
+                    Objects.requireNonNull(user, "user");
+                    User other = Objects.requireNonNull(userDirectory.findUser(id));
+                    return user.getName() + other.getName();
+                }
+                public String nonNull(String id) {
+                    return userDirectory.getUser(id).getName();
+                }
+                public Optional<Config> config() {
+                    Optional<Config> safe = Optional.ofNullable(UserDirectory.lookup("b"));
+                    return Optional.of(UserDirectory.lookup("a"));
+                }
+                public String overloads(String email) {
+                    String a = userDirectory.byEmail(email).getName();
+                    return a + userDirectory.byEmail(email, true).getName();
+                }
+            }
+        "#;
+        let file = PathBuf::from("OrderService.java");
+        let issues = analyzer.analyze_with_context(code, &file, Some(&table), None).unwrap();
+        let hits: Vec<(usize, String)> = issues.iter()
+            .filter(|i| i.id == "NPE_RISK")
+            .map(|i| (i.line, i.context.clone().unwrap_or_default()))
+            .collect();
+        let lines: Vec<usize> = hits.iter().map(|(line, _)| *line).collect();
+        // 判空、requireNonNull、非 @Nullable 方法与非 @Nullable 重载不报告
+        assert_eq!(lines, vec![4, 9, 29, 32], "{hits:?}");
+        assert!(hits[0].1.starts_with("findUser(...).getName() → findUser() 标注 @Nullable"), "{hits:?}");
+        assert!(hits[1].1.starts_with("user.getName() → user 来自 @Nullable 方法 findUser()"), "{hits:?}");
+        assert!(hits[2].1.contains("应使用 Optional.ofNullable"), "{hits:?}");
+
+        // 没有 SymbolTable 时无法确认注解
+        let issues = analyzer.analyze_with_context(code, &file, None, None).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "NPE_RISK"));
+    }
+
+    #[test]
+    fn test_dao_list_query_rules() {
+        use crate::project_config::RuleOverride;
+
+        let code = r#"
+            @Repository
+            public class OrderDao {
+                private static final String RECENT = "SELECT * FROM orders ORDER BY id DESC LIMIT 100";
+                public List<Order> all() {
+                    return jdbcTemplate.query("SELECT * FROM orders", (rs, rowNum) -> map(rs));
+                }
+                public List<Map<String, Object>> rows(long userId) {
+                    return jdbcTemplate.queryForList("SELECT * FROM orders WHERE user_id = ?", userId);
+                }
+                public List<Order> recent() {
+                    return jdbcTemplate.query(RECENT, new BeanPropertyRowMapper<>(Order.class));
+                }
+                public void export(Writer out) {
+                    jdbcTemplate.query("SELECT * FROM orders", rs -> { write(out, rs); });
+                }
+                public List<OrderRecord> jooq(long userId) {
+                    List<OrderRecord> page = dsl.selectFrom(ORDERS).where(ORDERS.USER_ID.eq(userId)).limit(20).fetch();
+                    return dsl.selectFrom(ORDERS).where(ORDERS.USER_ID.eq(userId)).fetchInto(OrderRecord.class);
+                }
+            }
+            class ReportService {
+                public List<Map<String, Object>> rows() {
+                    return jdbcTemplate.queryForList("SELECT * FROM orders");
+                }
+            }
+        "#;
+        let file = PathBuf::from("OrderDao.java");
+        let hits = |analyzer: &JavaTreeSitterAnalyzer, id: &str| -> Vec<(usize, String)> {
+            analyzer.analyze(code, &file).unwrap().into_iter()
+                .filter(|i| i.id == id)
+                .map(|i| (i.line, i.context.unwrap_or_default()))
+                .collect()
+        };
+
+        // LIMIT 常量、单参数回调 (ResultSetExtractor/RowCallbackHandler)、limit() 与非 DAO 类不报告
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let unbounded = hits(&analyzer, "UNBOUNDED_RESULT_LIST");
+        let lines: Vec<usize> = unbounded.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![6, 9, 19], "{unbounded:?}");
+        assert!(unbounded[1].1.starts_with("jdbcTemplate.queryForList() 未限制行数"), "{unbounded:?}");
+        assert!(unbounded[2].1.contains(".limit(n)"), "{unbounded:?}");
+        assert!(hits(&analyzer, "JDBC_FETCH_SIZE_REQUIRED").is_empty(), "opt-in rule is off by default");
+
+        // .javaperf.toml 启用可选规则
+        let mut registry = RuleRegistry::default();
+        registry.apply_overrides(&[RuleOverride { id: "JDBC_FETCH_SIZE_REQUIRED".into(), exclude: Vec::new(), enabled: Some(true), sample_rate: None }]).unwrap();
+        let analyzer = JavaTreeSitterAnalyzer::with_registry(&registry).unwrap();
+        let fetch_size = hits(&analyzer, "JDBC_FETCH_SIZE_REQUIRED");
+        let lines: Vec<usize> = fetch_size.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![6, 9, 12, 18, 19], "{fetch_size:?}");
+        assert!(fetch_size[0].1.contains("setFetchSize(n)"), "{fetch_size:?}");
+
+        let tuned = code.replace("public List<Order> all() {", "OrderDao() { jdbcTemplate.setFetchSize(500); jdbcTemplate.setMaxRows(10_000); }\n public List<Order> all() {");
+        let issues = analyzer.analyze(&tuned, &file).unwrap();
+        let remaining: Vec<_> = issues.iter()
+            .filter(|i| i.id == "JDBC_FETCH_SIZE_REQUIRED" || i.id == "UNBOUNDED_RESULT_LIST")
+            .map(|i| (i.id.as_str(), i.line))
+            .collect();
+        // setFetchSize/setMaxRows 只作用于 JdbcTemplate，jOOQ 查询仍报告
+        assert_eq!(remaining, vec![("UNBOUNDED_RESULT_LIST", 20), ("JDBC_FETCH_SIZE_REQUIRED", 19), ("JDBC_FETCH_SIZE_REQUIRED", 20)], "{issues:?}");
+    }
+
+    #[test]
+    fn test_loop_bound_escalation() {
+        let code = r#"
+            public class ReportService {
+                public void export(@RequestBody List<Long> ids) {
+                    for (Long id : ids) {
+                        userRepository.findById(id);
+                    }
+                    List<Order> orders = orderRepository.findAll();
+                    for (Order o : orders) {
+                        StringBuilder sb = new StringBuilder();
+                    }
+                    for (int i = 0; i < 10_000; i++) {
+                        Item item = new Item(i);
+                    }
+                    for (int i = 0; i < 10; i++) {
+                        Item item = new Item(i);
+                    }
+                }
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("ReportService.java")).unwrap();
+
+        let n1 = issues.iter().find(|i| i.id == "N_PLUS_ONE").unwrap();
+        assert_eq!(n1.severity, Severity::P0);
+        assert!(n1.context.as_deref().unwrap().ends_with("[循环规模: 遍历请求批量参数 @RequestBody ids]"), "{n1:?}");
+
+        let objects: Vec<_> = issues.iter().filter(|i| i.id == "OBJECT_IN_LOOP")
+            .map(|i| (i.line, i.severity, i.context.as_deref()))
+            .collect();
+        assert_eq!(objects, vec![
+            (9, Severity::P0, Some("[循环规模: 遍历全表查询 orders = orderRepository.findAll()]")),
+            (12, Severity::P0, Some("[循环规模: 循环上界 10000]")),
+            (15, Severity::P1, None),
+        ]);
+    }
+
+    #[test]
+    fn test_entity_equals_collection() {
+        let code = r#"
+            @Entity
+            public class Order {
+                @Id private Long id;
+                @OneToMany(mappedBy = "order")
+                private List<OrderItem> items;
+                @ManyToMany(fetch = FetchType.EAGER)
+                private Set<Tag> tags;
+
+                @Override
+                public boolean equals(Object o) { return o instanceof Order && Objects.equals(id, ((Order) o).id); }
+
+                @Override
+                public int hashCode() { return Objects.hash(id, items, tags); }
+            }
+
+            @Entity @Data
+            class Customer {
+                @Id private Long id;
+                @OneToMany private List<Address> addresses;
+            }
+
+            @Document(collection = "carts")
+            @EqualsAndHashCode(exclude = {"lines"})
+            class Cart {
+                private String id;
+                private List<Line> lines;
+            }
+
+            @Entity
+            @Data
+            @EqualsAndHashCode(onlyExplicitlyIncluded = true)
+            class Invoice {
+                @EqualsAndHashCode.Include private Long id;
+                @OneToMany private List<InvoiceLine> lines;
+            }
+
+            @Entity
+            @Data
+            class Product {
+                @Id private Long id;
+                @EqualsAndHashCode.Exclude @OneToMany private List<Sku> skus;
+            }
+
+            @Data
+            class OrderDto {
+                private List<String> items;
+            }
+        "#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("Order.java")).unwrap();
+        let found: Vec<_> = issues.iter()
+            .filter(|i| i.id == "ENTITY_EQUALS_COLLECTION")
+            .map(|i| (i.line, i.context.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(found, vec![
+            (13, "hashCode() 包含集合字段: items (懒加载), tags"),
+            (17, "@Data 包含集合字段: addresses (懒加载)"),
+        ]);
+    }
+
+    #[test]
+    fn test_sync_collection_misuse() {
+        let code = r#"
+public class Registry {
+    private final Map<String, Session> sessions = Collections.synchronizedMap(new HashMap<>());
+    private final List<Listener> listeners = Collections.synchronizedList(new ArrayList<>());
+    private final List<String> log = Collections.synchronizedList(new ArrayList<>());
+    private final Set<String> ids = Collections.synchronizedSet(new HashSet<>());
+
+    void open(String id) {
+        if (!sessions.containsKey(id)) {
+            sessions.put(id, new Session());
+        }
+    }
+    void expire() {
+        for (String key : sessions.keySet()) { close(key); }
+    }
+    void subscribe(Listener l) { listeners.add(l); }
+    void fire(Event e) {
+        for (Listener l : listeners) { l.on(e); }
+        listeners.stream().count();
+    }
+    void append(String line) {
+        log.add(line);
+        synchronized (log) {
+            for (String s : log) { print(s); }
+        }
+        ids.forEach(this::print);
+    }
+}
+"#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("Registry.java")).unwrap();
+        let found: Vec<_> = issues.iter()
+            .filter(|i| i.id == "SYNC_COLLECTION_MISUSE")
+            .map(|i| (i.line, i.context.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(found, vec![
+            (3, "sessions = Collections.synchronizedMap: 未加锁遍历 1 处 (L14)，先查后写 1 处 (L9)，写入 1 处 → 建议 ConcurrentHashMap，先查后写改为 computeIfAbsent/putIfAbsent"),
+            (4, "listeners = Collections.synchronizedList: 未加锁遍历 2 处 (L18, L19)，写入 1 处 → 建议 CopyOnWriteArrayList (写少读多)"),
+        ]);
+    }
+
+    #[test]
+    fn test_reactive_in_loop() {
+        let code = r#"
+import reactor.core.publisher.Flux;
+import reactor.core.publisher.Mono;
+
+public class Notifier {
+    private final WebClient webClient;
+
+    void notifyAll(List<Long> ids) {
+        for (Long id : ids) {
+            webClient.post().uri("/notify/{id}", id).retrieve().bodyToMono(Void.class).subscribe();
+        }
+        for (int i = 0; i < 5000; i++) {
+            Mono.fromCallable(() -> load(i)).block();
+        }
+        Flux.fromIterable(ids).flatMap(id -> webClient.get().retrieve().bodyToMono(String.class), 8).subscribe();
+        for (Long id : ids) {
+            future.get().subscribe();
+        }
+    }
+}
+"#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("Notifier.java")).unwrap();
+        let found: Vec<_> = issues.iter()
+            .filter(|i| i.id == "REACTIVE_IN_LOOP")
+            .map(|i| (i.line, i.severity, i.context.as_deref().unwrap_or("").split(':').next().unwrap_or("").to_string()))
+            .collect();
+        assert_eq!(found, vec![
+            (10, Severity::P1, "webClient...subscribe() 位于 for-each 循环内".to_string()),
+            (13, Severity::P0, "Mono...block() 位于 for 循环内".to_string()),
+        ]);
+
+        // 未引入 Reactor 的文件不检查
+        let plain = code.replace("import reactor.core.publisher.", "import com.acme.");
+        let issues = analyzer.analyze(&plain, &PathBuf::from("Notifier.java")).unwrap();
+        assert!(issues.iter().all(|i| i.id != "REACTIVE_IN_LOOP"));
+    }
+
+    #[test]
+    fn test_sql_plan_rules() {
+        let code = r#"
+public class OrderDao {
+    private static final String PURGE = "DELETE FROM order_audit";
+
+    @Query("SELECT o FROM Order o WHERE o.status = :status ORDER BY o.createdAt DESC")
+    List<Order> findByStatus(String status);
+
+    @Query("SELECT o FROM Order o WHERE o.userId = :userId ORDER BY o.createdAt DESC")
+    Page<Order> findByUser(Long userId, Pageable pageable);
+
+    public List<Map<String, Object>> report(String day) {
+        return jdbc.queryForList("SELECT o.id, u.name FROM orders o, users u "
+            + "WHERE DATE(o.created_at) = ?", day);
+    }
+
+    public void close(Long id) {
+        jdbc.update("UPDATE orders SET status = 'CLOSED' WHERE id = ?", id);
+        StringBuilder sql = new StringBuilder();
+        sql.append("UPDATE orders SET status = 'CLOSED'");
+        sql.append(" WHERE id = ?");
+    }
+}
+"#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("OrderDao.java")).unwrap();
+        let mut found: Vec<_> = issues.iter()
+            .filter(|i| i.id.starts_with("SQL_"))
+            .map(|i| (i.id.as_str(), i.line))
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![
+            ("SQL_CARTESIAN_JOIN", 12),
+            ("SQL_FUNCTION_ON_COLUMN", 12),
+            ("SQL_ORDER_BY_NO_LIMIT", 5),
+            ("SQL_UPDATE_NO_WHERE", 3),
+        ]);
+        let cartesian = issues.iter().find(|i| i.id == "SQL_CARTESIAN_JOIN").unwrap();
+        assert!(cartesian.context.as_deref().unwrap_or("").contains("orders AS o 与 users AS u"), "{:?}", cartesian.context);
+    }
+
+    #[test]
+    fn test_controller_pagination_and_request_body() {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let mut table = SymbolTable::new();
+        let dtos = r#"
+public class BatchRequest {
+    private List<Long> ids;
+    @Size(max = 100)
+    private List<String> tags;
+}
+"#;
+        let (_, bindings, _) = analyzer.extract_symbols(dtos, &PathBuf::from("BatchRequest.java")).unwrap();
+        for binding in bindings {
+            table.register_field("BatchRequest", binding);
+        }
+        table.register_field("OrderQuery", VarBinding::new("pageSize", "int", true));
+        assert_eq!(table.fields_of("BatchRequest")[1].annotations, vec!["Size"]);
+
+        let code = r#"
+@RestController
+@RequestMapping("/api/orders")
+public class OrderController {
+    @GetMapping
+    public List<OrderDto> list(@RequestParam String status) { return null; }
+
+    @GetMapping("/paged")
+    public Page<OrderDto> paged(Pageable pageable) { return null; }
+
+    @GetMapping("/recent")
+    public ResponseEntity<List<OrderDto>> recent(@RequestParam int limit) { return null; }
+
+    @RequestMapping(value = "/search", method = RequestMethod.POST)
+    public ResponseEntity<List<OrderDto>> search(@RequestBody OrderQuery query) { return null; }
+
+    @PostMapping("/batch")
+    public void batch(@RequestBody List<Long> ids, @RequestBody @Size(max = 50) Set<Long> capped) {}
+
+    @PutMapping("/bulk")
+    public void bulk(@Valid @RequestBody BatchRequest request) {}
+
+    @GetMapping("/{id}")
+    public OrderDto get(@PathVariable Long id) { return null; }
+}
+"#;
+        let issues = analyzer.analyze_with_context(code, &PathBuf::from("OrderController.java"), Some(&table), None).unwrap();
+        let found = |id: &str| -> Vec<(usize, String)> {
+            issues.iter().filter(|i| i.id == id).map(|i| (i.line, i.context.clone().unwrap_or_default())).collect()
+        };
+        let unpaged = found("UNPAGED_LIST_ENDPOINT");
+        assert_eq!(unpaged.len(), 1, "{unpaged:?}");
+        assert_eq!(unpaged[0].0, 6);
+        assert!(unpaged[0].1.starts_with("GET /api/orders 返回 List<OrderDto>"), "{}", unpaged[0].1);
+
+        let bodies = found("UNBOUNDED_REQUEST_BODY");
+        assert_eq!(bodies.len(), 2, "{bodies:?}");
+        assert!(bodies[0].0 == 18 && bodies[0].1.starts_with("POST /api/orders/batch: 请求体 List<Long> ids"), "{:?}", bodies[0]);
+        assert!(bodies[1].0 == 21 && bodies[1].1.contains("ids (List<Long>)") && !bodies[1].1.contains("tags"), "{:?}", bodies[1]);
+
+        // 非 Controller 类不报告分页
+        let service = "public class OrderService {\n    @GetMapping\n    public List<Order> all() { return null; }\n}\n";
+        let issues = analyzer.analyze(service, &PathBuf::from("OrderService.java")).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "UNPAGED_LIST_ENDPOINT"));
+    }
+
+    #[test]
+    fn test_find_by_id_in_loop() {
+        let code = r#"
+public class OrderService {
+    void settle(List<Long> ids) {
+        for (Long id : ids) {
+            Order order = orderRepository.findById(id).orElseThrow();
+        }
+        for (Item item : cart.getItems()) {
+            Product p = productRepository.findById(item.getProductId()).get();
+        }
+        List<User> users = ids.stream().map(id -> userRepository.findById(id).get()).collect(toList());
+        for (int i = 0; i < ids.size(); i++) {
+            orderRepository.findById(ids.get(i)).orElseThrow();
+        }
+    }
+
+    Order one(Long id) {
+        Supplier<Order> lazy = () -> orderRepository.findById(id).get();
+        return orderRepository.findById(id).orElseThrow();
+    }
+}
+"#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("OrderService.java")).unwrap();
+        let found: Vec<(usize, String)> = issues.iter()
+            .filter(|i| i.id == "FIND_BY_ID_IN_LOOP")
+            .map(|i| (i.line, i.context.clone().unwrap_or_default()))
+            .collect();
+        assert_eq!(found.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![5, 8, 10, 12], "{found:?}");
+        assert!(found[0].1.contains("改为循环外 orderRepository.findAllById(ids) 批量查询"), "{}", found[0].1);
+        assert!(found[1].1.contains("先收集各元素的 item.getProductId()"), "{}", found[1].1);
+        assert!(found[2].1.contains("userRepository.findAllById(ids)"), "{}", found[2].1);
+        assert!(found[3].1.contains("findAllById(ids)"), "{}", found[3].1);
+        assert!(issues.iter().filter(|i| i.id == "FIND_BY_ID_IN_LOOP").all(|i| i.confidence == Some(super::super::Confidence::Medium)));
+    }
+
+    #[test]
+    fn test_deterministic_fix_patches() {
+        let code = r#"
+public class Report {
+    private static final SimpleDateFormat FMT = new SimpleDateFormat("yyyy-MM-dd");
+
+    String render(List<Row> rows, Date day) {
+        log.info("rows=" + rows.size() + " day=" + FMT.format(day));
+        log.debug(prefix + count + " items");
+        String out = "";
+        for (Row row : rows) {
+            out += row.name();
+        }
+        int total = 0;
+        for (Row row : rows) {
+            total += row.size();
+        }
+        return out;
+    }
+}
+"#;
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("Report.java")).unwrap();
+        let patch = |id: &str, line: usize| {
+            issues.iter().find(|i| i.id == id && i.line == line).unwrap_or_else(|| panic!("{id}@{line}: {issues:?}")).patch.clone()
+        };
+
+        let log = patch("LOG_STRING_CONCAT", 6).unwrap();
+        assert!(log.contains(
+            "-        log.info(\"rows=\" + rows.size() + \" day=\" + FMT.format(day));\n\
+             +        log.info(\"rows={} day={}\", rows.size(), FMT.format(day));\n"
+        ), "{log}");
+        // 前两个操作数都不是字面量: prefix + count 可能是数值加法，不改写
+        assert_eq!(patch("LOG_STRING_CONCAT", 7), None);
+
+        let date = patch("SIMPLE_DATE_FORMAT", 3).unwrap();
+        assert!(date.contains("+    private static final ThreadLocal<SimpleDateFormat> FMT = ThreadLocal.withInitial(() -> new SimpleDateFormat(\"yyyy-MM-dd\"));\n"), "{date}");
+        assert!(date.contains("FMT.get().format(day)"), "{date}");
+
+        let concat = patch("STRING_CONCAT_LOOP", 10).unwrap();
+        let expected = [
+            "+        StringBuilder outBuilder = new StringBuilder(out);",
+            "         for (Row row : rows) {",
+            "-            out += row.name();",
+            "+            outBuilder.append(row.name());",
+            "         }",
+            "+        out = outBuilder.toString();",
+        ].join("\n");
+        assert!(concat.contains(&expected), "{concat}");
+        // total 不是 String
+        assert_eq!(patch("STRING_CONCAT_LOOP", 14), None);
+    }
+
+    #[test]
+    fn test_issue_column_is_char_based() {
+        // 行内前缀包含多字节字符时，列号按字符计算而非字节
+        let code = "public class Test {\n    void f() { String s = \"中文\"; Executors.newCachedThreadPool(); }\n}\n";
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let issues = analyzer.analyze(code, &PathBuf::from("Test.java")).unwrap();
+        let issue = issues.iter().find(|i| i.id == "UNBOUNDED_POOL").expect("UNBOUNDED_POOL expected");
+
+        let line_text = code.lines().nth(issue.line - 1).unwrap();
+        let expected = line_text[..line_text.find("Executors").unwrap()].chars().count();
+        assert_eq!(issue.column, expected);
+    }
+
     #[test]
     fn test_extract_imports() {
         let code = r#"
@@ -1623,7 +3291,7 @@ mod tests {
             public class Test {}
         "#;
 
-        let language = tree_sitter_java::language();
+        let language = grammar::language();
         let mut parser = tree_sitter::Parser::new();
         parser.set_language(&language).unwrap();
         let tree = parser.parse(code, None).unwrap();
@@ -1725,6 +3393,69 @@ mod tests {
         assert!(import_index.local_classes.contains(&"SimpleClass".to_string()));
     }
 
+    #[test]
+    fn test_extract_symbols_interface_and_annotation_type() {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let repository = r#"
+            package com.shop;
+            @Repository
+            public interface OrderRepository extends JpaRepository<Order, Long> {}
+        "#;
+        let (info, _, _) = analyzer.extract_symbols(repository, &PathBuf::from("OrderRepository.java")).unwrap();
+        let info = info.expect("interface should register a TypeInfo");
+        assert_eq!(info.fqn, "com.shop.OrderRepository");
+        assert!(info.annotations.contains(&"Repository".to_string()));
+        assert!(info.supertypes.contains(&"JpaRepository".to_string()));
+
+        let annotation = r#"
+            package com.shop;
+            @Documented
+            @Retention(RetentionPolicy.RUNTIME)
+            public @interface Audited { String value() default ""; }
+        "#;
+        let (info, _, _) = analyzer.extract_symbols(annotation, &PathBuf::from("Audited.java")).unwrap();
+        let info = info.expect("annotation type should register a TypeInfo");
+        assert_eq!(info.fqn, "com.shop.Audited");
+        assert!(info.annotations.contains(&"Retention".to_string()));
+
+        // package-info 只有包级注解，不登记类型
+        let package_info = "@NonNullApi\npackage com.shop;\nimport org.springframework.lang.NonNullApi;\n";
+        let (info, _, import_index) = analyzer.extract_symbols(package_info, &PathBuf::from("package-info.java")).unwrap();
+        assert!(info.is_none());
+        assert_eq!(import_index.package.as_deref(), Some("com.shop"));
+    }
+
+    #[test]
+    fn test_bom_prefixed_source_matches_stripped() {
+        let code = r#"package com.shop;
+
+public class OrderService {
+    public void load(List<Long> ids) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+    }
+}
+"#;
+        let with_bom = format!("\u{FEFF}{code}");
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let file = PathBuf::from("OrderService.java");
+
+        let (info, _, _) = analyzer.extract_symbols(&with_bom, &file).unwrap();
+        assert_eq!(info.unwrap().fqn, "com.shop.OrderService");
+        assert_eq!(analyzer.extract_package(&with_bom).unwrap().as_deref(), Some("com.shop"));
+
+        let positions = |code: &str| analyzer.analyze_with_context(code, &file, None, None).unwrap()
+            .into_iter().map(|i| (i.id, i.line, i.column)).collect::<Vec<_>>();
+        assert!(!positions(code).is_empty());
+        assert_eq!(positions(&with_bom), positions(code));
+
+        // 首行问题的列号不受 BOM 影响
+        let one_line = "public class Lock { public synchronized void run() {} }";
+        assert_eq!(positions(one_line).len(), 1);
+        assert_eq!(positions(&format!("\u{FEFF}{one_line}")), positions(one_line));
+    }
+
     // ====== Property-Based Tests for v9.6 ======
 
     use proptest::prelude::*;
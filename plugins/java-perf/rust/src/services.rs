@@ -0,0 +1,287 @@
+//! Monorepo 服务发现 (`scan --per-service`)
+//!
+//! 一个仓库内有多个可独立部署的服务时 (如 WebFlux 网关 + 若干 Servlet 服务)，
+//! 合并报告会混用各服务的技术栈。这里零配置识别服务模块并逐个扫描，
+//! 每个服务按自己的依赖检测技术栈、调整规则，输出独立的报告。
+//!
+//! 服务判定 (按模块目录):
+//! - Maven: `<build><plugins>` 声明 spring-boot / quarkus / micronaut 的 maven 插件
+//!   (`<pluginManagement>` 中的声明只是版本管理，不算)
+//! - Gradle: 应用 `org.springframework.boot`、`io.quarkus`、`io.micronaut.application`
+//!   或 `application` 插件
+//! - 其它: `src/main/java` 下有 `public static void main(String[] args)`
+//!
+//! 不属于任何服务的模块 (公共库) 列在 `shared` 中，不单独出报告。逐服务扫描时
+//! 进入公共库的调用链无法解析，跨模块问题仍需对整个仓库执行一次普通 scan。
+
+use std::fs;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+
+use crate::analysis::AnalysisReport;
+use crate::ast_engine::{self, ScanOptions};
+use crate::density;
+use crate::project_detector::detect_modules;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 声明为可部署应用的 Maven 插件
+const MAVEN_APP_PLUGINS: &[&str] = &["spring-boot-maven-plugin", "quarkus-maven-plugin", "micronaut-maven-plugin"];
+
+static GRADLE_APP_PLUGIN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)(?:id\s*\(?\s*|apply\s+plugin\s*:\s*)["'](org\.springframework\.boot|io\.quarkus|io\.micronaut\.application|application)["']|^\s*(application)\s*$"#).unwrap()
+});
+
+static MAIN_METHOD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"public\s+static\s+void\s+main\s*\(\s*(?:final\s+)?String").unwrap()
+});
+
+/// 判定为服务的依据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum ServiceMarker {
+    /// 构建插件 (如 spring-boot-maven-plugin)
+    BuildPlugin(String),
+    /// 含 main 方法的类 (简单名)
+    MainClass(String),
+}
+
+impl std::fmt::Display for ServiceMarker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceMarker::BuildPlugin(plugin) => write!(f, "{plugin}"),
+            ServiceMarker::MainClass(class) => write!(f, "{class}.main()"),
+        }
+    }
+}
+
+/// 可独立部署的服务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Service {
+    /// 服务名 (模块目录名)
+    pub name: String,
+    /// 相对仓库根的目录 ('/' 分隔，单模块项目为 ".")
+    pub path: String,
+    pub marker: ServiceMarker,
+}
+
+/// 发现结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Discovery {
+    pub services: Vec<Service>,
+    /// 不属于任何服务的模块 (公共库)
+    pub shared: Vec<String>,
+}
+
+/// 识别仓库内的服务模块
+pub fn discover(root: &Path) -> Discovery {
+    let modules = detect_modules(root);
+    if modules.is_empty() {
+        let name = root.canonicalize().ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| ".".to_string());
+        let services = service_marker(root)
+            .map(|marker| Service { name, path: ".".to_string(), marker })
+            .into_iter()
+            .collect();
+        return Discovery { services, shared: Vec::new() };
+    }
+
+    let marked: Vec<(String, ServiceMarker)> = modules.iter()
+        .filter_map(|module| service_marker(&root.join(module)).map(|marker| (module.clone(), marker)))
+        .collect();
+    let contains = |outer: &str, inner: &str| inner.starts_with(&format!("{outer}/"));
+
+    // 嵌套时保留最内层的服务模块
+    let services: Vec<Service> = marked.iter()
+        .filter(|(path, _)| !marked.iter().any(|(other, _)| contains(path, other)))
+        .map(|(path, marker)| Service {
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: path.clone(),
+            marker: marker.clone(),
+        })
+        .collect();
+    // 公共库: 既不是服务、也不是服务的上级聚合模块或子模块
+    let shared = modules.into_iter()
+        .filter(|module| !services.iter().any(|s| s.path == *module || contains(module, &s.path) || contains(&s.path, module)))
+        .collect();
+    Discovery { services, shared }
+}
+
+/// 模块目录的服务判定依据
+fn service_marker(dir: &Path) -> Option<ServiceMarker> {
+    if let Ok(pom) = fs::read_to_string(dir.join("pom.xml")) {
+        let active = strip_plugin_management(&pom);
+        if let Some(plugin) = MAVEN_APP_PLUGINS.iter().find(|p| active.contains(*p)) {
+            return Some(ServiceMarker::BuildPlugin(plugin.to_string()));
+        }
+    }
+    for script in ["build.gradle", "build.gradle.kts"] {
+        let Ok(content) = fs::read_to_string(dir.join(script)) else { continue };
+        if let Some(caps) = GRADLE_APP_PLUGIN.captures(&content) {
+            let plugin = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+            return Some(ServiceMarker::BuildPlugin(plugin.to_string()));
+        }
+    }
+    main_class(&dir.join("src/main/java")).map(ServiceMarker::MainClass)
+}
+
+/// 去掉 `<pluginManagement>` 段 (只管理版本，不生效)
+fn strip_plugin_management(pom: &str) -> String {
+    let mut rest = pom;
+    let mut out = String::with_capacity(pom.len());
+    while let Some(start) = rest.find("<pluginManagement>") {
+        out.push_str(&rest[..start]);
+        match rest[start..].find("</pluginManagement>") {
+            Some(end) => rest = &rest[start + end + "</pluginManagement>".len()..],
+            None => return out,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 源码目录中第一个含 main 方法的类 (按路径排序)
+fn main_class(src: &Path) -> Option<String> {
+    if !src.is_dir() {
+        return None;
+    }
+    WalkDir::new(src)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().and_then(|x| x.to_str()) == Some("java"))
+        .find(|e| fs::read_to_string(e.path()).is_ok_and(|code| MAIN_METHOD.is_match(&code)))
+        .and_then(|e| e.path().file_stem().map(|s| s.to_string_lossy().into_owned()))
+}
+
+/// 单个服务的分析结果
+#[derive(Debug)]
+pub struct ServiceReport {
+    pub service: Service,
+    pub analysis: AnalysisReport,
+}
+
+/// 逐服务扫描
+pub fn analyze_services(root: &str, options: &ScanOptions) -> Result<(Vec<ServiceReport>, Vec<String>), BoxError> {
+    let discovery = discover(Path::new(root));
+    if discovery.services.is_empty() {
+        return Err(format!(
+            "未在 {root} 中发现可独立部署的服务 (spring-boot/quarkus/micronaut 构建插件或 main 方法)，请直接使用 scan"
+        ).into());
+    }
+    let mut reports = Vec::with_capacity(discovery.services.len());
+    for service in discovery.services {
+        let dir = Path::new(root).join(&service.path);
+        let analysis = ast_engine::analyze_project(&dir.to_string_lossy(), options)?;
+        reports.push(ServiceReport { service, analysis });
+    }
+    Ok((reports, discovery.shared))
+}
+
+/// 结构化 JSON: 每个服务一份完整报告 (同 `--json scan`)
+pub fn render_json(reports: &[ServiceReport], shared: &[String], options: &ScanOptions) -> Value {
+    let services: Vec<Value> = reports.iter()
+        .map(|r| {
+            let mut value = ast_engine::render_json(&r.analysis, options);
+            value["service"] = json!(r.service);
+            value
+        })
+        .collect();
+    json!({ "services": services, "shared": shared })
+}
+
+/// Markdown: 服务总览表 + 每个服务的报告
+pub fn render_report(reports: &[ServiceReport], shared: &[String], compact: bool, max_p1: usize, options: &ScanOptions) -> Value {
+    let mut out = format!("# 🧩 按服务扫描 ({} 个服务)\n\n", reports.len());
+    // 问题/KLoC 让大小不同的服务可以直接比较
+    out.push_str("| 服务 | 目录 | 依据 | 技术栈 | P0 | P1 | LOC | 问题/KLoC |\n|------|------|------|--------|----|----|-----|-----------|\n");
+    for r in reports {
+        let scan = &r.analysis.scan;
+        let stack = r.analysis.stack.as_ref().map_or("-", |s| s.framework());
+        out.push_str(&format!(
+            "| {} | `{}` | {} | {} | {} | {} | {} | {:.2} |\n",
+            r.service.name, r.service.path, r.service.marker, stack, scan.p0_count(), scan.p1_count(),
+            scan.loc.total(), density::per_kloc(scan.issues.len(), scan.loc.total())
+        ));
+    }
+    if !shared.is_empty() {
+        out.push_str(&format!("\n*公共模块未单独扫描: {}*\n", shared.join(", ")));
+    }
+    for r in reports {
+        out.push_str(&format!("\n---\n\n# {} (`{}`)\n\n", r.service.name, r.service.path));
+        let report = ast_engine::render_report(&r.analysis, compact, max_p1, options);
+        out.push_str(report.as_str().unwrap_or_default());
+    }
+    json!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let file = root.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+
+    #[test]
+    fn test_discover_maven_services() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(root, "pom.xml", r#"<project><modules><module>common</module><module>services</module></modules>
+            <build><pluginManagement><plugins><plugin><artifactId>spring-boot-maven-plugin</artifactId></plugin></plugins></pluginManagement></build>
+            </project>"#);
+        write(root, "common/pom.xml", "<project></project>");
+        write(root, "services/pom.xml", "<project><modules><module>order</module><module>gateway</module><module>batch</module></modules></project>");
+        write(root, "services/order/pom.xml",
+            "<project><build><plugins><plugin><artifactId>spring-boot-maven-plugin</artifactId></plugin></plugins></build></project>");
+        write(root, "services/gateway/pom.xml",
+            "<project><build><plugins><plugin><artifactId>quarkus-maven-plugin</artifactId></plugin></plugins></build></project>");
+        write(root, "services/batch/pom.xml", "<project></project>");
+        write(root, "services/batch/src/main/java/com/acme/BatchMain.java",
+            "public class BatchMain {\n    public static void main(String[] args) {}\n}\n");
+
+        let discovery = discover(root);
+        let found: Vec<_> = discovery.services.iter().map(|s| (s.name.as_str(), s.marker.to_string())).collect();
+        assert_eq!(found, vec![
+            ("order", "spring-boot-maven-plugin".to_string()),
+            ("gateway", "quarkus-maven-plugin".to_string()),
+            ("batch", "BatchMain.main()".to_string()),
+        ]);
+        assert_eq!(discovery.services[0].path, "services/order");
+        assert_eq!(discovery.shared, vec!["common"], "聚合模块 services 不算公共库");
+    }
+
+    #[test]
+    fn test_discover_gradle_plugins() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(root, "settings.gradle.kts", "include(\":api\", \":worker\", \":lib\")\n");
+        write(root, "api/build.gradle.kts", "plugins {\n    java\n    id(\"org.springframework.boot\") version \"3.2.1\"\n}\n");
+        write(root, "worker/build.gradle", "plugins {\n    id 'java'\n    id 'application'\n}\n");
+        write(root, "lib/build.gradle.kts",
+            "dependencies {\n    implementation(platform(\"org.springframework.boot:spring-boot-dependencies:3.2.1\"))\n}\n");
+
+        let discovery = discover(root);
+        let found: Vec<_> = discovery.services.iter().map(|s| (s.path.as_str(), s.marker.to_string())).collect();
+        assert_eq!(found, vec![("api", "org.springframework.boot".to_string()), ("worker", "application".to_string())]);
+        assert_eq!(discovery.shared, vec!["lib"], "BOM 引用不是插件");
+    }
+
+    #[test]
+    fn test_single_module_without_service_is_error() {
+        let dir = tempdir().unwrap();
+        write(dir.path(), "src/main/java/Util.java", "public class Util {}\n");
+        assert!(discover(dir.path()).services.is_empty());
+        let err = analyze_services(&dir.path().to_string_lossy(), &ScanOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("未在"), "{err}");
+    }
+}
@@ -0,0 +1,289 @@
+//! 共享文件路径 ([`FilePath`])
+//!
+//! 项目索引中每个类型、字符串常量、调用点，以及每个问题都带着所在文件的路径，大型项目的调用图有几十万条
+//! 调用边，每条各克隆一份 `PathBuf` 在 profile 中很显眼。[`FilePath`] 是共享的 `Arc<Utf8Path>`
+//! (camino)：每个文件的路径在创建处 (Phase 1 的 `index_file`、Phase 2 的逐文件分析) 分配一次，
+//! 该文件的所有条目克隆同一份，克隆只增加引用计数。
+//!
+//! - 没有进程级路径表，也不加锁；路径随持有它的索引/报告释放 (daemon 反复重建索引不会累积)。
+//!   最初设想的全局路径 id 表 (路径 → u32，条目只存 id) 没有采用: 表只增不减，daemon 每次
+//!   rescan 都会留下已删除/改名文件的路径，且 Phase 1 并发写入需要加锁；而同一文件的条目
+//!   本来就在同一处创建，共享一个 `Arc` 已做到每个路径只分配一次，克隆的代价与复制 id 相当
+//! - 内部为 UTF-8: 从 `Path` 严格转换用 `TryFrom` (非 UTF-8 返回 [`NonUtf8Path`])；扫描使用
+//!   [`FilePath::from_path_lossy`]，非 UTF-8 的文件名 (如 GBK 编码的中文文件名) 中无法表示的字节
+//!   显示为 U+FFFD，文件照常按原路径读取与分析，并报告一条警告诊断
+//! - 序列化为路径字符串，JSON 报告格式不变；索引缓存中路径只记在文件键上 (见 symbol_cache)
+//! - 相等、哈希与排序按路径字符串 (同一份分配时直接比较指针)
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 空路径共用一份分配 (`Default`)
+static EMPTY: Lazy<Arc<Utf8Path>> = Lazy::new(|| Arc::from(Utf8Path::new("")));
+
+/// 共享的文件路径
+#[derive(Clone)]
+pub struct FilePath(Arc<Utf8Path>);
+
+/// 路径不是合法 UTF-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonUtf8Path(pub PathBuf);
+
+impl fmt::Display for NonUtf8Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "路径不是 UTF-8: {}", self.0.display())
+    }
+}
+
+impl std::error::Error for NonUtf8Path {}
+
+impl FilePath {
+    pub fn new(path: &str) -> Self {
+        if path.is_empty() {
+            return Self::default();
+        }
+        Self(Arc::from(Utf8Path::new(path)))
+    }
+
+    /// 非 UTF-8 路径做有损转换 (无法表示的字节替换为 U+FFFD)，与报告中的相对路径一致
+    pub fn from_path_lossy(path: &Path) -> Self {
+        Self::new(&path.to_string_lossy())
+    }
+
+    pub fn as_path(&self) -> &Utf8Path {
+        &self.0
+    }
+
+    pub fn as_std_path(&self) -> &Path {
+        self.0.as_std_path()
+    }
+}
+
+impl Default for FilePath {
+    fn default() -> Self {
+        Self(EMPTY.clone())
+    }
+}
+
+impl Deref for FilePath {
+    type Target = Utf8Path;
+
+    fn deref(&self) -> &Utf8Path {
+        self.as_path()
+    }
+}
+
+impl AsRef<Path> for FilePath {
+    fn as_ref(&self) -> &Path {
+        self.as_std_path()
+    }
+}
+
+impl AsRef<Utf8Path> for FilePath {
+    fn as_ref(&self) -> &Utf8Path {
+        &self.0
+    }
+}
+
+impl AsRef<str> for FilePath {
+    fn as_ref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+/// 集合按 `&str` 查找 (哈希与 str 一致)
+impl Borrow<str> for FilePath {
+    fn borrow(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl From<&str> for FilePath {
+    fn from(path: &str) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<String> for FilePath {
+    fn from(path: String) -> Self {
+        Self::new(&path)
+    }
+}
+
+impl From<&String> for FilePath {
+    fn from(path: &String) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<&Utf8Path> for FilePath {
+    fn from(path: &Utf8Path) -> Self {
+        Self::new(path.as_str())
+    }
+}
+
+impl From<Utf8PathBuf> for FilePath {
+    fn from(path: Utf8PathBuf) -> Self {
+        Self::new(path.as_str())
+    }
+}
+
+impl From<&FilePath> for FilePath {
+    fn from(path: &FilePath) -> Self {
+        path.clone()
+    }
+}
+
+impl TryFrom<&Path> for FilePath {
+    type Error = NonUtf8Path;
+
+    fn try_from(path: &Path) -> Result<Self, NonUtf8Path> {
+        path.to_str().map(Self::new).ok_or_else(|| NonUtf8Path(path.to_path_buf()))
+    }
+}
+
+impl TryFrom<&PathBuf> for FilePath {
+    type Error = NonUtf8Path;
+
+    fn try_from(path: &PathBuf) -> Result<Self, NonUtf8Path> {
+        Self::try_from(path.as_path())
+    }
+}
+
+impl TryFrom<PathBuf> for FilePath {
+    type Error = NonUtf8Path;
+
+    fn try_from(path: PathBuf) -> Result<Self, NonUtf8Path> {
+        Self::try_from(path.as_path())
+    }
+}
+
+impl PartialEq for FilePath {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl Eq for FilePath {}
+
+impl Hash for FilePath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_str().hash(state);
+    }
+}
+
+impl PartialEq<Path> for FilePath {
+    fn eq(&self, other: &Path) -> bool {
+        self.as_std_path() == other
+    }
+}
+
+impl PartialEq<str> for FilePath {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for FilePath {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for FilePath {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl Ord for FilePath {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return Ordering::Equal;
+        }
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for FilePath {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for FilePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for FilePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for FilePath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for FilePath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        Ok(Self::new(&path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_shared_path_keeps_json_shape() {
+        let a = FilePath::try_from(PathBuf::from("src/main/java/OrderService.java")).unwrap();
+        let b = a.clone();
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(a, FilePath::from("src/main/java/OrderService.java"));
+        assert_eq!(a.file_name(), Some("OrderService.java"));
+        assert_eq!(a.as_std_path(), Path::new("src/main/java/OrderService.java"));
+        assert_eq!(FilePath::from(""), FilePath::default());
+
+        let set: HashSet<FilePath> = HashSet::from([a.clone()]);
+        assert!(set.contains("src/main/java/OrderService.java"));
+        assert!(!set.contains("src/main/java/Missing.java"));
+
+        let json = serde_json::to_string(&vec![a.clone(), FilePath::default()]).unwrap();
+        assert_eq!(json, r#"["src/main/java/OrderService.java",""]"#);
+        let back: Vec<FilePath> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, vec![a, FilePath::default()]);
+
+        let (y, z) = (FilePath::from("a/Y.java"), FilePath::from("a/Z.java"));
+        assert!(y < z);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path_strict_and_lossy() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"src/Caf\xe9.java"));
+        let err = FilePath::try_from(path).unwrap_err();
+        assert_eq!(err.0, path);
+        assert!(err.to_string().contains("UTF-8"));
+        assert_eq!(FilePath::from_path_lossy(path), "src/Caf\u{FFFD}.java");
+        assert_eq!(FilePath::from_path_lossy(Path::new("src/A.java")), "src/A.java");
+    }
+}
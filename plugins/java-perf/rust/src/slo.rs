@@ -0,0 +1,515 @@
+//! 延迟预算 (`slo.yaml`，位于扫描根目录或由 `scan --slo FILE` 指定)
+//!
+//! ```yaml
+//! budgets:
+//!   # HTTP 接口 (Spring @GetMapping/@PostMapping/@RequestMapping，类级 @RequestMapping 前缀)
+//!   - endpoint: GET /api/orders/{id}
+//!     latency_ms: 80
+//!   # 省略 HTTP 方法时匹配该路径的所有方法
+//!   - endpoint: /api/reports
+//!     latency_ms: 2000
+//!   # 类或 类#方法 (简单名或全限定名)
+//!   - target: com.shop.PaymentController#pay
+//!     latency_ms: 150
+//! ```
+//!
+//! 每条预算解析为一组入口方法，沿 CallGraph 做 BFS (深度同热度，见 hotness 模块) 得到预算覆盖的方法。
+//! 问题所在方法被多个预算覆盖时取最紧的预算写入 `budget` (目标、毫秒数与调用距离)：
+//! `--sort budget` 按预算从紧到松排列，报告列出各预算下的问题数，未匹配到入口的预算单独提示。
+//! 路径变量按位置比较 (`{id}` 与 `{orderId}` 等价)，尾部 `/` 不影响匹配。
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::hotness::{self, MethodSpan};
+use crate::scanner::node_walk::{NodeWalk, TYPE_DECLARATIONS};
+use crate::symbol_table::SymbolTable;
+use crate::taint::{CallGraph, MethodSig};
+
+/// 默认配置文件名 (扫描根目录)
+pub const SLO_FILE: &str = "slo.yaml";
+
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+/// 延迟预算配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SloConfig {
+    #[serde(default)]
+    pub budgets: Vec<Budget>,
+}
+
+/// 单条预算 (`endpoint` 与 `target` 二选一)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    /// HTTP 接口，如 `GET /api/orders/{id}`
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// 类或 `类#方法`
+    #[serde(default)]
+    pub target: Option<String>,
+    pub latency_ms: u64,
+}
+
+impl Budget {
+    /// 报告中显示的预算目标
+    pub fn label(&self) -> &str {
+        self.endpoint.as_deref().or(self.target.as_deref()).unwrap_or_default()
+    }
+}
+
+/// 读取延迟预算：显式指定的文件必须存在；未指定时读取根目录下的 slo.yaml (不存在时为 None)
+pub fn load(root: &Path, explicit: Option<&Path>) -> Result<Option<SloConfig>, String> {
+    let path: PathBuf = match explicit {
+        Some(path) => path.to_path_buf(),
+        None if root.is_dir() && root.join(SLO_FILE).is_file() => root.join(SLO_FILE),
+        None => return Ok(None),
+    };
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let config: SloConfig = serde_yaml::from_str(&content).map_err(|e| format!("{} 格式错误: {e}", path.display()))?;
+    for budget in &config.budgets {
+        if budget.endpoint.is_some() == budget.target.is_some() {
+            return Err(format!("{}: 每条预算需且只需指定 endpoint 或 target 之一", path.display()));
+        }
+    }
+    Ok(Some(config))
+}
+
+/// Controller 方法的 HTTP 映射
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    /// 所在类型的简单名
+    pub class: String,
+    pub method: String,
+    /// HTTP 方法 (None 为 @RequestMapping 未限定方法)
+    pub http: Option<String>,
+    /// 类级前缀 + 方法路径 (已规范化)
+    pub path: String,
+}
+
+/// 文件可能含 HTTP 映射 (避免为每个文件解析)
+pub fn is_candidate(code: &str) -> bool {
+    code.contains("Mapping")
+}
+
+/// 收集文件中 Spring MVC / WebFlux 注解声明的 HTTP 映射
+pub fn collect_routes(root: Node, code: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "method_declaration" {
+            routes.extend(method_routes(node, code));
+        }
+        // 逆序入栈，按源码顺序输出
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    routes
+}
+
+fn method_routes(method: Node, code: &str) -> Vec<Route> {
+    let Some(class_node) = NodeWalk::new(code).ancestor_of_kind(method, TYPE_DECLARATIONS) else { return Vec::new() };
+    let text = |n: Node| n.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+    let name = method.child_by_field_name("name").map(text).unwrap_or_default();
+    let class = class_node.child_by_field_name("name").map(text).unwrap_or_default();
+    let prefixes = annotations(class_node, code).into_iter()
+        .find(|(name, _)| name == "RequestMapping")
+        .map(|(_, args)| mapping_paths(args, code))
+        .unwrap_or_else(|| vec![String::new()]);
+
+    let mut routes = Vec::new();
+    for (annotation, args) in annotations(method, code) {
+        let http: Vec<Option<String>> = match annotation.strip_suffix("Mapping") {
+            Some("Request") => {
+                let methods = request_methods(args, code);
+                if methods.is_empty() { vec![None] } else { methods.into_iter().map(Some).collect() }
+            }
+            Some(verb) if HTTP_METHODS.contains(&verb.to_uppercase().as_str()) => vec![Some(verb.to_uppercase())],
+            _ => continue,
+        };
+        for prefix in &prefixes {
+            for path in mapping_paths(args, code) {
+                for http in &http {
+                    routes.push(Route {
+                        class: class.clone(),
+                        method: name.clone(),
+                        http: http.clone(),
+                        path: normalize_path(&format!("{prefix}/{path}")),
+                    });
+                }
+            }
+        }
+    }
+    routes
+}
+
+/// 声明上的注解: (简单名, 参数列表)
+fn annotations<'t>(decl: Node<'t>, code: &str) -> Vec<(String, Option<Node<'t>>)> {
+    let mut cursor = decl.walk();
+    let Some(modifiers) = decl.children(&mut cursor).find(|n| n.kind() == "modifiers") else { return Vec::new() };
+    let mut cursor = modifiers.walk();
+    modifiers.children(&mut cursor)
+        .filter(|n| matches!(n.kind(), "annotation" | "marker_annotation"))
+        .filter_map(|n| {
+            let name = n.child_by_field_name("name")?.utf8_text(code.as_bytes()).ok()?;
+            let simple = name.rsplit('.').next().unwrap_or(name).to_string();
+            Some((simple, n.child_by_field_name("arguments")))
+        })
+        .collect()
+}
+
+/// 注解的路径参数 (位置参数或 value/path)，无路径时为 `[""]`
+fn mapping_paths(args: Option<Node>, code: &str) -> Vec<String> {
+    let Some(args) = args else { return vec![String::new()] };
+    let mut cursor = args.walk();
+    let value = args.named_children(&mut cursor).find_map(|arg| match arg.kind() {
+        "element_value_pair" => {
+            let key = arg.child_by_field_name("key")?.utf8_text(code.as_bytes()).ok()?;
+            matches!(key, "value" | "path").then(|| arg.child_by_field_name("value")).flatten()
+        }
+        _ => Some(arg),
+    });
+    let paths: Vec<String> = value.map(|v| string_values(v, code)).unwrap_or_default();
+    if paths.is_empty() { vec![String::new()] } else { paths }
+}
+
+/// `method = RequestMethod.POST` / `{RequestMethod.GET, RequestMethod.POST}`
+fn request_methods(args: Option<Node>, code: &str) -> Vec<String> {
+    let Some(args) = args else { return Vec::new() };
+    let mut cursor = args.walk();
+    let pairs: Vec<Node> = args.named_children(&mut cursor).filter(|n| n.kind() == "element_value_pair").collect();
+    pairs.into_iter()
+        .filter(|pair| pair.child_by_field_name("key").and_then(|k| k.utf8_text(code.as_bytes()).ok()) == Some("method"))
+        .filter_map(|pair| pair.child_by_field_name("value"))
+        .flat_map(|value| {
+            let text = value.utf8_text(code.as_bytes()).unwrap_or("");
+            HTTP_METHODS.iter()
+                .filter(|verb| text.split(|c: char| !c.is_ascii_alphabetic()).any(|word| word == **verb))
+                .map(|verb| verb.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// 字符串字面量或字符串数组中的值 (常量引用无法解析，忽略)
+fn string_values(node: Node, code: &str) -> Vec<String> {
+    match node.kind() {
+        "string_literal" => {
+            let text = node.utf8_text(code.as_bytes()).unwrap_or("");
+            vec![text.trim_matches('"').to_string()]
+        }
+        "element_value_array_initializer" => {
+            let mut cursor = node.walk();
+            let children: Vec<Node> = node.named_children(&mut cursor).collect();
+            children.into_iter().flat_map(|child| string_values(child, code)).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// 路径规范化：单个 `/` 分隔、去掉尾部 `/`、路径变量统一为 `{}`
+fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| if s.starts_with('{') && s.ends_with('}') { "{}" } else { s })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// 解析 `GET /api/orders` 为 (HTTP 方法, 规范化路径)
+fn parse_endpoint(endpoint: &str) -> (Option<String>, String) {
+    let endpoint = endpoint.trim();
+    match endpoint.split_once(char::is_whitespace) {
+        Some((verb, path)) if HTTP_METHODS.contains(&verb.to_uppercase().as_str()) => {
+            (Some(verb.to_uppercase()), normalize_path(path.trim()))
+        }
+        _ => (None, normalize_path(endpoint)),
+    }
+}
+
+/// 问题危及的 (最紧的) 延迟预算
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetRef {
+    /// 预算目标 (`endpoint` 或 `target` 原文)
+    pub target: String,
+    pub latency_ms: u64,
+    /// 问题所在方法到预算入口的调用距离
+    pub distance: usize,
+}
+
+/// 单条预算覆盖的方法
+#[derive(Debug)]
+struct Coverage {
+    budget: Budget,
+    /// 预算直接覆盖的整个类 (`target` 只写类名时)
+    classes: Vec<String>,
+    /// (类简单名, 方法名) -> 到入口的距离
+    reach: HashMap<(String, String), usize>,
+}
+
+impl Coverage {
+    fn distance(&self, class: &str, method: &str) -> Option<usize> {
+        if self.classes.iter().any(|c| c == class) {
+            return Some(0);
+        }
+        self.reach.get(&(class.to_string(), method.to_string())).copied()
+    }
+
+    fn matched(&self) -> bool {
+        !self.classes.is_empty() || !self.reach.is_empty()
+    }
+}
+
+/// 所有预算的覆盖范围
+#[derive(Debug, Default)]
+pub struct BudgetIndex {
+    coverage: Vec<Coverage>,
+}
+
+impl BudgetIndex {
+    pub fn build(config: &SloConfig, routes: &[Route], graph: &CallGraph, table: &SymbolTable, max_depth: usize) -> Self {
+        let coverage = config.budgets.iter()
+            .map(|budget| {
+                let (classes, entries) = entries(budget, routes);
+                let reach = reach(&entries, &classes, graph, table, max_depth);
+                Coverage { budget: budget.clone(), classes, reach }
+            })
+            .collect();
+        Self { coverage }
+    }
+
+    /// 覆盖该方法的最紧预算
+    pub fn tightest(&self, class: &str, method: &str) -> Option<BudgetRef> {
+        self.coverage.iter()
+            .filter_map(|c| c.distance(class, method).map(|distance| (c, distance)))
+            .min_by_key(|(c, distance)| (c.budget.latency_ms, *distance))
+            .map(|(c, distance)| BudgetRef { target: c.budget.label().to_string(), latency_ms: c.budget.latency_ms, distance })
+    }
+}
+
+/// 预算的入口: (整类覆盖的类, 入口方法)
+fn entries(budget: &Budget, routes: &[Route]) -> (Vec<String>, Vec<(String, String)>) {
+    if let Some(endpoint) = &budget.endpoint {
+        let (http, path) = parse_endpoint(endpoint);
+        let methods = routes.iter()
+            .filter(|r| r.path == path && (http.is_none() || r.http.is_none() || r.http == http))
+            .map(|r| (r.class.clone(), r.method.clone()))
+            .collect();
+        return (Vec::new(), methods);
+    }
+    let target = budget.target.as_deref().unwrap_or_default();
+    let simple = |class: &str| class.rsplit('.').next().unwrap_or(class).to_string();
+    match target.split_once('#') {
+        Some((class, method)) => (Vec::new(), vec![(simple(class), method.to_string())]),
+        None => (vec![simple(target)], Vec::new()),
+    }
+}
+
+/// 从入口方法 (及整类覆盖的类的所有方法) 出发的 BFS
+fn reach(
+    entries: &[(String, String)],
+    classes: &[String],
+    graph: &CallGraph,
+    table: &SymbolTable,
+    max_depth: usize,
+) -> HashMap<(String, String), usize> {
+    let key = |sig: &MethodSig| (sig.simple_class_name().to_string(), sig.name.clone());
+    let mut distances: HashMap<(String, String), usize> = entries.iter().map(|e| (e.clone(), 0)).collect();
+    let mut queue: VecDeque<(MethodSig, usize)> = graph.outgoing.keys()
+        .filter(|sig| distances.contains_key(&key(sig)) || classes.iter().any(|c| c == sig.simple_class_name()))
+        .map(|sig| (sig.clone(), 0))
+        .collect();
+    while let Some((sig, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+        for site in graph.outgoing.get(&sig).into_iter().flatten() {
+            for target in hotness::callee_targets(graph, table, site) {
+                let k = key(&target);
+                if distances.contains_key(&k) || classes.contains(&k.0) {
+                    continue;
+                }
+                distances.insert(k, depth + 1);
+                queue.push_back((target, depth + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// 为问题填充 budget：取问题行所在的最内层方法
+pub fn annotate(issues: &mut [AstIssue], spans: &HashMap<String, Vec<MethodSpan>>, index: &BudgetIndex) {
+    for issue in issues.iter_mut() {
+        let Some(span) = spans.get(issue.path.as_str()).and_then(|s| hotness::innermost(s, issue.line)) else {
+            continue;
+        };
+        issue.budget = index.tightest(&span.class, &span.method);
+    }
+}
+
+/// 单条预算下的问题统计
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetSummary {
+    pub target: String,
+    pub latency_ms: u64,
+    pub p0: usize,
+    pub p1: usize,
+    /// 找到了入口方法 (false 时检查 endpoint/target 是否拼写正确)
+    pub matched: bool,
+}
+
+/// 按预算从紧到松汇总 (问题只计入其最紧的预算)
+pub fn summarize(index: &BudgetIndex, issues: &[AstIssue]) -> Vec<BudgetSummary> {
+    let mut summaries: Vec<BudgetSummary> = index.coverage.iter()
+        .map(|c| {
+            let label = c.budget.label();
+            let count = |severity| issues.iter()
+                .filter(|i| i.severity == severity && i.budget.as_ref().is_some_and(|b| b.target == label))
+                .count();
+            BudgetSummary {
+                target: label.to_string(),
+                latency_ms: c.budget.latency_ms,
+                p0: count(Severity::P0),
+                p1: count(Severity::P1),
+                matched: c.matched(),
+            }
+        })
+        .collect();
+    summaries.sort_by_key(|s| s.latency_ms);
+    summaries
+}
+
+/// 问题行的预算标记
+pub fn format_budget(issue: &AstIssue) -> String {
+    issue.budget.as_ref().map(|b| format!(" ⏱️{}ms", b.latency_ms)).unwrap_or_default()
+}
+
+/// Markdown 报告中的延迟预算章节
+pub fn render_section(summaries: &[BudgetSummary], p0_label: &str, p1_label: &str) -> String {
+    if summaries.is_empty() {
+        return String::new();
+    }
+    let mut section = "\n### ⏱️ 延迟预算 (由紧到松)\n\n".to_string();
+    for (rank, s) in summaries.iter().enumerate() {
+        let status = if s.matched {
+            format!("{p0_label}: {}, {p1_label}: {}", s.p0, s.p1)
+        } else {
+            "未找到对应的入口方法，检查 slo.yaml 中的 endpoint/target".to_string()
+        };
+        section.push_str(&format!("{}. **{}** (≤{}ms) - {status}\n", rank + 1, s.target, s.latency_ms));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+    use crate::taint::LayerType;
+
+    fn routes_of(code: &str) -> Vec<Route> {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(code, |tree| collect_routes(tree.root_node(), code)).unwrap()
+    }
+
+    #[test]
+    fn test_collect_spring_routes() {
+        let routes = routes_of(r#"
+            @RestController
+            @RequestMapping("/api/orders/")
+            public class OrderController {
+                @GetMapping("/{orderId}")
+                public Order get(@PathVariable Long orderId) { return null; }
+                @PostMapping
+                public Order create() { return null; }
+                @RequestMapping(value = {"/search", "/find"}, method = {RequestMethod.GET, RequestMethod.POST})
+                public List<Order> search() { return null; }
+                @RequestMapping(path = "/all")
+                public List<Order> all() { return null; }
+                public void helper() {}
+            }
+        "#);
+        let routes: Vec<(String, Option<&str>, &str)> = routes.iter()
+            .map(|r| (format!("{}#{}", r.class, r.method), r.http.as_deref(), r.path.as_str()))
+            .collect();
+        assert_eq!(routes, [
+            ("OrderController#get".into(), Some("GET"), "/api/orders/{}"),
+            ("OrderController#create".into(), Some("POST"), "/api/orders"),
+            ("OrderController#search".into(), Some("GET"), "/api/orders/search"),
+            ("OrderController#search".into(), Some("POST"), "/api/orders/search"),
+            ("OrderController#search".into(), Some("GET"), "/api/orders/find"),
+            ("OrderController#search".into(), Some("POST"), "/api/orders/find"),
+            ("OrderController#all".into(), None, "/api/orders/all"),
+        ]);
+        assert_eq!(parse_endpoint("get /api/orders/{id}/"), (Some("GET".into()), "/api/orders/{}".into()));
+        assert_eq!(parse_endpoint("/api//orders"), (None, "/api/orders".into()));
+    }
+
+    #[test]
+    fn test_tightest_budget_through_call_graph() {
+        let mut graph = CallGraph::new();
+        graph.register_class("com.shop.OrderController", "", LayerType::Controller);
+        graph.register_class("com.shop.OrderService", "", LayerType::Service);
+        graph.register_class("com.shop.ReportJob", "", LayerType::Unknown);
+        graph.add_call(MethodSig::new_fqn("com.shop.OrderController", "get"), MethodSig::new_fqn("com.shop.OrderService", "load"), "", 1);
+        graph.add_call(MethodSig::new_fqn("com.shop.OrderController", "list"), MethodSig::new_fqn("com.shop.OrderService", "load"), "", 2);
+        graph.add_call(MethodSig::new_fqn("com.shop.ReportJob", "run"), MethodSig::new_fqn("com.shop.OrderService", "export"), "", 3);
+        let routes = vec![
+            Route { class: "OrderController".into(), method: "get".into(), http: Some("GET".into()), path: "/orders/{}".into() },
+            Route { class: "OrderController".into(), method: "list".into(), http: Some("GET".into()), path: "/orders".into() },
+        ];
+        let config: SloConfig = serde_yaml::from_str(
+            "budgets:\n  - endpoint: GET /orders\n    latency_ms: 300\n  - endpoint: GET /orders/{id}\n    latency_ms: 80\n\
+             \x20 - target: com.shop.ReportJob\n    latency_ms: 60000\n  - endpoint: DELETE /orders\n    latency_ms: 50\n"
+        ).unwrap();
+        let index = BudgetIndex::build(&config, &routes, &graph, &SymbolTable::new(), hotness::MAX_DEPTH);
+
+        // 两个接口都调用 load，取更紧的 80ms
+        let load = index.tightest("OrderService", "load").unwrap();
+        assert_eq!((load.target.as_str(), load.latency_ms, load.distance), ("GET /orders/{id}", 80, 1));
+        assert_eq!(index.tightest("OrderController", "list").unwrap().latency_ms, 300);
+        assert_eq!(index.tightest("ReportJob", "anything").unwrap().distance, 0);
+        assert_eq!(index.tightest("OrderService", "export").unwrap().latency_ms, 60000);
+        assert_eq!(index.tightest("OrderService", "unused"), None);
+
+        let mut issues: Vec<AstIssue> = [("A.java", 3, Severity::P0), ("A.java", 9, Severity::P1)].iter()
+            .map(|(path, line, severity)| serde_json::from_value(serde_json::json!({
+                "severity": severity, "id": "N_PLUS_ONE", "file": "", "path": path, "line": line, "description": ""
+            })).unwrap())
+            .collect();
+        let spans = HashMap::from([("A.java".to_string(), vec![
+            MethodSpan { class: "OrderService".into(), method: "load".into(), fqn: "com.shop.OrderService.load".into(), start_line: 1, end_line: 5 },
+        ])]);
+        annotate(&mut issues, &spans, &index);
+        assert_eq!(issues[0].budget.as_ref().map(|b| b.latency_ms), Some(80));
+        assert_eq!(issues[1].budget, None);
+        assert_eq!(format_budget(&issues[0]), " ⏱️80ms");
+
+        let summaries = summarize(&index, &issues);
+        let order: Vec<(&str, usize, bool)> = summaries.iter().map(|s| (s.target.as_str(), s.p0, s.matched)).collect();
+        assert_eq!(order, [("DELETE /orders", 0, false), ("GET /orders/{id}", 1, true), ("GET /orders", 0, true), ("com.shop.ReportJob", 0, true)]);
+        let section = render_section(&summaries, "P0", "P1");
+        assert!(section.contains("1. **DELETE /orders** (≤50ms) - 未找到对应的入口方法"), "{section}");
+        assert!(section.contains("2. **GET /orders/{id}** (≤80ms) - P0: 1, P1: 0"), "{section}");
+    }
+
+    #[test]
+    fn test_load_validates_budgets() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load(dir.path(), None).unwrap(), None);
+        assert!(load(dir.path(), Some(&dir.path().join("missing.yaml"))).is_err());
+
+        std::fs::write(dir.path().join(SLO_FILE), "budgets:\n  - target: A#b\n    latency_ms: 10\n").unwrap();
+        assert_eq!(load(dir.path(), None).unwrap().unwrap().budgets[0].label(), "A#b");
+        std::fs::write(dir.path().join(SLO_FILE), "budgets:\n  - latency_ms: 10\n").unwrap();
+        assert!(load(dir.path(), None).unwrap_err().contains("endpoint 或 target"));
+        std::fs::write(dir.path().join(SLO_FILE), "budgets:\n  - target: A\n    latency: 10\n").unwrap();
+        assert!(load(dir.path(), None).unwrap_err().contains("格式错误"));
+    }
+}
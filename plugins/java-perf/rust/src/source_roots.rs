@@ -0,0 +1,121 @@
+//! 多根目录扫描 (`scan app ../shared-lib`)
+//!
+//! 共享库源码位于单独的 checkout 时，把它作为附加根目录一起扫描：所有根目录的 Java 文件
+//! 共同构建 SymbolTable / CallGraph / ImportIndex，对共享库方法的调用可以跨根解析
+//! (N+1、阻塞调用等依赖被调方层级与签名的规则因此能给出高置信度结果)。
+//!
+//! - 第一个根目录为主根：`.javaperf.toml`、技术栈检测、CODEOWNERS、基线与索引缓存都取自主根
+//! - 附加根中文件的报告路径为 `<根目录参数>/<相对路径>`，如 `../shared-lib/src/main/java/Foo.java`
+//! - `--report-root DIR` 只分析并报告该根目录下的文件，其余根目录只参与索引
+//!
+//! 根目录之间不能相互包含 (同一文件会被索引两次)。
+
+use std::path::{Path, PathBuf};
+
+/// 附加根目录
+#[derive(Debug, Clone)]
+struct ExtraRoot {
+    dir: PathBuf,
+    /// 报告路径前缀 (命令行参数原样，`/` 分隔)
+    label: String,
+}
+
+/// 本次扫描的根目录集合
+#[derive(Debug, Clone)]
+pub struct SourceRoots {
+    primary: PathBuf,
+    extra: Vec<ExtraRoot>,
+}
+
+impl SourceRoots {
+    /// 校验附加根目录：必须是目录，且与主根及彼此互不包含
+    pub fn new(primary: &Path, extra: &[PathBuf]) -> Result<Self, String> {
+        if !extra.is_empty() && !primary.is_dir() {
+            return Err(format!("多根扫描的主根必须是目录: {}", primary.display()));
+        }
+        let mut seen = vec![(primary.to_path_buf(), canonical(primary))];
+        let mut roots = Vec::with_capacity(extra.len());
+        for dir in extra {
+            if !dir.is_dir() {
+                return Err(format!("附加根目录不存在或不是目录: {}", dir.display()));
+            }
+            let canonical_dir = canonical(dir);
+            if let Some((other, _)) = seen.iter().find(|(_, c)| c.starts_with(&canonical_dir) || canonical_dir.starts_with(c)) {
+                return Err(format!("根目录 {} 与 {} 相互包含", dir.display(), other.display()));
+            }
+            seen.push((dir.clone(), canonical_dir));
+            let label = dir.to_string_lossy().replace('\\', "/").trim_end_matches('/').to_string();
+            roots.push(ExtraRoot { dir: dir.clone(), label });
+        }
+        Ok(Self { primary: primary.to_path_buf(), extra: roots })
+    }
+
+    pub fn is_multi(&self) -> bool {
+        !self.extra.is_empty()
+    }
+
+    /// 附加根目录 (遍历文件用)
+    pub fn extra_dirs(&self) -> impl Iterator<Item = &Path> {
+        self.extra.iter().map(|root| root.dir.as_path())
+    }
+
+    /// 附加根目录的报告前缀
+    pub fn extra_labels(&self) -> Vec<String> {
+        self.extra.iter().map(|root| root.label.clone()).collect()
+    }
+
+    /// 报告路径：主根下为相对路径，附加根下为 `<label>/<相对路径>`
+    pub fn relative_path(&self, file: &Path) -> String {
+        self.extra.iter()
+            .find(|root| file.starts_with(&root.dir))
+            .map(|root| format!("{}/{}", root.label, crate::ast_engine::relative_path(&root.dir, file)))
+            .unwrap_or_else(|| crate::ast_engine::relative_path(&self.primary, file))
+    }
+
+    /// 把 `--report-root` 解析为某个根目录 (遍历时使用的路径)，不是扫描根之一时报错
+    pub fn resolve(&self, report_root: &Path) -> Result<&Path, String> {
+        let wanted = canonical(report_root);
+        std::iter::once(self.primary.as_path())
+            .chain(self.extra_dirs())
+            .find(|dir| canonical(dir) == wanted)
+            .ok_or_else(|| {
+                let roots: Vec<String> = std::iter::once(self.primary.display().to_string())
+                    .chain(self.extra.iter().map(|root| root.label.clone()))
+                    .collect();
+                format!("--report-root {} 不是扫描根目录之一 ({})", report_root.display(), roots.join(", "))
+            })
+    }
+}
+
+fn canonical(dir: &Path) -> PathBuf {
+    dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roots_paths_and_validation() {
+        let workspace = tempfile::tempdir().unwrap();
+        let app = workspace.path().join("app");
+        let shared = workspace.path().join("shared-lib");
+        std::fs::create_dir_all(app.join("src")).unwrap();
+        std::fs::create_dir_all(shared.join("src")).unwrap();
+
+        let roots = SourceRoots::new(&app, std::slice::from_ref(&shared)).unwrap();
+        assert!(roots.is_multi());
+        assert_eq!(roots.relative_path(&app.join("src/A.java")), "src/A.java");
+        let label = shared.to_string_lossy().replace('\\', "/");
+        assert_eq!(roots.relative_path(&shared.join("src/B.java")), format!("{label}/src/B.java"));
+        assert_eq!(roots.resolve(&app.join("src/..")).unwrap(), app.as_path());
+        assert_eq!(roots.resolve(&shared).unwrap(), shared.as_path());
+        assert!(roots.resolve(workspace.path()).unwrap_err().contains("不是扫描根目录之一"));
+
+        // 嵌套根目录、不存在的目录
+        assert!(SourceRoots::new(&app, &[app.join("src")]).unwrap_err().contains("相互包含"));
+        assert!(SourceRoots::new(&app, &[workspace.path().to_path_buf()]).unwrap_err().contains("相互包含"));
+        assert!(SourceRoots::new(&app, &[workspace.path().join("missing")]).is_err());
+        assert!(!SourceRoots::new(&app, &[]).unwrap().is_multi());
+    }
+}
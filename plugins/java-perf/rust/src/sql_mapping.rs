@@ -0,0 +1,360 @@
+//! N+1 调用的 DAO 方法 → SQL 文本 (MyBatis mapper XML / `@Query` 等注解)
+//!
+//! N+1 问题只说明"循环中调用了 `orderMapper.selectById`"，看不到每次迭代实际执行的 SQL。
+//! 扫描后处理中对 N+1 类问题 (`N_PLUS_ONE`、`FIND_BY_ID_IN_LOOP`):
+//!
+//! - 按所在类的字段绑定 (SymbolTable) 解析接收者类型，如 `orderMapper` → `OrderMapper`
+//! - MyBatis: `<mapper namespace="...OrderMapper">` 下 `<select|insert|update|delete id="selectById">` 的 SQL
+//!   (`<include refid>` 展开同一 mapper 中的 `<sql>` 片段，`<if>`/`<foreach>` 等动态标签只保留其中的文本)
+//! - 注解: 接口方法上的 `@Query` (Spring Data JPA/JDBC) 与 `@Select`/`@Insert`/`@Update`/`@Delete` (MyBatis)
+//! - 找到时问题附带 SQL 文本与来源位置 (JSON: `sql`)，SQL 再经 sql_plan 检查，执行计划问题一并列出
+//!
+//! 只在存在可解析接收者的 N+1 问题时读取 mapper XML 与对应的 Java 文件；派生查询 (`findByStatus`) 没有 SQL 文本，不附加。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Parser};
+
+use crate::ast_engine::{relative_path, AstIssue};
+use crate::encoding::read_source;
+use crate::hotness::{self, MethodSpan};
+use crate::scanner::tree_sitter_java::string_value;
+use crate::scanner::node_walk::{NodeWalk, TYPE_DECLARATIONS};
+use crate::scanner::{grammar, sql_plan, truncate_display};
+use crate::symbol_table::SymbolTable;
+
+/// 附加 SQL 的规则
+pub const RULES: &[&str] = &["N_PLUS_ONE", "FIND_BY_ID_IN_LOOP"];
+
+/// Markdown 中 SQL 的显示宽度上限
+const MAX_SQL_WIDTH: usize = 160;
+
+/// 提供 SQL 的方法注解
+const SQL_ANNOTATIONS: &[&str] = &["Query", "Select", "Insert", "Update", "Delete"];
+
+/// MyBatis 语句标签
+const STATEMENT_TAGS: &[&[u8]] = &[b"select", b"insert", b"update", b"delete"];
+
+/// 问题上下文开头的调用: `orderMapper.selectById(...)`
+static RE_CALL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:this\.)?([A-Za-z_$][\w$]*)\.([A-Za-z_$][\w$]*)\(").unwrap());
+
+/// MyBatis 参数占位符 (`#{id}` / `${table}`)
+static RE_PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"[#$]\{[^}]*\}").unwrap());
+
+/// SQL 的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlOrigin {
+    MapperXml,
+    Annotation,
+}
+
+/// DAO 方法对应的 SQL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MappedSql {
+    /// DAO 方法 (`OrderMapper.selectById`)
+    pub method: String,
+    /// SQL 文本 (合并空白)
+    pub text: String,
+    /// 定义位置 (`src/main/resources/mapper/OrderMapper.xml:12`)
+    pub source: String,
+    pub origin: SqlOrigin,
+    /// SQL 本身的执行计划问题 (见 scanner::sql_plan)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plan: Vec<String>,
+}
+
+/// (类型简单名, 方法名) → SQL
+#[derive(Debug, Default)]
+pub struct SqlIndex {
+    statements: HashMap<(String, String), MappedSql>,
+}
+
+impl SqlIndex {
+    /// 收集 `types` (简单名) 的 mapper XML 语句与 SQL 注解
+    pub fn build(root: &Path, files: &[PathBuf], table: &SymbolTable, types: &HashSet<String>) -> Self {
+        let mut index = Self::default();
+        for file in files.iter().filter(|f| f.extension().and_then(|e| e.to_str()) == Some("xml")) {
+            let Ok(source) = read_source(file) else { continue };
+            if !source.content.contains("<mapper") {
+                continue;
+            }
+            for (namespace, id, text, line) in parse_mapper(&source.content) {
+                let class = simple_name(&namespace);
+                if types.contains(class) {
+                    let location = format!("{}:{line}", relative_path(root, file));
+                    index.insert(class, &id, text, location, SqlOrigin::MapperXml);
+                }
+            }
+        }
+        let mut parser = Parser::new();
+        if parser.set_language(&grammar::language()).is_err() {
+            return index;
+        }
+        let java_files: HashSet<&Path> = types.iter()
+            .flat_map(|name| table.simple_name_index.get(name).into_iter().flatten())
+            .filter_map(|fqn| table.classes.get(fqn))
+            .map(|info| info.file.as_std_path())
+            .collect();
+        for file in java_files {
+            let Ok(source) = read_source(file) else { continue };
+            let Some(tree) = parser.parse(&source.content, None) else { continue };
+            for (class, method, text, line) in annotated_statements(tree.root_node(), &source.content) {
+                if types.contains(&class) {
+                    let location = format!("{}:{line}", relative_path(root, file));
+                    index.insert(&class, &method, text, location, SqlOrigin::Annotation);
+                }
+            }
+        }
+        index
+    }
+
+    fn insert(&mut self, class: &str, method: &str, text: String, source: String, origin: SqlOrigin) {
+        let plan = sql_plan::analyze(&RE_PLACEHOLDER.replace_all(&text, "?"))
+            .into_iter()
+            .map(|finding| finding.detail)
+            .collect();
+        self.statements.entry((class.to_string(), method.to_string())).or_insert(MappedSql {
+            method: format!("{class}.{method}"),
+            text,
+            source,
+            origin,
+            plan,
+        });
+    }
+
+    pub fn get(&self, class: &str, method: &str) -> Option<&MappedSql> {
+        self.statements.get(&(class.to_string(), method.to_string()))
+    }
+}
+
+/// 为 N+1 问题附加所调用 DAO 方法的 SQL，返回附加的问题数
+pub fn annotate(
+    issues: &mut [AstIssue],
+    table: &SymbolTable,
+    spans: &HashMap<String, Vec<MethodSpan>>,
+    root: &Path,
+    files: &[PathBuf],
+) -> usize {
+    let targets: Vec<(usize, String, String)> = issues.iter().enumerate()
+        .filter(|(_, issue)| RULES.contains(&issue.issue_type.as_str()))
+        .filter_map(|(index, issue)| {
+            let call = RE_CALL.captures(issue.context.as_deref()?)?;
+            let class = spans.get(issue.path.as_str()).and_then(|file_spans| hotness::innermost(file_spans, issue.line))?;
+            let binding = table.fields.get(&(class.class.clone(), call[1].to_string()))?;
+            let receiver = binding.type_name.split('<').next().unwrap_or(&binding.type_name).trim();
+            Some((index, simple_name(receiver).to_string(), call[2].to_string()))
+        })
+        .collect();
+    if targets.is_empty() {
+        return 0;
+    }
+    let types: HashSet<String> = targets.iter().map(|(_, class, _)| class.clone()).collect();
+    let index = SqlIndex::build(root, files, table, &types);
+    let mut attached = 0;
+    for (position, class, method) in targets {
+        if let Some(sql) = index.get(&class, &method) {
+            issues[position].sql = Some(sql.clone());
+            attached += 1;
+        }
+    }
+    attached
+}
+
+/// 问题下方的 SQL 行 (未关联时为空)
+pub fn format_sql(issue: &AstIssue) -> String {
+    let Some(sql) = &issue.sql else { return String::new() };
+    let plan = if sql.plan.is_empty() { String::new() } else { format!(" ⚠️ {}", sql.plan.join("；")) };
+    format!(
+        "  - 🗄️ 每次迭代执行 `{}` (`{}`): `{}`{plan}\n",
+        sql.method, sql.source, truncate_display(&sql.text, MAX_SQL_WIDTH)
+    )
+}
+
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// mapper XML 中的语句: (namespace, id, SQL, 行号)
+fn parse_mapper(xml: &str) -> Vec<(String, String, String, usize)> {
+    enum Part {
+        Text(String),
+        Include(String),
+    }
+    let line_of = |offset: usize| xml[..offset.min(xml.len())].matches('\n').count() + 1;
+    let mut reader = Reader::from_str(xml);
+    let mut namespace = String::new();
+    let mut fragments: HashMap<String, String> = HashMap::new();
+    let mut statements: Vec<(String, usize, Vec<Part>)> = Vec::new();
+    // 当前语句或 <sql> 片段: (是否为片段, id, 行号, 内容, 嵌套深度)
+    let mut current: Option<(bool, String, usize, Vec<Part>, usize)> = None;
+    loop {
+        let offset = reader.buffer_position();
+        let Ok(event) = reader.read_event() else { break };
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let attr = |name: &[u8]| {
+                    e.attributes().flatten()
+                        .find(|a| a.key.as_ref() == name)
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|v| v.into_owned())
+                };
+                let is_start = matches!(event, Event::Start(_));
+                match (&mut current, e.name().as_ref()) {
+                    (Some((_, _, _, parts, _)), b"include") => parts.extend(attr(b"refid").map(Part::Include)),
+                    (Some((_, _, _, _, depth)), _) => *depth += usize::from(is_start),
+                    (None, b"mapper") => namespace = attr(b"namespace").unwrap_or_default(),
+                    (None, tag) if is_start && (tag == b"sql" || STATEMENT_TAGS.contains(&tag)) => {
+                        let Some(id) = attr(b"id") else { continue };
+                        current = Some((tag == b"sql", id, line_of(offset), Vec::new(), 0));
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(text) => {
+                if let (Some((_, _, _, parts, _)), Ok(text)) = (&mut current, text.unescape()) {
+                    parts.push(Part::Text(text.into_owned()));
+                }
+            }
+            Event::CData(data) => {
+                if let Some((_, _, _, parts, _)) = &mut current {
+                    parts.push(Part::Text(String::from_utf8_lossy(data).into_owned()));
+                }
+            }
+            Event::End(_) => match &mut current {
+                Some((_, _, _, _, depth)) if *depth > 0 => *depth -= 1,
+                Some(_) => {
+                    let (fragment, id, line, parts, _) = current.take().expect("current statement");
+                    if fragment {
+                        let text = parts.iter()
+                            .map(|part| match part { Part::Text(text) => text.as_str(), Part::Include(_) => "" })
+                            .collect::<String>();
+                        fragments.insert(id, text);
+                    } else {
+                        statements.push((id, line, parts));
+                    }
+                }
+                None => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    statements.into_iter()
+        .map(|(id, line, parts)| {
+            let text: String = parts.iter()
+                .map(|part| match part {
+                    Part::Text(text) => format!("{text} "),
+                    Part::Include(refid) => format!("{} ", fragments.get(refid).map_or("", String::as_str)),
+                })
+                .collect();
+            (namespace.clone(), id, normalize(&text), line)
+        })
+        .collect()
+}
+
+/// 方法上 SQL 注解的语句: (所在类型简单名, 方法名, SQL, 行号)
+fn annotated_statements(root: Node, code: &str) -> Vec<(String, String, String, usize)> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "method_declaration" {
+            if let Some(found) = method_sql(node, code) {
+                out.push(found);
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    out
+}
+
+fn method_sql(method: Node, code: &str) -> Option<(String, String, String, usize)> {
+    let text = |node: Node| node.utf8_text(code.as_bytes()).unwrap_or("");
+    let mut cursor = method.walk();
+    let modifiers = method.children(&mut cursor).find(|n| n.kind() == "modifiers")?;
+    let mut cursor = modifiers.walk();
+    let annotation = modifiers.children(&mut cursor)
+        .filter(|n| n.kind() == "annotation")
+        .find(|n| n.child_by_field_name("name").is_some_and(|name| SQL_ANNOTATIONS.contains(&simple_name(text(name)))))?;
+    let arguments = annotation.child_by_field_name("arguments")?;
+    let mut cursor = arguments.walk();
+    let value = arguments.named_children(&mut cursor).find_map(|arg| match arg.kind() {
+        "element_value_pair" => arg.child_by_field_name("key")
+            .filter(|key| text(*key) == "value")
+            .and_then(|_| arg.child_by_field_name("value")),
+        "element_value_array_initializer" => arg.named_child(0),
+        _ => Some(arg),
+    })?;
+    let sql = string_value(value, code)?;
+    let owner = NodeWalk::new(code).ancestor_of_kind(method, TYPE_DECLARATIONS)?;
+    let class = text(owner.child_by_field_name("name")?).to_string();
+    let name = text(method.child_by_field_name("name")?).to_string();
+    Some((class, name, normalize(&sql), annotation.start_position().row + 1))
+}
+
+fn normalize(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE mapper PUBLIC "-//mybatis.org//DTD Mapper 3.0//EN" "http://mybatis.org/dtd/mybatis-3-mapper.dtd">
+<mapper namespace="com.shop.mapper.OrderMapper">
+    <sql id="columns">id, user_id, status</sql>
+    <select id="selectById" resultType="Order">
+        SELECT <include refid="columns"/>
+        FROM orders
+        <where>
+            <if test="id != null">id = #{id}</if>
+        </where>
+    </select>
+    <select id="selectByUser"><![CDATA[ SELECT * FROM orders WHERE user_id < #{userId} ORDER BY id ]]></select>
+</mapper>
+"#;
+
+    #[test]
+    fn test_parse_mapper_statements() {
+        let statements = parse_mapper(MAPPER);
+        let summary: Vec<(&str, &str, usize)> = statements.iter().map(|(ns, id, _, line)| (ns.as_str(), id.as_str(), *line)).collect();
+        assert_eq!(summary, [("com.shop.mapper.OrderMapper", "selectById", 5), ("com.shop.mapper.OrderMapper", "selectByUser", 12)]);
+        assert_eq!(statements[0].2, "SELECT id, user_id, status FROM orders id = #{id}");
+        assert_eq!(statements[1].2, "SELECT * FROM orders WHERE user_id < #{userId} ORDER BY id");
+    }
+
+    #[test]
+    fn test_annotated_statements() {
+        let code = r#"package com.shop;
+public interface OrderRepository extends JpaRepository<Order, Long> {
+    @Query(value = "SELECT o FROM Order o " +
+                   "WHERE o.userId = :userId", nativeQuery = false)
+    List<Order> findByUser(Long userId);
+
+    @Select("SELECT * FROM item WHERE order_id = #{orderId}")
+    List<Item> items(Long orderId);
+
+    List<Order> findByStatus(String status);
+}
+"#;
+        let mut parser = Parser::new();
+        parser.set_language(&grammar::language()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let mut found = annotated_statements(tree.root_node(), code);
+        found.sort();
+        assert_eq!(found, [
+            ("OrderRepository".to_string(), "findByUser".to_string(), "SELECT o FROM Order o WHERE o.userId = :userId".to_string(), 3),
+            ("OrderRepository".to_string(), "items".to_string(), "SELECT * FROM item WHERE order_id = #{orderId}".to_string(), 7),
+        ]);
+    }
+}
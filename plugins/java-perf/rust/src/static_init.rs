@@ -0,0 +1,664 @@
+//! 类静态初始化依赖分析
+//!
+//! 从 static 字段初始化器、static 块、枚举常量参数 (以及它们调用的本类静态方法)
+//! 中收集对其他类的引用，构建"静态初始化依赖图"：
+//! - `STATIC_INIT_CYCLE` (P0): 依赖图中的环。两个线程分别首次触发 A、B 的初始化时，
+//!   JVM 类初始化锁互相等待，表现为启动卡死且无死锁检测日志
+//! - `STATIC_INIT_HEAVY` (P1): 静态初始化中的重量级操作 (IO、数据库连接、反射加载等)，
+//!   附带触发它的静态初始化链
+//!
+//! 依赖边只记录会触发类初始化的引用：静态方法调用、非编译期常量的静态字段访问、
+//! `new`、以及子类对父类的隐式依赖。lambda、方法引用、匿名类体延迟执行，不计入。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::Path;
+use tree_sitter::Node;
+
+use crate::ast_engine::{AstIssue, Severity};
+use crate::shared_path::FilePath;
+use crate::scanner::issue_builder::fingerprint;
+
+/// 本模块产出的规则 (不经 RuleRegistry 的 Query，扫描时按筛选决定是否收集)
+pub const RULES: &[&str] = &["STATIC_INIT_CYCLE", "STATIC_INIT_HEAVY"];
+
+/// 静态初始化中的重量级调用 (接收者, 方法名前缀)；接收者为空表示任意接收者
+const HEAVY_CALLS: &[(&str, &str)] = &[
+    ("Class", "forName"),
+    ("DriverManager", "getConnection"),
+    ("Files", "read"),
+    ("Files", "lines"),
+    ("Files", "newBufferedReader"),
+    ("Files", "newInputStream"),
+    ("System", "loadLibrary"),
+    ("System", "load"),
+    ("Thread", "sleep"),
+    ("InetAddress", "getLocalHost"),
+    ("InetAddress", "getByName"),
+    ("ServiceLoader", "load"),
+    ("Executors", "new"),
+    ("", "getResourceAsStream"),
+    ("", "getConnection"),
+];
+
+/// 静态初始化中的重量级对象创建
+const HEAVY_TYPES: &[&str] = &[
+    "FileInputStream", "FileReader", "RandomAccessFile", "ZipFile", "JarFile",
+    "Socket", "ServerSocket", "Thread", "ThreadPoolExecutor", "ScheduledThreadPoolExecutor",
+];
+
+const LITERAL_KINDS: &[&str] = &[
+    "string_literal", "character_literal", "decimal_integer_literal", "hex_integer_literal",
+    "octal_integer_literal", "binary_integer_literal", "decimal_floating_point_literal",
+    "hex_floating_point_literal", "true", "false",
+];
+
+/// 静态初始化中对另一个类的引用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticDep {
+    pub target: String,
+    /// 静态字段访问的字段名 (`Target.FIELD`)，用于排除编译期常量
+    pub member: Option<String>,
+    pub line: usize,
+    pub snippet: String,
+    /// 子类对父类的隐式依赖
+    pub extends: bool,
+}
+
+/// 静态初始化中的重量级操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeavyOp {
+    pub call: String,
+    pub line: usize,
+    pub snippet: String,
+}
+
+/// 单个类型的静态初始化信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticInit {
+    pub class: String,
+    pub path: String,
+    pub line: usize,
+    pub deps: Vec<StaticDep>,
+    pub heavy: Vec<HeavyOp>,
+    /// 编译期常量字段 (访问它们不触发类初始化)
+    pub constants: HashSet<String>,
+}
+
+/// 文件是否可能包含静态初始化逻辑 (预过滤，避免无谓遍历)
+pub fn is_candidate(code: &str) -> bool {
+    code.contains("static") || code.contains("enum ") || code.contains("extends")
+}
+
+/// 收集文件中每个类型声明的静态初始化依赖
+pub fn collect(root: Node, code: &str, rel_path: &str) -> Vec<StaticInit> {
+    let mut result = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "class_declaration" | "enum_declaration" | "interface_declaration" | "record_declaration") {
+            if let Some(init) = collect_type(node, code, rel_path) {
+                result.push(init);
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    result.sort_by_key(|i| i.line);
+    result
+}
+
+fn text<'a>(node: Node, code: &'a str) -> &'a str {
+    node.utf8_text(code.as_bytes()).unwrap_or("")
+}
+
+fn line_text(code: &str, line: usize) -> String {
+    code.lines().nth(line.saturating_sub(1)).unwrap_or("").trim().to_string()
+}
+
+/// 去掉泛型参数和包名: `java.util.List<String>` → `List`
+fn simple_type_name(type_text: &str) -> &str {
+    let base = type_text.split('<').next().unwrap_or(type_text).trim();
+    base.rsplit('.').next().unwrap_or(base)
+}
+
+/// 大写开头且含小写字母 (排除 `HANDLERS` 这类常量字段)
+fn is_type_like(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && name.chars().any(|c| c.is_ascii_lowercase())
+}
+
+fn is_compile_time_constant(value: Node) -> bool {
+    match value.kind() {
+        "binary_expression" => {
+            let mut cursor = value.walk();
+            let all = value.named_children(&mut cursor).all(is_compile_time_constant);
+            all
+        }
+        "parenthesized_expression" => value.named_child(0).is_some_and(is_compile_time_constant),
+        kind => LITERAL_KINDS.contains(&kind),
+    }
+}
+
+struct Collector<'a> {
+    code: &'a str,
+    /// 本类方法名 → 方法体 (静态初始化中无接收者调用时继续追踪)
+    methods: HashMap<&'a str, Node<'a>>,
+    visited_methods: HashSet<&'a str>,
+    deps: Vec<StaticDep>,
+    heavy: Vec<HeavyOp>,
+}
+
+impl<'a> Collector<'a> {
+    fn dep(&mut self, target: &str, member: Option<&str>, node: Node) {
+        let line = node.start_position().row + 1;
+        self.deps.push(StaticDep {
+            target: target.to_string(),
+            member: member.map(str::to_string),
+            line,
+            snippet: line_text(self.code, line),
+            extends: false,
+        });
+    }
+
+    fn heavy_op(&mut self, call: String, node: Node) {
+        let line = node.start_position().row + 1;
+        self.heavy.push(HeavyOp { call, line, snippet: line_text(self.code, line) });
+    }
+
+    fn scan(&mut self, node: Node<'a>) {
+        match node.kind() {
+            // 延迟执行的代码不属于类初始化
+            "lambda_expression" | "method_reference" | "class_body" => return,
+            "method_invocation" => {
+                let name = node.child_by_field_name("name").map(|n| text(n, self.code)).unwrap_or("");
+                match node.child_by_field_name("object") {
+                    Some(object) => {
+                        let receiver = text(object, self.code);
+                        if object.kind() == "identifier" && is_type_like(receiver) {
+                            self.dep(receiver, None, node);
+                        }
+                        let receiver_type = simple_type_name(receiver);
+                        let is_heavy = HEAVY_CALLS.iter().any(|(r, m)| {
+                            (r.is_empty() || *r == receiver_type) && name.starts_with(m)
+                        });
+                        if is_heavy {
+                            self.heavy_op(format!("{receiver}.{name}()"), node);
+                        }
+                    }
+                    None => {
+                        // 本类静态方法: 追踪其方法体 (每个方法只展开一次)
+                        if let Some(body) = self.methods.get(name).copied() {
+                            if self.visited_methods.insert(name) {
+                                self.scan(body);
+                            }
+                        }
+                    }
+                }
+            }
+            "field_access" => {
+                if let (Some(object), Some(field)) = (node.child_by_field_name("object"), node.child_by_field_name("field")) {
+                    let receiver = text(object, self.code);
+                    if object.kind() == "identifier" && is_type_like(receiver) {
+                        self.dep(receiver, Some(text(field, self.code)), node);
+                    }
+                }
+            }
+            "object_creation_expression" => {
+                if let Some(type_node) = node.child_by_field_name("type") {
+                    let type_name = simple_type_name(text(type_node, self.code));
+                    self.dep(type_name, None, node);
+                    if HEAVY_TYPES.contains(&type_name) {
+                        self.heavy_op(format!("new {type_name}()"), node);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<_> = node.children(&mut cursor).collect();
+        for child in children {
+            self.scan(child);
+        }
+    }
+}
+
+fn collect_type<'a>(decl: Node<'a>, code: &'a str, rel_path: &str) -> Option<StaticInit> {
+    let class = text(decl.child_by_field_name("name")?, code).to_string();
+    let body = decl.child_by_field_name("body")?;
+    let is_interface = decl.kind() == "interface_declaration";
+
+    // 类体成员 (枚举的成员在 enum_body_declarations 中)
+    let mut members = Vec::new();
+    let mut cursor = body.walk();
+    for child in body.children(&mut cursor) {
+        if child.kind() == "enum_body_declarations" {
+            let mut inner = child.walk();
+            members.extend(child.children(&mut inner));
+        } else {
+            members.push(child);
+        }
+    }
+
+    let mut collector = Collector {
+        code,
+        methods: HashMap::new(),
+        visited_methods: HashSet::new(),
+        deps: Vec::new(),
+        heavy: Vec::new(),
+    };
+    for member in &members {
+        if member.kind() == "method_declaration" {
+            if let (Some(name), Some(method_body)) = (member.child_by_field_name("name"), member.child_by_field_name("body")) {
+                collector.methods.insert(text(name, code), method_body);
+            }
+        }
+    }
+
+    let mut constants = HashSet::new();
+    for member in members {
+        let modifiers = {
+            let mut c = member.walk();
+            let mods = member.children(&mut c).find(|n| n.kind() == "modifiers");
+            mods.map(|m| text(m, code)).unwrap_or("")
+        };
+        let is_static_field = member.kind() == "constant_declaration"
+            || (member.kind() == "field_declaration" && (is_interface || modifiers.contains("static")));
+        let is_final = is_interface || member.kind() == "constant_declaration" || modifiers.contains("final");
+
+        match member.kind() {
+            "field_declaration" | "constant_declaration" if is_static_field => {
+                let mut c = member.walk();
+                for declarator in member.children_by_field_name("declarator", &mut c) {
+                    let Some(value) = declarator.child_by_field_name("value") else { continue };
+                    if is_final && is_compile_time_constant(value) {
+                        if let Some(name) = declarator.child_by_field_name("name") {
+                            constants.insert(text(name, code).to_string());
+                        }
+                    } else {
+                        collector.scan(value);
+                    }
+                }
+            }
+            "static_initializer" => collector.scan(member),
+            // 枚举常量在类初始化时构造
+            "enum_constant" => {
+                if let Some(args) = member.child_by_field_name("arguments") {
+                    collector.scan(args);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let line = decl.start_position().row + 1;
+    let mut deps = collector.deps;
+    deps.retain(|d| d.target != class);
+    if let Some(superclass) = decl.child_by_field_name("superclass").and_then(|s| s.named_child(0)) {
+        deps.push(StaticDep {
+            target: simple_type_name(text(superclass, code)).to_string(),
+            member: None,
+            line,
+            snippet: line_text(code, line),
+            extends: true,
+        });
+    }
+
+    Some(StaticInit {
+        class,
+        path: rel_path.to_string(),
+        line,
+        deps,
+        heavy: collector.heavy,
+        constants,
+    })
+}
+
+/// 依赖图中的一条边
+#[derive(Debug, Clone, Copy)]
+struct Edge<'a> {
+    to: usize,
+    dep: &'a StaticDep,
+}
+
+fn build_edges(inits: &[StaticInit]) -> Vec<Vec<Edge<'_>>> {
+    // 同名类型取第一个 (按路径排序后稳定)
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (i, init) in inits.iter().enumerate() {
+        index.entry(init.class.as_str()).or_insert(i);
+    }
+
+    inits.iter().map(|init| {
+        let mut edges: Vec<Edge> = Vec::new();
+        for dep in &init.deps {
+            let Some(&to) = index.get(dep.target.as_str()) else { continue };
+            let is_constant = dep.member.as_ref().is_some_and(|m| inits[to].constants.contains(m));
+            if is_constant || edges.iter().any(|e| e.to == to) {
+                continue;
+            }
+            edges.push(Edge { to, dep });
+        }
+        edges
+    }).collect()
+}
+
+/// Tarjan 强连通分量 (只返回包含 ≥ 2 个节点的分量)
+fn cycles(edges: &[Vec<Edge>]) -> Vec<Vec<usize>> {
+    struct State {
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next: usize,
+        result: Vec<Vec<usize>>,
+    }
+
+    fn visit(v: usize, edges: &[Vec<Edge>], s: &mut State) {
+        s.index[v] = Some(s.next);
+        s.low[v] = s.next;
+        s.next += 1;
+        s.stack.push(v);
+        s.on_stack[v] = true;
+
+        for edge in &edges[v] {
+            match s.index[edge.to] {
+                None => {
+                    visit(edge.to, edges, s);
+                    s.low[v] = s.low[v].min(s.low[edge.to]);
+                }
+                Some(idx) if s.on_stack[edge.to] => s.low[v] = s.low[v].min(idx),
+                _ => {}
+            }
+        }
+
+        if Some(s.low[v]) == s.index[v] {
+            let mut component = Vec::new();
+            while let Some(w) = s.stack.pop() {
+                s.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            if component.len() > 1 {
+                component.sort_unstable();
+                s.result.push(component);
+            }
+        }
+    }
+
+    let n = edges.len();
+    let mut state = State {
+        index: vec![None; n],
+        low: vec![0; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        next: 0,
+        result: Vec::new(),
+    };
+    for v in 0..n {
+        if state.index[v].is_none() {
+            visit(v, edges, &mut state);
+        }
+    }
+    state.result
+}
+
+/// 在分量内用 BFS 找一条从 start 出发回到 start 的最短环
+fn cycle_path<'a>(start: usize, members: &HashSet<usize>, edges: &[Vec<Edge<'a>>]) -> Vec<Edge<'a>> {
+    let mut prev: HashMap<usize, Edge<'a>> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    let mut seen = HashSet::from([start]);
+    let mut closing = None;
+
+    'bfs: while let Some(v) = queue.pop_front() {
+        for edge in &edges[v] {
+            if !members.contains(&edge.to) {
+                continue;
+            }
+            if edge.to == start {
+                closing = Some((v, *edge));
+                break 'bfs;
+            }
+            if seen.insert(edge.to) {
+                prev.insert(edge.to, Edge { to: v, dep: edge.dep });
+                queue.push_back(edge.to);
+            }
+        }
+    }
+
+    let Some((mut v, last)) = closing else { return Vec::new() };
+    let mut path = vec![last];
+    while v != start {
+        let back = prev[&v];
+        path.push(Edge { to: v, dep: back.dep });
+        v = back.to;
+    }
+    path.reverse();
+    path
+}
+
+/// 反向 BFS 找到触发 target 初始化的最长静态初始化链 (根 → ... → target)
+fn trigger_chain(target: usize, edges: &[Vec<Edge>]) -> Vec<usize> {
+    let mut reverse: Vec<Vec<usize>> = vec![Vec::new(); edges.len()];
+    for (from, out) in edges.iter().enumerate() {
+        for edge in out {
+            reverse[edge.to].push(from);
+        }
+    }
+
+    let mut next: HashMap<usize, usize> = HashMap::new();
+    let mut queue = VecDeque::from([target]);
+    let mut seen = HashSet::from([target]);
+    let mut farthest = target;
+    while let Some(v) = queue.pop_front() {
+        farthest = v;
+        for &from in &reverse[v] {
+            if seen.insert(from) {
+                next.insert(from, v);
+                queue.push_back(from);
+            }
+        }
+    }
+
+    let mut chain = vec![farthest];
+    let mut v = farthest;
+    while let Some(&n) = next.get(&v) {
+        chain.push(n);
+        v = n;
+    }
+    chain
+}
+
+fn issue(severity: Severity, id: &str, init: &StaticInit, line: usize, snippet: &str, description: &str, context: String) -> AstIssue {
+    let scope = Path::new(&init.path).file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    AstIssue {
+        severity,
+        issue_type: id.to_string(),
+        file: init.path.rsplit('/').next().unwrap_or(&init.path).to_string(),
+        path: FilePath::new(&init.path),
+        line,
+        column: 0,
+        description: description.to_string(),
+        context: Some(context),
+        snippet: snippet.to_string(),
+        fingerprint: fingerprint(id, &scope, snippet),
+        hotness: 0,
+        method: None,
+        secondary: Vec::new(),
+        inactive: false,
+        suppressed: false,
+        suppression: None,
+        owners: Vec::new(),
+        suggested_patch: None,
+        triage: None,
+        age: None,
+        budget: None,
+        coverage: None,
+        debt: None,
+        sql: None,
+    }
+}
+
+/// 分析全项目的静态初始化依赖图，返回环与重量级初始化问题
+pub fn analyze(mut inits: Vec<StaticInit>) -> Vec<AstIssue> {
+    inits.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    let edges = build_edges(&inits);
+    let mut issues = Vec::new();
+
+    for component in cycles(&edges) {
+        let start = component[0];
+        let members: HashSet<usize> = component.iter().copied().collect();
+        let path = cycle_path(start, &members, &edges);
+        let Some(first) = path.first() else { continue };
+
+        let mut chain = vec![inits[start].class.clone()];
+        let mut from = start;
+        for edge in &path {
+            let kind = if edge.dep.extends { " (extends)" } else { "" };
+            chain.push(format!("{}{} @{}:{}", inits[edge.to].class, kind, inits[from].path, edge.dep.line));
+            from = edge.to;
+        }
+        issues.push(issue(
+            Severity::P0,
+            "STATIC_INIT_CYCLE",
+            &inits[start],
+            first.dep.line,
+            &first.dep.snippet,
+            "类静态初始化循环依赖，多线程并发首次访问时可能发生类初始化死锁",
+            chain.join(" → "),
+        ));
+    }
+
+    for (i, init) in inits.iter().enumerate() {
+        let Some(op) = init.heavy.first() else { continue };
+        let mut calls: BTreeMap<&str, ()> = BTreeMap::new();
+        for h in &init.heavy {
+            calls.insert(&h.call, ());
+        }
+        let calls: Vec<&str> = calls.into_keys().collect();
+
+        let chain = trigger_chain(i, &edges);
+        let context = if chain.len() > 1 {
+            let names: Vec<&str> = chain.iter().map(|&c| inits[c].class.as_str()).collect();
+            format!("{}.<clinit> 执行 {}；触发链: {}", init.class, calls.join(", "), names.join(" → "))
+        } else {
+            format!("{}.<clinit> 执行 {}", init.class, calls.join(", "))
+        };
+        issues.push(issue(
+            Severity::P1,
+            "STATIC_INIT_HEAVY",
+            init,
+            op.line,
+            &op.snippet,
+            "静态初始化中执行 IO/连接/反射加载等重量级操作，拖慢启动且失败时抛出 ExceptionInInitializerError",
+            context,
+        ));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+    fn collect_all(files: &[(&str, &str)]) -> Vec<StaticInit> {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        files.iter()
+            .flat_map(|(path, code)| {
+                analyzer.parse_with(code, |tree| collect(tree.root_node(), code, path)).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collect_static_deps() {
+        let inits = collect_all(&[("Config.java", r#"
+            public class Config {
+                public static final String NAME = "app";
+                static final int SIZE = 1 + 2;
+                private static final Registry REGISTRY = Registry.create();
+                private static final Map<String, Handler> HANDLERS = new HashMap<>();
+                private static Supplier<Cache> LAZY = () -> Cache.build();
+                private String instance = Other.VALUE;
+
+                static {
+                    HANDLERS.put("x", new Handler(Defaults.TIMEOUT));
+                    load();
+                }
+
+                private static void load() {
+                    Class.forName("com.mysql.Driver");
+                }
+            }
+        "#)]);
+        assert_eq!(inits.len(), 1);
+        let config = &inits[0];
+        let targets: Vec<&str> = config.deps.iter().map(|d| d.target.as_str()).collect();
+        assert_eq!(targets, vec!["Registry", "HashMap", "Handler", "Defaults", "Class"]);
+        assert_eq!(config.deps[3].member.as_deref(), Some("TIMEOUT"));
+        assert!(config.constants.contains("NAME") && config.constants.contains("SIZE"));
+        assert_eq!(config.heavy.len(), 1);
+        assert_eq!(config.heavy[0].call, "Class.forName()");
+        assert_eq!(config.heavy[0].line, 16);
+    }
+
+    #[test]
+    fn test_parent_child_cycle() {
+        // 经典死锁: 父类静态字段引用子类，子类初始化需先初始化父类
+        let inits = collect_all(&[
+            ("Shape.java", "public class Shape {\n    public static final Shape DEFAULT = new Circle();\n}"),
+            ("Circle.java", "public class Circle extends Shape {\n}"),
+            ("Point.java", "public class Point {\n    static final Shape ORIGIN = Shape.DEFAULT;\n}"),
+        ]);
+        let issues = analyze(inits);
+        let cycles: Vec<_> = issues.iter().filter(|i| i.issue_type == "STATIC_INIT_CYCLE").collect();
+        assert_eq!(cycles.len(), 1, "{issues:?}");
+        assert_eq!(cycles[0].path, "Circle.java");
+        assert_eq!(
+            cycles[0].context.as_deref(),
+            Some("Circle → Shape (extends) @Circle.java:1 → Circle @Shape.java:2"),
+        );
+    }
+
+    #[test]
+    fn test_constant_access_is_not_dependency() {
+        let inits = collect_all(&[
+            ("A.java", "class A {\n    static final int LIMIT = B.MAX;\n    static final int MAX = 10;\n}"),
+            ("B.java", "class B {\n    static final int MAX = A.MAX;\n}"),
+        ]);
+        assert!(analyze(inits).iter().all(|i| i.issue_type != "STATIC_INIT_CYCLE"));
+    }
+
+    #[test]
+    fn test_heavy_init_with_trigger_chain() {
+        let inits = collect_all(&[
+            ("App.java", "class App {\n    static final Holder HOLDER = Holder.get();\n}"),
+            ("Holder.java", "class Holder {\n    static final Db DB = new Db();\n    static Holder get() { return null; }\n}"),
+            ("Db.java", "class Db {\n    static final Connection CONN;\n    static {\n        CONN = DriverManager.getConnection(URL);\n    }\n}"),
+        ]);
+        let issues = analyze(inits);
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        let heavy = &issues[0];
+        assert_eq!((heavy.issue_type.as_str(), heavy.line), ("STATIC_INIT_HEAVY", 4));
+        assert_eq!(heavy.severity, Severity::P1);
+        assert_eq!(
+            heavy.context.as_deref(),
+            Some("Db.<clinit> 执行 DriverManager.getConnection()；触发链: App → Holder → Db"),
+        );
+    }
+
+    #[test]
+    fn test_enum_constant_arguments() {
+        let inits = collect_all(&[
+            ("Status.java", "enum Status {\n    OK(Codes.lookup(\"ok\")),\n    FAIL(() -> Codes.lookup(\"x\"));\n    Status(Object o) {}\n}"),
+            ("Codes.java", "class Codes {\n    static final Status DEFAULT = Status.OK;\n    static Object lookup(String s) { return s; }\n}"),
+        ]);
+        let status = inits.iter().find(|i| i.class == "Status").unwrap();
+        assert_eq!(status.deps.len(), 1, "lambda argument should be ignored");
+        let issues = analyze(inits);
+        assert!(issues.iter().any(|i| i.issue_type == "STATIC_INIT_CYCLE"));
+    }
+}
@@ -0,0 +1,512 @@
+//! 项目索引缓存 (`.javaperf/symbols.bin`)
+//!
+//! Phase 1 的 SymbolTable + CallGraph + ImportIndex 按文件持久化为一个 bincode 文件。
+//! 每个文件记录内容哈希，下次构建时只重新索引哈希变化的文件，已删除的文件从缓存中剔除；
+//! 格式版本或工具版本不一致时整体重建。
+//!
+//! `symbols dump --class Foo` 输出某个类在索引中的样子 (FQN、字段绑定、import、调用边)，
+//! 用于排查 "为什么这个 N+1 没报 / Bean 解析错了" 一类问题。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+
+use crate::ast_engine::{index_file, ImportIndexMap};
+use crate::encoding::decode_bytes;
+use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+use crate::symbol_table::{ImportIndex, MethodInfo, StringConstant, SymbolTable, TypeInfo, VarBinding};
+use crate::hotness::callee_targets;
+use crate::shared_path::FilePath;
+use crate::taint::{CallGraph, CallSite, LayerType, MethodEffects, MethodSig, ProxyKind};
+
+/// 缓存目录 (相对项目根)
+pub const CACHE_DIR: &str = ".javaperf";
+const CACHE_FILE: &str = "symbols.bin";
+/// 缓存结构或 Phase 1 提取逻辑变化时递增
+const FORMAT_VERSION: u32 = 7;
+
+/// 单个文件的索引结果 (SymbolTable 的 map 键为元组，这里展开为列表)
+///
+/// 条目中的文件路径都是该文件自身，只记在缓存的键上 (条目中置空)，合并时恢复为同一份共享路径，
+/// 缓存不会为每个类型/常量/调用点重复写入路径字符串。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileIndex {
+    /// 文件内容哈希 (FNV-1a)
+    hash: u64,
+    classes: Vec<TypeInfo>,
+    /// (类名, 字段绑定)
+    fields: Vec<(String, VarBinding)>,
+    /// (类名, 方法)
+    methods: Vec<(String, MethodInfo)>,
+    constants: Vec<StringConstant>,
+    /// (类名, 常量名, 值)
+    bool_constants: Vec<(String, String, bool)>,
+    calls: Vec<CallSite>,
+    /// (类名, 层级)
+    class_layers: Vec<(String, LayerType)>,
+    /// 被代理拦截的方法
+    proxied: Vec<(MethodSig, ProxyKind)>,
+    /// 方法体内的加锁与阻塞调用
+    effects: Vec<(MethodSig, MethodEffects)>,
+    imports: Vec<(String, ImportIndex)>,
+}
+
+impl FileIndex {
+    fn new(hash: u64, (table, graph, imports): (SymbolTable, CallGraph, ImportIndexMap)) -> Self {
+        Self {
+            hash,
+            classes: table.classes.into_values().map(|info| TypeInfo { file: FilePath::default(), ..info }).collect(),
+            fields: table.fields.into_iter().map(|((class, _), binding)| (class, binding)).collect(),
+            methods: table.methods.into_iter().map(|((class, _), method)| (class, method)).collect(),
+            constants: table.constants.into_values().map(|constant| StringConstant { file: FilePath::default(), ..constant }).collect(),
+            bool_constants: table.bool_constants.into_iter().map(|((class, name), value)| (class, name, value)).collect(),
+            calls: graph.outgoing.into_values().flatten().map(|site| CallSite { file: FilePath::default(), ..site }).collect(),
+            class_layers: graph.class_layers.into_iter().collect(),
+            proxied: graph.proxied.into_iter().collect(),
+            effects: graph.effects.into_iter().collect(),
+            imports: imports.into_iter().collect(),
+        }
+    }
+
+    /// 合并到项目级索引 (file 为该条目的文件路径)
+    fn merge_into(self, file: &FilePath, table: &mut SymbolTable, graph: &mut CallGraph, imports: &mut ImportIndexMap) {
+        for info in self.classes {
+            table.register_class_fqn(TypeInfo { file: file.clone(), ..info });
+        }
+        for (class, binding) in self.fields {
+            table.register_field(&class, binding);
+        }
+        for (class, method) in self.methods {
+            table.register_method(&class, method);
+        }
+        for constant in self.constants {
+            table.register_constant(StringConstant { file: file.clone(), ..constant });
+        }
+        for (class, name, value) in self.bool_constants {
+            table.register_bool_constant(&class, &name, value);
+        }
+        for site in self.calls {
+            graph.add_call(site.caller, site.callee, file, site.line);
+        }
+        for (class, layer) in self.class_layers {
+            graph.register_class(&class, file, layer);
+        }
+        for (method, kind) in self.proxied {
+            graph.mark_proxied(method, kind);
+        }
+        for (method, effects) in self.effects {
+            graph.mark_effects(method, effects);
+        }
+        imports.extend(self.imports);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexCache {
+    format: u32,
+    tool_version: String,
+    files: HashMap<PathBuf, FileIndex>,
+}
+
+/// 缓存命中统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// 缓存缺失、损坏或版本不符，全部重新索引
+    pub rebuilt: bool,
+    /// 哈希未变、直接复用的文件数
+    pub reused: usize,
+    /// 新增或内容变化、重新索引的文件数
+    pub reindexed: usize,
+    /// 已从项目中删除、剔除的文件数
+    pub removed: usize,
+}
+
+/// 项目级 Phase 1 索引
+#[derive(Debug, Default)]
+pub struct ProjectIndex {
+    pub table: SymbolTable,
+    pub graph: CallGraph,
+    pub imports: ImportIndexMap,
+    pub stats: CacheStats,
+}
+
+/// 文件内容哈希 (FNV-1a 64，跨平台/跨版本稳定)
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3))
+}
+
+pub fn cache_path(root: &Path) -> PathBuf {
+    root.join(CACHE_DIR).join(CACHE_FILE)
+}
+
+/// 读取缓存；缺失、损坏或版本不符时返回 None
+fn load(root: &Path) -> Option<IndexCache> {
+    let bytes = std::fs::read(cache_path(root)).ok()?;
+    let cache: IndexCache = bincode::deserialize(&bytes).ok()?;
+    (cache.format == FORMAT_VERSION && cache.tool_version == env!("CARGO_PKG_VERSION")).then_some(cache)
+}
+
+/// 写缓存失败 (只读检出等) 不影响本次分析
+fn save(root: &Path, cache: &IndexCache) {
+    if std::fs::create_dir_all(root.join(CACHE_DIR)).is_err() {
+        return;
+    }
+    if let Ok(bytes) = bincode::serialize(cache) {
+        let _ = std::fs::write(cache_path(root), bytes);
+    }
+}
+
+/// 项目中的 Java 文件 (跳过 .git 与缓存目录)
+pub(crate) fn java_files(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git" && e.file_name() != CACHE_DIR)
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("java"))
+        .collect()
+}
+
+/// 构建项目索引，复用 `root/.javaperf/symbols.bin` 中哈希未变的文件
+///
+/// refresh: 忽略已有缓存全部重建
+/// exclude: 照常索引并写入缓存、但不合并到结果中的文件 (precommit 用暂存内容替代)
+pub(crate) fn build(
+    root: &Path,
+    java_files: &[&Path],
+    java_analyzer: &JavaTreeSitterAnalyzer,
+    refresh: bool,
+    exclude: &HashSet<PathBuf>,
+) -> ProjectIndex {
+    let previous = if refresh { None } else { load(root) };
+    let mut stats = CacheStats { rebuilt: previous.is_none(), ..Default::default() };
+    let mut previous = previous.map(|cache| cache.files).unwrap_or_default();
+
+    // 并行读取 + 哈希；命中缓存的文件不解析
+    let fresh: Vec<(PathBuf, Option<FileIndex>)> = java_files.par_iter()
+        .filter_map(|path| {
+            let bytes = std::fs::read(path).ok()?;
+            let hash = content_hash(&bytes);
+            if previous.get(*path).is_some_and(|entry| entry.hash == hash) {
+                return Some((path.to_path_buf(), None));
+            }
+            let source = decode_bytes(&bytes);
+            Some((path.to_path_buf(), Some(FileIndex::new(hash, index_file(path, &source.content, java_analyzer)))))
+        })
+        .collect();
+
+    let mut files = HashMap::with_capacity(fresh.len());
+    for (path, entry) in fresh {
+        let entry = match entry {
+            Some(entry) => {
+                stats.reindexed += 1;
+                previous.remove(&path);
+                entry
+            }
+            None => {
+                stats.reused += 1;
+                previous.remove(&path).expect("hash matched a cached entry")
+            }
+        };
+        files.insert(path, entry);
+    }
+    stats.removed = previous.len();
+
+    let cache = IndexCache {
+        format: FORMAT_VERSION,
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        files,
+    };
+    if stats.rebuilt || stats.reindexed > 0 || stats.removed > 0 {
+        save(root, &cache);
+    }
+
+    let mut index = ProjectIndex { stats, ..Default::default() };
+    for (path, entry) in cache.files {
+        if exclude.contains(&path) {
+            continue;
+        }
+        entry.merge_into(&FilePath::from_path_lossy(&path), &mut index.table, &mut index.graph, &mut index.imports);
+    }
+    index
+}
+
+// ============================================================================
+// symbols dump
+// ============================================================================
+
+/// 构建 (或增量更新) 项目索引后输出类的解析结果
+pub fn dump(code_path: &str, class: Option<&str>, refresh: bool) -> Result<Value, Box<dyn std::error::Error>> {
+    let root = Path::new(code_path);
+    let java_analyzer = JavaTreeSitterAnalyzer::new()?;
+    let files = java_files(root);
+    let paths: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+    let index = build(root, &paths, &java_analyzer, refresh, &HashSet::new());
+    Ok(describe(&index, class))
+}
+
+/// 按简单名或 FQN 查找类并输出其索引信息；class 为空时输出索引概况
+fn describe(index: &ProjectIndex, class: Option<&str>) -> Value {
+    let Some(name) = class else {
+        return json!({
+            "classes": index.table.classes.len(),
+            "fields": index.table.fields.len(),
+            "constants": index.table.constants.len(),
+            "call_sites": index.graph.outgoing.values().map(Vec::len).sum::<usize>(),
+            "cache": index.stats,
+        });
+    };
+
+    let classes: Vec<Value> = find_classes(&index.table, name).into_iter()
+        .map(|info| describe_class(index, info))
+        .collect();
+    json!({ "query": name, "classes": classes })
+}
+
+/// 按 FQN 或简单名查找类 (同名类按 FQN 排序)
+pub(crate) fn find_classes<'a>(table: &'a SymbolTable, name: &str) -> Vec<&'a TypeInfo> {
+    let mut matches: Vec<&TypeInfo> = match table.lookup_by_fqn(name) {
+        Some(info) => vec![info],
+        None => table.lookup_by_simple_name(name),
+    };
+    matches.sort_by(|a, b| a.fqn.cmp(&b.fqn));
+    matches
+}
+
+/// 类的索引信息：TypeInfo、字段绑定、import、调用边
+pub(crate) fn describe_class(index: &ProjectIndex, info: &TypeInfo) -> Value {
+    let table = &index.table;
+
+    let fields: Vec<Value> = table.fields_of(&info.name).into_iter()
+        .map(|binding| {
+            let type_name = binding.type_name.split('<').next().unwrap_or(&binding.type_name).trim();
+            let candidates: Vec<&str> = table.bean_candidates(type_name).into_iter().map(|c| c.fqn.as_str()).collect();
+            json!({
+                "name": binding.name,
+                "type": binding.type_name,
+                "annotations": binding.annotations,
+                "resolves_to": candidates,
+            })
+        })
+        .collect();
+
+    let mut calls: Vec<Value> = index.graph.outgoing.iter()
+        .filter(|(caller, _)| caller.class_fqn == info.fqn)
+        .flat_map(|(_, sites)| sites.iter())
+        .map(|site| json!({
+            "caller": site.caller.name,
+            "callee": format!("{}.{}", site.callee.class_fqn, site.callee.name),
+            "line": site.line,
+        }))
+        .collect();
+    calls.sort_by_key(|c| c["line"].as_u64());
+
+    // 被调方多为 `UNRESOLVED:字段名`，按字段类型/Bean 候选解析后再匹配
+    let mut callers: Vec<Value> = index.graph.outgoing.values()
+        .flatten()
+        .filter(|site| callee_targets(&index.graph, table, site).iter().any(|target| target.class_fqn == info.fqn))
+        .map(|site| json!({
+            "caller": format!("{}.{}", site.caller.class_fqn, site.caller.name),
+            "callee": site.callee.name,
+            "file": site.file,
+            "line": site.line,
+        }))
+        .collect();
+    callers.sort_by(|a, b| (a["caller"].as_str(), a["line"].as_u64()).cmp(&(b["caller"].as_str(), b["line"].as_u64())));
+
+    let imports = index.imports.get(info.file.as_str());
+
+    json!({
+        "fqn": info.fqn,
+        "file": info.file,
+        "line": info.line,
+        "layer": info.layer,
+        "annotations": info.annotations,
+        "supertypes": info.supertypes,
+        "bean_name": info.is_bean().then(|| info.effective_bean_name()),
+        "fields": fields,
+        "imports": imports,
+        "calls": calls,
+        "callers": callers,
+    })
+}
+
+/// 渲染 dump 结果 (人类可读模式为缩进文本)
+pub fn render_dump(dump: &Value, json_output: bool) -> Value {
+    if json_output {
+        return dump.clone();
+    }
+
+    let Some(classes) = dump["classes"].as_array() else {
+        let cache = &dump["cache"];
+        return json!(format!(
+            "符号索引: {} 个类, {} 个字段, {} 个常量, {} 个调用点\n缓存: 复用 {} / 重新索引 {} / 剔除 {}{}",
+            dump["classes"], dump["fields"], dump["constants"], dump["call_sites"],
+            cache["reused"], cache["reindexed"], cache["removed"],
+            if cache["rebuilt"] == true { " (已重建)" } else { "" },
+        ));
+    };
+    if classes.is_empty() {
+        return json!(format!("❓ 索引中没有类 {}", dump["query"].as_str().unwrap_or_default()));
+    }
+
+    let mut out = String::new();
+    for class in classes {
+        out.push_str(&render_class(class));
+        out.push('\n');
+    }
+    json!(out.trim_end())
+}
+
+fn str_list(v: &Value) -> String {
+    v.as_array().map(|items| items.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(", ")).unwrap_or_default()
+}
+
+/// 渲染 `describe_class` 的结果
+pub(crate) fn render_class(class: &Value) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "## {}\n- 文件: {}:{}\n- 层级: {}\n- 注解: {}\n- 父类型: {}\n",
+        class["fqn"].as_str().unwrap_or_default(),
+        class["file"].as_str().unwrap_or_default(),
+        class["line"],
+        class["layer"].as_str().unwrap_or_default(),
+        str_list(&class["annotations"]),
+        str_list(&class["supertypes"]),
+    ));
+    if let Some(bean) = class["bean_name"].as_str() {
+        out.push_str(&format!("- Bean: {bean}\n"));
+    }
+    out.push_str("\n### 字段\n");
+    for field in class["fields"].as_array().into_iter().flatten() {
+        let resolves = str_list(&field["resolves_to"]);
+        out.push_str(&format!(
+            "- {}: {} → {}\n",
+            field["name"].as_str().unwrap_or_default(),
+            field["type"].as_str().unwrap_or_default(),
+            if resolves.is_empty() { "(未解析)".to_string() } else { resolves },
+        ));
+    }
+    if let Some(imports) = class["imports"].as_object() {
+        out.push_str(&format!(
+            "\n### Import\n- package: {}\n- 显式: {}\n- 通配: {}\n",
+            imports["package"].as_str().unwrap_or("(default)"),
+            imports["explicit"].as_object().map(|m| m.len()).unwrap_or_default(),
+            str_list(&imports["wildcards"]),
+        ));
+    }
+    out.push_str("\n### 调用\n");
+    for call in class["calls"].as_array().into_iter().flatten() {
+        out.push_str(&format!(
+            "- L{} {}() → {}\n",
+            call["line"], call["caller"].as_str().unwrap_or_default(), call["callee"].as_str().unwrap_or_default(),
+        ));
+    }
+    out.push_str("\n### 被调用\n");
+    for caller in class["callers"].as_array().into_iter().flatten() {
+        out.push_str(&format!(
+            "- {} → {}() ({}:{})\n",
+            caller["caller"].as_str().unwrap_or_default(), caller["callee"].as_str().unwrap_or_default(),
+            caller["file"].as_str().unwrap_or_default(), caller["line"],
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_dir(root: &Path, refresh: bool, exclude: &HashSet<PathBuf>) -> ProjectIndex {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        let files = java_files(root);
+        let paths: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+        build(root, &paths, &analyzer, refresh, exclude)
+    }
+
+    fn sample_project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("OrderRepository.java"),
+            "package com.shop;\n@Repository\npublic class OrderRepository {}\n").unwrap();
+        std::fs::write(dir.path().join("OrderService.java"),
+            "package com.shop;\n@Service\npublic class OrderService {\n    @Autowired\n    private OrderRepository orderRepository;\n\n    public void load() {\n        orderRepository.findAll();\n    }\n}\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(content_hash(b"class A {}"), content_hash(b"class B {}"));
+    }
+
+    #[test]
+    fn test_incremental_rebuild_by_hash() {
+        let dir = sample_project();
+        let root = dir.path();
+
+        let first = build_dir(root, false, &HashSet::new());
+        assert_eq!(first.stats, CacheStats { rebuilt: true, reused: 0, reindexed: 2, removed: 0 });
+        assert!(cache_path(root).exists());
+
+        let second = build_dir(root, false, &HashSet::new());
+        assert_eq!(second.stats, CacheStats { rebuilt: false, reused: 2, reindexed: 0, removed: 0 });
+        assert_eq!(second.table.classes.len(), first.table.classes.len());
+        assert_eq!(second.graph.outgoing.len(), first.graph.outgoing.len());
+        assert!(second.table.is_dao_var("OrderService", "orderRepository"));
+        // 路径只存在缓存键上，载入后各条目恢复为所在文件
+        let service = root.join("OrderService.java");
+        assert_eq!(second.table.lookup_by_simple_name("OrderService")[0].file, *service.as_path());
+        assert!(second.graph.outgoing.values().flatten().all(|site| site.file == *service.as_path()));
+        assert_eq!(second.graph.class_index.get("com.shop.OrderService").map(|file| file == service.as_path()), Some(true));
+
+        std::fs::write(root.join("OrderService.java"), "package com.shop;\npublic class OrderService {}\n").unwrap();
+        std::fs::remove_file(root.join("OrderRepository.java")).unwrap();
+        let third = build_dir(root, false, &HashSet::new());
+        assert_eq!(third.stats, CacheStats { rebuilt: false, reused: 0, reindexed: 1, removed: 1 });
+        assert!(third.table.fields.is_empty());
+
+        assert!(build_dir(root, true, &HashSet::new()).stats.rebuilt);
+    }
+
+    #[test]
+    fn test_excluded_files_are_cached_but_not_merged() {
+        let dir = sample_project();
+        let root = dir.path();
+        let service = root.join("OrderService.java");
+
+        let index = build_dir(root, false, &HashSet::from([service]));
+        assert!(index.table.lookup_by_simple_name("OrderService").is_empty());
+        assert!(index.table.fields.is_empty());
+        assert_eq!(index.table.lookup_by_simple_name("OrderRepository").len(), 1);
+
+        // 被排除的文件仍写入了缓存
+        let again = build_dir(root, false, &HashSet::new());
+        assert_eq!(again.stats.reused, 2);
+        assert_eq!(again.table.lookup_by_simple_name("OrderService").len(), 1);
+    }
+
+    #[test]
+    fn test_dump_class() {
+        let dir = sample_project();
+        let index = build_dir(dir.path(), false, &HashSet::new());
+
+        let dump = describe(&index, Some("OrderService"));
+        let class = &dump["classes"][0];
+        assert_eq!(class["fqn"], "com.shop.OrderService");
+        assert_eq!(class["layer"], "Service");
+        assert_eq!(class["fields"][0]["name"], "orderRepository");
+        assert_eq!(class["fields"][0]["resolves_to"][0], "com.shop.OrderRepository");
+        assert_eq!(class["calls"][0]["caller"], "load");
+        assert_eq!(class["imports"]["package"], "com.shop");
+
+        let text = render_dump(&dump, false);
+        assert!(text.as_str().unwrap().contains("- orderRepository: OrderRepository → com.shop.OrderRepository"), "{text}");
+        assert_eq!(render_dump(&describe(&index, Some("Missing")), false), json!("❓ 索引中没有类 Missing"));
+    }
+}
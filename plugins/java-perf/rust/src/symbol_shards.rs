@@ -0,0 +1,179 @@
+//! 分片并发符号表 (Phase 1 索引)
+//!
+//! 原先每个文件产出一个 SymbolTable，再由 Rayon reduce 两两 [`SymbolTable::merge`]：
+//! 数万文件的项目在合并过程中反复扩容大 HashMap，构建阶段出现明显的内存尖峰。
+//!
+//! [`SymbolShards`] 以 DashMap 分片存储，工作线程把单文件的小 SymbolTable 直接并发写入
+//! ([`SymbolShards::absorb`])，不再有中间合并；键与值都从单文件表中移动过来，不重新分配字符串。
+//! 全部文件写入后 [`SymbolShards::into_table`] 按确切容量一次性转为只读的 SymbolTable，
+//! 读 API 不变。
+//!
+//! 结果与顺序合并一致：同一键以文件序号较大者为准 (对应 merge 的后者覆盖)，
+//! 简单名索引与重载列表按文件序号排序后去重 (对应 merge 的追加顺序)。
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+
+use crate::symbol_table::{MethodInfo, StringConstant, SymbolTable, TypeInfo, VarBinding};
+
+/// (类名, 成员名) 键
+type MemberKey = (String, String);
+
+/// 分片符号表 (构建期，可并发写入)
+#[derive(Debug, Default)]
+pub struct SymbolShards {
+    classes: DashMap<String, (usize, TypeInfo)>,
+    simple_name_index: DashMap<String, Vec<(usize, String)>>,
+    fields: DashMap<MemberKey, (usize, VarBinding)>,
+    methods: DashMap<MemberKey, (usize, MethodInfo)>,
+    method_index: DashMap<MemberKey, Vec<(usize, String)>>,
+    constants: DashMap<MemberKey, (usize, StringConstant)>,
+    bool_constants: DashMap<MemberKey, (usize, bool)>,
+}
+
+impl SymbolShards {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入第 `ordinal` 个文件的符号 (可在多个线程中并发调用)
+    pub fn absorb(&self, ordinal: usize, table: SymbolTable) {
+        for (fqn, info) in table.classes {
+            upsert(&self.classes, fqn, ordinal, info);
+        }
+        for (simple_name, fqns) in table.simple_name_index {
+            self.simple_name_index.entry(simple_name).or_default().extend(fqns.into_iter().map(|fqn| (ordinal, fqn)));
+        }
+        for (key, binding) in table.fields {
+            upsert(&self.fields, key, ordinal, binding);
+        }
+        for (key, method) in table.methods {
+            upsert(&self.methods, key, ordinal, method);
+        }
+        for (key, sigs) in table.method_index {
+            self.method_index.entry(key).or_default().extend(sigs.into_iter().map(|sig| (ordinal, sig)));
+        }
+        for (key, constant) in table.constants {
+            upsert(&self.constants, key, ordinal, constant);
+        }
+        for (key, value) in table.bool_constants {
+            upsert(&self.bool_constants, key, ordinal, value);
+        }
+    }
+
+    /// 转为只读 SymbolTable (各 HashMap 按最终条目数一次分配)
+    pub fn into_table(self) -> SymbolTable {
+        let mut table = SymbolTable::new();
+        table.classes = collect(self.classes, latest);
+        table.simple_name_index = collect(self.simple_name_index, |(name, fqns)| (name, ordered(fqns)));
+        table.fields = collect(self.fields, latest);
+        table.methods = collect(self.methods, latest);
+        table.method_index = collect(self.method_index, |(key, sigs)| (key, ordered(sigs)));
+        table.constants = collect(self.constants, latest);
+        table.bool_constants = collect(self.bool_constants, latest);
+        table
+    }
+}
+
+fn latest<K, V>((key, (_, value)): (K, (usize, V))) -> (K, V) {
+    (key, value)
+}
+
+/// 文件序号不小于已有条目时覆盖
+fn upsert<K: Eq + Hash, V>(map: &DashMap<K, (usize, V)>, key: K, ordinal: usize, value: V) {
+    match map.entry(key) {
+        Entry::Occupied(mut existing) => {
+            if existing.get().0 <= ordinal {
+                existing.insert((ordinal, value));
+            }
+        }
+        Entry::Vacant(vacant) => {
+            vacant.insert((ordinal, value));
+        }
+    }
+}
+
+fn collect<K, V, K2: Eq + Hash, V2>(map: DashMap<K, V>, convert: impl Fn((K, V)) -> (K2, V2)) -> HashMap<K2, V2>
+where
+    K: Eq + Hash,
+{
+    let mut out = HashMap::with_capacity(map.len());
+    out.extend(map.into_iter().map(convert));
+    out
+}
+
+/// 按文件序号排序并去重 (保留首次出现)
+fn ordered<T: PartialEq>(mut items: Vec<(usize, T)>) -> Vec<T> {
+    items.sort_by_key(|(ordinal, _)| *ordinal);
+    let mut out: Vec<T> = Vec::with_capacity(items.len());
+    for (_, item) in items {
+        if !out.contains(&item) {
+            out.push(item);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_table(package: &str, class: &str, field_type: &str, sql: &str) -> SymbolTable {
+        let mut table = SymbolTable::new();
+        let info = TypeInfo::new_with_package(class, Some(package), format!("{package}/{class}.java"), 1);
+        table.register_class_fqn(info);
+        table.register_field(class, VarBinding::new("repo", field_type, true));
+        let mut method = MethodInfo::new("find", class, 3);
+        method.add_param("id", field_type);
+        table.register_method(class, method);
+        table.register_constant(StringConstant {
+            class: class.to_string(),
+            name: "SQL".to_string(),
+            value: sql.to_string(),
+            file: format!("{class}.java").into(),
+            line: 2,
+        });
+        table.register_bool_constant(class, "ENABLED", sql.len().is_multiple_of(2));
+        table
+    }
+
+    #[test]
+    fn test_sharded_build_matches_sequential_merge() {
+        // 同名类分属多个包 (字段/方法按简单名登记，后者覆盖，重载签名去重)
+        let tables = || vec![
+            file_table("com.a", "Order", "OrderRepo", "select 1"),
+            file_table("com.b", "Order", "LegacyRepo", "select 22"),
+            file_table("com.a", "Item", "ItemRepo", "select 333"),
+            file_table("com.c", "Order", "NewRepo", "select 4444"),
+            file_table("com.d", "Order", "NewRepo", "select 55555"),
+        ];
+        let mut merged = SymbolTable::new();
+        for table in tables() {
+            merged.merge(table);
+        }
+
+        let shards = SymbolShards::new();
+        // 乱序并发写入，结果仍按文件序号确定
+        std::thread::scope(|scope| {
+            for (ordinal, table) in tables().into_iter().enumerate().rev() {
+                let shards = &shards;
+                scope.spawn(move || shards.absorb(ordinal, table));
+            }
+        });
+        let sharded = shards.into_table();
+
+        assert_eq!(sharded.stats(), merged.stats());
+        let fqns = |table: &SymbolTable| table.lookup_by_simple_name("Order").iter().map(|i| i.fqn.clone()).collect::<Vec<_>>();
+        assert_eq!(fqns(&sharded), vec!["com.a.Order", "com.b.Order", "com.c.Order", "com.d.Order"]);
+        assert_eq!(fqns(&sharded), fqns(&merged));
+        assert_eq!(sharded.fields[&("Order".to_string(), "repo".to_string())].type_name, "NewRepo");
+        assert_eq!(sharded.resolve_constant("Order", "SQL").unwrap().value, "select 55555");
+        assert_eq!(sharded.resolve_bool_constant("Order", "ENABLED"), merged.resolve_bool_constant("Order", "ENABLED"));
+        let sigs = |table: &SymbolTable| table.lookup_methods("Order", "find").iter().map(|m| m.signature()).collect::<Vec<_>>();
+        assert_eq!(sigs(&sharded), vec!["find(OrderRepo)", "find(LegacyRepo)", "find(NewRepo)"]);
+        assert_eq!(sigs(&sharded), sigs(&merged));
+    }
+}
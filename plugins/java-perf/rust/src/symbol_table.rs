@@ -3,7 +3,7 @@
 // ============================================================================
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::shared_path::FilePath;
 use serde::{Serialize, Deserialize};
 
 // ============================================================================
@@ -204,18 +204,41 @@ pub enum LayerType {
 
 impl LayerType {
     /// 从注解名称推断层级
+    ///
+    /// 除 Spring 组件注解外，JAX-RS `@Path` 资源类视为 Controller，CDI / Micronaut
+    /// 作用域注解 (`@ApplicationScoped`、`@Singleton`) 视为 Service，
+    /// Micronaut Data 的 `@JdbcRepository` 等视为 Repository。
     pub fn from_annotation(annotation: &str) -> Self {
         match annotation {
-            "Controller" | "RestController" => LayerType::Controller,
+            "Controller" | "RestController" | "Path" => LayerType::Controller,
             "Service" => LayerType::Service,
-            "Repository" | "Mapper" => LayerType::Repository,
+            a if SCOPE_ANNOTATIONS.contains(&a) => LayerType::Service,
+            "Repository" | "Mapper" | "JdbcRepository" | "R2dbcRepository" | "MongoRepository" => LayerType::Repository,
             "Component" => LayerType::Component,
             _ => LayerType::Unknown,
         }
     }
-    
+
+    /// 从父类/接口推断层级: Panache 与 Spring/Micronaut Data 的 Repository 基类
+    pub fn from_supertype(supertype: &str) -> Self {
+        if REPOSITORY_SUPERTYPES.contains(&supertype) {
+            LayerType::Repository
+        } else {
+            LayerType::Unknown
+        }
+    }
 }
 
+/// CDI / Micronaut 作用域注解: 只声明生命周期，不指明层级，不覆盖已有层级
+const SCOPE_ANNOTATIONS: &[&str] = &["ApplicationScoped", "RequestScoped", "Singleton", "Dependent", "Prototype"];
+
+/// 数据访问基类 (Quarkus Panache、Spring Data、Micronaut Data)
+const REPOSITORY_SUPERTYPES: &[&str] = &[
+    "PanacheRepository", "PanacheRepositoryBase", "PanacheMongoRepository", "PanacheMongoRepositoryBase",
+    "CrudRepository", "JpaRepository", "PagingAndSortingRepository", "PageableRepository",
+    "ReactiveStreamsCrudRepository", "GenericRepository",
+];
+
 /// 类型信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeInfo {
@@ -224,29 +247,43 @@ pub struct TypeInfo {
     pub package: Option<String>,    // "com.example.repository"
     pub annotations: Vec<String>,   // ["Repository", "Component"]
     pub layer: LayerType,
-    pub file: PathBuf,
+    pub file: FilePath,
     pub line: usize,
+    /// 父类与实现的接口 (简单名)，用于聚合同类型 Bean 候选
+    #[serde(default)]
+    pub supertypes: Vec<String>,
+    /// 显式 Bean 名 (`@Service("alipayService")`)
+    #[serde(default)]
+    pub bean_name: Option<String>,
 }
 
+/// 组件注解 (类被注册为 Bean): Spring 组件注解与 CDI / Micronaut 作用域注解
+const BEAN_STEREOTYPES: &[&str] = &[
+    "Service", "Component", "Repository", "Controller", "RestController", "Configuration",
+    "ApplicationScoped", "RequestScoped", "Singleton", "Dependent", "Prototype",
+];
+
 impl TypeInfo {
     /// Create a new TypeInfo with simple name (legacy method for backward compatibility)
     /// 
     /// Note: Prefer `new_with_package` for new code as it properly handles FQN
     #[allow(dead_code)]
-    pub fn new(name: &str, file: PathBuf, line: usize) -> Self {
+    pub fn new(name: &str, file: impl Into<FilePath>, line: usize) -> Self {
         Self {
             name: name.to_string(),
             fqn: name.to_string(),
             package: None,
             annotations: Vec::new(),
             layer: LayerType::Unknown,
-            file,
+            file: file.into(),
             line,
+            supertypes: Vec::new(),
+            bean_name: None,
         }
     }
 
     /// Create a new TypeInfo with package information to build proper FQN
-    pub fn new_with_package(name: &str, package: Option<&str>, file: PathBuf, line: usize) -> Self {
+    pub fn new_with_package(name: &str, package: Option<&str>, file: impl Into<FilePath>, line: usize) -> Self {
         let fqn = match package {
             Some(pkg) if !pkg.is_empty() => format!("{}.{}", pkg, name),
             _ => name.to_string(),
@@ -257,24 +294,60 @@ impl TypeInfo {
             package: package.map(|s| s.to_string()),
             annotations: Vec::new(),
             layer: LayerType::Unknown,
-            file,
+            file: file.into(),
             line,
+            supertypes: Vec::new(),
+            bean_name: None,
         }
     }
     
     /// 添加注解并更新层级
     pub fn add_annotation(&mut self, annotation: &str) {
         self.annotations.push(annotation.to_string());
-        // 更新层级（取优先级最高的）
+        // 更新层级（取优先级最高的）；作用域注解只补充尚未确定的层级
         let new_layer = LayerType::from_annotation(annotation);
-        if new_layer != LayerType::Unknown {
+        let scope_only = SCOPE_ANNOTATIONS.contains(&annotation);
+        if new_layer != LayerType::Unknown && !(scope_only && self.layer != LayerType::Unknown) {
             self.layer = new_layer;
         }
     }
+
+    /// 添加父类/接口 (简单名)；继承 Repository 基类的类归入 Repository 层
+    pub fn add_supertype(&mut self, supertype: &str) {
+        self.supertypes.push(supertype.to_string());
+        if LayerType::from_supertype(supertype) == LayerType::Repository {
+            self.layer = LayerType::Repository;
+        }
+    }
     
+    /// 是否通过组件注解注册为 Spring Bean
+    pub fn is_bean(&self) -> bool {
+        self.annotations.iter().any(|a| BEAN_STEREOTYPES.contains(&a.as_str()))
+    }
+
+    /// 是否标注 @Primary (CDI 中为 @Alternative)
+    pub fn is_primary(&self) -> bool {
+        self.annotations.iter().any(|a| a == "Primary" || a == "Alternative")
+    }
+
+    /// Bean 名: 显式名优先，否则按 Spring 规则将类名首字母小写
+    /// (前两个字母均大写时保持原样，如 `URLService`)
+    pub fn effective_bean_name(&self) -> String {
+        if let Some(name) = &self.bean_name {
+            return name.clone();
+        }
+        let mut chars = self.name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(a), Some(b)) if a.is_uppercase() && b.is_uppercase() => self.name.clone(),
+            (Some(a), _) => a.to_lowercase().chain(self.name.chars().skip(1)).collect(),
+            _ => String::new(),
+        }
+    }
+
     /// 判断是否是 DAO 类型
     pub fn is_dao(&self) -> bool {
         self.layer == LayerType::Repository
+            || self.supertypes.iter().any(|s| REPOSITORY_SUPERTYPES.contains(&s.as_str()))
             || self.annotations.iter().any(|a| {
                 a == "Repository" || a == "Mapper" || a.ends_with("Repository") || a.ends_with("Dao")
             })
@@ -304,6 +377,26 @@ impl VarBinding {
     }
 }
 
+/// 字符串常量 (`static final String` 字段 / 枚举常量的字符串参数)
+///
+/// 用于把 `jdbcTemplate.query(FIND_ALL, ...)` 这类引用解析回 SQL 文本。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StringConstant {
+    pub class: String,          // "OrderSql" (声明所在类/接口/枚举的简单名)
+    pub name: String,           // "FIND_ALL"
+    pub value: String,          // 拼接后的字符串值 (枚举常量为全部字符串参数，换行分隔)
+    pub file: FilePath,
+    pub line: usize,
+}
+
+impl StringConstant {
+    /// 声明位置 ("OrderSql.java:12")，作为关联位置输出
+    pub fn location(&self) -> String {
+        let file = self.file.file_name().unwrap_or_default();
+        format!("{}:{}", file, self.line)
+    }
+}
+
 /// 方法参数信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParamInfo {
@@ -353,6 +446,16 @@ impl MethodInfo {
     }
 }
 
+/// 符号表规模统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolStats {
+    pub classes: usize,
+    pub fields: usize,
+    pub methods: usize,
+    /// 字符串常量与 boolean 常量
+    pub constants: usize,
+}
+
 /// 符号表 - 跟踪类型和变量 (v9.2: 支持方法重载)
 #[derive(Debug, Default)]
 pub struct SymbolTable {
@@ -366,7 +469,11 @@ pub struct SymbolTable {
     /// 注意: 方法签名格式为 "methodName(Type1,Type2)"
     pub methods: HashMap<(String, String), MethodInfo>,
     /// (类名, 方法名) -> 方法签名列表 (用于查找重载)
-    method_index: HashMap<(String, String), Vec<String>>,
+    pub(crate) method_index: HashMap<(String, String), Vec<String>>,
+    /// (类名, 常量名) -> 字符串常量
+    pub constants: HashMap<(String, String), StringConstant>,
+    /// (类名, 常量名) -> `static final boolean` 常量值 (功能开关)
+    pub bool_constants: HashMap<(String, String), bool>,
 }
 
 impl SymbolTable {
@@ -374,6 +481,15 @@ impl SymbolTable {
         Self::default()
     }
 
+    pub fn stats(&self) -> SymbolStats {
+        SymbolStats {
+            classes: self.classes.len(),
+            fields: self.fields.len(),
+            methods: self.methods.len(),
+            constants: self.constants.len() + self.bool_constants.len(),
+        }
+    }
+
     /// 合并另一个 SymbolTable (用于 Rayon 并行 reduce)
     /// 
     /// v9.4: 支持并行构建符号表，避免串行合并瓶颈
@@ -381,9 +497,16 @@ impl SymbolTable {
         self.classes.extend(other.classes);
         self.fields.extend(other.fields);
         self.methods.extend(other.methods);
-        // 合并方法索引
+        self.constants.extend(other.constants);
+        self.bool_constants.extend(other.bool_constants);
+        // 合并方法索引 (不同包的同名类可能注册相同的重载签名)
         for (key, sigs) in other.method_index {
-            self.method_index.entry(key).or_default().extend(sigs);
+            let entry = self.method_index.entry(key).or_default();
+            for sig in sigs {
+                if !entry.contains(&sig) {
+                    entry.push(sig);
+                }
+            }
         }
         // 合并 simple_name_index
         for (simple_name, fqns) in other.simple_name_index {
@@ -422,18 +545,110 @@ impl SymbolTable {
         self.fields.insert((class.to_string(), binding.name.clone()), binding);
     }
 
+    /// 类的全部字段 (按字段名排序)
+    pub fn fields_of(&self, class: &str) -> Vec<&VarBinding> {
+        let mut fields: Vec<&VarBinding> = self.fields.iter()
+            .filter(|((owner, _), _)| owner == class)
+            .map(|(_, binding)| binding)
+            .collect();
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+        fields
+    }
+
+    /// 注册字符串常量
+    pub fn register_constant(&mut self, constant: StringConstant) {
+        self.constants.insert((constant.class.clone(), constant.name.clone()), constant);
+    }
+
+    /// 解析常量引用
+    ///
+    /// 支持 `FIND_ALL` (当前类)、`OrderSql.FIND_ALL` 以及全限定形式
+    /// `com.example.OrderSql.FIND_ALL` (取最后两段)。
+    pub fn resolve_constant(&self, current_class: &str, reference: &str) -> Option<&StringConstant> {
+        let mut parts = reference.rsplitn(3, '.');
+        let name = parts.next()?.trim();
+        let class = parts.next().map(str::trim).unwrap_or(current_class);
+        self.constants.get(&(class.to_string(), name.to_string()))
+    }
+
+    /// 注册布尔常量
+    pub fn register_bool_constant(&mut self, class: &str, name: &str, value: bool) {
+        self.bool_constants.insert((class.to_string(), name.to_string()), value);
+    }
+
+    /// 解析布尔常量引用 (引用形式同 `resolve_constant`)
+    pub fn resolve_bool_constant(&self, current_class: &str, reference: &str) -> Option<bool> {
+        let mut parts = reference.rsplitn(3, '.');
+        let name = parts.next()?.trim();
+        let class = parts.next().map(str::trim).unwrap_or(current_class);
+        self.bool_constants.get(&(class.to_string(), name.to_string())).copied()
+    }
+
     /// 查询变量的类型信息
     pub fn lookup_var_type(&self, class: &str, var_name: &str) -> Option<&TypeInfo> {
         // 先查字段
-        if let Some(binding) = self.fields.get(&(class.to_string(), var_name.to_string())) {
-            return self.classes.get(&binding.type_name);
-        }
-        None
+        let binding = self.fields.get(&(class.to_string(), var_name.to_string()))?;
+        self.classes.get(&binding.type_name).or_else(|| {
+            // 按 FQN 注册的类：简单名唯一时才采用 (同名类无法区分)
+            match self.lookup_by_simple_name(&binding.type_name).as_slice() {
+                [only] => Some(*only),
+                _ => None,
+            }
+        })
     }
     
+    /// 解析接收者表达式的类型 (简单名)
+    ///
+    /// 逐段求值，方法调用取已索引方法的返回类型:
+    ///
+    /// | 表达式 | 解析 |
+    /// |--------|------|
+    /// | `repo` / `this.repo` | 字段类型 |
+    /// | `getRepo()` / `this.getRepo()` | 当前类方法的返回类型 |
+    /// | `orderService.getRepo().findAll()` | 字段类型 → 各段方法的返回类型 |
+    ///
+    /// 局部变量、静态调用、重载返回类型不一致等无法确定的情况返回 None。
+    pub fn resolve_expr_type(&self, class: &str, expr: &str) -> Option<String> {
+        let mut segments = split_member_chain(expr).into_iter();
+        let first = segments.next()?;
+        let mut current = match first {
+            "this" => class.to_string(),
+            _ => self.member_type(class, first)?,
+        };
+        for segment in segments {
+            current = self.member_type(&current, segment)?;
+        }
+        Some(current)
+    }
+
+    /// 接收者表达式的类型信息 (见 `resolve_expr_type`)
+    pub fn lookup_expr_type(&self, class: &str, expr: &str) -> Option<&TypeInfo> {
+        let type_name = self.resolve_expr_type(class, expr)?;
+        self.classes.get(&type_name)
+            .or_else(|| self.lookup_by_simple_name(&type_name).into_iter().next())
+    }
+
+    /// 单段成员的类型: `name(...)` 为方法返回类型，否则为字段类型
+    fn member_type(&self, class: &str, segment: &str) -> Option<String> {
+        match segment.split_once('(') {
+            Some((name, _)) => {
+                let mut returns = self.lookup_methods(class, name.trim()).into_iter()
+                    .map(|m| m.return_type.as_deref().map(simple_type_name));
+                let first = returns.next()??;
+                returns.all(|r| r.as_deref() == Some(first.as_str())).then_some(first)
+            }
+            None => self.fields.get(&(class.to_string(), segment.to_string()))
+                .map(|binding| simple_type_name(&binding.type_name)),
+        }
+    }
+
     /// 判断变量是否是 DAO 类型
+    ///
+    /// `var_name` 也可以是链式接收者 (`orderService.getRepo()`)，按方法返回类型解析。
     pub fn is_dao_var(&self, class: &str, var_name: &str) -> bool {
-        if let Some(type_info) = self.lookup_var_type(class, var_name) {
+        if let Some(type_info) = self.lookup_var_type(class, var_name)
+            .or_else(|| self.lookup_expr_type(class, var_name))
+        {
             return type_info.is_dao();
         }
         // 退化到名称猜测
@@ -468,6 +683,16 @@ impl SymbolTable {
         false
     }
 
+    /// 可注入到 `type_name` 类型注入点的 Bean 候选 (类自身或其子类/实现类)
+    pub fn bean_candidates(&self, type_name: &str) -> Vec<&TypeInfo> {
+        let mut candidates: Vec<&TypeInfo> = self.classes.values()
+            .filter(|info| info.is_bean())
+            .filter(|info| info.name == type_name || info.supertypes.iter().any(|s| s == type_name))
+            .collect();
+        candidates.sort_by(|a, b| a.fqn.cmp(&b.fqn));
+        candidates
+    }
+
     /// Lookup class by FQN (Fully Qualified Name)
     /// 
     /// # Arguments
@@ -489,7 +714,6 @@ impl SymbolTable {
     /// 
     /// # Returns
     /// Vector of references to TypeInfo for all matching classes
-    #[allow(dead_code)]
     pub fn lookup_by_simple_name(&self, simple_name: &str) -> Vec<&TypeInfo> {
         if let Some(fqns) = self.simple_name_index.get(simple_name) {
             fqns.iter()
@@ -527,16 +751,17 @@ impl SymbolTable {
     /// # Arguments
     /// * `class` - The class name (simple or FQN)
     /// * `method` - The method info to register
-    #[allow(dead_code)]
     pub fn register_method(&mut self, class: &str, method: MethodInfo) {
         let sig = format!("{}({})", method.name, 
             method.params.iter().map(|p| p.type_name.as_str()).collect::<Vec<_>>().join(","));
         
         // Add to method_index for lookup by name
-        self.method_index
+        let sigs = self.method_index
             .entry((class.to_string(), method.name.clone()))
-            .or_default()
-            .push(sig.clone());
+            .or_default();
+        if !sigs.contains(&sig) {
+            sigs.push(sig.clone());
+        }
         
         // Add to methods map with signature as key
         self.methods.insert((class.to_string(), sig), method);
@@ -550,7 +775,6 @@ impl SymbolTable {
     /// 
     /// # Returns
     /// Vector of references to MethodInfo for all overloads
-    #[allow(dead_code)]
     pub fn lookup_methods(&self, class: &str, method_name: &str) -> Vec<&MethodInfo> {
         if let Some(sigs) = self.method_index.get(&(class.to_string(), method_name.to_string())) {
             sigs.iter()
@@ -575,6 +799,31 @@ impl SymbolTable {
     }
 }
 
+/// 类型文本的简单名: `java.util.List<Order>` → `List`，`Order[]` → `Order`
+fn simple_type_name(type_text: &str) -> String {
+    let raw = type_text.split('<').next().unwrap_or(type_text).trim_end_matches("[]").trim();
+    raw.rsplit('.').next().unwrap_or(raw).to_string()
+}
+
+/// 按顶层 `.` 拆分成员链 (括号内的参数不拆): `a.b(x.y).c()` → [`a`, `b(x.y)`, `c()`]
+fn split_member_chain(expr: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in expr.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth = depth.saturating_sub(1),
+            '.' if depth == 0 => {
+                segments.push(expr[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(expr[start..].trim());
+    segments
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -937,13 +1186,13 @@ mod tests {
             let type1 = TypeInfo::new_with_package(
                 &class_name,
                 Some(&pkg1),
-                PathBuf::from("File1.java"),
+                "File1.java",
                 1,
             );
             let type2 = TypeInfo::new_with_package(
                 &class_name,
                 Some(&pkg2),
-                PathBuf::from("File2.java"),
+                "File2.java",
                 1,
             );
 
@@ -1166,10 +1415,31 @@ mod tests {
         assert_eq!(LayerType::from_annotation("RestController"), LayerType::Controller);
         assert_eq!(LayerType::from_annotation("Service"), LayerType::Service);
     }
+
+    #[test]
+    fn test_quarkus_micronaut_layers() {
+        assert_eq!(LayerType::from_annotation("Path"), LayerType::Controller);
+        assert_eq!(LayerType::from_annotation("ApplicationScoped"), LayerType::Service);
+
+        // 作用域注解不覆盖已确定的层级
+        let mut repo = TypeInfo::new("OrderStore", "OrderStore.java", 1);
+        repo.add_annotation("JdbcRepository");
+        repo.add_annotation("Singleton");
+        assert_eq!(repo.layer, LayerType::Repository);
+        assert!(repo.is_bean());
+
+        // Panache: @ApplicationScoped 类实现 PanacheRepository<Order>
+        let mut panache = TypeInfo::new("OrderFinder", "OrderFinder.java", 1);
+        panache.add_annotation("ApplicationScoped");
+        assert_eq!(panache.layer, LayerType::Service);
+        panache.add_supertype("PanacheRepository");
+        assert_eq!(panache.layer, LayerType::Repository);
+        assert!(panache.is_dao());
+    }
     
     #[test]
     fn test_is_dao_type() {
-        let mut type_info = TypeInfo::new("UserRepository", PathBuf::from("test.java"), 1);
+        let mut type_info = TypeInfo::new("UserRepository", "test.java", 1);
         assert!(type_info.is_dao()); // 基于名称
         
         type_info.add_annotation("Repository");
@@ -1182,7 +1452,7 @@ mod tests {
         let mut table = SymbolTable::new();
         
         // 注册 Repository 类
-        let mut repo_type = TypeInfo::new("UserRepository", PathBuf::from("UserRepository.java"), 1);
+        let mut repo_type = TypeInfo::new("UserRepository", "UserRepository.java", 1);
         repo_type.add_annotation("Repository");
         table.register_class(repo_type);
         
@@ -1225,6 +1495,40 @@ mod tests {
         assert_eq!(method2.unwrap().params[0].type_name, "String");
     }
 
+    #[test]
+    fn test_resolve_chained_receiver_type() {
+        let mut table = SymbolTable::new();
+        let mut repo = TypeInfo::new_with_package("OrderStore", Some("com.shop.repo"), "OrderStore.java", 1);
+        repo.add_annotation("Repository");
+        table.register_class_fqn(repo);
+        table.register_field("CheckoutService", VarBinding::new("orderService", "OrderService", true));
+
+        let mut get_store = MethodInfo::new("getStore", "OrderService", 5);
+        get_store.return_type = Some("com.shop.repo.OrderStore".to_string());
+        table.register_method("OrderService", get_store);
+        let mut items = MethodInfo::new("items", "OrderStore", 8);
+        items.return_type = Some("List<Item>".to_string());
+        table.register_method("OrderStore", items);
+
+        assert_eq!(table.resolve_expr_type("CheckoutService", "orderService").as_deref(), Some("OrderService"));
+        assert_eq!(table.resolve_expr_type("CheckoutService", "this.orderService.getStore()").as_deref(), Some("OrderStore"));
+        assert_eq!(table.resolve_expr_type("CheckoutService", "orderService.getStore().items(a.b(), c)").as_deref(), Some("List"));
+        assert_eq!(table.resolve_expr_type("OrderService", "getStore()").as_deref(), Some("OrderStore"));
+        assert_eq!(table.resolve_expr_type("CheckoutService", "orderService.unknown()"), None);
+        assert_eq!(table.resolve_expr_type("CheckoutService", "localVar.getStore()"), None);
+
+        // 链式接收者按返回类型识别 DAO (方法名与变量名都不像 DAO)
+        assert!(table.is_dao_call("CheckoutService", "orderService.getStore()", "load"));
+        assert_eq!(table.lookup_expr_type("CheckoutService", "orderService.getStore()").map(|t| t.fqn.as_str()), Some("com.shop.repo.OrderStore"));
+
+        // 重载返回类型不一致时不猜测
+        let mut overload = MethodInfo::new("getStore", "OrderService", 9);
+        overload.add_param("region", "String");
+        overload.return_type = Some("ArchiveStore".to_string());
+        table.register_method("OrderService", overload);
+        assert_eq!(table.resolve_expr_type("CheckoutService", "orderService.getStore()"), None);
+    }
+
     #[test]
     fn test_method_signature() {
         let mut method = MethodInfo::new("save", "UserRepository", 20);
@@ -1238,14 +1542,14 @@ mod tests {
     fn test_symbol_table_merge() {
         // 创建第一个表
         let mut table1 = SymbolTable::new();
-        let mut repo_type = TypeInfo::new("UserRepository", PathBuf::from("UserRepository.java"), 1);
+        let mut repo_type = TypeInfo::new("UserRepository", "UserRepository.java", 1);
         repo_type.add_annotation("Repository");
         table1.register_class(repo_type);
         table1.register_field("UserService", VarBinding::new("userRepo", "UserRepository", true));
 
         // 创建第二个表
         let mut table2 = SymbolTable::new();
-        let service_type = TypeInfo::new("OrderService", PathBuf::from("OrderService.java"), 1);
+        let service_type = TypeInfo::new("OrderService", "OrderService.java", 1);
         table2.register_class(service_type);
         table2.register_field("OrderController", VarBinding::new("orderService", "OrderService", true));
 
@@ -1259,4 +1563,31 @@ mod tests {
         assert_eq!(table1.fields.len(), 2);
         assert!(table1.is_dao_var("UserService", "userRepo"));
     }
+
+    #[test]
+    fn test_effective_bean_name() {
+        let mut info = TypeInfo::new("OrderService", "OrderService.java", 1);
+        assert_eq!(info.effective_bean_name(), "orderService");
+        info.bean_name = Some("orders".to_string());
+        assert_eq!(info.effective_bean_name(), "orders");
+        assert_eq!(TypeInfo::new("URLService", "URLService.java", 1).effective_bean_name(), "URLService");
+    }
+
+    #[test]
+    fn test_resolve_constant() {
+        let mut table = SymbolTable::new();
+        table.register_constant(StringConstant {
+            class: "OrderSql".to_string(),
+            name: "FIND_ALL".to_string(),
+            value: "SELECT * FROM orders".to_string(),
+            file: "src/OrderSql.java".into(),
+            line: 5,
+        });
+
+        assert!(table.resolve_constant("OrderSql", "FIND_ALL").is_some());
+        assert!(table.resolve_constant("OrderDao", "OrderSql.FIND_ALL").is_some());
+        assert!(table.resolve_constant("OrderDao", "com.example.OrderSql.FIND_ALL").is_some());
+        assert!(table.resolve_constant("OrderDao", "FIND_ALL").is_none());
+        assert_eq!(table.resolve_constant("OrderSql", "FIND_ALL").unwrap().location(), "OrderSql.java:5");
+    }
 }
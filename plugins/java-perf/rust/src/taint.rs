@@ -24,8 +24,8 @@
 
 #![allow(dead_code)]
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use crate::shared_path::FilePath;
 use serde::{Serialize, Deserialize};
 use crate::symbol_table::{ImportIndex, SymbolTable};
 
@@ -155,12 +155,23 @@ impl MethodSig {
 /// 调用点
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallSite {
-    pub file: PathBuf,
+    pub file: FilePath,
     pub line: usize,
     pub callee: MethodSig,
     pub caller: MethodSig,
 }
 
+/// 调用图规模统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallGraphStats {
+    /// 出现在调用关系中的方法 (调用方或被调用方)
+    pub methods: usize,
+    /// 调用边 (调用点) 数
+    pub edges: usize,
+    /// 已登记文件位置的类
+    pub classes: usize,
+}
+
 /// 调用图 - 用于追踪 Controller -> Service -> DAO 链
 #[derive(Debug, Default)]
 pub struct CallGraph {
@@ -169,13 +180,84 @@ pub struct CallGraph {
     /// 方法签名 -> 调用该方法的其他方法  
     pub incoming: HashMap<MethodSig, Vec<CallSite>>,
     /// 类名 (FQN preferred) -> 文件路径
-    pub class_index: HashMap<String, PathBuf>,
+    pub class_index: HashMap<String, FilePath>,
     /// Class FQN -> Layer type (Controller/Service/Repository)
     /// 
     /// For accurate cross-package tracing, classes should be registered with their
     /// fully qualified names (e.g., "com.example.service.UserService").
     /// The trace_to_layer() method will first try FQN lookup, then fall back to simple name.
     pub class_layers: HashMap<String, LayerType>,
+    /// 被 Spring 代理拦截的方法 (FQN 与简单名两种键，同 class_index)
+    pub proxied: HashMap<MethodSig, ProxyKind>,
+    /// 方法体内的加锁与阻塞操作 (FQN 与简单名两种键，供 HELD_LOCK_BLOCKING 跨方法追踪)
+    pub effects: HashMap<MethodSig, MethodEffects>,
+}
+
+/// 方法体内直接执行的加锁与阻塞操作 (不含 lambda 与内部类)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodEffects {
+    /// 获取的锁: `类名.this` (synchronized 实例方法 / `synchronized (this)`)、`类名.class`、`类名.字段`
+    pub locks: Vec<String>,
+    /// 阻塞调用 (如 `restTemplate.getForObject`)
+    pub blocking: Vec<String>,
+}
+
+impl MethodEffects {
+    pub fn is_empty(&self) -> bool {
+        self.locks.is_empty() && self.blocking.is_empty()
+    }
+}
+
+/// 持锁期间经调用链到达的危险操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockHazard {
+    /// 获取另一把锁
+    Lock(String),
+    /// 阻塞调用
+    Blocking(String),
+    /// 调用 Repository 层 (数据库访问)
+    Repository,
+}
+
+/// 代理拦截类型：经 `this` 自调用时不经过代理，注解不生效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProxyKind {
+    /// @Transactional
+    Transactional,
+    /// @Cacheable / @CachePut / @CacheEvict / @Caching
+    Caching,
+    /// @Async
+    Async,
+}
+
+impl ProxyKind {
+    /// 方法或类上的注解 (不含 @) 对应的代理类型
+    pub fn from_annotation(annotation: &str) -> Option<Self> {
+        match annotation {
+            "Transactional" => Some(Self::Transactional),
+            "Cacheable" | "CachePut" | "CacheEvict" | "Caching" => Some(Self::Caching),
+            "Async" => Some(Self::Async),
+            _ => None,
+        }
+    }
+
+    /// 报告中的注解名
+    pub fn annotation(self) -> &'static str {
+        match self {
+            Self::Transactional => "@Transactional",
+            Self::Caching => "@Cacheable 等缓存注解",
+            Self::Async => "@Async",
+        }
+    }
+
+    /// 自调用时失效的效果
+    pub fn effect(self) -> &'static str {
+        match self {
+            Self::Transactional => "事务不生效",
+            Self::Caching => "缓存不生效，每次都执行方法体",
+            Self::Async => "在调用线程同步执行",
+        }
+    }
 }
 
 /// 代码层级类型
@@ -192,6 +274,15 @@ impl CallGraph {
         Self::default()
     }
 
+    pub fn stats(&self) -> CallGraphStats {
+        let methods: HashSet<&MethodSig> = self.outgoing.keys().chain(self.incoming.keys()).collect();
+        CallGraphStats {
+            methods: methods.len(),
+            edges: self.outgoing.values().map(Vec::len).sum(),
+            classes: self.class_index.len(),
+        }
+    }
+
     /// 合并另一个 CallGraph (用于 Rayon 并行 reduce) - v9.4
     pub fn merge(&mut self, other: Self) {
         // 合并 outgoing 边
@@ -205,12 +296,14 @@ impl CallGraph {
         // 合并类索引
         self.class_index.extend(other.class_index);
         self.class_layers.extend(other.class_layers);
+        self.proxied.extend(other.proxied);
+        self.effects.extend(other.effects);
     }
     
     /// 添加调用关系
-    pub fn add_call(&mut self, caller: MethodSig, callee: MethodSig, file: PathBuf, line: usize) {
+    pub fn add_call(&mut self, caller: MethodSig, callee: MethodSig, file: impl Into<FilePath>, line: usize) {
         let call_site = CallSite {
-            file: file.clone(),
+            file: file.into(),
             line,
             callee: callee.clone(),
             caller: caller.clone(),
@@ -241,11 +334,74 @@ impl CallGraph {
     /// # Note
     /// For accurate cross-package tracing (Property 11), always use FQN when available.
     /// The trace_to_layer() method will first try FQN lookup, then fall back to simple name.
-    pub fn register_class(&mut self, class_fqn: &str, file: PathBuf, layer: LayerType) {
-        self.class_index.insert(class_fqn.to_string(), file);
+    pub fn register_class(&mut self, class_fqn: &str, file: impl Into<FilePath>, layer: LayerType) {
+        self.class_index.insert(class_fqn.to_string(), file.into());
         self.class_layers.insert(class_fqn.to_string(), layer);
     }
     
+    /// 登记被代理拦截的方法 (方法注解，或类上的 @Transactional 等作用于类的全部方法)
+    pub fn mark_proxied(&mut self, method: MethodSig, kind: ProxyKind) {
+        self.proxied.entry(method).or_insert(kind);
+    }
+
+    /// 方法的代理拦截类型 (类名为 FQN 或简单名)
+    pub fn proxy_kind(&self, class: &str, method: &str) -> Option<ProxyKind> {
+        self.proxied.get(&MethodSig::new(class, method)).copied()
+    }
+
+    /// 登记方法体内的加锁与阻塞操作 (重载方法合并)
+    pub fn mark_effects(&mut self, method: MethodSig, effects: MethodEffects) {
+        let entry = self.effects.entry(method).or_default();
+        entry.locks.extend(effects.locks);
+        entry.blocking.extend(effects.blocking);
+    }
+
+    /// 持有 `held` 锁时调用 `start`：沿调用图 (广度优先，`start` 为第 1 层，最多 `max_depth` 层)
+    /// 查找获取其他锁、执行阻塞调用或进入 Repository 层的方法，返回最短调用路径与危险操作
+    ///
+    /// 重入同一把锁不算。方法按 FQN 查找，未登记时回退到简单类名；Phase 1 未能解析的被调用方
+    /// (接收者为字段等) 交给 `resolve` 按完整符号表解析，解析不了的边跳过。
+    pub fn find_lock_hazard(
+        &self,
+        start: &MethodSig,
+        held: &str,
+        max_depth: usize,
+        resolve: impl Fn(&CallSite) -> Option<MethodSig>,
+    ) -> Option<(Vec<MethodSig>, LockHazard)> {
+        let mut visited = HashSet::from([start.clone()]);
+        let mut frontier = vec![vec![start.clone()]];
+        for _ in 0..max_depth {
+            let mut next = Vec::new();
+            for path in frontier {
+                let method = path.last().expect("非空路径");
+                let simple = || MethodSig::new(method.simple_class_name(), &method.name);
+                let layer = self.class_layers.get(&method.class_fqn)
+                    .or_else(|| self.class_layers.get(method.simple_class_name()));
+                if layer == Some(&LayerType::Repository) {
+                    return Some((path, LockHazard::Repository));
+                }
+                if let Some(effects) = self.effects.get(method).or_else(|| self.effects.get(&simple())) {
+                    if let Some(lock) = effects.locks.iter().find(|lock| *lock != held) {
+                        return Some((path.clone(), LockHazard::Lock(lock.clone())));
+                    }
+                    if let Some(call) = effects.blocking.first() {
+                        return Some((path.clone(), LockHazard::Blocking(call.clone())));
+                    }
+                }
+                for site in self.outgoing.get(method).into_iter().flatten() {
+                    let callee = if site.callee.is_unresolved() { resolve(site) } else { Some(site.callee.clone()) };
+                    if let Some(callee) = callee.filter(|callee| visited.insert(callee.clone())) {
+                        let mut extended = path.clone();
+                        extended.push(callee);
+                        next.push(extended);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        None
+    }
+
     /// Trace from a method to a target architectural layer
     /// 
     /// This method performs a depth-first search through the call graph to find all paths
@@ -367,15 +523,15 @@ mod tests {
         let mut graph = CallGraph::new();
         
         // 注册类
-        graph.register_class("UserController", PathBuf::from("UserController.java"), LayerType::Controller);
-        graph.register_class("UserService", PathBuf::from("UserService.java"), LayerType::Service);
-        graph.register_class("UserRepository", PathBuf::from("UserRepository.java"), LayerType::Repository);
+        graph.register_class("UserController", "UserController.java", LayerType::Controller);
+        graph.register_class("UserService", "UserService.java", LayerType::Service);
+        graph.register_class("UserRepository", "UserRepository.java", LayerType::Repository);
         
         // Controller -> Service
         graph.add_call(
             MethodSig::new("UserController", "getUsers"),
             MethodSig::new("UserService", "findAll"),
-            PathBuf::from("UserController.java"),
+            "UserController.java",
             10,
         );
         
@@ -383,7 +539,7 @@ mod tests {
         graph.add_call(
             MethodSig::new("UserService", "findAll"),
             MethodSig::new("UserRepository", "findById"),
-            PathBuf::from("UserService.java"),
+            "UserService.java",
             20,
         );
         
@@ -656,7 +812,7 @@ mod tests {
             let field_type_info = TypeInfo::new_with_package(
                 &field_type_class,
                 Some(&field_type_pkg),
-                std::path::PathBuf::from("FieldType.java"),
+                "FieldType.java",
                 1,
             );
             symbol_table.register_class_fqn(field_type_info);
@@ -729,7 +885,7 @@ mod tests {
             let field_type_info = TypeInfo::new_with_package(
                 &field_type_class,
                 Some(&field_type_pkg),
-                std::path::PathBuf::from("FieldType.java"),
+                "FieldType.java",
                 1,
             );
             symbol_table.register_class_fqn(field_type_info);
@@ -815,9 +971,9 @@ mod tests {
             let mut graph = CallGraph::new();
             
             // Register classes with their FQNs
-            graph.register_class(&controller_fqn, PathBuf::from("Controller.java"), LayerType::Controller);
-            graph.register_class(&service_fqn, PathBuf::from("Service.java"), LayerType::Service);
-            graph.register_class(&repo_fqn, PathBuf::from("Repository.java"), LayerType::Repository);
+            graph.register_class(&controller_fqn, "Controller.java", LayerType::Controller);
+            graph.register_class(&service_fqn, "Service.java", LayerType::Service);
+            graph.register_class(&repo_fqn, "Repository.java", LayerType::Repository);
 
             // Create method signatures using FQNs
             let controller_sig = MethodSig::new_fqn(&controller_fqn, &controller_method);
@@ -828,13 +984,13 @@ mod tests {
             graph.add_call(
                 controller_sig.clone(),
                 service_sig.clone(),
-                PathBuf::from("Controller.java"),
+                "Controller.java",
                 10,
             );
             graph.add_call(
                 service_sig.clone(),
                 repo_sig.clone(),
-                PathBuf::from("Service.java"),
+                "Service.java",
                 20,
             );
 
@@ -960,10 +1116,10 @@ mod tests {
             let mut graph = CallGraph::new();
             
             // Register classes
-            graph.register_class(&controller_fqn, PathBuf::from("Controller.java"), LayerType::Controller);
-            graph.register_class(&service1_fqn, PathBuf::from("Service1.java"), LayerType::Service);
-            graph.register_class(&service2_fqn, PathBuf::from("Service2.java"), LayerType::Service);
-            graph.register_class(&repo_fqn, PathBuf::from("Repository.java"), LayerType::Repository);
+            graph.register_class(&controller_fqn, "Controller.java", LayerType::Controller);
+            graph.register_class(&service1_fqn, "Service1.java", LayerType::Service);
+            graph.register_class(&service2_fqn, "Service2.java", LayerType::Service);
+            graph.register_class(&repo_fqn, "Repository.java", LayerType::Repository);
 
             // Create method signatures
             let controller_sig = MethodSig::new_fqn(&controller_fqn, &controller_method);
@@ -973,10 +1129,10 @@ mod tests {
 
             // Add two paths: Controller -> Service1 -> Repository
             //                Controller -> Service2 -> Repository
-            graph.add_call(controller_sig.clone(), service1_sig.clone(), PathBuf::from("Controller.java"), 10);
-            graph.add_call(controller_sig.clone(), service2_sig.clone(), PathBuf::from("Controller.java"), 15);
-            graph.add_call(service1_sig.clone(), repo_sig.clone(), PathBuf::from("Service1.java"), 20);
-            graph.add_call(service2_sig.clone(), repo_sig.clone(), PathBuf::from("Service2.java"), 25);
+            graph.add_call(controller_sig.clone(), service1_sig.clone(), "Controller.java", 10);
+            graph.add_call(controller_sig.clone(), service2_sig.clone(), "Controller.java", 15);
+            graph.add_call(service1_sig.clone(), repo_sig.clone(), "Service1.java", 20);
+            graph.add_call(service2_sig.clone(), repo_sig.clone(), "Service2.java", 25);
 
             // Property 1: Should find both paths
             let paths = graph.trace_to_layer(&controller_sig, LayerType::Repository, 5);
@@ -0,0 +1,284 @@
+//! 性能相关的 TODO/FIXME 注释 (`scan --todo-debt`)
+//!
+//! `// TODO: 这里很慢，先临时这样`、`/* FIXME N+1, optimize later */` 这类注释往往标记着团队已知但尚未修复的热点。
+//! 开启后扫描 Java 文件中带 TODO/FIXME/HACK/XXX 标记且提到性能关键词 (slow、optimize、N+1、temporary、
+//! 性能、优化、临时等) 的注释，按所在方法与静态分析的问题关联:
+//!
+//! - 注释位于方法体内，或紧贴在方法声明之前 (Javadoc/行注释，最多隔 [`LEADING_GAP`] 行)，归属该方法；
+//!   其余注释 (字段、类级) 归属所在文件的方法之外
+//! - 同一文件同一归属的问题附加最近的一条注释 (JSON: `debt`，Markdown: 📝TODO@行号)
+//! - 报告单列这些注释，与问题位于同一方法的排在前面 (JSON: `todo_debt`)
+//!
+//! 只看注释文字，不判断注释描述的问题是否仍然存在。
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::ast_engine::AstIssue;
+use crate::hotness::{self, MethodSpan};
+use crate::scanner::truncate_display;
+
+/// 注释与其后方法声明之间最多相隔的行数
+pub const LEADING_GAP: usize = 2;
+
+/// 报告中列出的注释数
+const SHOWN: usize = 20;
+
+/// 注释文字的显示宽度上限
+const MAX_TEXT_WIDTH: usize = 120;
+
+static RE_MARKER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(TODO|FIXME|HACK|XXX)\b").unwrap());
+
+static RE_KEYWORD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:slow(?:er|ly|ness)?|optimi[sz]\w*|perf(?:ormance)?|n\s*\+\s*1|temporar(?:y|ily)|workaround|bottleneck|inefficient|latency|expensive)\b|慢|优化|性能|临时|瓶颈|卡顿")
+        .unwrap()
+});
+
+/// 一条性能相关的 TODO 注释
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebtComment {
+    /// 相对路径
+    pub path: String,
+    pub line: usize,
+    /// TODO / FIXME / HACK / XXX
+    pub marker: String,
+    /// 命中的性能关键词 (小写，去重)
+    pub keywords: Vec<String>,
+    /// 注释文字 (去掉注释符号，合并空白)
+    pub text: String,
+    /// 归属方法的全限定名 (方法之外的注释为 None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// 与注释归属相同的问题数
+    #[serde(default)]
+    pub findings: usize,
+}
+
+/// 问题上附加的注释引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebtRef {
+    pub line: usize,
+    pub marker: String,
+    pub text: String,
+}
+
+/// 快速预筛: 源码中有 TODO 类标记
+pub fn is_candidate(code: &str) -> bool {
+    RE_MARKER.is_match(code)
+}
+
+/// 收集文件中性能相关的 TODO 注释，按方法行范围确定归属
+pub fn collect(root: Node, code: &str, rel_path: &str, spans: &[MethodSpan]) -> Vec<DebtComment> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "line_comment" | "block_comment" | "comment") {
+            let Some(raw) = code.get(node.byte_range()) else { continue };
+            if let Some((marker, keywords, text)) = parse_comment(raw) {
+                let line = node.start_position().row + 1;
+                let end_line = node.end_position().row + 1;
+                out.push(DebtComment {
+                    path: rel_path.to_string(),
+                    line,
+                    marker,
+                    keywords,
+                    text,
+                    method: owner(spans, line, end_line).map(|span| span.fqn.clone()),
+                    findings: 0,
+                });
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    out.sort_by_key(|c| c.line);
+    out
+}
+
+/// 注释归属的方法: 所在的最内层方法，否则为紧随其后声明的方法
+fn owner(spans: &[MethodSpan], line: usize, end_line: usize) -> Option<&MethodSpan> {
+    hotness::innermost(spans, line).or_else(|| {
+        spans.iter()
+            .filter(|s| s.start_line > end_line && s.start_line <= end_line + 1 + LEADING_GAP)
+            .min_by_key(|s| s.start_line)
+    })
+}
+
+/// 解析注释: (标记, 关键词, 文字)；没有标记或不涉及性能时返回 None
+fn parse_comment(raw: &str) -> Option<(String, Vec<String>, String)> {
+    let marker = RE_MARKER.find(raw)?.as_str().to_string();
+    let mut keywords: Vec<String> = Vec::new();
+    for found in RE_KEYWORD.find_iter(raw) {
+        let keyword: String = found.as_str().to_lowercase().split_whitespace().collect();
+        if !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
+    }
+    if keywords.is_empty() {
+        return None;
+    }
+    let text: Vec<&str> = raw.lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches("/**")
+                .trim_start_matches("/*")
+                .trim_start_matches("//")
+                .trim_end_matches("*/")
+                .trim_start_matches('*')
+                .trim()
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    Some((marker, keywords, truncate_display(&text.join(" "), MAX_TEXT_WIDTH)))
+}
+
+/// 为问题附加同一归属中最近的注释，并统计每条注释关联的问题数
+pub fn annotate(issues: &mut [AstIssue], comments: &mut [DebtComment]) {
+    let mut by_owner: HashMap<(&str, Option<&str>), Vec<usize>> = HashMap::new();
+    for (index, comment) in comments.iter().enumerate() {
+        by_owner.entry((comment.path.as_str(), comment.method.as_deref())).or_default().push(index);
+    }
+    let mut findings = vec![0; comments.len()];
+    for issue in issues.iter_mut() {
+        let Some(candidates) = by_owner.get(&(issue.path.as_str(), issue.method.as_deref())) else { continue };
+        for &index in candidates {
+            findings[index] += 1;
+        }
+        let nearest = candidates.iter()
+            .map(|&index| &comments[index])
+            .min_by_key(|c| c.line.abs_diff(issue.line));
+        issue.debt = nearest.map(|c| DebtRef { line: c.line, marker: c.marker.clone(), text: c.text.clone() });
+    }
+    for (comment, count) in comments.iter_mut().zip(findings) {
+        comment.findings = count;
+    }
+}
+
+/// 报告排序: 关联问题多的在前，其余按位置
+pub fn sort(comments: &mut [DebtComment]) {
+    comments.sort_by(|a, b| b.findings.cmp(&a.findings).then_with(|| (&a.path, a.line).cmp(&(&b.path, b.line))));
+}
+
+/// 问题的注释标记 (未关联时为空)
+pub fn format_debt(issue: &AstIssue) -> String {
+    issue.debt.as_ref().map_or_else(String::new, |debt| format!(" 📝{}@{}", debt.marker, debt.line))
+}
+
+/// `com.shop.OrderService.load` → `OrderService.load`
+fn short_method(fqn: &str) -> &str {
+    let mut dots = fqn.rmatch_indices('.').map(|(index, _)| index);
+    match (dots.next(), dots.next()) {
+        (Some(_), Some(start)) => &fqn[start + 1..],
+        _ => fqn,
+    }
+}
+
+/// 报告章节 (没有注释时返回空串)
+pub fn render_section(comments: &[DebtComment]) -> String {
+    if comments.is_empty() {
+        return String::new();
+    }
+    let linked = comments.iter().filter(|c| c.findings > 0).count();
+    let mut out = format!("\n### 📝 性能相关 TODO ({} 条，{linked} 条与问题位于同一方法)\n\n", comments.len());
+    for comment in comments.iter().take(SHOWN) {
+        let scope = comment.method.as_deref().map_or_else(String::new, |method| format!(" `{}`", short_method(method)));
+        let findings = if comment.findings > 0 { format!(" — 同处 {} 个问题", comment.findings) } else { String::new() };
+        out.push_str(&format!(
+            "- `{}:{}` {} ({}){scope} - {}{findings}\n",
+            comment.path, comment.line, comment.marker, comment.keywords.join(", "), comment.text
+        ));
+    }
+    if comments.len() > SHOWN {
+        out.push_str(&format!("\n*（只列出前 {SHOWN} 条，使用 --json 查看全部）*\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tree_sitter_java::JavaTreeSitterAnalyzer;
+
+    const CODE: &str = r#"package com.shop;
+
+public class OrderService {
+    // TODO: cache this, lookup is slow
+    private Map<Long, Order> orders;
+
+    /**
+     * FIXME N+1 here, batch the item lookups
+     */
+    public List<Order> load(List<Long> ids) {
+        List<Order> out = new ArrayList<>();
+        for (Long id : ids) {
+            // HACK temporary workaround until the batch API ships
+            out.add(repo.findById(id));
+        }
+        return out;
+    }
+
+    // TODO rename this method
+    public void rename() {}
+
+    public void export() {
+        /* XXX 导出很慢，需要优化 */
+        write();
+    }
+}
+"#;
+
+    fn comments() -> Vec<DebtComment> {
+        let analyzer = JavaTreeSitterAnalyzer::new().unwrap();
+        analyzer.parse_with(CODE, |tree| {
+            let spans = hotness::collect_spans(tree.root_node(), CODE);
+            collect(tree.root_node(), CODE, "src/OrderService.java", &spans)
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_collect_performance_todos_with_owner() {
+        let found = comments();
+        let summary: Vec<(usize, &str, Option<&str>)> = found.iter()
+            .map(|c| (c.line, c.marker.as_str(), c.method.as_deref()))
+            .collect();
+        assert_eq!(summary, [
+            (4, "TODO", None),
+            (7, "FIXME", Some("com.shop.OrderService.load")),
+            (13, "HACK", Some("com.shop.OrderService.load")),
+            (23, "XXX", Some("com.shop.OrderService.export")),
+        ]);
+        assert_eq!(found[1].text, "FIXME N+1 here, batch the item lookups");
+        assert_eq!(found[1].keywords, ["n+1"]);
+        assert_eq!(found[2].keywords, ["temporary", "workaround"]);
+        assert_eq!(found[3].keywords, ["慢", "优化"]);
+        assert!(!is_candidate("// just slow code"));
+    }
+
+    #[test]
+    fn test_annotate_links_same_method() {
+        let mut found = comments();
+        let issue = |line: usize, method: Option<&str>| -> AstIssue {
+            serde_json::from_value(serde_json::json!({
+                "severity": "P0", "id": "N_PLUS_ONE", "file": "", "path": "src/OrderService.java", "line": line,
+                "description": "", "method": method
+            })).unwrap()
+        };
+        let mut issues = vec![issue(14, Some("com.shop.OrderService.load")), issue(5, None), issue(20, Some("com.shop.OrderService.rename"))];
+        annotate(&mut issues, &mut found);
+        assert_eq!(issues[0].debt.as_ref().map(|d| d.line), Some(13));
+        assert_eq!(issues[1].debt.as_ref().map(|d| d.marker.as_str()), Some("TODO"));
+        assert!(issues[2].debt.is_none());
+        assert_eq!(found.iter().map(|c| c.findings).collect::<Vec<_>>(), [1, 1, 1, 0]);
+
+        sort(&mut found);
+        let section = render_section(&found);
+        assert!(section.contains("(4 条，3 条与问题位于同一方法)"), "{section}");
+        assert!(section.contains("- `src/OrderService.java:7` FIXME (n+1) `OrderService.load` - FIXME N+1 here, batch the item lookups — 同处 1 个问题"), "{section}");
+        assert_eq!(format_debt(&issues[0]), " 📝HACK@13");
+    }
+}
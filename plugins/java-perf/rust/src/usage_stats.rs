@@ -0,0 +1,379 @@
+//! 本地使用统计 (`.javaperf/stats.jsonl`，默认关闭)
+//!
+//! 决定裁掉哪些噪音规则之前，需要知道各规则实际命中了多少、是否一直被忽略。开启后每次
+//! `scan` 结束追加一行记录，文件只写在本地，不做任何网络请求，团队可自行收集汇总:
+//!
+//! ```toml
+//! # .javaperf.toml
+//! [stats]
+//! enabled = true
+//! # file = "build/javaperf-stats.jsonl"   # 相对扫描根，默认 .javaperf/stats.jsonl
+//! ```
+//!
+//! ```text
+//! {"format":1,"timestamp":"2026-10-17T10:02:11+08:00","tool_version":"9.5.0","files":412,"rules_enabled":61,"duration_ms":5230,"jobs":8,"issues":37,"p0":4,"p1":33,"rules":{"N_PLUS_ONE":3,…}}
+//! ```
+//!
+//! 记录只含数量与耗时，不含路径、代码片段或指纹。按比例抽样的规则 (见 sampling 模块) 另记抽样比例、
+//! 抽样前后的问题数 (`sampling`)。`stats show` 汇总最近若干次扫描的耗时变化与各规则的命中趋势；
+//! 无法解析的行 (如写了一半) 计数后跳过。
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::analysis::AnalysisReport;
+use crate::project_config::{self, StatsConfig};
+use crate::sampling::{self, SampledRule};
+use crate::symbol_cache::CACHE_DIR;
+use crate::workspace::safe_relative;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 默认统计文件名 (位于 .javaperf/ 下)
+pub const STATS_FILE: &str = "stats.jsonl";
+
+/// 记录格式版本
+pub const FORMAT_VERSION: u32 = 1;
+
+/// 一次扫描的统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanRecord {
+    pub format: u32,
+    /// RFC 3339 本地时间
+    pub timestamp: String,
+    pub tool_version: String,
+    pub files: usize,
+    pub rules_enabled: usize,
+    pub duration_ms: u64,
+    /// 工作线程数 (比较不同机器/容器上的耗时时参考；旧记录为 0)
+    #[serde(default)]
+    pub jobs: usize,
+    pub issues: usize,
+    pub p0: usize,
+    pub p1: usize,
+    /// 规则 → 报告的问题数 (未命中的规则不记录)
+    #[serde(default)]
+    pub rules: BTreeMap<String, usize>,
+    /// 抽样报告的规则: 比例与抽样前后的问题数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sampling: Vec<SampledRule>,
+}
+
+impl ScanRecord {
+    pub fn from_report(report: &AnalysisReport) -> Self {
+        let mut rules: BTreeMap<String, usize> = BTreeMap::new();
+        for issue in &report.scan.issues {
+            *rules.entry(issue.issue_type.clone()).or_default() += 1;
+        }
+        Self {
+            format: FORMAT_VERSION,
+            timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            files: report.scan.file_count,
+            rules_enabled: report.rules.enabled,
+            duration_ms: report.timing.total_ms,
+            jobs: report.parallelism.jobs,
+            issues: report.scan.issues.len(),
+            p0: report.scan.p0_count(),
+            p1: report.scan.p1_count(),
+            rules,
+            sampling: report.sampling.clone(),
+        }
+    }
+}
+
+/// 统计文件路径 (`file` 为绝对路径或含 `..` 时报错，统计只写在扫描根内)
+pub fn stats_path(root: &Path, config: &StatsConfig) -> Result<PathBuf, BoxError> {
+    match &config.file {
+        None => Ok(root.join(CACHE_DIR).join(STATS_FILE)),
+        Some(file) => safe_relative(Path::new(file))
+            .map(|rel| root.join(rel))
+            .ok_or_else(|| format!("[stats] file 必须是扫描根下的相对路径 (不含 ..): {file}").into()),
+    }
+}
+
+/// 按 `.javaperf.toml` 的 `[stats]` 追加本次扫描的统计 (未开启时返回 None)
+pub fn record(root: &Path, report: &AnalysisReport) -> Result<Option<PathBuf>, BoxError> {
+    let config = project_config::load(root)?;
+    if !config.stats.enabled {
+        return Ok(None);
+    }
+    let path = stats_path(root, &config.stats)?;
+    append(&path, &ScanRecord::from_report(report))?;
+    Ok(Some(path))
+}
+
+fn append(path: &Path, record: &ScanRecord) -> Result<(), BoxError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    // 整行一次写入，避免并发扫描的记录交错
+    OpenOptions::new().create(true).append(true).open(path)?.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// 读取统计文件: (记录, 无法解析的行数)
+pub fn read(path: &Path) -> Result<(Vec<ScanRecord>, usize), BoxError> {
+    let content = std::fs::read_to_string(path)?;
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<ScanRecord>(line) {
+            Ok(record) => records.push(record),
+            Err(_) => skipped += 1,
+        }
+    }
+    Ok((records, skipped))
+}
+
+/// 规则在统计窗口内的命中情况
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleTrend {
+    pub id: String,
+    /// 命中的扫描次数
+    pub scans: usize,
+    /// 窗口内的问题总数
+    pub total: usize,
+    /// 窗口内第一次与最近一次扫描的问题数
+    pub first: usize,
+    pub latest: usize,
+    /// 最近一次扫描中按比例抽样报告 (问题数为抽样后)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SampledRule>,
+}
+
+/// `stats show` 的汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSummary {
+    pub file: String,
+    /// 统计文件中的全部扫描次数
+    pub total_scans: usize,
+    /// 无法解析而跳过的行
+    pub skipped_lines: usize,
+    /// 参与汇总的最近几次扫描 (由旧到新)
+    pub recent: Vec<ScanRecord>,
+    /// 窗口内的平均耗时，以及前一半与后一半扫描的平均耗时
+    pub avg_duration_ms: u64,
+    pub earlier_avg_duration_ms: u64,
+    pub later_avg_duration_ms: u64,
+    /// 按问题总数由多到少
+    pub rules: Vec<RuleTrend>,
+}
+
+/// 汇总最近 `last` 次扫描 (统计文件不存在时报错并提示如何开启)
+pub fn show(code_path: &str, last: usize) -> Result<StatsSummary, BoxError> {
+    let root = Path::new(code_path);
+    let config = project_config::load(root)?;
+    let path = stats_path(root, &config.stats)?;
+    if !path.is_file() {
+        return Err(format!("{} 不存在: 在 .javaperf.toml 中设置 [stats] enabled = true 后，scan 会记录使用统计", path.display()).into());
+    }
+    let (records, skipped) = read(&path)?;
+    Ok(summarize(path.display().to_string(), records, skipped, last))
+}
+
+fn summarize(file: String, records: Vec<ScanRecord>, skipped_lines: usize, last: usize) -> StatsSummary {
+    let total_scans = records.len();
+    let recent: Vec<ScanRecord> = records.into_iter().skip(total_scans.saturating_sub(last.max(1))).collect();
+    let avg = |records: &[ScanRecord]| {
+        if records.is_empty() { 0 } else { records.iter().map(|r| r.duration_ms).sum::<u64>() / records.len() as u64 }
+    };
+    let (earlier, later) = recent.split_at(recent.len() / 2);
+
+    let mut rules: BTreeMap<&str, RuleTrend> = BTreeMap::new();
+    for record in &recent {
+        for (id, count) in &record.rules {
+            let trend = rules.entry(id).or_insert_with(|| RuleTrend { id: id.clone(), scans: 0, total: 0, first: 0, latest: 0, sampling: None });
+            trend.scans += 1;
+            trend.total += count;
+        }
+    }
+    if let (Some(first), Some(latest)) = (recent.first(), recent.last()) {
+        // 抽样后没有报告任何问题的规则同样列出
+        for sampled in &latest.sampling {
+            rules.entry(&sampled.id).or_insert_with(|| RuleTrend {
+                id: sampled.id.clone(), scans: 0, total: 0, first: 0, latest: 0, sampling: None,
+            });
+        }
+        for trend in rules.values_mut() {
+            trend.first = first.rules.get(&trend.id).copied().unwrap_or(0);
+            trend.latest = latest.rules.get(&trend.id).copied().unwrap_or(0);
+            trend.sampling = latest.sampling.iter().find(|s| s.id == trend.id).cloned();
+        }
+    }
+    let mut rules: Vec<RuleTrend> = rules.into_values().collect();
+    rules.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.id.cmp(&b.id)));
+
+    StatsSummary {
+        file,
+        total_scans,
+        skipped_lines,
+        avg_duration_ms: avg(&recent),
+        // 只有一次扫描时前后两半相同
+        earlier_avg_duration_ms: avg(if earlier.is_empty() { later } else { earlier }),
+        later_avg_duration_ms: avg(later),
+        recent,
+        rules,
+    }
+}
+
+pub fn render(summary: &StatsSummary, json_output: bool) -> Value {
+    if json_output {
+        return json!(summary);
+    }
+    let recent = &summary.recent;
+    let mut out = format!("📈 使用统计 ({})\n\n", summary.file);
+    if recent.is_empty() {
+        out.push_str("尚无扫描记录\n");
+        return json!(out);
+    }
+    out.push_str(&format!(
+        "最近 {} / 共 {} 次扫描 ({} ~ {})，平均耗时 {} ms (前半 {} ms → 后半 {} ms)\n",
+        recent.len(), summary.total_scans, recent[0].timestamp, recent[recent.len() - 1].timestamp,
+        summary.avg_duration_ms, summary.earlier_avg_duration_ms, summary.later_avg_duration_ms
+    ));
+    if summary.skipped_lines > 0 {
+        out.push_str(&format!("⚠️ 跳过 {} 行无法解析的记录\n", summary.skipped_lines));
+    }
+
+    out.push_str("\n| 时间 | 文件 | 问题 | P0 | 耗时 (ms) |\n|------|------|------|----|-----------|\n");
+    for record in recent {
+        out.push_str(&format!("| {} | {} | {} | {} | {} |\n", record.timestamp, record.files, record.issues, record.p0, record.duration_ms));
+    }
+
+    if !summary.rules.is_empty() {
+        out.push_str("\n| 规则 | 命中扫描 | 问题总数 | 趋势 |\n|------|----------|----------|------|\n");
+        for rule in &summary.rules {
+            let arrow = match rule.latest.cmp(&rule.first) {
+                std::cmp::Ordering::Less => "↓",
+                std::cmp::Ordering::Equal => "→",
+                std::cmp::Ordering::Greater => "↑",
+            };
+            let sampled = rule.sampling.as_ref().map_or_else(String::new, |s| {
+                format!(" (抽样 {}%，命中 {})", sampling::format_rate(s.rate), s.matched)
+            });
+            out.push_str(&format!(
+                "| {}{sampled} | {}/{} | {} | {} {arrow} {} |\n",
+                rule.id, rule.scans, recent.len(), rule.total, rule.first, rule.latest
+            ));
+        }
+        out.push_str("\n> 每次扫描都命中且数量不降的规则，可能是被团队忽略的噪音，调整或关闭前先确认问题是否真实。\n");
+    }
+    json!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(duration_ms: u64, rules: &[(&str, usize)]) -> ScanRecord {
+        let rules: BTreeMap<String, usize> = rules.iter().map(|(id, count)| (id.to_string(), *count)).collect();
+        ScanRecord {
+            format: FORMAT_VERSION,
+            timestamp: "2026-10-17T10:00:00+08:00".to_string(),
+            tool_version: "9.5.0".to_string(),
+            files: 10,
+            rules_enabled: 60,
+            duration_ms,
+            jobs: 4,
+            issues: rules.values().sum(),
+            p0: 0,
+            p1: rules.values().sum(),
+            rules,
+            sampling: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_skips_partial_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".javaperf").join(STATS_FILE);
+        append(&path, &record(100, &[("N_PLUS_ONE", 2)])).unwrap();
+        // 中断时写了一半的行
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"format\":1,\"times\n").unwrap();
+        append(&path, &record(200, &[])).unwrap();
+
+        let (records, skipped) = read(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(skipped, 1);
+        assert_eq!(records[0].rules["N_PLUS_ONE"], 2);
+    }
+
+    #[test]
+    fn test_summarize_rule_trends_over_window() {
+        let records = vec![
+            record(900, &[("EMPTY_CATCH", 9)]),
+            record(100, &[("N_PLUS_ONE", 4), ("EMPTY_CATCH", 5)]),
+            record(200, &[("EMPTY_CATCH", 5)]),
+            record(300, &[("N_PLUS_ONE", 1), ("EMPTY_CATCH", 6)]),
+            record(500, &[("EMPTY_CATCH", 6), ("LARGE_ARRAY", 1)]),
+        ];
+        let summary = summarize("stats.jsonl".to_string(), records, 0, 4);
+        assert_eq!(summary.total_scans, 5);
+        assert_eq!(summary.recent.len(), 4);
+        assert_eq!((summary.avg_duration_ms, summary.earlier_avg_duration_ms, summary.later_avg_duration_ms), (275, 150, 400));
+        let trends: Vec<(&str, usize, usize, usize, usize)> = summary.rules.iter()
+            .map(|r| (r.id.as_str(), r.scans, r.total, r.first, r.latest))
+            .collect();
+        assert_eq!(trends, [("EMPTY_CATCH", 4, 22, 5, 6), ("N_PLUS_ONE", 2, 5, 4, 0), ("LARGE_ARRAY", 1, 1, 0, 1)]);
+
+        let text = render(&summary, false);
+        let text = text.as_str().unwrap();
+        assert!(text.contains("最近 4 / 共 5 次扫描"), "{text}");
+        assert!(text.contains("| N_PLUS_ONE | 2/4 | 5 | 4 ↓ 0 |"), "{text}");
+    }
+
+    #[test]
+    fn test_summarize_sampled_rules() {
+        let mut latest = record(100, &[("N_PLUS_ONE", 1)]);
+        latest.sampling = vec![
+            SampledRule { id: "MANUAL_DTO_COPY".to_string(), rate: 0.1, matched: 4, reported: 0 },
+            SampledRule { id: "N_PLUS_ONE".to_string(), rate: 0.5, matched: 3, reported: 1 },
+        ];
+        let summary = summarize("stats.jsonl".to_string(), vec![record(100, &[]), latest], 0, 10);
+        let sampled: Vec<(&str, usize, Option<usize>)> = summary.rules.iter()
+            .map(|r| (r.id.as_str(), r.total, r.sampling.as_ref().map(|s| s.matched)))
+            .collect();
+        assert_eq!(sampled, [("N_PLUS_ONE", 1, Some(3)), ("MANUAL_DTO_COPY", 0, Some(4))]);
+        let text = render(&summary, false);
+        assert!(text.as_str().unwrap().contains("| MANUAL_DTO_COPY (抽样 10%，命中 4) | 0/2 | 0 | 0 → 0 |"), "{text}");
+    }
+
+    #[test]
+    fn test_record_only_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = show(dir.path().to_str().unwrap(), 10).unwrap_err().to_string();
+        assert!(err.contains("[stats] enabled = true"), "{err}");
+
+        std::fs::write(dir.path().join(project_config::CONFIG_FILE), "[stats]\nenabled = true\nfile = \"build/stats.jsonl\"\n").unwrap();
+        let config = project_config::load(dir.path()).unwrap();
+        assert_eq!(stats_path(dir.path(), &config.stats).unwrap(), dir.path().join("build/stats.jsonl"));
+        assert_eq!(stats_path(dir.path(), &StatsConfig::default()).unwrap(), dir.path().join(".javaperf/stats.jsonl"));
+    }
+
+    #[test]
+    fn test_stats_file_must_stay_under_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = |file: &str| StatsConfig { enabled: true, file: Some(file.to_string()) };
+        assert_eq!(stats_path(dir.path(), &config("./build/s.jsonl")).unwrap(), dir.path().join("build/s.jsonl"));
+        for file in ["/tmp/stats.jsonl", "../stats.jsonl", "build/../../stats.jsonl", ""] {
+            let err = stats_path(dir.path(), &config(file)).unwrap_err().to_string();
+            assert!(err.contains("相对路径"), "{file}: {err}");
+        }
+
+        // 配置了越界路径时 scan 不写入任何统计
+        let root = dir.path().join("svc");
+        std::fs::create_dir(&root).unwrap();
+        let report = crate::ast_engine::analyze_project(root.to_str().unwrap(), &Default::default()).unwrap();
+        std::fs::write(root.join(project_config::CONFIG_FILE), "[stats]\nenabled = true\nfile = \"../escaped.jsonl\"\n").unwrap();
+        assert!(super::record(&root, &report).is_err());
+        assert!(!dir.path().join("escaped.jsonl").exists());
+    }
+}
@@ -0,0 +1,387 @@
+//! 修复验证模式 (`verify --against report.json`)
+//!
+//! 迭代修复流程中，每改一处就全量扫描代价太高。verify 读取上一次
+//! `java-perf --json scan` 的输出，只重新分析出现过问题的文件，
+//! 并逐条给出状态:
+//!
+//! - **已修复 (fixed)**: 同一文件中不再出现该规则的对应问题
+//! - **仍存在 (still_present)**: 同规则、同一行仍然报告
+//! - **已移动 (moved)**: 同规则、同一行源码出现在其他行 (上方插入/删除了代码)
+//! - **无法验证 (unverifiable)**: 跨文件后处理产生的问题 (静态初始化成环、构建依赖审计)，
+//!   只重新分析单个文件无法判断，需要重新扫描
+//!
+//! 只重新分析标记过的文件，但先重建 Phase 1 索引 (存在 `--cache` 符号缓存时增量复用)，
+//! 依赖符号表与调用图的规则与全量扫描结果一致。状态匹配只依赖规则 ID 与位置，不受置信度影响。
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ast_engine::{build_index, locate_issue, AstIssue, FileAnalyzers};
+use crate::dedup::{dedup, DedupPolicy};
+use crate::dependency_audit;
+use crate::encoding::read_source;
+use crate::shared_path::FilePath;
+use crate::project_config;
+use crate::project_detector::detect_stack;
+use crate::rules::registry::RuleRegistry;
+use crate::static_init;
+use crate::symbol_cache::{self, ProjectIndex};
+
+/// 单条问题的验证状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixStatus {
+    Fixed,
+    StillPresent,
+    Moved,
+    Unverifiable,
+}
+
+/// 由跨文件后处理 (而非逐文件分析) 产生的规则，verify 无法重新判断
+pub fn is_cross_file_rule(id: &str) -> bool {
+    static_init::RULES.contains(&id) || dependency_audit::RULES.contains(&id)
+}
+
+/// 验证结果条目
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyEntry {
+    pub id: String,
+    pub path: String,
+    pub line: usize,
+    pub status: FixStatus,
+    /// 移动后的行号 (仅 Moved)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_line: Option<usize>,
+}
+
+/// 验证汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyResult {
+    pub entries: Vec<VerifyEntry>,
+    /// 重新分析的文件数
+    pub files_rescanned: usize,
+    /// 已标记文件中新出现的问题
+    pub new_issues: Vec<AstIssue>,
+}
+
+impl VerifyResult {
+    pub fn count(&self, status: FixStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+}
+
+/// 从 `--json scan` 的输出中读取问题列表
+///
+/// 同时接受带 `{"success":true,"data":{...}}` 包装的完整输出和裸 data 对象。
+pub fn load_report(report_json: &str) -> Result<Vec<AstIssue>, Box<dyn std::error::Error>> {
+    let value: Value = serde_json::from_str(report_json)?;
+    let data = value.get("data").unwrap_or(&value);
+    let issues = data.get("issues")
+        .ok_or("report.json 中缺少 issues 字段 (请使用 `java-perf --json scan` 生成)")?;
+    Ok(serde_json::from_value(issues.clone())?)
+}
+
+/// 将同一文件的旧问题与新问题逐条匹配
+///
+/// 先按 (规则, 行号) 精确匹配，再按 (规则, 行源码) 匹配移动的问题，
+/// 每个新问题最多被匹配一次。返回 (验证条目, 未被匹配的新问题下标)。
+pub fn match_file_issues(previous: &[AstIssue], current: &[AstIssue]) -> (Vec<VerifyEntry>, Vec<usize>) {
+    let mut used: HashSet<usize> = HashSet::new();
+    let mut results: Vec<Option<VerifyEntry>> = vec![None; previous.len()];
+
+    // Pass 1: 同规则同一行
+    for (i, prev) in previous.iter().enumerate() {
+        let hit = current.iter().enumerate()
+            .find(|(j, cur)| !used.contains(j) && cur.issue_type == prev.issue_type && cur.line == prev.line);
+        if let Some((j, _)) = hit {
+            used.insert(j);
+            results[i] = Some(entry(prev, FixStatus::StillPresent, None));
+        }
+    }
+
+    // Pass 2: 同规则同源码，位置变化
+    for (i, prev) in previous.iter().enumerate() {
+        if results[i].is_some() {
+            continue;
+        }
+        let hit = current.iter().enumerate()
+            .filter(|(j, cur)| !used.contains(j) && cur.issue_type == prev.issue_type)
+            .filter(|(_, cur)| !prev.snippet.is_empty() && cur.snippet == prev.snippet)
+            .min_by_key(|(_, cur)| cur.line.abs_diff(prev.line));
+        results[i] = Some(match hit {
+            Some((j, cur)) => {
+                used.insert(j);
+                entry(prev, FixStatus::Moved, Some(cur.line))
+            }
+            None => entry(prev, FixStatus::Fixed, None),
+        });
+    }
+
+    let unmatched = (0..current.len()).filter(|j| !used.contains(j)).collect();
+    (results.into_iter().flatten().collect(), unmatched)
+}
+
+fn entry(prev: &AstIssue, status: FixStatus, new_line: Option<usize>) -> VerifyEntry {
+    VerifyEntry {
+        id: prev.issue_type.clone(),
+        path: prev.path.to_string(),
+        line: prev.line,
+        status,
+        new_line,
+    }
+}
+
+/// 重新分析上次报告中出现过问题的文件
+pub fn verify_against(code_path: &str, report_path: &str) -> Result<VerifyResult, Box<dyn std::error::Error>> {
+    let root = Path::new(code_path);
+    // 报告可能以任意 --dedup 策略生成：两侧都按行合并后再比对，避免被合并的次要规则误判为新增/已修复
+    let previous = dedup(load_report(&std::fs::read_to_string(report_path)?)?, DedupPolicy::Line);
+
+    // 按文件分组 (BTreeMap 保证输出顺序稳定)
+    let mut by_file: BTreeMap<String, Vec<AstIssue>> = BTreeMap::new();
+    for issue in previous {
+        let key = if issue.path.as_str().is_empty() { issue.file.clone() } else { issue.path.to_string() };
+        by_file.entry(key).or_default().push(issue);
+    }
+
+    let config = project_config::load(root)?;
+    let mut registry = if root.is_dir() {
+        RuleRegistry::with_stack(&detect_stack(root))
+    } else {
+        RuleRegistry::default()
+    };
+    registry.apply_overrides(&config.rule_overrides)?;
+    let exclusions = config.exclusions()?;
+    let analyzers = FileAnalyzers::new(&registry)?.with_exclusions(root, exclusions);
+    let index = root.is_dir().then(|| phase1_index(root, &analyzers));
+
+    let mut entries = Vec::new();
+    let mut new_issues = Vec::new();
+    let mut files_rescanned = 0;
+
+    for (rel_path, prev_issues) in &by_file {
+        let (cross_file, prev_issues): (Vec<AstIssue>, Vec<AstIssue>) = prev_issues.iter()
+            .cloned()
+            .partition(|issue| is_cross_file_rule(&issue.issue_type));
+        entries.extend(cross_file.iter().map(|issue| entry(issue, FixStatus::Unverifiable, None)));
+        if prev_issues.is_empty() {
+            continue;
+        }
+        let file_path = if root.is_dir() { root.join(rel_path) } else { root.to_path_buf() };
+
+        // 文件已删除 → 全部视为已修复
+        let current: Vec<AstIssue> = match read_source(&file_path) {
+            Ok(source) => {
+                files_rescanned += 1;
+                let issue_path = FilePath::new(rel_path);
+                let issues = analyzers.analyze_file(&file_path, &source.content, index.as_ref().map(|i| &i.table), index.as_ref().map(|i| &i.graph))
+                    .into_iter()
+                    .map(|issue| locate_issue(issue, &issue_path, &source.content))
+                    .collect();
+                dedup(issues, DedupPolicy::Line)
+            }
+            Err(_) => Vec::new(),
+        };
+
+        let (file_entries, unmatched) = match_file_issues(&prev_issues, &current);
+        entries.extend(file_entries);
+        new_issues.extend(unmatched.into_iter().map(|j| current[j].clone()));
+    }
+
+    Ok(VerifyResult { entries, files_rescanned, new_issues })
+}
+
+/// 重建 Phase 1 索引：项目已有符号缓存 (`--cache`) 时增量更新，否则全量构建
+fn phase1_index(root: &Path, analyzers: &FileAnalyzers) -> ProjectIndex {
+    let files = symbol_cache::java_files(root);
+    let paths: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+    if symbol_cache::cache_path(root).is_file() {
+        return symbol_cache::build(root, &paths, analyzers.java(), false, &HashSet::new());
+    }
+    let (table, graph, imports) = build_index(&paths, analyzers.java());
+    ProjectIndex { table, graph, imports, ..Default::default() }
+}
+
+/// 渲染验证结果
+pub fn render(result: &VerifyResult, json_output: bool) -> Value {
+    let fixed = result.count(FixStatus::Fixed);
+    let still = result.count(FixStatus::StillPresent);
+    let moved = result.count(FixStatus::Moved);
+    let unverifiable = result.count(FixStatus::Unverifiable);
+
+    if json_output {
+        return json!({
+            "files_rescanned": result.files_rescanned,
+            "fixed": fixed,
+            "still_present": still,
+            "moved": moved,
+            "unverifiable": unverifiable,
+            "new": result.new_issues.len(),
+            "entries": result.entries,
+            "new_issues": result.new_issues,
+        });
+    }
+
+    let mut report = format!(
+        "## 🔁 修复验证\n\n\
+        **重新分析**: {} 个文件\n\
+        **已修复**: {fixed} | **仍存在**: {still} | **已移动**: {moved} | **无法验证**: {unverifiable} | **新增**: {}\n\n",
+        result.files_rescanned, result.new_issues.len()
+    );
+
+    let sections = [
+        (FixStatus::StillPresent, "### 🔴 仍存在"),
+        (FixStatus::Moved, "### ↪️ 已移动 (仍需修复)"),
+        (FixStatus::Fixed, "### ✅ 已修复"),
+        (FixStatus::Unverifiable, "### ❔ 无法验证 (跨文件规则，请重新扫描)"),
+    ];
+    for (status, title) in sections {
+        let items: Vec<_> = result.entries.iter().filter(|e| e.status == status).collect();
+        if items.is_empty() {
+            continue;
+        }
+        report.push_str(title);
+        report.push_str("\n\n");
+        for e in items {
+            match e.new_line {
+                Some(new_line) => report.push_str(&format!("- `{}` {}:{} → {}\n", e.id, e.path, e.line, new_line)),
+                None => report.push_str(&format!("- `{}` {}:{}\n", e.id, e.path, e.line)),
+            }
+        }
+        report.push('\n');
+    }
+
+    if !result.new_issues.is_empty() {
+        report.push_str("### 🆕 新增问题 (修复引入)\n\n");
+        for issue in &result.new_issues {
+            report.push_str(&format!("- `{}` {}:{} - {}\n", issue.issue_type, issue.path, issue.line, issue.description));
+        }
+    }
+
+    json!(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast_engine::Severity;
+
+    fn issue(id: &str, line: usize, snippet: &str) -> AstIssue {
+        AstIssue {
+            severity: Severity::P0,
+            issue_type: id.to_string(),
+            file: "A.java".to_string(),
+            path: "src/A.java".into(),
+            line,
+            column: 0,
+            description: String::new(),
+            context: None,
+            snippet: snippet.to_string(),
+            fingerprint: String::new(),
+            hotness: 0,
+            method: None,
+            secondary: Vec::new(),
+            inactive: false,
+            suppressed: false,
+            suppression: None,
+            owners: Vec::new(),
+            suggested_patch: None,
+            triage: None,
+            age: None,
+            budget: None,
+            coverage: None,
+            debt: None,
+            sql: None,
+        }
+    }
+
+    #[test]
+    fn test_match_still_present_moved_fixed() {
+        let previous = vec![
+            issue("N_PLUS_ONE", 10, "repo.findById(id);"),
+            issue("EMPTY_CATCH", 20, "} catch (Exception e) {"),
+            issue("SYSTEM_EXIT", 30, "System.exit(1);"),
+        ];
+        let current = vec![
+            issue("N_PLUS_ONE", 10, "repo.findById(id);"),
+            issue("EMPTY_CATCH", 24, "} catch (Exception e) {"),
+            issue("UNBOUNDED_POOL", 40, "Executors.newCachedThreadPool();"),
+        ];
+
+        let (entries, unmatched) = match_file_issues(&previous, &current);
+        assert_eq!(entries[0].status, FixStatus::StillPresent);
+        assert_eq!(entries[1].status, FixStatus::Moved);
+        assert_eq!(entries[1].new_line, Some(24));
+        assert_eq!(entries[2].status, FixStatus::Fixed);
+        assert_eq!(unmatched, vec![2]);
+    }
+
+    #[test]
+    fn test_each_current_issue_matched_once() {
+        let previous = vec![
+            issue("N_PLUS_ONE", 10, "repo.save(x);"),
+            issue("N_PLUS_ONE", 12, "repo.save(x);"),
+        ];
+        let current = vec![issue("N_PLUS_ONE", 15, "repo.save(x);")];
+
+        let (entries, unmatched) = match_file_issues(&previous, &current);
+        assert_eq!(entries.iter().filter(|e| e.status == FixStatus::Moved).count(), 1);
+        assert_eq!(entries.iter().filter(|e| e.status == FixStatus::Fixed).count(), 1);
+        assert!(unmatched.is_empty());
+    }
+
+    #[test]
+    fn test_load_report_accepts_wrapped_and_raw() {
+        let raw = r#"{"issues":[{"severity":"P0","id":"SYSTEM_EXIT","file":"A.java","path":"src/A.java","line":3,"description":"d"}]}"#;
+        let wrapped = format!(r#"{{"success":true,"data":{raw}}}"#);
+        assert_eq!(load_report(raw).unwrap().len(), 1);
+        assert_eq!(load_report(&wrapped).unwrap()[0].path, "src/A.java");
+        assert!(load_report(r#"{"data":"markdown"}"#).is_err());
+    }
+
+    #[test]
+    fn test_verify_against_rescans_flagged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Exit.java");
+        std::fs::write(&file, "public class Exit {\n    void a() { System.exit(1); }\n}\n").unwrap();
+
+        let scan = crate::ast_engine::radar_scan_json(dir.path().to_str().unwrap(), &crate::ast_engine::ScanOptions::default()).unwrap();
+        let report_path = dir.path().join("report.json");
+        std::fs::write(&report_path, serde_json::to_string(&json!({"success": true, "data": scan})).unwrap()).unwrap();
+
+        // 在问题上方插入两行 → moved
+        std::fs::write(&file, "public class Exit {\n\n\n    void a() { System.exit(1); }\n}\n").unwrap();
+        let result = verify_against(dir.path().to_str().unwrap(), report_path.to_str().unwrap()).unwrap();
+        assert_eq!(result.files_rescanned, 1);
+        assert_eq!(result.count(FixStatus::Moved), 1);
+
+        // 删除调用 → fixed
+        std::fs::write(&file, "public class Exit {\n    void a() { }\n}\n").unwrap();
+        let result = verify_against(dir.path().to_str().unwrap(), report_path.to_str().unwrap()).unwrap();
+        assert_eq!(result.count(FixStatus::Fixed), 1);
+        assert_eq!(result.count(FixStatus::StillPresent), 0);
+    }
+
+    #[test]
+    fn test_verify_cross_file_rules_unverifiable() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Shape.java"), "public class Shape {\n    public static final Shape DEFAULT = new Circle();\n}\n").unwrap();
+        std::fs::write(dir.path().join("Circle.java"), "public class Circle extends Shape {\n}\n").unwrap();
+
+        let scan = crate::ast_engine::radar_scan_json(dir.path().to_str().unwrap(), &crate::ast_engine::ScanOptions::default()).unwrap();
+        assert!(scan["issues"].as_array().unwrap().iter().any(|i| i["id"] == "STATIC_INIT_CYCLE"), "{scan}");
+        let report_path = dir.path().join("report.json");
+        std::fs::write(&report_path, serde_json::to_string(&scan).unwrap()).unwrap();
+
+        // 代码未改动：跨文件规则不能因为单文件重新分析没有报告而判为已修复
+        let result = verify_against(dir.path().to_str().unwrap(), report_path.to_str().unwrap()).unwrap();
+        assert_eq!(result.count(FixStatus::Fixed), 0, "{:?}", result.entries);
+        let cycle = result.entries.iter().find(|e| e.id == "STATIC_INIT_CYCLE").unwrap();
+        assert_eq!(cycle.status, FixStatus::Unverifiable);
+        assert!(result.new_issues.is_empty());
+        assert!(render(&result, false).as_str().unwrap().contains("无法验证 (跨文件规则，请重新扫描)"));
+    }
+}
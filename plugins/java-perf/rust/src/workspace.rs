@@ -0,0 +1,433 @@
+//! 远程/压缩包扫描源 (`scan --git` / `scan --archive`)
+//!
+//! 平台团队审计大量服务时通常没有本地 checkout：这里把 git 仓库或源码压缩包
+//! 拉取/解压到临时工作区，扫描结束后按清理策略删除。
+//!
+//! - git: `git init` + `git fetch --depth 1 -- <url> <ref>` + `checkout FETCH_HEAD`，
+//!   分支、tag 与 commit (服务端允许时) 走同一条路径；以 `-` 开头的 url/ref 被拒绝
+//! - 压缩包: `.zip`、`.tar`、`.tar.gz`/`.tgz`；拒绝越出工作区的条目，跳过符号链接
+//! - 大小上限: 解压时按实际写入字节计数；git fetch 期间定时统计已接收的对象大小，
+//!   超限即终止传输，检出后再统计工作树大小 (压缩的 pack 展开后可能更大)
+//!
+//! 工作区在 [`Workspace`] drop 时删除 (包括拉取失败的半成品)，`keep` 时保留供排查。
+
+use std::fs;
+use std::io::{self, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use walkdir::WalkDir;
+
+use crate::git;
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// 默认大小上限 (MB)
+pub const DEFAULT_MAX_MB: u64 = 512;
+
+/// git fetch 期间统计已接收大小的间隔
+const FETCH_POLL: Duration = Duration::from_millis(50);
+
+/// 扫描源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteSource {
+    /// git 仓库 URL 与 ref (None 为远端 HEAD)
+    Git { url: String, reference: Option<String> },
+    /// 本地压缩包
+    Archive(PathBuf),
+}
+
+/// 拉取参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchOptions {
+    /// 工作区内容大小上限 (字节)
+    pub max_bytes: u64,
+    /// 扫描结束后保留工作区
+    pub keep: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self { max_bytes: DEFAULT_MAX_MB * 1024 * 1024, keep: false }
+    }
+}
+
+/// 临时工作区
+#[derive(Debug)]
+pub struct Workspace {
+    dir: PathBuf,
+    keep: bool,
+}
+
+impl Workspace {
+    fn create(keep: bool) -> Result<Self, BoxError> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("java-perf-{}-{}", std::process::id(), nanos));
+        fs::create_dir_all(&dir).map_err(|e| format!("无法创建工作区 {}: {e}", dir.display()))?;
+        Ok(Self { dir, keep })
+    }
+
+    /// 扫描根目录
+    ///
+    /// 压缩包常见的单一顶层目录 (`project-main/`) 会被展开，保证项目配置
+    /// (`.javaperf.toml`、pom.xml) 位于扫描根。
+    pub fn root(&self) -> PathBuf {
+        let entries: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map(|rd| rd.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+            .unwrap_or_default();
+        match entries.as_slice() {
+            [only] if only.is_dir() && only.file_name().is_some_and(|n| n != ".git") => only.clone(),
+            _ => self.dir.clone(),
+        }
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if self.keep {
+            tracing::info!("保留工作区 {}", self.dir.display());
+        } else if let Err(e) = fs::remove_dir_all(&self.dir) {
+            tracing::warn!("清理工作区 {} 失败: {e}", self.dir.display());
+        }
+    }
+}
+
+/// 拉取/解压到临时工作区
+pub fn fetch(source: &RemoteSource, options: FetchOptions) -> Result<Workspace, BoxError> {
+    let workspace = Workspace::create(options.keep)?;
+    match source {
+        RemoteSource::Git { url, reference } => {
+            clone(url, reference.as_deref(), &workspace.dir, options.max_bytes)?;
+            let size = tree_size(&workspace.dir);
+            if size > options.max_bytes {
+                return Err(size_error(options.max_bytes).into());
+            }
+        }
+        RemoteSource::Archive(path) => extract(path, &workspace.dir, options.max_bytes)?,
+    }
+    Ok(workspace)
+}
+
+// ============================================================================
+// git
+// ============================================================================
+
+/// URL 与 ref 以 `-` 开头时会被 git 当作选项 (如 `--upload-pack=<命令>`)，直接拒绝
+fn clone(url: &str, reference: Option<&str>, dir: &Path, max_bytes: u64) -> Result<(), BoxError> {
+    let reference = reference.unwrap_or("HEAD");
+    if url.starts_with('-') {
+        return Err(format!("非法的 git 仓库地址: {url}").into());
+    }
+    if reference.starts_with('-') {
+        return Err(format!("非法的 git ref: {reference}").into());
+    }
+    git::run(dir, &["init", "-q"])?;
+    fetch_bounded(dir, &["fetch", "-q", "--depth", "1", "--", url, reference], max_bytes)?;
+    git::run(dir, &["checkout", "-q", "FETCH_HEAD"])?;
+    Ok(())
+}
+
+/// 执行 git fetch，传输期间 `.git/objects` 超过 `max_bytes` 时终止进程
+///
+/// git 客户端没有接收大小上限的选项 (`receive.maxInputSize` 只作用于服务端)，这里按间隔统计已写入的
+/// pack/松散对象；进程结束后再统计一次，覆盖两次统计之间完成的传输。
+fn fetch_bounded(dir: &Path, args: &[&str], max_bytes: u64) -> Result<(), BoxError> {
+    let mut child = git::command(dir, args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("无法执行 git: {e}"))?;
+    let objects = dir.join(".git").join("objects");
+    loop {
+        let status = child.try_wait()?;
+        if tree_size(&objects) > max_bytes {
+            if status.is_none() {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            return Err(size_error(max_bytes).into());
+        }
+        match status {
+            Some(status) if status.success() => return Ok(()),
+            Some(_) => {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    pipe.read_to_string(&mut stderr)?;
+                }
+                return Err(format!("git {} 失败: {}", args.join(" "), stderr.trim()).into());
+            }
+            None => std::thread::sleep(FETCH_POLL),
+        }
+    }
+}
+
+/// 目录下文件的总大小 (不含 .git)
+fn tree_size(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+// ============================================================================
+// 压缩包
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+fn size_error(max_bytes: u64) -> String {
+    format!("内容超过大小上限 {} MB (--max-fetch-mb)", max_bytes / (1024 * 1024))
+}
+
+/// 按实际写入字节计数的配额
+struct Quota {
+    remaining: u64,
+    max_bytes: u64,
+}
+
+impl Quota {
+    /// 把 `reader` 写入 `dest`，超出剩余配额时失败
+    fn copy(&mut self, reader: &mut dyn Read, dest: &Path) -> Result<(), BoxError> {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = fs::File::create(dest)?;
+        // 多读 1 字节用于判断是否超限
+        let written = io::copy(&mut reader.take(self.remaining + 1), &mut out)?;
+        if written > self.remaining {
+            return Err(size_error(self.max_bytes).into());
+        }
+        self.remaining -= written;
+        Ok(())
+    }
+}
+
+/// 条目路径只允许普通组件 (拒绝绝对路径与 `..`)；配置文件中相对扫描根的路径同样按此校验
+pub(crate) fn safe_relative(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+fn extract(archive: &Path, dest: &Path, max_bytes: u64) -> Result<(), BoxError> {
+    let kind = archive_kind(archive)
+        .ok_or_else(|| format!("不支持的压缩包格式: {} (支持 .zip/.tar/.tar.gz/.tgz)", archive.display()))?;
+    let file = fs::File::open(archive).map_err(|e| format!("无法打开 {}: {e}", archive.display()))?;
+    let mut quota = Quota { remaining: max_bytes, max_bytes };
+    match kind {
+        ArchiveKind::Zip => extract_zip(file, dest, &mut quota),
+        ArchiveKind::Tar => extract_tar(file, dest, &mut quota),
+        ArchiveKind::TarGz => extract_tar(flate2::read::GzDecoder::new(file), dest, &mut quota),
+    }
+}
+
+fn extract_zip<R: Read + Seek>(reader: R, dest: &Path, quota: &mut Quota) -> Result<(), BoxError> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(rel) = safe_relative(Path::new(entry.name())) else {
+            tracing::warn!("跳过越出工作区的条目: {}", entry.name());
+            continue;
+        };
+        if entry.is_dir() {
+            fs::create_dir_all(dest.join(rel))?;
+        } else if entry.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000) {
+            tracing::debug!("跳过符号链接: {}", entry.name());
+        } else {
+            quota.copy(&mut entry, &dest.join(rel))?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_tar<R: Read>(reader: R, dest: &Path, quota: &mut Quota) -> Result<(), BoxError> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let Some(rel) = safe_relative(&path) else {
+            tracing::warn!("跳过越出工作区的条目: {}", path.display());
+            continue;
+        };
+        match entry.header().entry_type() {
+            tar::EntryType::Directory => fs::create_dir_all(dest.join(rel))?,
+            tar::EntryType::Regular | tar::EntryType::Continuous => quota.copy(&mut entry, &dest.join(rel))?,
+            // 符号链接、硬链接、设备文件等不解压
+            other => tracing::debug!("跳过 {:?} 条目: {}", other, path.display()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, data) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_archive_workspace_roots_and_cleanup() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("svc.zip");
+        fs::write(&zip_path, zip_bytes(&[
+            ("svc-main/pom.xml", b"<project/>"),
+            ("svc-main/src/A.java", b"class A {}"),
+        ])).unwrap();
+        let tgz_path = dir.path().join("svc.tgz");
+        fs::write(&tgz_path, tar_gz_bytes(&[("B.java", b"class B {}"), ("lib/C.java", b"class C {}")])).unwrap();
+
+        let workspace = fetch(&RemoteSource::Archive(zip_path), FetchOptions::default()).unwrap();
+        let root = workspace.root();
+        // 单一顶层目录被展开
+        assert!(root.ends_with("svc-main"));
+        assert_eq!(fs::read_to_string(root.join("src/A.java")).unwrap(), "class A {}");
+        let base = workspace.dir.clone();
+        drop(workspace);
+        assert!(!base.exists());
+
+        let workspace = fetch(&RemoteSource::Archive(tgz_path), FetchOptions { keep: true, ..FetchOptions::default() }).unwrap();
+        assert_eq!(workspace.root(), workspace.dir);
+        assert!(workspace.root().join("lib/C.java").is_file());
+        let base = workspace.dir.clone();
+        drop(workspace);
+        assert!(base.exists(), "keep 时保留工作区");
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn test_archive_size_cap_and_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = dir.path().join("big.zip");
+        fs::write(&big, zip_bytes(&[("A.java", &[b' '; 4096])])).unwrap();
+        let err = fetch(&RemoteSource::Archive(big), FetchOptions { max_bytes: 1024, keep: false }).unwrap_err();
+        assert!(err.to_string().contains("大小上限"), "{err}");
+
+        let evil = dir.path().join("evil.zip");
+        fs::write(&evil, zip_bytes(&[("../escape.java", b"x"), ("/abs.java", b"x"), ("ok.java", b"x")])).unwrap();
+        let workspace = fetch(&RemoteSource::Archive(evil), FetchOptions::default()).unwrap();
+        let names: Vec<_> = fs::read_dir(workspace.root()).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["ok.java"]);
+        assert!(!workspace.dir.parent().unwrap().join("escape.java").exists());
+
+        let err = fetch(&RemoteSource::Archive(dir.path().join("x.rar")), FetchOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("不支持"));
+    }
+
+    #[test]
+    fn test_git_fetch_local_repo() {
+        let repo = tempfile::tempdir().unwrap();
+        let run = |args: &[&str]| git::run(repo.path(), args).unwrap();
+        run(&["init", "-q"]);
+        fs::write(repo.path().join("A.java"), "class A {}").unwrap();
+        run(&["add", "."]);
+        run(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qm", "init"]);
+        run(&["tag", "v1"]);
+
+        let url = repo.path().to_string_lossy().to_string();
+        let source = RemoteSource::Git { url, reference: Some("v1".into()) };
+        let workspace = fetch(&source, FetchOptions::default()).unwrap();
+        assert!(workspace.root().join("A.java").is_file());
+
+        let err = fetch(&source, FetchOptions { max_bytes: 4, keep: false }).unwrap_err();
+        assert!(err.to_string().contains("大小上限"));
+    }
+
+    #[test]
+    fn test_git_fetch_stops_at_size_cap() {
+        let repo = tempfile::tempdir().unwrap();
+        let run = |args: &[&str]| git::run(repo.path(), args).unwrap();
+        run(&["init", "-q"]);
+        // 不可压缩的内容，pack 大小约等于文件大小
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let noise: Vec<u8> = (0..512 * 1024).map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        }).collect();
+        fs::write(repo.path().join("blob.bin"), noise).unwrap();
+        run(&["add", "."]);
+        run(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qm", "init"]);
+
+        let dest = tempfile::tempdir().unwrap();
+        git::run(dest.path(), &["init", "-q"]).unwrap();
+        let url = repo.path().to_string_lossy().to_string();
+        let args = ["fetch", "-q", "--depth", "1", "--", url.as_str(), "HEAD"];
+        // 拉取阶段即失败，不会进入检出
+        let err = fetch_bounded(dest.path(), &args, 64 * 1024).unwrap_err();
+        assert!(err.to_string().contains("大小上限"), "{err}");
+        assert!(!dest.path().join("blob.bin").exists());
+
+        // 被终止的 fetch 可能留下锁文件 (实际使用时工作区随错误一起删除)，这里换新目录
+        let dest = tempfile::tempdir().unwrap();
+        git::run(dest.path(), &["init", "-q"]).unwrap();
+        fetch_bounded(dest.path(), &args, 4 * 1024 * 1024).unwrap();
+        assert!(dest.path().join(".git/FETCH_HEAD").is_file());
+    }
+
+    #[test]
+    fn test_git_rejects_option_like_arguments() {
+        let marker = tempfile::tempdir().unwrap();
+        let injected = format!("--upload-pack=touch {}", marker.path().join("pwned").display());
+        let source = RemoteSource::Git { url: injected.clone(), reference: None };
+        assert!(fetch(&source, FetchOptions::default()).unwrap_err().to_string().contains("非法的 git 仓库地址"));
+
+        let source = RemoteSource::Git { url: "https://example.com/repo.git".into(), reference: Some(injected) };
+        assert!(fetch(&source, FetchOptions::default()).unwrap_err().to_string().contains("非法的 git ref"));
+        assert!(!marker.path().join("pwned").exists());
+    }
+}
@@ -52,7 +52,7 @@ mod common {
 
 #[test]
 fn test_cross_package_n_plus_one_detection() {
-    use java_perf::ast_engine::radar_scan;
+    use java_perf::ast_engine::{radar_scan, ScanOptions};
     
     let fixture_path = common::cross_package_fixture();
     
@@ -63,7 +63,7 @@ fn test_cross_package_n_plus_one_detection() {
     }
     
     // Run radar scan on the cross-package fixture
-    let result = radar_scan(fixture_path.to_str().unwrap(), false, 100);
+    let result = radar_scan(fixture_path.to_str().unwrap(), false, 100, &ScanOptions::default());
     
     assert!(result.is_ok(), "radar_scan should succeed");
     
@@ -154,7 +154,7 @@ fn test_cross_package_callgraph_construction() {
             };
             
             // Register class in call graph with FQN
-            call_graph.register_class(&class_fqn, file_path.clone(), layer);
+            call_graph.register_class(&class_fqn, file_path.to_str().unwrap(), layer);
             
             // Register in symbol table
             symbol_table.register_class_fqn(type_info);
@@ -167,7 +167,7 @@ fn test_cross_package_callgraph_construction() {
                 for (caller_method, receiver, callee_method, line) in call_sites {
                     let caller = MethodSig::new_fqn(&class_fqn, &caller_method);
                     let callee = MethodSig::resolve(&receiver, &callee_method, &import_index, &symbol_table);
-                    call_graph.add_call(caller, callee, file_path.clone(), line);
+                    call_graph.add_call(caller, callee, file_path.to_str().unwrap(), line);
                 }
             }
         }
@@ -227,7 +227,7 @@ fn test_cross_package_callgraph_construction() {
 
 #[test]
 fn test_spring_boot_sample_full_scan() {
-    use java_perf::ast_engine::radar_scan;
+    use java_perf::ast_engine::{radar_scan, ScanOptions};
     
     let fixture_path = common::spring_boot_sample_fixture();
     
@@ -238,7 +238,7 @@ fn test_spring_boot_sample_full_scan() {
     }
     
     // Run radar scan on the spring-boot-sample fixture
-    let result = radar_scan(fixture_path.to_str().unwrap(), false, 100);
+    let result = radar_scan(fixture_path.to_str().unwrap(), false, 100, &ScanOptions::default());
     
     assert!(result.is_ok(), "radar_scan should succeed on spring-boot-sample");
     
@@ -400,7 +400,7 @@ fn test_spring_boot_sample_callgraph_accuracy() {
                 }
             };
             
-            call_graph.register_class(&class_fqn, file_path.clone(), layer);
+            call_graph.register_class(&class_fqn, file_path.to_str().unwrap(), layer);
             symbol_table.register_class_fqn(type_info);
             
             for binding in bindings {
@@ -412,7 +412,7 @@ fn test_spring_boot_sample_callgraph_accuracy() {
                 for (caller_method, receiver, callee_method, line) in call_sites {
                     let caller = MethodSig::new_fqn(&class_fqn, &caller_method);
                     let callee = MethodSig::resolve(&receiver, &callee_method, &import_index, &symbol_table);
-                    call_graph.add_call(caller, callee, file_path.clone(), line);
+                    call_graph.add_call(caller, callee, file_path.to_str().unwrap(), line);
                 }
             }
         }
@@ -441,3 +441,1897 @@ fn test_spring_boot_sample_callgraph_accuracy() {
     // The spring-boot-sample has calls between layers, so we should have some edges
     // Note: The exact number depends on how well call site extraction works
 }
+
+// ============================================================================
+// Non-UTF-8 Sources: GBK-encoded files are transcoded before parsing
+// ============================================================================
+
+#[test]
+fn test_gbk_encoded_source_is_scanned() {
+    use java_perf::ast_engine::{radar_scan, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let code = r#"
+public class 订单服务 {
+    // 遗留系统：批量查询订单详情，注释使用 GBK 编码保存
+    public void process(List<Long> ids) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+    }
+}
+"#;
+    let (gbk, _, _) = encoding_rs::GBK.encode(code);
+    std::fs::write(dir.path().join("OrderService.java"), &gbk).unwrap();
+
+    let report = radar_scan(dir.path().to_str().unwrap(), false, 100, &ScanOptions::default()).unwrap();
+    let report_str = report.as_str().unwrap_or("");
+
+    assert!(report_str.contains("N_PLUS_ONE"), "GBK file should be analyzed: {report_str}");
+    assert!(report_str.contains("**编码**"), "Encoding stats should be reported: {report_str}");
+}
+
+// ============================================================================
+// Stack-aware RuleRegistry: WebFlux projects escalate BLOCKING_IO to P0
+// ============================================================================
+
+#[test]
+fn test_reactive_stack_escalates_blocking_io() {
+    use java_perf::ast_engine::{radar_scan, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("pom.xml"), r#"<project>
+    <dependencies>
+        <dependency>
+            <groupId>org.springframework.boot</groupId>
+            <artifactId>spring-boot-starter-webflux</artifactId>
+        </dependency>
+    </dependencies>
+</project>"#).unwrap();
+    std::fs::write(dir.path().join("FileHandler.java"), r#"
+public class FileHandler {
+    public void read() throws Exception {
+        FileInputStream in = new FileInputStream("data.bin");
+    }
+}
+"#).unwrap();
+
+    // compact 模式只列出 P0
+    let report = radar_scan(dir.path().to_str().unwrap(), true, 0, &ScanOptions::default()).unwrap();
+    let report_str = report.as_str().unwrap_or("");
+    assert!(report_str.contains("`BLOCKING_IO`"), "BLOCKING_IO should be P0 in WebFlux: {report_str}");
+
+    let full = radar_scan(dir.path().to_str().unwrap(), false, 10, &ScanOptions::default()).unwrap();
+    assert!(full.as_str().unwrap_or("").contains("技术栈规则调整"));
+}
+
+// ============================================================================
+// verify --against: 修复后只重新分析标记过的文件
+// ============================================================================
+
+#[test]
+fn test_verify_against_previous_scan() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+    use java_perf::verify::{verify_against, FixStatus};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    let file = dir.path().join("Worker.java");
+    std::fs::write(&file, r#"public class Worker {
+    public void shutdown() {
+        System.exit(1);
+    }
+    public void run() {
+        ExecutorService pool = Executors.newCachedThreadPool();
+    }
+}
+"#).unwrap();
+
+    let scan = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let issues = scan["issues"].as_array().unwrap();
+    assert!(issues.iter().any(|i| i["id"] == "SYSTEM_EXIT"));
+    assert!(issues.iter().all(|i| i["path"] == "Worker.java" && i["snippet"].is_string()));
+    let report = dir.path().join("report.json");
+    std::fs::write(&report, serde_json::json!({"success": true, "data": scan}).to_string()).unwrap();
+
+    // 修复 SYSTEM_EXIT，线程池代码因上方新增注释行而下移
+    std::fs::write(&file, r#"public class Worker {
+    public void shutdown() {
+        // graceful shutdown
+        // handled by container
+        running = false;
+    }
+    public void run() {
+        ExecutorService pool = Executors.newCachedThreadPool();
+    }
+}
+"#).unwrap();
+
+    let result = verify_against(root, report.to_str().unwrap()).unwrap();
+    let status_of = |id: &str| result.entries.iter().find(|e| e.id == id).map(|e| e.status);
+    assert_eq!(status_of("SYSTEM_EXIT"), Some(FixStatus::Fixed));
+    assert_eq!(status_of("UNBOUNDED_POOL"), Some(FixStatus::Moved));
+    assert!(result.new_issues.is_empty(), "no new issues expected: {:?}", result.new_issues);
+}
+
+// ============================================================================
+// SQL 常量: DAO 顶部 / 独立常量类 / 枚举中定义的 SQL 在调用点被分析
+// ============================================================================
+
+#[test]
+fn test_sql_constants_resolved_across_files() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("OrderQuery.java"), r#"
+public enum OrderQuery {
+    ALL("SELECT * FROM orders"),
+    BY_ID("SELECT id, amount FROM orders WHERE id = ?");
+
+    private final String sql;
+    OrderQuery(String sql) { this.sql = sql; }
+    public String sql() { return sql; }
+}
+"#).unwrap();
+    std::fs::write(dir.path().join("OrderDao.java"), r#"
+@Repository
+public class OrderDao {
+    private static final String SEARCH =
+        "SELECT id FROM orders " +
+        "WHERE remark LIKE '%" + "?'";
+
+    public List<Order> all() {
+        return jdbcTemplate.query(OrderQuery.ALL.sql(), mapper);
+    }
+    public Order byId(long id) {
+        return jdbcTemplate.queryForObject(OrderQuery.BY_ID.sql(), mapper, id);
+    }
+    public List<Order> search(String remark) {
+        return jdbcTemplate.query(SEARCH, mapper, remark);
+    }
+}
+"#).unwrap();
+
+    let scan = radar_scan_json(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let issues = scan["issues"].as_array().unwrap();
+    let find = |id: &str, path: &str| issues.iter()
+        .filter(|i| i["id"] == id && i["path"] == path)
+        .collect::<Vec<_>>();
+
+    let select_star = find("SELECT_STAR", "OrderDao.java");
+    assert_eq!(select_star.len(), 1, "only OrderQuery.ALL holds SELECT *: {issues:?}");
+    assert_eq!(select_star[0]["line"], 9);
+    assert!(select_star[0]["context"].as_str().unwrap().contains("OrderQuery.java:3"));
+
+    // 拼接片段本身仍按字面量报告，调用点额外报告解析后的完整 SQL
+    let like: Vec<_> = find("LIKE_LEADING_WILDCARD", "OrderDao.java").into_iter()
+        .filter(|i| i["line"] == 15)
+        .collect();
+    assert_eq!(like.len(), 1, "split LIKE '%' constant should be resolved: {issues:?}");
+    assert!(like[0]["context"].as_str().unwrap().contains("OrderDao.SEARCH 定义于 OrderDao.java:4"));
+}
+
+// ============================================================================
+// 线程池容量建议: 代码 + application.yml + 任务负载类型
+// ============================================================================
+
+#[test]
+fn test_thread_pool_sizing_advice() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("application.yml"), "spring:\n  datasource:\n    hikari:\n      maximum-pool-size: 10\n").unwrap();
+    std::fs::write(dir.path().join("AsyncConfig.java"), r#"
+@Configuration
+public class AsyncConfig {
+    @Bean
+    public Executor orderExecutor() {
+        ThreadPoolTaskExecutor executor = new ThreadPoolTaskExecutor();
+        executor.setCorePoolSize(50);
+        executor.setMaxPoolSize(50);
+        executor.setQueueCapacity(1000);
+        return executor;
+    }
+}
+"#).unwrap();
+    std::fs::write(dir.path().join("OrderService.java"), r#"
+@Service
+public class OrderService {
+    @Autowired
+    private OrderRepository orderRepository;
+
+    @Async("orderExecutor")
+    public void sync(Long id) {
+        orderRepository.findById(id);
+    }
+}
+"#).unwrap();
+
+    let scan = radar_scan_json(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let pools = scan["thread_pools"].as_array().unwrap();
+    assert_eq!(pools.len(), 1, "{pools:?}");
+    assert_eq!(pools[0]["pool"]["name"], "orderExecutor");
+    assert_eq!(pools[0]["workload"], "io_bound");
+    assert!(pools[0]["warnings"].as_array().unwrap().iter()
+        .any(|w| w.as_str().unwrap().contains("maximum-pool-size (10)")));
+
+    let report = radar_scan(dir.path().to_str().unwrap(), false, 10, &ScanOptions::default()).unwrap();
+    let report_str = report.as_str().unwrap_or("");
+    assert!(report_str.contains("### 🧵 线程池容量建议"), "{report_str}");
+    assert!(report_str.contains("orderRepository.findById"));
+}
+
+// ============================================================================
+// DI 歧义: 多个 @Service 实现同一接口，注入点缺少 @Qualifier
+// ============================================================================
+
+#[test]
+fn test_di_ambiguous_bean_across_files() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("PaymentService.java"), "public interface PaymentService { void pay(); }\n").unwrap();
+    std::fs::write(dir.path().join("AlipayService.java"), "@Service\npublic class AlipayService implements PaymentService { public void pay() {} }\n").unwrap();
+    std::fs::write(dir.path().join("CardService.java"), "@Service(\"card\")\npublic class CardService implements PaymentService { public void pay() {} }\n").unwrap();
+    std::fs::write(dir.path().join("OrderController.java"), r#"
+@RestController
+@RequiredArgsConstructor
+public class OrderController {
+    private final PaymentService paymentService;
+}
+"#).unwrap();
+
+    let scan = radar_scan_json(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let issue = scan["issues"].as_array().unwrap().iter()
+        .find(|i| i["id"] == "DI_AMBIGUOUS_BEAN")
+        .expect("ambiguous injection should be reported");
+    assert_eq!(issue["path"], "OrderController.java");
+    assert_eq!(issue["severity"], "P0");
+    assert!(issue["context"].as_str().unwrap().contains("alipayService(AlipayService.java), card(CardService.java)"));
+}
+
+#[test]
+fn test_static_init_cycle_across_files() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("model")).unwrap();
+    std::fs::write(dir.path().join("model/Currency.java"), r#"package model;
+public class Currency {
+    public static final Currency DEFAULT = Money.ZERO.currency();
+}
+"#).unwrap();
+    std::fs::write(dir.path().join("model/Money.java"), r#"package model;
+public class Money {
+    public static final Money ZERO = new Money(Currency.DEFAULT);
+    Money(Currency c) {}
+    Currency currency() { return null; }
+}
+"#).unwrap();
+
+    let scan = radar_scan_json(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let cycles: Vec<_> = scan["issues"].as_array().unwrap().iter()
+        .filter(|i| i["id"] == "STATIC_INIT_CYCLE")
+        .collect();
+    assert_eq!(cycles.len(), 1, "{cycles:?}");
+    assert_eq!(cycles[0]["path"], "model/Currency.java");
+    assert_eq!(cycles[0]["line"], 3);
+    assert_eq!(cycles[0]["context"], "Currency → Money @model/Currency.java:3 → Currency @model/Money.java:3");
+}
+
+#[test]
+fn test_hotness_ranks_request_path_first() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, IssueSort, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let n_plus_one = "        for (Long id : ids) {\n            orderRepository.findById(id);\n        }\n";
+    std::fs::write(dir.path().join("OrderRepository.java"), "@Repository\npublic interface OrderRepository {}\n").unwrap();
+    std::fs::write(dir.path().join("OrderService.java"), "public interface OrderService { void load(List<Long> ids); }\n").unwrap();
+    std::fs::write(dir.path().join("OrderController.java"), r#"
+@RestController
+public class OrderController {
+    @Autowired
+    private OrderService orderService;
+
+    @GetMapping("/orders")
+    public void list(List<Long> ids) {
+        orderService.load(ids);
+    }
+}
+"#).unwrap();
+    std::fs::write(dir.path().join("OrderServiceImpl.java"), format!(r#"
+@Service
+public class OrderServiceImpl implements OrderService {{
+    @Autowired
+    private OrderRepository orderRepository;
+
+    public void load(List<Long> ids) {{
+{n_plus_one}    }}
+}}
+"#)).unwrap();
+    std::fs::write(dir.path().join("BatchJob.java"), format!(r#"
+@Component
+public class BatchJob {{
+    @Autowired
+    private OrderRepository orderRepository;
+
+    public void run(List<Long> ids) {{
+{n_plus_one}    }}
+}}
+"#)).unwrap();
+
+    let root = dir.path().to_str().unwrap();
+    let hotness_of = |scan: &serde_json::Value| -> Vec<(String, u64)> {
+        scan["issues"].as_array().unwrap().iter()
+            .filter(|i| i["id"] == "N_PLUS_ONE")
+            .map(|i| (i["path"].as_str().unwrap().to_string(), i["hotness"].as_u64().unwrap()))
+            .collect()
+    };
+
+    let by_location = hotness_of(&radar_scan_json(root, &ScanOptions::default()).unwrap());
+    assert_eq!(by_location, vec![("BatchJob.java".to_string(), 0), ("OrderServiceImpl.java".to_string(), 50)]);
+
+    let by_hotness = hotness_of(&radar_scan_json(root, &ScanOptions { sort: IssueSort::Hotness, ..Default::default() }).unwrap());
+    assert_eq!(by_hotness, vec![("OrderServiceImpl.java".to_string(), 50), ("BatchJob.java".to_string(), 0)]);
+
+    let report = radar_scan(root, false, 10, &ScanOptions { sort: IssueSort::Hotness, ..Default::default() }).unwrap();
+    assert!(report.as_str().unwrap().contains("`OrderServiceImpl.java:9` - "), "{report}");
+    assert!(report.as_str().unwrap().contains(" 🔥50\n"), "{report}");
+}
+
+#[test]
+fn test_dedup_merges_overlapping_loop_findings() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+    use java_perf::dedup::DedupPolicy;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("OrderRepository.java"), "@Repository\npublic interface OrderRepository {}\n").unwrap();
+    std::fs::write(dir.path().join("OrderService.java"), r#"
+@Service
+public class OrderService {
+    @Autowired
+    private OrderRepository orderRepository;
+
+    public void importAll(List<Long> ids) {
+        String log = "";
+        for (Long id : ids) {
+            orderRepository.save(new Order(id)); log += id;
+        }
+    }
+}
+"#).unwrap();
+
+    let root = dir.path().to_str().unwrap();
+    let ids_at_line = |scan: &serde_json::Value| -> Vec<(String, Vec<String>)> {
+        scan["issues"].as_array().unwrap().iter()
+            .filter(|i| i["line"] == 10)
+            .map(|i| (
+                i["id"].as_str().unwrap().to_string(),
+                i["secondary"].as_array().map(|s| s.iter().map(|v| v.as_str().unwrap().to_string()).collect()).unwrap_or_default(),
+            ))
+            .collect()
+    };
+
+    let raw = ids_at_line(&radar_scan_json(root, &ScanOptions { dedup: DedupPolicy::Off, ..Default::default() }).unwrap());
+    assert_eq!(raw.len(), 2, "{raw:?}");
+
+    let merged = ids_at_line(&radar_scan_json(root, &ScanOptions::default()).unwrap());
+    assert_eq!(merged.len(), 1, "{merged:?}");
+    assert_eq!(merged[0].0, "N_PLUS_ONE");
+    assert_eq!(merged[0].1, vec!["STRING_CONCAT_LOOP".to_string()]);
+}
+
+#[test]
+fn test_findings_in_disabled_feature_flag_branch_are_downgraded() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Features.java"), "public interface Features {\n    boolean LEGACY_IMPORT = false;\n}\n").unwrap();
+    std::fs::write(dir.path().join("ImportJob.java"), r#"
+public class ImportJob {
+    public void run() {
+        if (Features.LEGACY_IMPORT) {
+            Executors.newCachedThreadPool();
+        }
+        Executors.newCachedThreadPool();
+    }
+}
+"#).unwrap();
+
+    let scan = radar_scan_json(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let pools: Vec<(u64, bool, &str)> = scan["issues"].as_array().unwrap().iter()
+        .filter(|i| i["id"] == "UNBOUNDED_POOL")
+        .map(|i| (i["line"].as_u64().unwrap(), i["inactive"].as_bool().unwrap_or(false), i["severity"].as_str().unwrap()))
+        .collect();
+    assert_eq!(pools, vec![(5, true, "P1"), (7, false, "P0")]);
+}
+
+#[test]
+fn test_severity_map_labels_json_and_exports() {
+    use java_perf::ast_engine::{radar_scan_json, scan_project, ScanOptions};
+    use java_perf::report::{export, ExportFormat};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join(".javaperf.toml"), "[severity_map]\nP0 = \"Blocker\"\nP1 = \"Major\"\n").unwrap();
+    std::fs::write(dir.path().join("Jobs.java"), "public class Jobs {\n    void run() {\n        Executors.newCachedThreadPool();\n    }\n}\n").unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let scan = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let pool = scan["issues"].as_array().unwrap().iter().find(|i| i["id"] == "UNBOUNDED_POOL").unwrap();
+    // 内部级别不变，映射名称单独输出
+    assert_eq!((pool["severity"].as_str(), pool["severity_label"].as_str()), (Some("P0"), Some("Blocker")));
+
+    let project = scan_project(root, &ScanOptions::default()).unwrap();
+    let sarif = export(&project, ExportFormat::Sarif);
+    let result = sarif["runs"][0]["results"].as_array().unwrap().iter().find(|r| r["ruleId"] == "UNBOUNDED_POOL").unwrap();
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["properties"]["severity"], "Blocker");
+    assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 3);
+
+    let checkstyle = export(&project, ExportFormat::Checkstyle);
+    let checkstyle = checkstyle.as_str().unwrap();
+    assert!(checkstyle.contains("<file name=\"Jobs.java\">"), "{checkstyle}");
+    assert!(checkstyle.contains("line=\"3\" column=\"9\" severity=\"error\" message=\"[Blocker] "), "{checkstyle}");
+    assert!(checkstyle.contains("source=\"java-perf.UNBOUNDED_POOL\""), "{checkstyle}");
+
+    let rdjson = export(&project, ExportFormat::Rdjson);
+    let diagnostic = rdjson["diagnostics"].as_array().unwrap().iter().find(|d| d["code"]["value"] == "UNBOUNDED_POOL").unwrap();
+    assert_eq!(diagnostic["severity"], "ERROR");
+    assert_eq!(diagnostic["location"]["range"]["start"], serde_json::json!({ "line": 3, "column": 9 }));
+    assert!(diagnostic["message"].as_str().unwrap().starts_with("[Blocker] "), "{diagnostic}");
+}
+
+#[test]
+fn test_include_suppressed_lists_silenced_findings_with_source() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Jobs.java"), r#"public class Jobs {
+    void run() {
+        // java-perf-ignore-next-line: UNBOUNDED_POOL
+        Executors.newCachedThreadPool();
+        Executors.newCachedThreadPool();
+    }
+}
+"#).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let pools = |list: &serde_json::Value| -> Vec<u64> {
+        list.as_array().unwrap().iter()
+            .filter(|i| i["id"] == "UNBOUNDED_POOL")
+            .map(|i| i["line"].as_u64().unwrap())
+            .collect()
+    };
+
+    let default = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert_eq!(pools(&default["issues"]), vec![5]);
+    assert!(default.get("suppressed").is_none());
+
+    let audit = radar_scan_json(root, &ScanOptions { include_suppressed: true, ..Default::default() }).unwrap();
+    assert_eq!(pools(&audit["issues"]), vec![5]);
+    assert_eq!(audit["p0"], default["p0"]);
+    let suppressed = &audit["suppressed"][0];
+    assert_eq!((suppressed["id"].as_str(), suppressed["line"].as_u64()), (Some("UNBOUNDED_POOL"), Some(4)));
+    assert_eq!(suppressed["suppressed"], true);
+    assert_eq!(suppressed["suppression"], serde_json::json!({ "kind": "next-line-comment", "line": 3 }));
+}
+
+#[test]
+fn test_rule_and_category_filters() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+    use java_perf::rules::registry::{RuleCategory, RuleFilter};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Jobs.java"), r#"public class Jobs {
+    public synchronized void run() {
+        Executors.newCachedThreadPool();
+        try { work(); } catch (Exception e) {}
+    }
+}
+"#).unwrap();
+    std::fs::write(dir.path().join("Dockerfile"), "FROM openjdk:latest\n").unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let ids = |rules: &[&str], categories: &[RuleCategory]| -> Vec<String> {
+        let rules: Vec<String> = rules.iter().map(|r| r.to_string()).collect();
+        let options = ScanOptions { rules: RuleFilter::new(&rules, categories).unwrap(), ..Default::default() };
+        let scan = radar_scan_json(root, &options).unwrap();
+        let mut ids: Vec<String> = scan["issues"].as_array().unwrap().iter()
+            .map(|i| i["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    let all = ids(&[], &[]);
+    assert!(all.contains(&"EMPTY_CATCH".to_string()) && all.contains(&"DOCKER_LATEST_TAG".to_string()), "{all:?}");
+    assert_eq!(ids(&["EMPTY_CATCH"], &[]), vec!["EMPTY_CATCH"]);
+    assert_eq!(ids(&[], &[RuleCategory::Concurrency]), vec!["SYNC_METHOD", "UNBOUNDED_POOL"]);
+    assert_eq!(ids(&["DOCKER_LATEST_TAG"], &[RuleCategory::Concurrency]), vec!["DOCKER_LATEST_TAG", "SYNC_METHOD", "UNBOUNDED_POOL"]);
+}
+
+#[test]
+fn test_rule_overrides_exclude_paths() {
+    use java_perf::ast_engine::{scan_project, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let source = r#"public class Jobs {
+    public void run(List<Long> ids) {
+        try { work(); } catch (Exception e) {}
+        for (Long id : ids) { orderRepository.findById(id); }
+    }
+}
+"#;
+    for rel in ["src/generated/Jobs.java", "src/main/Jobs.java"] {
+        let file = dir.path().join(rel);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, source).unwrap();
+    }
+    std::fs::write(dir.path().join(".javaperf.toml"), r#"
+[[rule_overrides]]
+id = "EMPTY_CATCH"
+exclude = ["**/generated/**"]
+
+[[rule_overrides]]
+id = "N_PLUS_ONE"
+exclude = ["src/generated/*.java"]
+"#).unwrap();
+
+    let scan = scan_project(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let ids_in = |path: &str| -> Vec<&str> {
+        let mut ids: Vec<&str> = scan.issues.iter()
+            .filter(|i| i.path == path)
+            .map(|i| i.issue_type.as_str())
+            .collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids_in("src/main/Jobs.java"), vec!["EMPTY_CATCH", "N_PLUS_ONE"]);
+    assert!(ids_in("src/generated/Jobs.java").is_empty(), "{:?}", ids_in("src/generated/Jobs.java"));
+}
+
+#[test]
+fn test_codeowners_attached_and_grouped() {
+    use java_perf::ast_engine::{radar_scan_json, GroupBy, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    for (rel, body) in [
+        ("order/OrderJob.java", "public class OrderJob { public synchronized void run() {} }\n"),
+        ("user/UserJob.java", "public class UserJob { void run() { try { work(); } catch (Exception e) {} } }\n"),
+    ] {
+        let file = dir.path().join(rel);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, body).unwrap();
+    }
+    std::fs::create_dir_all(dir.path().join(".github")).unwrap();
+    std::fs::write(dir.path().join(".github/CODEOWNERS"), "* @org/platform\n/order/ @org/order\n").unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let options = ScanOptions { group_by: Some(GroupBy::Owner), ..Default::default() };
+    let report = radar_scan_json(root, &options).unwrap();
+    for issue in report["issues"].as_array().unwrap() {
+        let expected = if issue["path"].as_str().unwrap().starts_with("order/") { "@org/order" } else { "@org/platform" };
+        assert_eq!(issue["owners"], serde_json::json!([expected]), "{issue}");
+    }
+    let owners: Vec<&str> = report["by_owner"].as_array().unwrap().iter()
+        .map(|g| g["owner"].as_str().unwrap())
+        .collect();
+    assert_eq!(owners.len(), 2, "{owners:?}");
+    assert!(owners.contains(&"@org/order") && owners.contains(&"@org/platform"));
+
+    // 未指定 --group-by 时只附加负责人，不输出汇总
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(report.get("by_owner").is_none());
+}
+
+#[test]
+fn test_analysis_report_stats_and_stack() {
+    use java_perf::ast_engine::{render_json, ScanOptions};
+    use java_perf::analyze_project;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("pom.xml"), "<project><dependencies><dependency><artifactId>spring-boot-starter-web</artifactId></dependency></dependencies></project>\n").unwrap();
+    for (rel, body) in [
+        ("src/UserService.java", "public class UserService {\n    private UserRepository userRepository;\n    public void load(java.util.List<Long> ids) {\n        for (Long id : ids) { userRepository.findById(id); }\n    }\n}\n"),
+        ("src/UserRepository.java", "public interface UserRepository { Object findById(Long id); }\n"),
+        ("src/Job.java", "public class Job { void run() { try { work(); } catch (Exception e) {} } void work() {} }\n"),
+    ] {
+        let file = dir.path().join(rel);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, body).unwrap();
+    }
+    let root = dir.path().to_str().unwrap();
+    let options = ScanOptions::default();
+
+    let report = analyze_project(root, &options).unwrap();
+    let stack = report.stack.as_ref().expect("目录扫描应检测技术栈");
+    assert!(stack.is_maven);
+    assert!(report.rules.enabled > 0 && report.rules.enabled <= report.rules.total);
+    assert!(report.symbols.classes >= 2, "{:?}", report.symbols);
+    assert!(report.symbols.fields >= 1 && report.symbols.methods >= 3, "{:?}", report.symbols);
+    assert!(report.call_graph.edges >= 1, "{:?}", report.call_graph);
+    assert!(report.timing.total_ms >= report.timing.index_ms);
+
+    let by_file = report.by_file();
+    assert_eq!(by_file.values().map(Vec::len).sum::<usize>(), report.scan.issues.len());
+    assert!(by_file.keys().any(|path| path.ends_with("Job.java")), "{:?}", by_file.keys());
+    assert!(by_file.values().flatten().all(|issue| by_file.contains_key(issue.path.as_str())));
+
+    // JSON 渲染器保留原有键 (verify 依赖 issues)，并附加技术栈与统计
+    let json = render_json(&report, &options);
+    assert_eq!(json["issues"].as_array().unwrap().len(), report.scan.issues.len());
+    assert_eq!(json["stack"]["is_maven"], true);
+    assert_eq!(json["stats"]["symbols"]["classes"], report.symbols.classes);
+    assert!(json["stats"]["timing"]["total_ms"].is_u64());
+    // 整份报告可直接序列化
+    let value = serde_json::to_value(&report).unwrap();
+    assert_eq!(value["scan"]["file_count"], 4);
+}
+
+#[test]
+fn test_scan_baseline_reports_only_new_issues() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+    use java_perf::baseline;
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    std::fs::write(dir.path().join("Job.java"), "public class Job { void run() { try { work(); } catch (Exception e) {} } }\n").unwrap();
+    let outcome = baseline::update(root).unwrap();
+    assert!(outcome.entries.unwrap() > 0);
+
+    // 已知问题下移一行 (指纹不变)，另一个文件新增问题
+    std::fs::write(dir.path().join("Job.java"), "\npublic class Job { void run() { try { work(); } catch (Exception e) {} } }\n").unwrap();
+    std::fs::write(dir.path().join("Lock.java"), "public class Lock { public synchronized void run() {} }\n").unwrap();
+
+    let options = ScanOptions { baseline: true, ..Default::default() };
+    let report = radar_scan_json(root, &options).unwrap();
+    let paths: Vec<&str> = report["issues"].as_array().unwrap().iter()
+        .map(|i| i["path"].as_str().unwrap())
+        .collect();
+    assert!(!paths.is_empty() && paths.iter().all(|p| *p == "Lock.java"), "{paths:?}");
+    assert_eq!(report["baselined"], outcome.entries.unwrap());
+
+    // 未指定 --baseline 时报告全部问题
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(report["issues"].as_array().unwrap().iter().any(|i| i["path"] == "Job.java"));
+    assert!(report.get("baselined").is_none());
+}
+
+#[test]
+fn test_per_service_scan_uses_each_service_stack() {
+    use java_perf::ast_engine::ScanOptions;
+    use java_perf::services;
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let write = |path: &str, content: &str| {
+        let file = root.join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, content).unwrap();
+    };
+    let boot_pom = |starter: &str| format!(
+        "<project><dependencies><dependency><groupId>org.springframework.boot</groupId><artifactId>{starter}</artifactId></dependency></dependencies>\
+         <build><plugins><plugin><artifactId>spring-boot-maven-plugin</artifactId></plugin></plugins></build></project>"
+    );
+    write("pom.xml", "<project><modules><module>gateway</module><module>orders</module><module>common</module></modules></project>");
+    write("gateway/pom.xml", &boot_pom("spring-boot-starter-webflux"));
+    write("orders/pom.xml", &boot_pom("spring-boot-starter-web"));
+    write("common/pom.xml", "<project></project>");
+    let blocking = "public class Client { void call() throws Exception { Thread.sleep(100); } }\n";
+    write("gateway/src/main/java/Client.java", blocking);
+    write("orders/src/main/java/Client.java", blocking);
+
+    let (reports, shared) = services::analyze_services(root.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    assert_eq!(shared, vec!["common"]);
+    let names: Vec<&str> = reports.iter().map(|r| r.service.name.as_str()).collect();
+    assert_eq!(names, vec!["gateway", "orders"]);
+    assert!(reports[0].analysis.stack.as_ref().unwrap().is_reactive);
+    assert!(!reports[1].analysis.stack.as_ref().unwrap().is_reactive);
+
+    let json = services::render_json(&reports, &shared, &ScanOptions::default());
+    assert_eq!(json["services"][0]["service"]["path"], "gateway");
+    assert_eq!(json["services"][1]["stack"]["is_spring_mvc"], true);
+    let markdown = services::render_report(&reports, &shared, true, 5, &ScanOptions::default());
+    assert!(markdown.as_str().unwrap().contains("| gateway | `gateway` | spring-boot-maven-plugin | Spring Boot |"));
+}
+
+#[test]
+fn test_parse_degraded_files_reported_and_recovered() {
+    use java_perf::ast_engine::{analyze_project, render_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    std::fs::write(dir.path().join("Clean.java"), "public class Clean { public synchronized void run() {} }\n").unwrap();
+    // 第 4 行语法错误：同一行的空 catch 不可信，其余方法照常分析
+    std::fs::write(dir.path().join("Broken.java"), r#"
+public class Broken {
+    public synchronized void ok() {}
+    void broken() { int y = = = ; try { work(); } catch (Exception e) {} }
+    void later() { try { work(); } catch (Exception e) {} }
+}
+"#).unwrap();
+
+    let options = ScanOptions::default();
+    let report = analyze_project(root, &options).unwrap();
+    let degraded = &report.scan.parse_degraded;
+    assert_eq!(degraded.len(), 1, "{degraded:?}");
+    assert_eq!(degraded[0].path, "Broken.java");
+    assert_eq!(degraded[0].first_error_line, 4);
+
+    let broken: Vec<(usize, &str)> = report.scan.issues.iter()
+        .filter(|i| i.path == "Broken.java")
+        .map(|i| (i.line, i.issue_type.as_str()))
+        .collect();
+    assert!(broken.iter().all(|(line, _)| *line != 4), "{broken:?}");
+    assert!(broken.contains(&(5, "EMPTY_CATCH")), "{broken:?}");
+    assert!(broken.iter().any(|(line, _)| *line == 3), "{broken:?}");
+
+    let json = render_json(&report, &options);
+    assert_eq!(json["stats"]["parse_degraded"][0]["path"], "Broken.java");
+    assert_eq!(json["stats"]["parse_degraded"][0]["status"], "degraded");
+
+    // --strict-parse：存在语法错误时扫描失败并指出位置
+    let strict = ScanOptions { strict_parse: true, ..Default::default() };
+    let err = analyze_project(root, &strict).unwrap_err().to_string();
+    assert!(err.contains("Broken.java:4"), "{err}");
+}
+
+#[test]
+fn test_custom_rules_registered_at_runtime() {
+    use java_perf::ast_engine::{analyze_project, ScanOptions};
+    use java_perf::rules::custom::CustomRules;
+    use java_perf::rules::registry::RuleRegistry;
+    use java_perf::scanner::issue_builder::RuleMeta;
+    use java_perf::scanner::rule_handlers::{RuleContext, RuleHandler, SimpleMatchHandler};
+    use java_perf::scanner::{Issue, Severity};
+
+    /// 替换内置处理器：不报告任何空 catch
+    struct Silent;
+    impl RuleHandler for Silent {
+        fn handle(&self, _: &tree_sitter::Query, _: &tree_sitter::QueryMatch, _: &RuleMeta, _: &RuleContext) -> Option<Issue> {
+            None
+        }
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    std::fs::write(dir.path().join("Job.java"), r#"
+public class Job {
+    void run() {
+        try { work(); } catch (Exception e) {}
+        long now = new Date().getTime();
+    }
+}
+"#).unwrap();
+
+    let mut custom = CustomRules::new();
+    custom.register_rule("LEGACY_DATE", Severity::P1, r#"
+        (object_creation_expression
+            type: (type_identifier) @type
+            (#eq? @type "Date")) @new
+    "#, "java.util.Date 可变且非线程安全", Box::new(SimpleMatchHandler { line_capture: "new" })).unwrap();
+    custom.register_handler("EMPTY_CATCH", Box::new(Silent));
+
+    let report = analyze_project(root, &ScanOptions { custom_rules: custom, ..Default::default() }).unwrap();
+    let found: Vec<(&str, usize)> = report.scan.issues.iter().map(|i| (i.issue_type.as_str(), i.line)).collect();
+    assert!(found.contains(&("LEGACY_DATE", 5)), "{found:?}");
+    assert!(!found.iter().any(|(id, _)| *id == "EMPTY_CATCH"), "{found:?}");
+    assert_eq!(report.rules.total, RuleRegistry::default().stats().total + 1);
+
+    // 默认选项下内置处理器照常报告
+    let report = analyze_project(root, &ScanOptions::default()).unwrap();
+    assert!(report.scan.issues.iter().any(|i| i.issue_type == "EMPTY_CATCH"));
+    assert!(!report.scan.issues.iter().any(|i| i.issue_type == "LEGACY_DATE"));
+
+    // 替换不存在的规则的处理器：报错而不是静默忽略
+    let mut unknown = CustomRules::new();
+    unknown.register_handler("NO_SUCH_RULE", Box::new(Silent));
+    let err = analyze_project(root, &ScanOptions { custom_rules: unknown, ..Default::default() }).unwrap_err();
+    assert!(err.to_string().contains("NO_SUCH_RULE"), "{err}");
+}
+
+#[test]
+fn test_worst_methods_rollup() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    std::fs::write(dir.path().join("OrderService.java"), r#"
+package com.shop;
+
+public class OrderService {
+    void sync(java.util.List<Long> ids) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+        try { work(); } catch (Exception e) {}
+    }
+
+    void quiet() {
+        try { work(); } catch (Exception e) {}
+    }
+}
+"#).unwrap();
+
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let issues = report["issues"].as_array().unwrap();
+    assert!(issues.iter().all(|i| i["method"].as_str().is_some_and(|m| m.starts_with("com.shop.OrderService."))), "{issues:?}");
+    let worst = report["worst_methods"].as_array().unwrap();
+    assert_eq!(worst[0]["method"], "com.shop.OrderService.sync");
+    assert!(worst[0]["score"].as_u64() > worst.last().unwrap()["score"].as_u64());
+
+    let markdown = radar_scan(root, false, 50, &ScanOptions::default()).unwrap();
+    assert!(markdown.as_str().unwrap().contains("1. **com.shop.OrderService.sync**"));
+}
+
+#[test]
+fn test_entry_scope_limits_report_to_reachable_code() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+    use java_perf::entry_scope::EntryPoint;
+
+    let dir = tempfile::tempdir().unwrap();
+    for (rel, body) in [
+        ("com/shop/OrderController.java", "package com.shop;\n@RestController\npublic class OrderController {\n    @Autowired\n    private OrderService orderService;\n    public void create(List<Long> ids) {\n        orderService.place(ids);\n    }\n}\n"),
+        ("com/shop/OrderService.java", "package com.shop;\n@Service\npublic class OrderService {\n    private final OrderRepository orderRepository;\n    public void place(List<Long> ids) {\n        for (Long id : ids) { orderRepository.findById(id); }\n    }\n    public void export(List<Long> ids) {\n        for (Long id : ids) { orderRepository.findById(id); }\n    }\n}\n"),
+        ("com/shop/ReportJob.java", "package com.shop;\npublic class ReportJob {\n    void run() { try { work(); } catch (Exception e) {} }\n}\n"),
+        ("application.yml", "spring:\n  datasource:\n    hikari:\n      maximum-pool-size: 2\n"),
+        ("Dockerfile", "FROM openjdk:latest\n"),
+    ] {
+        let file = dir.path().join(rel);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, body).unwrap();
+    }
+    let root = dir.path().to_str().unwrap();
+    let ids = |report: &serde_json::Value| -> Vec<(String, u64)> {
+        report["issues"].as_array().unwrap().iter()
+            .map(|i| (i["id"].as_str().unwrap().to_string(), i["line"].as_u64().unwrap()))
+            .collect()
+    };
+
+    let full = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(ids(&full).contains(&("EMPTY_CATCH".to_string(), 3)));
+    assert!(full.get("entry").is_none());
+
+    let options = ScanOptions { entry: Some(EntryPoint::parse("OrderController#create").unwrap()), ..Default::default() };
+    let scoped = radar_scan_json(root, &options).unwrap();
+    let scoped_ids = ids(&scoped);
+    // 只保留 place() 中的 N+1 与 Repository 相关的连接池配置
+    assert!(scoped_ids.contains(&("N_PLUS_ONE".to_string(), 6)), "{scoped_ids:?}");
+    assert!(!scoped_ids.contains(&("N_PLUS_ONE".to_string(), 9)), "{scoped_ids:?}");
+    assert!(scoped_ids.iter().any(|(id, _)| id == "DB_POOL_SMALL"), "{scoped_ids:?}");
+    assert!(!scoped_ids.iter().any(|(id, _)| id == "EMPTY_CATCH" || id.starts_with("DOCKER_")), "{scoped_ids:?}");
+    assert_eq!(scoped["entry"]["entry"], "OrderController#create");
+    assert_eq!(scoped["entry"]["files"], 2);
+
+    let missing = ScanOptions { entry: Some(EntryPoint::parse("OrderController#delete").unwrap()), ..Default::default() };
+    assert!(radar_scan_json(root, &missing).is_err());
+}
+
+#[test]
+fn test_reachability_prunes_deep_analysis() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    for (rel, body) in [
+        ("com/shop/OrderController.java", "package com.shop;\n@RestController\npublic class OrderController {\n    @Autowired\n    private OrderService orderService;\n    public void create() { orderService.place(); }\n}\n"),
+        ("com/shop/OrderService.java", "package com.shop;\n@Service\npublic class OrderService {\n    public void place() {}\n}\n"),
+        ("com/shop/tools/Migrator.java", "package com.shop.tools;\npublic class Migrator {\n    static final Connection CONN;\n    static {\n        CONN = DriverManager.getConnection(URL);\n    }\n    void run() { try { work(); } catch (Exception e) {} }\n}\n"),
+        (".javaperf.toml", "[reachability]\nentrypoints = [\"*Controller\"]\n"),
+    ] {
+        let file = dir.path().join(rel);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, body).unwrap();
+    }
+    let root = dir.path().to_str().unwrap();
+    let ids = |report: &serde_json::Value| -> Vec<String> {
+        report["issues"].as_array().unwrap().iter().map(|i| i["id"].as_str().unwrap().to_string()).collect()
+    };
+
+    // 不可达的 Migrator 上不做静态初始化分析，单文件规则照常报告
+    let pruned = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(!ids(&pruned).contains(&"STATIC_INIT_HEAVY".to_string()), "{:?}", ids(&pruned));
+    assert!(ids(&pruned).contains(&"EMPTY_CATCH".to_string()), "{:?}", ids(&pruned));
+    assert_eq!(pruned["reachability"]["entrypoints"], serde_json::json!(["*Controller"]));
+    assert_eq!((&pruned["reachability"]["classes"], &pruned["reachability"]["total_classes"]), (&serde_json::json!(2), &serde_json::json!(3)));
+
+    let exhaustive = radar_scan_json(root, &ScanOptions { exhaustive: true, ..Default::default() }).unwrap();
+    assert!(ids(&exhaustive).contains(&"STATIC_INIT_HEAVY".to_string()), "{:?}", ids(&exhaustive));
+    assert!(exhaustive.get("reachability").is_none());
+
+    let typo = ScanOptions { entrypoints: vec!["*Contoller".to_string()], ..Default::default() };
+    assert!(radar_scan_json(root, &typo).is_err());
+}
+
+#[test]
+fn test_scan_reports_issue_density_per_kloc() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    let padding: String = (0..18).map(|i| format!("    int f{i};\n")).collect();
+    for (rel, body) in [
+        ("com/shop/Gate.java", format!("package com.shop;\n// 网关\npublic class Gate {{\n{padding}    public synchronized void run() {{}}\n}}\n")),
+        ("com/big/Orders.java", format!("package com.big;\npublic class Orders {{\n{padding}{padding}{padding}}}\n")),
+    ] {
+        let file = dir.path().join(rel);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, body).unwrap();
+    }
+
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let issues = report["issues"].as_array().unwrap().len();
+    assert!(issues > 0);
+    assert_eq!(report["loc"], 22 + 57);
+    assert_eq!(report["issues_per_kloc"].as_f64().unwrap(), ((issues as f64 * 1000.0 / 79.0) * 100.0).round() / 100.0);
+    let packages = report["packages"].as_array().unwrap();
+    assert_eq!(packages[0]["package"], "com.shop");
+    assert_eq!(packages[0]["loc"], 22);
+    assert_eq!(packages[1]["package"], "com.big");
+    assert_eq!(packages[1]["issues_per_kloc"], 0.0);
+    assert!(report["categories"].as_array().unwrap().iter().all(|c| c["issues_per_kloc"].as_f64().unwrap() > 0.0));
+}
+
+#[test]
+fn test_scan_ranks_lock_contention_risk() {
+    use java_perf::ast_engine::{radar_scan_json, render_report, ScanOptions};
+    use java_perf::analyze_project;
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    std::fs::write(dir.path().join("OrderController.java"), "package com.shop;\n@RestController\npublic class OrderController {\n    @Autowired\n    private StockService stockService;\n    public void create() { stockService.reserve(); }\n    public void update() { stockService.reserve(); }\n}\n").unwrap();
+    std::fs::write(dir.path().join("StockService.java"), "package com.shop;\n@Service\npublic class StockService {\n    public synchronized void reserve() {\n        count--;\n    }\n    public void reload() {\n        synchronized (LOCK) {\n            load();\n            count = 0;\n        }\n    }\n}\n").unwrap();
+
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let contention = report["contention"].as_array().unwrap();
+    assert_eq!(contention.len(), 2, "{contention:?}");
+    assert_eq!(contention[0]["method"], "reserve");
+    assert_eq!((&contention[0]["callers"], &contention[0]["hotness"], &contention[0]["kind"]), (&serde_json::json!(2), &serde_json::json!(50), &serde_json::json!("sync_method")));
+    assert_eq!(contention[1]["lock"], "StockService.LOCK");
+    assert!(contention[0]["score"].as_u64() > contention[1]["score"].as_u64());
+
+    let analysis = analyze_project(root, &ScanOptions::default()).unwrap();
+    let markdown = render_report(&analysis, false, 10, &ScanOptions::default());
+    assert!(markdown.as_str().unwrap().contains("### 🔒 锁竞争风险"), "{markdown}");
+    let compact = render_report(&analysis, true, 10, &ScanOptions::default());
+    assert!(!compact.as_str().unwrap().contains("锁竞争风险"));
+}
+
+#[test]
+fn test_scan_filters_by_baseline_state() {
+    use java_perf::ast_engine::{radar_scan_json, render_report, ScanOptions};
+    use java_perf::baseline::{self, IssueState};
+    use java_perf::analyze_project;
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    std::fs::write(dir.path().join("Job.java"), "public class Job { void run() { try { work(); } catch (Exception e) {} } }\n").unwrap();
+    baseline::update(root).unwrap();
+    std::fs::write(dir.path().join("Lock.java"), "public class Lock { public synchronized void run() {} }\n").unwrap();
+
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let catch = report["issues"].as_array().unwrap().iter().find(|i| i["id"] == "EMPTY_CATCH").unwrap();
+    let fingerprint = catch["fingerprint"].as_str().unwrap();
+    let outcome = baseline::set(root, fingerprint, Some(IssueState::Acknowledged), Some("bob".into()), None).unwrap();
+    assert_eq!(outcome.changed.unwrap().1.triage.assignee.as_deref(), Some("bob"));
+    assert!(baseline::set(root, fingerprint, None, None, None).is_err());
+
+    let acknowledged = ScanOptions { states: vec![IssueState::Acknowledged], ..Default::default() };
+    let report = radar_scan_json(root, &acknowledged).unwrap();
+    let issues = report["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 1, "{issues:?}");
+    assert_eq!(issues[0]["triage"]["state"], "acknowledged");
+    assert_eq!(issues[0]["triage"]["assignee"], "bob");
+
+    // 基线之外的问题视为 new
+    let new = ScanOptions { states: vec![IssueState::New], ..Default::default() };
+    let report = radar_scan_json(root, &new).unwrap();
+    assert!(report["issues"].as_array().unwrap().iter().all(|i| i["path"] == "Lock.java" && i.get("triage").is_none()));
+
+    let analysis = analyze_project(root, &acknowledged).unwrap();
+    let markdown = render_report(&analysis, false, 10, &ScanOptions::default());
+    assert!(markdown.as_str().unwrap().contains("📌 acknowledged @bob"), "{markdown}");
+}
+
+#[test]
+fn test_scan_edge_case_source_files() {
+    use java_perf::ast_engine::{radar_scan_json, render_report, ScanOptions};
+    use java_perf::analyze_project;
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    let shop = dir.path().join("com/shop");
+    std::fs::create_dir_all(&shop).unwrap();
+    // 默认包
+    std::fs::write(dir.path().join("NoPackage.java"), "import java.util.List;\n\npublic class NoPackage {\n    public synchronized void run(List<Long> ids) {}\n}\n").unwrap();
+    // 默认包 + BOM
+    std::fs::write(dir.path().join("BomNoPackage.java"), "\u{FEFF}public class BomNoPackage {\n    public void run() { try { work(); } catch (Exception e) {} }\n}\n").unwrap();
+    // 带 BOM 的 package 声明
+    std::fs::write(shop.join("BomService.java"), "\u{FEFF}package com.shop;\n\npublic class BomService {\n    public void run(java.util.List<Long> ids) {\n        for (Long id : ids) {\n            orderRepository.findById(id);\n        }\n    }\n}\n").unwrap();
+    std::fs::write(shop.join("package-info.java"), "@NonNullApi\npackage com.shop;\n\nimport org.springframework.lang.NonNullApi;\n").unwrap();
+    std::fs::write(shop.join("Audited.java"), "package com.shop;\n\n@Retention(RetentionPolicy.RUNTIME)\npublic @interface Audited {\n    String value() default \"\";\n}\n").unwrap();
+
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert_eq!(report["files"], 5);
+    let found: Vec<(String, u64, String)> = report["issues"].as_array().unwrap().iter()
+        .map(|i| (i["file"].as_str().unwrap().to_string(), i["line"].as_u64().unwrap(), i["id"].as_str().unwrap().to_string()))
+        .collect();
+    assert!(found.contains(&("NoPackage.java".into(), 4, "SYNC_METHOD".into())), "{found:?}");
+    assert!(found.contains(&("BomNoPackage.java".into(), 2, "EMPTY_CATCH".into())), "{found:?}");
+    assert!(found.contains(&("BomService.java".into(), 6, "N_PLUS_ONE".into())), "{found:?}");
+
+    let stats = &report["stats"];
+    assert_eq!(stats["symbols"]["classes"], 4, "interface/@interface 也登记为类型");
+    assert_eq!(stats["source_shapes"]["default_package"], 2);
+    assert_eq!(stats["source_shapes"]["package_info"], 1);
+    assert_eq!(stats["source_shapes"]["annotation_only"], 1);
+    assert_eq!(stats["source_shapes"]["bom"], 2);
+    assert!(stats["parse_degraded"].as_array().unwrap().is_empty());
+
+    let analysis = analyze_project(root, &ScanOptions::default()).unwrap();
+    let markdown = render_report(&analysis, false, 10, &ScanOptions::default());
+    assert!(markdown.as_str().unwrap().contains("**特殊文件**: 默认包 × 2, package-info × 1, 仅注解类型 × 1, BOM × 2"), "{markdown}");
+}
+
+#[test]
+fn test_blame_age_downgrades_stale_code() {
+    use java_perf::ast_engine::{radar_scan_json, IssueSort, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let git = |args: &[&str], date: &str| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=dev", "-c", "user.email=dev@example.com"])
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .current_dir(root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"], "");
+    std::fs::write(root.join("Legacy.java"), "public class Legacy {\n    public synchronized void run() {}\n}\n").unwrap();
+    git(&["add", "Legacy.java"], "");
+    git(&["commit", "-qm", "legacy"], "2015-01-01T00:00:00Z");
+    std::fs::write(root.join("Recent.java"), "public class Recent {\n    public synchronized void run() {}\n}\n").unwrap();
+    git(&["add", "Recent.java"], "");
+    git(&["commit", "-qm", "recent"], &chrono::Utc::now().to_rfc3339());
+    // 已暂存未提交的修改视为刚刚修改；未跟踪的文件没有 age
+    std::fs::write(root.join("Draft.java"), "public class Draft {\n    public synchronized void run() {}\n}\n").unwrap();
+    git(&["add", "Draft.java"], "");
+    std::fs::write(root.join("Scratch.java"), "public class Scratch {\n    public synchronized void run() {}\n}\n").unwrap();
+
+    let options = ScanOptions { blame_age: Some(3), sort: IssueSort::Recent, ..Default::default() };
+    let report = radar_scan_json(root.to_str().unwrap(), &options).unwrap();
+    let issues = report["issues"].as_array().unwrap();
+    let by_file = |file: &str| issues.iter().find(|i| i["file"] == file && i["id"] == "SYNC_METHOD").unwrap();
+
+    let legacy = by_file("Legacy.java");
+    assert_eq!(legacy["severity"], "P1");
+    assert_eq!(legacy["age"]["stale"], true);
+    assert!(legacy["age"]["days"].as_u64().unwrap() > 3 * 365);
+    assert_eq!(legacy["age"]["commit"].as_str().unwrap().len(), 7);
+
+    let recent = by_file("Recent.java");
+    assert_eq!(recent["severity"], "P0");
+    assert_eq!(recent["age"]["days"], 0);
+    assert!(recent["age"].get("stale").is_none());
+    let draft = by_file("Draft.java");
+    assert_eq!(draft["age"]["days"], 0);
+    assert!(draft["age"].get("commit").is_none());
+    assert!(by_file("Scratch.java").get("age").is_none());
+    // 最近修改的排在前面，没有 age 的排在最后
+    let order: Vec<&str> = issues.iter().filter(|i| i["id"] == "SYNC_METHOD").map(|i| i["file"].as_str().unwrap()).collect();
+    assert_eq!(&order[2..], ["Legacy.java", "Scratch.java"]);
+
+    // 未启用时不附加 age
+    let report = radar_scan_json(root.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    assert!(report["issues"].as_array().unwrap().iter().all(|i| i.get("age").is_none() && i["severity"] == "P0"));
+}
+
+#[test]
+fn test_multi_root_scan_resolves_calls_into_shared_library() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let workspace = tempfile::tempdir().unwrap();
+    let app = workspace.path().join("app");
+    let shared = workspace.path().join("shared-lib");
+    for (file, body) in [
+        (app.join("com/shop/OrderService.java"), "package com.shop;\nimport com.lib.Ledger;\n@Service\npublic class OrderService {\n    private final Ledger ledger;\n    public void load(List<Long> ids) {\n        for (Long id : ids) { ledger.lookup(id); }\n    }\n}\n"),
+        (shared.join("com/lib/Ledger.java"), "package com.lib;\n@Repository\npublic class Ledger {\n    public synchronized Entry lookup(Long id) { return null; }\n}\n"),
+    ] {
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, body).unwrap();
+    }
+    let issues = |report: &serde_json::Value| -> Vec<(String, String)> {
+        report["issues"].as_array().unwrap().iter()
+            .map(|i| (i["id"].as_str().unwrap().to_string(), i["path"].as_str().unwrap().to_string()))
+            .collect()
+    };
+    let n_plus_one = ("N_PLUS_ONE".to_string(), "com/shop/OrderService.java".to_string());
+    let shared_label = shared.to_string_lossy().replace('\\', "/");
+    let shared_sync = ("SYNC_METHOD".to_string(), format!("{shared_label}/com/lib/Ledger.java"));
+
+    // 单根扫描无法得知 Ledger 是 Repository
+    let single = radar_scan_json(app.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    assert!(!issues(&single).contains(&n_plus_one), "{:?}", issues(&single));
+
+    let options = ScanOptions { extra_roots: vec![shared.clone()], ..Default::default() };
+    let multi = radar_scan_json(app.to_str().unwrap(), &options).unwrap();
+    assert!(issues(&multi).contains(&n_plus_one), "{:?}", issues(&multi));
+    assert!(issues(&multi).contains(&shared_sync), "{:?}", issues(&multi));
+    assert_eq!(multi["extra_roots"][0], shared_label.as_str());
+
+    // --report-root: 共享库只参与索引
+    let options = ScanOptions { extra_roots: vec![shared.clone()], report_root: Some(app.clone()), ..Default::default() };
+    let scoped = radar_scan_json(app.to_str().unwrap(), &options).unwrap();
+    assert!(issues(&scoped).contains(&n_plus_one), "{:?}", issues(&scoped));
+    assert!(!issues(&scoped).iter().any(|(_, file)| file.starts_with(&shared_label)), "{:?}", issues(&scoped));
+
+    let outside = ScanOptions { report_root: Some(workspace.path().to_path_buf()), ..Default::default() };
+    assert!(radar_scan_json(app.to_str().unwrap(), &outside).is_err());
+}
+
+#[test]
+fn test_rule_goldens() {
+    use java_perf::goldens;
+
+    // 升级 tree-sitter 语法后失败时，确认变化符合预期再运行 `java-perf rules verify-goldens --bless`
+    let outcome = goldens::verify(&common::fixtures_dir().join("goldens"), &[], false).unwrap();
+    assert!(outcome.cases > 0);
+    assert!(!outcome.failed(), "{}", goldens::render(&outcome, false).as_str().unwrap());
+    // 新增 Query 规则时同时在 fixtures/goldens/<RULE_ID>/ 下添加 fixture 并 --bless 生成期望文件
+    assert!(outcome.uncovered.is_empty(), "以下规则没有 golden fixture: {:?}", outcome.uncovered);
+}
+
+#[test]
+fn test_report_sections_by_category() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let root = common::spring_boot_sample_fixture();
+    let root = root.to_str().unwrap();
+
+    let json = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let categories = json["categories"].as_array().unwrap();
+    let total: u64 = categories.iter().map(|c| c["p0"].as_u64().unwrap() + c["p1"].as_u64().unwrap()).sum();
+    assert_eq!(total as usize, json["issues"].as_array().unwrap().len());
+    let memory = categories.iter().find(|c| c["category"] == "memory").expect("STATIC_COLLECTION 属于内存类别");
+    assert_eq!(memory["anchor"], "category-memory");
+    assert_eq!(memory["checklist"][0]["rule"], "STATIC_COLLECTION");
+
+    // Markdown: 目录链接到带锚点的类别章节，章节内先给优先处理清单再列问题
+    let markdown = radar_scan(root, false, 100, &ScanOptions::default()).unwrap();
+    let markdown = markdown.as_str().unwrap();
+    assert!(markdown.contains("(#category-memory)"), "{markdown}");
+    let section = &markdown[markdown.find("<a id=\"category-memory\"></a>").expect(markdown)..];
+    let checklist = section.find("1. **STATIC_COLLECTION** (P0 × 1)").expect(section);
+    assert!(checklist < section.find("- 🔴 **STATIC_COLLECTION**").expect(section), "{section}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_safe_mode_stays_inside_scan_root() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let outside = tempfile::tempdir().unwrap();
+    std::fs::write(outside.path().join("Leak.java"), "public class Leak {\n    public synchronized void run() {}\n}\n").unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/Local.java"), "public class Local {\n    public synchronized void run() {}\n}\n").unwrap();
+    std::os::unix::fs::symlink(outside.path(), root.join("vendor")).unwrap();
+    // 来自仓库的配置：安全模式下不连接基线服务器
+    std::fs::write(root.join(".javaperf.toml"), "[baseline]\nserver = \"http://127.0.0.1:9\"\nproject = \"x\"\n").unwrap();
+    let paths = |report: &serde_json::Value| -> Vec<String> {
+        report["issues"].as_array().unwrap().iter().map(|i| i["path"].as_str().unwrap().to_string()).collect()
+    };
+
+    let normal = radar_scan_json(root.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    assert!(paths(&normal).contains(&"vendor/Leak.java".to_string()), "{:?}", paths(&normal));
+    assert!(normal.get("safe_mode").is_none());
+
+    let options = ScanOptions { safe_mode: true, baseline: true, ..Default::default() };
+    let safe = radar_scan_json(root.to_str().unwrap(), &options).unwrap();
+    assert_eq!(paths(&safe), ["src/Local.java"]);
+    assert_eq!(safe["safe_mode"]["skipped"][0]["path"], "vendor");
+    assert_eq!(safe["safe_mode"]["skipped"][0]["reason"], "outside_root");
+
+    let blame = ScanOptions { safe_mode: true, blame_age: Some(1), ..Default::default() };
+    assert!(radar_scan_json(root.to_str().unwrap(), &blame).unwrap_err().to_string().contains("--safe-mode"));
+}
+
+#[test]
+fn test_slo_budgets_annotate_and_prioritize_issues() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, IssueSort, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src/com/shop")).unwrap();
+    std::fs::write(root.join("src/com/shop/AuditJob.java"), "package com.shop;\npublic class AuditJob {\n    public synchronized void run() {}\n}\n").unwrap();
+    std::fs::write(root.join("src/com/shop/OrderService.java"), "package com.shop;\n@Service\npublic class OrderService {\n    public synchronized Object load(Long id) { return id; }\n}\n").unwrap();
+    std::fs::write(root.join("src/com/shop/OrderController.java"), r#"package com.shop;
+@RestController
+@RequestMapping("/api/orders")
+public class OrderController {
+    private OrderService orderService;
+    @GetMapping("/{id}")
+    public Object get(@PathVariable Long id) { return orderService.load(id); }
+}
+"#).unwrap();
+    std::fs::write(root.join("slo.yaml"), "budgets:\n  - endpoint: GET /api/orders/{orderId}\n    latency_ms: 80\n  - endpoint: POST /api/refunds\n    latency_ms: 500\n").unwrap();
+
+    let options = ScanOptions { sort: IssueSort::Budget, ..Default::default() };
+    let report = radar_scan_json(root.to_str().unwrap(), &options).unwrap();
+    let issues = report["issues"].as_array().unwrap();
+    assert_eq!(issues[0]["path"], "src/com/shop/OrderService.java", "{issues:?}");
+    assert_eq!(issues[0]["budget"]["target"], "GET /api/orders/{orderId}");
+    assert_eq!(issues[0]["budget"]["latency_ms"], 80);
+    assert_eq!(issues[0]["budget"]["distance"], 1);
+    assert!(issues.iter().any(|i| i["path"] == "src/com/shop/AuditJob.java" && i.get("budget").is_none()));
+    assert_eq!(report["budgets"][0]["p1"].as_u64().unwrap() + report["budgets"][0]["p0"].as_u64().unwrap(), 1);
+    assert_eq!(report["budgets"][1]["matched"], false);
+
+    let markdown = radar_scan(root.to_str().unwrap(), false, 10, &options).unwrap();
+    let markdown = markdown.as_str().unwrap();
+    assert!(markdown.contains("⏱️80ms"), "{markdown}");
+    assert!(markdown.contains("**POST /api/refunds** (≤500ms) - 未找到对应的入口方法"), "{markdown}");
+
+    std::fs::write(root.join("slo.yaml"), "budgets:\n  - latency_ms: 80\n").unwrap();
+    assert!(radar_scan_json(root.to_str().unwrap(), &ScanOptions::default()).is_err());
+}
+
+#[test]
+fn test_framework_version_notes_on_config_findings() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let pom = |version: &str| format!(r#"<project>
+    <parent>
+        <groupId>org.springframework.boot</groupId>
+        <artifactId>spring-boot-starter-parent</artifactId>
+        <version>{version}</version>
+    </parent>
+    <artifactId>app</artifactId>
+    <dependencies>
+        <dependency><groupId>org.springframework.boot</groupId><artifactId>spring-boot-starter-web</artifactId></dependency>
+    </dependencies>
+</project>"#);
+    std::fs::create_dir_all(root.join("src/main/resources")).unwrap();
+    std::fs::write(root.join("src/main/resources/application.yml"), "server:\n  tomcat:\n    max-threads: 50\n").unwrap();
+    let tomcat = |report: &serde_json::Value| -> String {
+        report["issues"].as_array().unwrap().iter()
+            .find(|i| i["id"] == "TOMCAT_THREADS_LOW")
+            .map(|i| i["description"].as_str().unwrap().to_string())
+            .unwrap_or_default()
+    };
+
+    std::fs::write(root.join("pom.xml"), pom("3.1.5")).unwrap();
+    let report = radar_scan_json(root.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    assert_eq!(report["stack"]["framework_versions"]["spring-boot"], "3.1.5");
+    assert!(tomcat(&report).contains("Spring Boot 3 已移除 server.tomcat.max-threads"), "{}", tomcat(&report));
+    assert!(report["stack_adjustments"].as_array().unwrap().iter().any(|a| a["rule_id"] == "TOMCAT_THREADS_LOW" && a["change"] == "note"));
+
+    std::fs::write(root.join("pom.xml"), pom("2.2.13.RELEASE")).unwrap();
+    let report = radar_scan_json(root.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    assert!(!tomcat(&report).is_empty());
+    assert!(!tomcat(&report).contains("Spring Boot"), "{}", tomcat(&report));
+}
+
+#[test]
+fn test_checkpoint_resumes_completed_files() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/A.java"), "public class A {\n    public synchronized void run() {}\n}\n").unwrap();
+    std::fs::write(root.join("src/Broken.java"), "public class Broken {\n    void f( {\n}\n").unwrap();
+    let checkpoint = dir.path().join("ckpt/scan.jsonl");
+    let description = |report: &serde_json::Value, path: &str| -> Option<String> {
+        report["issues"].as_array().unwrap().iter()
+            .find(|i| i["path"] == path)
+            .map(|i| i["description"].as_str().unwrap().to_string())
+    };
+    let fresh = radar_scan_json(root.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let original = description(&fresh, "src/A.java").unwrap();
+
+    // 扫描在后处理阶段失败: 已完成文件的结果留在检查点中
+    let strict = ScanOptions { strict_parse: true, checkpoint: Some(checkpoint.clone()), ..Default::default() };
+    assert!(radar_scan_json(root.to_str().unwrap(), &strict).is_err());
+    let saved = std::fs::read_to_string(&checkpoint).unwrap();
+    assert!(saved.lines().next().unwrap().contains("\"worklist\""));
+    // 篡改检查点中的结果，用来确认恢复时确实复用而不是重新分析
+    std::fs::write(&checkpoint, saved.replace(&original, "FROM_CHECKPOINT")).unwrap();
+
+    // 修复语法错误后重跑: Broken.java 内容变化需重新分析，A.java 复用
+    std::fs::write(root.join("src/Broken.java"), "public class Broken {\n    public synchronized void f() {}\n}\n").unwrap();
+    let resumed = radar_scan_json(root.to_str().unwrap(), &strict).unwrap();
+    assert_eq!(description(&resumed, "src/A.java").as_deref(), Some("FROM_CHECKPOINT"));
+    assert_eq!(description(&resumed, "src/Broken.java"), Some(original));
+    assert!(!checkpoint.exists(), "扫描成功后删除检查点");
+}
+
+#[test]
+fn test_self_call_to_inherited_proxied_method() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src/main/java/com/example");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("BaseService.java"), r#"package com.example;
+public abstract class BaseService {
+    @Async
+    public void audit(String event) { auditLog.write(event); }
+    @Transactional
+    public void touch() {}
+}
+"#).unwrap();
+    std::fs::write(src.join("OrderService.java"), r#"package com.example;
+@Service
+public class OrderService extends BaseService {
+    public void place(Order order) {
+        audit("placed");
+        touch();
+    }
+}
+"#).unwrap();
+
+    // 代理标记随 Phase 1 索引登记，符号缓存命中时同样生效
+    for cache in [false, true, true] {
+        let options = ScanOptions { cache, ..Default::default() };
+        let report = radar_scan_json(dir.path().to_str().unwrap(), &options).unwrap();
+        let hits: Vec<&serde_json::Value> = report["issues"].as_array().unwrap().iter()
+            .filter(|i| i["id"] == "TRANSACTION_SELF_CALL")
+            .collect();
+        assert_eq!(hits.len(), 2, "cache={cache}: {hits:?}");
+        assert!(hits[0]["context"].as_str().unwrap().contains("@Async 方法 audit()"), "{hits:?}");
+        assert!(hits[1]["context"].as_str().unwrap().contains("@Transactional 方法 touch()"), "{hits:?}");
+    }
+}
+
+#[test]
+fn test_jacoco_coverage_and_only_uncovered() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src/main/java/com/shop");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("Inventory.java"), r#"package com.shop;
+public class Inventory {
+    public synchronized void reserve() {
+        count++;
+    }
+    public synchronized void release() {
+        count--;
+    }
+}
+"#).unwrap();
+    let jacoco = dir.path().join("jacoco.xml");
+    std::fs::write(&jacoco, r#"<?xml version="1.0" encoding="UTF-8"?>
+<report name="shop"><package name="com/shop"><sourcefile name="Inventory.java">
+<line nr="4" mi="0" ci="5" mb="0" cb="0"/><line nr="7" mi="5" ci="0" mb="0" cb="0"/>
+</sourcefile></package></report>"#).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let options = ScanOptions { jacoco: Some(jacoco.clone()), ..Default::default() };
+    let report = radar_scan_json(root, &options).unwrap();
+    let coverage: Vec<(u64, &str)> = report["issues"].as_array().unwrap().iter()
+        .filter(|i| i["id"] == "SYNC_METHOD")
+        .map(|i| (i["line"].as_u64().unwrap(), i["coverage"].as_str().unwrap()))
+        .collect();
+    assert_eq!(coverage, [(3, "covered"), (6, "uncovered")]);
+    assert_eq!(report["coverage"]["uncovered_p0"], 1, "{}", report["coverage"]);
+
+    let markdown = radar_scan(root, false, 100, &options).unwrap();
+    let markdown = markdown.as_str().unwrap();
+    assert!(markdown.contains("未覆盖代码中的 P0 (1)"), "{markdown}");
+    assert!(markdown.contains("Inventory.java:6` SYNC_METHOD"), "{markdown}");
+
+    // 只保留未覆盖的问题；统计仍反映过滤前的全部问题
+    let only = ScanOptions { jacoco: Some(jacoco), only_uncovered: true, ..Default::default() };
+    let report = radar_scan_json(root, &only).unwrap();
+    let lines: Vec<u64> = report["issues"].as_array().unwrap().iter().map(|i| i["line"].as_u64().unwrap()).collect();
+    assert_eq!(lines, [6]);
+    assert_eq!(report["coverage"]["covered"], 1);
+
+    let missing = ScanOptions { only_uncovered: true, ..Default::default() };
+    assert!(radar_scan_json(root, &missing).is_err());
+}
+
+#[test]
+fn test_scan_diagnostics_section() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("Garbage.java"), "@@@ ### $$$ %%% ^^^ &&& *** ((( ))) !!! ~~~\n").unwrap();
+    std::fs::write(src.join("Ok.java"), "public class Ok {\n    public synchronized void run() {}\n}\n").unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    // 未建立基线时使用 --baseline，与无法解析的文件一起列为诊断
+    let options = ScanOptions { baseline: true, ..Default::default() };
+    let report = radar_scan_json(root, &options).unwrap();
+    let diagnostics: Vec<(&str, &str)> = report["diagnostics"].as_array().unwrap().iter()
+        .map(|d| (d["kind"].as_str().unwrap(), d["file"].as_str().unwrap_or("")))
+        .collect();
+    assert_eq!(diagnostics, [("parse_failure", "src/Garbage.java"), ("warning", "")], "{}", report["diagnostics"]);
+
+    let markdown = radar_scan(root, true, 0, &options).unwrap();
+    let markdown = markdown.as_str().unwrap();
+    assert!(markdown.contains("### 🩺 扫描诊断 (2)"), "{markdown}");
+    assert!(markdown.contains("- `src/Garbage.java` 语法错误过多，整个文件未分析"), "{markdown}");
+    assert!(markdown.contains("尚未建立基线"), "{markdown}");
+
+    // 没有诊断时不输出
+    std::fs::remove_file(src.join("Garbage.java")).unwrap();
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(report.get("diagnostics").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_keeps_non_utf8_paths() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let code = "public class Exit {\n    void a() { System.exit(1); }\n}\n";
+    // GBK 编码的文件名 "订单.java"
+    std::fs::write(dir.path().join(OsStr::from_bytes(b"\xb6\xa9\xb5\xa5.java")), code).unwrap();
+    std::fs::write(dir.path().join("Exit.java"), code).unwrap();
+
+    // 非 UTF-8 路径照常分析，报告中有损显示并给出警告
+    let report = radar_scan_json(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let mut paths: Vec<&str> = report["issues"].as_array().unwrap().iter().map(|i| i["path"].as_str().unwrap()).collect();
+    paths.sort_unstable();
+    assert_eq!(paths, ["Exit.java", "\u{FFFD}\u{FFFD}\u{FFFD}\u{FFFD}.java"], "{}", report["issues"]);
+    let diagnostics = report["diagnostics"].as_array().unwrap();
+    assert!(diagnostics.iter().any(|d| d["kind"] == "warning" && d["message"].as_str().unwrap().contains("UTF-8")), "{diagnostics:?}");
+    assert!(!diagnostics.iter().any(|d| d["kind"] == "parse_failure"), "{diagnostics:?}");
+}
+
+#[test]
+fn test_scan_jvm_tuning_appendix() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src/main/java/com/shop");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(dir.path().join("pom.xml"), "<project><properties><java.version>17</java.version></properties></project>\n").unwrap();
+    std::fs::write(src.join("ExportService.java"), r#"
+public class ExportService {
+    public byte[] export() {
+        byte[] buffer = new byte[16777216];
+        return buffer;
+    }
+}
+"#).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    // 未设置容器限制时只按问题建议
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let flags: Vec<&str> = report["jvm_tuning"]["advice"].as_array().unwrap().iter()
+        .map(|a| a["flag"].as_str().unwrap())
+        .collect();
+    assert_eq!(flags, ["-XX:G1HeapRegionSize=16m"], "{}", report["jvm_tuning"]);
+    assert_eq!(report["jvm_tuning"]["jdk"], 17);
+
+    std::fs::create_dir_all(dir.path().join("deploy")).unwrap();
+    std::fs::write(dir.path().join("deploy/deployment.yaml"), "resources:\n  limits:\n    memory: 4Gi\n    cpu: \"2\"\n").unwrap();
+    let markdown = radar_scan(root, false, 10, &ScanOptions::default()).unwrap();
+    let markdown = markdown.as_str().unwrap();
+    assert!(markdown.contains("### ⚙️ 附录: JVM 调优建议\n\nJDK 17 · 容器限制: 内存 4096 MiB, CPU 2 核 (`deploy/deployment.yaml`)"), "{markdown}");
+    assert!(markdown.contains("| `-XX:MaxRAMPercentage=75.0` | 容器限制 |"), "{markdown}");
+    assert!(markdown.contains("| `-XX:G1HeapRegionSize=4m` | LARGE_ARRAY ×1 |"), "{markdown}");
+    // 紧凑模式不输出
+    let compact = radar_scan(root, true, 0, &ScanOptions::default()).unwrap();
+    assert!(!compact.as_str().unwrap().contains("JVM 调优建议"));
+
+    // 没有相关问题与容器限制时不输出附录
+    std::fs::remove_file(src.join("ExportService.java")).unwrap();
+    std::fs::remove_file(dir.path().join("deploy/deployment.yaml")).unwrap();
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(report.get("jvm_tuning").is_none());
+}
+
+#[test]
+fn test_usage_stats_recorded_only_when_enabled() {
+    use java_perf::ast_engine::{analyze_project, ScanOptions};
+    use java_perf::usage_stats;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("A.java"), "public class A {\n    public synchronized void run() {}\n}\n").unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let analysis = analyze_project(root, &ScanOptions::default()).unwrap();
+    assert_eq!(usage_stats::record(dir.path(), &analysis).unwrap(), None);
+    assert!(!dir.path().join(".javaperf/stats.jsonl").exists());
+
+    std::fs::write(dir.path().join(".javaperf.toml"), "[stats]\nenabled = true\n").unwrap();
+    let mut file_count = 0;
+    for _ in 0..2 {
+        let analysis = analyze_project(root, &ScanOptions::default()).unwrap();
+        usage_stats::record(dir.path(), &analysis).unwrap();
+        file_count = analysis.scan.file_count;
+    }
+    let summary = usage_stats::show(root, 20).unwrap();
+    assert_eq!(summary.total_scans, 2);
+    assert_eq!(summary.recent[1].files, file_count);
+    let rule = summary.rules.iter().find(|r| r.id == "SYNC_METHOD").unwrap();
+    assert_eq!((rule.scans, rule.total, rule.latest), (2, 2, 1));
+    // 记录中不含路径与代码
+    let content = std::fs::read_to_string(dir.path().join(".javaperf/stats.jsonl")).unwrap();
+    assert!(!content.contains("A.java") && !content.contains("synchronized"), "{content}");
+}
+
+#[test]
+fn test_todo_debt_linked_to_findings() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src/main/java/com/shop");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("ReportService.java"), r#"package com.shop;
+
+public class ReportService {
+    // TODO: 临时方案，数据量大时很慢
+    public String build(List<String> rows) {
+        String out = "";
+        for (String row : rows) {
+            out += row;
+        }
+        return out;
+    }
+
+    // FIXME optimize once the export API supports paging
+    public void export() {}
+}
+"#).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    // 默认不收集
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(report.get("todo_debt").is_none());
+
+    let options = ScanOptions { todo_debt: true, ..Default::default() };
+    let report = radar_scan_json(root, &options).unwrap();
+    let debt = report["todo_debt"].as_array().unwrap();
+    assert_eq!(debt.len(), 2, "{debt:?}");
+    assert_eq!((debt[0]["line"].as_u64(), debt[0]["method"].as_str()), (Some(4), Some("com.shop.ReportService.build")));
+    assert!(debt[0]["findings"].as_u64().unwrap() >= 1);
+    assert_eq!(debt[1]["findings"], 0);
+    let concat = report["issues"].as_array().unwrap().iter()
+        .find(|i| i["id"] == "STRING_CONCAT_LOOP")
+        .unwrap();
+    assert_eq!(concat["debt"]["line"], 4);
+
+    let markdown = radar_scan(root, false, 10, &options).unwrap();
+    let markdown = markdown.as_str().unwrap();
+    assert!(markdown.contains("📝TODO@4"), "{markdown}");
+    assert!(markdown.contains("### 📝 性能相关 TODO (2 条，1 条与问题位于同一方法)"), "{markdown}");
+}
+
+#[test]
+fn test_rule_sample_rate_reports_fraction_and_records_stats() {
+    use java_perf::ast_engine::{analyze_project, ScanOptions};
+    use java_perf::usage_stats;
+
+    let dir = tempfile::tempdir().unwrap();
+    let methods: String = (0..40).map(|i| format!("    public synchronized void run{i}() {{}}\n")).collect();
+    std::fs::write(dir.path().join("A.java"), format!("public class A {{\n{methods}}}\n")).unwrap();
+    std::fs::write(
+        dir.path().join(".javaperf.toml"),
+        "[stats]\nenabled = true\n\n[[rule_overrides]]\nid = \"SYNC_METHOD\"\nsample_rate = 0.5\n",
+    ).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let scan = || {
+        let analysis = analyze_project(root, &ScanOptions::default()).unwrap();
+        usage_stats::record(dir.path(), &analysis).unwrap();
+        analysis
+    };
+    let first = scan();
+    let sampled = &first.sampling[0];
+    assert_eq!((sampled.id.as_str(), sampled.matched), ("SYNC_METHOD", 40));
+    assert!((1..40).contains(&sampled.reported), "{sampled:?}");
+    let reported: Vec<usize> = first.scan.issues.iter().filter(|i| i.issue_type == "SYNC_METHOD").map(|i| i.line).collect();
+    assert_eq!(reported.len(), sampled.reported);
+    // 同一问题每次扫描的抽样结果相同
+    let second = scan();
+    let again: Vec<usize> = second.scan.issues.iter().filter(|i| i.issue_type == "SYNC_METHOD").map(|i| i.line).collect();
+    assert_eq!(again, reported);
+
+    let summary = usage_stats::show(root, 20).unwrap();
+    let rule = summary.rules.iter().find(|r| r.id == "SYNC_METHOD").unwrap();
+    assert_eq!(rule.sampling.as_ref().map(|s| (s.matched, s.reported)), Some((40, reported.len())));
+
+    std::fs::write(dir.path().join(".javaperf.toml"), "[[rule_overrides]]\nid = \"SYNC_METHOD\"\nsample_rate = 0\n").unwrap();
+    let err = analyze_project(root, &ScanOptions::default()).unwrap_err();
+    assert!(err.to_string().contains("sample_rate"), "{err}");
+}
+
+#[test]
+fn test_analyze_range_matches_full_file_positions() {
+    use java_perf::ast_engine::FileAnalyzers;
+    use java_perf::rules::registry::RuleRegistry;
+
+    let analyzers = FileAnalyzers::new(&RuleRegistry::default()).unwrap();
+    let rel_path = "src/main/java/com/shop/Large.java";
+    let analyze_range = |code: &str, range: std::ops::Range<usize>| {
+        java_perf::region::analyze_range(&analyzers, std::path::Path::new(rel_path), rel_path, code, range, None, None)
+    };
+
+    let methods: String = (0..50)
+        .map(|i| format!("    public String join{i}(List<String> parts) {{\n        String out = \"\";\n        for (String p : parts) {{\n            out += p;\n        }}\n        return out;\n    }}\n\n"))
+        .collect();
+    let code = format!("package com.shop;\n\npublic class Large {{\n{methods}}}\n");
+    let full = analyze_range(&code, 0..code.len()).unwrap();
+    assert_eq!(full.issues.iter().filter(|i| i.issue_type == "STRING_CONCAT_LOOP").count(), 50);
+
+    // 只修改第 30 个方法
+    let start = code.find("join30").unwrap();
+    let partial = analyze_range(&code, start..start + 6).unwrap();
+    assert_eq!(partial.region.method.as_deref(), Some("com.shop.Large.join30"));
+    assert!(partial.issues.iter().all(|i| i.path == rel_path));
+    let concat: Vec<_> = partial.issues.iter().filter(|i| i.issue_type == "STRING_CONCAT_LOOP").collect();
+    assert_eq!(concat.len(), 1);
+    let expected = full.issues.iter()
+        .find(|i| i.issue_type == "STRING_CONCAT_LOOP" && i.line == concat[0].line)
+        .unwrap();
+    assert_eq!((concat[0].column, &concat[0].fingerprint), (expected.column, &expected.fingerprint));
+}
+
+#[test]
+fn test_n_plus_one_annotated_with_mapped_sql() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src/main/java/com/shop");
+    let mapper_dir = dir.path().join("src/main/resources/mapper");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::create_dir_all(&mapper_dir).unwrap();
+    std::fs::write(src.join("OrderMapper.java"), "package com.shop;\n@Mapper\npublic interface OrderMapper {\n    Order selectById(Long id);\n}\n").unwrap();
+    std::fs::write(src.join("ItemRepository.java"), r#"package com.shop;
+@Repository
+public interface ItemRepository extends JpaRepository<Item, Long> {
+    @Query("SELECT i FROM Item i WHERE i.orderId = :orderId")
+    List<Item> findByOrder(Long orderId);
+}
+"#).unwrap();
+    std::fs::write(mapper_dir.join("OrderMapper.xml"), r#"<?xml version="1.0" encoding="UTF-8"?>
+<mapper namespace="com.shop.OrderMapper">
+    <select id="selectById" resultType="Order">
+        SELECT * FROM orders WHERE id = #{id}
+    </select>
+</mapper>
+"#).unwrap();
+    std::fs::write(src.join("OrderService.java"), r#"package com.shop;
+@Service
+public class OrderService {
+    @Autowired
+    private OrderMapper orderMapper;
+    @Autowired
+    private ItemRepository itemRepository;
+
+    public void load(List<Long> ids) {
+        for (Long id : ids) {
+            orderMapper.selectById(id);
+            itemRepository.findByOrder(id);
+        }
+    }
+}
+"#).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let issues = report["issues"].as_array().unwrap();
+    let sql_of = |line: u64| issues.iter()
+        .find(|i| i["line"] == line && i["id"] == "N_PLUS_ONE")
+        .map(|i| i["sql"].clone())
+        .unwrap_or_else(|| panic!("line {line}: {report}"));
+    let mapper = sql_of(11);
+    assert_eq!(mapper["text"], "SELECT * FROM orders WHERE id = #{id}");
+    assert_eq!(mapper["source"], "src/main/resources/mapper/OrderMapper.xml:3");
+    assert_eq!(mapper["origin"], "mapper_xml");
+    let jpa = sql_of(12);
+    assert_eq!(jpa["method"], "ItemRepository.findByOrder");
+    assert_eq!(jpa["origin"], "annotation");
+
+    let markdown = radar_scan(root, false, 50, &ScanOptions::default()).unwrap();
+    assert!(markdown.as_str().unwrap().contains("🗄️ 每次迭代执行 `OrderMapper.selectById`"), "{markdown}");
+}
+
+#[test]
+fn test_reports_embed_rules_pack_version() {
+    use java_perf::ast_engine::{radar_scan, radar_scan_json, ScanOptions};
+    use java_perf::rules::version;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Job.java"),
+        "public class Job { void run() { try { work(); } catch (Exception e) {} } void work() {} }\n").unwrap();
+    let root = dir.path().to_str().unwrap();
+    let pack = version::current();
+
+    let json = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert_eq!(json["stats"]["rules"]["pack"], pack);
+    let markdown = radar_scan(root, true, 50, &ScanOptions::default()).unwrap();
+    assert!(markdown.as_str().unwrap().contains(&format!("规则包 `{pack}`")), "{markdown}");
+
+    // 当前版本已登记在历史中，按规则包版本或工具版本都能查到
+    let changelog = version::changelog(&pack[..6]).unwrap();
+    assert_eq!(changelog.to, pack);
+    assert!(changelog.changes.is_empty(), "{:?}", changelog.changes);
+    assert!(version::changelog(env!("CARGO_PKG_VERSION")).is_ok());
+    assert!(version::changelog("0000ffff").is_err());
+}
+
+#[test]
+fn test_complexity_summary_flags_spiking_methods() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Catalog.java"), r#"package com.shop;
+
+import java.util.List;
+
+public class Catalog {
+    private List<String> names;
+
+    public String first() { return names.get(0); }
+
+    public int count() { return names.size(); }
+
+    public boolean isEmpty() { return names.isEmpty(); }
+
+    public void merge(List<String> incoming) {
+        for (String name : incoming) {
+            if (!names.contains(name)) {
+                names.add(name);
+            }
+        }
+    }
+
+    public long ways(int n) {
+        return n <= 1 ? 1 : ways(n - 1) + ways(n - 2);
+    }
+}
+"#).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let plain = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(plain.get("complexity").is_none());
+
+    let options = ScanOptions { complexity: true, ..Default::default() };
+    let report = radar_scan_json(root, &options).unwrap();
+    let class = &report["complexity"][0];
+    assert_eq!(class["class"], "com.shop.Catalog");
+    assert_eq!(class["worst"], "O(2^n)");
+    assert_eq!(class["total_methods"], 5);
+    let methods: Vec<(&str, &str, bool)> = class["methods"].as_array().unwrap().iter()
+        .map(|m| (m["method"].as_str().unwrap(), m["estimate"].as_str().unwrap(), m["spike"] == true))
+        .collect();
+    assert_eq!(methods, vec![("merge", "O(n^2)", true), ("ways", "O(2^n)", true)]);
+    assert_eq!(class["methods"][0]["drivers"][0], "第 16 行: 循环内 List.contains() 线性查找");
+}
+
+#[test]
+fn test_rule_timings_and_category_exclusions() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let source = r#"package com.shop;
+
+import java.util.List;
+
+public class OrderService {
+    private OrderRepository orderRepository;
+
+    public void loadAll(List<Long> ids) {
+        for (Long id : ids) {
+            orderRepository.findById(id);
+        }
+    }
+}
+"#;
+    std::fs::write(dir.path().join("OrderService.java"), source).unwrap();
+    std::fs::create_dir_all(dir.path().join("generated")).unwrap();
+    std::fs::write(dir.path().join("generated/OrderService.java"), source).unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let plain = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    assert!(plain.get("rule_timings").is_none());
+    assert!(!dir.path().join(".javaperf/rule-timings.json").exists());
+
+    let options = ScanOptions { rule_timings: true, ..Default::default() };
+    let report = radar_scan_json(root, &options).unwrap();
+    let timings = report["rule_timings"].as_array().unwrap();
+    let n_plus_one = timings.iter().find(|t| t["id"] == "N_PLUS_ONE").unwrap();
+    assert_eq!(n_plus_one["files"], 2);
+    let totals: Vec<u64> = timings.iter().map(|t| t["total_us"].as_u64().unwrap()).collect();
+    assert!(totals.windows(2).all(|pair| pair[0] >= pair[1]), "{totals:?}");
+    let recorded: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join(".javaperf/rule-timings.json")).unwrap()).unwrap();
+    assert_eq!(recorded["rules"]["N_PLUS_ONE"]["files"], 2);
+
+    // 按类别排除生成代码目录：该目录下的文件不再执行 Query，耗时记录中也不计入
+    let categories: String = ["performance", "concurrency", "memory", "resource", "reactive", "reliability", "config"].iter()
+        .map(|category| format!("[[rule_overrides]]\nid = \"{category}\"\nexclude = [\"generated/**\"]\n\n"))
+        .collect();
+    std::fs::write(dir.path().join(".javaperf.toml"), categories).unwrap();
+    let report = radar_scan_json(root, &options).unwrap();
+    let paths: Vec<&str> = report["issues"].as_array().unwrap().iter().map(|i| i["path"].as_str().unwrap()).collect();
+    assert!(paths.contains(&"OrderService.java") && !paths.iter().any(|p| p.starts_with("generated")), "{paths:?}");
+    let n_plus_one = report["rule_timings"].as_array().unwrap().iter().find(|t| t["id"] == "N_PLUS_ONE").unwrap();
+    assert_eq!(n_plus_one["files"], 1);
+    // 记录跨扫描累加，之后的扫描据此安排规则执行顺序
+    let recorded: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join(".javaperf/rule-timings.json")).unwrap()).unwrap();
+    assert_eq!(recorded["rules"]["N_PLUS_ONE"]["files"], 3);
+}
+
+#[test]
+fn test_javax_jakarta_mixed_reported_per_module() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let write = |path: &str, content: &str| {
+        let file = root.join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, content).unwrap();
+    };
+    let dependency = |group: &str, artifact: &str, version: &str| format!(
+        "<dependency><groupId>{group}</groupId><artifactId>{artifact}</artifactId><version>{version}</version></dependency>"
+    );
+    write("pom.xml", "<project><groupId>com.shop</groupId><artifactId>shop</artifactId><version>1</version>\
+        <modules><module>api</module><module>legacy</module></modules></project>\n");
+    write("api/pom.xml", &format!(
+        "<project><artifactId>api</artifactId>\n<dependencies>\n{}\n</dependencies></project>\n",
+        dependency("jakarta.persistence", "jakarta.persistence-api", "3.1.0")
+    ));
+    write("legacy/pom.xml", &format!(
+        "<project><artifactId>legacy</artifactId>\n<dependencies>\n{}\n{}\n</dependencies></project>\n",
+        dependency("com.google.guava", "guava", "32.1.3-jre"),
+        dependency("org.hibernate", "hibernate-core", "5.6.15.Final")
+    ));
+    write("api/src/main/java/com/shop/Order.java",
+        "package com.shop;\n\nimport jakarta.persistence.Entity;\n\n@Entity\npublic class Order {}\n");
+    write("legacy/src/main/java/com/shop/legacy/Invoice.java",
+        "package com.shop.legacy;\n\nimport javax.persistence.Entity;\nimport javax.sql.DataSource;\n\n@Entity\npublic class Invoice { DataSource ds; }\n");
+
+    let report = radar_scan_json(root.to_str().unwrap(), &ScanOptions::default()).unwrap();
+    let mixed: Vec<&serde_json::Value> = report["issues"].as_array().unwrap().iter()
+        .filter(|issue| issue["id"] == "JAVAX_JAKARTA_MIXED")
+        .collect();
+    assert_eq!(mixed.len(), 1, "{mixed:#?}");
+    assert_eq!(mixed[0]["path"], "legacy/pom.xml");
+    assert_eq!(mixed[0]["line"], 4);
+    let context = mixed[0]["context"].as_str().unwrap();
+    assert!(context.contains("api: javax 0 / jakarta 1, legacy: javax 1 / jakarta 0"), "{context}");
+    assert!(context.contains("旧命名空间来自 org.hibernate:hibernate-core"), "{context}");
+}
+
+#[test]
+fn test_jobs_reported_in_stats() {
+    use java_perf::ast_engine::{radar_scan_json, ScanOptions};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Worker.java"), "public class Worker { public synchronized void run() {} }\n").unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    let report = radar_scan_json(root, &ScanOptions { jobs: Some(2), ..Default::default() }).unwrap();
+    let parallelism = &report["stats"]["parallelism"];
+    assert_eq!(parallelism["jobs"], 2);
+    assert_eq!(parallelism["source"], "flag");
+    assert!(parallelism["host_cpus"].as_u64().unwrap() >= 1);
+    assert!(parallelism["rayon_threads"].as_u64().unwrap() >= 1);
+
+    let report = radar_scan_json(root, &ScanOptions::default()).unwrap();
+    let parallelism = &report["stats"]["parallelism"];
+    assert_ne!(parallelism["source"], "flag");
+    assert!(parallelism["jobs"].as_u64().unwrap() <= parallelism["host_cpus"].as_u64().unwrap());
+    assert!(report["issues"].as_array().unwrap().iter().any(|issue| issue["id"] == "SYNC_METHOD"));
+}